@@ -0,0 +1,103 @@
+"""
+Versioning of the data directory's JSON layout.
+
+SCHEMA_VERSION is the layout this backend reads and writes. The data
+directory records the layout it is at in schema.json; a directory from
+before that file existed is at version 1, the layout the backend has
+always used. MIGRATIONS upgrade a directory one version at a time, so a
+directory from any earlier release can be brought up to date.
+
+The desktop shell asks for both versions with GET /version and runs the
+migrations through POST /migrate, or after an app update by starting the
+backend with --migrate (see BACKEND_INTEGRATION.md).
+"""
+
+import json
+import sys
+from pathlib import Path
+from typing import Callable, List, Optional, Tuple
+
+SCHEMA_VERSION = 1
+SCHEMA_FILE = "schema.json"
+
+# Each entry upgrades the directory from the version before to `version`:
+# (version, what it does, function taking the data directory)
+Migration = Tuple[int, str, Callable[[Path], None]]
+MIGRATIONS: List[Migration] = []
+
+
+class MigrationError(Exception):
+    """The data directory can't be brought to SCHEMA_VERSION."""
+
+
+def data_schema_version(data_dir) -> int:
+    """The layout the data directory is at."""
+    path = Path(data_dir) / SCHEMA_FILE
+    try:
+        return int(json.loads(path.read_text())["version"])
+    except FileNotFoundError:
+        return 1
+    except (ValueError, KeyError, TypeError) as e:
+        raise MigrationError(f"{path} is unreadable: {e}") from e
+
+
+def _record(data_dir: Path, version: int):
+    path = data_dir / SCHEMA_FILE
+    temp_path = data_dir / f"{SCHEMA_FILE}.tmp"
+    temp_path.write_text(json.dumps({"version": version}))
+    temp_path.replace(path)
+
+
+def migrate(data_dir, on_step: Optional[Callable[[int, int, str], None]] = None) -> int:
+    """
+    Upgrades the data directory to SCHEMA_VERSION, recording each version
+    as it is reached so an interrupted run resumes where it stopped.
+
+    Args:
+        data_dir: The data directory
+        on_step: Called with (step, total, description) before each migration
+
+    Returns:
+        The number of migrations run (0 when the data was already current)
+
+    Raises:
+        MigrationError: If the data is newer than this backend
+    """
+    data_dir = Path(data_dir)
+    current = data_schema_version(data_dir)
+    if current > SCHEMA_VERSION:
+        raise MigrationError(
+            f"The data is at schema {current}, newer than this backend's {SCHEMA_VERSION}"
+        )
+    data_dir.mkdir(parents=True, exist_ok=True)
+    steps = [m for m in MIGRATIONS if current < m[0] <= SCHEMA_VERSION]
+    for step, (version, description, upgrade) in enumerate(steps, start=1):
+        if on_step:
+            on_step(step, len(steps), description)
+        upgrade(data_dir)
+        _record(data_dir, version)
+    _record(data_dir, SCHEMA_VERSION)
+    return len(steps)
+
+
+def run_cli(data_dir) -> int:
+    """
+    The sidecar's --migrate mode: migrates the data directory, printing a
+    JSON line like {"step": 1, "total": 2, "message": "..."} on stdout
+    before each step. Anything else goes to stderr.
+
+    Returns:
+        The exit code: 0 once the data is current, 1 if it couldn't be
+    """
+
+    def report(step: int, total: int, message: str):
+        print(json.dumps({"step": step, "total": total, "message": message}), flush=True)
+
+    try:
+        migrated = migrate(data_dir, report)
+    except Exception as e:
+        print(f"Data migration failed: {e}", file=sys.stderr, flush=True)
+        return 1
+    print(f"Data directory is at schema {SCHEMA_VERSION} ({migrated} migrations run)",
+          file=sys.stderr, flush=True)
+    return 0
@@ -5,7 +5,7 @@ This is the main entry point for the REST API.
 Run with: uvicorn main:app --reload
 
 Core principles:
-- Local-first: No cloud services, no auth, no telemetry
+- Local-first: No cloud services, no accounts, no telemetry
 - Coach is the decision-maker: AI provides perspective only
 - Transparency: Everything is readable and reversible
 """
@@ -14,9 +14,12 @@ from fastapi import FastAPI, HTTPException, status
 from fastapi.middleware.cors import CORSMiddleware
 from typing import List
 from urllib.parse import urlparse
+import hmac
 import httpx
 import logging
 import os
+import sys
+import time
 import uuid
 from datetime import datetime
 
@@ -46,13 +49,20 @@ from models import (
     ChatRequest,
 )
 from storage import JSONStorage
+import data_migrations
 from ollama_client import LyraClient
 from ai_service import AIService, AIConfig
 from ai_config import AIConfigResponse
-from fastapi.responses import StreamingResponse
+from fastapi.responses import JSONResponse, StreamingResponse
 
 logger = logging.getLogger("dugout")
 
+# Used by /health to report uptime to the desktop shell
+STARTED_AT = time.monotonic()
+
+# Version of the HTTP API, checked by the desktop shell's /version handshake
+API_VERSION = 1
+
 
 # Initialize FastAPI app
 app = FastAPI(
@@ -85,8 +95,40 @@ app.add_middleware(
     allow_headers=["*"],
 )
 
-# Initialize storage and Ollama client
+# The desktop shell generates a token each time it starts the backend and
+# proxies every request with it, so other local processes can't read a
+# team's data off the port. Unset when run on its own for development,
+# which leaves the API open as before.
+AUTH_TOKEN = os.getenv("DUGOUT_BACKEND_TOKEN") or None
+AUTH_HEADER = "X-Dugout-Token"
+# Probed by the shell's health checks, which carry no token
+OPEN_PATHS = {"/health"}
+
+# The uvicorn server when run as a script (the sidecar), for /shutdown
+server = None
+
+
+@app.middleware("http")
+async def require_session_token(request: Request, call_next):
+    if AUTH_TOKEN and request.method != "OPTIONS" and request.url.path not in OPEN_PATHS:
+        supplied = request.headers.get(AUTH_HEADER, "")
+        if not hmac.compare_digest(supplied.encode(), AUTH_TOKEN.encode()):
+            return JSONResponse(
+                status_code=status.HTTP_401_UNAUTHORIZED,
+                content={"detail": "Missing or invalid session token"},
+            )
+    return await call_next(request)
+
+
 data_dir = os.getenv("DUGOUT_DATA_DIR", "data")
+
+# `backend-sidecar --migrate`: the desktop shell runs this once after an app
+# update, before starting the server. Handled before storage is initialized,
+# which would otherwise write default files in the current layout first.
+if __name__ == "__main__" and "--migrate" in sys.argv[1:]:
+    sys.exit(data_migrations.run_cli(data_dir))
+
+# Initialize storage and Ollama client
 storage = JSONStorage(data_dir=data_dir)
 lyra = LyraClient(model_name="lyra-coach:latest")
 
@@ -149,6 +191,12 @@ def normalize_game(game: Game) -> tuple[Game, bool]:
     return normalized_game, changed
 
 
+def get_backend_host() -> str:
+    # Only the shell on this machine talks to a sidecar; set this to serve a
+    # phone or tablet running the app against this backend instead
+    return os.getenv("DUGOUT_BACKEND_HOST", "127.0.0.1")
+
+
 def get_backend_port() -> int:
     port_value = os.getenv("DUGOUT_BACKEND_PORT", "8100")
     try:
@@ -201,12 +249,63 @@ def health_check():
     
     return {
         "api": "ok",
+        "version": app.version,
+        "uptime_seconds": round(time.monotonic() - STARTED_AT, 1),
         "ollama_connected": ollama_status,
         "ollama_models": ollama_models,
         "lyra_model_available": "lyra-coach:latest" in ollama_models,
     }
 
 
+@app.get("/version", tags=["Health"])
+def get_version():
+    """
+    Versions for the desktop shell's startup handshake.
+
+    schema_version is the data layout this backend reads and writes;
+    data_schema_version is the layout the data directory is at.
+    """
+    try:
+        data_schema_version = data_migrations.data_schema_version(storage.data_dir)
+    except data_migrations.MigrationError as e:
+        raise HTTPException(status_code=status.HTTP_500_INTERNAL_SERVER_ERROR, detail=str(e))
+    return {
+        "version": app.version,
+        "api_version": API_VERSION,
+        "schema_version": data_migrations.SCHEMA_VERSION,
+        "data_schema_version": data_schema_version,
+    }
+
+
+@app.post("/migrate", tags=["Health"])
+def migrate_data():
+    """Upgrade the data directory to this backend's schema_version."""
+    try:
+        migrated = data_migrations.migrate(storage.data_dir)
+    except data_migrations.MigrationError as e:
+        raise HTTPException(status_code=status.HTTP_409_CONFLICT, detail=str(e))
+    return {
+        "migrations_run": migrated,
+        "data_schema_version": data_migrations.SCHEMA_VERSION,
+    }
+
+
+@app.post("/shutdown", status_code=status.HTTP_202_ACCEPTED, tags=["Health"])
+def stop_server():
+    """
+    Stop serving once in-flight requests finish, so the data files are
+    closed cleanly. The desktop shell asks this way on Windows, which has
+    no SIGTERM for the sidecar.
+    """
+    if server is None:
+        raise HTTPException(
+            status_code=status.HTTP_409_CONFLICT,
+            detail="The backend wasn't started as a server it can stop",
+        )
+    server.should_exit = True
+    return {"status": "stopping"}
+
+
 # --- Player endpoints ---
 
 @app.get("/players", response_model=List[Player], tags=["Players"])
@@ -928,4 +1027,7 @@ async def startup_event():
 
 if __name__ == "__main__":
     import uvicorn
-    uvicorn.run(app, host="127.0.0.1", port=get_backend_port())
+    server = uvicorn.Server(
+        uvicorn.Config(app, host=get_backend_host(), port=get_backend_port())
+    )
+    server.run()
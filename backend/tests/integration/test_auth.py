@@ -0,0 +1,28 @@
+import pytest
+
+import main as backend_main
+
+
+@pytest.fixture
+def token(monkeypatch):
+    monkeypatch.setattr(backend_main, "AUTH_TOKEN", "session-token")
+    return "session-token"
+
+
+@pytest.mark.integration
+def test_requests_without_the_session_token_are_rejected(client, token):
+    assert client.get("/players").status_code == 401
+    assert client.get("/players", headers={"X-Dugout-Token": "wrong"}).status_code == 401
+
+    response = client.get("/players", headers={"X-Dugout-Token": token})
+    assert response.status_code == 200
+
+
+@pytest.mark.integration
+def test_health_stays_open_when_a_token_is_required(client, token):
+    assert client.get("/health").status_code == 200
+
+
+@pytest.mark.integration
+def test_api_is_open_without_a_configured_token(client):
+    assert client.get("/players").status_code == 200
@@ -0,0 +1,13 @@
+import pytest
+
+
+@pytest.mark.integration
+def test_health_reports_version_and_uptime(client):
+    response = client.get("/health")
+
+    assert response.status_code == 200
+    body = response.json()
+    assert body["api"] == "ok"
+    assert body["version"] == "1.0.0"
+    assert body["uptime_seconds"] >= 0
+    assert body["ollama_connected"] is True
@@ -0,0 +1,50 @@
+import json
+import os
+import subprocess
+import sys
+from pathlib import Path
+
+import pytest
+
+import data_migrations
+
+BACKEND_DIR = Path(__file__).resolve().parents[2]
+
+
+def run_migrate(data_dir: Path) -> subprocess.CompletedProcess:
+    return subprocess.run(
+        [sys.executable, "main.py", "--migrate"],
+        cwd=BACKEND_DIR,
+        env={**os.environ, "DUGOUT_DATA_DIR": str(data_dir)},
+        capture_output=True,
+        text=True,
+        timeout=60,
+    )
+
+
+@pytest.mark.integration
+def test_migrate_mode_exits_once_the_data_is_current(tmp_path):
+    data_dir = tmp_path / "data"
+    data_dir.mkdir()
+    (data_dir / "players.json").write_text("[]")
+
+    result = run_migrate(data_dir)
+
+    assert result.returncode == 0, result.stderr
+    # Nothing to migrate yet, so no progress lines, and no server started
+    assert result.stdout == ""
+    assert data_migrations.data_schema_version(data_dir) == data_migrations.SCHEMA_VERSION
+    assert not (data_dir / "lineup.json").exists()
+
+
+@pytest.mark.integration
+def test_migrate_mode_fails_on_data_from_a_newer_backend(tmp_path):
+    (tmp_path / data_migrations.SCHEMA_FILE).write_text(
+        json.dumps({"version": data_migrations.SCHEMA_VERSION + 1})
+    )
+
+    result = run_migrate(tmp_path)
+
+    assert result.returncode == 1
+    assert "newer than this backend" in result.stderr
+
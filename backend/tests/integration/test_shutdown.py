@@ -0,0 +1,37 @@
+from types import SimpleNamespace
+
+import pytest
+
+import main as backend_main
+
+
+@pytest.fixture
+def server(monkeypatch):
+    server = SimpleNamespace(should_exit=False)
+    monkeypatch.setattr(backend_main, "server", server)
+    return server
+
+
+@pytest.mark.integration
+def test_shutdown_stops_the_server(client, server):
+    response = client.post("/shutdown")
+
+    assert response.status_code == 202
+    assert server.should_exit is True
+
+
+@pytest.mark.integration
+def test_shutdown_requires_the_session_token(client, server, monkeypatch):
+    monkeypatch.setattr(backend_main, "AUTH_TOKEN", "session-token")
+
+    assert client.post("/shutdown").status_code == 401
+    assert server.should_exit is False
+
+    response = client.post("/shutdown", headers={"X-Dugout-Token": "session-token"})
+    assert response.status_code == 202
+    assert server.should_exit is True
+
+
+@pytest.mark.integration
+def test_shutdown_without_a_server_is_refused(client):
+    assert client.post("/shutdown").status_code == 409
@@ -0,0 +1,46 @@
+import json
+
+import pytest
+
+import data_migrations
+
+
+@pytest.mark.integration
+def test_version_reports_api_and_schema_versions(client):
+    response = client.get("/version")
+
+    assert response.status_code == 200
+    assert response.json() == {
+        "version": "1.0.0",
+        "api_version": 1,
+        "schema_version": data_migrations.SCHEMA_VERSION,
+        "data_schema_version": data_migrations.SCHEMA_VERSION,
+    }
+
+
+@pytest.mark.integration
+def test_migrate_brings_old_data_up_to_date(client, isolated_storage, monkeypatch):
+    upgraded = []
+    monkeypatch.setattr(data_migrations, "SCHEMA_VERSION", 2)
+    monkeypatch.setattr(
+        data_migrations,
+        "MIGRATIONS",
+        [(2, "Converting games", lambda data_dir: upgraded.append(data_dir))],
+    )
+    assert client.get("/version").json()["data_schema_version"] == 1
+
+    response = client.post("/migrate")
+
+    assert response.status_code == 200
+    assert response.json() == {"migrations_run": 1, "data_schema_version": 2}
+    assert upgraded == [isolated_storage.data_dir]
+    assert client.get("/version").json()["data_schema_version"] == 2
+
+
+@pytest.mark.integration
+def test_migrate_refuses_data_from_a_newer_backend(client, isolated_storage):
+    (isolated_storage.data_dir / data_migrations.SCHEMA_FILE).write_text(
+        json.dumps({"version": data_migrations.SCHEMA_VERSION + 1})
+    )
+
+    assert client.post("/migrate").status_code == 409
@@ -0,0 +1,70 @@
+import json
+
+import pytest
+
+import data_migrations
+
+
+@pytest.fixture
+def two_migrations(monkeypatch):
+    run = []
+    monkeypatch.setattr(data_migrations, "SCHEMA_VERSION", 3)
+    monkeypatch.setattr(
+        data_migrations,
+        "MIGRATIONS",
+        [
+            (2, "Splitting names", lambda data_dir: run.append(2)),
+            (3, "Converting games", lambda data_dir: run.append(3)),
+        ],
+    )
+    return run
+
+
+@pytest.mark.unit
+def test_directories_without_a_schema_file_are_at_version_1(tmp_path):
+    assert data_migrations.data_schema_version(tmp_path) == 1
+
+
+@pytest.mark.unit
+def test_migrate_runs_only_the_missing_steps(tmp_path, two_migrations):
+    (tmp_path / data_migrations.SCHEMA_FILE).write_text(json.dumps({"version": 2}))
+    steps = []
+
+    assert data_migrations.migrate(tmp_path, lambda *step: steps.append(step)) == 1
+
+    assert two_migrations == [3]
+    assert steps == [(1, 1, "Converting games")]
+    assert data_migrations.data_schema_version(tmp_path) == 3
+    assert data_migrations.migrate(tmp_path) == 0
+
+
+@pytest.mark.unit
+def test_a_failed_step_keeps_the_versions_reached(tmp_path, two_migrations, monkeypatch):
+    def fail(data_dir):
+        raise OSError("disk full")
+
+    first = data_migrations.MIGRATIONS[0]
+    monkeypatch.setattr(data_migrations, "MIGRATIONS", [first, (3, "Converting games", fail)])
+
+    with pytest.raises(OSError):
+        data_migrations.migrate(tmp_path)
+    assert data_migrations.data_schema_version(tmp_path) == 2
+
+
+@pytest.mark.unit
+def test_unreadable_schema_files_are_errors(tmp_path):
+    (tmp_path / data_migrations.SCHEMA_FILE).write_text("{")
+
+    with pytest.raises(data_migrations.MigrationError):
+        data_migrations.data_schema_version(tmp_path)
+
+
+@pytest.mark.unit
+def test_migrate_mode_prints_a_progress_line_per_step(tmp_path, two_migrations, capsys):
+    assert data_migrations.run_cli(tmp_path) == 0
+
+    lines = [json.loads(line) for line in capsys.readouterr().out.splitlines()]
+    assert lines == [
+        {"step": 1, "total": 2, "message": "Splitting names"},
+        {"step": 2, "total": 2, "message": "Converting games"},
+    ]
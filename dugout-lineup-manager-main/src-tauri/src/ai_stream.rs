@@ -0,0 +1,152 @@
+//! Streamed AI responses delivered as events, so a long lineup suggestion
+//! shows up token by token instead of all at once when it's finished.
+//!
+//! The webview picks a request id, listens on `ai-stream:{request_id}`, then
+//! calls `stream_ai_request`. Each piece of text arrives as a `chunk` event,
+//! followed by exactly one `done`, `cancelled` or `error`.
+//! `cancel_ai_request` drops the connection to the sidecar mid-stream.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::oneshot;
+
+use crate::proxy::{self, BackendRequest, ProxyError};
+use crate::sidecar::SidecarManager;
+use crate::telemetry::{Feature, Telemetry};
+
+/// Prefix of the per-request event name.
+pub const AI_STREAM_EVENT_PREFIX: &str = "ai-stream:";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AiStreamEvent {
+  Chunk { text: String },
+  Done,
+  Cancelled,
+  Error { error: ProxyError },
+}
+
+/// Cancel switches for the streams in flight, by request id.
+#[derive(Default)]
+pub struct AiStreams(Mutex<HashMap<String, oneshot::Sender<()>>>);
+
+pub fn event_name(request_id: &str) -> String {
+  format!("{AI_STREAM_EVENT_PREFIX}{request_id}")
+}
+
+/// Event names only allow a few characters, and the id ends up in one.
+fn validate_request_id(request_id: &str) -> Result<(), ProxyError> {
+  let valid = !request_id.is_empty()
+    && request_id.len() <= 64
+    && request_id
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+  if valid {
+    Ok(())
+  } else {
+    Err(ProxyError::InvalidRequest(format!("bad request id {request_id:?}")))
+  }
+}
+
+/// Streams `request` to the sidecar as `request_id`, emitting its response
+/// as [`AiStreamEvent`]s and handing each piece of text to `on_text` as
+/// well. Returns `false` if it was cancelled; a failure is also sent as an
+/// `error` event.
+pub(crate) async fn stream(
+  app: &AppHandle,
+  request_id: &str,
+  request: &BackendRequest,
+  mut on_text: impl FnMut(&str),
+) -> Result<bool, ProxyError> {
+  validate_request_id(request_id)?;
+  let streams = app.state::<AiStreams>();
+  let (cancel, cancelled) = oneshot::channel();
+  {
+    let mut streams = streams.0.lock().unwrap();
+    if streams.contains_key(request_id) {
+      return Err(ProxyError::InvalidRequest(format!(
+        "request {request_id} is already streaming"
+      )));
+    }
+    streams.insert(request_id.to_string(), cancel);
+  }
+
+  let event = event_name(request_id);
+  let emit = |payload: AiStreamEvent| {
+    if let Err(e) = app.emit(&event, payload) {
+      log::warn!("Failed to emit {}: {}", event, e);
+    }
+  };
+  let sidecar = app.state::<SidecarManager>();
+  let streamed = proxy::stream_text(&sidecar, request, |text| {
+    on_text(&text);
+    emit(AiStreamEvent::Chunk { text });
+    true
+  });
+  // Dropping the stream closes the connection, which stops the sidecar
+  // generating the rest
+  let outcome = tokio::select! {
+    result = streamed => Some(result),
+    _ = cancelled => None,
+  };
+  streams.0.lock().unwrap().remove(request_id);
+
+  match outcome {
+    Some(Ok(())) => {
+      app.state::<Telemetry>().record(Feature::AiRequest);
+      emit(AiStreamEvent::Done);
+      Ok(true)
+    }
+    Some(Err(error)) => {
+      log::warn!("AI stream {} failed: {}", request_id, error);
+      emit(AiStreamEvent::Error {
+        error: error.clone(),
+      });
+      Err(error)
+    }
+    None => {
+      log::debug!("AI stream {} cancelled", request_id);
+      emit(AiStreamEvent::Cancelled);
+      Ok(false)
+    }
+  }
+}
+
+/// Streams one request to the sidecar, emitting its response as
+/// [`AiStreamEvent`]s. Resolves once the stream has finished, been
+/// cancelled or failed; the error is also sent as an `error` event.
+#[tauri::command]
+pub async fn stream_ai_request(
+  app: AppHandle,
+  request_id: String,
+  request: BackendRequest,
+) -> Result<(), ProxyError> {
+  stream(&app, &request_id, &request, |_| {}).await.map(|_| ())
+}
+
+/// Aborts a stream started with `stream_ai_request`. Returns `false` if it
+/// had already finished.
+#[tauri::command]
+pub fn cancel_ai_request(streams: State<'_, AiStreams>, request_id: String) -> bool {
+  match streams.0.lock().unwrap().remove(&request_id) {
+    Some(cancel) => cancel.send(()).is_ok(),
+    None => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn request_ids_must_be_safe_in_event_names() {
+    assert!(validate_request_id("3f1c9a2e-7b4d-4c1e-9a55-0f6b2d8e1c33").is_ok());
+    assert_eq!(event_name("abc_1"), "ai-stream:abc_1");
+    for id in ["", "a b", "a/b", "a:b", &"x".repeat(65)] {
+      assert!(validate_request_id(id).is_err(), "{id}");
+    }
+  }
+}
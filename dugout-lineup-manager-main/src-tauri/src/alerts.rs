@@ -0,0 +1,161 @@
+//! Alerts for a pitcher or the game clock crossing a threshold the coach
+//! set, shown as an OS notification as well as in the app so they still
+//! get noticed with the lineup card in front of the screen.
+//!
+//! Thresholds are [`AlertSettings`] in settings, with overrides per team
+//! since a coach running two teams may be under two leagues' rules.
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::error::{Error, Result};
+use crate::settings::AppSettings;
+
+/// Emitted with a [`ThresholdAlert`] whenever one is raised, for the app to
+/// show front and centre.
+pub const THRESHOLD_ALERT_EVENT: &str = "threshold-alert";
+
+/// Most pitches an alert can be set at.
+const MAX_PITCH_COUNT: u32 = 200;
+
+/// Most minutes before the time limit a warning can be set at.
+const MAX_CLOCK_MINUTES: u32 = 120;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AlertThresholds {
+  /// Pitch counts in a day to alert at, on top of the rest tiers and
+  /// daily limit in the pitch rules.
+  pub pitch_counts: Vec<u32>,
+  /// Minutes before the time limit to warn at.
+  pub clock_minutes: Vec<u32>,
+}
+
+impl Default for AlertThresholds {
+  fn default() -> Self {
+    Self {
+      pitch_counts: Vec::new(),
+      clock_minutes: vec![10],
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AlertSettings {
+  /// Show OS notifications, not just in-app alerts.
+  pub notifications: bool,
+  /// Thresholds for teams without their own.
+  pub defaults: AlertThresholds,
+  /// Overrides keyed by team id.
+  pub teams: BTreeMap<String, AlertThresholds>,
+}
+
+impl Default for AlertSettings {
+  fn default() -> Self {
+    Self {
+      notifications: true,
+      defaults: AlertThresholds::default(),
+      teams: BTreeMap::new(),
+    }
+  }
+}
+
+impl AlertSettings {
+  /// The thresholds `team_id` alerts at.
+  pub fn for_team(&self, team_id: Option<&str>) -> &AlertThresholds {
+    team_id
+      .and_then(|team_id| self.teams.get(team_id))
+      .unwrap_or(&self.defaults)
+  }
+}
+
+/// Rejects thresholds that could never fire.
+pub fn validate(settings: &AlertSettings) -> Result<()> {
+  for thresholds in std::iter::once(&settings.defaults).chain(settings.teams.values()) {
+    if thresholds.pitch_counts.iter().any(|&p| p == 0 || p > MAX_PITCH_COUNT) {
+      return Err(Error::Validation(format!(
+        "Pitch count alerts must be between 1 and {MAX_PITCH_COUNT} pitches"
+      )));
+    }
+    if thresholds.clock_minutes.iter().any(|&m| m == 0 || m > MAX_CLOCK_MINUTES) {
+      return Err(Error::Validation(format!(
+        "Game clock warnings must be between 1 and {MAX_CLOCK_MINUTES} minutes before the limit"
+      )));
+    }
+  }
+  Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertSource {
+  PitchCount,
+  GameClock,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdAlert {
+  pub source: AlertSource,
+  pub team_id: Option<String>,
+  /// Ready to show, e.g. "Jake Miller needs 2 days rest".
+  pub message: String,
+}
+
+impl ThresholdAlert {
+  fn title(&self) -> &'static str {
+    match self.source {
+      AlertSource::PitchCount => "Pitch count",
+      AlertSource::GameClock => "Game clock",
+    }
+  }
+}
+
+/// Emits [`THRESHOLD_ALERT_EVENT`] and, unless turned off, shows the alert
+/// as an OS notification.
+pub fn raise(app: &AppHandle, alert: ThresholdAlert) {
+  log::info!("{}: {}", alert.title(), alert.message);
+  let notify = app
+    .try_state::<RwLock<AppSettings>>()
+    .is_some_and(|settings| settings.read().unwrap().alerts.notifications);
+  if notify {
+    let shown = app
+      .notification()
+      .builder()
+      .title(alert.title())
+      .body(&alert.message)
+      .show();
+    if let Err(e) = shown {
+      log::warn!("Failed to show alert notification: {}", e);
+    }
+  }
+  let _ = app.emit(THRESHOLD_ALERT_EVENT, alert);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn teams_fall_back_to_the_defaults() {
+    let mut settings = AlertSettings::default();
+    let strict = AlertThresholds {
+      pitch_counts: vec![50, 70],
+      clock_minutes: vec![15, 5],
+    };
+    settings.teams.insert("majors".into(), strict.clone());
+
+    assert_eq!(settings.for_team(Some("majors")), &strict);
+    assert_eq!(settings.for_team(Some("minors")).clock_minutes, [10]);
+    assert_eq!(settings.for_team(None), &settings.defaults);
+    assert!(validate(&settings).is_ok());
+
+    settings.teams.get_mut("majors").unwrap().clock_minutes.push(0);
+    assert!(validate(&settings).is_err());
+  }
+}
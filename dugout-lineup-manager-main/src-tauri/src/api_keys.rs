@@ -0,0 +1,162 @@
+//! Bring-your-own AI provider keys, kept in the OS keychain.
+//!
+//! Keys never touch disk in plaintext: they are read from the keychain when
+//! the sidecar spawns and handed to it through its environment, where the
+//! backend already looks for `OPENAI_API_KEY` / `ANTHROPIC_API_KEY`.
+
+use crate::error::{Error, Result};
+use crate::sidecar::SidecarManager;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+/// Keychain service name; matches the bundle identifier.
+pub(crate) const SERVICE: &str = "com.dugout.manager";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+  OpenAi,
+  Anthropic,
+}
+
+impl Provider {
+  pub const ALL: [Provider; 2] = [Provider::OpenAi, Provider::Anthropic];
+
+  fn account(self) -> &'static str {
+    match self {
+      Provider::OpenAi => "openai",
+      Provider::Anthropic => "anthropic",
+    }
+  }
+
+  /// Environment variable the backend reads the key from.
+  fn env_var(self) -> &'static str {
+    match self {
+      Provider::OpenAi => "OPENAI_API_KEY",
+      Provider::Anthropic => "ANTHROPIC_API_KEY",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyStatus {
+  pub provider: Provider,
+  pub set: bool,
+  /// Last four characters, in the same `...abcd` form the backend uses.
+  pub hint: Option<String>,
+}
+
+fn entry(provider: Provider) -> Result<keyring::Entry> {
+  Ok(keyring::Entry::new(SERVICE, provider.account())?)
+}
+
+fn read(provider: Provider) -> Result<Option<String>> {
+  match entry(provider)?.get_password() {
+    Ok(key) => Ok(Some(key)),
+    Err(keyring::Error::NoEntry) => Ok(None),
+    Err(e) => Err(e.into()),
+  }
+}
+
+fn hint(key: &str) -> String {
+  let chars: Vec<char> = key.chars().collect();
+  if chars.len() >= 4 {
+    format!("...{}", chars[chars.len() - 4..].iter().collect::<String>())
+  } else {
+    "...****".into()
+  }
+}
+
+/// Environment for the sidecar: every provider with a stored key.
+///
+/// A keychain that can't be read (locked, no secret service running) only
+/// costs the cloud providers, so it is logged rather than failing the spawn.
+pub fn sidecar_env() -> Vec<(&'static str, String)> {
+  Provider::ALL
+    .into_iter()
+    .filter_map(|provider| match read(provider) {
+      Ok(key) => key.map(|key| (provider.env_var(), key)),
+      Err(e) => {
+        log::warn!("Could not read {} API key: {}", provider.account(), e);
+        None
+      }
+    })
+    .collect()
+}
+
+/// The sidecar only sees keys at spawn, so a running one is restarted to
+/// pick up the change.
+async fn reload_sidecar(app: AppHandle) -> Result<()> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let manager = app.state::<SidecarManager>();
+    if manager.status().running {
+      if let Err(e) = manager.restart(&app) {
+        log::warn!("Failed to restart backend after API key change: {}", e);
+      }
+    }
+  })
+  .await?;
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn get_api_key_status() -> Result<Vec<ApiKeyStatus>> {
+  tauri::async_runtime::spawn_blocking(|| {
+    Provider::ALL
+      .into_iter()
+      .map(|provider| {
+        let key = read(provider)?;
+        Ok(ApiKeyStatus {
+          provider,
+          set: key.is_some(),
+          hint: key.as_deref().map(hint),
+        })
+      })
+      .collect()
+  })
+  .await?
+}
+
+#[tauri::command]
+pub async fn set_api_key(app: AppHandle, provider: Provider, key: String) -> Result<ApiKeyStatus> {
+  let key = key.trim().to_string();
+  if key.is_empty() {
+    return Err(Error::Validation("API key cannot be empty".into()));
+  }
+
+  let status = ApiKeyStatus {
+    provider,
+    set: true,
+    hint: Some(hint(&key)),
+  };
+  tauri::async_runtime::spawn_blocking(move || entry(provider)?.set_password(&key).map_err(Error::from))
+    .await??;
+  log::info!("Stored {} API key in keychain", provider.account());
+
+  reload_sidecar(app).await?;
+  Ok(status)
+}
+
+#[tauri::command]
+pub async fn delete_api_key(app: AppHandle, provider: Provider) -> Result<()> {
+  tauri::async_runtime::spawn_blocking(move || match entry(provider)?.delete_credential() {
+    Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+    Err(e) => Err(Error::from(e)),
+  })
+  .await??;
+  log::info!("Removed {} API key from keychain", provider.account());
+
+  reload_sidecar(app).await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn hint_shows_only_the_last_four_characters() {
+    assert_eq!(hint("sk-ant-abcd1234"), "...1234");
+    assert_eq!(hint("abc"), "...****");
+  }
+}
@@ -0,0 +1,445 @@
+//! `.dugout` backup archives.
+//!
+//! An archive is a zip of the whole data directory (database, sidecar JSON
+//! files, images) plus a `manifest.json` recording the format version, the
+//! app and schema versions that wrote it, and a SHA-256 for every file.
+//! Restoring validates the manifest, snapshots the current data, and then
+//! swaps the archived files in.
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::backup::{self, BackupKind};
+use crate::db::{encryption, migrations, versions, Database, DB_FILE};
+use crate::error::{Error, Result};
+use crate::notes_index;
+use crate::paths::AppPaths;
+use crate::sidecar::SidecarManager;
+
+/// File extension of backup archives.
+pub const EXTENSION: &str = "dugout";
+
+pub(crate) const MANIFEST_FILE: &str = "manifest.json";
+
+/// Bumped whenever the archive layout changes incompatibly.
+const FORMAT_VERSION: u32 = 1;
+
+/// Most memory reserved up front for one file while validating; larger
+/// files grow the buffer as they're read.
+const MAX_PREALLOCATION: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+  pub format_version: u32,
+  pub app_version: String,
+  pub schema_version: u32,
+  pub created_at: String,
+  pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+  /// Path relative to the data directory, always `/`-separated.
+  pub path: String,
+  pub size: u64,
+  pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+  pub manifest: Manifest,
+  /// Archive of the data that was replaced.
+  pub snapshot_path: PathBuf,
+}
+
+/// Writes an archive of `data_dir` to `dest`.
+pub fn export(db: &Database, data_dir: &Path, dest: &Path, app_version: &str) -> Result<Manifest> {
+  if let Some(parent) = dest.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+
+  // Capture a consistent copy of the live database rather than the file
+  // that is open for writing.
+  let db_copy = temp_path(dest, "db");
+  let _cleanup = RemoveOnDrop(db_copy.clone());
+  db.conn()
+    .execute("VACUUM INTO ?1", [db_copy.to_string_lossy()])?;
+
+  let mut sources = vec![(DB_FILE.to_string(), db_copy.clone())];
+  for path in collect_files(data_dir)? {
+    let relative = relative_name(data_dir, &path);
+    if !is_database_file(&relative) && !is_search_index(&relative) {
+      sources.push((relative, path));
+    }
+  }
+
+  let partial = temp_path(dest, "partial");
+  let mut zip = ZipWriter::new(File::create(&partial)?);
+  let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+  let mut files = Vec::with_capacity(sources.len());
+  for (name, path) in sources {
+    let bytes = std::fs::read(&path)?;
+    zip.start_file(name.as_str(), options)?;
+    zip.write_all(&bytes)?;
+    files.push(ManifestEntry {
+      path: name,
+      size: bytes.len() as u64,
+      sha256: sha256_hex(&bytes),
+    });
+  }
+
+  let manifest = Manifest {
+    format_version: FORMAT_VERSION,
+    app_version: app_version.to_string(),
+    schema_version: migrations::current_version(&db.conn())?,
+    created_at: crate::db::timestamp(),
+    files,
+  };
+  zip.start_file(MANIFEST_FILE, options)?;
+  zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+  zip.finish()?;
+
+  std::fs::rename(&partial, dest)?;
+  Ok(manifest)
+}
+
+/// Reads and fully validates an archive without changing anything.
+pub fn inspect(path: &Path) -> Result<Manifest> {
+  let mut archive = ZipArchive::new(File::open(path)?)?;
+  validate(&mut archive)
+}
+
+fn validate(archive: &mut ZipArchive<File>) -> Result<Manifest> {
+  let manifest: Manifest = match archive.by_name(MANIFEST_FILE) {
+    Ok(file) => serde_json::from_reader(file)?,
+    Err(zip::result::ZipError::FileNotFound) => {
+      return Err(Error::Validation(
+        "Not a Dugout backup: manifest.json is missing".into(),
+      ))
+    }
+    Err(e) => return Err(e.into()),
+  };
+
+  if manifest.format_version > FORMAT_VERSION {
+    return Err(Error::Validation(format!(
+      "This backup was created by a newer version of Dugout ({}). Update the app to restore it.",
+      manifest.app_version
+    )));
+  }
+  if manifest.schema_version > migrations::latest_version() {
+    return Err(Error::Validation(format!(
+      "This backup uses database schema v{}, but this version of Dugout only supports up to v{}.",
+      manifest.schema_version,
+      migrations::latest_version()
+    )));
+  }
+  if !manifest.files.iter().any(|f| f.path == DB_FILE) {
+    return Err(Error::Validation("Backup does not contain a database".into()));
+  }
+
+  for entry in &manifest.files {
+    let mut file = archive.by_name(&entry.path).map_err(|_| {
+      Error::Validation(format!("Backup is missing {}", entry.path))
+    })?;
+    if file.enclosed_name().is_none() {
+      return Err(Error::Validation(format!(
+        "Backup contains an unsafe path: {}",
+        entry.path
+      )));
+    }
+    // The manifest's size is only a claim, so it can't decide the allocation
+    let capacity = entry.size.min(file.size()).min(MAX_PREALLOCATION);
+    let mut bytes = Vec::with_capacity(capacity as usize);
+    file.read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != entry.size {
+      return Err(Error::Validation(format!(
+        "Backup is corrupted: size mismatch for {}",
+        entry.path
+      )));
+    }
+    if sha256_hex(&bytes) != entry.sha256 {
+      return Err(Error::Validation(format!(
+        "Backup is corrupted: checksum mismatch for {}",
+        entry.path
+      )));
+    }
+  }
+  Ok(manifest)
+}
+
+/// Replaces the contents of `data_dir` with the archive at `path`, after
+/// writing a safety snapshot of the current data into `snapshot_dir`.
+pub fn import(
+  db: &Database,
+  data_dir: &Path,
+  path: &Path,
+  snapshot_dir: &Path,
+  app_version: &str,
+) -> Result<ImportReport> {
+  let mut archive = ZipArchive::new(File::open(path)?)?;
+  let manifest = validate(&mut archive)?;
+
+  let snapshot_path = snapshot_dir.join(backup::file_name(
+    BackupKind::PreRestore,
+    chrono::Utc::now(),
+  ));
+  export(db, data_dir, &snapshot_path, app_version)?;
+  log::info!("Saved safety snapshot before restore: {:?}", snapshot_path);
+
+  // Extract everything next to the data directory first so a failure
+  // part-way through leaves the current data untouched.
+  let staging = data_dir.with_extension("restoring");
+  if staging.exists() {
+    std::fs::remove_dir_all(&staging)?;
+  }
+  let _cleanup = RemoveOnDrop(staging.clone());
+  for entry in &manifest.files {
+    let mut file = archive.by_name(&entry.path)?;
+    let target = staging.join(file.enclosed_name().unwrap_or_default());
+    if let Some(parent) = target.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::io::copy(&mut file, &mut File::create(&target)?)?;
+  }
+
+  // Load the database through SQLite so the open connection stays valid,
+  // then bring an older schema up to date. The archive may be encrypted or
+  // not, whatever the live database is.
+  {
+    let live_key = encryption::key_for(&data_dir.join(DB_FILE))?;
+    let mut conn = db.conn();
+    encryption::restore_from(&mut conn, live_key.as_deref(), &staging.join(DB_FILE))?;
+    migrations::run(&mut conn, None)?;
+    versions::new_replica(&conn)?;
+  }
+
+  for existing in collect_files(data_dir)? {
+    let relative = relative_name(data_dir, &existing);
+    if !is_database_file(&relative) && !is_search_index(&relative) {
+      std::fs::remove_file(existing)?;
+    }
+  }
+  for entry in manifest.files.iter().filter(|f| f.path != DB_FILE) {
+    let target = data_dir.join(&entry.path);
+    if let Some(parent) = target.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(staging.join(&entry.path), target)?;
+  }
+
+  Ok(ImportReport {
+    manifest,
+    snapshot_path,
+  })
+}
+
+pub(crate) fn collect_files(dir: &Path) -> Result<Vec<PathBuf>> {
+  let mut files = Vec::new();
+  let mut pending = vec![dir.to_path_buf()];
+  while let Some(dir) = pending.pop() {
+    for entry in std::fs::read_dir(&dir)? {
+      let entry = entry?;
+      let path = entry.path();
+      if entry.file_type()?.is_dir() {
+        pending.push(path);
+      } else {
+        files.push(path);
+      }
+    }
+  }
+  files.sort();
+  Ok(files)
+}
+
+/// `path` under `root`, with `/` separators as in archives.
+pub fn relative_name(root: &Path, path: &Path) -> String {
+  path
+    .strip_prefix(root)
+    .unwrap_or(path)
+    .components()
+    .map(|c| c.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+/// The live database and SQLite's side files are handled separately.
+pub fn is_database_file(relative: &str) -> bool {
+  relative
+    .strip_prefix(DB_FILE)
+    .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+}
+
+/// The notes search index is rebuilt from the database, and its files are
+/// held open while the app runs.
+pub fn is_search_index(relative: &str) -> bool {
+  relative
+    .strip_prefix(notes_index::INDEX_DIR)
+    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+  format!("{:x}", Sha256::digest(bytes))
+}
+
+fn temp_path(dest: &Path, suffix: &str) -> PathBuf {
+  let mut name = dest.file_name().unwrap_or_default().to_os_string();
+  name.push(format!(".{suffix}.tmp"));
+  dest.with_file_name(name)
+}
+
+struct RemoveOnDrop(PathBuf);
+
+impl Drop for RemoveOnDrop {
+  fn drop(&mut self) {
+    let _ = if self.0.is_dir() {
+      std::fs::remove_dir_all(&self.0)
+    } else {
+      std::fs::remove_file(&self.0)
+    };
+  }
+}
+
+/// Zips the data directory into a `.dugout` archive at `path`.
+#[tauri::command]
+pub async fn export_backup(app: AppHandle, path: PathBuf) -> Result<Manifest> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let version = app.package_info().version.to_string();
+    let data_dir = app.state::<AppPaths>().data();
+    export(&app.state::<Database>(), &data_dir, &path, &version)
+  })
+  .await?
+}
+
+/// Validates a `.dugout` archive and returns its manifest for a restore
+/// preview.
+#[tauri::command]
+pub async fn inspect_backup(path: PathBuf) -> Result<Manifest> {
+  tauri::async_runtime::spawn_blocking(move || inspect(&path)).await?
+}
+
+/// Restores the archive at `path` over the app's data directory and
+/// restarts the backend so it picks up the restored files.
+pub fn restore(app: &AppHandle, path: &Path) -> Result<ImportReport> {
+  let version = app.package_info().version.to_string();
+  let paths = app.state::<AppPaths>();
+  let report = import(
+    &app.state::<Database>(),
+    &paths.data(),
+    path,
+    &paths.backups(),
+    &version,
+  )?;
+  // The restored database has its own change history
+  app.state::<crate::stats::StatsCache>().clear();
+  app.state::<crate::sync::SyncState>().resend_snapshot();
+
+  let sidecar = app.state::<SidecarManager>();
+  if sidecar.status().running {
+    if let Err(e) = sidecar.restart(app) {
+      log::warn!("Failed to restart backend after restore: {}", e);
+    }
+  }
+  Ok(report)
+}
+
+/// Restores a `.dugout` archive over the current data.
+#[tauri::command]
+pub async fn import_backup(app: AppHandle, path: PathBuf) -> Result<ImportReport> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  tauri::async_runtime::spawn_blocking(move || restore(&app, &path)).await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dugout-archive-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn round_trips_the_data_directory() {
+    let root = temp_dir();
+    let data = root.join("data");
+    std::fs::create_dir_all(data.join("images")).unwrap();
+    std::fs::write(data.join("players.json"), "[]").unwrap();
+    std::fs::write(data.join("images/logo.png"), [1, 2, 3]).unwrap();
+    let db = Database::open(&data, &root.join("pre-migration")).unwrap();
+    crate::db::roster::default_team_id(&db.conn()).unwrap();
+
+    let archive = root.join("backup.dugout");
+    let manifest = export(&db, &data, &archive, "0.1.5").unwrap();
+    assert_eq!(manifest.files.len(), 3);
+    assert_eq!(inspect(&archive).unwrap().files.len(), 3);
+
+    std::fs::write(data.join("players.json"), "[{\"changed\":true}]").unwrap();
+    std::fs::write(data.join("stray.json"), "{}").unwrap();
+    db.conn().execute("DELETE FROM teams", []).unwrap();
+
+    let report = import(&db, &data, &archive, &root.join("snapshots"), "0.1.5").unwrap();
+    assert!(report.snapshot_path.exists());
+    assert_eq!(std::fs::read_to_string(data.join("players.json")).unwrap(), "[]");
+    assert!(!data.join("stray.json").exists());
+    assert_eq!(std::fs::read(data.join("images/logo.png")).unwrap(), [1, 2, 3]);
+    let teams: i64 = db
+      .conn()
+      .query_row("SELECT COUNT(*) FROM teams", [], |row| row.get(0))
+      .unwrap();
+    assert_eq!(teams, 1);
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+
+  #[test]
+  fn rejects_archives_without_a_manifest() {
+    let root = temp_dir();
+    let path = root.join("not-a-backup.dugout");
+    let mut zip = ZipWriter::new(File::create(&path).unwrap());
+    zip
+      .start_file("players.json", SimpleFileOptions::default())
+      .unwrap();
+    zip.write_all(b"[]").unwrap();
+    zip.finish().unwrap();
+
+    assert!(matches!(inspect(&path), Err(Error::Validation(_))));
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+
+  #[test]
+  fn rejects_a_manifest_claiming_an_oversized_file() {
+    let root = temp_dir();
+    let path = root.join("oversized.dugout");
+    let manifest = Manifest {
+      format_version: FORMAT_VERSION,
+      app_version: "0.1.5".into(),
+      schema_version: 1,
+      created_at: String::new(),
+      files: vec![ManifestEntry {
+        path: DB_FILE.into(),
+        size: u64::MAX,
+        sha256: sha256_hex(b"db"),
+      }],
+    };
+    let mut zip = ZipWriter::new(File::create(&path).unwrap());
+    zip.start_file(MANIFEST_FILE, SimpleFileOptions::default()).unwrap();
+    zip.write_all(&serde_json::to_vec(&manifest).unwrap()).unwrap();
+    zip.start_file(DB_FILE, SimpleFileOptions::default()).unwrap();
+    zip.write_all(b"db").unwrap();
+    zip.finish().unwrap();
+
+    assert!(matches!(inspect(&path), Err(Error::Validation(_))));
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
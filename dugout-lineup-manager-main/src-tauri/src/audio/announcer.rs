@@ -0,0 +1,226 @@
+//! Reads who's up over the laptop speaker, or the PA it's plugged into,
+//! in the OS's own voice: `say` on macOS, System.Speech on Windows and
+//! speech-dispatcher (or eSpeak) on Linux.
+//!
+//! Lines are queued and spoken one at a time on their own thread, so an
+//! announcement made while another is still playing waits its turn.
+//! [`ANNOUNCER_EVENT`] carries the queue each time it changes.
+
+use std::collections::{HashMap, VecDeque};
+use std::process::{Child, Command};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::db::{games, roster, Database};
+use crate::error::{Error, Result};
+use crate::scorebook;
+
+/// Emitted with the [`AnnouncerStatus`] when a line starts, the queue
+/// empties or it's stopped.
+pub const ANNOUNCER_EVENT: &str = "announcer";
+
+/// What each of the next batters is called out as.
+const SLOTS: [&str; 3] = ["Now batting", "On deck", "In the hole"];
+
+/// How often the speaking thread checks whether the line has finished.
+const POLL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnouncerStatus {
+  pub speaking: Option<String>,
+  pub queued: Vec<String>,
+  /// Why the last line couldn't be spoken. The queue is dropped with it.
+  pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct Queue {
+  pending: VecDeque<String>,
+  speaking: Option<(String, Child)>,
+  /// Whether the speaking thread is running.
+  running: bool,
+  error: Option<String>,
+}
+
+impl Queue {
+  fn status(&self) -> AnnouncerStatus {
+    AnnouncerStatus {
+      speaking: self.speaking.as_ref().map(|(line, _)| line.clone()),
+      queued: self.pending.iter().cloned().collect(),
+      error: self.error.clone(),
+    }
+  }
+}
+
+#[derive(Clone, Default)]
+pub struct Announcer(Arc<Mutex<Queue>>);
+
+impl Announcer {
+  fn status(&self) -> AnnouncerStatus {
+    self.0.lock().unwrap().status()
+  }
+
+  /// Adds `lines` after whatever is queued, starting the speaking thread
+  /// if it's idle.
+  fn enqueue(&self, app: &AppHandle, lines: Vec<String>) -> Result<AnnouncerStatus> {
+    let mut queue = self.0.lock().unwrap();
+    queue.pending.extend(lines);
+    queue.error = None;
+    if !queue.running {
+      let announcer = self.clone();
+      let app = app.clone();
+      std::thread::Builder::new()
+        .name("announcer".into())
+        .spawn(move || announcer.run(&app))?;
+      queue.running = true;
+    }
+    Ok(queue.status())
+  }
+
+  fn run(&self, app: &AppHandle) {
+    loop {
+      let mut queue = self.0.lock().unwrap();
+      if let Some((_, child)) = &mut queue.speaking {
+        if matches!(child.try_wait(), Ok(None)) {
+          drop(queue);
+          std::thread::sleep(POLL);
+          continue;
+        }
+        queue.speaking = None;
+      }
+      let Some(line) = queue.pending.pop_front() else {
+        queue.running = false;
+        emit(app, &queue.status());
+        return;
+      };
+      match speak(&line) {
+        Ok(child) => queue.speaking = Some((line, child)),
+        Err(e) => {
+          log::warn!("Failed to announce {:?}: {}", line, e);
+          queue.pending.clear();
+          queue.error = Some(e.to_string());
+        }
+      }
+      emit(app, &queue.status());
+    }
+  }
+
+  /// Cuts off the line being spoken, and with `clear` the rest of the
+  /// queue too.
+  fn stop(&self, clear: bool) -> AnnouncerStatus {
+    let mut queue = self.0.lock().unwrap();
+    if clear {
+      queue.pending.clear();
+    }
+    if let Some((_, mut child)) = queue.speaking.take() {
+      let _ = child.kill();
+      let _ = child.wait();
+      // speech-dispatcher keeps talking after its client is gone
+      #[cfg(target_os = "linux")]
+      let _ = Command::new("spd-say").arg("--cancel").status();
+    }
+    queue.status()
+  }
+}
+
+fn emit(app: &AppHandle, status: &AnnouncerStatus) {
+  if let Err(e) = app.emit(ANNOUNCER_EVENT, status) {
+    log::warn!("Failed to emit the announcer status: {}", e);
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn speak(line: &str) -> Result<Child> {
+  Ok(Command::new("say").arg(line).spawn()?)
+}
+
+#[cfg(windows)]
+fn speak(line: &str) -> Result<Child> {
+  use std::os::windows::process::CommandExt;
+  const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+  let script = format!(
+    "Add-Type -AssemblyName System.Speech; \
+     (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak('{}')",
+    line.replace('\'', "''")
+  );
+  Ok(Command::new("powershell")
+    .args(["-NoProfile", "-Command", &script])
+    .creation_flags(CREATE_NO_WINDOW)
+    .spawn()?)
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn speak(line: &str) -> Result<Child> {
+  let voices: [(&str, &[&str]); 3] =
+    [("spd-say", &["--wait"]), ("espeak-ng", &[]), ("espeak", &[])];
+  for (program, args) in voices {
+    match Command::new(program).args(args).arg(line).spawn() {
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+      spawned => return Ok(spawned?),
+    }
+  }
+  Err(Error::Audio("install speech-dispatcher or eSpeak to hear announcements".into()))
+}
+
+/// "Now batting, number 12, Sam Ortiz" and so on for the next batters.
+fn batting_order_lines(conn: &rusqlite::Connection, game_id: &str) -> Result<Vec<String>> {
+  let game = games::get(conn, game_id)?;
+  let players: HashMap<String, roster::Player> = roster::list(conn, &game.team_id)?
+    .into_iter()
+    .map(|p| (p.id.clone(), p))
+    .collect();
+  let upcoming = scorebook::upcoming(conn, game_id, SLOTS.len())?;
+  if upcoming.is_empty() {
+    return Err(Error::Validation("The game has no batting order".into()));
+  }
+  Ok(SLOTS
+    .iter()
+    .zip(&upcoming)
+    .filter_map(|(slot, player_id)| {
+      let player = players.get(player_id)?;
+      Some(match player.number {
+        Some(number) => format!("{slot}, number {number}, {}", player.name),
+        None => format!("{slot}, {}", player.name),
+      })
+    })
+    .collect())
+}
+
+/// Queues the game's next three batters after anything already waiting.
+#[tauri::command]
+pub fn announce_batting_order(
+  app: AppHandle,
+  db: State<'_, Database>,
+  announcer: State<'_, Announcer>,
+  game_id: String,
+) -> Result<AnnouncerStatus> {
+  let lines = batting_order_lines(&db.conn(), &game_id)?;
+  let status = announcer.enqueue(&app, lines)?;
+  emit(&app, &status);
+  Ok(status)
+}
+
+#[tauri::command]
+pub fn get_announcer_status(announcer: State<'_, Announcer>) -> AnnouncerStatus {
+  announcer.status()
+}
+
+/// Cuts off the current line and moves on to the next.
+#[tauri::command]
+pub fn skip_announcement(app: AppHandle, announcer: State<'_, Announcer>) -> AnnouncerStatus {
+  let status = announcer.stop(false);
+  emit(&app, &status);
+  status
+}
+
+/// Stops speaking and empties the queue.
+#[tauri::command]
+pub fn stop_announcements(app: AppHandle, announcer: State<'_, Announcer>) -> AnnouncerStatus {
+  let status = announcer.stop(true);
+  emit(&app, &status);
+  status
+}
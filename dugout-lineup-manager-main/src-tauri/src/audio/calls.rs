@@ -0,0 +1,234 @@
+//! Reads a scoring call out of a transcript: the batter's number, what
+//! happened and where the ball went.
+
+use serde::Serialize;
+
+use crate::db::roster::Player;
+use crate::scorebook::{Event, PlayResult};
+
+/// What a transcribed call said.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceCall {
+  pub transcript: String,
+  pub number: Option<u32>,
+  /// The roster player wearing `number`.
+  pub player_id: Option<String>,
+  pub result: Option<PlayResult>,
+  /// Position code of the fielder the ball went to, e.g. `LF`.
+  pub fielder: Option<String>,
+  /// Ready for `record_play` when the call named a result and any number
+  /// it named is on the roster. Left out otherwise, for the coach to fix.
+  pub event: Option<Event>,
+}
+
+/// Checked in order, so longer phrases win over the words inside them.
+const RESULTS: &[(&str, PlayResult)] = &[
+  ("hit by a pitch", PlayResult::HitByPitch),
+  ("hit by pitch", PlayResult::HitByPitch),
+  ("hbp", PlayResult::HitByPitch),
+  ("sacrifice fly", PlayResult::SacrificeFly),
+  ("sac fly", PlayResult::SacrificeFly),
+  ("sacrifice bunt", PlayResult::SacrificeBunt),
+  ("sac bunt", PlayResult::SacrificeBunt),
+  ("fielders choice", PlayResult::FieldersChoice),
+  ("home run", PlayResult::HomeRun),
+  ("homerun", PlayResult::HomeRun),
+  ("homer", PlayResult::HomeRun),
+  ("grand slam", PlayResult::HomeRun),
+  ("triple", PlayResult::Triple),
+  ("double play", PlayResult::Out),
+  ("double", PlayResult::Double),
+  ("single", PlayResult::Single),
+  ("base on balls", PlayResult::Walk),
+  ("ball four", PlayResult::Walk),
+  ("walk", PlayResult::Walk),
+  ("walks", PlayResult::Walk),
+  ("walked", PlayResult::Walk),
+  ("struck out", PlayResult::Strikeout),
+  ("strikes out", PlayResult::Strikeout),
+  ("strike out", PlayResult::Strikeout),
+  ("strikeout", PlayResult::Strikeout),
+  ("k", PlayResult::Strikeout),
+  ("error", PlayResult::ReachedOnError),
+  ("out", PlayResult::Out),
+  ("outs", PlayResult::Out),
+];
+
+const FIELDERS: &[(&str, &str)] = &[
+  ("pitcher", "P"),
+  ("mound", "P"),
+  ("catcher", "C"),
+  ("first", "1B"),
+  ("second", "2B"),
+  ("third", "3B"),
+  ("short", "SS"),
+  ("shortstop", "SS"),
+  ("left", "LF"),
+  ("center", "CF"),
+  ("centre", "CF"),
+  ("right", "RF"),
+];
+
+const UNITS: [&str; 20] = [
+  "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven",
+  "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+
+const TENS: [&str; 8] = [
+  "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// Lowercase words with the punctuation gone, so "Fielder's choice, #12!"
+/// reads as `fielders choice 12`.
+fn words(transcript: &str) -> Vec<String> {
+  transcript
+    .to_lowercase()
+    .replace('\'', "")
+    .split(|c: char| !c.is_alphanumeric())
+    .filter(|word| !word.is_empty())
+    .map(str::to_string)
+    .collect()
+}
+
+/// The number spoken at the start of `words` and how many words it took.
+fn number_at(words: &[String]) -> Option<(u32, usize)> {
+  let first = words.first()?;
+  if let Ok(n) = first.parse() {
+    return Some((n, 1));
+  }
+  if let Some(n) = UNITS.iter().position(|unit| unit == first) {
+    return Some((n as u32, 1));
+  }
+  let tens = TENS.iter().position(|tens| tens == first)? as u32 * 10 + 20;
+  match words.get(1).and_then(|next| UNITS[1..10].iter().position(|unit| unit == next)) {
+    Some(unit) => Some((tens + unit as u32 + 1, 2)),
+    None => Some((tens, 1)),
+  }
+}
+
+/// The number after "number", or failing that the first one in the call.
+fn jersey(words: &[String]) -> Option<(u32, usize)> {
+  let after_keyword = words
+    .iter()
+    .position(|word| word == "number" || word == "no")
+    .and_then(|i| number_at(&words[i + 1..]).map(|(n, len)| (n, i + 1 + len)));
+  after_keyword.or_else(|| {
+    (0..words.len()).find_map(|i| number_at(&words[i..]).map(|(n, len)| (n, i + len)))
+  })
+}
+
+fn phrase_at(words: &[String], phrase: &str) -> Option<usize> {
+  let phrase: Vec<&str> = phrase.split(' ').collect();
+  words
+    .windows(phrase.len())
+    .position(|window| window.iter().zip(&phrase).all(|(word, part)| word == part))
+    .map(|i| i + phrase.len())
+}
+
+/// Parses a call like "Number 12, single to left" against the team's
+/// roster.
+pub fn parse(transcript: &str, players: &[Player]) -> VoiceCall {
+  let words = words(transcript);
+  let jersey = jersey(&words);
+  let number = jersey.map(|(n, _)| n);
+  let skip = jersey.map_or(0, |(_, end)| end);
+
+  // Results are looked for after the number, so "number one out" isn't an out
+  let rest = &words[skip..];
+  let found = RESULTS
+    .iter()
+    .find_map(|(phrase, result)| phrase_at(rest, phrase).map(|end| (*result, end)));
+  let result = found.map(|(result, _)| result);
+  let fielder = found.and_then(|(_, end)| {
+    rest[end..].iter().find_map(|word| {
+      FIELDERS
+        .iter()
+        .find(|(name, _)| name == word)
+        .map(|(_, code)| code.to_string())
+    })
+  });
+
+  // An inactive player only when nobody on the active roster wears it
+  let wearing = |active: bool| {
+    players
+      .iter()
+      .find(|p| p.number.is_some() && p.number == number && (p.status == "active") == active)
+  };
+  let player_id = wearing(true).or_else(|| wearing(false)).map(|p| p.id.clone());
+
+  let event = match result {
+    Some(result) if number.is_none() || player_id.is_some() => Some(Event::PlateAppearance {
+      batter_id: player_id.clone(),
+      result,
+      runners: None,
+      rbi: None,
+    }),
+    _ => None,
+  };
+  VoiceCall {
+    transcript: transcript.trim().to_string(),
+    number,
+    player_id,
+    result,
+    fielder,
+    event,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn player(id: &str, number: u32) -> Player {
+    Player {
+      id: id.into(),
+      team_id: "t1".into(),
+      name: id.into(),
+      number: Some(number),
+      primary_position: "SS".into(),
+      secondary_positions: vec![],
+      bats: "R".into(),
+      throws: "R".into(),
+      status: "active".into(),
+      notes: String::new(),
+      created_at: String::new(),
+      updated_at: String::new(),
+    }
+  }
+
+  #[test]
+  fn reads_number_result_and_fielder() {
+    let players = [player("p12", 12), player("p23", 23)];
+
+    let call = parse("Number 12, single to left!", &players);
+    assert_eq!(call.number, Some(12));
+    assert_eq!(call.player_id.as_deref(), Some("p12"));
+    assert_eq!(call.fielder.as_deref(), Some("LF"));
+    assert_eq!(
+      call.event,
+      Some(Event::PlateAppearance {
+        batter_id: Some("p12".into()),
+        result: PlayResult::Single,
+        runners: None,
+        rbi: None,
+      })
+    );
+
+    let call = parse("twenty three struck out", &players);
+    assert_eq!((call.number, call.result), (Some(23), Some(PlayResult::Strikeout)));
+    assert_eq!(call.fielder, None);
+
+    let call = parse("Number 4, fielder's choice to short", &players);
+    assert_eq!(call.result, Some(PlayResult::FieldersChoice));
+    assert_eq!(call.fielder.as_deref(), Some("SS"));
+    // Nobody wears 4, so there's nothing to record yet
+    assert_eq!((call.player_id, call.event), (None, None));
+
+    let call = parse("double play to second", &players);
+    assert_eq!((call.number, call.result), (None, Some(PlayResult::Out)));
+    assert!(call.event.is_some());
+    assert_eq!(parse("number one out", &players).result, Some(PlayResult::Out));
+    assert_eq!(parse("something else", &players).event, None);
+  }
+}
@@ -0,0 +1,198 @@
+//! Voice scorekeeping, so a coach in the third-base box can keep score by
+//! calling out plays instead of tapping.
+//!
+//! `start_voice_capture` starts recording from the default microphone and
+//! `stop_voice_capture` sends the clip to the sidecar's transcription
+//! endpoint, reads the call in it ("Number 12, single to left") and emits
+//! the [`VoiceCall`] as [`VOICE_CALL_EVENT`]. Nothing is recorded in the
+//! scorebook: the webview shows the call and passes its event to
+//! `record_play` once the coach confirms it. A clip stops by itself after
+//! [`MAX_CLIP`] in case the stop never comes.
+//!
+//! [`announcer`] goes the other way, reading the batting order aloud.
+
+pub mod announcer;
+mod calls;
+mod wav;
+
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SampleFormat, SizedSample};
+use serde::Deserialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub use calls::VoiceCall;
+
+use crate::db::{roster, Database};
+use crate::error::{Error, Result};
+use crate::proxy;
+use crate::sidecar::SidecarManager;
+
+/// Emitted with a [`VoiceCall`] each time a clip is transcribed.
+pub const VOICE_CALL_EVENT: &str = "voice-call";
+
+/// Calls take a few seconds; anything longer was left running.
+const MAX_CLIP: Duration = Duration::from_secs(15);
+
+const TRANSCRIBE_PATH: &str = "/lyra/transcribe";
+
+/// Local transcription models are slow on older laptops.
+const TRANSCRIBE_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct Clip {
+  sample_rate: u32,
+  samples: Vec<i16>,
+}
+
+/// A clip being recorded on its own thread, since cpal streams can't move
+/// between threads.
+struct Recording {
+  stop: mpsc::Sender<()>,
+  thread: JoinHandle<Clip>,
+}
+
+#[derive(Default)]
+pub struct VoiceCapture(Mutex<Option<Recording>>);
+
+#[derive(Deserialize)]
+struct Transcription {
+  text: String,
+}
+
+fn audio_error(e: impl std::fmt::Display) -> Error {
+  Error::Audio(e.to_string())
+}
+
+fn input_stream<T>(
+  device: &cpal::Device,
+  config: &cpal::StreamConfig,
+  samples: Arc<Mutex<Vec<i16>>>,
+) -> Result<cpal::Stream>
+where
+  T: SizedSample,
+  i16: FromSample<T>,
+{
+  let channels = usize::from(config.channels);
+  device
+    .build_input_stream(
+      config,
+      move |data: &[T], _: &cpal::InputCallbackInfo| {
+        // The first channel is plenty for a voice
+        let mut samples = samples.lock().unwrap();
+        samples.extend(data.iter().step_by(channels).map(|&sample| i16::from_sample(sample)));
+      },
+      |e| log::warn!("Microphone stream error: {}", e),
+      None,
+    )
+    .map_err(audio_error)
+}
+
+fn open_microphone(samples: Arc<Mutex<Vec<i16>>>) -> Result<(cpal::Stream, u32)> {
+  let device = cpal::default_host()
+    .default_input_device()
+    .ok_or_else(|| Error::Audio("no microphone found".into()))?;
+  let supported = device.default_input_config().map_err(audio_error)?;
+  let config = supported.config();
+  let stream = match supported.sample_format() {
+    SampleFormat::F32 => input_stream::<f32>(&device, &config, samples),
+    SampleFormat::I16 => input_stream::<i16>(&device, &config, samples),
+    SampleFormat::U16 => input_stream::<u16>(&device, &config, samples),
+    SampleFormat::I32 => input_stream::<i32>(&device, &config, samples),
+    other => Err(Error::Audio(format!("unsupported sample format {other}"))),
+  }?;
+  stream.play().map_err(audio_error)?;
+  Ok((stream, config.sample_rate.0))
+}
+
+/// Records until `stop` is sent or dropped, reporting whether the
+/// microphone opened through `started`.
+fn record(started: mpsc::Sender<Result<()>>, stop: mpsc::Receiver<()>) -> Clip {
+  let samples = Arc::new(Mutex::new(Vec::new()));
+  let (stream, sample_rate) = match open_microphone(samples.clone()) {
+    Ok(opened) => {
+      let _ = started.send(Ok(()));
+      opened
+    }
+    Err(e) => {
+      let _ = started.send(Err(e));
+      return Clip::default();
+    }
+  };
+  let _ = stop.recv_timeout(MAX_CLIP);
+  drop(stream);
+  let samples = std::mem::take(&mut *samples.lock().unwrap());
+  Clip {
+    sample_rate,
+    samples,
+  }
+}
+
+async fn transcribe(sidecar: &SidecarManager, clip: &Clip) -> Result<String> {
+  let wav = wav::encode(&clip.samples, clip.sample_rate);
+  let body = proxy::upload(sidecar, TRANSCRIBE_PATH, "audio/wav", wav, TRANSCRIBE_TIMEOUT).await?;
+  let transcription: Transcription = serde_json::from_value(body)?;
+  Ok(transcription.text)
+}
+
+/// Starts recording a call from the default microphone.
+#[tauri::command]
+pub async fn start_voice_capture(app: AppHandle) -> Result<()> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let capture = app.state::<VoiceCapture>();
+    let mut recording = capture.0.lock().unwrap();
+    if recording.is_some() {
+      return Err(Error::Validation("Already listening for a call".into()));
+    }
+    let (started, started_rx) = mpsc::channel();
+    let (stop, stop_rx) = mpsc::channel();
+    let thread = std::thread::Builder::new()
+      .name("voice-capture".into())
+      .spawn(move || record(started, stop_rx))?;
+    started_rx
+      .recv()
+      .map_err(|_| Error::Audio("the recording thread stopped".into()))??;
+    *recording = Some(Recording { stop, thread });
+    Ok(())
+  })
+  .await?
+}
+
+/// Stops recording and reads the call against the team's roster, the
+/// default team when `team_id` is left out.
+#[tauri::command]
+pub async fn stop_voice_capture(app: AppHandle, team_id: Option<String>) -> Result<VoiceCall> {
+  let recording = app
+    .state::<VoiceCapture>()
+    .0
+    .lock()
+    .unwrap()
+    .take()
+    .ok_or_else(|| Error::Validation("Not listening for a call".into()))?;
+  let _ = recording.stop.send(());
+  let clip = tauri::async_runtime::spawn_blocking(move || recording.thread.join())
+    .await?
+    .map_err(|_| Error::Audio("the recording thread panicked".into()))?;
+  if clip.samples.is_empty() {
+    return Err(Error::Audio("nothing was recorded".into()));
+  }
+
+  let transcript = transcribe(&app.state::<SidecarManager>(), &clip).await?;
+  let players = {
+    let db = app.state::<Database>();
+    let conn = db.conn();
+    let team_id = match team_id {
+      Some(team_id) => team_id,
+      None => roster::default_team_id(&conn)?,
+    };
+    roster::list(&conn, &team_id)?
+  };
+  let call = calls::parse(&transcript, &players);
+  if let Err(e) = app.emit(VOICE_CALL_EVENT, &call) {
+    log::warn!("Failed to emit the voice call: {}", e);
+  }
+  Ok(call)
+}
@@ -0,0 +1,42 @@
+//! Just enough of the WAV format to hand a clip to the transcriber.
+
+/// 16-bit mono PCM in a WAV container.
+pub fn encode(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+  let data_len = (samples.len() * 2) as u32;
+  let mut wav = Vec::with_capacity(44 + samples.len() * 2);
+  wav.extend_from_slice(b"RIFF");
+  wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+  wav.extend_from_slice(b"WAVEfmt ");
+  wav.extend_from_slice(&16u32.to_le_bytes());
+  // PCM, one channel
+  wav.extend_from_slice(&1u16.to_le_bytes());
+  wav.extend_from_slice(&1u16.to_le_bytes());
+  wav.extend_from_slice(&sample_rate.to_le_bytes());
+  wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+  // Bytes per frame, then bits per sample
+  wav.extend_from_slice(&2u16.to_le_bytes());
+  wav.extend_from_slice(&16u16.to_le_bytes());
+  wav.extend_from_slice(b"data");
+  wav.extend_from_slice(&data_len.to_le_bytes());
+  for sample in samples {
+    wav.extend_from_slice(&sample.to_le_bytes());
+  }
+  wav
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn writes_a_mono_pcm_header() {
+    let wav = encode(&[0, -1, i16::MAX], 16_000);
+    assert_eq!(wav.len(), 44 + 6);
+    assert_eq!(&wav[..4], b"RIFF");
+    assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()), 42);
+    assert_eq!(&wav[8..16], b"WAVEfmt ");
+    assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 16_000);
+    assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 6);
+    assert_eq!(&wav[44..], &[0, 0, 0xff, 0xff, 0xff, 0x7f]);
+  }
+}
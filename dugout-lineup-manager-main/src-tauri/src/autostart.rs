@@ -0,0 +1,39 @@
+//! Launch at login, for coaches who rely on the tray's game reminders.
+//!
+//! The OS starts the app with [`MINIMIZED_ARG`], so it comes up in the
+//! tray rather than opening a window at every login.
+
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
+
+use crate::error::Result;
+
+/// Passed by the login item to start without showing the main window.
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+pub fn init() -> TauriPlugin<Wry> {
+  tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, Some(vec![MINIMIZED_ARG]))
+}
+
+/// Whether this launch came from the login item.
+pub fn launched_minimized() -> bool {
+  std::env::args().skip(1).any(|arg| arg == MINIMIZED_ARG)
+}
+
+#[tauri::command]
+pub fn get_autostart(app: AppHandle) -> Result<bool> {
+  Ok(app.autolaunch().is_enabled()?)
+}
+
+/// Adds or removes the login item, returning whether it is now set.
+#[tauri::command]
+pub fn set_autostart(app: AppHandle, enabled: bool) -> Result<bool> {
+  let autolaunch = app.autolaunch();
+  if enabled {
+    autolaunch.enable()?;
+  } else {
+    autolaunch.disable()?;
+  }
+  Ok(autolaunch.is_enabled()?)
+}
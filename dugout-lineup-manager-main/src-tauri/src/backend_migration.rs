@@ -0,0 +1,316 @@
+//! Upgrades the sidecar's files in the data directory when an app update
+//! ships a sidecar that stores them differently, before that sidecar ever
+//! serves them.
+//!
+//! The data directory remembers in [`VERSION_FILE`] which release's sidecar
+//! last used it. When launch finds another release there, the sidecar's
+//! files are copied aside, the sidecar is run once with
+//! [`MIGRATE_ARG`](crate::sidecar::MIGRATE_ARG) to rewrite them, and only
+//! once that succeeds is it started normally. If it fails, the copies are
+//! put back and the sidecar stays stopped rather than misreading them. A
+//! data directory switched to while running is caught by the version
+//! handshake in [`crate::handshake`] instead.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::CommandEvent;
+
+use crate::archive::{collect_files, is_database_file, is_search_index, relative_name};
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+use crate::sidecar::{self, LogStream, SidecarManager};
+use crate::splash::{self, BootStage};
+
+/// In the data directory, the release whose sidecar last used it.
+pub const VERSION_FILE: &str = "backend-version";
+
+/// Emitted with a [`MigrationProgress`] as the migration goes.
+pub const BACKEND_MIGRATION_EVENT: &str = "backend-migration";
+
+/// However many files there are, the migration shouldn't hang the launch.
+const MIGRATION_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationPhase {
+  Snapshotting,
+  Migrating,
+  Done,
+  /// It failed and the files are as they were before it.
+  RolledBack,
+  /// It failed and the files couldn't all be put back; the snapshot has
+  /// them.
+  Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationProgress {
+  pub phase: MigrationPhase,
+  /// The release the files were from, if it was recorded.
+  pub from: Option<String>,
+  pub to: String,
+  /// Reported by the sidecar while migrating.
+  pub step: Option<u32>,
+  pub total: Option<u32>,
+  pub message: String,
+  /// Where the files were copied before migrating.
+  pub snapshot: Option<PathBuf>,
+}
+
+/// A line of the sidecar's output in migration mode reporting progress;
+/// anything else goes to the backend log.
+#[derive(Debug, Deserialize)]
+struct ProgressLine {
+  step: u32,
+  total: u32,
+  message: String,
+}
+
+/// The files of `data_dir` that belong to the sidecar: everything but the
+/// database, the search index and [`VERSION_FILE`].
+fn is_backend_file(relative: &str) -> bool {
+  !is_database_file(relative) && !is_search_index(relative) && relative != VERSION_FILE
+}
+
+fn backend_files(data_dir: &Path) -> Result<Vec<String>> {
+  Ok(
+    collect_files(data_dir)?
+      .iter()
+      .map(|path| relative_name(data_dir, path))
+      .filter(|relative| is_backend_file(relative))
+      .collect(),
+  )
+}
+
+fn record(data_dir: &Path, version: &str) -> Result<()> {
+  std::fs::write(data_dir.join(VERSION_FILE), version)?;
+  Ok(())
+}
+
+/// Whether `data_dir` has to be migrated before the sidecar of `version`
+/// may use it, and if so from which release (`None` if it predates the
+/// version being recorded). A fresh data directory is recorded as is.
+pub fn pending(data_dir: &Path, version: &str) -> Result<Option<Option<String>>> {
+  match std::fs::read_to_string(data_dir.join(VERSION_FILE)) {
+    Ok(recorded) if recorded.trim() == version => Ok(None),
+    Ok(recorded) => Ok(Some(Some(recorded.trim().to_string()))),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+      if backend_files(data_dir)?.is_empty() {
+        record(data_dir, version)?;
+        Ok(None)
+      } else {
+        Ok(Some(None))
+      }
+    }
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// Copies the sidecar's files from `data_dir` into `dest`.
+fn snapshot(data_dir: &Path, dest: &Path) -> Result<()> {
+  for relative in backend_files(data_dir)? {
+    let target = dest.join(&relative);
+    if let Some(parent) = target.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(data_dir.join(&relative), target)?;
+  }
+  Ok(())
+}
+
+/// Puts the sidecar's files in `data_dir` back as they are in `snapshot`,
+/// removing any the migration added.
+fn roll_back(data_dir: &Path, snapshot: &Path) -> Result<()> {
+  let kept: HashSet<String> = backend_files(snapshot)?.into_iter().collect();
+  for relative in backend_files(data_dir)? {
+    if !kept.contains(&relative) {
+      std::fs::remove_file(data_dir.join(&relative))?;
+    }
+  }
+  for relative in &kept {
+    let target = data_dir.join(relative);
+    if let Some(parent) = target.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(snapshot.join(relative), target)?;
+  }
+  Ok(())
+}
+
+struct Migration {
+  app: AppHandle,
+  from: Option<String>,
+  to: String,
+  snapshot: Option<PathBuf>,
+}
+
+impl Migration {
+  fn emit(&self, phase: MigrationPhase, step: Option<(u32, u32)>, message: String) {
+    let progress = MigrationProgress {
+      phase,
+      from: self.from.clone(),
+      to: self.to.clone(),
+      step: step.map(|(step, _)| step),
+      total: step.map(|(_, total)| total),
+      message,
+      snapshot: self.snapshot.clone(),
+    };
+    if let Err(e) = self.app.emit(BACKEND_MIGRATION_EVENT, progress) {
+      log::warn!("Failed to emit the backend migration progress: {}", e);
+    }
+  }
+
+  /// Runs the sidecar in migration mode until it exits, passing on its
+  /// progress.
+  async fn run_sidecar(&self) -> Result<()> {
+    let manager = self.app.state::<SidecarManager>();
+    let (mut rx, child) = manager
+      .migration_command(&self.app)
+      .and_then(|cmd| cmd.spawn().map_err(|e| e.to_string()))
+      .map_err(|e| Error::Validation(format!("Couldn't start the data migration: {e}")))?;
+
+    let exited = tokio::time::timeout(MIGRATION_TIMEOUT, async {
+      while let Some(event) = rx.recv().await {
+        match event {
+          CommandEvent::Stdout(bytes) => match serde_json::from_slice::<ProgressLine>(&bytes) {
+            Ok(line) => {
+              self.emit(MigrationPhase::Migrating, Some((line.step, line.total)), line.message)
+            }
+            Err(_) => manager.record_output(LogStream::Stdout, &bytes),
+          },
+          CommandEvent::Stderr(bytes) => manager.record_output(LogStream::Stderr, &bytes),
+          CommandEvent::Terminated(payload) => return payload.code,
+          _ => {}
+        }
+      }
+      None
+    })
+    .await;
+    match exited {
+      Ok(Some(0)) => Ok(()),
+      Ok(code) => Err(Error::Validation(format!(
+        "The data migration failed (exit code {})",
+        code.map_or("unknown".into(), |c| c.to_string())
+      ))),
+      Err(_) => {
+        let _ = child.kill();
+        Err(Error::Validation(format!(
+          "The data migration didn't finish within {} minutes",
+          MIGRATION_TIMEOUT.as_secs() / 60
+        )))
+      }
+    }
+  }
+}
+
+/// Migrates the data directory the sidecar is about to use, rolling back
+/// if that fails.
+async fn migrate(app: &AppHandle, from: Option<String>) -> Result<()> {
+  let data_dir = app.state::<SidecarManager>().data_dir();
+  let to = app.package_info().version.to_string();
+  let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+  let dest = app
+    .state::<AppPaths>()
+    .backups()
+    .join("pre-migration")
+    .join(format!("backend-{stamp}"));
+  let mut migration = Migration {
+    app: app.clone(),
+    from,
+    to,
+    snapshot: None,
+  };
+  log::info!(
+    "Migrating the backend's data from {} to {}",
+    migration.from.as_deref().unwrap_or("an earlier release"),
+    migration.to
+  );
+
+  migration.emit(MigrationPhase::Snapshotting, None, "Backing up your data".into());
+  snapshot(&data_dir, &dest)?;
+  migration.snapshot = Some(dest.clone());
+
+  migration.emit(MigrationPhase::Migrating, None, "Upgrading your data".into());
+  if let Err(e) = migration.run_sidecar().await {
+    log::error!("{}; rolling back to {:?}", e, dest);
+    match roll_back(&data_dir, &dest) {
+      Ok(()) => migration.emit(MigrationPhase::RolledBack, None, e.to_string()),
+      Err(rollback) => {
+        log::error!("Failed to roll back the backend's data: {}", rollback);
+        migration.emit(
+          MigrationPhase::Failed,
+          None,
+          format!("{e}, and putting the old files back failed: {rollback}"),
+        );
+      }
+    }
+    return Err(e);
+  }
+
+  record(&data_dir, &migration.to)?;
+  log::info!("Migrated the backend's data; the old files are in {:?}", dest);
+  migration.emit(MigrationPhase::Done, None, "Your data is up to date".into());
+  Ok(())
+}
+
+/// Starts the sidecar after migrating its data, or reports that it can't
+/// be started. Reveals the main window afterwards if `reveal`.
+pub async fn migrate_then_spawn(app: AppHandle, from: Option<String>, reveal: bool) {
+  splash::report(&app, BootStage::MigratingBackendData);
+  match migrate(&app, from).await {
+    Ok(()) => {
+      splash::report(&app, BootStage::StartingBackend);
+      if let Err(e) = app.state::<SidecarManager>().spawn(&app) {
+        log::warn!("{}. AI features will be unavailable.", e);
+      }
+    }
+    Err(e) => sidecar::emit_status(
+      &app,
+      sidecar::BackendState::Failed,
+      0,
+      Some(format!("{e}. Your data was left as it was and the AI engine wasn't started.")),
+    ),
+  }
+  if reveal {
+    crate::health::reveal_main_window_when_ready(app).await;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rolls_the_backends_files_back() {
+    let root = std::env::temp_dir().join(format!("dugout-migration-{}", uuid::Uuid::new_v4()));
+    let (data, saved) = (root.join("data"), root.join("snapshot"));
+    std::fs::create_dir_all(data.join("images")).unwrap();
+    assert_eq!(pending(&data, "1.4.0").unwrap(), None);
+    assert_eq!(pending(&data, "1.5.0").unwrap(), Some(Some("1.4.0".into())));
+
+    std::fs::write(data.join("players.json"), "[1]").unwrap();
+    std::fs::write(data.join("images").join("logo.png"), [0; 4]).unwrap();
+    std::fs::write(data.join(crate::db::DB_FILE), "db").unwrap();
+    snapshot(&data, &saved).unwrap();
+    assert!(!saved.join(crate::db::DB_FILE).exists());
+
+    std::fs::write(data.join("players.json"), "{\"players\": [1]}").unwrap();
+    std::fs::write(data.join("schema.json"), "2").unwrap();
+    std::fs::write(data.join(crate::db::DB_FILE), "db, changed").unwrap();
+    roll_back(&data, &saved).unwrap();
+    assert_eq!(std::fs::read_to_string(data.join("players.json")).unwrap(), "[1]");
+    assert!(!data.join("schema.json").exists());
+    assert!(data.join("images").join("logo.png").exists());
+    // The database isn't the sidecar's to migrate
+    assert_eq!(std::fs::read_to_string(data.join(crate::db::DB_FILE)).unwrap(), "db, changed");
+
+    std::fs::remove_file(data.join(VERSION_FILE)).unwrap();
+    assert_eq!(pending(&data, "1.5.0").unwrap(), Some(None));
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
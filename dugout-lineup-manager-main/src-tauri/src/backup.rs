@@ -0,0 +1,287 @@
+//! Automatic backups of the data directory.
+//!
+//! A background task writes a `.dugout` archive into `backups/` whenever
+//! the configured interval has elapsed since the last automatic backup, and
+//! prunes old ones by count and age. Backups are also taken before updates
+//! and restores; manual backups are never pruned. With a cloud account
+//! linked, each automatic backup is also uploaded there (see
+//! [`crate::cloud_backup`]).
+
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::archive::{self, ImportReport, EXTENSION};
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+use crate::settings::{AppSettings, BackupSettings};
+
+const STAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Wait after launch before the first scheduled check, to stay out of the
+/// way of startup.
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+/// Longest the scheduler sleeps, so settings changes are picked up.
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupKind {
+  Auto,
+  PreUpdate,
+  PreRestore,
+  Manual,
+}
+
+impl BackupKind {
+  fn prefix(self) -> &'static str {
+    match self {
+      Self::Auto => "auto",
+      Self::PreUpdate => "pre-update",
+      Self::PreRestore => "pre-restore",
+      Self::Manual => "manual",
+    }
+  }
+
+  fn from_prefix(prefix: &str) -> Option<Self> {
+    [Self::Auto, Self::PreUpdate, Self::PreRestore, Self::Manual]
+      .into_iter()
+      .find(|kind| kind.prefix() == prefix)
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+  pub file_name: String,
+  pub path: PathBuf,
+  pub kind: BackupKind,
+  pub created_at: DateTime<Utc>,
+  pub size: u64,
+}
+
+/// Name for a new backup of `kind` taken at `at`.
+pub fn file_name(kind: BackupKind, at: DateTime<Utc>) -> String {
+  format!("{}-{}.{EXTENSION}", kind.prefix(), at.format(STAMP_FORMAT))
+}
+
+/// The kind and time of a backup named by [`file_name`].
+pub fn parse_file_name(name: &str) -> Option<(BackupKind, DateTime<Utc>)> {
+  let stem = name.strip_suffix(&format!(".{EXTENSION}"))?;
+  let (prefix, stamp) = stem.rsplit_once('-')?;
+  let kind = BackupKind::from_prefix(prefix)?;
+  let at = NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT).ok()?;
+  Some((kind, at.and_utc()))
+}
+
+/// Lists the archives in `dir`, newest first.
+pub fn list(dir: &Path) -> Result<Vec<BackupInfo>> {
+  let mut backups = Vec::new();
+  let entries = match std::fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(backups),
+    Err(e) => return Err(e.into()),
+  };
+  for entry in entries {
+    let entry = entry?;
+    let name = entry.file_name().to_string_lossy().to_string();
+    if let Some((kind, created_at)) = parse_file_name(&name) {
+      backups.push(BackupInfo {
+        file_name: name,
+        path: entry.path(),
+        kind,
+        created_at,
+        size: entry.metadata()?.len(),
+      });
+    }
+  }
+  backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+  Ok(backups)
+}
+
+/// Deletes automatic backups beyond the retention policy. The newest
+/// backup is always kept, however old, and manual backups are never
+/// touched. Returns the removed file names.
+pub fn prune(dir: &Path, policy: &BackupSettings, now: DateTime<Utc>) -> Result<Vec<String>> {
+  let max_age = chrono::Duration::days(i64::from(policy.keep_days));
+  let mut removed = Vec::new();
+  let prunable = list(dir)?
+    .into_iter()
+    .filter(|b| b.kind != BackupKind::Manual);
+  for (index, backup) in prunable.enumerate() {
+    let too_many = index >= policy.keep_count.max(1);
+    let too_old = index > 0 && now - backup.created_at > max_age;
+    if too_many || too_old {
+      std::fs::remove_file(&backup.path)?;
+      removed.push(backup.file_name);
+    }
+  }
+  Ok(removed)
+}
+
+/// Writes a backup of `kind` and applies the retention policy.
+pub fn create(app: &AppHandle, kind: BackupKind) -> Result<BackupInfo> {
+  let paths = app.state::<AppPaths>();
+  let now = Utc::now();
+  let path = paths.backups().join(file_name(kind, now));
+  let version = app.package_info().version.to_string();
+  archive::export(&app.state::<Database>(), &paths.data(), &path, &version)?;
+  log::info!("Created {:?} backup {:?}", kind, path);
+
+  let policy = app.state::<RwLock<AppSettings>>().read().unwrap().backup.clone();
+  for name in prune(&paths.backups(), &policy, now)? {
+    log::info!("Pruned old backup {}", name);
+  }
+
+  Ok(BackupInfo {
+    file_name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+    size: std::fs::metadata(&path)?.len(),
+    path,
+    kind,
+    created_at: now,
+  })
+}
+
+/// How long until the next automatic backup is due (zero if overdue).
+fn next_due(dir: &Path, policy: &BackupSettings, now: DateTime<Utc>) -> Result<Duration> {
+  let interval = chrono::Duration::hours(i64::from(policy.interval_hours.max(1)));
+  let last = list(dir)?
+    .into_iter()
+    .find(|b| b.kind == BackupKind::Auto)
+    .map(|b| b.created_at);
+  Ok(match last {
+    Some(last) => (last + interval - now).to_std().unwrap_or_default(),
+    None => Duration::ZERO,
+  })
+}
+
+/// Starts the background task that takes scheduled backups.
+pub fn start_scheduler(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    tokio::time::sleep(STARTUP_DELAY).await;
+    loop {
+      let handle = app.clone();
+      let wait = tauri::async_runtime::spawn_blocking(move || run_if_due(&handle))
+        .await
+        .unwrap_or_else(|e| Err(e.into()))
+        .unwrap_or_else(|e| {
+          log::error!("Scheduled backup failed: {}", e);
+          MAX_CHECK_INTERVAL
+        });
+      tokio::time::sleep(wait.min(MAX_CHECK_INTERVAL)).await;
+    }
+  });
+}
+
+/// Takes an automatic backup if one is due; returns how long to wait
+/// before checking again.
+fn run_if_due(app: &AppHandle) -> Result<Duration> {
+  let policy = app.state::<RwLock<AppSettings>>().read().unwrap().backup.clone();
+  if !policy.enabled {
+    return Ok(MAX_CHECK_INTERVAL);
+  }
+  let dir = app.state::<AppPaths>().backups();
+  let wait = next_due(&dir, &policy, Utc::now())?;
+  if !wait.is_zero() {
+    return Ok(wait);
+  }
+  let backup = create(app, BackupKind::Auto)?;
+  #[cfg(desktop)]
+  crate::cloud_backup::upload_in_background(app, backup);
+  next_due(&dir, &policy, Utc::now())
+}
+
+/// Lists every backup in the backups folder, newest first.
+#[tauri::command]
+pub fn list_backups(paths: State<'_, AppPaths>) -> Result<Vec<BackupInfo>> {
+  list(&paths.backups())
+}
+
+/// Takes a backup now, e.g. a manual one or right before installing an
+/// update.
+#[tauri::command]
+pub async fn create_backup(app: AppHandle, kind: Option<BackupKind>) -> Result<BackupInfo> {
+  tauri::async_runtime::spawn_blocking(move || create(&app, kind.unwrap_or(BackupKind::Manual)))
+    .await?
+}
+
+/// Restores one of the archives listed by `list_backups`.
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, file_name: String) -> Result<ImportReport> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  if parse_file_name(&file_name).is_none() {
+    return Err(Error::Validation(format!("Unknown backup: {file_name}")));
+  }
+  let path = app.state::<AppPaths>().backups().join(&file_name);
+  if !path.is_file() {
+    return Err(Error::NotFound(format!("Backup {file_name}")));
+  }
+  tauri::async_runtime::spawn_blocking(move || archive::restore(&app, &path)).await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  fn touch(dir: &Path, kind: BackupKind, at: DateTime<Utc>) {
+    std::fs::write(dir.join(file_name(kind, at)), b"").unwrap();
+  }
+
+  #[test]
+  fn parses_backup_file_names() {
+    let at = Utc.with_ymd_and_hms(2026, 5, 11, 9, 30, 0).unwrap();
+    let name = file_name(BackupKind::PreUpdate, at);
+    assert_eq!(name, "pre-update-20260511T093000Z.dugout");
+    assert_eq!(parse_file_name(&name), Some((BackupKind::PreUpdate, at)));
+    assert_eq!(parse_file_name("notes.txt"), None);
+  }
+
+  #[test]
+  fn prunes_by_count_and_age_but_keeps_manual_backups() {
+    let dir = std::env::temp_dir().join(format!("dugout-backups-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let now = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+    for days_ago in [1, 2, 3, 40] {
+      touch(&dir, BackupKind::Auto, now - chrono::Duration::days(days_ago));
+    }
+    touch(&dir, BackupKind::Manual, now - chrono::Duration::days(90));
+
+    let policy = BackupSettings {
+      keep_count: 2,
+      keep_days: 30,
+      ..Default::default()
+    };
+    let removed = prune(&dir, &policy, now).unwrap();
+
+    assert_eq!(removed.len(), 2);
+    let remaining: Vec<_> = list(&dir).unwrap().into_iter().map(|b| b.kind).collect();
+    assert_eq!(
+      remaining,
+      [BackupKind::Auto, BackupKind::Auto, BackupKind::Manual]
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn schedules_the_next_backup_from_the_last_automatic_one() {
+    let dir = std::env::temp_dir().join(format!("dugout-backups-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let now = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+    let policy = BackupSettings::default();
+
+    assert_eq!(next_due(&dir, &policy, now).unwrap(), Duration::ZERO);
+    touch(&dir, BackupKind::Auto, now - chrono::Duration::hours(20));
+    assert_eq!(
+      next_due(&dir, &policy, now).unwrap(),
+      Duration::from_secs(4 * 60 * 60)
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
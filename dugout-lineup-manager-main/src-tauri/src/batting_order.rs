@@ -0,0 +1,323 @@
+//! Continuous batting orders that rotate across the season.
+//!
+//! Each player's "lateness" is where they batted in the saved lineups of
+//! past games, from 0 (leadoff) to 1 (last), averaged with the latest game
+//! counting most. The new order puts the latest batters first, so whoever
+//! batted at the bottom last game leads off this one. Coaches can pin
+//! players to a spot, e.g. the pitcher bats last.
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::lineups::{self, Lineup};
+use crate::db::roster::{self, Player};
+use crate::db::Database;
+use crate::error::{Error, Result};
+
+/// Weight of each older game relative to the one after it.
+const DECAY: f64 = 0.5;
+
+/// Games further back than this no longer move the order.
+const HISTORY_GAMES: usize = 8;
+
+/// Where a player with no history is treated as having batted.
+const NO_HISTORY: f64 = 0.5;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateBattingOrderRequest {
+  /// Defaults to the first team.
+  pub team_id: Option<String>,
+  /// Ids of the players at the game. Defaults to every active player.
+  pub attendance: Option<Vec<String>>,
+  #[serde(default)]
+  pub constraints: Vec<OrderConstraint>,
+}
+
+/// Holds a player in a spot, e.g. `{ "kind": "last", "playerId": ... }`
+/// for the pitcher.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum OrderConstraint {
+  #[serde(rename_all = "camelCase")]
+  First { player_id: String },
+  #[serde(rename_all = "camelCase")]
+  Last { player_id: String },
+  /// `slot` counts from 1.
+  #[serde(rename_all = "camelCase")]
+  Slot { player_id: String, slot: u32 },
+}
+
+impl OrderConstraint {
+  fn player_id(&self) -> &str {
+    match self {
+      Self::First { player_id }
+      | Self::Last { player_id }
+      | Self::Slot { player_id, .. } => player_id,
+    }
+  }
+
+  /// Zero-based index into an order of `len` batters.
+  fn index(&self, len: usize) -> Option<usize> {
+    match self {
+      Self::First { .. } => Some(0),
+      Self::Last { .. } => len.checked_sub(1),
+      Self::Slot { slot, .. } => (*slot as usize).checked_sub(1).filter(|&i| i < len),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BattingOrderPlan {
+  pub order: Vec<BattingSlot>,
+  /// How evenly the spots have been shared over the season including this
+  /// order, from 0 to 100 where everyone's average spot is the same.
+  pub fairness: f64,
+  /// Past games the order was based on.
+  pub games_considered: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BattingSlot {
+  pub slot: u32,
+  pub player_id: String,
+  pub name: String,
+  /// Their spot in the latest game they played, if any.
+  pub previous_slot: Option<u32>,
+  /// Weighted lateness from 0 (leadoff) to 1 (last) the order was built
+  /// from; `None` without history.
+  pub lateness: Option<f64>,
+  /// Held in place by a constraint rather than by rotation.
+  pub pinned: bool,
+}
+
+/// Position from 0 (first) to 1 (last) of `index` in an order of `len`.
+fn relative(index: usize, len: usize) -> f64 {
+  if len > 1 {
+    index as f64 / (len - 1) as f64
+  } else {
+    NO_HISTORY
+  }
+}
+
+/// Past batting orders as player ids, oldest first.
+fn history(lineups: &[Lineup]) -> Vec<Vec<String>> {
+  lineups
+    .iter()
+    // Drafts and templates never got played
+    .filter(|lineup| lineup.game_id.is_some())
+    .map(|lineup| {
+      lineup
+        .slots
+        .iter()
+        .filter_map(|slot| slot.player_id.clone())
+        .collect::<Vec<_>>()
+    })
+    .filter(|order| order.len() > 1)
+    .collect()
+}
+
+fn lateness(player_id: &str, games: &[Vec<String>]) -> Option<f64> {
+  let (mut total, mut weights) = (0.0, 0.0);
+  let mut weight = 1.0;
+  for order in games.iter().rev().take(HISTORY_GAMES) {
+    if let Some(index) = order.iter().position(|id| id == player_id) {
+      total += weight * relative(index, order.len());
+      weights += weight;
+    }
+    weight *= DECAY;
+  }
+  (weights > 0.0).then(|| total / weights)
+}
+
+/// 100 minus the spread of everyone's season-average spot, scaled so the
+/// widest possible spread (half at the top, half at the bottom) is 0.
+fn fairness(players: &[&Player], games: &[Vec<String>]) -> f64 {
+  let averages: Vec<f64> = players
+    .iter()
+    .filter_map(|player| {
+      let spots: Vec<f64> = games
+        .iter()
+        .filter_map(|order| {
+          let index = order.iter().position(|id| *id == player.id)?;
+          Some(relative(index, order.len()))
+        })
+        .collect();
+      (!spots.is_empty()).then(|| spots.iter().sum::<f64>() / spots.len() as f64)
+    })
+    .collect();
+  if averages.len() < 2 {
+    return 100.0;
+  }
+  let mean = averages.iter().sum::<f64>() / averages.len() as f64;
+  let variance = averages.iter().map(|a| (a - mean).powi(2)).sum::<f64>() / averages.len() as f64;
+  let score = (1.0 - variance.sqrt() / 0.5).clamp(0.0, 1.0) * 100.0;
+  (score * 10.0).round() / 10.0
+}
+
+/// Builds the next order for `players` (in attendance order) from past
+/// `games`, oldest first.
+pub fn rotate(
+  players: &[&Player],
+  games: &[Vec<String>],
+  constraints: &[OrderConstraint],
+) -> Result<BattingOrderPlan> {
+  let len = players.len();
+  let mut order: Vec<Option<usize>> = vec![None; len];
+  let mut pinned = vec![false; len];
+  for constraint in constraints {
+    let player = players
+      .iter()
+      .position(|p| p.id == constraint.player_id())
+      .ok_or_else(|| {
+        Error::Validation(format!("{} isn't batting this game", constraint.player_id()))
+      })?;
+    let index = constraint
+      .index(len)
+      .ok_or_else(|| Error::Validation(format!("There are only {len} batters")))?;
+    if pinned[player] || order[index].is_some() {
+      return Err(Error::Validation(format!(
+        "Conflicting batting order constraints for {}",
+        players[player].name
+      )));
+    }
+    order[index] = Some(player);
+    pinned[player] = true;
+  }
+
+  let scores: Vec<Option<f64>> = players.iter().map(|p| lateness(&p.id, games)).collect();
+  let mut rotating: Vec<usize> = (0..len).filter(|&i| !pinned[i]).collect();
+  // Latest batters first; stable, so ties keep attendance order
+  rotating.sort_by(|&a, &b| {
+    let (a, b) = (scores[a].unwrap_or(NO_HISTORY), scores[b].unwrap_or(NO_HISTORY));
+    b.total_cmp(&a)
+  });
+  let mut rotating = rotating.into_iter();
+  for spot in order.iter_mut().filter(|spot| spot.is_none()) {
+    *spot = rotating.next();
+  }
+
+  let order: Vec<usize> = order.into_iter().flatten().collect();
+  let new_game: Vec<String> = order.iter().map(|&i| players[i].id.clone()).collect();
+  let season: Vec<Vec<String>> = games.iter().cloned().chain([new_game]).collect();
+  Ok(BattingOrderPlan {
+    order: order
+      .iter()
+      .enumerate()
+      .map(|(index, &i)| BattingSlot {
+        slot: index as u32 + 1,
+        player_id: players[i].id.clone(),
+        name: players[i].name.clone(),
+        previous_slot: games.iter().rev().find_map(|order| {
+          order.iter().position(|id| *id == players[i].id).map(|p| p as u32 + 1)
+        }),
+        lateness: scores[i],
+        pinned: pinned[i],
+      })
+      .collect(),
+    fairness: fairness(players, &season),
+    games_considered: games.len().min(HISTORY_GAMES),
+  })
+}
+
+/// Produces the next batting order for a game from the team's saved
+/// lineups, rotating late batters to the top.
+#[tauri::command]
+pub fn rotate_batting_order(
+  db: State<'_, Database>,
+  request: RotateBattingOrderRequest,
+) -> Result<BattingOrderPlan> {
+  let conn = db.conn();
+  let team_id = match request.team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(&conn)?,
+  };
+  let roster = roster::list(&conn, &team_id)?;
+  let players: Vec<&Player> = match &request.attendance {
+    Some(ids) => ids
+      .iter()
+      .map(|id| {
+        roster
+          .iter()
+          .find(|p| &p.id == id)
+          .ok_or_else(|| Error::NotFound(format!("Player {id}")))
+      })
+      .collect::<Result<_>>()?,
+    None => roster.iter().filter(|p| p.status == "active").collect(),
+  };
+  if players.is_empty() {
+    return Err(Error::Validation("No players to build a batting order from".into()));
+  }
+  let games = history(&lineups::list(&conn, &team_id)?);
+  rotate(&players, &games, &request.constraints)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn player(id: &str) -> Player {
+    Player {
+      id: id.into(),
+      team_id: "t1".into(),
+      name: id.to_uppercase(),
+      number: None,
+      primary_position: "SS".into(),
+      secondary_positions: Vec::new(),
+      bats: "R".into(),
+      throws: "R".into(),
+      status: "active".into(),
+      notes: String::new(),
+      created_at: String::new(),
+      updated_at: String::new(),
+    }
+  }
+
+  fn ids(plan: &BattingOrderPlan) -> Vec<&str> {
+    plan.order.iter().map(|s| s.player_id.as_str()).collect()
+  }
+
+  fn game(order: &[&str]) -> Vec<String> {
+    order.iter().map(|id| id.to_string()).collect()
+  }
+
+  #[test]
+  fn late_batters_lead_off_the_next_game() {
+    let roster: Vec<Player> = ["a", "b", "c", "d"].map(player).to_vec();
+    let players: Vec<&Player> = roster.iter().collect();
+    let games = [game(&["a", "b", "c", "d"])];
+
+    let plan = rotate(&players, &games, &[]).unwrap();
+    assert_eq!(ids(&plan), ["d", "c", "b", "a"]);
+    assert_eq!(plan.order[0].previous_slot, Some(4));
+    // Two games in mirror order even everyone out
+    assert_eq!(plan.fairness, 100.0);
+    // A single game is as uneven as a season gets
+    assert!(rotate(&players, &[], &[]).unwrap().fairness < 50.0);
+  }
+
+  #[test]
+  fn pinned_players_keep_their_spot() {
+    let roster: Vec<Player> = ["a", "b", "c", "p"].map(player).to_vec();
+    let players: Vec<&Player> = roster.iter().collect();
+    let games = [game(&["a", "b", "c", "p"])];
+    let pitcher_last = [OrderConstraint::Last {
+      player_id: "p".into(),
+    }];
+
+    let plan = rotate(&players, &games, &pitcher_last).unwrap();
+    assert_eq!(ids(&plan), ["c", "b", "a", "p"]);
+    assert!(plan.order[3].pinned);
+
+    let clash = [
+      pitcher_last[0].clone(),
+      OrderConstraint::Slot {
+        player_id: "a".into(),
+        slot: 4,
+      },
+    ];
+    assert!(rotate(&players, &games, &clash).is_err());
+  }
+}
@@ -0,0 +1,131 @@
+//! The part of launch that needs the database, run once it is open.
+//!
+//! Opening the database applies any pending migrations, which can take a
+//! while after an update, so setup only calls [`start`]. It waits for the
+//! splash to load, opens the database while the splash counts through the
+//! migrations, and then starts everything that reads it: the main window
+//! (which `tauri.conf.json` leaves for this to create, so its webview can't
+//! ask for data too early), the integrity check, the data watcher and the
+//! schedulers. [`crate::health`] waits for [`wait_until_open`] before
+//! showing the main window.
+
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tokio::sync::{oneshot, watch};
+
+use crate::db::{self, Database};
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+use crate::settings::AppSettings;
+use crate::splash::{self, BootStage};
+use crate::{backup, kiosk, network, reminders, theme, window_state};
+
+const MAIN_WINDOW: &str = "main";
+
+/// A splash that never loads shouldn't hold up the launch.
+const SPLASH_LOAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Whether the database is open and the main window created.
+struct Opened(watch::Receiver<bool>);
+
+/// Opens the database in `data_dir` in the background, once
+/// `splash_loaded` is told (if there is a splash), then starts what needs
+/// it. If it can't be opened, the coach is told why and the app exits.
+pub fn start(
+  app: &AppHandle,
+  data_dir: PathBuf,
+  backup_dir: PathBuf,
+  splash_loaded: Option<oneshot::Receiver<()>>,
+) {
+  let (opened, on_opened) = watch::channel(false);
+  app.manage(Opened(on_opened));
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    if let Some(loaded) = splash_loaded {
+      if tokio::time::timeout(SPLASH_LOAD_TIMEOUT, loaded).await.is_err() {
+        log::warn!("Splash didn't load within {:?}", SPLASH_LOAD_TIMEOUT);
+      }
+    }
+    match open(&app, data_dir, backup_dir).await {
+      Ok(()) => {
+        let _ = opened.send(true);
+      }
+      Err(e) => fail(&app, e),
+    }
+  });
+}
+
+async fn open(app: &AppHandle, data_dir: PathBuf, backup_dir: PathBuf) -> Result<()> {
+  splash::report(app, BootStage::RunningMigrations);
+  let db = {
+    let (app, data_dir) = (app.clone(), data_dir.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+      Database::open_with_progress(&data_dir, &backup_dir, |step, total| {
+        splash::report_migration(&app, step, total)
+      })
+    })
+    .await??
+  };
+  app.manage(db);
+
+  let paths = app.state::<AppPaths>();
+  if db::integrity::mark_running(&paths.running) {
+    db::integrity::check_after_unclean_shutdown(app.clone());
+  }
+  #[cfg(desktop)]
+  if let Err(e) = crate::data_watcher::watch(app, &data_dir) {
+    log::warn!("Failed to watch the data directory: {}", e);
+  }
+
+  create_main_window(app)?;
+  kiosk::init(app, &app.state::<RwLock<AppSettings>>().read().unwrap());
+  theme::init(app);
+  backup::start_scheduler(app.clone());
+  reminders::start_scheduler(app.clone());
+  network::start_watcher(app.clone());
+  Ok(())
+}
+
+/// Creates the main window from its entry in `tauri.conf.json`, where it
+/// starts hidden, where it was last left.
+fn create_main_window(app: &AppHandle) -> Result<()> {
+  let config = app
+    .config()
+    .app
+    .windows
+    .iter()
+    .find(|window| window.label == MAIN_WINDOW)
+    .cloned()
+    .ok_or_else(|| Error::NotFound("The main window's configuration".into()))?;
+  let window = tauri::WebviewWindowBuilder::from_config(app, &config)?.build()?;
+  window_state::restore(&window);
+  Ok(())
+}
+
+fn fail(app: &AppHandle, e: Error) {
+  log::error!("Failed to open the database: {}", e);
+  app
+    .dialog()
+    .message(format!("Dugout couldn't open your data: {e}"))
+    .title("Dugout can't start")
+    .kind(MessageDialogKind::Error)
+    .show({
+      let app = app.clone();
+      move |_| app.exit(1)
+    });
+}
+
+/// Resolves once the database is open and the main window created, or
+/// with `false` if opening it failed.
+pub async fn wait_until_open<R: Runtime>(app: &AppHandle<R>) -> bool {
+  let Some(opened) = app.try_state::<Opened>() else {
+    return false;
+  };
+  let mut opened = opened.0.clone();
+  let open = opened.wait_for(|open| *open).await.is_ok();
+  open
+}
@@ -0,0 +1,347 @@
+//! Command-line options that override, for one run, what `run()` would
+//! otherwise work out: where the app keeps its state, the backend's port,
+//! the profile in use and whether the sidecar is started at all. Mostly for
+//! testing, portable installs and reproducing a coach's problem with a copy
+//! of their data:
+//!
+//! ```text
+//! dugout --data-dir ./demo --no-sidecar
+//! ```
+//!
+//! Anything else that isn't an option is a file to open, which
+//! [`crate::deep_link::handle_args`] takes care of. With `--headless` the
+//! app opens no window at all and runs one [`HeadlessCommand`] instead:
+//!
+//! ```text
+//! dugout --headless export-lineup <game-id> --format pdf|csv
+//! ```
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+const USAGE: &str = "\
+Usage: dugout [OPTIONS] [FILE]...
+       dugout [OPTIONS] --headless <COMMAND>
+
+Commands:
+  export-lineup <GAME-ID> --format <pdf|csv> [--output <FILE>]
+                    Save the latest lineup for the game, as a lineup card or
+                    a CSV of the batting order and positions by inning; to
+                    <team> - <game>.<format> in the working directory unless
+                    --output says otherwise
+
+Options:
+  --data-dir <DIR>  Keep settings, profiles and data in DIR instead of the
+                    usual per-user directory
+  --port <PORT>     Start the backend on PORT, whatever the settings say
+  --profile <NAME>  Use this profile (its name or id) for this run
+  --no-sidecar      Don't start the backend sidecar; AI features need a
+                    remote backend
+  --kiosk           Start locked down in kiosk mode
+  --minimized       Start in the tray without opening a window
+  --headless        Run the command without opening a window, then exit
+  -h, --help        Print this help
+  -V, --version     Print the version";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliArgs {
+  /// Replaces the per-user app data directory, relative to the working
+  /// directory the app was launched from.
+  pub data_dir: Option<PathBuf>,
+  pub port: Option<u16>,
+  pub profile: Option<String>,
+  pub no_sidecar: bool,
+  /// Run instead of the app.
+  pub headless: Option<HeadlessCommand>,
+  /// The arguments that aren't options: files to open and `dugout://`
+  /// links.
+  pub rest: Vec<String>,
+}
+
+impl CliArgs {
+  /// Where the log files go instead of the per-user log directory, so a
+  /// data directory from the command line holds everything of the run.
+  pub fn log_dir(&self) -> Option<PathBuf> {
+    self.data_dir.as_ref().map(|dir| dir.join("logs"))
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineupFormat {
+  Pdf,
+  Csv,
+}
+
+impl LineupFormat {
+  pub fn extension(self) -> &'static str {
+    match self {
+      Self::Pdf => "pdf",
+      Self::Csv => "csv",
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeadlessCommand {
+  ExportLineup {
+    game_id: String,
+    format: LineupFormat,
+    /// Relative to the working directory, like `data_dir`.
+    output: Option<PathBuf>,
+  },
+}
+
+/// What the command line asks for besides running the app.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Parsed {
+  Run(CliArgs),
+  Help,
+  Version,
+}
+
+/// Flags read where they are used, which only need letting through.
+fn is_handled_elsewhere(flag: &str) -> bool {
+  #[cfg(desktop)]
+  if flag == crate::autostart::MINIMIZED_ARG {
+    return true;
+  }
+  flag == crate::kiosk::KIOSK_ARG
+}
+
+/// Parses the arguments after the program name. Options take their value
+/// as the next argument or after `=`; everything after `--` is left as is.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Parsed, String> {
+  let mut parsed = CliArgs::default();
+  let (mut headless, mut format, mut output) = (false, None, None);
+  let mut args = args.into_iter();
+  while let Some(arg) = args.next() {
+    if arg == "--" {
+      parsed.rest.extend(args.by_ref());
+      break;
+    }
+    if !arg.starts_with("--") || is_handled_elsewhere(&arg) {
+      match arg.as_str() {
+        "-h" => return Ok(Parsed::Help),
+        "-V" => return Ok(Parsed::Version),
+        _ => parsed.rest.push(arg),
+      }
+      continue;
+    }
+
+    let (name, inline) = match arg.split_once('=') {
+      Some((name, value)) => (name.to_string(), Some(value.to_string())),
+      None => (arg, None),
+    };
+    let mut value = || {
+      inline
+        .clone()
+        .or_else(|| args.next())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("{name} needs a value"))
+    };
+    match name.as_str() {
+      "--data-dir" => parsed.data_dir = Some(PathBuf::from(value()?)),
+      "--port" => {
+        let port = value()?;
+        match port.parse::<u16>() {
+          Ok(port) if port > 0 => parsed.port = Some(port),
+          _ => return Err(format!("{port} isn't a port number")),
+        }
+      }
+      "--profile" => parsed.profile = Some(value()?),
+      "--format" => {
+        format = Some(match value()?.to_ascii_lowercase().as_str() {
+          "pdf" => LineupFormat::Pdf,
+          "csv" => LineupFormat::Csv,
+          other => return Err(format!("{other} isn't a format; use pdf or csv")),
+        })
+      }
+      "--output" => output = Some(PathBuf::from(value()?)),
+      "--no-sidecar" | "--headless" | "--help" | "--version" if inline.is_some() => {
+        return Err(format!("{name} doesn't take a value"));
+      }
+      "--no-sidecar" => parsed.no_sidecar = true,
+      "--headless" => headless = true,
+      "--help" => return Ok(Parsed::Help),
+      "--version" => return Ok(Parsed::Version),
+      _ => return Err(format!("Unknown option {name}")),
+    }
+  }
+
+  if !headless {
+    if format.is_some() || output.is_some() {
+      return Err("--format and --output are only for --headless commands".into());
+    }
+    return Ok(Parsed::Run(parsed));
+  }
+  let mut rest = std::mem::take(&mut parsed.rest).into_iter();
+  let command = match (rest.next().as_deref(), rest.next(), rest.next()) {
+    (Some("export-lineup"), Some(game_id), None) => HeadlessCommand::ExportLineup {
+      game_id,
+      format: format.ok_or("export-lineup needs --format pdf or --format csv")?,
+      output,
+    },
+    (Some("export-lineup"), ..) => return Err("export-lineup takes one game id".into()),
+    (Some(other), ..) => return Err(format!("Unknown command {other}")),
+    (None, ..) => return Err("--headless needs a command".into()),
+  };
+  parsed.headless = Some(command);
+  Ok(Parsed::Run(parsed))
+}
+
+static ARGS: OnceLock<CliArgs> = OnceLock::new();
+
+/// Windows release builds are GUI programs, started without a console, so
+/// what they print goes nowhere unless they attach to the console of
+/// whatever started them. The shell doesn't wait for a GUI program, so
+/// the output can land after its next prompt; `start /wait dugout ...`
+/// avoids that.
+#[cfg(windows)]
+fn attach_console() {
+  const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+  #[link(name = "kernel32")]
+  extern "system" {
+    fn AttachConsole(process_id: u32) -> i32;
+  }
+
+  // Fails harmlessly when started from Explorer, with no console to attach
+  // to, or in a debug build, which has its own
+  unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+/// Parses this process's command line, exiting after printing the help or
+/// version if asked for them, or the problem if it can't be parsed.
+pub fn init() {
+  let parsed = parse(std::env::args().skip(1));
+  // Only for a run that prints something, rather than opening the window
+  if !matches!(parsed, Ok(Parsed::Run(CliArgs { headless: None, .. }))) {
+    attach_console();
+  }
+  let parsed = match parsed {
+    Ok(Parsed::Run(mut args)) => {
+      let cwd = std::env::current_dir().unwrap_or_default();
+      if let Some(dir) = args.data_dir.take() {
+        args.data_dir = Some(cwd.join(dir));
+      }
+      if let Some(HeadlessCommand::ExportLineup {
+        output: Some(path), ..
+      }) = &mut args.headless
+      {
+        *path = cwd.join(&*path);
+      }
+      args
+    }
+    Ok(Parsed::Help) => {
+      println!("{USAGE}");
+      std::process::exit(0);
+    }
+    Ok(Parsed::Version) => {
+      println!("dugout {}", env!("CARGO_PKG_VERSION"));
+      std::process::exit(0);
+    }
+    Err(e) => {
+      eprintln!("{e}\n\n{USAGE}");
+      std::process::exit(2);
+    }
+  };
+  let _ = ARGS.set(parsed);
+}
+
+/// This process's options, as parsed by [`init`].
+pub fn args() -> &'static CliArgs {
+  ARGS.get_or_init(CliArgs::default)
+}
+
+/// The files and links in a second launch's arguments, handed to the
+/// running instance. Its options were meant for a new instance, so they
+/// are dropped, and so is the lot if they can't be parsed.
+pub fn forwarded_args(args: impl IntoIterator<Item = String>) -> Vec<String> {
+  match parse(args) {
+    Ok(Parsed::Run(args)) => args.rest,
+    Ok(_) => Vec::new(),
+    Err(e) => {
+      log::warn!("Ignoring a second launch's arguments: {}", e);
+      Vec::new()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse_args(args: &[&str]) -> Result<Parsed, String> {
+    parse(args.iter().map(|arg| arg.to_string()))
+  }
+
+  #[test]
+  fn parses_options_and_leaves_files() {
+    let parsed = parse_args(&[
+      "--data-dir",
+      "./demo",
+      "--no-sidecar",
+      "--port=8123",
+      "--kiosk",
+      "lineup.dugout",
+      "--profile",
+      "Travel Team",
+      "--",
+      "--not-an-option",
+    ]);
+    assert_eq!(
+      parsed,
+      Ok(Parsed::Run(CliArgs {
+        data_dir: Some("./demo".into()),
+        port: Some(8123),
+        profile: Some("Travel Team".into()),
+        no_sidecar: true,
+        headless: None,
+        rest: vec!["--kiosk".into(), "lineup.dugout".into(), "--not-an-option".into()],
+      }))
+    );
+    assert_eq!(parse_args(&[]), Ok(Parsed::Run(CliArgs::default())));
+    assert_eq!(parse_args(&["lineup.dugout", "-h"]), Ok(Parsed::Help));
+    assert_eq!(parse_args(&["--version"]), Ok(Parsed::Version));
+
+    let Ok(Parsed::Run(export)) = parse_args(&[
+      "--headless",
+      "export-lineup",
+      "game-1",
+      "--format=CSV",
+      "--data-dir",
+      "./demo",
+    ]) else {
+      panic!("export-lineup didn't parse");
+    };
+    assert_eq!(
+      export.headless,
+      Some(HeadlessCommand::ExportLineup {
+        game_id: "game-1".into(),
+        format: LineupFormat::Csv,
+        output: None,
+      })
+    );
+    assert!(export.rest.is_empty());
+
+    for bad in [
+      &["--port", "0"][..],
+      &["--port", "dugout"],
+      &["--data-dir"],
+      &["--data-dir="],
+      &["--no-sidecar=yes"],
+      &["--data-dri", "./demo"],
+      &["--format", "pdf"],
+      &["--headless"],
+      &["--headless", "export-lineup", "game-1"],
+      &["--headless", "export-lineup", "game-1", "game-2", "--format", "pdf"],
+      &["--headless", "export-lineup", "game-1", "--format", "docx"],
+      &["--headless", "export-stats"],
+    ] {
+      assert!(parse_args(bad).is_err(), "{bad:?}");
+    }
+  }
+}
@@ -0,0 +1,210 @@
+//! Copying a lineup to paste somewhere else: plain text for the team group
+//! chat, an HTML table for email and documents, or a picture of the
+//! batting order for chat apps that mangle text (GroupMe, WhatsApp).
+
+use serde::Deserialize;
+use tauri::image::Image;
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::db::Database;
+use crate::error::Result;
+use crate::export::pdf::{self, Card};
+use crate::export::raster::{Canvas, Color, GLYPH};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClipboardFormat {
+  #[default]
+  Text,
+  /// A table, with the plain text for apps that don't take HTML.
+  Html,
+  /// The batting order drawn as a picture.
+  Image,
+}
+
+/// The lineup as it would be typed into a group chat.
+fn text(card: &Card) -> String {
+  let mut text = card.heading();
+  text.push('\n');
+  for line in card.batting_order() {
+    text.push('\n');
+    text.push_str(&line);
+  }
+  if !card.bench.is_empty() {
+    text.push_str(&format!("\n\nBench: {}", card.bench.join(", ")));
+  }
+  text
+}
+
+fn escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn html(card: &Card) -> String {
+  let mut html = format!("<p><strong>{}</strong></p>\n<table>\n", escape(&card.heading()));
+  html.push_str("<tr><th>#</th><th>Player</th><th>No.</th><th>Pos</th></tr>\n");
+  for slot in &card.lineup.slots {
+    let Some(name) = &slot.player_name else {
+      continue;
+    };
+    html.push_str(&format!(
+      "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+      slot.slot_number,
+      escape(name),
+      slot.number.map(|n| n.to_string()).unwrap_or_default(),
+      escape(slot.position.as_deref().unwrap_or("")),
+    ));
+  }
+  html.push_str("</table>\n");
+  if !card.bench.is_empty() {
+    html.push_str(&format!("<p>Bench: {}</p>\n", escape(&card.bench.join(", "))));
+  }
+  html
+}
+
+/// Pixels per font pixel for the heading and for the rows.
+const HEADING_SCALE: usize = 3;
+const ROW_SCALE: usize = 2;
+const PADDING: usize = 24;
+/// Space above and below each row's text.
+const ROW_GAP: usize = 6;
+/// Bench names wrap past this many characters.
+const BENCH_WIDTH: usize = 40;
+
+const INK: Color = [0x1a, 0x1a, 0x1a];
+const STRIPE: Color = [0xee, 0xf2, 0xee];
+
+/// Breaks `text` at spaces into lines of at most `width` characters.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+  let mut lines: Vec<String> = Vec::new();
+  for word in text.split(' ') {
+    match lines.last_mut() {
+      Some(line) if line.chars().count() + 1 + word.chars().count() <= width => {
+        line.push(' ');
+        line.push_str(word);
+      }
+      _ => lines.push(word.to_string()),
+    }
+  }
+  lines
+}
+
+/// The batting order drawn as an RGBA picture, one striped row per slot so
+/// it reads at a glance on a phone.
+fn image(card: &Card) -> Image<'static> {
+  let heading = card.heading();
+  let rows = card.batting_order();
+  let bench = if card.bench.is_empty() {
+    Vec::new()
+  } else {
+    wrap(&format!("Bench: {}", card.bench.join(", ")), BENCH_WIDTH)
+  };
+
+  let chars = |lines: &[String]| lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+  let row_height = GLYPH * ROW_SCALE + 2 * ROW_GAP;
+  let heading_height = GLYPH * HEADING_SCALE + 2 * ROW_GAP;
+  let width = (heading.chars().count() * GLYPH * HEADING_SCALE)
+    .max(chars(&rows).max(chars(&bench)) * GLYPH * ROW_SCALE)
+    + 2 * PADDING;
+  let bench_height = if bench.is_empty() {
+    0
+  } else {
+    row_height / 2 + bench.len() * row_height
+  };
+  let height = 2 * PADDING + heading_height + rows.len() * row_height + bench_height;
+
+  let mut canvas = Canvas::new(width, height);
+  canvas.text(PADDING, PADDING + ROW_GAP, &heading, HEADING_SCALE, INK);
+  let mut y = PADDING + heading_height;
+  for (i, row) in rows.iter().enumerate() {
+    if i % 2 == 0 {
+      canvas.fill(PADDING / 2, y, width - PADDING, row_height, STRIPE);
+    }
+    canvas.text(PADDING, y + ROW_GAP, row, ROW_SCALE, INK);
+    y += row_height;
+  }
+  y += row_height / 2;
+  for line in &bench {
+    canvas.text(PADDING, y + ROW_GAP, line, ROW_SCALE, INK);
+    y += row_height;
+  }
+  Image::new_owned(canvas.rgba, width as u32, height as u32)
+}
+
+/// Puts a lineup on the clipboard as text (the default), an HTML table or a
+/// picture, ready to paste into a chat or email.
+#[tauri::command]
+pub fn copy_lineup_to_clipboard(
+  app: AppHandle,
+  db: State<'_, Database>,
+  lineup_id: String,
+  format: Option<ClipboardFormat>,
+) -> Result<()> {
+  let card = pdf::card(&db.conn(), &lineup_id)?;
+  let clipboard = app.clipboard();
+  match format.unwrap_or_default() {
+    ClipboardFormat::Text => clipboard.write_text(text(&card))?,
+    ClipboardFormat::Html => clipboard.write_html(html(&card), Some(text(&card)))?,
+    ClipboardFormat::Image => clipboard.write_image(&image(&card))?,
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::lineups::{Lineup, LineupSlot};
+
+  fn card() -> Card {
+    let slot = |n: u32, name: &str, number: Option<u32>, position: &str| LineupSlot {
+      slot_number: n,
+      player_id: Some(format!("p{n}")),
+      player_name: Some(name.into()),
+      number,
+      position: Some(position.into()),
+    };
+    Card {
+      team_name: "Eagles".into(),
+      game: Some("vs Tigers \u{b7} 2026-04-11".into()),
+      lineup: Lineup {
+        id: "l1".into(),
+        team_id: "t1".into(),
+        name: "Game 1".into(),
+        game_id: Some("g1".into()),
+        use_dh: false,
+        innings: 6,
+        slots: vec![slot(1, "Jake Miller", Some(12), "SS"), slot(2, "Zoë <Z> Park", None, "CF")],
+        positions: Vec::new(),
+        updated_at: String::new(),
+        versions: Default::default(),
+      },
+      bench: vec!["#4 Sam Ortiz".into()],
+    }
+  }
+
+  #[test]
+  fn copies_the_batting_order_in_each_format() {
+    let card = card();
+    assert_eq!(
+      text(&card),
+      "Eagles lineup vs Tigers \u{b7} 2026-04-11\n\n\
+       1. #12 Jake Miller, SS\n2. Zoë <Z> Park, CF\n\nBench: #4 Sam Ortiz"
+    );
+    let row = "<tr><td>2</td><td>Zoë &lt;Z&gt; Park</td><td></td><td>CF</td></tr>";
+    assert!(html(&card).contains(row));
+
+    let picture = image(&card);
+    let (width, height) = (picture.width() as usize, picture.height() as usize);
+    assert_eq!(picture.rgba().len(), width * height * 4);
+    // Heading at triple size sets the width: 36 characters of 24 pixels
+    assert_eq!(width, 36 * 24 + 2 * PADDING);
+    assert!(picture.rgba().chunks(4).any(|pixel| pixel[..3] == INK));
+
+    assert_eq!(wrap("Bench: Ava, Max, Sam", 11), ["Bench: Ava,", "Max, Sam"]);
+  }
+}
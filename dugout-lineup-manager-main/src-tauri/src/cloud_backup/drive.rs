@@ -0,0 +1,107 @@
+//! Google Drive, using the hidden app-data folder so backups don't clutter
+//! the coach's Drive and the token can't read their other files.
+
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, RequestBuilder};
+use serde_json::json;
+
+use crate::oauth::Endpoints;
+
+use super::{CloudTarget, RemoteFile};
+
+const API_URL: &str = "https://www.googleapis.com/drive/v3";
+const UPLOAD_URL: &str = "https://www.googleapis.com/upload/drive/v3";
+
+/// Separates the metadata and contents of an upload; too long to turn up
+/// in a compressed archive by chance.
+const BOUNDARY: &str = "dugout-backup-part";
+
+/// Google issues desktop apps a secret too, though it can't be kept secret.
+const CLIENT_ID: Option<&str> = option_env!("GOOGLE_DRIVE_CLIENT_ID");
+const CLIENT_SECRET: Option<&str> = option_env!("GOOGLE_DRIVE_CLIENT_SECRET");
+
+pub struct GoogleDrive;
+
+/// A `multipart/related` body naming the file and placing it in the
+/// app-data folder, followed by its contents.
+fn multipart(file_name: &str, archive: Vec<u8>) -> Vec<u8> {
+  let metadata = json!({ "name": file_name, "parents": ["appDataFolder"] });
+  let mut body = format!(
+    "--{BOUNDARY}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{metadata}\r\n\
+     --{BOUNDARY}\r\nContent-Type: application/octet-stream\r\n\r\n"
+  )
+  .into_bytes();
+  body.extend(archive);
+  body.extend(format!("\r\n--{BOUNDARY}--\r\n").into_bytes());
+  body
+}
+
+impl CloudTarget for GoogleDrive {
+  fn id(&self) -> &'static str {
+    "google-drive"
+  }
+
+  fn name(&self) -> &'static str {
+    "Google Drive"
+  }
+
+  fn oauth(&self) -> Endpoints {
+    Endpoints {
+      auth_url: "https://accounts.google.com/o/oauth2/v2/auth",
+      token_url: "https://oauth2.googleapis.com/token",
+      client_id: CLIENT_ID,
+      client_secret: CLIENT_SECRET,
+      auth_params: &[
+        ("scope", "https://www.googleapis.com/auth/drive.appdata"),
+        ("access_type", "offline"),
+        ("prompt", "consent"),
+      ],
+    }
+  }
+
+  fn upload(&self, client: &Client, file_name: &str, archive: Vec<u8>) -> RequestBuilder {
+    client
+      .post(format!("{UPLOAD_URL}/files?uploadType=multipart"))
+      .header(CONTENT_TYPE, format!("multipart/related; boundary={BOUNDARY}"))
+      .body(multipart(file_name, archive))
+  }
+
+  fn list(&self, client: &Client) -> RequestBuilder {
+    client.get(format!(
+      "{API_URL}/files?spaces=appDataFolder&fields=files(id,name,size)&pageSize=1000"
+    ))
+  }
+
+  fn parse_list(&self, body: &serde_json::Value) -> Vec<RemoteFile> {
+    let files = body["files"].as_array().map(Vec::as_slice).unwrap_or_default();
+    files
+      .iter()
+      .filter_map(|file| {
+        Some(RemoteFile {
+          id: file["id"].as_str()?.to_string(),
+          name: file["name"].as_str()?.to_string(),
+          // Drive sends sizes as strings
+          size: file["size"].as_str().and_then(|s| s.parse().ok()).unwrap_or_default(),
+        })
+      })
+      .collect()
+  }
+
+  fn download(&self, client: &Client, id: &str) -> RequestBuilder {
+    client.get(format!("{API_URL}/files/{id}?alt=media"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn wraps_the_archive_in_a_multipart_body() {
+    let body = String::from_utf8(multipart("manual-20260511T093000Z.dugout", b"PK".to_vec()))
+      .unwrap();
+    assert!(body.starts_with("--dugout-backup-part\r\n"));
+    assert!(body.contains(r#""parents":["appDataFolder"]"#));
+    assert!(body.ends_with("\r\n\r\nPK\r\n--dugout-backup-part--\r\n"));
+  }
+}
@@ -0,0 +1,100 @@
+//! Dropbox, registered as an app-folder app so uploads land in
+//! `Apps/Dugout` and the token can't see anything else.
+
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, RequestBuilder};
+use serde_json::json;
+
+use crate::oauth::Endpoints;
+
+use super::{CloudTarget, RemoteFile};
+
+const API_URL: &str = "https://api.dropboxapi.com/2";
+const CONTENT_URL: &str = "https://content.dropboxapi.com/2";
+
+/// Content endpoints take their arguments in this header.
+const API_ARG: &str = "Dropbox-API-Arg";
+
+const CLIENT_ID: Option<&str> = option_env!("DROPBOX_CLIENT_ID");
+
+pub struct Dropbox;
+
+impl CloudTarget for Dropbox {
+  fn id(&self) -> &'static str {
+    "dropbox"
+  }
+
+  fn name(&self) -> &'static str {
+    "Dropbox"
+  }
+
+  fn oauth(&self) -> Endpoints {
+    Endpoints {
+      auth_url: "https://www.dropbox.com/oauth2/authorize",
+      token_url: "https://api.dropboxapi.com/oauth2/token",
+      client_id: CLIENT_ID,
+      client_secret: None,
+      auth_params: &[("token_access_type", "offline")],
+    }
+  }
+
+  fn upload(&self, client: &Client, file_name: &str, archive: Vec<u8>) -> RequestBuilder {
+    let arg = json!({ "path": format!("/{file_name}"), "mode": "overwrite" });
+    client
+      .post(format!("{CONTENT_URL}/files/upload"))
+      .header(API_ARG, arg.to_string())
+      .header(CONTENT_TYPE, "application/octet-stream")
+      .body(archive)
+  }
+
+  fn list(&self, client: &Client) -> RequestBuilder {
+    client
+      .post(format!("{API_URL}/files/list_folder"))
+      .json(&json!({ "path": "" }))
+  }
+
+  fn parse_list(&self, body: &serde_json::Value) -> Vec<RemoteFile> {
+    let entries = body["entries"].as_array().map(Vec::as_slice).unwrap_or_default();
+    entries
+      .iter()
+      .filter(|entry| entry[".tag"] == "file")
+      .filter_map(|entry| {
+        Some(RemoteFile {
+          id: entry["id"].as_str()?.to_string(),
+          name: entry["name"].as_str()?.to_string(),
+          size: entry["size"].as_u64().unwrap_or_default(),
+        })
+      })
+      .collect()
+  }
+
+  fn download(&self, client: &Client, id: &str) -> RequestBuilder {
+    client
+      .post(format!("{CONTENT_URL}/files/download"))
+      .header(API_ARG, json!({ "path": id }).to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lists_only_files() {
+    let body = json!({
+      "entries": [
+        { ".tag": "folder", "name": "old", "id": "id:a" },
+        { ".tag": "file", "name": "auto-20260511T093000Z.dugout", "id": "id:b", "size": 2048 },
+      ],
+      "has_more": false,
+    });
+    assert_eq!(
+      Dropbox.parse_list(&body),
+      [RemoteFile {
+        id: "id:b".into(),
+        name: "auto-20260511T093000Z.dugout".into(),
+        size: 2048,
+      }]
+    );
+  }
+}
@@ -0,0 +1,321 @@
+//! Uploading automatic backups to Dropbox or Google Drive, so a lost or
+//! broken laptop doesn't take the season with it.
+//!
+//! `link_cloud_backup` signs the coach in through the browser with
+//! [`oauth`], the same way as TeamSnap: the service sends it back to
+//! `dugout://cloud-backup/callback`, the code is traded for a token, and
+//! the service becomes the backup settings' upload target. From then on
+//! each scheduled backup is uploaded once it's written, into the app's own
+//! folder on the service, which `list_cloud_backups` lists and
+//! `restore_from_cloud` restores from.
+//!
+//! To add a service, implement [`CloudTarget`] in a submodule and return it
+//! from [`target`].
+
+mod drive;
+mod dropbox;
+
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::{Client, RequestBuilder};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State, Url};
+
+use crate::archive::{self, ImportReport};
+use crate::backup::{self, BackupInfo, BackupKind};
+use crate::error::{Error, Result};
+use crate::oauth::{self, Endpoints, Provider};
+use crate::paths::AppPaths;
+use crate::settings::{self, AppSettings, CloudProvider, SETTINGS_CHANGED_EVENT};
+
+/// Emitted with a [`CloudLink`] when sign-in in the browser finishes.
+pub const CLOUD_BACKUP_LINK_EVENT: &str = "cloud-backup-linked";
+
+const REDIRECT_URI: &str = "dugout://cloud-backup/callback";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Uploads and downloads carry a whole archive, on whatever connection the
+/// coach has.
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// A file in the app's folder on a service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteFile {
+  pub id: String,
+  pub name: String,
+  pub size: u64,
+}
+
+/// A storage service backups can be uploaded to. Requests are built here
+/// and sent by this module, which adds the token.
+pub trait CloudTarget: Sync {
+  /// Names the service's keychain entry, e.g. `dropbox`.
+  fn id(&self) -> &'static str;
+  /// Shown to the coach, e.g. "Dropbox".
+  fn name(&self) -> &'static str;
+  fn oauth(&self) -> Endpoints;
+  /// Uploads `archive` into the app's folder as `file_name`.
+  fn upload(&self, client: &Client, file_name: &str, archive: Vec<u8>) -> RequestBuilder;
+  /// Asks for the files in the app's folder.
+  fn list(&self, client: &Client) -> RequestBuilder;
+  /// Reads the files out of the response to [`list`](Self::list).
+  fn parse_list(&self, body: &serde_json::Value) -> Vec<RemoteFile>;
+  /// Asks for the contents of the file `id`.
+  fn download(&self, client: &Client, id: &str) -> RequestBuilder;
+}
+
+/// The service behind `provider`.
+pub fn target(provider: CloudProvider) -> &'static dyn CloudTarget {
+  match provider {
+    CloudProvider::Dropbox => &dropbox::Dropbox,
+    CloudProvider::GoogleDrive => &drive::GoogleDrive,
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudLink {
+  pub provider: CloudProvider,
+  pub linked: bool,
+  /// Why linking failed, ready to show.
+  pub error: Option<String>,
+}
+
+/// A backup uploaded to the linked service.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudBackup {
+  /// The service's id for the file, for `restore_from_cloud`.
+  pub id: String,
+  pub file_name: String,
+  pub kind: BackupKind,
+  pub created_at: DateTime<Utc>,
+  pub size: u64,
+}
+
+/// A sign-in waiting for the browser to come back.
+struct PendingLink {
+  provider: CloudProvider,
+  link: oauth::PendingLink,
+}
+
+#[derive(Default)]
+pub struct CloudBackupState(Mutex<Option<PendingLink>>);
+
+fn oauth_provider(target: &dyn CloudTarget) -> Provider {
+  Provider {
+    name: target.name(),
+    endpoints: target.oauth(),
+    redirect_uri: REDIRECT_URI,
+    keychain_account: format!("cloud-backup-{}", target.id()),
+    error: Error::CloudBackup,
+  }
+}
+
+fn request_error(e: reqwest::Error) -> Error {
+  Error::CloudBackup(e.to_string())
+}
+
+/// Sends a request built by `target` with the linked account's token.
+async fn send(
+  target: &'static dyn CloudTarget,
+  request: RequestBuilder,
+  timeout: Duration,
+) -> Result<reqwest::Response> {
+  let response = request
+    .bearer_auth(oauth_provider(target).access_token().await?)
+    .timeout(timeout)
+    .send()
+    .await
+    .map_err(request_error)?;
+  let status = response.status();
+  if status == reqwest::StatusCode::UNAUTHORIZED {
+    return Err(Error::CloudBackup(format!(
+      "{} sign-in has expired; link it again",
+      target.name()
+    )));
+  }
+  if !status.is_success() {
+    return Err(Error::CloudBackup(format!("{} said {status}", target.name())));
+  }
+  Ok(response)
+}
+
+/// The configured upload target, if an account is linked.
+fn configured(app: &AppHandle) -> Option<CloudProvider> {
+  app.state::<RwLock<AppSettings>>().read().unwrap().backup.cloud
+}
+
+fn require_configured(app: &AppHandle) -> Result<CloudProvider> {
+  configured(app).ok_or_else(|| Error::Validation("No cloud backup account is linked".into()))
+}
+
+/// Saves `provider` as the upload target and tells the webview.
+fn set_configured(app: &AppHandle, provider: Option<CloudProvider>) -> Result<()> {
+  let paths = app.state::<AppPaths>();
+  let state = app.state::<RwLock<AppSettings>>();
+  let updated = {
+    let mut settings = state.write().unwrap();
+    let mut updated = settings.clone();
+    updated.backup.cloud = provider;
+    settings::save(&paths.settings, &updated)?;
+    *settings = updated.clone();
+    updated
+  };
+  let _ = app.emit(SETTINGS_CHANGED_EVENT, updated);
+  Ok(())
+}
+
+async fn upload(provider: CloudProvider, backup: &BackupInfo) -> Result<()> {
+  let target = target(provider);
+  let archive = tokio::fs::read(&backup.path).await?;
+  let request = target.upload(&Client::new(), &backup.file_name, archive);
+  send(target, request, TRANSFER_TIMEOUT).await?;
+  log::info!("Uploaded backup {} to {}", backup.file_name, target.name());
+  Ok(())
+}
+
+/// Uploads a freshly written backup to the linked service, if any. A
+/// failure is only logged; the backup is still on disk.
+pub fn upload_in_background(app: &AppHandle, backup: BackupInfo) {
+  let Some(provider) = configured(app) else {
+    return;
+  };
+  tauri::async_runtime::spawn(async move {
+    if let Err(e) = upload(provider, &backup).await {
+      log::warn!("Failed to upload backup {}: {}", backup.file_name, e);
+    }
+  });
+}
+
+async fn list(provider: CloudProvider) -> Result<Vec<CloudBackup>> {
+  let target = target(provider);
+  let response = send(target, target.list(&Client::new()), REQUEST_TIMEOUT).await?;
+  let body: serde_json::Value = response.json().await.map_err(request_error)?;
+  let mut backups: Vec<_> = target
+    .parse_list(&body)
+    .into_iter()
+    .filter_map(|file| {
+      let (kind, created_at) = backup::parse_file_name(&file.name)?;
+      Some(CloudBackup {
+        id: file.id,
+        file_name: file.name,
+        kind,
+        created_at,
+        size: file.size,
+      })
+    })
+    .collect();
+  backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+  Ok(backups)
+}
+
+/// Whether `url` is a service sending the browser back after sign-in.
+pub fn is_callback(url: &Url) -> bool {
+  url.scheme() == crate::deep_link::SCHEME && url.host_str() == Some("cloud-backup")
+}
+
+/// Finishes a sign-in from the callback link and reports the outcome with
+/// [`CLOUD_BACKUP_LINK_EVENT`].
+pub fn handle_callback(app: &AppHandle, url: Url) {
+  let Some(pending) = app.state::<CloudBackupState>().0.lock().unwrap().take() else {
+    log::warn!("Ignoring cloud backup sign-in that wasn't started here");
+    return;
+  };
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let provider = pending.provider;
+    let link = match finish_link(&app, &url, pending).await {
+      Ok(()) => CloudLink {
+        provider,
+        linked: true,
+        error: None,
+      },
+      Err(e) => {
+        log::warn!("Cloud backup sign-in failed: {}", e);
+        CloudLink {
+          provider,
+          linked: false,
+          error: Some(e.to_string()),
+        }
+      }
+    };
+    let _ = app.emit(CLOUD_BACKUP_LINK_EVENT, link);
+  });
+}
+
+async fn finish_link(app: &AppHandle, url: &Url, pending: PendingLink) -> Result<()> {
+  let target = target(pending.provider);
+  oauth_provider(target).finish_link(url, &pending.link).await?;
+  set_configured(app, Some(pending.provider))?;
+  log::info!("Linked {} for backups", target.name());
+  Ok(())
+}
+
+/// Opens the service's sign-in page in the browser. The result arrives as
+/// [`CLOUD_BACKUP_LINK_EVENT`], and once linked, automatic backups are
+/// uploaded there.
+#[tauri::command]
+pub fn link_cloud_backup(
+  app: AppHandle,
+  state: State<'_, CloudBackupState>,
+  provider: CloudProvider,
+) -> Result<()> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  let link = oauth_provider(target(provider)).start_link(&app)?;
+  let pending = PendingLink { provider, link };
+  *state.0.lock().unwrap() = Some(pending);
+  Ok(())
+}
+
+/// Forgets the service's token, and stops uploading there if it was the
+/// upload target. Backups already uploaded stay on the service.
+#[tauri::command]
+pub async fn unlink_cloud_backup(app: AppHandle, provider: CloudProvider) -> Result<()> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  let target = target(provider);
+  oauth_provider(target).unlink().await?;
+  if configured(&app) == Some(provider) {
+    set_configured(&app, None)?;
+  }
+  log::info!("Unlinked {} for backups", target.name());
+  Ok(())
+}
+
+/// Lists the backups uploaded to the linked service, newest first.
+#[tauri::command]
+pub async fn list_cloud_backups(app: AppHandle) -> Result<Vec<CloudBackup>> {
+  list(require_configured(&app)?).await
+}
+
+/// Downloads one of the backups listed by `list_cloud_backups` and
+/// restores it, like `restore_backup`.
+#[tauri::command]
+pub async fn restore_from_cloud(app: AppHandle, id: String) -> Result<ImportReport> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  let provider = require_configured(&app)?;
+  let target = target(provider);
+  let backup = list(provider)
+    .await?
+    .into_iter()
+    .find(|b| b.id == id)
+    .ok_or_else(|| Error::NotFound(format!("Cloud backup {id}")))?;
+  let response = send(target, target.download(&Client::new(), &backup.id), TRANSFER_TIMEOUT).await?;
+  let archive = response.bytes().await.map_err(request_error)?;
+
+  let path = app.state::<AppPaths>().staging.join(&backup.file_name);
+  tokio::fs::write(&path, &archive).await?;
+  log::info!("Downloaded backup {} from {}", backup.file_name, target.name());
+  let report = {
+    let path = path.clone();
+    tauri::async_runtime::spawn_blocking(move || archive::restore(&app, &path)).await?
+  };
+  if let Err(e) = std::fs::remove_file(&path) {
+    log::warn!("Failed to remove downloaded backup {:?}: {}", path, e);
+  }
+  report
+}
+
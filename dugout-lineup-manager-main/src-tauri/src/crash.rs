@@ -0,0 +1,241 @@
+//! Crash reports.
+//!
+//! The panic hook installed at the top of `run()` writes a JSON report to
+//! the crashes directory. Reports stay there until the user reviews them
+//! on a later launch and either dismisses or submits each one.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::panic::Location;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State, Url};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::error::{Error, Result};
+use crate::logging;
+use crate::paths::AppPaths;
+
+/// Set once the app data directory is known; panics before that are only
+/// printed by the default hook.
+static CRASH_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+const NEW_ISSUE_URL: &str = "https://github.com/jordanheckler-HMM/dugout-app/issues/new";
+
+/// Backtrace lines included in a submitted issue; the full report stays on
+/// disk.
+const ISSUE_BACKTRACE_LINES: usize = 40;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+  /// File stem of the report in the crashes directory.
+  pub id: String,
+  pub created_at: String,
+  pub app_version: String,
+  pub os: String,
+  pub arch: String,
+  pub thread: Option<String>,
+  pub message: String,
+  /// `file:line:column` of the panic.
+  pub location: Option<String>,
+  pub backtrace: String,
+  /// The last [`logging::RECENT_LINES`] log lines.
+  pub log: Vec<String>,
+}
+
+impl CrashReport {
+  fn capture(payload: &(dyn Any + Send), location: Option<&Location>) -> Self {
+    let now = chrono::Utc::now();
+    Self {
+      id: format!(
+        "crash-{}-{}",
+        now.format("%Y%m%dT%H%M%SZ"),
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+      ),
+      created_at: now.to_rfc3339(),
+      app_version: env!("CARGO_PKG_VERSION").to_string(),
+      os: std::env::consts::OS.to_string(),
+      arch: std::env::consts::ARCH.to_string(),
+      thread: std::thread::current().name().map(str::to_string),
+      message: panic_message(payload),
+      location: location.map(|l| l.to_string()),
+      backtrace: Backtrace::force_capture().to_string(),
+      log: logging::recent_lines(),
+    }
+  }
+
+  /// A prefilled "new issue" link. Logs are left out: they can mention
+  /// player names.
+  pub fn issue_url(&self) -> Url {
+    let backtrace: Vec<&str> = self
+      .backtrace
+      .lines()
+      .take(ISSUE_BACKTRACE_LINES)
+      .collect();
+    let body = format!(
+      "**Dugout {}** on {} ({})\n\nPanicked at {}: {}\n\n```\n{}\n```\n",
+      self.app_version,
+      self.os,
+      self.arch,
+      self.location.as_deref().unwrap_or("unknown location"),
+      self.message,
+      backtrace.join("\n")
+    );
+    let mut url = Url::parse(NEW_ISSUE_URL).expect("issue URL is valid");
+    url
+      .query_pairs_mut()
+      .append_pair("title", &format!("Crash: {}", self.message))
+      .append_pair("body", &body)
+      .append_pair("labels", "crash");
+    url
+  }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+  if let Some(message) = payload.downcast_ref::<&str>() {
+    message.to_string()
+  } else if let Some(message) = payload.downcast_ref::<String>() {
+    message.clone()
+  } else {
+    "Box<dyn Any>".to_string()
+  }
+}
+
+/// Chains a hook that writes a report before the default one runs.
+pub fn install_panic_hook() {
+  let default = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |info| {
+    if let Some(dir) = CRASH_DIR.get() {
+      let report = CrashReport::capture(info.payload(), info.location());
+      match write(dir, &report) {
+        Ok(path) => eprintln!("Crash report written to {:?}", path),
+        Err(e) => eprintln!("Failed to write crash report: {}", e),
+      }
+    }
+    default(info);
+  }));
+}
+
+/// Starts saving reports into `dir`.
+pub fn set_dir(dir: PathBuf) {
+  let _ = CRASH_DIR.set(dir);
+}
+
+fn report_path(dir: &Path, id: &str) -> Result<PathBuf> {
+  let valid = id
+    .chars()
+    .all(|c| c.is_ascii_alphanumeric() || c == '-');
+  if !valid || id.is_empty() {
+    return Err(Error::Validation(format!("Invalid crash report id {id:?}")));
+  }
+  Ok(dir.join(format!("{id}.json")))
+}
+
+pub fn write(dir: &Path, report: &CrashReport) -> Result<PathBuf> {
+  std::fs::create_dir_all(dir)?;
+  let path = report_path(dir, &report.id)?;
+  std::fs::write(&path, serde_json::to_vec_pretty(report)?)?;
+  Ok(path)
+}
+
+/// Reports in `dir`, newest first. Unreadable files are skipped.
+pub fn list(dir: &Path) -> Result<Vec<CrashReport>> {
+  let entries = match std::fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(e.into()),
+  };
+  let mut reports = Vec::new();
+  for entry in entries {
+    let path = entry?.path();
+    if path.extension().is_some_and(|ext| ext == "json") {
+      match std::fs::read(&path).map(|bytes| serde_json::from_slice::<CrashReport>(&bytes)) {
+        Ok(Ok(report)) => reports.push(report),
+        _ => log::warn!("Skipping unreadable crash report {:?}", path),
+      }
+    }
+  }
+  reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+  Ok(reports)
+}
+
+fn load(dir: &Path, id: &str) -> Result<CrashReport> {
+  let path = report_path(dir, id)?;
+  match std::fs::read(&path) {
+    Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+      Err(Error::NotFound(format!("Crash report {id}")))
+    }
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// Crash reports left by earlier runs that the user hasn't reviewed.
+#[tauri::command]
+pub fn get_pending_crash_reports(paths: State<'_, AppPaths>) -> Result<Vec<CrashReport>> {
+  list(&paths.crashes)
+}
+
+/// Deletes a report without sending it.
+#[tauri::command]
+pub fn dismiss_crash_report(paths: State<'_, AppPaths>, id: String) -> Result<()> {
+  let path = report_path(&paths.crashes, &id)?;
+  match std::fs::remove_file(path) {
+    Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+    _ => Ok(()),
+  }
+}
+
+/// Opens a prefilled GitHub issue for the report in the browser, then
+/// deletes it locally.
+#[tauri::command]
+pub fn submit_crash_report(app: AppHandle, paths: State<'_, AppPaths>, id: String) -> Result<()> {
+  let report = load(&paths.crashes, &id)?;
+  app
+    .opener()
+    .open_url(report.issue_url().as_str(), None::<&str>)
+    .map_err(|e| Error::Validation(format!("Couldn't open the browser: {e}")))?;
+  dismiss_crash_report(paths, id)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("dugout-crashes-{}", uuid::Uuid::new_v4()))
+  }
+
+  #[test]
+  fn writes_and_lists_reports() {
+    let dir = temp_dir();
+    let payload: Box<dyn Any + Send> = Box::new(String::from("lineup slot out of range"));
+    let report = CrashReport::capture(payload.as_ref(), None);
+    write(&dir, &report).unwrap();
+    std::fs::write(dir.join("garbage.json"), "{").unwrap();
+
+    assert_eq!(list(&dir).unwrap(), vec![report.clone()]);
+    assert_eq!(load(&dir, &report.id).unwrap().message, "lineup slot out of range");
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn rejects_ids_that_escape_the_directory() {
+    assert!(report_path(Path::new("/tmp"), "../settings").is_err());
+    assert!(report_path(Path::new("/tmp"), "").is_err());
+    assert!(report_path(Path::new("/tmp"), "crash-20240511T120000Z-ab12cd34").is_ok());
+  }
+
+  #[test]
+  fn leaves_logs_out_of_the_issue() {
+    let payload: Box<dyn Any + Send> = Box::new("boom");
+    let mut report = CrashReport::capture(payload.as_ref(), None);
+    report.log = vec!["Saved lineup for Ava Díaz".into()];
+    let url = report.issue_url().to_string();
+    assert!(url.contains("Crash%3A+boom") || url.contains("Crash:+boom"));
+    assert!(!url.contains("Ava"));
+  }
+}
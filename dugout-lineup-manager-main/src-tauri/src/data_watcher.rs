@@ -0,0 +1,168 @@
+//! Notices when something other than the shell changes the data directory,
+//! such as a sync client bringing in edits made on another computer or the
+//! backend writing its own files, and sends a [`DATA_CHANGED_EVENT`] so the
+//! webview reloads instead of showing stale lineups.
+//!
+//! Changes are gathered until the folder has been quiet for [`DEBOUNCE`].
+//! The database's files change with every write the shell makes, so they
+//! only count once SQLite reports that another connection committed. The
+//! notes search index is the shell's own cache and is ignored. Files the
+//! shell writes itself, like imported images, are reported too; reloading
+//! for them is harmless.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::archive::{is_database_file, is_search_index, relative_name};
+use crate::db::Database;
+use crate::error::Result;
+use crate::stats::StatsCache;
+
+/// Emitted with a [`DataChanged`] after outside changes settle.
+pub const DATA_CHANGED_EVENT: &str = "data-changed";
+
+/// How long the folder has to be quiet before changes are reported, so a
+/// sync client writing many files causes one reload.
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataChanged {
+  /// Another program wrote to the database.
+  pub database: bool,
+  /// Other files that changed, relative to the data directory.
+  pub files: Vec<String>,
+}
+
+/// Whether any of `paths` are database files, and the others relative to
+/// whichever of `roots` they're under. The search index is left out.
+fn classify(roots: &[&Path], paths: impl IntoIterator<Item = PathBuf>) -> (bool, Vec<String>) {
+  let mut database = false;
+  let mut files = Vec::new();
+  for path in paths {
+    let Some(root) = roots.iter().find(|root| path.starts_with(root)) else {
+      continue;
+    };
+    let relative = relative_name(root, &path);
+    if is_database_file(&relative) {
+      database = true;
+    } else if !relative.is_empty() && !is_search_index(&relative) {
+      files.push(relative);
+    }
+  }
+  files.sort();
+  files.dedup();
+  (database, files)
+}
+
+/// Bumped by SQLite whenever another connection commits to the database.
+fn data_version(conn: &Connection) -> Result<i64> {
+  Ok(conn.query_row("PRAGMA data_version", [], |row| row.get(0))?)
+}
+
+struct Watching {
+  dir: PathBuf,
+  _debouncer: Debouncer<RecommendedWatcher>,
+}
+
+/// The watcher on the data directory in use, if it could be started.
+#[derive(Default)]
+pub struct DataWatcher(Mutex<Option<Watching>>);
+
+/// Watches `dir`, the data directory now in use, instead of any before it.
+/// Call after the database there is open.
+pub fn watch(app: &AppHandle, dir: &Path) -> Result<()> {
+  let state = app.state::<DataWatcher>();
+  let mut watching = state.0.lock().unwrap();
+  if watching.as_ref().is_some_and(|w| w.dir == dir) {
+    return Ok(());
+  }
+  *watching = None;
+
+  // Events may name the folder the way the OS resolves it, e.g. through
+  // /private on macOS
+  let root = dir.to_path_buf();
+  let resolved = dir.canonicalize().unwrap_or_else(|_| root.clone());
+  let mut seen_version = data_version(&app.state::<Database>().conn())?;
+  let handle = app.clone();
+  let mut debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| {
+    let events = match result {
+      Ok(events) => events,
+      Err(e) => {
+        log::warn!("Watching the data directory failed: {}", e);
+        return;
+      }
+    };
+    let (database, files) = classify(&[&root, &resolved], events.into_iter().map(|e| e.path));
+    let mut changed = DataChanged {
+      database: false,
+      files,
+    };
+    if database {
+      match data_version(&handle.state::<Database>().conn()) {
+        Ok(version) => {
+          changed.database = version != seen_version;
+          seen_version = version;
+        }
+        Err(e) => log::warn!("Failed to check the database for outside changes: {}", e),
+      }
+    }
+    if changed == DataChanged::default() {
+      return;
+    }
+
+    if changed.database {
+      handle.state::<StatsCache>().clear();
+    }
+    log::info!(
+      "Data changed outside the app (database: {}, files: {})",
+      changed.database,
+      changed.files.len()
+    );
+    let _ = handle.emit(DATA_CHANGED_EVENT, changed);
+  })?;
+  debouncer.watcher().watch(dir, RecursiveMode::Recursive)?;
+
+  log::info!("Watching {:?} for outside changes", dir);
+  *watching = Some(Watching {
+    dir: dir.to_path_buf(),
+    _debouncer: debouncer,
+  });
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sorts_out_database_files_and_skips_the_search_index() {
+    let root = Path::new("/data");
+    let resolved = Path::new("/private/data");
+    let (database, files) = classify(
+      &[root, resolved],
+      [
+        "/data/dugout.db-wal",
+        "/private/data/images/logo.png",
+        "/data/images/logo.png",
+        "/data/search-index/meta.json",
+        "/data",
+        "/elsewhere/notes.txt",
+      ]
+      .map(PathBuf::from),
+    );
+    assert!(database);
+    assert_eq!(files, ["images/logo.png"]);
+
+    let (database, files) = classify(&[root], [PathBuf::from("/data/backend.json")]);
+    assert!(!database);
+    assert_eq!(files, ["backend.json"]);
+  }
+}
@@ -0,0 +1,252 @@
+//! AI requests made while offline, kept until they can be sent.
+//!
+//! Requests that need the remote AI provider are queued here when the
+//! network is down and replayed by `network` once it comes back. The
+//! response is stored with the request until the frontend has shown it, so
+//! nothing is lost if the app closes in between.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::{new_id, timestamp, Database};
+use crate::error::{Error, Result};
+
+/// Attempts before a request that keeps failing is given up on.
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// Backend operations that can be queued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueuedKind {
+  /// `POST /lyra/chat/stream`; the streamed text is collected.
+  Chat,
+  /// `POST /lyra/analyze`.
+  Analyze,
+}
+
+impl QueuedKind {
+  /// Sidecar endpoint the request is replayed against.
+  pub fn path(self) -> &'static str {
+    match self {
+      Self::Chat => "/lyra/chat/stream",
+      Self::Analyze => "/lyra/analyze",
+    }
+  }
+
+  fn as_str(self) -> &'static str {
+    match self {
+      Self::Chat => "chat",
+      Self::Analyze => "analyze",
+    }
+  }
+
+  fn parse(value: &str) -> Option<Self> {
+    [Self::Chat, Self::Analyze]
+      .into_iter()
+      .find(|kind| kind.as_str() == value)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueStatus {
+  Pending,
+  /// Sent; `response` holds the result.
+  Done,
+  /// Rejected by the backend or out of attempts; `last_error` says why.
+  Failed,
+}
+
+impl QueueStatus {
+  fn as_str(self) -> &'static str {
+    match self {
+      Self::Pending => "pending",
+      Self::Done => "done",
+      Self::Failed => "failed",
+    }
+  }
+
+  fn parse(value: &str) -> Self {
+    match value {
+      "done" => Self::Done,
+      "failed" => Self::Failed,
+      _ => Self::Pending,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedRequest {
+  pub id: String,
+  pub kind: QueuedKind,
+  /// JSON body sent to the endpoint.
+  pub payload: serde_json::Value,
+  pub status: QueueStatus,
+  pub attempts: u32,
+  pub response: Option<String>,
+  pub last_error: Option<String>,
+  pub created_at: String,
+  pub updated_at: String,
+}
+
+const QUEUE_COLUMNS: &str =
+  "id, kind, payload, status, attempts, response, last_error, created_at, updated_at";
+
+fn request_from_row(row: &Row<'_>) -> rusqlite::Result<QueuedRequest> {
+  let kind: String = row.get(1)?;
+  let payload: String = row.get(2)?;
+  let status: String = row.get(3)?;
+  Ok(QueuedRequest {
+    id: row.get(0)?,
+    kind: QueuedKind::parse(&kind).ok_or_else(|| {
+      rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, kind.into())
+    })?,
+    payload: serde_json::from_str(&payload).map_err(|e| {
+      rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+    })?,
+    status: QueueStatus::parse(&status),
+    attempts: row.get(4)?,
+    response: row.get(5)?,
+    last_error: row.get(6)?,
+    created_at: row.get(7)?,
+    updated_at: row.get(8)?,
+  })
+}
+
+pub fn enqueue(conn: &Connection, kind: QueuedKind, payload: &serde_json::Value) -> Result<QueuedRequest> {
+  if !payload.is_object() {
+    return Err(Error::Validation("Queued request payload must be a JSON object".into()));
+  }
+  let id = new_id();
+  let now = timestamp();
+  conn.execute(
+    "INSERT INTO ai_queue (id, kind, payload, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+    params![id, kind.as_str(), payload.to_string(), now],
+  )?;
+  get(conn, &id)
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<QueuedRequest> {
+  conn
+    .query_row(
+      &format!("SELECT {QUEUE_COLUMNS} FROM ai_queue WHERE id = ?1"),
+      [id],
+      request_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| Error::NotFound(format!("Queued request {id}")))
+}
+
+/// Every queued request, oldest first.
+pub fn list(conn: &Connection) -> Result<Vec<QueuedRequest>> {
+  let mut stmt = conn.prepare(&format!(
+    "SELECT {QUEUE_COLUMNS} FROM ai_queue ORDER BY created_at, rowid"
+  ))?;
+  let requests = stmt
+    .query_map([], request_from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(requests)
+}
+
+/// Requests still waiting to be sent, oldest first.
+pub fn pending(conn: &Connection) -> Result<Vec<QueuedRequest>> {
+  Ok(
+    list(conn)?
+      .into_iter()
+      .filter(|request| request.status == QueueStatus::Pending)
+      .collect(),
+  )
+}
+
+pub fn complete(conn: &Connection, id: &str, response: &str) -> Result<()> {
+  conn.execute(
+    "UPDATE ai_queue SET status = ?2, attempts = attempts + 1, response = ?3, last_error = NULL, \
+     updated_at = ?4 WHERE id = ?1",
+    params![id, QueueStatus::Done.as_str(), response, timestamp()],
+  )?;
+  Ok(())
+}
+
+/// Records a failed attempt. The request stays pending for the next replay
+/// unless `retry` is false or it has run out of attempts.
+pub fn record_failure(conn: &Connection, id: &str, error: &str, retry: bool) -> Result<()> {
+  let attempts: u32 = conn.query_row(
+    "UPDATE ai_queue SET attempts = attempts + 1, last_error = ?2, updated_at = ?3 \
+     WHERE id = ?1 RETURNING attempts",
+    params![id, error, timestamp()],
+    |row| row.get(0),
+  )?;
+  if !retry || attempts >= MAX_ATTEMPTS {
+    conn.execute(
+      "UPDATE ai_queue SET status = ?2 WHERE id = ?1",
+      params![id, QueueStatus::Failed.as_str()],
+    )?;
+  }
+  Ok(())
+}
+
+pub fn remove(conn: &Connection, id: &str) -> Result<()> {
+  if conn.execute("DELETE FROM ai_queue WHERE id = ?1", [id])? == 0 {
+    return Err(Error::NotFound(format!("Queued request {id}")));
+  }
+  Ok(())
+}
+
+#[tauri::command]
+pub fn list_ai_queue(db: State<'_, Database>) -> Result<Vec<QueuedRequest>> {
+  list(&db.conn())
+}
+
+/// Drops a request from the queue, typically once its response has been
+/// shown.
+#[tauri::command]
+pub fn dismiss_ai_request(db: State<'_, Database>, id: String) -> Result<()> {
+  remove(&db.conn(), &id)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+
+  #[test]
+  fn keeps_requests_until_they_are_sent() {
+    let conn = open_in_memory();
+    let payload = serde_json::json!({ "messages": [], "model": "gpt-4o" });
+    let first = enqueue(&conn, QueuedKind::Chat, &payload).unwrap();
+    let second = enqueue(&conn, QueuedKind::Chat, &payload).unwrap();
+    assert_eq!(first.status, QueueStatus::Pending);
+    assert_eq!(first.payload, payload);
+
+    complete(&conn, &first.id, "Bat Ava leadoff.").unwrap();
+    let pending = pending(&conn).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, second.id);
+
+    let done = get(&conn, &first.id).unwrap();
+    assert_eq!(done.status, QueueStatus::Done);
+    assert_eq!(done.response.as_deref(), Some("Bat Ava leadoff."));
+
+    remove(&conn, &first.id).unwrap();
+    assert_eq!(list(&conn).unwrap().len(), 1);
+  }
+
+  #[test]
+  fn gives_up_after_too_many_attempts() {
+    let conn = open_in_memory();
+    let request = enqueue(&conn, QueuedKind::Analyze, &serde_json::json!({})).unwrap();
+
+    for _ in 1..MAX_ATTEMPTS {
+      record_failure(&conn, &request.id, "timed out", true).unwrap();
+    }
+    assert_eq!(get(&conn, &request.id).unwrap().status, QueueStatus::Pending);
+
+    record_failure(&conn, &request.id, "timed out", true).unwrap();
+    let failed = get(&conn, &request.id).unwrap();
+    assert_eq!(failed.status, QueueStatus::Failed);
+    assert_eq!(failed.attempts, MAX_ATTEMPTS);
+    assert_eq!(failed.last_error.as_deref(), Some("timed out"));
+  }
+}
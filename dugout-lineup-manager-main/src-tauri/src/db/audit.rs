@@ -0,0 +1,385 @@
+//! Who changed what in the roster and lineups, and when.
+//!
+//! Each change is written to `audit_log` with the old and new value of every
+//! field it touched, in the same transaction as the change itself. Edits
+//! synced from a paired device carry that device's name, so a head coach can
+//! see what the assistant changed on their phone last night. The table is
+//! append-only: triggers refuse updates and deletes.
+
+use rusqlite::{params, params_from_iter, types::Value, Connection, Row};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::lineups::Lineup;
+use super::roster::Player;
+use super::{timestamp, Database};
+use crate::error::Result;
+
+/// Entries [`get_audit_log`] returns when no limit is given.
+const DEFAULT_LIMIT: u32 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Entity {
+  Player,
+  Lineup,
+}
+
+impl Entity {
+  fn as_str(self) -> &'static str {
+    match self {
+      Self::Player => "player",
+      Self::Lineup => "lineup",
+    }
+  }
+
+  fn parse(value: &str) -> Self {
+    match value {
+      "lineup" => Self::Lineup,
+      _ => Self::Player,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditAction {
+  Created,
+  Updated,
+  Deleted,
+}
+
+impl AuditAction {
+  fn as_str(self) -> &'static str {
+    match self {
+      Self::Created => "created",
+      Self::Updated => "updated",
+      Self::Deleted => "deleted",
+    }
+  }
+
+  fn parse(value: &str) -> Self {
+    match value {
+      "created" => Self::Created,
+      "deleted" => Self::Deleted,
+      _ => Self::Updated,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+  pub id: i64,
+  pub changed_at: String,
+  /// The paired device the change was synced from; `None` for edits made
+  /// on this one.
+  pub device: Option<String>,
+  pub entity: Entity,
+  pub entity_id: String,
+  /// The player's or lineup's name at the time.
+  pub entity_name: String,
+  pub action: AuditAction,
+  /// The field an update changed, e.g. `number` or `battingOrder`; `None`
+  /// for creates and deletes.
+  pub field: Option<String>,
+  pub old_value: Option<String>,
+  pub new_value: Option<String>,
+}
+
+fn entry_from_row(row: &Row) -> rusqlite::Result<AuditEntry> {
+  Ok(AuditEntry {
+    id: row.get(0)?,
+    changed_at: row.get(1)?,
+    device: row.get(2)?,
+    entity: Entity::parse(&row.get::<_, String>(3)?),
+    entity_id: row.get(4)?,
+    entity_name: row.get(5)?,
+    action: AuditAction::parse(&row.get::<_, String>(6)?),
+    field: row.get(7)?,
+    old_value: row.get(8)?,
+    new_value: row.get(9)?,
+  })
+}
+
+/// Narrows [`list`]; every filter left out matches everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditFilter {
+  pub entity: Option<Entity>,
+  pub entity_id: Option<String>,
+  /// A paired device's name, or `""` for this device's own edits.
+  pub device: Option<String>,
+  /// RFC 3339; changes at or after this.
+  pub since: Option<String>,
+  /// RFC 3339; changes before this.
+  pub until: Option<String>,
+  pub limit: Option<u32>,
+}
+
+/// A record's audited fields as display text, `None` where empty.
+type Fields = Vec<(&'static str, Option<String>)>;
+
+fn text(value: &str) -> Option<String> {
+  (!value.is_empty()).then(|| value.to_string())
+}
+
+fn player_fields(player: &Player) -> Fields {
+  vec![
+    ("name", text(&player.name)),
+    ("number", player.number.map(|n| n.to_string())),
+    ("primaryPosition", text(&player.primary_position)),
+    ("secondaryPositions", text(&player.secondary_positions.join(", "))),
+    ("bats", text(&player.bats)),
+    ("throws", text(&player.throws)),
+    ("status", text(&player.status)),
+    ("notes", text(&player.notes)),
+  ]
+}
+
+fn lineup_fields(lineup: &Lineup) -> Fields {
+  let name_of = |player_id: &str| {
+    lineup
+      .slots
+      .iter()
+      .find(|s| s.player_id.as_deref() == Some(player_id))
+      .and_then(|s| s.player_name.clone())
+      .unwrap_or_else(|| player_id.to_string())
+  };
+  let batting_order: Vec<String> = lineup
+    .slots
+    .iter()
+    .map(|slot| {
+      let who = slot.player_name.as_deref().or(slot.player_id.as_deref()).unwrap_or("empty");
+      match &slot.position {
+        Some(position) => format!("{}. {who} {position}", slot.slot_number),
+        None => format!("{}. {who}", slot.slot_number),
+      }
+    })
+    .collect();
+  let positions: Vec<String> = lineup
+    .positions
+    .iter()
+    .map(|p| format!("{}: {} {}", p.inning, p.position, name_of(&p.player_id)))
+    .collect();
+  vec![
+    ("name", text(&lineup.name)),
+    ("gameId", lineup.game_id.clone()),
+    ("useDh", Some(lineup.use_dh.to_string())),
+    ("innings", Some(lineup.innings.to_string())),
+    ("battingOrder", text(&batting_order.join(", "))),
+    ("positions", text(&positions.join("; "))),
+  ]
+}
+
+struct Record<'a> {
+  device: Option<&'a str>,
+  entity: Entity,
+  entity_id: &'a str,
+  entity_name: &'a str,
+}
+
+impl Record<'_> {
+  fn write(
+    &self,
+    conn: &Connection,
+    action: AuditAction,
+    field: Option<&str>,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+  ) -> Result<()> {
+    conn.execute(
+      "INSERT INTO audit_log (changed_at, device, entity, entity_id, entity_name, action, \
+       field, old_value, new_value) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+      params![
+        timestamp(),
+        self.device,
+        self.entity.as_str(),
+        self.entity_id,
+        self.entity_name,
+        action.as_str(),
+        field,
+        old_value,
+        new_value
+      ],
+    )?;
+    Ok(())
+  }
+
+  fn changed(
+    &self,
+    conn: &Connection,
+    before: Option<Fields>,
+    after: Option<Fields>,
+  ) -> Result<()> {
+    match (before, after) {
+      (None, None) => Ok(()),
+      (None, Some(_)) => self.write(conn, AuditAction::Created, None, None, None),
+      (Some(_), None) => self.write(conn, AuditAction::Deleted, None, None, None),
+      (Some(before), Some(after)) => {
+        for ((field, old), (_, new)) in before.iter().zip(&after) {
+          if old != new {
+            self.write(conn, AuditAction::Updated, Some(field), old.as_deref(), new.as_deref())?;
+          }
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+/// Records a player being created (`before` is `None`), edited or deleted
+/// (`after` is `None`). `device` is the paired device it came from.
+pub(crate) fn player_changed(
+  conn: &Connection,
+  device: Option<&str>,
+  before: Option<&Player>,
+  after: Option<&Player>,
+) -> Result<()> {
+  let Some(player) = after.or(before) else {
+    return Ok(());
+  };
+  let record = Record {
+    device,
+    entity: Entity::Player,
+    entity_id: &player.id,
+    entity_name: &player.name,
+  };
+  record.changed(conn, before.map(player_fields), after.map(player_fields))
+}
+
+/// Like [`player_changed`], for a lineup.
+pub(crate) fn lineup_changed(
+  conn: &Connection,
+  device: Option<&str>,
+  before: Option<&Lineup>,
+  after: Option<&Lineup>,
+) -> Result<()> {
+  let Some(lineup) = after.or(before) else {
+    return Ok(());
+  };
+  let record = Record {
+    device,
+    entity: Entity::Lineup,
+    entity_id: &lineup.id,
+    entity_name: &lineup.name,
+  };
+  record.changed(conn, before.map(lineup_fields), after.map(lineup_fields))
+}
+
+/// Matching entries, newest first.
+pub fn list(conn: &Connection, filter: &AuditFilter) -> Result<Vec<AuditEntry>> {
+  let mut clauses = Vec::new();
+  let mut values: Vec<Value> = Vec::new();
+  let mut add = |column: &str, op: &str, value: String| {
+    values.push(value.into());
+    clauses.push(format!("{column} {op} ?{}", values.len()));
+  };
+  if let Some(entity) = filter.entity {
+    add("entity", "=", entity.as_str().into());
+  }
+  if let Some(entity_id) = &filter.entity_id {
+    add("entity_id", "=", entity_id.clone());
+  }
+  if let Some(device) = filter.device.as_deref().filter(|d| !d.is_empty()) {
+    add("device", "=", device.into());
+  }
+  if let Some(since) = &filter.since {
+    add("changed_at", ">=", since.clone());
+  }
+  if let Some(until) = &filter.until {
+    add("changed_at", "<", until.clone());
+  }
+  if filter.device.as_deref() == Some("") {
+    clauses.push("device IS NULL".into());
+  }
+  let filters = if clauses.is_empty() { "1 = 1".into() } else { clauses.join(" AND ") };
+  values.push(i64::from(filter.limit.unwrap_or(DEFAULT_LIMIT)).into());
+
+  let sql = format!(
+    "SELECT id, changed_at, device, entity, entity_id, entity_name, action, field, \
+     old_value, new_value FROM audit_log WHERE {filters} ORDER BY id DESC LIMIT ?{}",
+    values.len()
+  );
+  let entries = conn
+    .prepare(&sql)?
+    .query_map(params_from_iter(values), entry_from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(entries)
+}
+
+#[tauri::command]
+pub fn get_audit_log(
+  db: State<'_, Database>,
+  filter: Option<AuditFilter>,
+) -> Result<Vec<AuditEntry>> {
+  list(&db.conn(), &filter.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+  use crate::db::roster::{self, NewPlayer, PlayerChanges};
+
+  #[test]
+  fn records_each_changed_field_and_filters_by_device() {
+    let conn = open_in_memory();
+    let player = roster::create(
+      &conn,
+      NewPlayer {
+        team_id: None,
+        name: "Jake Miller".into(),
+        number: Some(12),
+        primary_position: "SS".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap();
+    let changes = PlayerChanges {
+      number: Some(Some(21)),
+      primary_position: Some("2B".into()),
+      ..Default::default()
+    };
+    let edited = roster::update(&conn, &player.id, changes).unwrap();
+    let mut synced = edited.clone();
+    synced.notes = "Sore arm".into();
+    player_changed(&conn, Some("Assistant's iPad"), Some(&edited), Some(&synced)).unwrap();
+
+    let all = list(&conn, &AuditFilter::default()).unwrap();
+    let changed: Vec<_> = all
+      .iter()
+      .map(|e| (e.action, e.field.as_deref(), e.old_value.as_deref(), e.new_value.as_deref()))
+      .collect();
+    assert_eq!(
+      changed,
+      [
+        (AuditAction::Updated, Some("notes"), None, Some("Sore arm")),
+        (AuditAction::Updated, Some("primaryPosition"), Some("SS"), Some("2B")),
+        (AuditAction::Updated, Some("number"), Some("12"), Some("21")),
+        (AuditAction::Created, None, None, None),
+      ]
+    );
+
+    let from_assistant = AuditFilter {
+      device: Some("Assistant's iPad".into()),
+      ..Default::default()
+    };
+    assert_eq!(list(&conn, &from_assistant).unwrap().len(), 1);
+    let local = AuditFilter {
+      device: Some(String::new()),
+      entity: Some(Entity::Player),
+      limit: Some(2),
+      ..Default::default()
+    };
+    let local = list(&conn, &local).unwrap();
+    assert_eq!(local.len(), 2);
+    assert!(local.iter().all(|e| e.device.is_none() && e.entity_name == "Jake Miller"));
+
+    assert!(conn.execute("DELETE FROM audit_log", []).is_err());
+  }
+}
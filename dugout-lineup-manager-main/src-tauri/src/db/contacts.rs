@@ -0,0 +1,132 @@
+//! Parents' email addresses, kept per player so a lineup can be sent home
+//! to the whole team. Stored on this device only; they aren't synced.
+
+use rusqlite::{params, Connection};
+use tauri::State;
+
+use super::{roster, Database};
+use crate::error::{Error, Result};
+
+/// Whether `email` looks like something a mail client will accept. Mail
+/// servers have the final say; this only catches typos and pastes.
+pub fn is_valid_email(email: &str) -> bool {
+  let Some((local, domain)) = email.rsplit_once('@') else {
+    return false;
+  };
+  !local.is_empty()
+    && domain.contains('.')
+    && !domain.starts_with('.')
+    && !domain.ends_with('.')
+    && !email.chars().any(|c| c.is_whitespace() || matches!(c, ',' | ';' | '<' | '>' | '"'))
+}
+
+/// A player's parent emails, in the order they were entered.
+pub fn for_player(conn: &Connection, player_id: &str) -> Result<Vec<String>> {
+  let mut stmt =
+    conn.prepare("SELECT email FROM player_contacts WHERE player_id = ?1 ORDER BY position")?;
+  let emails = stmt
+    .query_map([player_id], |row| row.get(0))?
+    .collect::<rusqlite::Result<_>>()?;
+  Ok(emails)
+}
+
+/// Replaces a player's parent emails, dropping blanks and repeats.
+pub fn set(conn: &mut Connection, player_id: &str, emails: &[String]) -> Result<Vec<String>> {
+  roster::get(conn, player_id)?;
+  let mut kept: Vec<String> = Vec::new();
+  for email in emails.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+    if !is_valid_email(email) {
+      return Err(Error::Validation(format!("{email} isn't an email address")));
+    }
+    if !kept.iter().any(|k| k.eq_ignore_ascii_case(email)) {
+      kept.push(email.to_string());
+    }
+  }
+  let tx = conn.transaction()?;
+  tx.execute("DELETE FROM player_contacts WHERE player_id = ?1", [player_id])?;
+  for (position, email) in kept.iter().enumerate() {
+    tx.execute(
+      "INSERT INTO player_contacts (player_id, email, position) VALUES (?1, ?2, ?3)",
+      params![player_id, email, position as i64],
+    )?;
+  }
+  tx.commit()?;
+  Ok(kept)
+}
+
+/// Every parent email for the team's active players, without repeats
+/// (siblings share parents).
+pub fn for_team(conn: &Connection, team_id: &str) -> Result<Vec<String>> {
+  let mut stmt = conn.prepare(
+    "SELECT c.email FROM player_contacts c JOIN players p ON p.id = c.player_id \
+     WHERE p.team_id = ?1 AND p.status = 'active' \
+     ORDER BY p.name COLLATE NOCASE, c.position",
+  )?;
+  let rows = stmt.query_map([team_id], |row| row.get::<_, String>(0))?;
+  let mut emails: Vec<String> = Vec::new();
+  for email in rows {
+    let email = email?;
+    if !emails.iter().any(|e| e.eq_ignore_ascii_case(&email)) {
+      emails.push(email);
+    }
+  }
+  Ok(emails)
+}
+
+#[tauri::command]
+pub fn get_player_contacts(db: State<'_, Database>, player_id: String) -> Result<Vec<String>> {
+  for_player(&db.conn(), &player_id)
+}
+
+/// Saves a player's parent emails, returning them as stored.
+#[tauri::command]
+pub fn set_player_contacts(
+  db: State<'_, Database>,
+  player_id: String,
+  emails: Vec<String>,
+) -> Result<Vec<String>> {
+  set(&mut db.conn(), &player_id, &emails)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+  use crate::db::roster::NewPlayer;
+
+  fn player(conn: &Connection, name: &str) -> String {
+    roster::create(
+      conn,
+      NewPlayer {
+        team_id: None,
+        name: name.into(),
+        number: None,
+        primary_position: "CF".into(),
+        secondary_positions: Vec::new(),
+        bats: "L".into(),
+        throws: "L".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap()
+    .id
+  }
+
+  #[test]
+  fn collects_team_emails_without_repeats() {
+    let mut conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let ava = player(&conn, "Ava Chen");
+    let max = player(&conn, "Max Chen");
+
+    let emails = ["lin@example.com".into(), " ".into(), "LIN@example.com".into()];
+    assert_eq!(set(&mut conn, &ava, &emails).unwrap(), ["lin@example.com"]);
+    let emails = ["Lin@Example.com".into(), "wei@example.com".into()];
+    set(&mut conn, &max, &emails).unwrap();
+    assert_eq!(for_team(&conn, &team_id).unwrap(), ["lin@example.com", "wei@example.com"]);
+
+    assert!(set(&mut conn, &ava, &["lin at example.com".into()]).is_err());
+    assert_eq!(for_player(&conn, &ava).unwrap(), ["lin@example.com"]);
+  }
+}
@@ -0,0 +1,213 @@
+//! The coach's library of drills, for one team or shared by every team,
+//! which practice plans are written from (see [`crate::practice`]).
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::{new_id, roster, timestamp, Database};
+use crate::error::{Error, Result};
+
+/// What a drill works on, and what a plan can focus on.
+pub const FOCUSES: [&str; 7] = [
+  "hitting",
+  "fielding",
+  "throwing",
+  "pitching",
+  "catching",
+  "baserunning",
+  "general",
+];
+
+/// Longest drill or practice, in minutes.
+pub const MAX_MINUTES: u32 = 240;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Drill {
+  pub id: String,
+  /// `None` for drills every team can use.
+  pub team_id: Option<String>,
+  pub name: String,
+  pub focus: String,
+  pub minutes: u32,
+  pub description: String,
+  pub created_at: String,
+  pub updated_at: String,
+}
+
+/// A drill to create, or to replace when `id` is given.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrillInput {
+  pub id: Option<String>,
+  pub team_id: Option<String>,
+  pub name: String,
+  pub focus: String,
+  pub minutes: u32,
+  #[serde(default)]
+  pub description: String,
+}
+
+const DRILL_COLUMNS: &str =
+  "id, team_id, name, focus, minutes, description, created_at, updated_at";
+
+fn drill_from_row(row: &Row<'_>) -> rusqlite::Result<Drill> {
+  Ok(Drill {
+    id: row.get(0)?,
+    team_id: row.get(1)?,
+    name: row.get(2)?,
+    focus: row.get(3)?,
+    minutes: row.get(4)?,
+    description: row.get(5)?,
+    created_at: row.get(6)?,
+    updated_at: row.get(7)?,
+  })
+}
+
+pub fn validate_minutes(minutes: u32) -> Result<()> {
+  if minutes == 0 || minutes > MAX_MINUTES {
+    return Err(Error::Validation(format!(
+      "Minutes must be between 1 and {MAX_MINUTES}"
+    )));
+  }
+  Ok(())
+}
+
+pub fn validate_focus(focus: &str) -> Result<()> {
+  if !FOCUSES.contains(&focus) {
+    return Err(Error::Validation(format!(
+      "Focus must be one of {}, not {focus:?}",
+      FOCUSES.join(", ")
+    )));
+  }
+  Ok(())
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<Drill> {
+  conn
+    .query_row(
+      &format!("SELECT {DRILL_COLUMNS} FROM drills WHERE id = ?1"),
+      [id],
+      drill_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| Error::NotFound(format!("Drill {id}")))
+}
+
+/// The team's drills and the shared ones, by focus then name.
+pub fn list(conn: &Connection, team_id: &str) -> Result<Vec<Drill>> {
+  let mut stmt = conn.prepare(&format!(
+    "SELECT {DRILL_COLUMNS} FROM drills WHERE team_id = ?1 OR team_id IS NULL \
+     ORDER BY focus, name COLLATE NOCASE"
+  ))?;
+  let drills = stmt
+    .query_map([team_id], drill_from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(drills)
+}
+
+pub fn save(conn: &Connection, input: DrillInput) -> Result<Drill> {
+  let name = input.name.trim().to_string();
+  if name.is_empty() {
+    return Err(Error::Validation("A drill needs a name".into()));
+  }
+  validate_focus(&input.focus)?;
+  validate_minutes(input.minutes)?;
+  if let Some(team_id) = &input.team_id {
+    roster::team_name(conn, team_id)?;
+  }
+
+  let now = timestamp();
+  let id = match input.id {
+    Some(id) => {
+      get(conn, &id)?;
+      conn.execute(
+        "UPDATE drills SET team_id = ?2, name = ?3, focus = ?4, minutes = ?5, \
+         description = ?6, updated_at = ?7 WHERE id = ?1",
+        params![id, input.team_id, name, input.focus, input.minutes, input.description, now],
+      )?;
+      id
+    }
+    None => {
+      let id = new_id();
+      conn.execute(
+        "INSERT INTO drills (id, team_id, name, focus, minutes, description, created_at, \
+         updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+        params![id, input.team_id, name, input.focus, input.minutes, input.description, now],
+      )?;
+      id
+    }
+  };
+  get(conn, &id)
+}
+
+pub fn delete(conn: &Connection, id: &str) -> Result<()> {
+  if conn.execute("DELETE FROM drills WHERE id = ?1", [id])? == 0 {
+    return Err(Error::NotFound(format!("Drill {id}")));
+  }
+  Ok(())
+}
+
+/// Lists a team's drills and the shared ones, defaulting to the first team.
+#[tauri::command]
+pub fn list_drills(db: State<'_, Database>, team_id: Option<String>) -> Result<Vec<Drill>> {
+  let conn = db.conn();
+  let team_id = match team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(&conn)?,
+  };
+  list(&conn, &team_id)
+}
+
+#[tauri::command]
+pub fn save_drill(db: State<'_, Database>, drill: DrillInput) -> Result<Drill> {
+  save(&db.conn(), drill)
+}
+
+#[tauri::command]
+pub fn delete_drill(db: State<'_, Database>, id: String) -> Result<()> {
+  delete(&db.conn(), &id)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+
+  fn drill(team_id: Option<&str>, name: &str, focus: &str) -> DrillInput {
+    DrillInput {
+      id: None,
+      team_id: team_id.map(str::to_string),
+      name: name.into(),
+      focus: focus.into(),
+      minutes: 15,
+      description: String::new(),
+    }
+  }
+
+  #[test]
+  fn lists_team_and_shared_drills() {
+    let conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let tee = save(&conn, drill(Some(&team_id), "Tee work", "hitting")).unwrap();
+    save(&conn, drill(None, "Relay race", "throwing")).unwrap();
+    assert!(save(&conn, drill(None, "Naps", "resting")).is_err());
+    assert!(save(&conn, drill(Some("nope"), "Tee work", "hitting")).is_err());
+
+    let renamed = save(
+      &conn,
+      DrillInput {
+        id: Some(tee.id.clone()),
+        ..drill(Some(&team_id), "Soft toss", "hitting")
+      },
+    )
+    .unwrap();
+    assert_eq!(renamed.created_at, tee.created_at);
+    let names: Vec<String> = list(&conn, &team_id).unwrap().into_iter().map(|d| d.name).collect();
+    assert_eq!(names, ["Soft toss", "Relay race"]);
+
+    delete(&conn, &tee.id).unwrap();
+    assert!(delete(&conn, &tee.id).is_err());
+  }
+}
@@ -0,0 +1,260 @@
+//! Optional at-rest encryption of the database with SQLCipher, for leagues
+//! that keep medical notes and guardian contacts in the app.
+//!
+//! The key lives in the OS keychain, never on disk. Whether a file is
+//! encrypted is read from the file itself (plaintext SQLite starts with a
+//! fixed header), so there is no setting to fall out of step with it.
+//! Backups taken while encrypted use the same key, so they only restore on
+//! a computer whose keychain has it.
+
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use super::{migrations, Database, DB_FILE};
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+
+/// Keychain account holding the database key.
+#[cfg(desktop)]
+const KEYCHAIN_ACCOUNT: &str = "database-key";
+
+/// How every plaintext SQLite file begins.
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+  pub enabled: bool,
+  /// Whether this platform has a keychain to hold the key.
+  pub available: bool,
+}
+
+/// Whether the database file at `path` is encrypted. Missing and empty
+/// files aren't; SQLite writes a new database's header lazily.
+pub fn is_encrypted(path: &Path) -> Result<bool> {
+  use std::io::Read;
+
+  let mut header = [0u8; 16];
+  let read = match std::fs::File::open(path) {
+    Ok(mut file) => file.read(&mut header)?,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+    Err(e) => return Err(e.into()),
+  };
+  Ok(read > 0 && &header != PLAINTEXT_HEADER)
+}
+
+#[cfg(desktop)]
+fn keychain() -> Result<keyring::Entry> {
+  Ok(keyring::Entry::new(crate::api_keys::SERVICE, KEYCHAIN_ACCOUNT)?)
+}
+
+#[cfg(desktop)]
+fn stored_key() -> Result<Option<String>> {
+  match keychain()?.get_password() {
+    Ok(key) => Ok(Some(key)),
+    Err(keyring::Error::NoEntry) => Ok(None),
+    Err(e) => Err(e.into()),
+  }
+}
+
+#[cfg(not(desktop))]
+fn stored_key() -> Result<Option<String>> {
+  Ok(None)
+}
+
+/// The key to open `path` with: `None` for a plaintext file.
+pub fn key_for(path: &Path) -> Result<Option<String>> {
+  if !is_encrypted(path)? {
+    return Ok(None);
+  }
+  match stored_key()? {
+    Some(key) => Ok(Some(key)),
+    None => Err(Error::Validation(
+      "The database is encrypted, but its key isn't in this computer's keychain".into(),
+    )),
+  }
+}
+
+/// A raw 256-bit key as 64 hex digits, so SQLCipher uses it as is rather
+/// than deriving one from a passphrase.
+fn new_key() -> String {
+  format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
+/// SQL for `key`, or for no encryption at all.
+fn key_literal(key: Option<&str>) -> String {
+  key.map_or_else(String::new, |key| format!("x'{key}'"))
+}
+
+/// Unlocks `conn` with `key`. Must come before anything else reads the
+/// database.
+pub fn unlock(conn: &Connection, key: &str) -> Result<()> {
+  conn.pragma_update(None, "key", key_literal(Some(key)))?;
+  // A wrong key only shows on the first read
+  conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))?;
+  Ok(())
+}
+
+/// Opens the database at `path`, unlocking it if it is encrypted.
+pub fn open(path: &Path) -> Result<Connection> {
+  let conn = Connection::open(path)?;
+  if let Some(key) = key_for(path)? {
+    unlock(&conn, &key)?;
+  }
+  Ok(conn)
+}
+
+/// Writes a copy of `conn`'s database to `dest`, encrypted with `key` or
+/// in plaintext without one.
+pub fn export(conn: &Connection, dest: &Path, key: Option<&str>) -> Result<()> {
+  let version = migrations::current_version(conn)?;
+  conn.execute(
+    "ATTACH DATABASE ?1 AS export KEY ?2",
+    (dest.to_string_lossy(), key_literal(key)),
+  )?;
+  let exported = conn
+    .query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()))
+    .and_then(|_| conn.pragma_update(Some("export"), "user_version", version));
+  conn.execute_batch("DETACH DATABASE export")?;
+  exported?;
+  Ok(())
+}
+
+/// Replaces `conn`'s contents with the database at `source`, which may be
+/// encrypted or not either way round. SQLite's backup only copies between
+/// files protected alike, so a mismatched source is converted first.
+pub fn restore_from(conn: &mut Connection, live_key: Option<&str>, source: &Path) -> Result<()> {
+  let mut source_conn = open(source)?;
+  let converted = source.with_extension("converted");
+  if is_encrypted(source)? != live_key.is_some() {
+    let _ = std::fs::remove_file(&converted);
+    export(&source_conn, &converted, live_key)?;
+    source_conn = Connection::open(&converted)?;
+    if let Some(key) = live_key {
+      unlock(&source_conn, key)?;
+    }
+  }
+  let restored = rusqlite::backup::Backup::new(&source_conn, conn)
+    .and_then(|backup| backup.run_to_completion(256, std::time::Duration::ZERO, None));
+  drop(source_conn);
+  let _ = std::fs::remove_file(&converted);
+  Ok(restored?)
+}
+
+/// Encrypts the live database in place: writes an encrypted copy, closes
+/// the plaintext file and swaps the copy in. Does nothing if it already is.
+pub fn enable(db: &Database, path: &Path) -> Result<()> {
+  if is_encrypted(path)? {
+    return Ok(());
+  }
+  #[cfg(not(desktop))]
+  {
+    let _ = db;
+    Err(Error::Validation("Encryption needs the OS keychain".into()))
+  }
+
+  #[cfg(desktop)]
+  {
+    // Stored before anything is encrypted with it, so a crash can't leave
+    // a file nobody can open. A key from an earlier try is reused.
+    let key = match stored_key()? {
+      Some(key) => key,
+      None => {
+        let key = new_key();
+        keychain()?.set_password(&key)?;
+        key
+      }
+    };
+
+    let encrypted = path.with_extension("db.encrypting");
+    let _ = std::fs::remove_file(&encrypted);
+    let mut conn = db.conn();
+    export(&conn, &encrypted, Some(&key))?;
+
+    // Close the plaintext file (and fold in its journal) before replacing it
+    *conn = Connection::open_in_memory()?;
+    let swapped = std::fs::rename(&encrypted, path);
+    *conn = super::connect(path)?;
+    swapped?;
+    log::info!("Encrypted the database at {:?}", path);
+    Ok(())
+  }
+}
+
+fn db_path(app: &AppHandle) -> std::path::PathBuf {
+  app.state::<AppPaths>().data().join(DB_FILE)
+}
+
+#[tauri::command]
+pub fn get_encryption_status(app: AppHandle) -> Result<EncryptionStatus> {
+  Ok(EncryptionStatus {
+    enabled: is_encrypted(&db_path(&app))?,
+    available: cfg!(desktop),
+  })
+}
+
+/// Encrypts the database with a key kept in the OS keychain. One-way:
+/// backups taken before stay as they were, and those taken after can only be
+/// restored where the key is.
+#[tauri::command]
+pub async fn enable_encryption(app: AppHandle) -> Result<EncryptionStatus> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let path = db_path(&app);
+    enable(&app.state::<Database>(), &path)?;
+    Ok(EncryptionStatus {
+      enabled: is_encrypted(&path)?,
+      available: cfg!(desktop),
+    })
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn round_trips_between_encrypted_and_plaintext_files() {
+    let dir = std::env::temp_dir().join(format!("dugout-encryption-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let conn = crate::db::open_in_memory();
+    conn
+      .execute("INSERT INTO teams (id, name, created_at) VALUES ('t2', 'Eagles', '')", [])
+      .unwrap();
+
+    let key = new_key();
+    let encrypted = dir.join("encrypted.db");
+    export(&conn, &encrypted, Some(&key)).unwrap();
+    assert!(is_encrypted(&encrypted).unwrap());
+    let on_disk = std::fs::read(&encrypted).unwrap();
+    assert!(!on_disk.windows(6).any(|w| w == b"Eagles"));
+
+    // Only the right key opens it, and the schema version comes along
+    let wrong = Connection::open(&encrypted).unwrap();
+    assert!(unlock(&wrong, &new_key()).is_err());
+    let reopened = Connection::open(&encrypted).unwrap();
+    unlock(&reopened, &key).unwrap();
+    let version = migrations::current_version(&reopened).unwrap();
+    assert_eq!(version, migrations::latest_version());
+    let copy = dir.join("copy.db");
+    reopened.execute("VACUUM INTO ?1", [copy.to_string_lossy()]).unwrap();
+    assert!(is_encrypted(&copy).unwrap(), "VACUUM INTO keeps the key");
+
+    // Restoring into a plaintext database converts on the way in
+    let mut live = Connection::open_in_memory().unwrap();
+    let plaintext = dir.join("plaintext.db");
+    export(&reopened, &plaintext, None).unwrap();
+    assert!(!is_encrypted(&plaintext).unwrap());
+    restore_from(&mut live, None, &plaintext).unwrap();
+    let name: String = live
+      .query_row("SELECT name FROM teams WHERE id = 't2'", [], |row| row.get(0))
+      .unwrap();
+    assert_eq!(name, "Eagles");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
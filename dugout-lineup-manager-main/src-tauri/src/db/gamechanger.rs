@@ -0,0 +1,704 @@
+//! Game import from GameChanger stats exports.
+//!
+//! GameChanger exports a game's stats as one row per player, with batting,
+//! pitching and fielding columns side by side under a row of group labels.
+//! Like the roster import this is a two-step flow: the first call parses
+//! the file and suggests which player each row belongs to, matched by name
+//! and jersey number; the frontend shows the matches for the coach to
+//! confirm or correct, then calls again with `commit` to merge the game's
+//! score and box scores into the local database. Importing the same game
+//! again replaces its stats rather than adding to them.
+
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::PathBuf;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::games::{self, Game};
+use super::pitch_counts;
+use super::roster::{self, Player};
+use super::roster_csv::RejectedRow;
+use super::{new_id, timestamp, Database};
+use crate::error::{Error, Result};
+
+/// The game a stats file belongs to. GameChanger leaves it out of the
+/// export, so the coach fills it in.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportGame {
+  /// Merges into this game. Without it, a game on the same date against the
+  /// same opponent is reused, or a new one created.
+  pub id: Option<String>,
+  /// `YYYY-MM-DD`.
+  pub date: String,
+  pub opponent: String,
+  pub home_away: Option<String>,
+  pub score_us: Option<u32>,
+  pub score_them: Option<u32>,
+}
+
+/// One player's line from the file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatLine {
+  pub ab: u32,
+  pub r: u32,
+  pub h: u32,
+  pub doubles: u32,
+  pub triples: u32,
+  pub hr: u32,
+  pub rbi: u32,
+  pub bb: u32,
+  pub so: u32,
+  pub sb: u32,
+  pub cs: u32,
+  /// Baseball notation: `2.1` is two and a third innings.
+  pub ip: f64,
+  pub h_allowed: u32,
+  pub r_allowed: u32,
+  pub er: u32,
+  pub bb_allowed: u32,
+  pub k: u32,
+  pub pitches: u32,
+  pub po: u32,
+  pub a: u32,
+  pub e: u32,
+  pub innings_played: f64,
+}
+
+/// How a row was matched to a player on the roster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchKind {
+  NameAndNumber,
+  Name,
+  Number,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerRow {
+  /// 1-based line in the file.
+  pub line: usize,
+  pub name: String,
+  pub number: Option<u32>,
+  /// The suggested or confirmed player; `None` leaves the row out.
+  pub player_id: Option<String>,
+  /// `None` when no player matched or the caller picked one.
+  pub matched_by: Option<MatchKind>,
+  pub stats: StatLine,
+}
+
+/// A confirmed match: the player `line` belongs to, or `None` to skip it.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerMatch {
+  pub line: usize,
+  pub player_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameChangerPreview {
+  pub rows: Vec<PlayerRow>,
+  pub rejected: Vec<RejectedRow>,
+  /// The game merged into; `None` unless the import was committed.
+  pub game: Option<Game>,
+  /// Box scores written; zero unless the import was committed.
+  pub imported: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Group {
+  Batting,
+  Pitching,
+  Fielding,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+  Number,
+  Name,
+  First,
+  Last,
+  Stat(&'static str),
+}
+
+pub(crate) fn normalize(cell: &str) -> String {
+  cell
+    .chars()
+    .filter(|c| c.is_alphanumeric() || *c == '#')
+    .flat_map(char::to_lowercase)
+    .collect()
+}
+
+fn group_label(cell: &str) -> Option<Group> {
+  match normalize(cell).as_str() {
+    "batting" | "offense" => Some(Group::Batting),
+    "pitching" => Some(Group::Pitching),
+    "fielding" | "defense" => Some(Group::Fielding),
+    _ => None,
+  }
+}
+
+/// The `StatLine` field a header maps to within its group.
+pub(crate) fn stat_field(group: Group, header: &str) -> Option<&'static str> {
+  Some(match (group, header) {
+    (Group::Batting, "ab") => "ab",
+    (Group::Batting, "r") => "r",
+    (Group::Batting, "h") => "h",
+    (Group::Batting, "2b") => "doubles",
+    (Group::Batting, "3b") => "triples",
+    (Group::Batting, "hr") => "hr",
+    (Group::Batting, "rbi") => "rbi",
+    (Group::Batting, "bb") => "bb",
+    (Group::Batting, "so" | "k") => "so",
+    (Group::Batting, "sb") => "sb",
+    (Group::Batting, "cs") => "cs",
+    (Group::Pitching, "ip") => "ip",
+    (Group::Pitching, "h") => "h_allowed",
+    (Group::Pitching, "r") => "r_allowed",
+    (Group::Pitching, "er") => "er",
+    (Group::Pitching, "bb") => "bb_allowed",
+    (Group::Pitching, "so" | "k") => "k",
+    (Group::Pitching, "#p" | "np" | "pc" | "pitches") => "pitches",
+    (Group::Fielding, "po") => "po",
+    (Group::Fielding, "a") => "a",
+    (Group::Fielding, "e") => "e",
+    (Group::Fielding, "inn") => "innings_played",
+    _ => return None,
+  })
+}
+
+/// Works out what each header cell holds. Without a group row, stats are
+/// batting until the first pitching-only column and pitching until the
+/// first fielding-only one, which is the order GameChanger writes them in.
+fn columns(headers: &[String], groups: Option<&[String]>) -> Vec<Option<Column>> {
+  let mut group = Group::Batting;
+  headers
+    .iter()
+    .enumerate()
+    .map(|(i, header)| {
+      let header = normalize(header);
+      match groups {
+        Some(groups) => {
+          if let Some(label) = groups.get(i).and_then(|cell| group_label(cell)) {
+            group = label;
+          }
+        }
+        None => match header.as_str() {
+          "ip" | "er" | "#p" | "np" => group = Group::Pitching,
+          "po" | "tc" | "fpct" => group = Group::Fielding,
+          _ => {}
+        },
+      }
+      match header.as_str() {
+        "number" | "no" | "num" | "#" | "jersey" => Some(Column::Number),
+        "name" | "player" | "playername" => Some(Column::Name),
+        "first" | "firstname" => Some(Column::First),
+        "last" | "lastname" => Some(Column::Last),
+        other => stat_field(group, other).map(Column::Stat),
+      }
+    })
+    .collect()
+}
+
+fn is_header(record: &[String]) -> bool {
+  let cells: Vec<String> = record.iter().map(|c| normalize(c)).collect();
+  let has = |aliases: &[&str]| cells.iter().any(|c| aliases.contains(&c.as_str()));
+  has(&["name", "player", "playername", "first", "last", "firstname", "lastname"])
+    && has(&["ab", "ip", "po"])
+}
+
+pub(crate) fn set_stat(
+  stats: &mut StatLine,
+  field: &str,
+  raw: &str,
+) -> std::result::Result<(), String> {
+  // GameChanger writes "-" for stats that don't apply
+  let raw = match raw {
+    "" | "-" => "0",
+    raw => raw,
+  };
+  if matches!(field, "ip" | "innings_played") {
+    let value: f64 = raw
+      .parse()
+      .ok()
+      .filter(|v: &f64| *v >= 0.0)
+      .ok_or_else(|| format!("\"{raw}\" isn't a number of innings"))?;
+    match field {
+      "ip" => stats.ip = value,
+      _ => stats.innings_played = value,
+    }
+    return Ok(());
+  }
+  let value: u32 = raw
+    .parse()
+    .map_err(|_| format!("{} \"{raw}\" is not a whole number", field.to_uppercase()))?;
+  let slot = match field {
+    "ab" => &mut stats.ab,
+    "r" => &mut stats.r,
+    "h" => &mut stats.h,
+    "doubles" => &mut stats.doubles,
+    "triples" => &mut stats.triples,
+    "hr" => &mut stats.hr,
+    "rbi" => &mut stats.rbi,
+    "bb" => &mut stats.bb,
+    "so" => &mut stats.so,
+    "sb" => &mut stats.sb,
+    "cs" => &mut stats.cs,
+    "h_allowed" => &mut stats.h_allowed,
+    "r_allowed" => &mut stats.r_allowed,
+    "er" => &mut stats.er,
+    "bb_allowed" => &mut stats.bb_allowed,
+    "k" => &mut stats.k,
+    "pitches" => &mut stats.pitches,
+    "po" => &mut stats.po,
+    "a" => &mut stats.a,
+    _ => &mut stats.e,
+  };
+  *slot = value;
+  Ok(())
+}
+
+/// Suggests the player for a row: name and number together beat a name
+/// alone, which beats a number alone, since jerseys change hands more often
+/// than names do.
+pub(crate) fn suggest(
+  name: &str,
+  number: Option<u32>,
+  roster: &[Player],
+) -> Option<(String, MatchKind)> {
+  let by_name = roster.iter().find(|p| p.name.eq_ignore_ascii_case(name));
+  let by_number = number.and_then(|n| roster.iter().find(|p| p.number == Some(n)));
+  match (by_name, by_number) {
+    (Some(a), Some(b)) if a.id == b.id => Some((a.id.clone(), MatchKind::NameAndNumber)),
+    (Some(player), _) => Some((player.id.clone(), MatchKind::Name)),
+    (None, Some(player)) => Some((player.id.clone(), MatchKind::Number)),
+    (None, None) => None,
+  }
+}
+
+/// Parses a GameChanger export and matches its rows against `roster`.
+pub fn preview<R: Read>(mut input: R, roster: &[Player]) -> Result<GameChangerPreview> {
+  let mut text = String::new();
+  input.read_to_string(&mut text)?;
+  let first_line = text.lines().find(|line| !line.trim().is_empty()).unwrap_or("");
+  let delimiter = if first_line.contains('\t') { b'\t' } else { b',' };
+  // Blank lines are skipped by the reader, so keep each record's own line
+  let records: Vec<(usize, Vec<String>)> = csv::ReaderBuilder::new()
+    .has_headers(false)
+    .flexible(true)
+    .trim(csv::Trim::All)
+    .delimiter(delimiter)
+    .from_reader(text.as_bytes())
+    .records()
+    .map(|record| {
+      let record = record?;
+      let line = record.position().map_or(0, |p| p.line() as usize);
+      Ok((line, record.iter().map(str::to_string).collect()))
+    })
+    .collect::<Result<_>>()?;
+
+  let header_at = records
+    .iter()
+    .position(|(_, record)| is_header(record))
+    .ok_or_else(|| Error::Validation("This doesn't look like a GameChanger stats export".into()))?;
+  let groups = header_at
+    .checked_sub(1)
+    .map(|i| records[i].1.as_slice())
+    .filter(|row| row.iter().any(|cell| group_label(cell).is_some()));
+  let columns = columns(&records[header_at].1, groups);
+
+  let mut rows = Vec::new();
+  let mut rejected = Vec::new();
+  let width = records[header_at].1.len();
+  for (line, values) in &records[header_at + 1..] {
+    let line = *line;
+    if values.len() * 2 < width {
+      // The glossary after the table is a couple of columns wide
+      break;
+    }
+    let (mut name, mut first, mut last, mut number) = ("", "", "", None);
+    let mut stats = StatLine::default();
+    let mut errors = Vec::new();
+    for (column, value) in columns.iter().zip(values) {
+      match column {
+        Some(Column::Name) => name = value,
+        Some(Column::First) => first = value,
+        Some(Column::Last) => last = value,
+        Some(Column::Number) => match value.trim_start_matches('#') {
+          "" => {}
+          raw => match raw.parse::<u32>() {
+            Ok(n) => number = Some(n),
+            Err(_) => errors.push(format!("Jersey number \"{raw}\" is not a whole number")),
+          },
+        },
+        Some(Column::Stat(field)) => {
+          if let Err(e) = set_stat(&mut stats, field, value) {
+            errors.push(e);
+          }
+        }
+        None => {}
+      }
+    }
+    let name = match name {
+      "" => format!("{first} {last}").trim().to_string(),
+      name => name.to_string(),
+    };
+    if matches!(name.to_lowercase().as_str(), "totals" | "team" | "total") {
+      continue;
+    }
+    if name.is_empty() {
+      errors.push("Row has no player name".into());
+    }
+    if !errors.is_empty() {
+      rejected.push(RejectedRow {
+        line,
+        values: values.clone(),
+        errors,
+      });
+      continue;
+    }
+    let suggestion = suggest(&name, number, roster);
+    rows.push(PlayerRow {
+      line,
+      name,
+      number,
+      player_id: suggestion.as_ref().map(|(id, _)| id.clone()),
+      matched_by: suggestion.map(|(_, kind)| kind),
+      stats,
+    });
+  }
+
+  Ok(GameChangerPreview {
+    rows,
+    rejected,
+    game: None,
+    imported: 0,
+  })
+}
+
+/// Applies the coach's confirmed matches over the suggested ones.
+pub fn confirm(rows: &mut [PlayerRow], matches: &[PlayerMatch], roster: &[Player]) -> Result<()> {
+  for confirmed in matches {
+    let row = rows
+      .iter_mut()
+      .find(|row| row.line == confirmed.line)
+      .ok_or_else(|| Error::Validation(format!("No player row on line {}", confirmed.line)))?;
+    if let Some(id) = &confirmed.player_id {
+      if !roster.iter().any(|p| &p.id == id) {
+        return Err(Error::NotFound(format!("Player {id}")));
+      }
+    }
+    if row.player_id != confirmed.player_id {
+      row.player_id = confirmed.player_id.clone();
+      row.matched_by = None;
+    }
+  }
+  let mut seen = HashSet::new();
+  for row in rows.iter() {
+    if let Some(id) = &row.player_id {
+      if !seen.insert(id) {
+        let name = roster.iter().find(|p| &p.id == id).map_or(id.as_str(), |p| &p.name);
+        return Err(Error::Validation(format!("{name} is matched to more than one row")));
+      }
+    }
+  }
+  Ok(())
+}
+
+fn result(score_us: Option<u32>, score_them: Option<u32>) -> Option<&'static str> {
+  let (us, them) = (score_us?, score_them?);
+  Some(match us.cmp(&them) {
+    std::cmp::Ordering::Greater => "W",
+    std::cmp::Ordering::Less => "L",
+    std::cmp::Ordering::Equal => "T",
+  })
+}
+
+/// The team's game on `date` against `opponent`, if there is one.
+pub(crate) fn find_game(
+  conn: &Connection,
+  team_id: &str,
+  date: &str,
+  opponent: &str,
+) -> Result<Option<String>> {
+  Ok(
+    conn
+      .query_row(
+        "SELECT id FROM games WHERE team_id = ?1 AND date = ?2 AND opponent = ?3 COLLATE NOCASE",
+        params![team_id, date, opponent.trim()],
+        |row| row.get(0),
+      )
+      .optional()?,
+  )
+}
+
+/// Creates or updates the game, keeping anything the import doesn't know
+/// about such as notes.
+fn upsert_game(conn: &Connection, team_id: &str, game: &ImportGame) -> Result<String> {
+  pitch_counts::parse_date(&game.date)?;
+  let opponent = game.opponent.trim();
+  if opponent.is_empty() {
+    return Err(Error::Validation("Opponent is required".into()));
+  }
+  let home_away = game.home_away.as_deref().unwrap_or("home");
+  if !matches!(home_away, "home" | "away") {
+    return Err(Error::Validation(format!(
+      "Home or away must be \"home\" or \"away\", not {home_away:?}"
+    )));
+  }
+
+  let existing = match &game.id {
+    Some(id) => Some(games::get(conn, id)?.id),
+    None => find_game(conn, team_id, &game.date, opponent)?,
+  };
+  let result = result(game.score_us, game.score_them);
+  let status = if result.is_some() { "completed" } else { "scheduled" };
+  let now = timestamp();
+  match existing {
+    Some(id) => {
+      conn.execute(
+        "UPDATE games SET date = ?2, opponent = ?3, home_away = ?4, \
+           score_us = COALESCE(?5, score_us), score_them = COALESCE(?6, score_them), \
+           result = COALESCE(?7, result), \
+           status = CASE WHEN ?7 IS NULL THEN status ELSE ?8 END, updated_at = ?9 \
+         WHERE id = ?1",
+        params![
+          id,
+          game.date,
+          opponent,
+          home_away,
+          game.score_us,
+          game.score_them,
+          result,
+          status,
+          now
+        ],
+      )?;
+      Ok(id)
+    }
+    None => {
+      let id = new_id();
+      conn.execute(
+        "INSERT INTO games (id, team_id, date, opponent, home_away, result, score_us, \
+           score_them, status, created_at, updated_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+        params![
+          id,
+          team_id,
+          game.date,
+          opponent,
+          home_away,
+          result,
+          game.score_us,
+          game.score_them,
+          status,
+          now
+        ],
+      )?;
+      Ok(id)
+    }
+  }
+}
+
+/// Merges the game and every matched row in one transaction. Pitchers'
+/// counts also go to the pitch log, so rest days follow the import.
+pub fn commit(
+  conn: &mut Connection,
+  team_id: &str,
+  game: &ImportGame,
+  rows: &[PlayerRow],
+) -> Result<(Game, usize)> {
+  let tx = conn.transaction()?;
+  let (game_id, imported) = merge(&tx, team_id, game, rows)?;
+  let game = games::get(&tx, &game_id)?;
+  tx.commit()?;
+  Ok((game, imported))
+}
+
+/// [`commit`] inside a transaction the caller commits, returning the game's
+/// id and how many box scores were written.
+pub(crate) fn merge(
+  tx: &Connection,
+  team_id: &str,
+  game: &ImportGame,
+  rows: &[PlayerRow],
+) -> Result<(String, usize)> {
+  let game_id = upsert_game(tx, team_id, game)?;
+  let mut imported = 0;
+  for row in rows {
+    let Some(player_id) = &row.player_id else {
+      continue;
+    };
+    let s = &row.stats;
+    let values = params![
+      game_id, player_id, s.ab, s.r, s.h, s.doubles, s.triples, s.hr, s.rbi, s.bb, s.so,
+      s.sb, s.cs, s.ip, s.h_allowed, s.r_allowed, s.er, s.bb_allowed, s.k, s.pitches, s.po,
+      s.a, s.e, s.innings_played
+    ];
+    // Not an upsert: its conflict handling would override the `OR REPLACE`
+    // in the stat_changes triggers. Positions played aren't in the export,
+    // so an existing row keeps them.
+    let updated = tx.execute(
+      "UPDATE game_stats SET ab = ?3, r = ?4, h = ?5, doubles = ?6, triples = ?7, hr = ?8, \
+         rbi = ?9, bb = ?10, so = ?11, sb = ?12, cs = ?13, ip = ?14, h_allowed = ?15, \
+         r_allowed = ?16, er = ?17, bb_allowed = ?18, k = ?19, pitches = ?20, po = ?21, \
+         a = ?22, e = ?23, innings_played = ?24 \
+       WHERE game_id = ?1 AND player_id = ?2",
+      values,
+    )?;
+    if updated == 0 {
+      tx.execute(
+        "INSERT INTO game_stats (game_id, player_id, ab, r, h, doubles, triples, hr, rbi, \
+           bb, so, sb, cs, ip, h_allowed, r_allowed, er, bb_allowed, k, pitches, po, a, e, \
+           innings_played) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, \
+           ?18, ?19, ?20, ?21, ?22, ?23, ?24)",
+        values,
+      )?;
+    }
+    if s.pitches > 0 {
+      pitch_counts::record(tx, player_id, &game_id, &game.date, s.pitches)?;
+    }
+    imported += 1;
+  }
+  Ok((game_id, imported))
+}
+
+/// Previews a GameChanger stats import with suggested player matches, or
+/// merges it into `game` when `commit` is set. `matches` confirms or
+/// corrects the suggestions; rows left without a player are skipped.
+#[tauri::command]
+pub fn import_gamechanger(
+  db: State<'_, Database>,
+  path: PathBuf,
+  game: ImportGame,
+  team_id: Option<String>,
+  matches: Option<Vec<PlayerMatch>>,
+  commit: Option<bool>,
+) -> Result<GameChangerPreview> {
+  let file = std::fs::File::open(&path)?;
+  let mut conn = db.conn();
+  let team_id = match team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(&conn)?,
+  };
+  let roster = roster::list(&conn, &team_id)?;
+  let mut preview = preview(file, &roster)?;
+  confirm(&mut preview.rows, matches.as_deref().unwrap_or_default(), &roster)?;
+  if commit.unwrap_or(false) {
+    let (game, imported) = self::commit(&mut conn, &team_id, &game, &preview.rows)?;
+    log::info!("Imported {} GameChanger box scores into game {}", imported, game.id);
+    preview.game = Some(game);
+    preview.imported = imported;
+  }
+  Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+  use crate::db::roster::NewPlayer;
+
+  const EXPORT: &str = "\
+,,,Batting,,,,,,,,Pitching,,,,,,Fielding,,
+Number,Last,First,AB,R,H,2B,HR,RBI,BB,SO,IP,H,R,ER,SO,#P,PO,E
+12,Miller,Jake,3,1,2,1,0,2,1,0,2.1,3,1,1,4,47,1,0
+3,Chen,Ava,4,0,1,0,1,1,-,2,-,-,-,-,-,-,3,1
+99,Ortiz,Sam,2,0,0,0,0,0,0,1,0,0,0,0,0,0,0,0
+7,Park,Lee,x,0,0,0,0,0,0,1,0,0,0,0,0,0,0,0
+,,Totals,12,1,3,1,1,3,1,4,2.1,3,1,1,4,47,4,1
+
+Glossary
+AB,At bats
+";
+
+  fn player(conn: &Connection, name: &str, number: u32) -> Player {
+    roster::create(
+      conn,
+      NewPlayer {
+        team_id: None,
+        name: name.into(),
+        number: Some(number),
+        primary_position: "SS".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn parses_grouped_columns_and_suggests_matches() {
+    let conn = open_in_memory();
+    let jake = player(&conn, "Jake Miller", 12);
+    let ava = player(&conn, "Ava Chen", 8);
+    let sam = player(&conn, "Sammy Ortiz", 99);
+    let preview = preview(EXPORT.as_bytes(), &[jake.clone(), ava, sam]).unwrap();
+
+    let matched: Vec<_> = preview.rows.iter().map(|r| r.matched_by).collect();
+    assert_eq!(
+      matched,
+      [Some(MatchKind::NameAndNumber), Some(MatchKind::Name), Some(MatchKind::Number)]
+    );
+    let stats = &preview.rows[0].stats;
+    assert_eq!((stats.h, stats.doubles, stats.rbi, stats.so), (2, 1, 2, 0));
+    assert_eq!((stats.ip, stats.h_allowed, stats.k, stats.pitches), (2.1, 3, 4, 47));
+    assert_eq!(preview.rows[1].stats.bb, 0);
+    assert_eq!(preview.rejected.len(), 1);
+    assert_eq!(preview.rejected[0].line, 6);
+  }
+
+  #[test]
+  fn commits_the_game_and_replaces_stats_on_reimport() {
+    let mut conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let jake = player(&conn, "Jake Miller", 12);
+    let ava = player(&conn, "Ava Chen", 3);
+    let roster = [jake.clone(), ava.clone()];
+    let game = ImportGame {
+      id: None,
+      date: "2026-05-02".into(),
+      opponent: "Tigers".into(),
+      home_away: None,
+      score_us: Some(6),
+      score_them: Some(4),
+    };
+
+    let mut rows = preview(EXPORT.as_bytes(), &roster).unwrap().rows;
+    // Sam isn't on the roster, and the coach leaves Ava's row out
+    let skip_ava = [PlayerMatch {
+      line: 4,
+      player_id: None,
+    }];
+    confirm(&mut rows, &skip_ava, &roster).unwrap();
+    let (saved, imported) = commit(&mut conn, &team_id, &game, &rows).unwrap();
+    assert_eq!(imported, 1);
+    assert_eq!((saved.result.as_deref(), saved.status.as_str()), (Some("W"), "completed"));
+
+    let rows = preview(EXPORT.as_bytes(), &roster).unwrap().rows;
+    let (again, imported) = commit(&mut conn, &team_id, &game, &rows).unwrap();
+    assert_eq!((again.id, imported), (saved.id.clone(), 2));
+    let totals = games::season_totals(&conn, &team_id).unwrap();
+    let jake_totals = totals.iter().find(|t| t.player_id == jake.id).unwrap();
+    assert_eq!((jake_totals.games, jake_totals.h), (1, 2));
+    let pitched = pitch_counts::since(&conn, &team_id, "2026-05-01").unwrap();
+    assert_eq!(pitched[0].pitches, 47);
+
+    let twice = [PlayerMatch {
+      line: 4,
+      player_id: Some(jake.id),
+    }];
+    let mut rows = preview(EXPORT.as_bytes(), &roster).unwrap().rows;
+    assert!(confirm(&mut rows, &twice, &roster).is_err());
+  }
+}
@@ -0,0 +1,229 @@
+//! Season schedule and box scores.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+
+use super::timestamp;
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Game {
+  pub id: String,
+  pub team_id: String,
+  /// `YYYY-MM-DD`.
+  pub date: String,
+  pub opponent: String,
+  pub home_away: String,
+  pub result: Option<String>,
+  pub score_us: Option<u32>,
+  pub score_them: Option<u32>,
+  pub status: String,
+  pub notes: String,
+  /// The field's address, for the forecast. Empty when not known.
+  pub location: String,
+  /// `HH:MM` local time, when the schedule gave one.
+  pub start_time: Option<String>,
+}
+
+const GAME_COLUMNS: &str = "id, team_id, date, opponent, home_away, result, score_us, score_them, \
+   status, notes, location, start_time";
+
+fn game_from_row(row: &Row<'_>) -> rusqlite::Result<Game> {
+  Ok(Game {
+    id: row.get(0)?,
+    team_id: row.get(1)?,
+    date: row.get(2)?,
+    opponent: row.get(3)?,
+    home_away: row.get(4)?,
+    result: row.get(5)?,
+    score_us: row.get(6)?,
+    score_them: row.get(7)?,
+    status: row.get(8)?,
+    notes: row.get(9)?,
+    location: row.get(10)?,
+    start_time: row.get(11)?,
+  })
+}
+
+/// A team's games in date order.
+pub fn list(conn: &Connection, team_id: &str) -> Result<Vec<Game>> {
+  let mut stmt = conn.prepare(&format!(
+    "SELECT {GAME_COLUMNS} FROM games WHERE team_id = ?1 ORDER BY date, created_at"
+  ))?;
+  let games = stmt
+    .query_map([team_id], game_from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(games)
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<Game> {
+  conn
+    .query_row(
+      &format!("SELECT {GAME_COLUMNS} FROM games WHERE id = ?1"),
+      [id],
+      game_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| Error::NotFound(format!("Game {id}")))
+}
+
+/// A game from an outside schedule, such as a calendar feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledGame {
+  /// Derived from the source's own id, so importing again updates the game.
+  pub id: String,
+  /// `YYYY-MM-DD`, in local time.
+  pub date: String,
+  pub opponent: String,
+  pub home_away: String,
+  pub score_us: Option<u32>,
+  pub score_them: Option<u32>,
+  pub notes: String,
+  pub location: String,
+  /// `HH:MM` local time.
+  pub start_time: Option<String>,
+}
+
+/// Adds a scheduled game to `team_id`, or updates it if it was imported
+/// before, returning whether it was new. Scores from the source mark the
+/// game completed; scores only kept here are not cleared.
+pub fn upsert_scheduled(conn: &Connection, team_id: &str, game: &ScheduledGame) -> Result<bool> {
+  let result = match (game.score_us, game.score_them) {
+    (Some(us), Some(them)) if us > them => Some("W"),
+    (Some(us), Some(them)) if us < them => Some("L"),
+    (Some(_), Some(_)) => Some("T"),
+    _ => None,
+  };
+  let status = if result.is_some() { "completed" } else { "scheduled" };
+  let now = timestamp();
+  let updated = conn.execute(
+    "UPDATE games SET date = ?2, opponent = ?3, home_away = ?4, \
+       score_us = COALESCE(?5, score_us), score_them = COALESCE(?6, score_them), \
+       result = COALESCE(?7, result), \
+       status = CASE WHEN ?7 IS NULL THEN status ELSE ?8 END, notes = ?9, updated_at = ?10, \
+       location = ?12, start_time = ?13 \
+     WHERE id = ?1 AND team_id = ?11",
+    params![
+      game.id,
+      game.date,
+      game.opponent,
+      game.home_away,
+      game.score_us,
+      game.score_them,
+      result,
+      status,
+      game.notes,
+      now,
+      team_id,
+      game.location,
+      game.start_time
+    ],
+  )?;
+  if updated > 0 {
+    return Ok(false);
+  }
+  conn.execute(
+    "INSERT INTO games (id, team_id, date, opponent, home_away, result, score_us, \
+       score_them, status, notes, location, start_time, created_at, updated_at) \
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?13)",
+    params![
+      game.id,
+      team_id,
+      game.date,
+      game.opponent,
+      game.home_away,
+      result,
+      game.score_us,
+      game.score_them,
+      status,
+      game.notes,
+      game.location,
+      game.start_time,
+      now
+    ],
+  )?;
+  Ok(true)
+}
+
+/// A player's counting stats summed over every game of the season.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonTotals {
+  pub player_id: String,
+  pub name: String,
+  pub number: Option<u32>,
+  pub games: u32,
+  pub ab: u32,
+  pub r: u32,
+  pub h: u32,
+  pub doubles: u32,
+  pub triples: u32,
+  pub hr: u32,
+  pub rbi: u32,
+  pub bb: u32,
+  pub so: u32,
+  pub sb: u32,
+  pub cs: u32,
+  pub ip: f64,
+  pub h_allowed: u32,
+  pub r_allowed: u32,
+  pub er: u32,
+  pub bb_allowed: u32,
+  pub k: u32,
+  pub pitches: u32,
+  pub po: u32,
+  pub a: u32,
+  pub e: u32,
+}
+
+/// Season totals for every player on the team, including players without
+/// any recorded games.
+pub fn season_totals(conn: &Connection, team_id: &str) -> Result<Vec<SeasonTotals>> {
+  let mut stmt = conn.prepare(
+    "SELECT p.id, p.name, p.number, COUNT(s.game_id), \
+       TOTAL(s.ab), TOTAL(s.r), TOTAL(s.h), TOTAL(s.doubles), TOTAL(s.triples), \
+       TOTAL(s.hr), TOTAL(s.rbi), TOTAL(s.bb), TOTAL(s.so), TOTAL(s.sb), TOTAL(s.cs), \
+       TOTAL(s.ip), TOTAL(s.h_allowed), TOTAL(s.r_allowed), TOTAL(s.er), \
+       TOTAL(s.bb_allowed), TOTAL(s.k), TOTAL(s.pitches), \
+       TOTAL(s.po), TOTAL(s.a), TOTAL(s.e) \
+     FROM players p LEFT JOIN game_stats s ON s.player_id = p.id \
+     WHERE p.team_id = ?1 \
+     GROUP BY p.id \
+     ORDER BY p.number IS NULL, p.number, p.name COLLATE NOCASE",
+  )?;
+  let totals = stmt
+    .query_map([team_id], |row| {
+      // TOTAL() always returns a float, even over integer columns.
+      let count = |i: usize| row.get::<_, f64>(i).map(|v| v as u32);
+      Ok(SeasonTotals {
+        player_id: row.get(0)?,
+        name: row.get(1)?,
+        number: row.get(2)?,
+        games: row.get(3)?,
+        ab: count(4)?,
+        r: count(5)?,
+        h: count(6)?,
+        doubles: count(7)?,
+        triples: count(8)?,
+        hr: count(9)?,
+        rbi: count(10)?,
+        bb: count(11)?,
+        so: count(12)?,
+        sb: count(13)?,
+        cs: count(14)?,
+        ip: row.get(15)?,
+        h_allowed: count(16)?,
+        r_allowed: count(17)?,
+        er: count(18)?,
+        bb_allowed: count(19)?,
+        k: count(20)?,
+        pitches: count(21)?,
+        po: count(22)?,
+        a: count(23)?,
+        e: count(24)?,
+      })
+    })?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(totals)
+}
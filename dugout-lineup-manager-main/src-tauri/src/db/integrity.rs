@@ -0,0 +1,335 @@
+//! Integrity checks and safe repairs of the database, run on request and
+//! at launch after an unclean shutdown (a crash, a power cut, the laptop
+//! lid closing on a dead battery).
+//!
+//! Repairs never lose data: indexes are rebuilt from their tables, a
+//! lineup slot whose player is gone is emptied as deleting the player does,
+//! and rows pointing at parents that no longer exist are moved into
+//! `quarantined_rows` rather than deleted. Damage to the tables themselves
+//! can't be repaired in place; the report says so, and a backup has to be
+//! restored.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, Transaction};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::{timestamp, Database};
+use crate::error::Result;
+use crate::stats::StatsCache;
+
+/// Emitted with the [`IntegrityReport`] of the check run at launch.
+pub const INTEGRITY_EVENT: &str = "integrity-report";
+
+/// `integrity_check` problems reported, past which there's no point
+/// listing more.
+const MAX_PROBLEMS: u32 = 100;
+
+/// Rounds of quarantining, each catching rows whose parent the last one
+/// took away.
+const MAX_ROUNDS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantinedRow {
+  pub table: String,
+  pub rowid: i64,
+  /// The table it pointed at without finding the row.
+  pub parent: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+  pub checked_at: String,
+  /// The last launch didn't exit cleanly, which is why this ran.
+  pub after_unclean_shutdown: bool,
+  /// What SQLite's `integrity_check` found, before any repair.
+  pub problems: Vec<String>,
+  pub reindexed: bool,
+  /// Problems still there after repairing. Only restoring a backup fixes
+  /// these.
+  pub unrepaired: Vec<String>,
+  pub quarantined: Vec<QuarantinedRow>,
+  /// Lineup slots emptied because their player is gone.
+  pub cleared_slots: u32,
+  /// Lineups unlinked from a game that is gone.
+  pub unlinked_lineups: u32,
+  /// Undo history dropped for lineups that are gone.
+  pub dropped_history: u32,
+  pub healthy: bool,
+}
+
+impl IntegrityReport {
+  fn repaired_anything(&self) -> bool {
+    self.reindexed
+      || !self.quarantined.is_empty()
+      || self.cleared_slots + self.unlinked_lineups + self.dropped_history > 0
+  }
+}
+
+/// The report of the last check, for a window that opens after the one at
+/// launch finished.
+#[derive(Default)]
+pub struct IntegrityState(Mutex<Option<IntegrityReport>>);
+
+fn integrity_problems(conn: &Connection) -> Result<Vec<String>> {
+  let mut stmt = conn.prepare(&format!("PRAGMA integrity_check({MAX_PROBLEMS})"))?;
+  let messages = stmt
+    .query_map([], |row| row.get::<_, String>(0))?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(messages.into_iter().filter(|m| m != "ok").collect())
+}
+
+/// Index damage is the one kind `REINDEX` can mend, as the index is
+/// rebuilt from its table.
+fn is_index_problem(message: &str) -> bool {
+  message.contains(" index ")
+}
+
+fn to_json(value: ValueRef) -> Value {
+  match value {
+    ValueRef::Null => Value::Null,
+    ValueRef::Integer(i) => i.into(),
+    ValueRef::Real(f) => f.into(),
+    ValueRef::Text(text) => String::from_utf8_lossy(text).into(),
+    ValueRef::Blob(blob) => STANDARD.encode(blob).into(),
+  }
+}
+
+fn quarantine(tx: &Transaction, table: &str, rowid: i64, parent: &str) -> Result<()> {
+  let mut stmt = tx.prepare(&format!("SELECT * FROM \"{table}\" WHERE rowid = ?1"))?;
+  let names: Vec<String> = stmt.column_names().into_iter().map(str::to_string).collect();
+  let data = stmt.query_row([rowid], |row| {
+    let mut data = Map::new();
+    for (i, name) in names.iter().enumerate() {
+      data.insert(name.clone(), to_json(row.get_ref(i)?));
+    }
+    Ok(Value::Object(data))
+  })?;
+  tx.execute(
+    "INSERT INTO quarantined_rows (table_name, data, reason, quarantined_at) \
+     VALUES (?1, ?2, ?3, ?4)",
+    params![table, data.to_string(), format!("No matching row in {parent}"), timestamp()],
+  )?;
+  tx.execute(&format!("DELETE FROM \"{table}\" WHERE rowid = ?1"), [rowid])?;
+  Ok(())
+}
+
+/// Mends what can be mended without losing anything, in one transaction.
+/// Foreign keys are off meanwhile, so quarantining a row leaves its own
+/// children for the next round rather than cascading them away.
+fn repair_references(conn: &mut Connection, report: &mut IntegrityReport) -> Result<()> {
+  let tx = conn.transaction()?;
+  report.cleared_slots = tx.execute(
+    "UPDATE lineup_slots SET player_id = NULL \
+     WHERE player_id IS NOT NULL AND player_id NOT IN (SELECT id FROM players)",
+    [],
+  )? as u32;
+  // Not a foreign key, so a game deleted outside the app leaves it behind
+  report.unlinked_lineups = tx.execute(
+    "UPDATE lineups SET game_id = NULL \
+     WHERE game_id IS NOT NULL AND game_id NOT IN (SELECT id FROM games)",
+    [],
+  )? as u32;
+  report.dropped_history = tx.execute(
+    "DELETE FROM undo_log WHERE lineup_id NOT IN (SELECT id FROM lineups)",
+    [],
+  )? as u32;
+
+  for _ in 0..MAX_ROUNDS {
+    let violations = {
+      let mut stmt = tx.prepare("PRAGMA foreign_key_check")?;
+      let rows = stmt.query_map([], |row| {
+        Ok((
+          row.get::<_, String>(0)?,
+          row.get::<_, Option<i64>>(1)?,
+          row.get::<_, String>(2)?,
+        ))
+      })?;
+      rows.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+    let mut quarantined = false;
+    for (table, rowid, parent) in violations {
+      // Tables without rowids can't be picked out; there are none yet
+      let Some(rowid) = rowid else { continue };
+      if report.quarantined.iter().any(|q| q.table == table && q.rowid == rowid) {
+        continue;
+      }
+      quarantine(&tx, &table, rowid, &parent)?;
+      report.quarantined.push(QuarantinedRow {
+        table,
+        rowid,
+        parent,
+      });
+      quarantined = true;
+    }
+    if !quarantined {
+      break;
+    }
+  }
+  tx.commit()?;
+  Ok(())
+}
+
+/// Checks the database and repairs what it safely can.
+pub fn check(conn: &mut Connection) -> Result<IntegrityReport> {
+  let mut report = IntegrityReport {
+    checked_at: timestamp(),
+    problems: integrity_problems(conn)?,
+    ..Default::default()
+  };
+  if report.problems.iter().any(|p| is_index_problem(p)) {
+    conn.execute_batch("REINDEX")?;
+    report.reindexed = true;
+  }
+
+  conn.pragma_update(None, "foreign_keys", "OFF")?;
+  let repaired = repair_references(conn, &mut report);
+  conn.pragma_update(None, "foreign_keys", "ON")?;
+  repaired?;
+
+  report.unrepaired = if report.problems.is_empty() {
+    Vec::new()
+  } else {
+    integrity_problems(conn)?
+  };
+  report.healthy = report.unrepaired.is_empty();
+  Ok(report)
+}
+
+fn run(app: &AppHandle, after_unclean_shutdown: bool) -> Result<IntegrityReport> {
+  let mut report = check(&mut app.state::<Database>().conn())?;
+  report.after_unclean_shutdown = after_unclean_shutdown;
+  if report.repaired_anything() {
+    if let Some(cache) = app.try_state::<StatsCache>() {
+      cache.clear();
+    }
+  }
+  if !report.healthy {
+    log::error!("The database is damaged: {}", report.unrepaired.join("; "));
+  } else if report.repaired_anything() {
+    log::warn!(
+      "Repaired the database: {} rows quarantined, {} slots cleared, reindexed: {}",
+      report.quarantined.len(),
+      report.cleared_slots,
+      report.reindexed
+    );
+  } else {
+    log::info!("The database passed its integrity check");
+  }
+  if let Some(state) = app.try_state::<IntegrityState>() {
+    *state.0.lock().unwrap() = Some(report.clone());
+  }
+  Ok(report)
+}
+
+/// Marks the app as running in `marker`, reporting whether the last run
+/// left its marker behind, i.e. didn't exit cleanly.
+pub fn mark_running(marker: &Path) -> bool {
+  let unclean = marker.exists();
+  if let Err(e) = std::fs::write(marker, timestamp()) {
+    log::warn!("Failed to write {:?}: {}", marker, e);
+  }
+  unclean
+}
+
+/// Removes the marker written by [`mark_running`] on a clean exit.
+pub fn mark_stopped(marker: &Path) {
+  if let Err(e) = std::fs::remove_file(marker) {
+    if e.kind() != std::io::ErrorKind::NotFound {
+      log::warn!("Failed to remove {:?}: {}", marker, e);
+    }
+  }
+}
+
+/// Checks the database in the background after an unclean shutdown, and
+/// emits the report.
+pub fn check_after_unclean_shutdown(app: AppHandle) {
+  log::warn!("The last run didn't exit cleanly; checking the database");
+  tauri::async_runtime::spawn_blocking(move || match run(&app, true) {
+    Ok(report) => {
+      let _ = app.emit(INTEGRITY_EVENT, report);
+    }
+    Err(e) => log::error!("Failed to check the database: {}", e),
+  });
+}
+
+/// Checks the database, repairs what it safely can and reports both.
+#[tauri::command]
+pub async fn check_data_integrity(app: AppHandle) -> Result<IntegrityReport> {
+  tauri::async_runtime::spawn_blocking(move || run(&app, false)).await?
+}
+
+/// The report of the last check this run, if there was one.
+#[tauri::command]
+pub fn get_integrity_report(state: State<'_, IntegrityState>) -> Option<IntegrityReport> {
+  state.0.lock().unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn quarantines_rows_whose_parents_are_gone() {
+    let mut conn = crate::db::open_in_memory();
+    conn
+      .execute_batch(
+        "INSERT INTO teams (id, name, created_at) VALUES ('t2', 'Eagles', 'now');
+         INSERT INTO players (id, team_id, name, primary_position, bats, throws, created_at,
+           updated_at)
+         VALUES ('p1', 't2', 'Jake Miller', 'SS', 'R', 'R', 'now', 'now');
+         INSERT INTO games (id, team_id, date, opponent, created_at, updated_at)
+         VALUES ('g1', 't2', '2024-04-11', 'Tigers', 'now', 'now');
+         INSERT INTO lineups (id, team_id, name, game_id, created_at, updated_at)
+         VALUES ('l1', 't2', 'Game 1', 'g1', 'now', 'now');
+         INSERT INTO lineup_slots (lineup_id, slot_number, player_id, position)
+         VALUES ('l1', 1, 'p1', 'SS');
+         INSERT INTO game_stats (game_id, player_id, ab, h) VALUES ('g1', 'p1', 3, 2);",
+      )
+      .unwrap();
+    let clean = check(&mut conn).unwrap();
+    assert!(clean.healthy && !clean.repaired_anything());
+
+    // As another program might, with foreign keys off
+    conn
+      .execute_batch(
+        "PRAGMA foreign_keys = OFF;
+         DELETE FROM players WHERE id = 'p1';
+         DELETE FROM games WHERE id = 'g1';
+         PRAGMA foreign_keys = ON;",
+      )
+      .unwrap();
+    let report = check(&mut conn).unwrap();
+    assert!(report.healthy);
+    assert_eq!((report.cleared_slots, report.unlinked_lineups), (1, 1));
+    assert_eq!(report.quarantined.len(), 1);
+    assert_eq!(report.quarantined[0].table, "game_stats");
+
+    let data: String = conn
+      .query_row("SELECT data FROM quarantined_rows", [], |row| row.get(0))
+      .unwrap();
+    let data: Value = serde_json::from_str(&data).unwrap();
+    assert_eq!((&data["game_id"], &data["h"]), (&Value::from("g1"), &Value::from(2)));
+    let fk_on: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+    assert!(fk_on);
+    assert!(!check(&mut conn).unwrap().repaired_anything());
+  }
+
+  #[test]
+  fn tells_unclean_shutdowns_from_clean_ones() {
+    let marker = std::env::temp_dir().join(format!("dugout-running-{}", uuid::Uuid::new_v4()));
+    assert!(!mark_running(&marker));
+    mark_stopped(&marker);
+    assert!(!mark_running(&marker));
+    assert!(mark_running(&marker));
+    mark_stopped(&marker);
+  }
+}
@@ -0,0 +1,470 @@
+//! Saved lineups: a batting order plus fielding positions by inning,
+//! optionally tied to a game.
+
+use std::collections::HashSet;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::roster::{self, POSITIONS};
+use super::undo::{self, Action};
+use super::versions::{self, VersionVector};
+use super::{audit, new_id, timestamp, Database};
+use crate::error::{Error, Result};
+use crate::sync::{Change, SyncState};
+use crate::telemetry::{Feature, Telemetry};
+
+/// Most innings a lineup can plan for, covering extra-inning games.
+pub(crate) const MAX_INNINGS: u32 = 12;
+
+/// Longest batting order accepted, for leagues that bat the whole roster.
+pub(crate) const MAX_SLOTS: u32 = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Lineup {
+  pub id: String,
+  pub team_id: String,
+  pub name: String,
+  pub game_id: Option<String>,
+  pub use_dh: bool,
+  pub innings: u32,
+  pub slots: Vec<LineupSlot>,
+  pub positions: Vec<InningPosition>,
+  pub updated_at: String,
+  /// Saves per replica, for sync; see [`versions`].
+  #[serde(default)]
+  pub versions: VersionVector,
+}
+
+/// One spot in the batting order, with the player's name and number
+/// resolved for display.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineupSlot {
+  pub slot_number: u32,
+  pub player_id: Option<String>,
+  pub player_name: Option<String>,
+  pub number: Option<u32>,
+  pub position: Option<String>,
+}
+
+/// Who plays `position` in `inning`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InningPosition {
+  pub inning: u32,
+  pub position: String,
+  pub player_id: String,
+}
+
+impl Lineup {
+  /// The position `player_id` plays in `inning`, if any.
+  pub fn position_in(&self, player_id: &str, inning: u32) -> Option<&str> {
+    self
+      .positions
+      .iter()
+      .find(|p| p.inning == inning && p.player_id == player_id)
+      .map(|p| p.position.as_str())
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineupInput {
+  /// Updates the lineup with this id; creates a new one when omitted.
+  pub id: Option<String>,
+  /// Defaults to the first team.
+  pub team_id: Option<String>,
+  pub name: String,
+  pub game_id: Option<String>,
+  #[serde(default = "default_use_dh")]
+  pub use_dh: bool,
+  #[serde(default = "default_innings")]
+  pub innings: u32,
+  #[serde(default)]
+  pub slots: Vec<SlotInput>,
+  #[serde(default)]
+  pub positions: Vec<InningPosition>,
+}
+
+fn default_use_dh() -> bool {
+  true
+}
+
+fn default_innings() -> u32 {
+  6
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotInput {
+  pub slot_number: u32,
+  pub player_id: Option<String>,
+  pub position: Option<String>,
+}
+
+/// A team's lineups, oldest first, each with its slots in batting order.
+pub fn list(conn: &Connection, team_id: &str) -> Result<Vec<Lineup>> {
+  let ids = conn
+    .prepare("SELECT id FROM lineups WHERE team_id = ?1 ORDER BY created_at, rowid")?
+    .query_map([team_id], |row| row.get::<_, String>(0))?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  ids.iter().map(|id| get(conn, id)).collect()
+}
+
+/// Ids of the lineups for a team's games still to be played, in schedule
+/// order. A game with several lineups uses the one saved last; games
+/// without one are skipped.
+pub fn for_scheduled_games(conn: &Connection, team_id: &str) -> Result<Vec<String>> {
+  let ids = conn
+    .prepare(
+      "SELECT l.id FROM games g JOIN lineups l ON l.id = ( \
+         SELECT id FROM lineups WHERE game_id = g.id \
+         ORDER BY updated_at DESC, rowid DESC LIMIT 1) \
+       WHERE g.team_id = ?1 AND g.status = 'scheduled' \
+       ORDER BY g.date, g.start_time, g.created_at",
+    )?
+    .query_map([team_id], |row| row.get::<_, String>(0))?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(ids)
+}
+
+/// The lineup saved last for a game, if it has any.
+pub fn latest_for_game(conn: &Connection, game_id: &str) -> Result<Option<Lineup>> {
+  let id = conn
+    .query_row(
+      "SELECT id FROM lineups WHERE game_id = ?1 ORDER BY updated_at DESC, rowid DESC LIMIT 1",
+      [game_id],
+      |row| row.get::<_, String>(0),
+    )
+    .optional()?;
+  id.map(|id| get(conn, &id)).transpose()
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<Lineup> {
+  let mut lineup = conn
+    .query_row(
+      "SELECT id, team_id, name, game_id, use_dh, innings, updated_at, versions \
+       FROM lineups WHERE id = ?1",
+      [id],
+      |row| {
+        Ok(Lineup {
+          id: row.get(0)?,
+          team_id: row.get(1)?,
+          name: row.get(2)?,
+          game_id: row.get(3)?,
+          use_dh: row.get(4)?,
+          innings: row.get(5)?,
+          slots: Vec::new(),
+          positions: Vec::new(),
+          updated_at: row.get(6)?,
+          versions: serde_json::from_str(&row.get::<_, String>(7)?).unwrap_or_default(),
+        })
+      },
+    )
+    .optional()?
+    .ok_or_else(|| Error::NotFound(format!("Lineup {id}")))?;
+  lineup.slots = slots(conn, id)?;
+  lineup.positions = conn
+    .prepare(
+      "SELECT inning, position, player_id FROM lineup_positions \
+       WHERE lineup_id = ?1 ORDER BY inning, position",
+    )?
+    .query_map([id], |row| {
+      Ok(InningPosition {
+        inning: row.get(0)?,
+        position: row.get(1)?,
+        player_id: row.get(2)?,
+      })
+    })?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(lineup)
+}
+
+fn slots(conn: &Connection, lineup_id: &str) -> Result<Vec<LineupSlot>> {
+  let mut stmt = conn.prepare(
+    "SELECT s.slot_number, s.player_id, p.name, p.number, s.position \
+     FROM lineup_slots s LEFT JOIN players p ON p.id = s.player_id \
+     WHERE s.lineup_id = ?1 ORDER BY s.slot_number",
+  )?;
+  let slots = stmt
+    .query_map([lineup_id], |row| {
+      Ok(LineupSlot {
+        slot_number: row.get(0)?,
+        player_id: row.get(1)?,
+        player_name: row.get(2)?,
+        number: row.get(3)?,
+        position: row.get(4)?,
+      })
+    })?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(slots)
+}
+
+/// Creates or replaces a lineup, including all of its slots and inning
+/// positions, in one transaction, and journals the edit so it can be
+/// undone.
+pub fn save(conn: &mut Connection, input: LineupInput) -> Result<Lineup> {
+  let tx = conn.transaction()?;
+  let before = input.id.as_deref().map(|id| get(&tx, id)).transpose()?;
+  let id = save_in(&tx, &input)?;
+  let after = get(&tx, &id)?;
+  undo::record(&tx, Action::SaveLineup, before.as_ref(), Some(&after))?;
+  audit::lineup_changed(&tx, None, before.as_ref(), Some(&after))?;
+  tx.commit()?;
+  Ok(after)
+}
+
+/// [`save`] inside a transaction the caller commits, returning the
+/// lineup's id.
+pub(crate) fn save_in(tx: &Connection, input: &LineupInput) -> Result<String> {
+  validate(input)?;
+  let now = timestamp();
+  let id = match &input.id {
+    Some(id) => {
+      let versions: String = tx
+        .query_row("SELECT versions FROM lineups WHERE id = ?1", [id], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| Error::NotFound(format!("Lineup {id}")))?;
+      let mut versions: VersionVector = serde_json::from_str(&versions).unwrap_or_default();
+      versions::bump(tx, &mut versions)?;
+      tx.execute(
+        "UPDATE lineups SET name = ?2, game_id = ?3, use_dh = ?4, innings = ?5, \
+         updated_at = ?6, versions = ?7 WHERE id = ?1",
+        params![
+          id,
+          input.name.trim(),
+          input.game_id,
+          input.use_dh,
+          input.innings,
+          now,
+          serde_json::to_string(&versions)?
+        ],
+      )?;
+      tx.execute("DELETE FROM lineup_slots WHERE lineup_id = ?1", [id])?;
+      tx.execute("DELETE FROM lineup_positions WHERE lineup_id = ?1", [id])?;
+      id.clone()
+    }
+    None => {
+      let id = new_id();
+      let team_id = match &input.team_id {
+        Some(team_id) => team_id.clone(),
+        None => roster::default_team_id(tx)?,
+      };
+      let mut versions = VersionVector::new();
+      versions::bump(tx, &mut versions)?;
+      tx.execute(
+        "INSERT INTO lineups (id, team_id, name, game_id, use_dh, innings, created_at, \
+         updated_at, versions) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7, ?8)",
+        params![
+          id,
+          team_id,
+          input.name.trim(),
+          input.game_id,
+          input.use_dh,
+          input.innings,
+          now,
+          serde_json::to_string(&versions)?
+        ],
+      )?;
+      id
+    }
+  };
+
+  for slot in &input.slots {
+    tx.execute(
+      "INSERT INTO lineup_slots (lineup_id, slot_number, player_id, position) \
+       VALUES (?1, ?2, ?3, ?4)",
+      params![id, slot.slot_number, slot.player_id, slot.position],
+    )?;
+  }
+  for assignment in &input.positions {
+    tx.execute(
+      "INSERT INTO lineup_positions (lineup_id, inning, position, player_id) \
+       VALUES (?1, ?2, ?3, ?4)",
+      params![
+        id,
+        assignment.inning,
+        assignment.position,
+        assignment.player_id
+      ],
+    )?;
+  }
+  Ok(id)
+}
+
+/// Writes a fully-formed lineup, keeping its id, `updated_at` and versions,
+/// e.g. one received from a synced device, inside a transaction the caller
+/// commits.
+/// Slots and positions for players that don't exist here are dropped.
+pub fn write(tx: &Connection, lineup: &Lineup) -> Result<()> {
+  let game_id = match &lineup.game_id {
+    Some(game_id) => tx
+      .query_row("SELECT id FROM games WHERE id = ?1", [game_id], |row| row.get::<_, String>(0))
+      .optional()?,
+    None => None,
+  };
+  tx.execute(
+    "INSERT INTO lineups (id, team_id, name, game_id, use_dh, innings, created_at, updated_at, \
+     versions) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7, ?8) \
+     ON CONFLICT(id) DO UPDATE SET team_id = ?2, name = ?3, game_id = ?4, use_dh = ?5, \
+     innings = ?6, updated_at = ?7, versions = ?8",
+    params![
+      lineup.id,
+      lineup.team_id,
+      lineup.name,
+      game_id,
+      lineup.use_dh,
+      lineup.innings,
+      lineup.updated_at,
+      serde_json::to_string(&lineup.versions)?
+    ],
+  )?;
+  tx.execute("DELETE FROM lineup_slots WHERE lineup_id = ?1", [&lineup.id])?;
+  tx.execute("DELETE FROM lineup_positions WHERE lineup_id = ?1", [&lineup.id])?;
+
+  let known = |player_id: &str| -> rusqlite::Result<bool> {
+    tx.query_row(
+      "SELECT EXISTS(SELECT 1 FROM players WHERE id = ?1)",
+      [player_id],
+      |row| row.get(0),
+    )
+  };
+  for slot in &lineup.slots {
+    let player_id = match &slot.player_id {
+      Some(id) if known(id)? => Some(id),
+      _ => None,
+    };
+    tx.execute(
+      "INSERT INTO lineup_slots (lineup_id, slot_number, player_id, position) \
+       VALUES (?1, ?2, ?3, ?4)",
+      params![lineup.id, slot.slot_number, player_id, slot.position],
+    )?;
+  }
+  for assignment in &lineup.positions {
+    if !known(&assignment.player_id)? {
+      continue;
+    }
+    tx.execute(
+      "INSERT INTO lineup_positions (lineup_id, inning, position, player_id) \
+       VALUES (?1, ?2, ?3, ?4)",
+      params![
+        lineup.id,
+        assignment.inning,
+        assignment.position,
+        assignment.player_id
+      ],
+    )?;
+  }
+  Ok(())
+}
+
+fn validate(input: &LineupInput) -> Result<()> {
+  if input.name.trim().is_empty() {
+    return Err(Error::Validation("Lineup name cannot be empty".into()));
+  }
+  if !(1..=MAX_INNINGS).contains(&input.innings) {
+    return Err(Error::Validation(format!(
+      "Innings must be between 1 and {MAX_INNINGS}"
+    )));
+  }
+  let mut slot_numbers = HashSet::new();
+  for slot in &input.slots {
+    if !(1..=MAX_SLOTS).contains(&slot.slot_number) {
+      return Err(Error::Validation(format!(
+        "Batting order slots must be between 1 and {MAX_SLOTS}"
+      )));
+    }
+    if !slot_numbers.insert(slot.slot_number) {
+      return Err(Error::Validation(format!(
+        "Batting order slot {} is used twice",
+        slot.slot_number
+      )));
+    }
+  }
+  let mut assigned = HashSet::new();
+  for p in &input.positions {
+    if !(1..=input.innings).contains(&p.inning) {
+      return Err(Error::Validation(format!(
+        "Inning {} is outside the {}-inning game",
+        p.inning, input.innings
+      )));
+    }
+    if !POSITIONS.contains(&p.position.as_str()) {
+      return Err(Error::Validation(format!(
+        "Position must be one of: {}",
+        POSITIONS.join(", ")
+      )));
+    }
+    if !assigned.insert((p.inning, p.player_id.as_str())) {
+      return Err(Error::Validation(format!(
+        "A player has two positions in inning {}",
+        p.inning
+      )));
+    }
+  }
+  Ok(())
+}
+
+/// Deletes a lineup the coach removed, journaling it so it can be undone.
+pub fn delete(conn: &mut Connection, id: &str) -> Result<()> {
+  let tx = conn.transaction()?;
+  let before = get(&tx, id)?;
+  delete_in(&tx, id)?;
+  undo::record(&tx, Action::DeleteLineup, Some(&before), None)?;
+  audit::lineup_changed(&tx, None, Some(&before), None)?;
+  tx.commit()?;
+  Ok(())
+}
+
+/// Deletes a lineup without journaling or auditing it, e.g. for a synced
+/// device's edit.
+pub(crate) fn delete_in(conn: &Connection, id: &str) -> Result<()> {
+  let removed = conn.execute("DELETE FROM lineups WHERE id = ?1", [id])?;
+  if removed == 0 {
+    return Err(Error::NotFound(format!("Lineup {id}")));
+  }
+  Ok(())
+}
+
+/// Lists a team's saved lineups, defaulting to the first team.
+#[tauri::command]
+pub fn list_lineups(db: State<'_, Database>, team_id: Option<String>) -> Result<Vec<Lineup>> {
+  let conn = db.conn();
+  let team_id = match team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(&conn)?,
+  };
+  list(&conn, &team_id)
+}
+
+#[tauri::command]
+pub fn get_lineup(db: State<'_, Database>, id: String) -> Result<Lineup> {
+  get(&db.conn(), &id)
+}
+
+#[tauri::command]
+pub fn save_lineup(
+  db: State<'_, Database>,
+  sync: State<'_, SyncState>,
+  telemetry: State<'_, Telemetry>,
+  lineup: LineupInput,
+) -> Result<Lineup> {
+  let lineup = save(&mut db.conn(), lineup)?;
+  telemetry.record(Feature::LineupSaved);
+  sync.broadcast(Change::LineupSaved { lineup: lineup.clone() });
+  Ok(lineup)
+}
+
+#[tauri::command]
+pub fn delete_lineup(
+  db: State<'_, Database>,
+  sync: State<'_, SyncState>,
+  id: String,
+) -> Result<()> {
+  delete(&mut db.conn(), &id)?;
+  sync.broadcast(Change::LineupDeleted { id });
+  Ok(())
+}
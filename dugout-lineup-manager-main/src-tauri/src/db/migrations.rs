@@ -0,0 +1,240 @@
+//! Versioned schema migrations for the local database.
+//!
+//! The schema version is stored in SQLite's `user_version` pragma. Each
+//! migration runs in its own transaction and bumps the version, so a failed
+//! upgrade leaves the database at the last good version. Before touching an
+//! existing database, a copy is written to the pre-migration backup folder.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::State;
+
+use super::Database;
+use crate::error::Result;
+
+struct Migration {
+  version: u32,
+  name: &'static str,
+  sql: &'static str,
+}
+
+/// Append new migrations here; never edit one that has shipped.
+const MIGRATIONS: &[Migration] = &[
+  Migration {
+    version: 1,
+    name: "initial",
+    sql: include_str!("migrations/0001_initial.sql"),
+  },
+  Migration {
+    version: 2,
+    name: "games",
+    sql: include_str!("migrations/0002_games.sql"),
+  },
+  Migration {
+    version: 3,
+    name: "lineup_positions",
+    sql: include_str!("migrations/0003_lineup_positions.sql"),
+  },
+  Migration {
+    version: 4,
+    name: "ai_queue",
+    sql: include_str!("migrations/0004_ai_queue.sql"),
+  },
+  Migration {
+    version: 5,
+    name: "pitch_counts",
+    sql: include_str!("migrations/0005_pitch_counts.sql"),
+  },
+  Migration {
+    version: 6,
+    name: "stat_changes",
+    sql: include_str!("migrations/0006_stat_changes.sql"),
+  },
+  Migration {
+    version: 7,
+    name: "game_weather",
+    sql: include_str!("migrations/0007_game_weather.sql"),
+  },
+  Migration {
+    version: 8,
+    name: "player_contacts",
+    sql: include_str!("migrations/0008_player_contacts.sql"),
+  },
+  Migration {
+    version: 9,
+    name: "game_reminders",
+    sql: include_str!("migrations/0009_game_reminders.sql"),
+  },
+  Migration {
+    version: 10,
+    name: "undo_log",
+    sql: include_str!("migrations/0010_undo_log.sql"),
+  },
+  Migration {
+    version: 11,
+    name: "audit_log",
+    sql: include_str!("migrations/0011_audit_log.sql"),
+  },
+  Migration {
+    version: 12,
+    name: "sync_versions",
+    sql: include_str!("migrations/0012_sync_versions.sql"),
+  },
+  Migration {
+    version: 13,
+    name: "images",
+    sql: include_str!("migrations/0013_images.sql"),
+  },
+  Migration {
+    version: 14,
+    name: "scorebook",
+    sql: include_str!("migrations/0014_scorebook.sql"),
+  },
+  Migration {
+    version: 15,
+    name: "note_changes",
+    sql: include_str!("migrations/0015_note_changes.sql"),
+  },
+  Migration {
+    version: 16,
+    name: "practice",
+    sql: include_str!("migrations/0016_practice.sql"),
+  },
+  Migration {
+    version: 17,
+    name: "season_archives",
+    sql: include_str!("migrations/0017_season_archives.sql"),
+  },
+  Migration {
+    version: 18,
+    name: "quarantined_rows",
+    sql: include_str!("migrations/0018_quarantined_rows.sql"),
+  },
+];
+
+/// Schema version the running build expects.
+pub fn latest_version() -> u32 {
+  MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+pub fn current_version(conn: &Connection) -> Result<u32> {
+  Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Brings the schema up to date, backing up the existing database into
+/// `backup_dir` first when there is anything to migrate.
+pub fn run(conn: &mut Connection, backup_dir: Option<&Path>) -> Result<()> {
+  run_with_progress(conn, backup_dir, |_, _| {})
+}
+
+/// Like [`run`], calling `on_step` with the step and the number of steps
+/// before each migration is applied.
+pub fn run_with_progress(
+  conn: &mut Connection,
+  backup_dir: Option<&Path>,
+  mut on_step: impl FnMut(usize, usize),
+) -> Result<()> {
+  let current = current_version(conn)?;
+  let pending: Vec<_> = MIGRATIONS.iter().filter(|m| m.version > current).collect();
+  if pending.is_empty() {
+    return Ok(());
+  }
+
+  if let Some(dir) = backup_dir {
+    if has_user_tables(conn)? {
+      let path = backup(conn, dir, current)?;
+      log::info!("Backed up database before migrating: {:?}", path);
+    }
+  }
+
+  let total = pending.len();
+  for (step, migration) in pending.into_iter().enumerate() {
+    on_step(step + 1, total);
+    log::info!(
+      "Applying database migration {} ({})",
+      migration.version,
+      migration.name
+    );
+    let tx = conn.transaction()?;
+    tx.execute_batch(migration.sql)?;
+    tx.pragma_update(None, "user_version", migration.version)?;
+    tx.commit()?;
+  }
+  Ok(())
+}
+
+fn has_user_tables(conn: &Connection) -> Result<bool> {
+  Ok(conn.query_row(
+    "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%')",
+    [],
+    |row| row.get(0),
+  )?)
+}
+
+/// Writes a consistent copy of the database with `VACUUM INTO`.
+fn backup(conn: &Connection, dir: &Path, version: u32) -> Result<PathBuf> {
+  std::fs::create_dir_all(dir)?;
+  let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+  let path = dir.join(format!("dugout-v{version}-{stamp}.db"));
+  conn.execute("VACUUM INTO ?1", [path.to_string_lossy()])?;
+  Ok(path)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaVersion {
+  pub current: u32,
+  pub latest: u32,
+}
+
+/// Reports the database's schema version and the one this build expects.
+#[tauri::command]
+pub fn get_schema_version(db: State<'_, Database>) -> Result<SchemaVersion> {
+  Ok(SchemaVersion {
+    current: current_version(&db.conn())?,
+    latest: latest_version(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn migrates_a_fresh_database_to_the_latest_version() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    run(&mut conn, None).unwrap();
+    assert_eq!(current_version(&conn).unwrap(), latest_version());
+
+    // Running again is a no-op.
+    run(&mut conn, None).unwrap();
+    assert_eq!(current_version(&conn).unwrap(), latest_version());
+  }
+
+  #[test]
+  fn reports_each_pending_migration() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    let mut steps = Vec::new();
+    run_with_progress(&mut conn, None, |step, total| steps.push((step, total))).unwrap();
+
+    let total = MIGRATIONS.len();
+    assert_eq!(steps, (1..=total).map(|step| (step, total)).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn backs_up_an_existing_database_before_migrating() {
+    let dir = std::env::temp_dir().join(format!("dugout-migrations-{}", uuid::Uuid::new_v4()));
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn
+      .execute_batch("CREATE TABLE legacy (id INTEGER PRIMARY KEY)")
+      .unwrap();
+
+    run(&mut conn, Some(&dir)).unwrap();
+
+    let backups = std::fs::read_dir(&dir).unwrap().count();
+    assert_eq!(backups, 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
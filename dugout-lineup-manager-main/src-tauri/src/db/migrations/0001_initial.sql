@@ -0,0 +1,40 @@
+-- Teams, players and saved lineups.
+CREATE TABLE IF NOT EXISTS teams (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS players (
+  id TEXT PRIMARY KEY,
+  team_id TEXT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  name TEXT NOT NULL,
+  number INTEGER,
+  primary_position TEXT NOT NULL,
+  secondary_positions TEXT NOT NULL DEFAULT '[]',
+  bats TEXT NOT NULL,
+  throws TEXT NOT NULL,
+  status TEXT NOT NULL DEFAULT 'active',
+  notes TEXT NOT NULL DEFAULT '',
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_players_team ON players(team_id);
+
+CREATE TABLE IF NOT EXISTS lineups (
+  id TEXT PRIMARY KEY,
+  team_id TEXT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  name TEXT NOT NULL,
+  game_id TEXT,
+  use_dh INTEGER NOT NULL DEFAULT 1,
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS lineup_slots (
+  lineup_id TEXT NOT NULL REFERENCES lineups(id) ON DELETE CASCADE,
+  slot_number INTEGER NOT NULL,
+  player_id TEXT REFERENCES players(id) ON DELETE SET NULL,
+  position TEXT,
+  PRIMARY KEY (lineup_id, slot_number)
+);
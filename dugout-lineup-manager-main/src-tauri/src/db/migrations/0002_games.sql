@@ -0,0 +1,47 @@
+-- Season schedule and per-player box scores, mirroring the backend's
+-- Game and GameStats models.
+CREATE TABLE IF NOT EXISTS games (
+  id TEXT PRIMARY KEY,
+  team_id TEXT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  date TEXT NOT NULL,
+  opponent TEXT NOT NULL,
+  home_away TEXT NOT NULL DEFAULT 'home',
+  result TEXT,
+  score_us INTEGER,
+  score_them INTEGER,
+  status TEXT NOT NULL DEFAULT 'scheduled',
+  notes TEXT NOT NULL DEFAULT '',
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_games_team_date ON games(team_id, date);
+
+CREATE TABLE IF NOT EXISTS game_stats (
+  game_id TEXT NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+  player_id TEXT NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+  ab INTEGER NOT NULL DEFAULT 0,
+  r INTEGER NOT NULL DEFAULT 0,
+  h INTEGER NOT NULL DEFAULT 0,
+  doubles INTEGER NOT NULL DEFAULT 0,
+  triples INTEGER NOT NULL DEFAULT 0,
+  hr INTEGER NOT NULL DEFAULT 0,
+  rbi INTEGER NOT NULL DEFAULT 0,
+  bb INTEGER NOT NULL DEFAULT 0,
+  so INTEGER NOT NULL DEFAULT 0,
+  sb INTEGER NOT NULL DEFAULT 0,
+  cs INTEGER NOT NULL DEFAULT 0,
+  ip REAL NOT NULL DEFAULT 0,
+  h_allowed INTEGER NOT NULL DEFAULT 0,
+  r_allowed INTEGER NOT NULL DEFAULT 0,
+  er INTEGER NOT NULL DEFAULT 0,
+  bb_allowed INTEGER NOT NULL DEFAULT 0,
+  k INTEGER NOT NULL DEFAULT 0,
+  pitches INTEGER NOT NULL DEFAULT 0,
+  po INTEGER NOT NULL DEFAULT 0,
+  a INTEGER NOT NULL DEFAULT 0,
+  e INTEGER NOT NULL DEFAULT 0,
+  positions_played TEXT NOT NULL DEFAULT '[]',
+  innings_played REAL NOT NULL DEFAULT 0,
+  PRIMARY KEY (game_id, player_id)
+);
+CREATE INDEX IF NOT EXISTS idx_game_stats_player ON game_stats(player_id);
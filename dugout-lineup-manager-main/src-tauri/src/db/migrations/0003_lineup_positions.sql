@@ -0,0 +1,10 @@
+-- Defensive assignments for each inning of a lineup.
+ALTER TABLE lineups ADD COLUMN innings INTEGER NOT NULL DEFAULT 6;
+
+CREATE TABLE IF NOT EXISTS lineup_positions (
+  lineup_id TEXT NOT NULL REFERENCES lineups(id) ON DELETE CASCADE,
+  inning INTEGER NOT NULL,
+  position TEXT NOT NULL,
+  player_id TEXT NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+  PRIMARY KEY (lineup_id, inning, position)
+);
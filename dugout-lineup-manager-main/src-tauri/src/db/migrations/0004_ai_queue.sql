@@ -0,0 +1,13 @@
+-- AI requests made while offline, replayed when connectivity returns.
+CREATE TABLE IF NOT EXISTS ai_queue (
+  id TEXT PRIMARY KEY,
+  kind TEXT NOT NULL,
+  payload TEXT NOT NULL,
+  status TEXT NOT NULL DEFAULT 'pending',
+  attempts INTEGER NOT NULL DEFAULT 0,
+  response TEXT,
+  last_error TEXT,
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_ai_queue_status ON ai_queue(status, created_at);
@@ -0,0 +1,12 @@
+-- Pitches thrown per pitcher per game, for league rest rules. `game_id`
+-- isn't a foreign key because the game may only be in the backend's
+-- schedule, so the date is kept here too.
+CREATE TABLE IF NOT EXISTS pitch_counts (
+  player_id TEXT NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+  game_id TEXT NOT NULL,
+  date TEXT NOT NULL,
+  pitches INTEGER NOT NULL DEFAULT 0,
+  updated_at TEXT NOT NULL,
+  PRIMARY KEY (player_id, game_id)
+);
+CREATE INDEX IF NOT EXISTS idx_pitch_counts_date ON pitch_counts(date);
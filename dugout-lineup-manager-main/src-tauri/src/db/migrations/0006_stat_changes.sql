@@ -0,0 +1,23 @@
+-- Players whose box scores changed, with an ever-increasing version, so
+-- cached stats are only recomputed for them.
+CREATE TABLE IF NOT EXISTS stat_changes (
+  player_id TEXT PRIMARY KEY,
+  version INTEGER NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS stat_changes_insert AFTER INSERT ON game_stats BEGIN
+  INSERT OR REPLACE INTO stat_changes (player_id, version)
+    VALUES (NEW.player_id, (SELECT COALESCE(MAX(version), 0) + 1 FROM stat_changes));
+END;
+
+CREATE TRIGGER IF NOT EXISTS stat_changes_update AFTER UPDATE ON game_stats BEGIN
+  INSERT OR REPLACE INTO stat_changes (player_id, version)
+    VALUES (OLD.player_id, (SELECT COALESCE(MAX(version), 0) + 1 FROM stat_changes));
+  INSERT OR REPLACE INTO stat_changes (player_id, version)
+    VALUES (NEW.player_id, (SELECT COALESCE(MAX(version), 0) + 1 FROM stat_changes));
+END;
+
+CREATE TRIGGER IF NOT EXISTS stat_changes_delete AFTER DELETE ON game_stats BEGIN
+  INSERT OR REPLACE INTO stat_changes (player_id, version)
+    VALUES (OLD.player_id, (SELECT COALESCE(MAX(version), 0) + 1 FROM stat_changes));
+END;
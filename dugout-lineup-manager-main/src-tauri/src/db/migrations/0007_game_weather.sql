@@ -0,0 +1,22 @@
+-- Where each game is played, and the forecasts fetched for it.
+ALTER TABLE games ADD COLUMN location TEXT NOT NULL DEFAULT '';
+
+-- Field addresses already looked up, keyed by the lowercased address.
+CREATE TABLE IF NOT EXISTS geocode_cache (
+  address TEXT PRIMARY KEY,
+  latitude REAL NOT NULL,
+  longitude REAL NOT NULL,
+  name TEXT NOT NULL,
+  fetched_at TEXT NOT NULL
+);
+
+-- The latest forecast per game, stored as JSON, with what it was asked for
+-- so a moved game or a new provider fetches again.
+CREATE TABLE IF NOT EXISTS weather_cache (
+  game_id TEXT PRIMARY KEY REFERENCES games(id) ON DELETE CASCADE,
+  provider TEXT NOT NULL,
+  address TEXT NOT NULL,
+  date TEXT NOT NULL,
+  forecast TEXT NOT NULL,
+  fetched_at TEXT NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Parents' email addresses for each player, for sending lineups home.
+CREATE TABLE IF NOT EXISTS player_contacts (
+  player_id TEXT NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+  email TEXT NOT NULL,
+  position INTEGER NOT NULL,
+  PRIMARY KEY (player_id, email)
+);
@@ -0,0 +1,12 @@
+-- When each game starts, for reminders. `HH:MM` local time; NULL when the
+-- schedule only gave a date.
+ALTER TABLE games ADD COLUMN start_time TEXT;
+
+-- The reminder last shown for each game, so a restart doesn't repeat it.
+-- `starts_at` is the start it was for; a rescheduled game is reminded again.
+CREATE TABLE IF NOT EXISTS game_reminders (
+  game_id TEXT PRIMARY KEY REFERENCES games(id) ON DELETE CASCADE,
+  starts_at TEXT NOT NULL,
+  fired_at TEXT,
+  snoozed_until TEXT
+);
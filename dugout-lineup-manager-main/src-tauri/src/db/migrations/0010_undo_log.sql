@@ -0,0 +1,13 @@
+-- The coach's lineup edits, oldest first, each with the lineup as it was
+-- before and after (JSON; NULL where it didn't exist) so it can be undone
+-- and redone. Undone entries are the redo stack until the next edit.
+CREATE TABLE IF NOT EXISTS undo_log (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  action TEXT NOT NULL,
+  lineup_id TEXT NOT NULL,
+  lineup_name TEXT NOT NULL,
+  before TEXT,
+  after TEXT,
+  undone INTEGER NOT NULL DEFAULT 0,
+  created_at TEXT NOT NULL
+);
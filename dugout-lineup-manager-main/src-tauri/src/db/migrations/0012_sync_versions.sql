@@ -0,0 +1,20 @@
+-- Each lineup's version vector: a JSON object of replica id to the number
+-- of saves made there, for spotting edits synced devices made at once.
+ALTER TABLE lineups ADD COLUMN versions TEXT NOT NULL DEFAULT '{}';
+
+-- This database's replica id in those vectors; one row.
+CREATE TABLE IF NOT EXISTS sync_replica (
+  id TEXT NOT NULL
+);
+
+INSERT INTO sync_replica (id)
+  SELECT lower(hex(randomblob(16))) WHERE NOT EXISTS (SELECT 1 FROM sync_replica);
+
+-- A paired device's edit to a lineup that clashed with one made here, kept
+-- (as the JSON lineup) until the coach decides which to keep.
+CREATE TABLE IF NOT EXISTS sync_conflicts (
+  lineup_id TEXT PRIMARY KEY REFERENCES lineups(id) ON DELETE CASCADE,
+  device TEXT NOT NULL,
+  theirs TEXT NOT NULL,
+  detected_at TEXT NOT NULL
+);
@@ -0,0 +1,13 @@
+-- Team logos and player photos brought in with `import_image`, at most one
+-- each. The files are in the data directory's `images/` folder, named
+-- after the id, so they travel with backups.
+CREATE TABLE IF NOT EXISTS images (
+  id TEXT PRIMARY KEY,
+  team_id TEXT UNIQUE REFERENCES teams(id) ON DELETE CASCADE,
+  player_id TEXT UNIQUE REFERENCES players(id) ON DELETE CASCADE,
+  extension TEXT NOT NULL,
+  width INTEGER NOT NULL,
+  height INTEGER NOT NULL,
+  created_at TEXT NOT NULL,
+  CHECK ((team_id IS NULL) <> (player_id IS NULL))
+);
@@ -0,0 +1,9 @@
+-- Play-by-play log for each game, replayed into the count, bases and box
+-- score. Entries are only ever appended, or the newest removed on undo.
+CREATE TABLE IF NOT EXISTS scorebook_events (
+  game_id TEXT NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+  seq INTEGER NOT NULL,
+  event TEXT NOT NULL,
+  created_at TEXT NOT NULL,
+  PRIMARY KEY (game_id, seq)
+);
@@ -0,0 +1,39 @@
+-- Players and games whose notes (or what's shown with them) changed, with
+-- an ever-increasing version, so the notes search index only re-reads them.
+-- `doc_id` is 'player:<id>' or 'game:<id>'.
+CREATE TABLE IF NOT EXISTS note_changes (
+  doc_id TEXT PRIMARY KEY,
+  version INTEGER NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS note_changes_player_insert AFTER INSERT ON players BEGIN
+  INSERT OR REPLACE INTO note_changes (doc_id, version)
+    VALUES ('player:' || NEW.id, (SELECT COALESCE(MAX(version), 0) + 1 FROM note_changes));
+END;
+
+CREATE TRIGGER IF NOT EXISTS note_changes_player_update
+AFTER UPDATE OF name, team_id, notes ON players BEGIN
+  INSERT OR REPLACE INTO note_changes (doc_id, version)
+    VALUES ('player:' || NEW.id, (SELECT COALESCE(MAX(version), 0) + 1 FROM note_changes));
+END;
+
+CREATE TRIGGER IF NOT EXISTS note_changes_player_delete AFTER DELETE ON players BEGIN
+  INSERT OR REPLACE INTO note_changes (doc_id, version)
+    VALUES ('player:' || OLD.id, (SELECT COALESCE(MAX(version), 0) + 1 FROM note_changes));
+END;
+
+CREATE TRIGGER IF NOT EXISTS note_changes_game_insert AFTER INSERT ON games BEGIN
+  INSERT OR REPLACE INTO note_changes (doc_id, version)
+    VALUES ('game:' || NEW.id, (SELECT COALESCE(MAX(version), 0) + 1 FROM note_changes));
+END;
+
+CREATE TRIGGER IF NOT EXISTS note_changes_game_update
+AFTER UPDATE OF team_id, date, opponent, home_away, notes ON games BEGIN
+  INSERT OR REPLACE INTO note_changes (doc_id, version)
+    VALUES ('game:' || NEW.id, (SELECT COALESCE(MAX(version), 0) + 1 FROM note_changes));
+END;
+
+CREATE TRIGGER IF NOT EXISTS note_changes_game_delete AFTER DELETE ON games BEGIN
+  INSERT OR REPLACE INTO note_changes (doc_id, version)
+    VALUES ('game:' || OLD.id, (SELECT COALESCE(MAX(version), 0) + 1 FROM note_changes));
+END;
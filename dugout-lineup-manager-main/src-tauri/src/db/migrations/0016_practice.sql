@@ -0,0 +1,31 @@
+-- Drills the coach keeps for building practices. `team_id` is NULL for
+-- drills every team can use.
+CREATE TABLE IF NOT EXISTS drills (
+  id TEXT PRIMARY KEY,
+  team_id TEXT REFERENCES teams(id) ON DELETE CASCADE,
+  name TEXT NOT NULL,
+  focus TEXT NOT NULL,
+  minutes INTEGER NOT NULL,
+  description TEXT NOT NULL DEFAULT '',
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_drills_team ON drills(team_id);
+
+-- Generated practice plans. `focus` and `drill_ids` are JSON arrays of the
+-- areas the plan works on and the drills it was offered; `prompt` is what
+-- was sent, so a plan can be written again.
+CREATE TABLE IF NOT EXISTS practice_plans (
+  id TEXT PRIMARY KEY,
+  team_id TEXT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  title TEXT NOT NULL,
+  minutes INTEGER NOT NULL,
+  focus TEXT NOT NULL DEFAULT '[]',
+  drill_ids TEXT NOT NULL DEFAULT '[]',
+  prompt TEXT NOT NULL,
+  model TEXT NOT NULL,
+  content TEXT NOT NULL,
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_practice_plans_team ON practice_plans(team_id, created_at);
@@ -0,0 +1,11 @@
+-- Finished seasons moved out of the live tables by `archive_season`, so
+-- schedule and stats queries only see seasons still in use. `data` holds
+-- the season's rows in the JSON export's layout, deflated and in base64.
+CREATE TABLE IF NOT EXISTS season_archives (
+  team_id TEXT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+  season TEXT NOT NULL,
+  games INTEGER NOT NULL,
+  data TEXT NOT NULL,
+  archived_at TEXT NOT NULL,
+  PRIMARY KEY (team_id, season)
+);
@@ -0,0 +1,10 @@
+-- Rows `check_data_integrity` took out of their table because they pointed
+-- at rows that no longer exist. `data` is the row as a JSON object keyed by
+-- column, kept so nothing a repair removes is lost for good.
+CREATE TABLE IF NOT EXISTS quarantined_rows (
+  id INTEGER PRIMARY KEY,
+  table_name TEXT NOT NULL,
+  data TEXT NOT NULL,
+  reason TEXT NOT NULL,
+  quarantined_at TEXT NOT NULL
+);
@@ -0,0 +1,126 @@
+//! Native SQLite data layer.
+//!
+//! Teams, players, lineups and games live in `dugout.db` inside the data directory,
+//! managed from Rust so core roster data keeps working even when the AI
+//! sidecar fails to start.
+
+pub mod ai_queue;
+pub mod audit;
+pub mod contacts;
+pub mod drills;
+pub mod encryption;
+pub mod gamechanger;
+pub mod games;
+pub mod integrity;
+pub mod lineups;
+pub mod migrations;
+pub mod pitch_counts;
+pub mod practice_plans;
+pub mod roster;
+pub mod roster_csv;
+pub mod seasons;
+pub mod undo;
+pub mod versions;
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use rusqlite::Connection;
+
+use crate::error::Result;
+
+/// File name of the database inside the data directory.
+pub const DB_FILE: &str = "dugout.db";
+
+/// Shared handle to the local database, registered as `tauri::State`.
+pub struct Database {
+  conn: Mutex<Connection>,
+}
+
+impl Database {
+  /// Opens (creating if needed) the database in `data_dir` and applies any
+  /// pending migrations, backing up the old file into `backup_dir` first.
+  pub fn open(data_dir: &Path, backup_dir: &Path) -> Result<Self> {
+    Self::open_with_progress(data_dir, backup_dir, |_, _| {})
+  }
+
+  /// Like [`open`](Self::open), passing each migration's step and the
+  /// number of steps to `on_step` before it is applied.
+  pub fn open_with_progress(
+    data_dir: &Path,
+    backup_dir: &Path,
+    on_step: impl FnMut(usize, usize),
+  ) -> Result<Self> {
+    let mut conn = connect(&data_dir.join(DB_FILE))?;
+    migrations::run_with_progress(&mut conn, Some(backup_dir), on_step)?;
+    Ok(Self {
+      conn: Mutex::new(conn),
+    })
+  }
+
+  /// Switches to the database in `data_dir`, migrated like [`open`]. The
+  /// current one stays open if the new one can't be.
+  ///
+  /// [`open`]: Self::open
+  pub fn reopen(&self, data_dir: &Path, backup_dir: &Path) -> Result<()> {
+    let mut conn = connect(&data_dir.join(DB_FILE))?;
+    migrations::run(&mut conn, Some(backup_dir))?;
+    *self.conn() = conn;
+    Ok(())
+  }
+
+  /// Runs `copy` with the database folded into its main file and nobody
+  /// else using it, then switches to the copied database in `data_dir` (see
+  /// [`reopen`]). The current one stays open if either step fails.
+  ///
+  /// [`reopen`]: Self::reopen
+  pub fn move_to(
+    &self,
+    data_dir: &Path,
+    backup_dir: &Path,
+    copy: impl FnOnce() -> Result<()>,
+  ) -> Result<()> {
+    let mut conn = self.conn();
+    conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE)")?;
+    copy()?;
+    let mut moved = connect(&data_dir.join(DB_FILE))?;
+    migrations::run(&mut moved, Some(backup_dir))?;
+    *conn = moved;
+    Ok(())
+  }
+
+  pub fn conn(&self) -> MutexGuard<'_, Connection> {
+    self.conn.lock().unwrap_or_else(|e| e.into_inner())
+  }
+}
+
+/// Opens the database file at `path`, unlocking it if it is encrypted.
+fn connect(path: &Path) -> Result<Connection> {
+  let conn = encryption::open(path)?;
+  configure(&conn)?;
+  Ok(conn)
+}
+
+fn configure(conn: &Connection) -> Result<()> {
+  conn.pragma_update(None, "foreign_keys", "ON")?;
+  conn.busy_timeout(std::time::Duration::from_secs(5))?;
+  Ok(())
+}
+
+/// Current time as an RFC 3339 string, the format used for every
+/// `*_at` column.
+pub fn timestamp() -> String {
+  chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+pub fn new_id() -> String {
+  uuid::Uuid::new_v4().to_string()
+}
+
+#[cfg(test)]
+pub(crate) fn open_in_memory() -> Connection {
+  let mut conn = Connection::open_in_memory().unwrap();
+  configure(&conn).unwrap();
+  migrations::run(&mut conn, None).unwrap();
+  conn
+}
@@ -0,0 +1,119 @@
+//! Pitches thrown by each pitcher in each game.
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Row};
+use serde::Serialize;
+
+use super::{roster, timestamp};
+use crate::error::{Error, Result};
+
+/// Most pitches recorded for one game; anything higher is a typo.
+const MAX_PITCHES: u32 = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PitchCount {
+  pub player_id: String,
+  pub game_id: String,
+  /// `YYYY-MM-DD`.
+  pub date: String,
+  pub pitches: u32,
+}
+
+fn count_from_row(row: &Row<'_>) -> rusqlite::Result<PitchCount> {
+  Ok(PitchCount {
+    player_id: row.get(0)?,
+    game_id: row.get(1)?,
+    date: row.get(2)?,
+    pitches: row.get(3)?,
+  })
+}
+
+pub fn parse_date(date: &str) -> Result<NaiveDate> {
+  NaiveDate::parse_from_str(date, "%Y-%m-%d")
+    .map_err(|_| Error::Validation(format!("Invalid date {date:?}; expected YYYY-MM-DD")))
+}
+
+/// Sets the pitches `player_id` has thrown in a game, replacing any count
+/// recorded for it before.
+pub fn record(
+  conn: &Connection,
+  player_id: &str,
+  game_id: &str,
+  date: &str,
+  pitches: u32,
+) -> Result<PitchCount> {
+  roster::get(conn, player_id)?;
+  if game_id.trim().is_empty() {
+    return Err(Error::Validation("A game id is required".into()));
+  }
+  parse_date(date)?;
+  if pitches > MAX_PITCHES {
+    return Err(Error::Validation(format!("Pitch count can't be more than {MAX_PITCHES}")));
+  }
+  conn.execute(
+    "INSERT INTO pitch_counts (player_id, game_id, date, pitches, updated_at) \
+     VALUES (?1, ?2, ?3, ?4, ?5) \
+     ON CONFLICT (player_id, game_id) \
+     DO UPDATE SET date = excluded.date, pitches = excluded.pitches, \
+       updated_at = excluded.updated_at",
+    params![player_id, game_id, date, pitches, timestamp()],
+  )?;
+  Ok(PitchCount {
+    player_id: player_id.to_string(),
+    game_id: game_id.to_string(),
+    date: date.to_string(),
+    pitches,
+  })
+}
+
+/// Counts for a team's players in games on or after `from`, oldest first.
+pub fn since(conn: &Connection, team_id: &str, from: &str) -> Result<Vec<PitchCount>> {
+  let mut stmt = conn.prepare(
+    "SELECT c.player_id, c.game_id, c.date, c.pitches \
+     FROM pitch_counts c JOIN players p ON p.id = c.player_id \
+     WHERE p.team_id = ?1 AND c.date >= ?2 AND c.pitches > 0 \
+     ORDER BY c.date, c.updated_at",
+  )?;
+  let counts = stmt
+    .query_map(params![team_id, from], count_from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+  use crate::db::roster::{create, NewPlayer};
+
+  #[test]
+  fn replaces_the_count_for_a_game() {
+    let conn = open_in_memory();
+    let pitcher = create(
+      &conn,
+      NewPlayer {
+        team_id: None,
+        name: "Jake Miller".into(),
+        number: Some(12),
+        primary_position: "P".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap();
+
+    record(&conn, &pitcher.id, "g1", "2026-04-01", 20).unwrap();
+    record(&conn, &pitcher.id, "g1", "2026-04-01", 42).unwrap();
+    record(&conn, &pitcher.id, "g2", "2026-04-05", 30).unwrap();
+
+    let counts = since(&conn, &pitcher.team_id, "2026-04-01").unwrap();
+    let summary: Vec<_> = counts.iter().map(|c| (c.game_id.as_str(), c.pitches)).collect();
+    assert_eq!(summary, [("g1", 42), ("g2", 30)]);
+    assert_eq!(since(&conn, &pitcher.team_id, "2026-04-02").unwrap().len(), 1);
+    assert!(record(&conn, &pitcher.id, "g3", "04/06/2026", 10).is_err());
+  }
+}
@@ -0,0 +1,218 @@
+//! Practice plans the AI wrote from the team's drills and recent stats
+//! (see [`crate::practice`]), kept like any other record so the coach can
+//! edit, reuse or delete them.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::{new_id, roster, timestamp, Database};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PracticePlan {
+  pub id: String,
+  pub team_id: String,
+  pub title: String,
+  pub minutes: u32,
+  /// Areas the plan was asked to work on, from [`super::drills::FOCUSES`].
+  pub focus: Vec<String>,
+  /// Drills the plan was written from.
+  pub drill_ids: Vec<String>,
+  /// What was sent to the AI.
+  pub prompt: String,
+  pub model: String,
+  /// The plan itself, as the AI wrote it or the coach edited it.
+  pub content: String,
+  pub created_at: String,
+  pub updated_at: String,
+}
+
+pub struct NewPracticePlan {
+  pub team_id: String,
+  pub title: String,
+  pub minutes: u32,
+  pub focus: Vec<String>,
+  pub drill_ids: Vec<String>,
+  pub prompt: String,
+  pub model: String,
+  pub content: String,
+}
+
+/// Partial update; `None` leaves a field unchanged.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PracticePlanChanges {
+  pub title: Option<String>,
+  pub content: Option<String>,
+}
+
+const PLAN_COLUMNS: &str = "id, team_id, title, minutes, focus, drill_ids, prompt, model, \
+   content, created_at, updated_at";
+
+fn plan_from_row(row: &Row<'_>) -> rusqlite::Result<PracticePlan> {
+  let focus: String = row.get(4)?;
+  let drill_ids: String = row.get(5)?;
+  Ok(PracticePlan {
+    id: row.get(0)?,
+    team_id: row.get(1)?,
+    title: row.get(2)?,
+    minutes: row.get(3)?,
+    focus: serde_json::from_str(&focus).unwrap_or_default(),
+    drill_ids: serde_json::from_str(&drill_ids).unwrap_or_default(),
+    prompt: row.get(6)?,
+    model: row.get(7)?,
+    content: row.get(8)?,
+    created_at: row.get(9)?,
+    updated_at: row.get(10)?,
+  })
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<PracticePlan> {
+  conn
+    .query_row(
+      &format!("SELECT {PLAN_COLUMNS} FROM practice_plans WHERE id = ?1"),
+      [id],
+      plan_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| Error::NotFound(format!("Practice plan {id}")))
+}
+
+/// A team's practice plans, newest first.
+pub fn list(conn: &Connection, team_id: &str) -> Result<Vec<PracticePlan>> {
+  let mut stmt = conn.prepare(&format!(
+    "SELECT {PLAN_COLUMNS} FROM practice_plans WHERE team_id = ?1 \
+     ORDER BY created_at DESC, rowid DESC"
+  ))?;
+  let plans = stmt
+    .query_map([team_id], plan_from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(plans)
+}
+
+pub fn create(conn: &Connection, plan: NewPracticePlan) -> Result<PracticePlan> {
+  let id = new_id();
+  conn.execute(
+    "INSERT INTO practice_plans (id, team_id, title, minutes, focus, drill_ids, prompt, model, \
+     content, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+    params![
+      id,
+      plan.team_id,
+      plan.title,
+      plan.minutes,
+      serde_json::to_string(&plan.focus)?,
+      serde_json::to_string(&plan.drill_ids)?,
+      plan.prompt,
+      plan.model,
+      plan.content,
+      timestamp()
+    ],
+  )?;
+  get(conn, &id)
+}
+
+pub fn update(conn: &Connection, id: &str, changes: PracticePlanChanges) -> Result<PracticePlan> {
+  let mut plan = get(conn, id)?;
+  if let Some(title) = changes.title {
+    plan.title = title.trim().to_string();
+  }
+  if let Some(content) = changes.content {
+    plan.content = content;
+  }
+  if plan.title.is_empty() {
+    return Err(Error::Validation("A practice plan needs a title".into()));
+  }
+  conn.execute(
+    "UPDATE practice_plans SET title = ?2, content = ?3, updated_at = ?4 WHERE id = ?1",
+    params![id, plan.title, plan.content, timestamp()],
+  )?;
+  get(conn, id)
+}
+
+pub fn delete(conn: &Connection, id: &str) -> Result<()> {
+  if conn.execute("DELETE FROM practice_plans WHERE id = ?1", [id])? == 0 {
+    return Err(Error::NotFound(format!("Practice plan {id}")));
+  }
+  Ok(())
+}
+
+/// Lists a team's practice plans, newest first, defaulting to the first
+/// team.
+#[tauri::command]
+pub fn list_practice_plans(
+  db: State<'_, Database>,
+  team_id: Option<String>,
+) -> Result<Vec<PracticePlan>> {
+  let conn = db.conn();
+  let team_id = match team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(&conn)?,
+  };
+  list(&conn, &team_id)
+}
+
+#[tauri::command]
+pub fn get_practice_plan(db: State<'_, Database>, id: String) -> Result<PracticePlan> {
+  get(&db.conn(), &id)
+}
+
+/// Renames a plan or saves the coach's edits to it.
+#[tauri::command]
+pub fn update_practice_plan(
+  db: State<'_, Database>,
+  id: String,
+  changes: PracticePlanChanges,
+) -> Result<PracticePlan> {
+  update(&db.conn(), &id, changes)
+}
+
+#[tauri::command]
+pub fn delete_practice_plan(db: State<'_, Database>, id: String) -> Result<()> {
+  delete(&db.conn(), &id)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+
+  #[test]
+  fn keeps_edits_to_generated_plans() {
+    let conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let plan = create(
+      &conn,
+      NewPracticePlan {
+        team_id: team_id.clone(),
+        title: "Tuesday practice".into(),
+        minutes: 60,
+        focus: vec!["hitting".into()],
+        drill_ids: vec!["d1".into()],
+        prompt: "Plan a practice".into(),
+        model: "gpt-4o".into(),
+        content: "1. Soft toss (15 min)".into(),
+      },
+    )
+    .unwrap();
+    assert_eq!(plan.focus, ["hitting"]);
+
+    let changes = PracticePlanChanges {
+      content: Some("1. Soft toss (20 min)".into()),
+      ..Default::default()
+    };
+    let edited = update(&conn, &plan.id, changes).unwrap();
+    assert_eq!(edited.title, "Tuesday practice");
+    assert_eq!(edited.content, "1. Soft toss (20 min)");
+    assert_eq!(list(&conn, &team_id).unwrap(), [edited]);
+    let untitled = PracticePlanChanges {
+      title: Some(" ".into()),
+      ..Default::default()
+    };
+    assert!(update(&conn, &plan.id, untitled).is_err());
+
+    delete(&conn, &plan.id).unwrap();
+    assert!(get(&conn, &plan.id).is_err());
+  }
+}
@@ -0,0 +1,474 @@
+//! Team roster: players and the team they belong to.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Deserializer, Serialize};
+use tauri::State;
+
+use super::{audit, new_id, timestamp, Database};
+use crate::error::{Error, Result};
+use crate::sync::{Change, SyncState};
+
+/// Position codes accepted for players, matching the backend's models.
+pub const POSITIONS: [&str; 10] = ["P", "C", "1B", "2B", "3B", "SS", "LF", "CF", "RF", "DH"];
+const BATS: [&str; 3] = ["L", "R", "S"];
+const THROWS: [&str; 2] = ["L", "R"];
+const STATUSES: [&str; 3] = ["active", "inactive", "archived"];
+
+/// Name given to the team created automatically on first use.
+const DEFAULT_TEAM_NAME: &str = "My Team";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Player {
+  pub id: String,
+  pub team_id: String,
+  pub name: String,
+  pub number: Option<u32>,
+  pub primary_position: String,
+  pub secondary_positions: Vec<String>,
+  pub bats: String,
+  pub throws: String,
+  pub status: String,
+  pub notes: String,
+  pub created_at: String,
+  pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewPlayer {
+  /// Defaults to the first team, which is created if none exist.
+  pub team_id: Option<String>,
+  pub name: String,
+  pub number: Option<u32>,
+  pub primary_position: String,
+  #[serde(default)]
+  pub secondary_positions: Vec<String>,
+  pub bats: String,
+  pub throws: String,
+  pub status: Option<String>,
+  pub notes: Option<String>,
+}
+
+/// Partial update; `None` leaves a field unchanged.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerChanges {
+  pub name: Option<String>,
+  /// `null` clears the number; omitting the field keeps it.
+  #[serde(default, deserialize_with = "double_option")]
+  pub number: Option<Option<u32>>,
+  pub primary_position: Option<String>,
+  pub secondary_positions: Option<Vec<String>>,
+  pub bats: Option<String>,
+  pub throws: Option<String>,
+  pub status: Option<String>,
+  pub notes: Option<String>,
+}
+
+fn double_option<'de, D, T>(deserializer: D) -> std::result::Result<Option<Option<T>>, D::Error>
+where
+  D: Deserializer<'de>,
+  T: Deserialize<'de>,
+{
+  Option::<T>::deserialize(deserializer).map(Some)
+}
+
+const PLAYER_COLUMNS: &str = "id, team_id, name, number, primary_position, secondary_positions, \
+   bats, throws, status, notes, created_at, updated_at";
+
+fn player_from_row(row: &Row<'_>) -> rusqlite::Result<Player> {
+  let secondary: String = row.get(5)?;
+  Ok(Player {
+    id: row.get(0)?,
+    team_id: row.get(1)?,
+    name: row.get(2)?,
+    number: row.get(3)?,
+    primary_position: row.get(4)?,
+    secondary_positions: serde_json::from_str(&secondary).unwrap_or_default(),
+    bats: row.get(6)?,
+    throws: row.get(7)?,
+    status: row.get(8)?,
+    notes: row.get(9)?,
+    created_at: row.get(10)?,
+    updated_at: row.get(11)?,
+  })
+}
+
+/// Returns the id of the first team, creating the default team if needed.
+pub fn default_team_id(conn: &Connection) -> Result<String> {
+  let existing = conn
+    .query_row(
+      "SELECT id FROM teams ORDER BY created_at, rowid LIMIT 1",
+      [],
+      |row| row.get(0),
+    )
+    .optional()?;
+  if let Some(id) = existing {
+    return Ok(id);
+  }
+  let id = new_id();
+  conn.execute(
+    "INSERT INTO teams (id, name, created_at) VALUES (?1, ?2, ?3)",
+    params![id, DEFAULT_TEAM_NAME, timestamp()],
+  )?;
+  Ok(id)
+}
+
+pub fn team_name(conn: &Connection, team_id: &str) -> Result<String> {
+  conn
+    .query_row("SELECT name FROM teams WHERE id = ?1", [team_id], |row| {
+      row.get(0)
+    })
+    .optional()?
+    .ok_or_else(|| Error::NotFound(format!("Team {team_id}")))
+}
+
+pub fn list(conn: &Connection, team_id: &str) -> Result<Vec<Player>> {
+  let mut stmt = conn.prepare(&format!(
+    "SELECT {PLAYER_COLUMNS} FROM players WHERE team_id = ?1 \
+     ORDER BY number IS NULL, number, name COLLATE NOCASE"
+  ))?;
+  let players = stmt
+    .query_map([team_id], player_from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(players)
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<Player> {
+  conn
+    .query_row(
+      &format!("SELECT {PLAYER_COLUMNS} FROM players WHERE id = ?1"),
+      [id],
+      player_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| Error::NotFound(format!("Player {id}")))
+}
+
+pub fn create(conn: &Connection, input: NewPlayer) -> Result<Player> {
+  let team_id = match input.team_id {
+    Some(team_id) => {
+      ensure_team_exists(conn, &team_id)?;
+      team_id
+    }
+    None => default_team_id(conn)?,
+  };
+  let now = timestamp();
+  let player = Player {
+    id: new_id(),
+    team_id,
+    name: input.name.trim().to_string(),
+    number: input.number,
+    primary_position: input.primary_position,
+    secondary_positions: input.secondary_positions,
+    bats: input.bats,
+    throws: input.throws,
+    status: input.status.unwrap_or_else(|| "active".into()),
+    notes: input.notes.unwrap_or_default(),
+    created_at: now.clone(),
+    updated_at: now,
+  };
+  validate(&player)?;
+  insert(conn, &player)?;
+  audit::player_changed(conn, None, None, Some(&player))?;
+  Ok(player)
+}
+
+/// Writes a fully-formed player row, e.g. one restored from an import.
+pub fn insert(conn: &Connection, player: &Player) -> Result<()> {
+  conn.execute(
+    &format!("INSERT INTO players ({PLAYER_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)"),
+    params![
+      player.id,
+      player.team_id,
+      player.name,
+      player.number,
+      player.primary_position,
+      serde_json::to_string(&player.secondary_positions)?,
+      player.bats,
+      player.throws,
+      player.status,
+      player.notes,
+      player.created_at,
+      player.updated_at,
+    ],
+  )?;
+  Ok(())
+}
+
+/// Overwrites every field of an existing player, keeping the given
+/// `updated_at`, e.g. an edit received from a synced device.
+pub fn overwrite(conn: &Connection, player: &Player) -> Result<()> {
+  conn.execute(
+    "UPDATE players SET team_id = ?2, name = ?3, number = ?4, primary_position = ?5, \
+     secondary_positions = ?6, bats = ?7, throws = ?8, status = ?9, notes = ?10, \
+     updated_at = ?11 WHERE id = ?1",
+    params![
+      player.id,
+      player.team_id,
+      player.name,
+      player.number,
+      player.primary_position,
+      serde_json::to_string(&player.secondary_positions)?,
+      player.bats,
+      player.throws,
+      player.status,
+      player.notes,
+      player.updated_at,
+    ],
+  )?;
+  Ok(())
+}
+
+pub fn update(conn: &Connection, id: &str, changes: PlayerChanges) -> Result<Player> {
+  let before = get(conn, id)?;
+  let mut player = before.clone();
+  if let Some(name) = changes.name {
+    player.name = name.trim().to_string();
+  }
+  if let Some(number) = changes.number {
+    player.number = number;
+  }
+  if let Some(position) = changes.primary_position {
+    player.primary_position = position;
+  }
+  if let Some(positions) = changes.secondary_positions {
+    player.secondary_positions = positions;
+  }
+  if let Some(bats) = changes.bats {
+    player.bats = bats;
+  }
+  if let Some(throws) = changes.throws {
+    player.throws = throws;
+  }
+  if let Some(status) = changes.status {
+    player.status = status;
+  }
+  if let Some(notes) = changes.notes {
+    player.notes = notes;
+  }
+  validate(&player)?;
+  player.updated_at = timestamp();
+
+  conn.execute(
+    "UPDATE players SET name = ?2, number = ?3, primary_position = ?4, \
+     secondary_positions = ?5, bats = ?6, throws = ?7, status = ?8, notes = ?9, \
+     updated_at = ?10 WHERE id = ?1",
+    params![
+      player.id,
+      player.name,
+      player.number,
+      player.primary_position,
+      serde_json::to_string(&player.secondary_positions)?,
+      player.bats,
+      player.throws,
+      player.status,
+      player.notes,
+      player.updated_at,
+    ],
+  )?;
+  audit::player_changed(conn, None, Some(&before), Some(&player))?;
+  Ok(player)
+}
+
+pub fn delete(conn: &Connection, id: &str) -> Result<()> {
+  let before = get(conn, id)?;
+  delete_in(conn, id)?;
+  audit::player_changed(conn, None, Some(&before), None)
+}
+
+/// Deletes a player without auditing it, for callers that record the
+/// change themselves.
+pub(crate) fn delete_in(conn: &Connection, id: &str) -> Result<()> {
+  let removed = conn.execute("DELETE FROM players WHERE id = ?1", [id])?;
+  if removed == 0 {
+    return Err(Error::NotFound(format!("Player {id}")));
+  }
+  Ok(())
+}
+
+fn ensure_team_exists(conn: &Connection, team_id: &str) -> Result<()> {
+  let exists: bool = conn.query_row(
+    "SELECT EXISTS(SELECT 1 FROM teams WHERE id = ?1)",
+    [team_id],
+    |row| row.get(0),
+  )?;
+  if !exists {
+    return Err(Error::NotFound(format!("Team {team_id}")));
+  }
+  Ok(())
+}
+
+/// Applies the same rules as the backend's `PlayerCreate` model.
+pub(crate) fn validate(player: &Player) -> Result<()> {
+  let name_len = player.name.chars().count();
+  if !(2..=50).contains(&name_len) {
+    return Err(Error::Validation(
+      "Name must be between 2 and 50 characters".into(),
+    ));
+  }
+  if let Some(number) = player.number {
+    if !(1..=99).contains(&number) {
+      return Err(Error::Validation(
+        "Jersey number must be between 1 and 99".into(),
+      ));
+    }
+  }
+  if !POSITIONS.contains(&player.primary_position.as_str()) {
+    return Err(Error::Validation(format!(
+      "Primary position must be one of: {}",
+      POSITIONS.join(", ")
+    )));
+  }
+  if let Some(pos) = player
+    .secondary_positions
+    .iter()
+    .find(|pos| !POSITIONS.contains(&pos.as_str()))
+  {
+    return Err(Error::Validation(format!(
+      "Invalid secondary position \"{pos}\". Must be one of: {}",
+      POSITIONS.join(", ")
+    )));
+  }
+  if !BATS.contains(&player.bats.as_str()) {
+    return Err(Error::Validation(format!(
+      "Bats must be one of: {}",
+      BATS.join(", ")
+    )));
+  }
+  if !THROWS.contains(&player.throws.as_str()) {
+    return Err(Error::Validation(format!(
+      "Throws must be one of: {}",
+      THROWS.join(", ")
+    )));
+  }
+  if !STATUSES.contains(&player.status.as_str()) {
+    return Err(Error::Validation(format!(
+      "Status must be one of: {}",
+      STATUSES.join(", ")
+    )));
+  }
+  Ok(())
+}
+
+#[tauri::command]
+pub fn create_player(
+  db: State<'_, Database>,
+  sync: State<'_, SyncState>,
+  player: NewPlayer,
+) -> Result<Player> {
+  let player = create(&db.conn(), player)?;
+  sync.broadcast(Change::PlayerSaved { player: player.clone() });
+  Ok(player)
+}
+
+#[tauri::command]
+pub fn update_player(
+  db: State<'_, Database>,
+  sync: State<'_, SyncState>,
+  id: String,
+  changes: PlayerChanges,
+) -> Result<Player> {
+  let player = update(&db.conn(), &id, changes)?;
+  sync.broadcast(Change::PlayerSaved { player: player.clone() });
+  Ok(player)
+}
+
+/// Lists a team's players, defaulting to the first team.
+#[tauri::command]
+pub fn list_roster(db: State<'_, Database>, team_id: Option<String>) -> Result<Vec<Player>> {
+  let conn = db.conn();
+  let team_id = match team_id {
+    Some(team_id) => team_id,
+    None => default_team_id(&conn)?,
+  };
+  list(&conn, &team_id)
+}
+
+#[tauri::command]
+pub fn delete_player(
+  db: State<'_, Database>,
+  sync: State<'_, SyncState>,
+  id: String,
+) -> Result<()> {
+  delete(&db.conn(), &id)?;
+  sync.broadcast(Change::PlayerDeleted { id });
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+
+  fn new_player(name: &str, number: Option<u32>) -> NewPlayer {
+    NewPlayer {
+      team_id: None,
+      name: name.into(),
+      number,
+      primary_position: "SS".into(),
+      secondary_positions: vec!["2B".into()],
+      bats: "R".into(),
+      throws: "R".into(),
+      status: None,
+      notes: None,
+    }
+  }
+
+  #[test]
+  fn creates_players_on_the_default_team() {
+    let conn = open_in_memory();
+    let first = create(&conn, new_player("Jake Miller", Some(12))).unwrap();
+    let second = create(&conn, new_player("Ava Chen", Some(3))).unwrap();
+
+    assert_eq!(first.team_id, second.team_id);
+    let roster = list(&conn, &first.team_id).unwrap();
+    let names: Vec<_> = roster.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Ava Chen", "Jake Miller"]);
+    assert_eq!(roster[0].secondary_positions, ["2B"]);
+  }
+
+  #[test]
+  fn rejects_invalid_players() {
+    let conn = open_in_memory();
+    assert!(matches!(
+      create(&conn, new_player("J", None)),
+      Err(Error::Validation(_))
+    ));
+    assert!(matches!(
+      create(&conn, new_player("Jake Miller", Some(100))),
+      Err(Error::Validation(_))
+    ));
+    let mut bad_position = new_player("Jake Miller", None);
+    bad_position.primary_position = "XX".into();
+    assert!(matches!(
+      create(&conn, bad_position),
+      Err(Error::Validation(_))
+    ));
+  }
+
+  #[test]
+  fn updates_and_deletes_players() {
+    let conn = open_in_memory();
+    let player = create(&conn, new_player("Jake Miller", Some(12))).unwrap();
+
+    let updated = update(
+      &conn,
+      &player.id,
+      PlayerChanges {
+        number: Some(None),
+        status: Some("inactive".into()),
+        ..Default::default()
+      },
+    )
+    .unwrap();
+    assert_eq!(updated.number, None);
+    assert_eq!(updated.status, "inactive");
+    assert_eq!(updated.name, "Jake Miller");
+
+    delete(&conn, &player.id).unwrap();
+    assert!(matches!(get(&conn, &player.id), Err(Error::NotFound(_))));
+    assert!(matches!(delete(&conn, &player.id), Err(Error::NotFound(_))));
+  }
+}
@@ -0,0 +1,387 @@
+//! Roster import from CSV files exported by league sites and spreadsheets.
+//!
+//! Importing is a two-step flow: the first call parses the file and returns
+//! a preview with the suggested column mapping and per-row problems; the
+//! frontend then calls again with `commit` (and optionally an edited
+//! mapping) to insert the accepted rows.
+
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::PathBuf;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::roster::{self, NewPlayer, Player, POSITIONS};
+use super::{timestamp, Database};
+use crate::error::Result;
+use crate::sync::SyncState;
+
+/// Zero-based column index for each player field, `None` when the file
+/// has no such column.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnMapping {
+  pub name: Option<usize>,
+  pub first_name: Option<usize>,
+  pub last_name: Option<usize>,
+  pub number: Option<usize>,
+  pub primary_position: Option<usize>,
+  pub secondary_positions: Option<usize>,
+  pub bats: Option<usize>,
+  pub throws: Option<usize>,
+  pub status: Option<usize>,
+  pub notes: Option<usize>,
+}
+
+impl ColumnMapping {
+  /// Guesses the mapping from common header spellings.
+  pub fn suggest(headers: &[String]) -> Self {
+    let find = |aliases: &[&str]| {
+      headers
+        .iter()
+        .position(|h| aliases.contains(&normalize_header(h).as_str()))
+    };
+    Self {
+      name: find(&["name", "player", "playername", "fullname"]),
+      first_name: find(&["first", "firstname", "givenname"]),
+      last_name: find(&["last", "lastname", "surname", "familyname"]),
+      number: find(&["number", "no", "num", "#", "jersey", "jerseynumber", "jerseyno"]),
+      primary_position: find(&["position", "positions", "pos", "primaryposition", "primary"]),
+      secondary_positions: find(&["secondary", "secondarypositions", "otherpositions"]),
+      bats: find(&["bats", "bat", "b"]),
+      throws: find(&["throws", "throw", "t"]),
+      status: find(&["status"]),
+      notes: find(&["notes", "note", "comments"]),
+    }
+  }
+
+  fn has_name(&self) -> bool {
+    self.name.is_some() || self.first_name.is_some() || self.last_name.is_some()
+  }
+}
+
+fn normalize_header(header: &str) -> String {
+  header
+    .chars()
+    .filter(|c| c.is_alphanumeric() || *c == '#')
+    .flat_map(char::to_lowercase)
+    .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptedRow {
+  /// 1-based line in the file, counting the header.
+  pub line: usize,
+  pub name: String,
+  pub number: Option<u32>,
+  pub primary_position: String,
+  pub secondary_positions: Vec<String>,
+  pub bats: String,
+  pub throws: String,
+  pub status: String,
+  pub notes: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedRow {
+  pub line: usize,
+  pub values: Vec<String>,
+  pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+  pub headers: Vec<String>,
+  /// The mapping used for this preview: the caller's, or the suggested one.
+  pub mapping: ColumnMapping,
+  pub accepted: Vec<AcceptedRow>,
+  pub rejected: Vec<RejectedRow>,
+  /// Players inserted; empty unless the import was committed.
+  pub imported: Vec<Player>,
+}
+
+/// Parses `input` and checks every row against the validation rules and
+/// the players already on `existing`.
+pub fn preview<R: Read>(
+  input: R,
+  mapping: Option<ColumnMapping>,
+  existing: &[Player],
+) -> Result<ImportPreview> {
+  let mut reader = csv::ReaderBuilder::new()
+    .flexible(true)
+    .trim(csv::Trim::All)
+    .from_reader(input);
+  let headers: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+  let mapping = mapping.unwrap_or_else(|| ColumnMapping::suggest(&headers));
+
+  let mut names: HashSet<String> = existing.iter().map(|p| p.name.to_lowercase()).collect();
+  let mut numbers: HashSet<u32> = existing.iter().filter_map(|p| p.number).collect();
+  let mut accepted = Vec::new();
+  let mut rejected = Vec::new();
+
+  for (index, record) in reader.records().enumerate() {
+    let line = index + 2;
+    let record = record?;
+    if record.iter().all(str::is_empty) {
+      continue;
+    }
+    let values: Vec<String> = record.iter().map(str::to_string).collect();
+    match parse_row(&values, &mapping, line) {
+      Ok(row) => {
+        let mut errors = Vec::new();
+        if !names.insert(row.name.to_lowercase()) {
+          errors.push(format!("Duplicate player name \"{}\"", row.name));
+        }
+        if let Some(number) = row.number {
+          if !numbers.insert(number) {
+            errors.push(format!("Jersey number {number} is already taken"));
+          }
+        }
+        if errors.is_empty() {
+          accepted.push(row);
+        } else {
+          rejected.push(RejectedRow { line, values, errors });
+        }
+      }
+      Err(errors) => rejected.push(RejectedRow { line, values, errors }),
+    }
+  }
+
+  Ok(ImportPreview {
+    headers,
+    mapping,
+    accepted,
+    rejected,
+    imported: Vec::new(),
+  })
+}
+
+fn parse_row(
+  values: &[String],
+  mapping: &ColumnMapping,
+  line: usize,
+) -> std::result::Result<AcceptedRow, Vec<String>> {
+  let cell = |column: Option<usize>| {
+    column
+      .and_then(|i| values.get(i))
+      .map(String::as_str)
+      .unwrap_or("")
+  };
+  let mut errors = Vec::new();
+
+  let name = match mapping.name {
+    Some(_) => cell(mapping.name).to_string(),
+    None => format!("{} {}", cell(mapping.first_name), cell(mapping.last_name))
+      .trim()
+      .to_string(),
+  };
+  if !mapping.has_name() {
+    errors.push("No name column is mapped".to_string());
+  }
+
+  let number = match cell(mapping.number).trim_start_matches('#') {
+    "" => None,
+    raw => match raw.parse::<u32>() {
+      Ok(number) => Some(number),
+      Err(_) => {
+        errors.push(format!("Jersey number \"{raw}\" is not a whole number"));
+        None
+      }
+    },
+  };
+
+  let mut positions = split_positions(cell(mapping.primary_position));
+  let primary_position = if positions.is_empty() {
+    String::new()
+  } else {
+    positions.remove(0)
+  };
+  // "SS/2B" in a single position column lists the secondaries too.
+  let secondary_positions = match mapping.secondary_positions {
+    Some(_) => split_positions(cell(mapping.secondary_positions)),
+    None => positions,
+  };
+
+  let player = Player {
+    id: String::new(),
+    team_id: String::new(),
+    name,
+    number,
+    primary_position,
+    secondary_positions,
+    bats: hand(cell(mapping.bats)),
+    throws: hand(cell(mapping.throws)),
+    status: match cell(mapping.status) {
+      "" => "active".to_string(),
+      status => status.to_lowercase(),
+    },
+    notes: cell(mapping.notes).to_string(),
+    created_at: timestamp(),
+    updated_at: String::new(),
+  };
+  if let Err(e) = roster::validate(&player) {
+    errors.push(e.to_string());
+  }
+  if !errors.is_empty() {
+    return Err(errors);
+  }
+
+  Ok(AcceptedRow {
+    line,
+    name: player.name,
+    number: player.number,
+    primary_position: player.primary_position,
+    secondary_positions: player.secondary_positions,
+    bats: player.bats,
+    throws: player.throws,
+    status: player.status,
+    notes: player.notes,
+  })
+}
+
+fn split_positions(raw: &str) -> Vec<String> {
+  raw
+    .split(|c: char| c == ',' || c == '/' || c == ';' || c.is_whitespace())
+    .filter(|s| !s.is_empty())
+    .map(|s| {
+      let code = s.to_uppercase();
+      // Some rosters use "1" and "2" for pitcher and catcher.
+      match code.as_str() {
+        "1" => "P".to_string(),
+        "2" => "C".to_string(),
+        _ if POSITIONS.contains(&code.as_str()) => code,
+        _ => s.to_string(),
+      }
+    })
+    .collect()
+}
+
+/// Maps "Right", "left", "Switch" and friends to the single-letter codes.
+/// Blank cells default to right-handed.
+fn hand(raw: &str) -> String {
+  match raw.to_lowercase().as_str() {
+    "" | "r" | "right" => "R".to_string(),
+    "l" | "left" => "L".to_string(),
+    "s" | "switch" | "b" | "both" => "S".to_string(),
+    _ => raw.to_string(),
+  }
+}
+
+/// Inserts the accepted rows of a preview onto `team_id` in one transaction.
+pub fn commit(conn: &mut Connection, team_id: &str, rows: &[AcceptedRow]) -> Result<Vec<Player>> {
+  let tx = conn.transaction()?;
+  let mut imported = Vec::with_capacity(rows.len());
+  for row in rows {
+    imported.push(roster::create(
+      &tx,
+      NewPlayer {
+        team_id: Some(team_id.to_string()),
+        name: row.name.clone(),
+        number: row.number,
+        primary_position: row.primary_position.clone(),
+        secondary_positions: row.secondary_positions.clone(),
+        bats: row.bats.clone(),
+        throws: row.throws.clone(),
+        status: Some(row.status.clone()),
+        notes: Some(row.notes.clone()),
+      },
+    )?);
+  }
+  tx.commit()?;
+  Ok(imported)
+}
+
+/// Previews a CSV roster import, or inserts the accepted rows when
+/// `commit` is set. Rows with errors are never imported.
+#[tauri::command]
+pub fn import_roster_csv(
+  db: State<'_, Database>,
+  sync: State<'_, SyncState>,
+  path: PathBuf,
+  mapping: Option<ColumnMapping>,
+  team_id: Option<String>,
+  commit: Option<bool>,
+) -> Result<ImportPreview> {
+  let file = std::fs::File::open(&path)?;
+  let mut conn = db.conn();
+  let team_id = match team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(&conn)?,
+  };
+  let existing = roster::list(&conn, &team_id)?;
+  let mut preview = preview(file, mapping, &existing)?;
+  if commit.unwrap_or(false) {
+    preview.imported = self::commit(&mut conn, &team_id, &preview.accepted)?;
+    sync.resend_snapshot();
+  }
+  Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+
+  const ROSTER: &str = "\
+First,Last,#,Pos,Bats,Throws
+Jake,Miller,12,SS/2B,Right,R
+Ava,Chen,3,cf,L,Left
+Sam,Ortiz,12,P,R,R
+Jake,Miller,7,C,R,R
+Lee,Park,x,XX,R,R
+";
+
+  #[test]
+  fn suggests_a_mapping_from_headers() {
+    let headers: Vec<String> = ["First", "Last", "#", "Pos", "Bats", "Throws"]
+      .map(String::from)
+      .into();
+    let mapping = ColumnMapping::suggest(&headers);
+    assert_eq!(mapping.first_name, Some(0));
+    assert_eq!(mapping.last_name, Some(1));
+    assert_eq!(mapping.number, Some(2));
+    assert_eq!(mapping.primary_position, Some(3));
+    assert_eq!(mapping.name, None);
+  }
+
+  #[test]
+  fn previews_rows_and_reports_errors() {
+    let preview = preview(ROSTER.as_bytes(), None, &[]).unwrap();
+
+    let names: Vec<_> = preview.accepted.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["Jake Miller", "Ava Chen"]);
+    assert_eq!(preview.accepted[0].secondary_positions, ["2B"]);
+    assert_eq!(preview.accepted[1].primary_position, "CF");
+    assert_eq!(preview.accepted[1].throws, "L");
+
+    let lines: Vec<_> = preview.rejected.iter().map(|r| r.line).collect();
+    assert_eq!(lines, [4, 5, 6]);
+    assert!(preview.rejected[0].errors[0].contains("Jersey number 12"));
+    assert!(preview.rejected[1].errors[0].contains("Duplicate player name"));
+    assert_eq!(preview.rejected[2].errors.len(), 2);
+  }
+
+  #[test]
+  fn commits_accepted_rows_against_the_existing_roster() {
+    let mut conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let existing = commit(
+      &mut conn,
+      &team_id,
+      &preview("Name,Number,Position\nAva Chen,3,CF\n".as_bytes(), None, &[])
+        .unwrap()
+        .accepted,
+    )
+    .unwrap();
+
+    let preview = preview(ROSTER.as_bytes(), None, &existing).unwrap();
+    assert_eq!(preview.accepted.len(), 1);
+    commit(&mut conn, &team_id, &preview.accepted).unwrap();
+    assert_eq!(roster::list(&conn, &team_id).unwrap().len(), 2);
+  }
+}
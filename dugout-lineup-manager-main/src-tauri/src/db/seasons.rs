@@ -0,0 +1,326 @@
+//! Archiving finished seasons, for coaches with years of history.
+//!
+//! `archive_season` moves a season's games and everything hanging off them
+//! (box scores, pitch counts, scorebook entries and game lineups) into a
+//! single compressed row of `season_archives`, so the schedule, stats and
+//! search no longer wade through them. `restore_season` puts them back as
+//! they were. A season is the calendar year of its games' dates.
+
+use std::collections::{BTreeMap, HashSet};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use tauri::State;
+
+use super::{roster, timestamp, Database};
+use crate::error::{Error, Result};
+use crate::json_archive::{self, Row};
+
+/// A season's games, as an SQL condition on `?1` (the team) and `?2` (the
+/// year).
+const SEASON_GAMES: &str = "SELECT id FROM games WHERE team_id = ?1 AND substr(date, 1, 4) = ?2";
+
+/// The archived tables, parents first, with the condition picking out a
+/// season's rows.
+fn season_tables() -> [(&'static str, String); 7] {
+  let lineups = format!("SELECT id FROM lineups WHERE game_id IN ({SEASON_GAMES})");
+  [
+    ("games", "team_id = ?1 AND substr(date, 1, 4) = ?2".into()),
+    ("game_stats", format!("game_id IN ({SEASON_GAMES})")),
+    // Not tied to a game row, as the game may only be in the backend's schedule
+    (
+      "pitch_counts",
+      "player_id IN (SELECT id FROM players WHERE team_id = ?1) AND substr(date, 1, 4) = ?2"
+        .into(),
+    ),
+    ("scorebook_events", format!("game_id IN ({SEASON_GAMES})")),
+    ("lineups", format!("game_id IN ({SEASON_GAMES})")),
+    ("lineup_slots", format!("lineup_id IN ({lineups})")),
+    ("lineup_positions", format!("lineup_id IN ({lineups})")),
+  ]
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonArchive {
+  pub team_id: String,
+  /// The year, e.g. `2024`.
+  pub season: String,
+  pub games: u32,
+  pub archived_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredSeason {
+  pub season: String,
+  pub games: u32,
+  /// Box scores, pitch counts and fielding assignments left out because
+  /// their player has since been deleted.
+  pub skipped: u32,
+}
+
+fn validate_season(season: &str) -> Result<()> {
+  if season.len() != 4 || !season.chars().all(|c| c.is_ascii_digit()) {
+    return Err(Error::Validation(format!("Season must be a year, not {season:?}")));
+  }
+  Ok(())
+}
+
+fn encode(data: &BTreeMap<String, Vec<Row>>) -> Result<String> {
+  let json = serde_json::to_vec(data)?;
+  Ok(STANDARD.encode(miniz_oxide::deflate::compress_to_vec(&json, 9)))
+}
+
+fn decode(data: &str) -> Result<BTreeMap<String, Vec<Row>>> {
+  let corrupt = || Error::Validation("The archived season is damaged".into());
+  let deflated = STANDARD.decode(data).map_err(|_| corrupt())?;
+  let json = miniz_oxide::inflate::decompress_to_vec(&deflated).map_err(|_| corrupt())?;
+  Ok(serde_json::from_slice(&json)?)
+}
+
+pub fn list(conn: &Connection, team_id: &str) -> Result<Vec<SeasonArchive>> {
+  let mut stmt = conn.prepare(
+    "SELECT team_id, season, games, archived_at FROM season_archives \
+     WHERE team_id = ?1 ORDER BY season DESC",
+  )?;
+  let archives = stmt
+    .query_map([team_id], |row| {
+      Ok(SeasonArchive {
+        team_id: row.get(0)?,
+        season: row.get(1)?,
+        games: row.get(2)?,
+        archived_at: row.get(3)?,
+      })
+    })?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(archives)
+}
+
+/// Moves `team_id`'s games in `season` into the archive, all or nothing.
+/// Refused while any of them are still to be played.
+pub fn archive(conn: &mut Connection, team_id: &str, season: &str) -> Result<SeasonArchive> {
+  validate_season(season)?;
+  let tx = conn.transaction()?;
+  let archived = tx
+    .query_row(
+      "SELECT 1 FROM season_archives WHERE team_id = ?1 AND season = ?2",
+      params![team_id, season],
+      |_| Ok(()),
+    )
+    .optional()?;
+  if archived.is_some() {
+    return Err(Error::Validation(format!(
+      "The {season} season is already archived; restore it first to add to it"
+    )));
+  }
+  let (games, last): (u32, Option<String>) = tx.query_row(
+    "SELECT COUNT(*), MAX(date) FROM games WHERE team_id = ?1 AND substr(date, 1, 4) = ?2",
+    params![team_id, season],
+    |row| Ok((row.get(0)?, row.get(1)?)),
+  )?;
+  let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+  match last {
+    None => return Err(Error::NotFound(format!("Games in {season}"))),
+    Some(last) if last >= today => {
+      return Err(Error::Validation(format!(
+        "The {season} season still has games to play"
+      )))
+    }
+    Some(_) => {}
+  }
+
+  let tables = season_tables();
+  let mut data = BTreeMap::new();
+  for (name, filter) in &tables {
+    let table = json_archive::table(name);
+    let rows = json_archive::read_rows(&tx, table, filter, params![team_id, season])?;
+    data.insert(name.to_string(), rows);
+  }
+  // Children first, while the rows their conditions look at still exist
+  tx.execute(
+    &format!(
+      "DELETE FROM undo_log WHERE lineup_id IN \
+       (SELECT id FROM lineups WHERE game_id IN ({SEASON_GAMES}))"
+    ),
+    params![team_id, season],
+  )?;
+  for (name, filter) in tables.iter().rev() {
+    tx.execute(&format!("DELETE FROM {name} WHERE {filter}"), params![team_id, season])?;
+  }
+
+  let archive = SeasonArchive {
+    team_id: team_id.to_string(),
+    season: season.to_string(),
+    games,
+    archived_at: timestamp(),
+  };
+  tx.execute(
+    "INSERT INTO season_archives (team_id, season, games, data, archived_at) \
+     VALUES (?1, ?2, ?3, ?4, ?5)",
+    params![team_id, season, games, encode(&data)?, archive.archived_at],
+  )?;
+  tx.commit()?;
+  Ok(archive)
+}
+
+/// Puts an archived season back in the live tables, all or nothing.
+pub fn restore(conn: &mut Connection, team_id: &str, season: &str) -> Result<RestoredSeason> {
+  validate_season(season)?;
+  let tx = conn.transaction()?;
+  let (games, data): (u32, String) = tx
+    .query_row(
+      "SELECT games, data FROM season_archives WHERE team_id = ?1 AND season = ?2",
+      params![team_id, season],
+      |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()?
+    .ok_or_else(|| Error::NotFound(format!("Archived {season} season")))?;
+  let mut data = decode(&data)?;
+
+  let players: HashSet<String> = {
+    let mut stmt = tx.prepare("SELECT id FROM players")?;
+    let ids = stmt.query_map([], |row| row.get(0))?;
+    ids.collect::<rusqlite::Result<_>>()?
+  };
+  let exists = |row: &Row| row["playerId"].as_str().is_some_and(|id| players.contains(id));
+  let mut skipped = 0;
+  for name in ["game_stats", "pitch_counts", "lineup_positions"] {
+    if let Some(rows) = data.get_mut(name) {
+      let before = rows.len();
+      rows.retain(exists);
+      skipped += (before - rows.len()) as u32;
+    }
+  }
+  // A deleted player's batting slot stays, empty, as deleting them does live
+  for slot in data.get_mut("lineup_slots").into_iter().flatten() {
+    if !slot["playerId"].is_null() && !exists(slot) {
+      slot.insert("playerId".into(), serde_json::Value::Null);
+    }
+  }
+
+  for (name, _) in season_tables() {
+    let rows = data.get(name).map(Vec::as_slice).unwrap_or_default();
+    json_archive::insert_rows(&tx, json_archive::table(name), rows).map_err(|e| match e {
+      // Most likely the same games were imported again since
+      Error::Validation(_) => Error::Validation(format!(
+        "The {season} season can't be restored over games that are back in the schedule: {e}"
+      )),
+      e => e,
+    })?;
+  }
+  tx.execute(
+    "DELETE FROM season_archives WHERE team_id = ?1 AND season = ?2",
+    params![team_id, season],
+  )?;
+  tx.commit()?;
+  Ok(RestoredSeason {
+    season: season.to_string(),
+    games,
+    skipped,
+  })
+}
+
+fn team_or_default(conn: &Connection, team_id: Option<String>) -> Result<String> {
+  match team_id {
+    Some(team_id) => Ok(team_id),
+    None => roster::default_team_id(conn),
+  }
+}
+
+#[tauri::command]
+pub fn list_archived_seasons(
+  db: State<'_, Database>,
+  team_id: Option<String>,
+) -> Result<Vec<SeasonArchive>> {
+  let conn = db.conn();
+  let team_id = team_or_default(&conn, team_id)?;
+  list(&conn, &team_id)
+}
+
+/// Archives a finished season, defaulting to the first team. Its games and
+/// stats are left out of everything until it's restored.
+#[tauri::command]
+pub fn archive_season(
+  db: State<'_, Database>,
+  team_id: Option<String>,
+  season: String,
+) -> Result<SeasonArchive> {
+  let mut conn = db.conn();
+  let team_id = team_or_default(&conn, team_id)?;
+  let archive = archive(&mut conn, &team_id, &season)?;
+  log::info!("Archived the {} season ({} games)", season, archive.games);
+  Ok(archive)
+}
+
+#[tauri::command]
+pub fn restore_season(
+  db: State<'_, Database>,
+  team_id: Option<String>,
+  season: String,
+) -> Result<RestoredSeason> {
+  let mut conn = db.conn();
+  let team_id = team_or_default(&conn, team_id)?;
+  let restored = restore(&mut conn, &team_id, &season)?;
+  log::info!("Restored the {} season ({} games)", season, restored.games);
+  Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn archives_and_restores_a_season() {
+    let mut conn = crate::db::open_in_memory();
+    conn
+      .execute_batch(
+        "INSERT INTO teams (id, name, created_at) VALUES ('t2', 'Eagles', 'now');
+         INSERT INTO players (id, team_id, name, primary_position, bats, throws, created_at,
+           updated_at)
+         VALUES ('p1', 't2', 'Jake Miller', 'SS', 'R', 'R', 'now', 'now'),
+                ('p2', 't2', 'Ava Chen', 'P', 'R', 'R', 'now', 'now');
+         INSERT INTO games (id, team_id, date, opponent, created_at, updated_at)
+         VALUES ('g1', 't2', '2024-04-11', 'Tigers', 'now', 'now'),
+                ('g2', 't2', '2099-04-11', 'Cubs', 'now', 'now');
+         INSERT INTO game_stats (game_id, player_id, ab, h) VALUES ('g1', 'p1', 3, 2),
+           ('g1', 'p2', 2, 0), ('g2', 'p1', 4, 1);
+         INSERT INTO pitch_counts (player_id, game_id, date, pitches, updated_at)
+         VALUES ('p2', 'g1', '2024-04-11', 45, 'now');
+         INSERT INTO lineups (id, team_id, name, game_id, created_at, updated_at)
+         VALUES ('l1', 't2', 'Game 1', 'g1', 'now', 'now');
+         INSERT INTO lineup_slots (lineup_id, slot_number, player_id, position)
+         VALUES ('l1', 1, 'p1', 'SS'), ('l1', 2, 'p2', 'P');",
+      )
+      .unwrap();
+    let count = |conn: &Connection, sql: &str| -> u32 {
+      conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    };
+
+    assert!(archive(&mut conn, "t2", "2099").is_err());
+    assert!(matches!(archive(&mut conn, "t2", "2023"), Err(Error::NotFound(_))));
+    let archived = archive(&mut conn, "t2", "2024").unwrap();
+    assert_eq!(archived.games, 1);
+    assert!(archive(&mut conn, "t2", "2024").is_err());
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM games"), 1);
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM game_stats"), 1);
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM pitch_counts"), 0);
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM lineup_slots"), 0);
+    assert_eq!(list(&conn, "t2").unwrap(), [archived]);
+
+    // Ava's box score and pitch count go with her; her batting slot stays
+    conn.execute("DELETE FROM players WHERE id = 'p2'", []).unwrap();
+    let restored = restore(&mut conn, "t2", "2024").unwrap();
+    assert_eq!((restored.games, restored.skipped), (1, 2));
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM games"), 2);
+    assert_eq!(count(&conn, "SELECT h FROM game_stats WHERE game_id = 'g1'"), 2);
+    assert_eq!(
+      count(&conn, "SELECT COUNT(*) FROM lineup_slots WHERE player_id IS NULL"),
+      1
+    );
+    assert!(list(&conn, "t2").unwrap().is_empty());
+    assert!(restore(&mut conn, "t2", "2024").is_err());
+  }
+}
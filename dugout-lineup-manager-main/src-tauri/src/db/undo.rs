@@ -0,0 +1,294 @@
+//! Undo and redo for the coach's lineup edits.
+//!
+//! Every save or delete made through [`lineups`] writes the lineup as it was
+//! before and after to `undo_log`, so the history survives a restart: a
+//! lineup cleared by accident just before the game can still be brought
+//! back. Undoing restores the `before` copy and redoing the `after` one.
+//! Edits that arrive from a synced device aren't journaled, since they
+//! weren't made here.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use tauri::State;
+
+use super::lineups::{self, Lineup};
+use super::{audit, timestamp, versions, Database};
+use crate::error::{Error, Result};
+use crate::sync::{Change, SyncState};
+
+/// Entries kept; the oldest are dropped past this.
+const MAX_ENTRIES: i64 = 100;
+
+/// Entries [`get_history`] returns when no limit is given.
+const DEFAULT_HISTORY: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Action {
+  SaveLineup,
+  DeleteLineup,
+}
+
+impl Action {
+  fn as_str(self) -> &'static str {
+    match self {
+      Self::SaveLineup => "save_lineup",
+      Self::DeleteLineup => "delete_lineup",
+    }
+  }
+
+  fn parse(value: &str) -> Self {
+    match value {
+      "delete_lineup" => Self::DeleteLineup,
+      _ => Self::SaveLineup,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+  Undo,
+  Redo,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+  pub id: i64,
+  pub action: Action,
+  pub lineup_id: String,
+  /// The lineup's name at the time, for labels like "Undo save of vs Tigers".
+  pub lineup_name: String,
+  /// Undone and waiting to be redone.
+  pub undone: bool,
+  pub created_at: String,
+}
+
+const ENTRY_COLUMNS: &str = "id, action, lineup_id, lineup_name, undone, created_at";
+
+fn entry_from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+  Ok(HistoryEntry {
+    id: row.get(0)?,
+    action: Action::parse(&row.get::<_, String>(1)?),
+    lineup_id: row.get(2)?,
+    lineup_name: row.get(3)?,
+    undone: row.get(4)?,
+    created_at: row.get(5)?,
+  })
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct History {
+  /// Newest first, undone entries included.
+  pub entries: Vec<HistoryEntry>,
+  pub can_undo: bool,
+  pub can_redo: bool,
+}
+
+/// What an undo or redo did.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoStep {
+  pub entry: HistoryEntry,
+  /// The lineup as it is now; `None` if the step deleted it.
+  pub lineup: Option<Lineup>,
+}
+
+/// Journals an edit inside the transaction that makes it. A new edit
+/// discards anything waiting to be redone.
+pub(crate) fn record(
+  tx: &Connection,
+  action: Action,
+  before: Option<&Lineup>,
+  after: Option<&Lineup>,
+) -> Result<()> {
+  let Some(lineup) = after.or(before) else {
+    return Ok(());
+  };
+  let before = before.map(serde_json::to_string).transpose()?;
+  let after = after.map(serde_json::to_string).transpose()?;
+  tx.execute("DELETE FROM undo_log WHERE undone = 1", [])?;
+  tx.execute(
+    "INSERT INTO undo_log (action, lineup_id, lineup_name, before, after, created_at) \
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    params![action.as_str(), lineup.id, lineup.name, before, after, timestamp()],
+  )?;
+  tx.execute(
+    "DELETE FROM undo_log WHERE id <= last_insert_rowid() - ?1",
+    [MAX_ENTRIES],
+  )?;
+  Ok(())
+}
+
+pub fn history(conn: &Connection, limit: u32) -> Result<History> {
+  let entries = conn
+    .prepare(&format!(
+      "SELECT {ENTRY_COLUMNS} FROM undo_log ORDER BY id DESC LIMIT ?1"
+    ))?
+    .query_map([limit], entry_from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  let (can_undo, can_redo) = conn.query_row(
+    "SELECT EXISTS(SELECT 1 FROM undo_log WHERE undone = 0), \
+     EXISTS(SELECT 1 FROM undo_log WHERE undone = 1)",
+    [],
+    |row| Ok((row.get(0)?, row.get(1)?)),
+  )?;
+  Ok(History {
+    entries,
+    can_undo,
+    can_redo,
+  })
+}
+
+/// Undoes the newest edit not yet undone, or redoes the oldest one that
+/// was. `None` when there is nothing to do.
+pub fn step(conn: &mut Connection, direction: Direction) -> Result<Option<UndoStep>> {
+  let tx = conn.transaction()?;
+  let (order, undone, copy) = match direction {
+    Direction::Undo => ("DESC", false, "before"),
+    Direction::Redo => ("ASC", true, "after"),
+  };
+  let found = tx
+    .query_row(
+      &format!(
+        "SELECT {ENTRY_COLUMNS}, {copy} FROM undo_log WHERE undone = ?1 \
+         ORDER BY id {order} LIMIT 1"
+      ),
+      [undone],
+      |row| Ok((entry_from_row(row)?, row.get::<_, Option<String>>(6)?)),
+    )
+    .optional()?;
+  let Some((mut entry, copy)) = found else {
+    return Ok(None);
+  };
+  let current = match lineups::get(&tx, &entry.lineup_id) {
+    Ok(lineup) => Some(lineup),
+    Err(Error::NotFound(_)) => None,
+    Err(e) => return Err(e),
+  };
+
+  let lineup = match copy {
+    Some(json) => {
+      let mut lineup: Lineup = serde_json::from_str(&json)?;
+      // A new save as far as synced devices go, so they take it too
+      lineup.updated_at = timestamp();
+      lineup.versions = current.as_ref().map(|c| c.versions.clone()).unwrap_or_default();
+      versions::bump(&tx, &mut lineup.versions)?;
+      lineups::write(&tx, &lineup)?;
+      Some(lineups::get(&tx, &lineup.id)?)
+    }
+    None => {
+      if current.is_some() {
+        lineups::delete_in(&tx, &entry.lineup_id)?;
+      }
+      None
+    }
+  };
+  audit::lineup_changed(&tx, None, current.as_ref(), lineup.as_ref())?;
+  entry.undone = direction == Direction::Undo;
+  tx.execute(
+    "UPDATE undo_log SET undone = ?2 WHERE id = ?1",
+    params![entry.id, entry.undone],
+  )?;
+  tx.commit()?;
+  Ok(Some(UndoStep { entry, lineup }))
+}
+
+fn run(db: &Database, sync: &SyncState, direction: Direction) -> Result<Option<UndoStep>> {
+  let step = step(&mut db.conn(), direction)?;
+  if let Some(step) = &step {
+    sync.broadcast(match &step.lineup {
+      Some(lineup) => Change::LineupSaved {
+        lineup: lineup.clone(),
+      },
+      None => Change::LineupDeleted {
+        id: step.entry.lineup_id.clone(),
+      },
+    });
+  }
+  Ok(step)
+}
+
+/// Undoes the last lineup edit.
+#[tauri::command]
+pub fn undo(db: State<'_, Database>, sync: State<'_, SyncState>) -> Result<Option<UndoStep>> {
+  run(&db, &sync, Direction::Undo)
+}
+
+/// Redoes the last undone lineup edit.
+#[tauri::command]
+pub fn redo(db: State<'_, Database>, sync: State<'_, SyncState>) -> Result<Option<UndoStep>> {
+  run(&db, &sync, Direction::Redo)
+}
+
+#[tauri::command]
+pub fn get_history(db: State<'_, Database>, limit: Option<u32>) -> Result<History> {
+  history(&db.conn(), limit.unwrap_or(DEFAULT_HISTORY))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::lineups::{LineupInput, SlotInput};
+  use crate::db::open_in_memory;
+  use crate::db::roster::{self, NewPlayer};
+
+  #[test]
+  fn undoes_and_redoes_a_cleared_lineup_then_a_delete() {
+    let mut conn = open_in_memory();
+    let player = roster::create(
+      &conn,
+      NewPlayer {
+        team_id: None,
+        name: "Jake Miller".into(),
+        number: Some(12),
+        primary_position: "SS".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap();
+    let mut input = LineupInput {
+      id: None,
+      team_id: None,
+      name: "vs Tigers".into(),
+      game_id: None,
+      use_dh: false,
+      innings: 6,
+      slots: vec![SlotInput {
+        slot_number: 1,
+        player_id: Some(player.id.clone()),
+        position: Some("SS".into()),
+      }],
+      positions: Vec::new(),
+    };
+    let lineup = lineups::save(&mut conn, input.clone()).unwrap();
+    input.id = Some(lineup.id.clone());
+    input.slots.clear();
+    lineups::save(&mut conn, input).unwrap();
+
+    let undone = step(&mut conn, Direction::Undo).unwrap().unwrap();
+    assert_eq!(undone.entry.action, Action::SaveLineup);
+    assert_eq!(undone.lineup.unwrap().slots.len(), 1);
+    assert!(history(&conn, 10).unwrap().can_redo);
+    let redone = step(&mut conn, Direction::Redo).unwrap().unwrap();
+    assert!(redone.lineup.unwrap().slots.is_empty());
+
+    lineups::delete(&mut conn, &lineup.id).unwrap();
+    step(&mut conn, Direction::Undo).unwrap();
+    assert_eq!(lineups::get(&conn, &lineup.id).unwrap().name, "vs Tigers");
+    // Undoing the first save removes the lineup
+    step(&mut conn, Direction::Undo).unwrap();
+    step(&mut conn, Direction::Undo).unwrap();
+    assert!(lineups::get(&conn, &lineup.id).is_err());
+    assert!(step(&mut conn, Direction::Undo).unwrap().is_none());
+
+    let history = history(&conn, 10).unwrap();
+    assert_eq!(history.entries.len(), 3);
+    assert!(!history.can_undo && history.can_redo);
+  }
+}
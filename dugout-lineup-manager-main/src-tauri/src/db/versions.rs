@@ -0,0 +1,93 @@
+//! Version vectors, for telling a synced lineup edit that builds on ours
+//! from one made at the same time.
+//!
+//! Every database has a replica id, and each lineup counts the saves made
+//! on each replica it has passed through. One vector that is at least the
+//! other everywhere has seen all of its edits; two that are each ahead
+//! somewhere were edited independently and conflict.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use rusqlite::Connection;
+
+use crate::error::Result;
+
+/// Saves of a record per replica id.
+pub type VersionVector = BTreeMap<String, u64>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+  Same,
+  /// The first vector is behind: the second has all of its edits and more.
+  Before,
+  After,
+  Concurrent,
+}
+
+pub fn compare(a: &VersionVector, b: &VersionVector) -> Order {
+  let (mut behind, mut ahead) = (false, false);
+  for key in a.keys().chain(b.keys()) {
+    let (x, y) = (a.get(key).unwrap_or(&0), b.get(key).unwrap_or(&0));
+    match x.cmp(y) {
+      Ordering::Less => behind = true,
+      Ordering::Greater => ahead = true,
+      Ordering::Equal => {}
+    }
+  }
+  match (behind, ahead) {
+    (false, false) => Order::Same,
+    (true, false) => Order::Before,
+    (false, true) => Order::After,
+    (true, true) => Order::Concurrent,
+  }
+}
+
+/// The vector that has seen every edit either has.
+pub fn merged(a: &VersionVector, b: &VersionVector) -> VersionVector {
+  let mut merged = a.clone();
+  for (key, &count) in b {
+    let entry = merged.entry(key.clone()).or_default();
+    *entry = (*entry).max(count);
+  }
+  merged
+}
+
+/// This database's replica id.
+pub fn replica_id(conn: &Connection) -> Result<String> {
+  Ok(conn.query_row("SELECT id FROM sync_replica", [], |row| row.get(0))?)
+}
+
+/// Counts a save made here.
+pub fn bump(conn: &Connection, versions: &mut VersionVector) -> Result<()> {
+  *versions.entry(replica_id(conn)?).or_default() += 1;
+  Ok(())
+}
+
+/// Gives the database a new replica id. Called after a backup is restored:
+/// its lineups are counted as of when it was taken, so numbering new saves
+/// on from there would reuse counts a paired device already saw.
+pub fn new_replica(conn: &Connection) -> Result<()> {
+  conn.execute("UPDATE sync_replica SET id = lower(hex(randomblob(16)))", [])?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn vector(entries: &[(&str, u64)]) -> VersionVector {
+    entries.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+  }
+
+  #[test]
+  fn orders_vectors_and_spots_concurrent_edits() {
+    let base = vector(&[("a", 2), ("b", 1)]);
+    assert_eq!(compare(&base, &base), Order::Same);
+    assert_eq!(compare(&base, &vector(&[("a", 3), ("b", 1)])), Order::Before);
+    assert_eq!(compare(&base, &vector(&[("a", 2)])), Order::After);
+    let theirs = vector(&[("a", 1), ("b", 1), ("c", 1)]);
+    assert_eq!(compare(&base, &theirs), Order::Concurrent);
+    assert_eq!(merged(&base, &theirs), vector(&[("a", 2), ("b", 1), ("c", 1)]));
+  }
+}
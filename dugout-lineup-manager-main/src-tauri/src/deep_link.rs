@@ -0,0 +1,246 @@
+//! `dugout://` links, e.g. `dugout://game/2024-05-11` or
+//! `dugout://import?file=/path/to/team.dugout`.
+//!
+//! Links are parsed into a [`DeepLink`] route and emitted to the webview.
+//! A link that launches the app arrives before the frontend is listening,
+//! so links are queued until it calls `take_pending_deep_links`.
+//!
+//! `.dugout` archives opened from the file manager take the same path as an
+//! import link: Windows and Linux pass them as launch arguments, macOS
+//! delivers them as `RunEvent::Opened`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State, Url};
+
+pub const SCHEME: &str = "dugout";
+
+/// Event carrying each [`DeepLink`] once the frontend is ready.
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "route", rename_all = "camelCase")]
+pub enum DeepLink {
+  /// `dugout://` or `dugout://home`.
+  Home,
+  /// `dugout://game/<YYYY-MM-DD>`.
+  Game { date: String },
+  /// `dugout://import?file=<path>`, for a `.dugout` archive.
+  Import { file: PathBuf },
+}
+
+impl DeepLink {
+  pub fn parse(url: &Url) -> Option<Self> {
+    if url.scheme() != SCHEME {
+      return None;
+    }
+    let segments: Vec<&str> = url
+      .path_segments()
+      .map(|s| s.filter(|s| !s.is_empty()).collect())
+      .unwrap_or_default();
+    match (url.host_str().unwrap_or(""), segments.as_slice()) {
+      ("" | "home", []) => Some(Self::Home),
+      ("game", [date]) => chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .map(|_| Self::Game {
+          date: date.to_string(),
+        }),
+      ("import", []) => {
+        let file = url
+          .query_pairs()
+          .find(|(key, _)| key == "file")
+          .map(|(_, value)| value.into_owned())?;
+        let file = match Url::parse(&file) {
+          Ok(file_url) if file_url.scheme() == "file" => file_url.to_file_path().ok()?,
+          _ => PathBuf::from(file),
+        };
+        is_archive(&file).then_some(Self::Import { file })
+      }
+      _ => None,
+    }
+  }
+
+  /// An import link for a `.dugout` archive opened from the OS. Relative
+  /// paths are resolved against `cwd`, the opening process's directory.
+  pub fn for_opened_file(path: &Path, cwd: &Path) -> Option<Self> {
+    if !is_archive(path) {
+      return None;
+    }
+    Some(Self::Import {
+      file: cwd.join(path),
+    })
+  }
+}
+
+fn is_archive(path: &Path) -> bool {
+  path
+    .extension()
+    .is_some_and(|ext| ext.eq_ignore_ascii_case(crate::archive::EXTENSION))
+}
+
+#[derive(Default)]
+struct Inner {
+  frontend_ready: bool,
+  pending: Vec<DeepLink>,
+}
+
+#[derive(Default)]
+pub struct DeepLinkState(Mutex<Inner>);
+
+/// Routes a link to the frontend, or queues it until the frontend is ready.
+pub fn dispatch(app: &AppHandle, link: DeepLink) {
+  log::info!("Opening deep link {:?}", link);
+  let state = app.state::<DeepLinkState>();
+  let mut inner = state.0.lock().unwrap();
+  if !inner.frontend_ready {
+    inner.pending.push(link);
+    return;
+  }
+  drop(inner);
+  if let Err(e) = app.emit(DEEP_LINK_EVENT, link) {
+    log::warn!("Failed to emit deep link: {}", e);
+  }
+}
+
+pub fn handle_urls(app: &AppHandle, urls: impl IntoIterator<Item = Url>) {
+  for url in urls {
+    #[cfg(desktop)]
+    if crate::teamsnap::is_callback(&url) {
+      crate::teamsnap::handle_callback(app, url);
+      continue;
+    }
+    #[cfg(desktop)]
+    if crate::cloud_backup::is_callback(&url) {
+      crate::cloud_backup::handle_callback(app, url);
+      continue;
+    }
+    match DeepLink::parse(&url) {
+      Some(link) => dispatch(app, link),
+      None => log::warn!("Ignoring unrecognized deep link {}", url),
+    }
+  }
+}
+
+/// Handles files passed on the command line, either this process's or those
+/// forwarded from a second launch. `dugout://` arguments are left to the
+/// deep-link plugin.
+pub fn handle_args(app: &AppHandle, args: impl IntoIterator<Item = String>, cwd: &Path) {
+  for arg in args {
+    // A Windows drive letter parses as a one-letter URL scheme.
+    if arg.starts_with('-') || Url::parse(&arg).is_ok_and(|url| url.scheme().len() > 1) {
+      continue;
+    }
+    if let Some(link) = DeepLink::for_opened_file(Path::new(&arg), cwd) {
+      dispatch(app, link);
+    }
+  }
+}
+
+/// Handles files the OS asks the running app to open (macOS). `dugout://`
+/// links arrive here too but are left to the deep-link plugin.
+#[cfg(target_os = "macos")]
+pub fn handle_opened(app: &AppHandle, urls: Vec<Url>) {
+  for url in urls.into_iter().filter(|url| url.scheme() == "file") {
+    let link = url
+      .to_file_path()
+      .ok()
+      .and_then(|path| DeepLink::for_opened_file(&path, Path::new("/")));
+    match link {
+      Some(link) => dispatch(app, link),
+      None => log::warn!("Ignoring opened file {}", url),
+    }
+  }
+}
+
+#[cfg(desktop)]
+pub fn init(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+  use tauri_plugin_deep_link::DeepLinkExt;
+
+  // Installed bundles register the scheme at install time; this covers
+  // development builds and AppImages.
+  #[cfg(any(windows, target_os = "linux"))]
+  app.deep_link().register_all()?;
+
+  let handle = app.handle().clone();
+  app
+    .deep_link()
+    .on_open_url(move |event| handle_urls(&handle, event.urls()));
+  if let Some(urls) = app.deep_link().get_current()? {
+    handle_urls(app.handle(), urls);
+  }
+  let cwd = std::env::current_dir().unwrap_or_default();
+  handle_args(app.handle(), crate::cli::args().rest.clone(), &cwd);
+  Ok(())
+}
+
+/// Marks the frontend as listening and returns links that arrived before
+/// it was.
+#[tauri::command]
+pub fn take_pending_deep_links(state: State<'_, DeepLinkState>) -> Vec<DeepLink> {
+  let mut inner = state.0.lock().unwrap();
+  inner.frontend_ready = true;
+  std::mem::take(&mut inner.pending)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(url: &str) -> Option<DeepLink> {
+    DeepLink::parse(&Url::parse(url).unwrap())
+  }
+
+  #[test]
+  fn parses_routes() {
+    assert_eq!(parse("dugout://"), Some(DeepLink::Home));
+    assert_eq!(
+      parse("dugout://game/2024-05-11"),
+      Some(DeepLink::Game {
+        date: "2024-05-11".into()
+      })
+    );
+    assert_eq!(
+      parse("dugout://import?file=%2Ftmp%2FTigers.dugout"),
+      Some(DeepLink::Import {
+        file: "/tmp/Tigers.dugout".into()
+      })
+    );
+    assert_eq!(
+      parse("dugout://import?file=file:///tmp/Tigers.dugout"),
+      Some(DeepLink::Import {
+        file: "/tmp/Tigers.dugout".into()
+      })
+    );
+    assert_eq!(parse("dugout://import?file=%2Fetc%2Fpasswd"), None);
+    assert_eq!(parse("dugout://import?file=file:///tmp/roster.csv"), None);
+  }
+
+  #[test]
+  fn opens_archives_only() {
+    assert_eq!(
+      DeepLink::for_opened_file(Path::new("Tigers.DUGOUT"), Path::new("/tmp")),
+      Some(DeepLink::Import {
+        file: "/tmp/Tigers.DUGOUT".into()
+      })
+    );
+    assert_eq!(
+      DeepLink::for_opened_file(Path::new("/home/coach/Tigers.dugout"), Path::new("/tmp")),
+      Some(DeepLink::Import {
+        file: "/home/coach/Tigers.dugout".into()
+      })
+    );
+    let cwd = Path::new("/tmp");
+    assert_eq!(DeepLink::for_opened_file(Path::new("roster.csv"), cwd), None);
+    assert_eq!(DeepLink::for_opened_file(Path::new("dugout"), cwd), None);
+  }
+
+  #[test]
+  fn rejects_unknown_links() {
+    assert_eq!(parse("dugout://game/not-a-date"), None);
+    assert_eq!(parse("dugout://import"), None);
+    assert_eq!(parse("dugout://settings/advanced"), None);
+    assert_eq!(parse("https://game/2024-05-11"), None);
+  }
+}
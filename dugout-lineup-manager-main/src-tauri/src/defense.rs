@@ -0,0 +1,376 @@
+//! Defensive rotations: who fields where in every inning, with the batting
+//! order left to the coach or [`crate::batting_order`].
+//!
+//! Each inning players who sat out the inning before take the field first,
+//! then those who've sat the most, so nobody sits twice in a row while
+//! someone else hasn't sat at all. Coaches pin a player to a position, or
+//! limit a position to a few players such as the adults who can play first
+//! base in coach-pitch; pins count for more than eligibility. Once the
+//! fielders are settled by the same matching as [`crate::optimizer`], the
+//! positions go out by preference weight, strongest first, with ties going
+//! to whoever has played the position least.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::lineups::{InningPosition, MAX_INNINGS};
+use crate::db::roster;
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::optimizer::{
+  self, assign, filled, pick_fielders, Candidate, PlayingTime, Tally, FIELD_POSITIONS,
+};
+
+/// Weight of a player's first eligible position when none is given.
+const PRIMARY_WEIGHT: f64 = 2.0;
+
+/// Weight of their other eligible positions when none is given.
+const SECONDARY_WEIGHT: f64 = 1.0;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefenseRotationRequest {
+  /// Defaults to the first team.
+  pub team_id: Option<String>,
+  #[serde(default = "default_innings")]
+  pub innings: u32,
+  /// Ids of the players at the game. Defaults to every active player.
+  pub attendance: Option<Vec<String>>,
+  /// Positions a player may field, replacing their roster positions.
+  #[serde(default)]
+  pub eligibility: HashMap<String, Vec<String>>,
+  /// How much a player would rather field each position, higher first.
+  /// Positions left out get the defaults for their roster positions.
+  #[serde(default)]
+  pub preferences: HashMap<String, HashMap<String, f64>>,
+  #[serde(default)]
+  pub pins: Vec<FieldPin>,
+}
+
+fn default_innings() -> u32 {
+  6
+}
+
+/// Holds a position for the given `innings`, or every inning when empty.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FieldPin {
+  /// Keeps a player at a position, e.g. the only catcher behind the plate.
+  #[serde(rename_all = "camelCase")]
+  Player {
+    player_id: String,
+    position: String,
+    #[serde(default)]
+    innings: Vec<u32>,
+  },
+  /// Only these players can field the position, e.g. an adult at first.
+  #[serde(rename_all = "camelCase")]
+  OneOf {
+    position: String,
+    player_ids: Vec<String>,
+    #[serde(default)]
+    innings: Vec<u32>,
+  },
+}
+
+impl FieldPin {
+  fn position(&self) -> &str {
+    match self {
+      Self::Player { position, .. } | Self::OneOf { position, .. } => position,
+    }
+  }
+
+  fn innings(&self) -> &[u32] {
+    match self {
+      Self::Player { innings, .. } | Self::OneOf { innings, .. } => innings,
+    }
+  }
+}
+
+/// A pin with players and the position as indexes.
+#[derive(Debug, Clone)]
+struct Pin {
+  position: usize,
+  players: Vec<usize>,
+  /// Whether it names one player who must field the position.
+  held: bool,
+  innings: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefenseRotation {
+  pub innings: u32,
+  pub positions: Vec<InningPosition>,
+  pub playing_time: Vec<PlayingTime>,
+  /// Times anyone sat the inning right after sitting one.
+  pub repeat_benchings: u32,
+  /// Problems the coach should know about, e.g. an empty position.
+  pub warnings: Vec<String>,
+}
+
+/// Each candidate's weight at every position.
+pub type Weights = Vec<[f64; FIELD_POSITIONS.len()]>;
+
+/// Default weights from each candidate's eligible positions, overridden
+/// by `preferences` keyed by player id and position.
+pub fn weights(
+  candidates: &[Candidate],
+  preferences: &HashMap<String, HashMap<String, f64>>,
+) -> Weights {
+  candidates
+    .iter()
+    .map(|candidate| {
+      let mut weights = [0.0; FIELD_POSITIONS.len()];
+      for (rank, &position) in candidate.eligible.iter().enumerate() {
+        weights[position] = if rank == 0 { PRIMARY_WEIGHT } else { SECONDARY_WEIGHT };
+      }
+      for (position, &weight) in preferences.get(&candidate.id).into_iter().flatten() {
+        if let Some(position) = FIELD_POSITIONS.iter().position(|f| f == position) {
+          weights[position] = weight;
+        }
+      }
+      weights
+    })
+    .collect()
+}
+
+fn resolve(candidates: &[Candidate], pins: &[FieldPin], innings: u32) -> Result<Vec<Pin>> {
+  let index = |id: &str| candidates.iter().position(|c| c.id == id);
+  pins
+    .iter()
+    .map(|pin| {
+      let position = FIELD_POSITIONS
+        .iter()
+        .position(|&f| f == pin.position())
+        .ok_or_else(|| {
+          Error::Validation(format!("{} is not a fielding position", pin.position()))
+        })?;
+      if let Some(inning) = pin.innings().iter().find(|&&i| i == 0 || i > innings) {
+        return Err(Error::Validation(format!(
+          "Inning {inning} is outside the {innings}-inning game"
+        )));
+      }
+      let (players, held) = match pin {
+        FieldPin::Player { player_id, .. } => {
+          let player = index(player_id).ok_or_else(|| {
+            Error::Validation(format!("{player_id} isn't at this game"))
+          })?;
+          (vec![player], true)
+        }
+        // Players who aren't at the game just can't be picked
+        FieldPin::OneOf { player_ids, .. } => {
+          (player_ids.iter().filter_map(|id| index(id)).collect(), false)
+        }
+      };
+      Ok(Pin {
+        position,
+        players,
+        held,
+        innings: pin.innings().to_vec(),
+      })
+    })
+    .collect()
+}
+
+pub fn rotate(
+  candidates: &[Candidate],
+  weights: &Weights,
+  pins: &[FieldPin],
+  innings: u32,
+) -> Result<DefenseRotation> {
+  let pins = resolve(candidates, pins, innings)?;
+  let mut tally = Tally::new(candidates);
+
+  for inning in 1..=innings {
+    let pins: Vec<&Pin> = pins
+      .iter()
+      .filter(|p| p.innings.is_empty() || p.innings.contains(&inning))
+      .collect();
+    let mut held = [None; FIELD_POSITIONS.len()];
+    let mut only: Vec<Option<&[usize]>> = vec![None; FIELD_POSITIONS.len()];
+    for pin in &pins {
+      if pin.held {
+        let player = pin.players[0];
+        let elsewhere = held.contains(&Some(player));
+        if elsewhere || held[pin.position].is_some_and(|h| h != player) {
+          return Err(Error::Validation(format!(
+            "Conflicting pins for {} in inning {inning}",
+            candidates[player].name
+          )));
+        }
+        held[pin.position] = Some(player);
+      } else if only[pin.position].is_some() {
+        return Err(Error::Validation(format!(
+          "More than one list of players for {} in inning {inning}",
+          FIELD_POSITIONS[pin.position]
+        )));
+      } else {
+        only[pin.position] = Some(&pin.players);
+      }
+    }
+    let allowed = |i: usize| -> Vec<usize> {
+      if let Some(position) = held.iter().position(|&h| h == Some(i)) {
+        return vec![position];
+      }
+      (0..FIELD_POSITIONS.len())
+        .filter(|&p| held[p].is_none())
+        .filter(|&p| match only[p] {
+          Some(players) => players.contains(&i),
+          None => candidates[i].eligible.contains(&p),
+        })
+        .collect()
+    };
+
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by_key(|&i| (!held.contains(&Some(i)), !tally.sat_last[i], tally.played[i], i));
+    let fielders = pick_fielders(&order, allowed);
+
+    // Same fielders, placed strongest preference first as long as the
+    // rest can still all be placed
+    let target = filled(&assign(&fielders, allowed));
+    let mut pairs: Vec<(usize, usize)> = fielders
+      .iter()
+      .flat_map(|&i| allowed(i).into_iter().map(move |p| (i, p)))
+      .collect();
+    pairs.sort_by(|&(a, p), &(b, q)| {
+      weights[b][q]
+        .total_cmp(&weights[a][p])
+        .then(tally.at_position[a][p].cmp(&tally.at_position[b][q]))
+    });
+    let mut holder = [None; FIELD_POSITIONS.len()];
+    for (player, position) in pairs {
+      if holder[position].is_some() || holder.contains(&Some(player)) {
+        continue;
+      }
+      holder[position] = Some(player);
+      let rest: Vec<usize> = fielders
+        .iter()
+        .copied()
+        .filter(|&i| !holder.contains(&Some(i)))
+        .collect();
+      let open = |i: usize| -> Vec<usize> {
+        allowed(i).into_iter().filter(|&p| holder[p].is_none()).collect()
+      };
+      if filled(&holder) + filled(&assign(&rest, open)) < target {
+        holder[position] = None;
+      }
+    }
+    tally.record(inning, &fielders, &holder);
+  }
+
+  let (playing_time, warnings) = (tally.playing_time(innings), tally.warnings());
+  Ok(DefenseRotation {
+    innings,
+    positions: tally.positions,
+    playing_time,
+    repeat_benchings: tally.repeat_benchings,
+    warnings,
+  })
+}
+
+/// Plans every inning's fielding positions for the players at a game.
+#[tauri::command]
+pub fn generate_defense_rotation(
+  db: State<'_, Database>,
+  request: DefenseRotationRequest,
+) -> Result<DefenseRotation> {
+  if request.innings == 0 || request.innings > MAX_INNINGS {
+    return Err(Error::Validation(format!(
+      "Innings must be between 1 and {MAX_INNINGS}"
+    )));
+  }
+  let conn = db.conn();
+  let team_id = match request.team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(&conn)?,
+  };
+  let roster = roster::list(&conn, &team_id)?;
+  let players = optimizer::attending(&roster, request.attendance.as_deref())?;
+  if players.is_empty() {
+    return Err(Error::Validation("No players to build a rotation from".into()));
+  }
+
+  let candidates: Vec<Candidate> = players
+    .iter()
+    .map(|p| optimizer::candidate(p, &request.eligibility))
+    .collect();
+  let weights = weights(&candidates, &request.preferences);
+  rotate(&candidates, &weights, &request.pins, request.innings)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn at(rotation: &DefenseRotation, inning: u32, position: &str) -> Option<String> {
+    rotation
+      .positions
+      .iter()
+      .find(|p| p.inning == inning && p.position == position)
+      .map(|p| p.player_id.clone())
+  }
+
+  fn kids(count: usize) -> Vec<Candidate> {
+    (0..count)
+      .map(|i| Candidate::new(&format!("k{i}"), &format!("Kid {i}"), &FIELD_POSITIONS))
+      .collect()
+  }
+
+  #[test]
+  fn nobody_sits_twice_in_a_row_and_preferences_win_ties() {
+    let candidates = kids(11);
+    let mut preferences = HashMap::new();
+    preferences.insert("k3".to_string(), HashMap::from([("SS".to_string(), 5.0)]));
+    let rotation = rotate(&candidates, &weights(&candidates, &preferences), &[], 6).unwrap();
+
+    assert_eq!(rotation.positions.len(), 6 * 9);
+    assert_eq!(rotation.repeat_benchings, 0);
+    assert!(rotation.warnings.is_empty());
+    let benched: Vec<u32> = rotation.playing_time.iter().map(|p| p.innings_benched).collect();
+    assert!(benched.iter().all(|&b| (1..=2).contains(&b)));
+    for inning in 1..=6 {
+      let fielding = rotation.positions.iter().any(|p| p.inning == inning && p.player_id == "k3");
+      if fielding {
+        assert_eq!(at(&rotation, inning, "SS").as_deref(), Some("k3"));
+      }
+    }
+  }
+
+  #[test]
+  fn honours_pins() {
+    let mut candidates = kids(10);
+    candidates.push(Candidate::new("coach", "Coach Dana", &[]));
+    let pins = [
+      FieldPin::OneOf {
+        position: "1B".into(),
+        player_ids: vec!["coach".into(), "absent".into()],
+        innings: Vec::new(),
+      },
+      FieldPin::Player {
+        player_id: "k0".into(),
+        position: "P".into(),
+        innings: vec![1, 2],
+      },
+    ];
+    let rotation = rotate(&candidates, &weights(&candidates, &HashMap::new()), &pins, 4).unwrap();
+
+    for inning in 1..=4 {
+      assert_eq!(at(&rotation, inning, "1B").as_deref(), Some("coach"));
+    }
+    assert_eq!(at(&rotation, 1, "P").as_deref(), Some("k0"));
+    assert_eq!(at(&rotation, 2, "P").as_deref(), Some("k0"));
+
+    let clash = [
+      pins[1].clone(),
+      FieldPin::Player {
+        player_id: "k1".into(),
+        position: "P".into(),
+        innings: vec![2],
+      },
+    ];
+    assert!(rotate(&candidates, &weights(&candidates, &HashMap::new()), &clash, 4).is_err());
+  }
+}
@@ -0,0 +1,74 @@
+//! Radars that talk Bluetooth Low Energy. Their readings are picked up by
+//! subscribing to every characteristic they notify on, since each make
+//! uses its own.
+
+use std::time::Duration;
+
+use btleplug::api::{Central, CharPropFlags, Manager as _, Peripheral as _, ScanFilter};
+use btleplug::platform::{Adapter, Manager, Peripheral};
+use futures_util::stream::{BoxStream, StreamExt};
+
+use super::{Device, Transport};
+use crate::error::{Error, Result};
+
+/// Long enough for a radar that's awake to advertise a few times.
+const SCAN_TIME: Duration = Duration::from_secs(4);
+
+pub async fn adapter() -> Result<Adapter> {
+  Manager::new()
+    .await?
+    .adapters()
+    .await?
+    .into_iter()
+    .next()
+    .ok_or_else(|| Error::Validation("This computer has no Bluetooth adapter".into()))
+}
+
+/// Named devices in range. Unnamed ones can't be told apart, so they're
+/// left out.
+pub async fn scan(adapter: &Adapter) -> Result<Vec<(Device, Peripheral)>> {
+  adapter.start_scan(ScanFilter::default()).await?;
+  tokio::time::sleep(SCAN_TIME).await;
+  if let Err(e) = adapter.stop_scan().await {
+    log::warn!("Failed to stop the Bluetooth scan: {}", e);
+  }
+  let mut found = Vec::new();
+  for peripheral in adapter.peripherals().await? {
+    let name = peripheral.properties().await?.and_then(|p| p.local_name);
+    if let Some(name) = name {
+      let device = Device {
+        id: format!("ble:{}", peripheral.id()),
+        name,
+        transport: Transport::Ble,
+      };
+      found.push((device, peripheral));
+    }
+  }
+  Ok(found)
+}
+
+/// Connects and subscribes, returning the notifications as text.
+pub async fn connect(peripheral: &Peripheral) -> Result<BoxStream<'static, String>> {
+  peripheral.connect().await?;
+  peripheral.discover_services().await?;
+  let mut subscribed = false;
+  for characteristic in peripheral.characteristics() {
+    if characteristic
+      .properties
+      .intersects(CharPropFlags::NOTIFY | CharPropFlags::INDICATE)
+    {
+      peripheral.subscribe(&characteristic).await?;
+      subscribed = true;
+    }
+  }
+  if !subscribed {
+    let _ = peripheral.disconnect().await;
+    return Err(Error::Validation("That device doesn't send readings".into()));
+  }
+  let notifications = peripheral.notifications().await?;
+  Ok(
+    notifications
+      .map(|notification| String::from_utf8_lossy(&notification.value).into_owned())
+      .boxed(),
+  )
+}
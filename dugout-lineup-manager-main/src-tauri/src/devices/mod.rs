@@ -0,0 +1,298 @@
+//! Pitch-speed radars, so a gun behind the backstop can clock the pitcher
+//! without anyone calling out the number.
+//!
+//! `list_radars` lists serial ports and scans for Bluetooth LE devices, and
+//! `connect_radar` starts reading from one of them; one radar is connected
+//! at a time. Each speed is emitted as a [`RadarReading`] and, once
+//! `set_radar_game` names the game being scored, recorded in its scorebook
+//! as the current pitch.
+//!
+//! [`scoreboard`] drives a scoreboard controller on a serial port the same
+//! way, in the other direction.
+
+mod ble;
+mod reading;
+pub mod scoreboard;
+mod serial;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use btleplug::api::Peripheral as _;
+use btleplug::platform::{Adapter, Peripheral};
+use futures_util::StreamExt;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::db::{games, Database};
+use crate::error::{Error, Result};
+use crate::scorebook::{self, Event};
+
+/// Emitted with a [`RadarReading`] for every speed the radar sends.
+pub const RADAR_READING_EVENT: &str = "radar-reading";
+
+/// Emitted with the [`RadarStatus`] when a radar connects or drops, or the
+/// game changes.
+pub const RADAR_STATUS_EVENT: &str = "radar-status";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Transport {
+  Ble,
+  Serial,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Device {
+  /// `ble:` or `serial:` and the device's own id.
+  pub id: String,
+  pub name: String,
+  pub transport: Transport,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarStatus {
+  pub device: Option<Device>,
+  /// The game readings are recorded in.
+  pub game_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarReading {
+  pub mph: f64,
+  pub device_id: String,
+  pub game_id: Option<String>,
+  /// Whether it went into the game's scorebook.
+  pub recorded: bool,
+}
+
+enum Link {
+  Serial(Arc<AtomicBool>, std::thread::JoinHandle<()>),
+  Ble(Peripheral, tauri::async_runtime::JoinHandle<()>),
+}
+
+struct Connected {
+  /// Tells this connection apart from a later one to the same radar.
+  session: u64,
+  device: Device,
+  link: Link,
+}
+
+#[derive(Default)]
+pub struct RadarState {
+  /// Kept so the devices it found stay usable.
+  adapter: Mutex<Option<Adapter>>,
+  /// Bluetooth devices seen by the last scan, by id.
+  found: Mutex<HashMap<String, (Device, Peripheral)>>,
+  connected: Mutex<Option<Connected>>,
+  sessions: AtomicU64,
+  game_id: Mutex<Option<String>>,
+}
+
+impl RadarState {
+  fn status(&self) -> RadarStatus {
+    RadarStatus {
+      device: self.connected.lock().unwrap().as_ref().map(|c| c.device.clone()),
+      game_id: self.game_id.lock().unwrap().clone(),
+    }
+  }
+
+  async fn scan(&self) -> Result<Vec<Device>> {
+    let cached = self.adapter.lock().unwrap().clone();
+    let adapter = match cached {
+      Some(adapter) => adapter,
+      None => {
+        let adapter = ble::adapter().await?;
+        *self.adapter.lock().unwrap() = Some(adapter.clone());
+        adapter
+      }
+    };
+    let found = ble::scan(&adapter).await?;
+    let devices = found.iter().map(|(device, _)| device.clone()).collect();
+    *self.found.lock().unwrap() = found
+      .into_iter()
+      .map(|(device, peripheral)| (device.id.clone(), (device, peripheral)))
+      .collect();
+    Ok(devices)
+  }
+
+  async fn disconnect(&self) {
+    let connected = self.connected.lock().unwrap().take();
+    match connected.map(|c| c.link) {
+      Some(Link::Serial(stop, thread)) => {
+        stop.store(true, Ordering::Relaxed);
+        // Let go of the port before anything tries to open it again
+        let _ = tauri::async_runtime::spawn_blocking(move || thread.join()).await;
+      }
+      Some(Link::Ble(peripheral, task)) => {
+        task.abort();
+        if let Err(e) = peripheral.disconnect().await {
+          log::warn!("Failed to disconnect the radar: {}", e);
+        }
+      }
+      None => {}
+    }
+  }
+}
+
+fn emit_status(app: &AppHandle, status: &RadarStatus) {
+  if let Err(e) = app.emit(RADAR_STATUS_EVENT, status) {
+    log::warn!("Failed to emit the radar status: {}", e);
+  }
+}
+
+fn on_reading(app: &AppHandle, device_id: &str, text: &str) {
+  let Some(mph) = reading::parse(text) else {
+    return;
+  };
+  let game_id = app.state::<RadarState>().game_id.lock().unwrap().clone();
+  let mut recorded = false;
+  if let Some(game_id) = &game_id {
+    let db = app.state::<Database>();
+    let result = scorebook::record(&mut db.conn(), game_id, Event::Pitch { mph });
+    match result {
+      Ok(box_score) => {
+        let _ = app.emit(scorebook::SCOREBOOK_EVENT, &box_score);
+        recorded = true;
+      }
+      Err(e) => log::warn!("Failed to record a {} mph pitch: {}", mph, e),
+    }
+  }
+  let reading = RadarReading {
+    mph,
+    device_id: device_id.to_string(),
+    game_id,
+    recorded,
+  };
+  if let Err(e) = app.emit(RADAR_READING_EVENT, &reading) {
+    log::warn!("Failed to emit the radar reading: {}", e);
+  }
+}
+
+/// Clears the connection when the radar goes away by itself.
+fn on_dropped(app: &AppHandle, session: u64) {
+  let state = app.state::<RadarState>();
+  let mut connected = state.connected.lock().unwrap();
+  if connected.as_ref().is_some_and(|c| c.session == session) {
+    let device = connected.take().map(|c| c.device);
+    drop(connected);
+    if let Some(device) = device {
+      log::info!("Radar {} disconnected", device.name);
+    }
+    emit_status(app, &state.status());
+  }
+}
+
+/// Serial ports and the Bluetooth devices in range, which takes a few
+/// seconds to scan.
+#[tauri::command]
+pub async fn list_radars(state: State<'_, RadarState>) -> Result<Vec<Device>> {
+  let mut devices = serial::list().unwrap_or_else(|e| {
+    log::warn!("Failed to list serial ports: {}", e);
+    Vec::new()
+  });
+  // Bluetooth can be off or missing, and a wired radar still works then
+  match state.scan().await {
+    Ok(found) => devices.extend(found),
+    Err(e) => log::warn!("Bluetooth scan failed: {}", e),
+  }
+  Ok(devices)
+}
+
+/// Connects to a radar from `list_radars`, replacing any connected before.
+/// `baud_rate` is for serial radars and defaults to 9600.
+#[tauri::command]
+pub async fn connect_radar(
+  app: AppHandle,
+  state: State<'_, RadarState>,
+  device_id: String,
+  baud_rate: Option<u32>,
+) -> Result<RadarStatus> {
+  state.disconnect().await;
+  let session = state.sessions.fetch_add(1, Ordering::Relaxed);
+
+  let (device, link) = if let Some(path) = device_id.strip_prefix("serial:") {
+    let device = serial::list()?
+      .into_iter()
+      .find(|d| d.id == device_id)
+      .ok_or_else(|| Error::NotFound(format!("Serial port {path}")))?;
+    let port = serial::open(path, baud_rate.unwrap_or(serial::DEFAULT_BAUD_RATE))?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = {
+      let (app, stop, id) = (app.clone(), stop.clone(), device.id.clone());
+      std::thread::Builder::new().name("radar".into()).spawn(move || {
+        if let Err(e) = serial::read(port, &stop, |line| on_reading(&app, &id, line)) {
+          log::warn!("Lost the serial radar: {}", e);
+        }
+        on_dropped(&app, session);
+      })?
+    };
+    (device, Link::Serial(stop, thread))
+  } else {
+    let found = state.found.lock().unwrap().get(&device_id).cloned();
+    let (device, peripheral) = found.ok_or_else(|| Error::NotFound(format!("Radar {device_id}")))?;
+    let mut readings = ble::connect(&peripheral).await?;
+    let task = {
+      let (app, id) = (app.clone(), device.id.clone());
+      tauri::async_runtime::spawn(async move {
+        while let Some(text) = readings.next().await {
+          on_reading(&app, &id, &text);
+        }
+        on_dropped(&app, session);
+      })
+    };
+    (device, Link::Ble(peripheral, task))
+  };
+
+  log::info!("Connected to radar {}", device.name);
+  *state.connected.lock().unwrap() = Some(Connected {
+    session,
+    device,
+    link,
+  });
+  let status = state.status();
+  emit_status(&app, &status);
+  Ok(status)
+}
+
+#[tauri::command]
+pub async fn disconnect_radar(app: AppHandle, state: State<'_, RadarState>) -> Result<RadarStatus> {
+  state.disconnect().await;
+  let status = state.status();
+  emit_status(&app, &status);
+  Ok(status)
+}
+
+/// Records readings in `game_id`'s scorebook from now on, or stops
+/// recording them when it's left out.
+#[tauri::command]
+pub fn set_radar_game(
+  app: AppHandle,
+  db: State<'_, Database>,
+  state: State<'_, RadarState>,
+  game_id: Option<String>,
+) -> Result<RadarStatus> {
+  if let Some(game_id) = &game_id {
+    games::get(&db.conn(), game_id)?;
+  }
+  *state.game_id.lock().unwrap() = game_id;
+  let status = state.status();
+  emit_status(&app, &status);
+  Ok(status)
+}
+
+/// Serial ports, for picking a wired radar or scoreboard controller
+/// without a Bluetooth scan.
+#[tauri::command]
+pub fn list_serial_ports() -> Result<Vec<Device>> {
+  serial::list()
+}
+
+#[tauri::command]
+pub fn get_radar_status(state: State<'_, RadarState>) -> RadarStatus {
+  state.status()
+}
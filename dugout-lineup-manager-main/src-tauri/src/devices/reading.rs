@@ -0,0 +1,58 @@
+//! Pulls a speed out of what a radar sends. Radars that talk to apps send
+//! each reading as a short line of text, like `72`, `S072` or `116 KPH`.
+
+use crate::scorebook::MAX_PITCH_MPH;
+
+const KM_PER_MILE: f64 = 1.609_344;
+
+/// The speed in a reading, in mph. Zero (what radars send between pitches)
+/// and anything no pitch could reach read as nothing.
+pub fn parse(text: &str) -> Option<f64> {
+  let start = text.find(|c: char| c.is_ascii_digit())?;
+  let rest = &text[start..];
+  let end = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+  let speed: f64 = rest[..end].trim_end_matches('.').parse().ok()?;
+  let unit = rest[end..].to_ascii_lowercase();
+  let mph = if unit.contains("km") || unit.contains("kph") {
+    speed / KM_PER_MILE
+  } else {
+    speed
+  };
+  (mph > 0.0 && mph <= MAX_PITCH_MPH).then(|| (mph * 10.0).round() / 10.0)
+}
+
+/// Splits off the complete lines in `buffer`, keeping a partial one for
+/// the next read.
+pub fn take_lines(buffer: &mut String) -> Vec<String> {
+  let Some(end) = buffer.rfind(['\r', '\n']) else {
+    return Vec::new();
+  };
+  let lines = buffer[..end]
+    .split(['\r', '\n'])
+    .filter(|line| !line.trim().is_empty())
+    .map(str::to_string)
+    .collect();
+  buffer.drain(..=end);
+  lines
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reads_speeds_and_lines() {
+    assert_eq!(parse("72"), Some(72.0));
+    assert_eq!(parse("S072\r"), Some(72.0));
+    assert_eq!(parse("Speed: 64.3 mph"), Some(64.3));
+    assert_eq!(parse("116 KPH"), Some(72.1));
+    assert_eq!(parse("000"), None);
+    assert_eq!(parse("999"), None);
+    assert_eq!(parse("---"), None);
+
+    let mut buffer = String::from("072\r\n068\r\n07");
+    assert_eq!(take_lines(&mut buffer), ["072", "068"]);
+    assert_eq!(buffer, "07");
+    assert!(take_lines(&mut buffer).is_empty());
+  }
+}
@@ -0,0 +1,175 @@
+//! Sends the live [`Scoreboard`] to a scoreboard controller on a serial
+//! port, in the frame format of its [`ScoreboardProtocol`], each time it
+//! changes.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Notify;
+
+use super::serial;
+use crate::error::Result;
+use crate::scoreboard::{self, Scoreboard, ScoreboardProtocol, ScoreboardState};
+use crate::settings::AppSettings;
+
+/// Emitted with the [`ScoreboardOutputStatus`] when the controller is
+/// connected, disconnected or lost.
+pub const SCOREBOARD_OUTPUT_EVENT: &str = "scoreboard-output";
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreboardOutputStatus {
+  pub port: Option<String>,
+  pub protocol: Option<ScoreboardProtocol>,
+}
+
+struct Output {
+  session: u64,
+  port: String,
+  protocol: ScoreboardProtocol,
+  stop: Arc<Notify>,
+  thread: std::thread::JoinHandle<()>,
+}
+
+#[derive(Default)]
+pub struct ScoreboardOutput {
+  current: Mutex<Option<Output>>,
+  sessions: AtomicU64,
+}
+
+impl ScoreboardOutput {
+  fn status(&self) -> ScoreboardOutputStatus {
+    let current = self.current.lock().unwrap();
+    ScoreboardOutputStatus {
+      port: current.as_ref().map(|o| o.port.clone()),
+      protocol: current.as_ref().map(|o| o.protocol.clone()),
+    }
+  }
+
+  /// Stops the output and waits for it to let go of the port.
+  fn stop(&self) {
+    let output = self.current.lock().unwrap().take();
+    if let Some(output) = output {
+      output.stop.notify_one();
+      let _ = output.thread.join();
+    }
+  }
+}
+
+fn emit_status(app: &AppHandle, status: &ScoreboardOutputStatus) {
+  if let Err(e) = app.emit(SCOREBOARD_OUTPUT_EVENT, status) {
+    log::warn!("Failed to emit the scoreboard output status: {}", e);
+  }
+}
+
+/// Writes a frame for every scoreboard update until `stop` is notified or
+/// the port fails.
+fn drive(
+  mut port: Box<dyn serialport::SerialPort>,
+  protocol: &ScoreboardProtocol,
+  mut board: tokio::sync::watch::Receiver<Scoreboard>,
+  stop: &Notify,
+) -> Result<()> {
+  let resend = protocol.resend_ms.map(Duration::from_millis);
+  loop {
+    let frame = scoreboard::render(&protocol.template, &board.borrow_and_update())?;
+    port.write_all(&frame)?;
+    port.flush()?;
+    let next = tauri::async_runtime::block_on(async {
+      tokio::select! {
+        changed = board.changed() => changed.is_ok(),
+        _ = stop.notified() => false,
+        _ = async {
+          match resend {
+            Some(every) => tokio::time::sleep(every).await,
+            None => std::future::pending().await,
+          }
+        } => true,
+      }
+    });
+    if !next {
+      return Ok(());
+    }
+  }
+}
+
+/// Protocols to pick from: the coach's own from the settings, then the
+/// built-in ones.
+#[tauri::command]
+pub fn list_scoreboard_protocols(
+  settings: State<'_, RwLock<AppSettings>>,
+) -> Vec<ScoreboardProtocol> {
+  let mut protocols = settings.read().unwrap().scoreboard_protocols.clone();
+  protocols.extend(scoreboard::built_in_protocols());
+  protocols
+}
+
+/// Starts sending the scoreboard to the controller on `port` (e.g.
+/// `/dev/ttyUSB0` or `COM3`), replacing any controller connected before.
+#[tauri::command]
+pub fn connect_scoreboard(
+  app: AppHandle,
+  settings: State<'_, RwLock<AppSettings>>,
+  output: State<'_, ScoreboardOutput>,
+  port: String,
+  protocol: String,
+) -> Result<ScoreboardOutputStatus> {
+  let custom = settings.read().unwrap().scoreboard_protocols.clone();
+  let protocol = scoreboard::protocol(&custom, &protocol)?;
+  let path = port.strip_prefix("serial:").unwrap_or(&port).to_string();
+  output.stop();
+  let serial = serial::open(&path, protocol.baud_rate)?;
+
+  let session = output.sessions.fetch_add(1, Ordering::Relaxed);
+  let stop = Arc::new(Notify::new());
+  let thread = {
+    let (app, protocol, stop) = (app.clone(), protocol.clone(), stop.clone());
+    let board = app.state::<ScoreboardState>().subscribe();
+    std::thread::Builder::new()
+      .name("scoreboard-output".into())
+      .spawn(move || {
+        if let Err(e) = drive(serial, &protocol, board, &stop) {
+          log::warn!("Lost the scoreboard controller: {}", e);
+          let output = app.state::<ScoreboardOutput>();
+          let mut current = output.current.lock().unwrap();
+          if current.as_ref().is_some_and(|o| o.session == session) {
+            *current = None;
+            drop(current);
+            emit_status(&app, &output.status());
+          }
+        }
+      })?
+  };
+
+  log::info!("Sending the scoreboard to {} as {}", path, protocol.name);
+  *output.current.lock().unwrap() = Some(Output {
+    session,
+    port: path,
+    protocol,
+    stop,
+    thread,
+  });
+  let status = output.status();
+  emit_status(&app, &status);
+  Ok(status)
+}
+
+#[tauri::command]
+pub fn disconnect_scoreboard(
+  app: AppHandle,
+  output: State<'_, ScoreboardOutput>,
+) -> ScoreboardOutputStatus {
+  output.stop();
+  let status = output.status();
+  emit_status(&app, &status);
+  status
+}
+
+#[tauri::command]
+pub fn get_scoreboard_output(output: State<'_, ScoreboardOutput>) -> ScoreboardOutputStatus {
+  output.status()
+}
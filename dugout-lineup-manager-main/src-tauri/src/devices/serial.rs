@@ -0,0 +1,63 @@
+//! Radars on a serial port, either wired over USB or paired over classic
+//! Bluetooth, which shows up as a serial port too.
+
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serialport::{SerialPort, SerialPortType};
+
+use super::{Device, Transport};
+use crate::error::Result;
+
+/// What radars with a serial link default to.
+pub const DEFAULT_BAUD_RATE: u32 = 9600;
+
+/// How long a read waits before checking whether to stop.
+const READ_TIMEOUT: Duration = Duration::from_millis(250);
+
+pub fn list() -> Result<Vec<Device>> {
+  Ok(serialport::available_ports()?
+    .into_iter()
+    .map(|port| {
+      let name = match &port.port_type {
+        SerialPortType::UsbPort(usb) => usb.product.clone(),
+        _ => None,
+      };
+      Device {
+        id: format!("serial:{}", port.port_name),
+        name: name.unwrap_or_else(|| port.port_name.clone()),
+        transport: Transport::Serial,
+      }
+    })
+    .collect())
+}
+
+pub fn open(path: &str, baud_rate: u32) -> Result<Box<dyn SerialPort>> {
+  Ok(serialport::new(path, baud_rate).timeout(READ_TIMEOUT).open()?)
+}
+
+/// Reads lines from `port` until `stop` is set or the port goes away,
+/// handing each one to `on_line`.
+pub fn read(
+  mut port: Box<dyn SerialPort>,
+  stop: &AtomicBool,
+  mut on_line: impl FnMut(&str),
+) -> Result<()> {
+  let mut buffer = String::new();
+  let mut chunk = [0; 256];
+  while !stop.load(Ordering::Relaxed) {
+    match port.read(&mut chunk) {
+      Ok(0) => break,
+      Ok(read) => {
+        buffer.push_str(&String::from_utf8_lossy(&chunk[..read]));
+        for line in super::reading::take_lines(&mut buffer) {
+          on_line(&line);
+        }
+      }
+      Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {}
+      Err(e) => return Err(e.into()),
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,79 @@
+//! Error type shared by the native data layer and its commands.
+
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+  #[error("Database error: {0}")]
+  Database(#[from] rusqlite::Error),
+  #[error("I/O error: {0}")]
+  Io(#[from] std::io::Error),
+  #[error("JSON error: {0}")]
+  Json(#[from] serde_json::Error),
+  #[error("CSV error: {0}")]
+  Csv(#[from] csv::Error),
+  #[error("Spreadsheet error: {0}")]
+  Xlsx(#[from] rust_xlsxwriter::XlsxError),
+  #[error("Image error: {0}")]
+  Image(#[from] image::ImageError),
+  #[error("Archive error: {0}")]
+  Zip(#[from] zip::result::ZipError),
+  #[error(transparent)]
+  Tauri(#[from] tauri::Error),
+  #[error(transparent)]
+  Backend(#[from] crate::proxy::ProxyError),
+  #[cfg(desktop)]
+  #[error("Update failed: {0}")]
+  Updater(#[from] tauri_plugin_updater::Error),
+  #[cfg(desktop)]
+  #[error("Keychain error: {0}")]
+  Keyring(#[from] keyring::Error),
+  #[cfg(desktop)]
+  #[error("Launch at login failed: {0}")]
+  Autostart(#[from] tauri_plugin_autostart::Error),
+  #[cfg(desktop)]
+  #[error("Watching the data folder failed: {0}")]
+  Watch(#[from] notify_debouncer_mini::notify::Error),
+  #[cfg(desktop)]
+  #[error("Microphone error: {0}")]
+  Audio(String),
+  #[cfg(desktop)]
+  #[error("Bluetooth error: {0}")]
+  Bluetooth(#[from] btleplug::Error),
+  #[cfg(desktop)]
+  #[error("Serial port error: {0}")]
+  Serial(#[from] serialport::Error),
+  #[error("Network discovery failed: {0}")]
+  Mdns(#[from] mdns_sd::Error),
+  #[error("Sync connection failed: {0}")]
+  WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+  #[error("Sync failed: {0}")]
+  Sync(String),
+  #[error("Failed to make QR code: {0}")]
+  Qr(#[from] qrcode::types::QrError),
+  #[error("TeamSnap: {0}")]
+  TeamSnap(String),
+  #[error("Cloud backup: {0}")]
+  CloudBackup(String),
+  #[error("Weather forecast failed: {0}")]
+  Weather(String),
+  #[error("Clipboard error: {0}")]
+  Clipboard(#[from] tauri_plugin_clipboard_manager::Error),
+  #[error("Search index error: {0}")]
+  SearchIndex(#[from] tantivy::TantivyError),
+  #[error("Printing failed: {0}")]
+  Print(String),
+  #[error("{0}")]
+  Validation(String),
+  #[error("{0} not found")]
+  NotFound(String),
+}
+
+/// Commands surface errors to the webview as plain messages.
+impl Serialize for Error {
+  fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&self.to_string())
+  }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,499 @@
+//! A diagram of the defense: who plays where in an inning, drawn on a
+//! field, optionally with where balls in play landed. As a PNG it can go
+//! on a card or be printed and taped to the dugout fence; as an SVG it
+//! prints sharp on any paper.
+//!
+//! The field is laid out in feet from home plate, `x` toward the
+//! first-base side and `y` toward center field, as one list of shapes that
+//! either format draws.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use rusqlite::Connection;
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use super::pdf::{self, Card};
+use super::raster::{Canvas, Color, GLYPH};
+use crate::db::{roster, Database};
+use crate::error::{Error, Result};
+
+const DEFAULT_WIDTH: u32 = 1200;
+const MIN_WIDTH: u32 = 400;
+const MAX_WIDTH: u32 = 4000;
+
+/// The part of the field drawn, in feet, with room above the fence for
+/// the heading.
+const LEFT: f32 = -240.0;
+const RIGHT: f32 = 240.0;
+const BOTTOM: f32 = -40.0;
+const TOP: f32 = 365.0;
+
+const FENCE: f32 = 300.0;
+const BASE_PATH: f32 = 90.0;
+const MOUND: (f32, f32) = (0.0, 60.5);
+const INFIELD_ARC: f32 = 95.0;
+
+/// Text heights and radii, in feet like everything else.
+const HEADING_SIZE: f32 = 9.0;
+const LABEL_SIZE: f32 = 7.0;
+const MARKER_RADIUS: f32 = 10.0;
+const HIT_RADIUS: f32 = 4.0;
+
+const GRASS: Color = [0xa8, 0xd5, 0x8f];
+const DIRT: Color = [0xd8, 0xb0, 0x80];
+const CHALK: Color = [0xff, 0xff, 0xff];
+const INK: Color = [0x1a, 0x1a, 0x1a];
+const MARKER: Color = [0x1f, 0x3a, 0x68];
+
+/// Where each position stands, roughly where a coach would chalk it, with
+/// room under each marker for the name clear of the bases.
+const STATIONS: [(&str, (f32, f32)); 9] = [
+  ("P", MOUND),
+  ("C", (0.0, -12.0)),
+  ("1B", (80.0, 100.0)),
+  ("2B", (55.0, 155.0)),
+  ("3B", (-80.0, 100.0)),
+  ("SS", (-55.0, 155.0)),
+  ("LF", (-140.0, 225.0)),
+  ("CF", (0.0, 265.0)),
+  ("RF", (140.0, 225.0)),
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagramFormat {
+  #[default]
+  Png,
+  Svg,
+}
+
+impl DiagramFormat {
+  fn extension(self) -> &'static str {
+    match self {
+      Self::Png => "png",
+      Self::Svg => "svg",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HitResult {
+  Out,
+  Single,
+  Double,
+  Triple,
+  HomeRun,
+}
+
+impl HitResult {
+  fn color(self) -> Color {
+    match self {
+      Self::Out => [0x6b, 0x6b, 0x6b],
+      Self::Single => [0x2e, 0x7d, 0x32],
+      Self::Double => [0x15, 0x65, 0xc0],
+      Self::Triple => [0xef, 0x6c, 0x00],
+      Self::HomeRun => [0xc6, 0x28, 0x28],
+    }
+  }
+}
+
+/// Where a ball in play landed, in feet from home plate.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SprayHit {
+  pub x: f32,
+  pub y: f32,
+  pub result: HitResult,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DiagramOptions {
+  pub format: DiagramFormat,
+  /// The inning whose alignment is drawn; the first if unset.
+  pub inning: Option<u32>,
+  /// Width of a PNG in pixels, 1200 if unset; the height follows.
+  pub width: Option<u32>,
+  /// Balls in play to plot, e.g. one hitter's or the opponent's.
+  pub spray: Vec<SprayHit>,
+}
+
+/// A player standing at one position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fielder {
+  pub position: String,
+  /// Number and last name, e.g. "#12 Miller".
+  pub label: String,
+}
+
+type Point = (f32, f32);
+
+enum Shape {
+  Polygon(Vec<Point>),
+  Circle(Point, f32),
+  /// From, to and thickness.
+  Line(Point, Point, f32),
+  /// Centered on the point, this many feet tall.
+  Text(Point, String, f32),
+}
+
+fn label(name: &str, number: Option<u32>) -> String {
+  let last = name.split_whitespace().last().unwrap_or(name);
+  match number {
+    Some(number) => format!("#{number} {last}"),
+    None => last.to_string(),
+  }
+}
+
+/// Who plays where in `inning`. Lineups without inning-by-inning positions
+/// use each batter's position.
+pub fn fielders(conn: &Connection, card: &Card, inning: u32) -> Result<Vec<Fielder>> {
+  let lineup = &card.lineup;
+  if inning == 0 || inning > lineup.innings {
+    return Err(Error::Validation(format!("Inning {inning} isn't in this lineup")));
+  }
+  let in_inning: Vec<_> = lineup.positions.iter().filter(|p| p.inning == inning).collect();
+  if in_inning.is_empty() {
+    return Ok(
+      lineup
+        .slots
+        .iter()
+        .filter_map(|slot| {
+          Some(Fielder {
+            position: slot.position.clone()?,
+            label: label(slot.player_name.as_deref()?, slot.number),
+          })
+        })
+        .collect(),
+    );
+  }
+  // The pitcher may not bat when there's a DH, so names come from the roster
+  let players: HashMap<_, _> = roster::list(conn, &lineup.team_id)?
+    .into_iter()
+    .map(|p| (p.id.clone(), p))
+    .collect();
+  Ok(
+    in_inning
+      .into_iter()
+      .filter_map(|p| {
+        let player = players.get(&p.player_id)?;
+        Some(Fielder {
+          position: p.position.clone(),
+          label: label(&player.name, player.number),
+        })
+      })
+      .collect(),
+  )
+}
+
+/// Points along the circle around `center` from `from` to `to` degrees.
+fn arc(center: Point, radius: f32, from: f32, to: f32) -> impl Iterator<Item = Point> {
+  let steps = ((to - from).abs() / 3.0).ceil().max(1.0) as usize;
+  (0..=steps).map(move |i| {
+    let angle = (from + (to - from) * i as f32 / steps as f32).to_radians();
+    (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+  })
+}
+
+fn is_fair(point: Point) -> bool {
+  point.1 >= point.0.abs()
+}
+
+/// A small square standing on one corner, like a base seen from above.
+fn base(at: Point, size: f32) -> Shape {
+  let half = size / 2.0;
+  Shape::Polygon(vec![
+    (at.0, at.1 - half),
+    (at.0 + half, at.1),
+    (at.0, at.1 + half),
+    (at.0 - half, at.1),
+  ])
+}
+
+/// Everything on the diagram in drawing order, each with its color.
+fn scene(heading: &str, fielders: &[Fielder], spray: &[SprayHit]) -> Vec<(Shape, Color)> {
+  let corner = BASE_PATH / std::f32::consts::SQRT_2;
+  let pole = FENCE / std::f32::consts::SQRT_2;
+  let home = (0.0, 0.0);
+
+  let mut outfield = vec![home];
+  outfield.extend(arc(home, FENCE, 45.0, 135.0));
+  let mut infield = vec![home];
+  infield.extend(arc(MOUND, INFIELD_ARC, -45.0, 225.0).filter(|&p| is_fair(p)));
+  // The grass inside the base paths, a little smaller than the diamond
+  let inner = |x: f32, y: f32| (x * 0.8, corner + (y - corner) * 0.8);
+
+  let mut shapes = vec![
+    (Shape::Polygon(outfield), GRASS),
+    (Shape::Polygon(infield), DIRT),
+    (Shape::Circle(home, 13.0), DIRT),
+    (
+      Shape::Polygon(vec![
+        inner(0.0, 0.0),
+        inner(corner, corner),
+        inner(0.0, 2.0 * corner),
+        inner(-corner, corner),
+      ]),
+      GRASS,
+    ),
+    (Shape::Circle(MOUND, 9.0), DIRT),
+    // In ink, since they run along the edge of the page's white
+    (Shape::Line(home, (pole, pole), 1.0), INK),
+    (Shape::Line(home, (-pole, pole), 1.0), INK),
+    (base(home, 6.0), CHALK),
+    (base((corner, corner), 6.0), CHALK),
+    (base((0.0, 2.0 * corner), 6.0), CHALK),
+    (base((-corner, corner), 6.0), CHALK),
+  ];
+  for hit in spray {
+    shapes.push((Shape::Circle((hit.x, hit.y), HIT_RADIUS), hit.result.color()));
+  }
+  for fielder in fielders {
+    let Some(&(_, at)) = STATIONS.iter().find(|(p, _)| *p == fielder.position) else {
+      continue;
+    };
+    shapes.push((Shape::Circle(at, MARKER_RADIUS), MARKER));
+    shapes.push((Shape::Text(at, fielder.position.clone(), LABEL_SIZE), CHALK));
+    let below = (at.0, at.1 - MARKER_RADIUS - LABEL_SIZE);
+    shapes.push((Shape::Text(below, fielder.label.clone(), LABEL_SIZE), INK));
+  }
+  shapes.push((
+    Shape::Text((0.0, TOP - 2.0 * HEADING_SIZE), heading.to_string(), HEADING_SIZE),
+    INK,
+  ));
+  shapes
+}
+
+fn png(shapes: &[(Shape, Color)], width: u32) -> Result<Vec<u8>> {
+  let scale = width as f32 / (RIGHT - LEFT);
+  let height = ((TOP - BOTTOM) * scale).round() as usize;
+  let to_px = |(x, y): Point| ((x - LEFT) * scale, (TOP - y) * scale);
+  let mut canvas = Canvas::new(width as usize, height);
+  for (shape, color) in shapes {
+    match shape {
+      Shape::Polygon(points) => {
+        let points: Vec<_> = points.iter().copied().map(to_px).collect();
+        canvas.polygon(&points, *color);
+      }
+      Shape::Circle(center, radius) => canvas.circle(to_px(*center), radius * scale, *color),
+      Shape::Line(from, to, thickness) => {
+        canvas.line(to_px(*from), to_px(*to), thickness * scale, *color)
+      }
+      Shape::Text(at, text, size) => {
+        let font_scale = ((size * scale) / GLYPH as f32).round().max(1.0) as usize;
+        let (x, y) = to_px(*at);
+        let half_width = (text.chars().count() * GLYPH * font_scale) as f32 / 2.0;
+        let half_height = (GLYPH * font_scale) as f32 / 2.0;
+        // Negative coordinates saturate to the edge
+        let (left, top) = ((x - half_width) as usize, (y - half_height) as usize);
+        canvas.text(left, top, text, font_scale, *color);
+      }
+    }
+  }
+  canvas.png()
+}
+
+fn escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn svg(shapes: &[(Shape, Color)]) -> String {
+  let (width, height) = (RIGHT - LEFT, TOP - BOTTOM);
+  let to_view = |(x, y): Point| (x - LEFT, TOP - y);
+  let mut svg = format!(
+    "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {width} {height}\">\n\
+     <rect width=\"{width}\" height=\"{height}\" fill=\"#ffffff\"/>\n"
+  );
+  for (shape, [r, g, b]) in shapes {
+    let color = format!("#{r:02x}{g:02x}{b:02x}");
+    let element = match shape {
+      Shape::Polygon(points) => {
+        let points: Vec<_> = points
+          .iter()
+          .map(|&p| {
+            let (x, y) = to_view(p);
+            format!("{x:.1},{y:.1}")
+          })
+          .collect();
+        format!("<polygon points=\"{}\" fill=\"{color}\"/>", points.join(" "))
+      }
+      Shape::Circle(center, radius) => {
+        let (x, y) = to_view(*center);
+        format!("<circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"{radius}\" fill=\"{color}\"/>")
+      }
+      Shape::Line(from, to, thickness) => {
+        let ((x1, y1), (x2, y2)) = (to_view(*from), to_view(*to));
+        format!(
+          "<line x1=\"{x1:.1}\" y1=\"{y1:.1}\" x2=\"{x2:.1}\" y2=\"{y2:.1}\" \
+           stroke=\"{color}\" stroke-width=\"{thickness}\"/>"
+        )
+      }
+      Shape::Text(at, text, size) => {
+        let (x, y) = to_view(*at);
+        format!(
+          "<text x=\"{x:.1}\" y=\"{y:.1}\" font-size=\"{size}\" fill=\"{color}\" \
+           font-family=\"Helvetica, Arial, sans-serif\" font-weight=\"bold\" \
+           text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+          escape(text)
+        )
+      }
+    };
+    svg.push_str(&element);
+    svg.push('\n');
+  }
+  svg.push_str("</svg>\n");
+  svg
+}
+
+/// Draws the defense in `fielders` as `options` asks, headed like the
+/// lineup card.
+pub fn render(card: &Card, fielders: &[Fielder], options: &DiagramOptions) -> Result<Vec<u8>> {
+  let inning = options.inning.unwrap_or(1);
+  let heading = format!("{}, inning {inning}", card.heading());
+  let shapes = scene(&heading, fielders, &options.spray);
+  match options.format {
+    DiagramFormat::Png => {
+      let width = options.width.unwrap_or(DEFAULT_WIDTH).clamp(MIN_WIDTH, MAX_WIDTH);
+      png(&shapes, width)
+    }
+    DiagramFormat::Svg => Ok(svg(&shapes).into_bytes()),
+  }
+}
+
+/// Draws a lineup's defensive alignment in one inning as a PNG or SVG,
+/// with any spray hits plotted. Without `path`, asks where to save it;
+/// returns `None` if the user cancels.
+#[tauri::command]
+pub async fn render_field_diagram(
+  app: AppHandle,
+  lineup_id: String,
+  options: Option<DiagramOptions>,
+  path: Option<PathBuf>,
+) -> Result<Option<PathBuf>> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let options = options.unwrap_or_default();
+    let (card, fielders) = {
+      let db = app.state::<Database>();
+      let conn = db.conn();
+      let card = pdf::card(&conn, &lineup_id)?;
+      let fielders = fielders(&conn, &card, options.inning.unwrap_or(1))?;
+      (card, fielders)
+    };
+    let bytes = render(&card, &fielders, &options)?;
+    let extension = options.format.extension();
+    let path = match path {
+      Some(path) => path,
+      None => {
+        let file_name = format!("{} field.{extension}", super::file_stem(&card.title()));
+        let filter = match options.format {
+          DiagramFormat::Png => "PNG image",
+          DiagramFormat::Svg => "SVG image",
+        };
+        match super::pick_save_path(&app, &file_name, filter, &[extension]) {
+          Some(path) => path,
+          None => return Ok(None),
+        }
+      }
+    };
+    std::fs::write(&path, bytes)?;
+    log::info!("Saved field diagram to {:?}", path);
+    Ok(Some(path))
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::lineups::{self, InningPosition, LineupInput, SlotInput};
+  use crate::db::open_in_memory;
+  use crate::db::roster::NewPlayer;
+
+  fn player(conn: &Connection, name: &str, number: u32) -> String {
+    roster::create(
+      conn,
+      NewPlayer {
+        team_id: None,
+        name: name.into(),
+        number: Some(number),
+        primary_position: "P".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap()
+    .id
+  }
+
+  #[test]
+  fn draws_each_inning_with_the_spray_chart() {
+    let mut conn = open_in_memory();
+    let jake = player(&conn, "Jake Miller", 12);
+    let ava = player(&conn, "Ava Chen", 3);
+    let slot = |slot_number: u32, player_id: &str, position: &str| SlotInput {
+      slot_number,
+      player_id: Some(player_id.into()),
+      position: Some(position.into()),
+    };
+    let at = |inning: u32, position: &str, player_id: &str| InningPosition {
+      inning,
+      position: position.into(),
+      player_id: player_id.into(),
+    };
+    let lineup = lineups::save(
+      &mut conn,
+      LineupInput {
+        id: None,
+        team_id: None,
+        name: "Opening Day".into(),
+        game_id: None,
+        use_dh: false,
+        innings: 3,
+        slots: vec![slot(1, &ava, "CF"), slot(2, &jake, "SS")],
+        positions: vec![at(2, "P", &jake), at(2, "SS", &ava)],
+      },
+    )
+    .unwrap();
+    let card = pdf::card(&conn, &lineup.id).unwrap();
+
+    let batting_positions = fielders(&conn, &card, 1).unwrap();
+    assert_eq!(batting_positions[1].position, "SS");
+    assert_eq!(batting_positions[1].label, "#12 Miller");
+    let second = fielders(&conn, &card, 2).unwrap();
+    assert_eq!(second[0].position, "P");
+    assert!(fielders(&conn, &card, 4).is_err());
+
+    let options = DiagramOptions {
+      inning: Some(2),
+      spray: vec![SprayHit {
+        x: -20.0,
+        y: 250.0,
+        result: HitResult::Double,
+      }],
+      ..Default::default()
+    };
+    let image = render(&card, &second, &options).unwrap();
+    let reader = png::Decoder::new(image.as_slice()).read_info().unwrap();
+    let info = reader.info();
+    assert_eq!((info.width, info.height), (1200, 1013));
+
+    let options = DiagramOptions {
+      format: DiagramFormat::Svg,
+      ..options
+    };
+    let svg = String::from_utf8(render(&card, &second, &options).unwrap()).unwrap();
+    assert!(svg.contains(">#12 Miller</text>"));
+    assert!(svg.contains(", inning 2</text>"));
+    assert!(svg.contains("fill=\"#1565c0\""));
+  }
+}
@@ -0,0 +1,85 @@
+//! A lineup as CSV: the batting order, one row per filled slot, with each
+//! player's position in every inning, for league admins who collect
+//! lineups in a spreadsheet.
+
+use crate::error::{Error, Result};
+
+use super::pdf::Card;
+
+/// Renders the card's batting order, e.g.
+/// `Order,#,Player,Pos,Inning 1,Inning 2` then `1,12,Jake Miller,SS,SS,P`.
+/// A player sitting out an inning has a blank cell for it.
+pub fn render(card: &Card) -> Result<Vec<u8>> {
+  let lineup = &card.lineup;
+  let mut writer = csv::Writer::from_writer(Vec::new());
+  let mut headers = vec!["Order".to_string(), "#".into(), "Player".into(), "Pos".into()];
+  headers.extend((1..=lineup.innings).map(|inning| format!("Inning {inning}")));
+  writer.write_record(&headers)?;
+
+  for slot in &lineup.slots {
+    let (Some(player_id), Some(name)) = (&slot.player_id, &slot.player_name) else {
+      continue;
+    };
+    let mut record = vec![
+      slot.slot_number.to_string(),
+      slot.number.map_or(String::new(), |n| n.to_string()),
+      name.clone(),
+      slot.position.clone().unwrap_or_default(),
+    ];
+    record.extend(
+      (1..=lineup.innings)
+        .map(|inning| lineup.position_in(player_id, inning).unwrap_or_default().to_string()),
+    );
+    writer.write_record(&record)?;
+  }
+  writer.into_inner().map_err(|e| Error::Io(e.into_error()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::lineups::{InningPosition, Lineup, LineupSlot};
+
+  #[test]
+  fn writes_positions_by_inning() {
+    let slot = |slot_number: u32, player: Option<(&str, u32)>, position: &str| LineupSlot {
+      slot_number,
+      player_id: player.map(|(name, _)| name.to_lowercase()),
+      player_name: player.map(|(name, _)| name.into()),
+      number: player.map(|(_, number)| number),
+      position: Some(position.into()),
+    };
+    let at = |inning: u32, position: &str, player_id: &str| InningPosition {
+      inning,
+      position: position.into(),
+      player_id: player_id.into(),
+    };
+    let card = Card {
+      team_name: "Eagles".into(),
+      game: None,
+      lineup: Lineup {
+        id: "lineup".into(),
+        team_id: "team".into(),
+        name: "Opening Day".into(),
+        game_id: None,
+        use_dh: false,
+        innings: 2,
+        slots: vec![
+          slot(1, Some(("Ava", 3)), "CF"),
+          slot(2, None, "SS"),
+          slot(3, Some(("Jake, Jr.", 12)), "SS"),
+        ],
+        positions: vec![at(1, "CF", "ava"), at(2, "CF", "ava"), at(1, "SS", "jake, jr.")],
+        updated_at: String::new(),
+        versions: Default::default(),
+      },
+      bench: Vec::new(),
+    };
+
+    let csv = String::from_utf8(render(&card).unwrap()).unwrap();
+    assert_eq!(
+      csv,
+      "Order,#,Player,Pos,Inning 1,Inning 2\n1,3,Ava,CF,CF,CF\n3,12,\"Jake, Jr.\",SS,SS,\n"
+    );
+  }
+}
@@ -0,0 +1,44 @@
+//! Files coaches take away from the app: spreadsheets, stats CSVs,
+//! printable cards, field diagrams and a website for the families.
+
+pub mod field;
+pub mod lineup_csv;
+pub mod parent_site;
+pub mod pdf;
+pub mod raster;
+pub mod stats_csv;
+pub mod xlsx;
+
+use std::path::PathBuf;
+
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+/// Shows the native save dialog, returning `None` if the user cancels.
+/// Blocks, so call it off the main thread.
+pub(crate) fn pick_save_path(
+  app: &AppHandle,
+  file_name: &str,
+  filter: &str,
+  extensions: &[&str],
+) -> Option<PathBuf> {
+  app
+    .dialog()
+    .file()
+    .set_file_name(file_name)
+    .add_filter(filter, extensions)
+    .blocking_save_file()
+    .and_then(|path| path.into_path().ok())
+}
+
+/// Makes `name` safe to use as a file name on every platform.
+pub(crate) fn file_stem(name: &str) -> String {
+  let stem: String = name
+    .chars()
+    .map(|c| match c {
+      '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+      c => c,
+    })
+    .collect();
+  stem.trim().to_string()
+}
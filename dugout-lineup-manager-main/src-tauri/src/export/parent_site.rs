@@ -0,0 +1,404 @@
+//! A static website for the team's families: the schedule, the roster and
+//! season stats as plain HTML pages that can be copied onto a league site
+//! or any web host as they are.
+//!
+//! Every page is the `parent_site.html` template filled in here, with its
+//! styles inline and no scripts, so the folder works wherever it lands,
+//! opened straight from disk included. Players are named according to
+//! [`NameDisplay`], and notes, contact details and inactive players are
+//! never published.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDate, NaiveTime};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+use super::stats_csv::rate;
+use crate::db::{games, roster, Database};
+use crate::error::{Error, Result};
+use crate::stats::{self, StatLines};
+
+const TEMPLATE: &str = include_str!("parent_site.html");
+
+const SCHEDULE_PAGE: &str = "index.html";
+const ROSTER_PAGE: &str = "roster.html";
+const STATS_PAGE: &str = "stats.html";
+
+/// How much of a player's name is published.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NameDisplay {
+  Full,
+  /// "Ava C.", the usual choice for youth teams.
+  #[default]
+  FirstNameLastInitial,
+  /// "A.C."
+  Initials,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ParentSiteOptions {
+  pub names: NameDisplay,
+  /// A year, to publish one season; every season otherwise.
+  pub season_id: Option<String>,
+  /// Leaves the stats page out, for leagues that don't publish them.
+  pub hide_stats: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParentSite {
+  pub dir: PathBuf,
+  /// The pages written, the schedule (`index.html`) first.
+  pub pages: Vec<PathBuf>,
+}
+
+fn escape(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+/// Replaces each `{{key}}` in `template` with its value, in one pass so a
+/// value that happens to contain a placeholder is left alone.
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+  let mut out = String::with_capacity(template.len());
+  let mut rest = template;
+  while let Some(start) = rest.find("{{") {
+    out.push_str(&rest[..start]);
+    let after = &rest[start + 2..];
+    let value = after
+      .find("}}")
+      .and_then(|end| Some((values.iter().find(|(key, _)| *key == &after[..end])?.1, end)));
+    match value {
+      Some((value, end)) => {
+        out.push_str(value);
+        rest = &after[end + 2..];
+      }
+      None => {
+        out.push_str("{{");
+        rest = after;
+      }
+    }
+  }
+  out.push_str(rest);
+  out
+}
+
+pub fn display_name(name: &str, display: NameDisplay) -> String {
+  let parts: Vec<&str> = name.split_whitespace().collect();
+  let initial = |part: &str| part.chars().next().map(|c| format!("{c}."));
+  match (display, parts.as_slice()) {
+    (NameDisplay::Full, _) | (_, []) => parts.join(" "),
+    (NameDisplay::FirstNameLastInitial, [first]) => first.to_string(),
+    (NameDisplay::FirstNameLastInitial, [first, .., last]) => {
+      format!("{} {}", first, initial(last).unwrap_or_default())
+    }
+    (NameDisplay::Initials, parts) => parts.iter().filter_map(|p| initial(p)).collect(),
+  }
+}
+
+/// A table with the columns from `numeric_from` on right-aligned. Cells
+/// are escaped here.
+fn table(headers: &[&str], numeric_from: usize, rows: &[Vec<String>]) -> String {
+  if rows.is_empty() {
+    return "<p class=\"empty\">Nothing here yet.</p>".into();
+  }
+  let mut html = String::from("<div class=\"table\"><table><thead><tr>");
+  for header in headers {
+    html.push_str(&format!("<th>{}</th>", escape(header)));
+  }
+  html.push_str("</tr></thead><tbody>");
+  for row in rows {
+    html.push_str("<tr>");
+    for (i, cell) in row.iter().enumerate() {
+      let class = if i >= numeric_from { " class=\"number\"" } else { "" };
+      html.push_str(&format!("<td{}>{}</td>", class, escape(cell)));
+    }
+    html.push_str("</tr>");
+  }
+  html.push_str("</tbody></table></div>");
+  html
+}
+
+fn in_season(date: &str, season_id: Option<&str>) -> bool {
+  season_id.map_or(true, |season| date.starts_with(season))
+}
+
+fn schedule(conn: &Connection, team_id: &str, season_id: Option<&str>) -> Result<String> {
+  let rows: Vec<Vec<String>> = games::list(conn, team_id)?
+    .into_iter()
+    .filter(|game| in_season(&game.date, season_id))
+    .map(|game| {
+      let date = NaiveDate::parse_from_str(&game.date, "%Y-%m-%d")
+        .map_or(game.date.clone(), |d| d.format("%a, %b %-d").to_string());
+      let time = game.start_time.as_deref().map_or(String::new(), |time| {
+        NaiveTime::parse_from_str(time, "%H:%M")
+          .map_or(time.to_string(), |t| t.format("%-I:%M %p").to_string())
+      });
+      let matchup = if game.home_away == "away" { "at" } else { "vs" };
+      let result = match (&game.result, game.score_us, game.score_them) {
+        (Some(result), Some(us), Some(them)) => format!("{result} {us}-{them}"),
+        _ => String::new(),
+      };
+      vec![date, time, format!("{} {}", matchup, game.opponent), game.location, result]
+    })
+    .collect();
+  Ok(table(&["Date", "Time", "Opponent", "Field", "Result"], 5, &rows))
+}
+
+fn roster_table(conn: &Connection, team_id: &str, names: NameDisplay) -> Result<String> {
+  let rows: Vec<Vec<String>> = roster::list(conn, team_id)?
+    .into_iter()
+    .filter(|player| player.status == "active")
+    .map(|player| {
+      vec![
+        player.number.map_or(String::new(), |n| n.to_string()),
+        display_name(&player.name, names),
+        player.primary_position,
+      ]
+    })
+    .collect();
+  Ok(table(&["#", "Player", "Position"], 3, &rows))
+}
+
+fn stats_tables(
+  conn: &Connection,
+  team_id: &str,
+  season_id: Option<&str>,
+  names: NameDisplay,
+) -> Result<String> {
+  let mut stmt = conn.prepare(&format!(
+    "SELECT p.name, p.number, {} FROM game_stats s \
+     JOIN players p ON p.id = s.player_id \
+     JOIN games g ON g.id = s.game_id \
+     WHERE p.team_id = ?1 AND p.status = 'active' \
+       AND (?2 IS NULL OR substr(g.date, 1, 4) = ?2) \
+     GROUP BY p.id ORDER BY p.number IS NULL, p.number, p.name COLLATE NOCASE",
+    stats::TOTALS
+  ))?;
+  let mut batting = Vec::new();
+  let mut pitching = Vec::new();
+  let mut rows = stmt.query(rusqlite::params![team_id, season_id])?;
+  while let Some(row) = rows.next()? {
+    let name = display_name(&row.get::<_, String>(0)?, names);
+    let number = row.get::<_, Option<u32>>(1)?.map_or(String::new(), |n| n.to_string());
+    let StatLines {
+      games,
+      batting: b,
+      pitching: p,
+      ..
+    } = stats::from_totals(row, 2)?;
+    batting.push(vec![
+      number.clone(),
+      name.clone(),
+      games.to_string(),
+      b.ab.to_string(),
+      b.r.to_string(),
+      b.h.to_string(),
+      b.rbi.to_string(),
+      b.bb.to_string(),
+      b.sb.to_string(),
+      rate(b.avg, 3),
+      rate(b.obp, 3),
+    ]);
+    if p.appearances > 0 {
+      pitching.push(vec![
+        number,
+        name,
+        p.appearances.to_string(),
+        format!("{:.1}", p.ip),
+        p.k.to_string(),
+        p.bb.to_string(),
+        rate(p.era, 2),
+      ]);
+    }
+  }
+  let batting_headers = ["#", "Player", "G", "AB", "R", "H", "RBI", "BB", "SB", "AVG", "OBP"];
+  let pitching_headers = ["#", "Player", "G", "IP", "SO", "BB", "ERA"];
+  Ok(format!(
+    "<h3>Batting</h3>{}<h3>Pitching</h3>{}",
+    table(&batting_headers, 2, &batting),
+    table(&pitching_headers, 2, &pitching),
+  ))
+}
+
+/// Writes the site into `dir` for `team_id`, replacing the pages of an
+/// earlier export.
+pub fn write(
+  conn: &Connection,
+  team_id: &str,
+  options: &ParentSiteOptions,
+  dir: &Path,
+) -> Result<ParentSite> {
+  let team = roster::team_name(conn, team_id)?;
+  let season = options.season_id.as_deref();
+  let mut pages = vec![
+    (SCHEDULE_PAGE, "Schedule", schedule(conn, team_id, season)?),
+    (ROSTER_PAGE, "Roster", roster_table(conn, team_id, options.names)?),
+  ];
+  if !options.hide_stats {
+    pages.push((STATS_PAGE, "Stats", stats_tables(conn, team_id, season, options.names)?));
+  }
+
+  let team_title = match season {
+    Some(season) => format!("{team} {season}"),
+    None => team,
+  };
+  let updated = chrono::Local::now().format("%B %-d, %Y").to_string();
+  std::fs::create_dir_all(dir)?;
+  let mut written = Vec::new();
+  for (file, title, content) in &pages {
+    let nav: String = pages
+      .iter()
+      .map(|(href, label, _)| {
+        let current = if href == file { " aria-current=\"page\"" } else { "" };
+        format!("<a href=\"{href}\"{current}>{label}</a>")
+      })
+      .collect();
+    let html = fill(
+      TEMPLATE,
+      &[
+        ("title", *title),
+        ("team", &escape(&team_title)),
+        ("nav", &nav),
+        ("content", content.as_str()),
+        ("updated", &updated),
+      ],
+    );
+    let path = dir.join(file);
+    std::fs::write(&path, html)?;
+    written.push(path);
+  }
+  // The stats page of an earlier export would otherwise stay reachable
+  if options.hide_stats {
+    let _ = std::fs::remove_file(dir.join(STATS_PAGE));
+  }
+  Ok(ParentSite {
+    dir: dir.to_path_buf(),
+    pages: written,
+  })
+}
+
+/// Writes the parent site into `dir`, defaulting to the first team. Without
+/// `dir`, asks for a folder and returns `None` if the user cancels.
+#[tauri::command]
+pub async fn export_parent_site(
+  app: AppHandle,
+  team_id: Option<String>,
+  options: Option<ParentSiteOptions>,
+  dir: Option<PathBuf>,
+) -> Result<Option<ParentSite>> {
+  let options = options.unwrap_or_default();
+  if let Some(season) = &options.season_id {
+    if season.len() != 4 || !season.chars().all(|c| c.is_ascii_digit()) {
+      return Err(Error::Validation(format!("Season must be a year, not {season:?}")));
+    }
+  }
+  tauri::async_runtime::spawn_blocking(move || {
+    let db = app.state::<Database>();
+    let team_id = match team_id {
+      Some(team_id) => team_id,
+      None => roster::default_team_id(&db.conn())?,
+    };
+    let dir = match dir {
+      Some(dir) => dir,
+      None => match app
+        .dialog()
+        .file()
+        .set_title("Choose a folder for the team website")
+        .blocking_pick_folder()
+        .and_then(|path| path.into_path().ok())
+      {
+        Some(dir) => dir,
+        None => return Ok(None),
+      },
+    };
+    let site = write(&db.conn(), &team_id, &options, &dir)?;
+    log::info!("Exported the parent site to {:?}", dir);
+    Ok(Some(site))
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::games::ScheduledGame;
+  use crate::db::roster::NewPlayer;
+
+  #[test]
+  fn publishes_filtered_names_and_escaped_text() {
+    assert_eq!(display_name("Ava  Chen", NameDisplay::Full), "Ava Chen");
+    assert_eq!(display_name("Ava Mae Chen", NameDisplay::FirstNameLastInitial), "Ava C.");
+    assert_eq!(display_name("Ava", NameDisplay::FirstNameLastInitial), "Ava");
+    assert_eq!(display_name("Ava Chen", NameDisplay::Initials), "A.C.");
+    assert_eq!(fill("{{a}} {{b}} {{c}}", &[("a", "{{b}}"), ("b", "<")]), "{{b}} < {{c}}");
+
+    let conn = crate::db::open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    for (name, status) in [("Ava Chen", None), ("Jake Miller", Some("inactive".to_string()))] {
+      roster::create(
+        &conn,
+        NewPlayer {
+          team_id: Some(team_id.clone()),
+          name: name.into(),
+          number: Some(7),
+          primary_position: "SS".into(),
+          secondary_positions: Vec::new(),
+          bats: "R".into(),
+          throws: "R".into(),
+          status,
+          notes: Some("Allergic to peanuts".into()),
+        },
+      )
+      .unwrap();
+    }
+    let game = ScheduledGame {
+      id: "g1".into(),
+      date: "2026-04-11".into(),
+      opponent: "Eagles <B>".into(),
+      home_away: "away".into(),
+      score_us: Some(5),
+      score_them: Some(3),
+      notes: "Bring the spare catcher's gear".into(),
+      location: "Riverside Park".into(),
+      start_time: Some("18:30".into()),
+    };
+    games::upsert_scheduled(&conn, &team_id, &game).unwrap();
+
+    let dir = std::env::temp_dir().join(format!("dugout-parent-site-{}", uuid::Uuid::new_v4()));
+    let options = ParentSiteOptions {
+      hide_stats: true,
+      ..Default::default()
+    };
+    let site = write(&conn, &team_id, &options, &dir).unwrap();
+    assert_eq!(site.pages, [dir.join(SCHEDULE_PAGE), dir.join(ROSTER_PAGE)]);
+    let read = |page: &str| std::fs::read_to_string(dir.join(page)).unwrap();
+
+    let schedule = read(SCHEDULE_PAGE);
+    assert!(schedule.contains("<td>at Eagles &lt;B&gt;</td>"));
+    assert!(schedule.contains("<td>Sat, Apr 11</td><td>6:30 PM</td>"));
+    assert!(!schedule.contains("catcher"));
+    assert!(!schedule.contains("stats.html"));
+
+    let roster = read(ROSTER_PAGE);
+    assert!(roster.contains("<td>Ava C.</td>"));
+    assert!(!roster.contains("Chen") && !roster.contains("Jake") && !roster.contains("peanuts"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
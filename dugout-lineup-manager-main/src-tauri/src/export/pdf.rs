@@ -0,0 +1,747 @@
+//! Printable lineup card rendered straight to PDF.
+//!
+//! Text uses the base-14 Helvetica fonts every PDF reader ships, so no font
+//! data is embedded; strings are encoded as WinAnsi, which covers the Latin
+//! names on a roster.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use miniz_oxide::deflate::{compress_to_vec_zlib, CompressionLevel};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str};
+use rusqlite::Connection;
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::db::lineups::{self, Lineup};
+use crate::db::{games, roster, Database};
+use crate::error::{Error, Result};
+use crate::telemetry::{Feature, Telemetry};
+
+const MARGIN: f32 = 36.0;
+const LOGO_HEIGHT: f32 = 54.0;
+/// Space kept under the grid for the bench list.
+const BENCH_RESERVE: f32 = 54.0;
+
+const REGULAR: Name<'static> = Name(b"F1");
+const BOLD: Name<'static> = Name(b"F2");
+const LOGO: Name<'static> = Name(b"Im1");
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PaperSize {
+  #[default]
+  Letter,
+  Legal,
+  A4,
+  A5,
+  /// 5.5 x 8.5 in, a common dugout card size.
+  HalfLetter,
+}
+
+impl PaperSize {
+  /// Width and height in points.
+  fn dimensions(self) -> (f32, f32) {
+    match self {
+      Self::Letter => (612.0, 792.0),
+      Self::Legal => (612.0, 1008.0),
+      Self::A4 => (595.28, 841.89),
+      Self::A5 => (419.53, 595.28),
+      Self::HalfLetter => (396.0, 612.0),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CardOptions {
+  pub paper_size: PaperSize,
+  /// PNG printed in the card's header.
+  pub logo_path: Option<PathBuf>,
+}
+
+/// Everything printed on a lineup card.
+#[derive(Debug, Clone)]
+pub struct Card {
+  pub team_name: String,
+  /// Opponent and date when the lineup belongs to a game.
+  pub game: Option<String>,
+  pub lineup: Lineup,
+  /// Active players who aren't in the batting order.
+  pub bench: Vec<String>,
+}
+
+impl Card {
+  /// Suggested file name, without extension.
+  pub fn title(&self) -> String {
+    format!(
+      "{} - {}",
+      self.team_name,
+      self.game.as_deref().unwrap_or(&self.lineup.name)
+    )
+  }
+
+  /// One line naming the lineup, e.g. "Eagles lineup vs Tigers · 2026-04-11".
+  pub fn heading(&self) -> String {
+    match &self.game {
+      Some(game) => format!("{} lineup {game}", self.team_name),
+      None => format!("{} lineup: {}", self.team_name, self.lineup.name),
+    }
+  }
+
+  /// The filled slots as text, e.g. "1. #12 Jake Miller, SS".
+  pub fn batting_order(&self) -> Vec<String> {
+    self
+      .lineup
+      .slots
+      .iter()
+      .filter_map(|slot| {
+        let name = slot.player_name.as_ref()?;
+        let mut line = format!("{}. ", slot.slot_number);
+        if let Some(number) = slot.number {
+          line.push_str(&format!("#{number} "));
+        }
+        line.push_str(name);
+        if let Some(position) = &slot.position {
+          line.push_str(&format!(", {position}"));
+        }
+        Some(line)
+      })
+      .collect()
+  }
+}
+
+pub fn card(conn: &Connection, lineup_id: &str) -> Result<Card> {
+  let lineup = lineups::get(conn, lineup_id)?;
+  let team_name = roster::team_name(conn, &lineup.team_id)?;
+  let game = match &lineup.game_id {
+    Some(id) => games::get(conn, id).ok().map(|g| {
+      let venue = if g.home_away == "away" { "@" } else { "vs" };
+      format!("{venue} {} \u{b7} {}", g.opponent, g.date)
+    }),
+    None => None,
+  };
+  let batting: HashSet<&str> = lineup
+    .slots
+    .iter()
+    .filter_map(|s| s.player_id.as_deref())
+    .collect();
+  let bench = roster::list(conn, &lineup.team_id)?
+    .into_iter()
+    .filter(|p| p.status == "active" && !batting.contains(p.id.as_str()))
+    .map(|p| match p.number {
+      Some(number) => format!("#{number} {}", p.name),
+      None => p.name,
+    })
+    .collect();
+  Ok(Card {
+    team_name,
+    game,
+    lineup,
+    bench,
+  })
+}
+
+struct Logo {
+  width: u32,
+  height: u32,
+  rgb: Vec<u8>,
+  alpha: Option<Vec<u8>>,
+}
+
+fn load_logo(path: &Path) -> Result<Logo> {
+  let invalid = |e: png::DecodingError| Error::Validation(format!("Could not read logo: {e}"));
+  let mut decoder = png::Decoder::new(std::fs::File::open(path)?);
+  decoder.set_transformations(png::Transformations::normalize_to_color8());
+  let mut reader = decoder.read_info().map_err(invalid)?;
+  let mut buf = vec![0; reader.output_buffer_size()];
+  let info = reader.next_frame(&mut buf).map_err(invalid)?;
+  buf.truncate(info.buffer_size());
+
+  let (rgb, alpha) = match info.color_type {
+    png::ColorType::Rgb => (buf, None),
+    png::ColorType::Rgba => {
+      let rgb = buf.chunks(4).flat_map(|px| [px[0], px[1], px[2]]).collect();
+      let alpha = buf.chunks(4).map(|px| px[3]).collect();
+      (rgb, Some(alpha))
+    }
+    png::ColorType::Grayscale => (buf.iter().flat_map(|&v| [v, v, v]).collect(), None),
+    png::ColorType::GrayscaleAlpha => {
+      let rgb = buf.chunks(2).flat_map(|px| [px[0], px[0], px[0]]).collect();
+      let alpha = buf.chunks(2).map(|px| px[1]).collect();
+      (rgb, Some(alpha))
+    }
+    png::ColorType::Indexed => {
+      return Err(Error::Validation("Unsupported logo color format".into()));
+    }
+  };
+  Ok(Logo {
+    width: info.width,
+    height: info.height,
+    rgb,
+    alpha,
+  })
+}
+
+/// Renders the card as a one-page PDF.
+pub fn render(card: &Card, options: &CardOptions) -> Result<Vec<u8>> {
+  render_all(std::slice::from_ref(card), options, |_| {})
+}
+
+/// Renders the cards as one PDF, a page each, sharing the fonts and logo.
+/// `on_page` is called with the number of pages done after each one.
+pub fn render_all(
+  cards: &[Card],
+  options: &CardOptions,
+  mut on_page: impl FnMut(usize),
+) -> Result<Vec<u8>> {
+  let logo = options.logo_path.as_deref().map(load_logo).transpose()?;
+  let (width, height) = options.paper_size.dimensions();
+  let pages = cards
+    .iter()
+    .enumerate()
+    .map(|(i, card)| {
+      let content = page(card, width, height, logo.as_ref());
+      on_page(i + 1);
+      content
+    })
+    .collect();
+  Ok(write_document(pages, width, height, logo.as_ref()))
+}
+
+fn page(card: &Card, width: f32, height: f32, logo: Option<&Logo>) -> Content {
+  let mut content = Content::new();
+  let left = MARGIN;
+  let right = width - MARGIN;
+  let mut top = height - MARGIN;
+
+  // Header: logo, team name, game and lineup name.
+  let mut text_x = left;
+  let mut header_height = 58.0_f32;
+  if let Some(logo) = logo {
+    let scale = LOGO_HEIGHT / logo.height as f32;
+    let logo_width = (logo.width as f32 * scale).min(LOGO_HEIGHT * 2.0);
+    content.save_state();
+    content.transform([logo_width, 0.0, 0.0, LOGO_HEIGHT, left, top - LOGO_HEIGHT]);
+    content.x_object(LOGO);
+    content.restore_state();
+    text_x += logo_width + 12.0;
+    header_height = header_height.max(LOGO_HEIGHT);
+  }
+  let text_width = right - text_x;
+  text(
+    &mut content,
+    BOLD,
+    20.0,
+    text_x,
+    top - 20.0,
+    &fit(&card.team_name, 20.0, text_width),
+  );
+  let mut line_y = top - 38.0;
+  if let Some(game) = &card.game {
+    text(
+      &mut content,
+      REGULAR,
+      12.0,
+      text_x,
+      line_y,
+      &fit(game, 12.0, text_width),
+    );
+    line_y -= 16.0;
+  }
+  if card.game.is_none() || card.lineup.name != card.team_name {
+    text(
+      &mut content,
+      REGULAR,
+      10.0,
+      text_x,
+      line_y,
+      &fit(&card.lineup.name, 10.0, text_width),
+    );
+  }
+  top -= header_height + 14.0;
+
+  // Batting order grid with a column per inning.
+  let lineup = &card.lineup;
+  let innings = lineup.innings.max(1);
+  let order_width = 24.0;
+  let number_width = 30.0;
+  let available = right - left;
+  let inning_width =
+    ((available - order_width - number_width - 120.0) / innings as f32).clamp(18.0, 40.0);
+  let name_width = available - order_width - number_width - inning_width * innings as f32;
+  let rows = lineup.slots.len().max(1);
+  let row_height = ((top - MARGIN - BENCH_RESERVE) / (rows + 1) as f32).clamp(14.0, 26.0);
+  let size = (row_height * 0.5).clamp(8.0, 12.0);
+
+  let mut columns = vec![left, left + order_width, left + order_width + number_width];
+  let first_inning_x = left + order_width + number_width + name_width;
+  columns.extend((0..=innings).map(|i| first_inning_x + inning_width * i as f32));
+  let baseline = |row: usize| top - row_height * (row as f32 + 0.5) - size * 0.35;
+
+  content.set_fill_gray(0.88);
+  content.rect(left, top - row_height, available, row_height);
+  content.fill_nonzero();
+  content.set_fill_gray(0.0);
+  centered(
+    &mut content,
+    BOLD,
+    size,
+    columns[0],
+    columns[1],
+    baseline(0),
+    "#",
+  );
+  centered(
+    &mut content,
+    BOLD,
+    size,
+    columns[1],
+    columns[2],
+    baseline(0),
+    "No.",
+  );
+  text(
+    &mut content,
+    BOLD,
+    size,
+    columns[2] + 4.0,
+    baseline(0),
+    "Player",
+  );
+  for inning in 1..=innings {
+    let x = columns[2 + inning as usize];
+    centered(
+      &mut content,
+      BOLD,
+      size,
+      x,
+      x + inning_width,
+      baseline(0),
+      &inning.to_string(),
+    );
+  }
+
+  for (i, slot) in lineup.slots.iter().enumerate() {
+    let y = baseline(i + 1);
+    centered(
+      &mut content,
+      REGULAR,
+      size,
+      columns[0],
+      columns[1],
+      y,
+      &slot.slot_number.to_string(),
+    );
+    if let Some(number) = slot.number {
+      centered(
+        &mut content,
+        REGULAR,
+        size,
+        columns[1],
+        columns[2],
+        y,
+        &number.to_string(),
+      );
+    }
+    let name = slot.player_name.as_deref().unwrap_or("");
+    text(
+      &mut content,
+      REGULAR,
+      size,
+      columns[2] + 4.0,
+      y,
+      &fit(name, size, name_width - 8.0),
+    );
+    let Some(player_id) = slot.player_id.as_deref() else {
+      continue;
+    };
+    for inning in 1..=innings {
+      let planned = lineup.positions.iter().any(|p| p.inning == inning);
+      let label = match lineup.position_in(player_id, inning) {
+        Some(position) => position,
+        // Only mark the bench for innings the coach has filled in.
+        None if planned => "BN",
+        None => continue,
+      };
+      let x = columns[2 + inning as usize];
+      centered(&mut content, REGULAR, size, x, x + inning_width, y, label);
+    }
+  }
+
+  let bottom = top - row_height * (rows + 1) as f32;
+  content.set_line_width(0.75);
+  for row in 0..=rows + 1 {
+    let y = top - row_height * row as f32;
+    content.move_to(left, y);
+    content.line_to(right, y);
+  }
+  for &x in &columns {
+    content.move_to(x, top);
+    content.line_to(x, bottom);
+  }
+  content.stroke();
+
+  // Bench list, wrapped under the grid.
+  let mut y = bottom - 20.0;
+  text(&mut content, BOLD, 11.0, left, y, "Bench:");
+  let indent = left + text_width_of("Bench: ", 11.0);
+  let bench = if card.bench.is_empty() {
+    "\u{2014}".to_string()
+  } else {
+    card.bench.join(", ")
+  };
+  for line in wrap(&bench, 11.0, right - indent) {
+    text(&mut content, REGULAR, 11.0, indent, y, &line);
+    y -= 14.0;
+  }
+
+  content
+}
+
+fn write_document(pages: Vec<Content>, width: f32, height: f32, logo: Option<&Logo>) -> Vec<u8> {
+  let catalog_id = Ref::new(1);
+  let page_tree_id = Ref::new(2);
+  let regular_id = Ref::new(3);
+  let bold_id = Ref::new(4);
+  let image_id = Ref::new(5);
+  let mask_id = Ref::new(6);
+  // Each page and its content stream follow the shared objects.
+  let page_ids: Vec<(Ref, Ref)> = (0..pages.len() as i32)
+    .map(|i| (Ref::new(7 + i * 2), Ref::new(8 + i * 2)))
+    .collect();
+
+  let mut pdf = Pdf::new();
+  pdf.catalog(catalog_id).pages(page_tree_id);
+  pdf
+    .pages(page_tree_id)
+    .kids(page_ids.iter().map(|&(page_id, _)| page_id))
+    .count(page_ids.len() as i32);
+
+  for &(page_id, content_id) in &page_ids {
+    let mut page = pdf.page(page_id);
+    page.media_box(Rect::new(0.0, 0.0, width, height));
+    page.parent(page_tree_id);
+    page.contents(content_id);
+    let mut resources = page.resources();
+    resources
+      .fonts()
+      .pair(REGULAR, regular_id)
+      .pair(BOLD, bold_id);
+    if logo.is_some() {
+      resources.x_objects().pair(LOGO, image_id);
+    }
+    resources.finish();
+    page.finish();
+  }
+
+  for (id, font) in [(regular_id, "Helvetica"), (bold_id, "Helvetica-Bold")] {
+    pdf
+      .type1_font(id)
+      .base_font(Name(font.as_bytes()))
+      .encoding_predefined(Name(b"WinAnsiEncoding"));
+  }
+
+  if let Some(logo) = logo {
+    let level = CompressionLevel::DefaultLevel as u8;
+    let encoded = compress_to_vec_zlib(&logo.rgb, level);
+    let mut image = pdf.image_xobject(image_id, &encoded);
+    image.filter(Filter::FlateDecode);
+    image.width(logo.width as i32);
+    image.height(logo.height as i32);
+    image.color_space().device_rgb();
+    image.bits_per_component(8);
+    if logo.alpha.is_some() {
+      image.s_mask(mask_id);
+    }
+    image.finish();
+
+    if let Some(alpha) = &logo.alpha {
+      let encoded = compress_to_vec_zlib(alpha, level);
+      let mut mask = pdf.image_xobject(mask_id, &encoded);
+      mask.filter(Filter::FlateDecode);
+      mask.width(logo.width as i32);
+      mask.height(logo.height as i32);
+      mask.color_space().device_gray();
+      mask.bits_per_component(8);
+    }
+  }
+
+  for (content, &(_, content_id)) in pages.into_iter().zip(&page_ids) {
+    pdf.stream(content_id, &content.finish());
+  }
+  pdf.finish()
+}
+
+fn text(content: &mut Content, font: Name, size: f32, x: f32, y: f32, value: &str) {
+  content.begin_text();
+  content.set_font(font, size);
+  content.next_line(x, y);
+  content.show(Str(&encode(value)));
+  content.end_text();
+}
+
+fn centered(content: &mut Content, font: Name, size: f32, x0: f32, x1: f32, y: f32, value: &str) {
+  let x = x0 + (x1 - x0 - text_width_of(value, size)) / 2.0;
+  text(content, font, size, x, y, value);
+}
+
+/// Encodes `value` as WinAnsi, replacing anything it can't represent.
+fn encode(value: &str) -> Vec<u8> {
+  value
+    .chars()
+    .map(|c| match c {
+      '\u{20}'..='\u{7e}' | '\u{a0}'..='\u{ff}' => c as u8,
+      '\u{2026}' => 0x85,
+      '\u{2018}' => 0x91,
+      '\u{2019}' => 0x92,
+      '\u{201c}' => 0x93,
+      '\u{201d}' => 0x94,
+      '\u{2022}' => 0x95,
+      '\u{2013}' => 0x96,
+      '\u{2014}' => 0x97,
+      _ => b'?',
+    })
+    .collect()
+}
+
+/// Helvetica advance widths for ASCII 32..=126, in thousandths of an em.
+const HELVETICA_WIDTHS: [u16; 95] = [
+  278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+  556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+  611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+  667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+  222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Approximate rendered width, close enough for fitting and centering
+/// (bold runs slightly wider).
+fn text_width_of(value: &str, size: f32) -> f32 {
+  let units: u32 = encode(value)
+    .into_iter()
+    .map(|b| match b {
+      32..=126 => u32::from(HELVETICA_WIDTHS[usize::from(b - 32)]),
+      _ => 556,
+    })
+    .sum();
+  units as f32 * size / 1000.0 * 1.05
+}
+
+/// Truncates `value` with an ellipsis so it fits in `max` points.
+fn fit(value: &str, size: f32, max: f32) -> String {
+  if text_width_of(value, size) <= max {
+    return value.to_string();
+  }
+  let mut chars: Vec<char> = value.chars().collect();
+  while !chars.is_empty() {
+    chars.pop();
+    let candidate = format!("{}\u{2026}", chars.iter().collect::<String>().trim_end());
+    if text_width_of(&candidate, size) <= max {
+      return candidate;
+    }
+  }
+  String::new()
+}
+
+/// Greedy word wrap to lines no wider than `max` points.
+fn wrap(value: &str, size: f32, max: f32) -> Vec<String> {
+  let mut lines = Vec::new();
+  let mut line = String::new();
+  for word in value.split(' ') {
+    let candidate = if line.is_empty() {
+      word.to_string()
+    } else {
+      format!("{line} {word}")
+    };
+    if !line.is_empty() && text_width_of(&candidate, size) > max {
+      lines.push(std::mem::replace(&mut line, word.to_string()));
+    } else {
+      line = candidate;
+    }
+  }
+  if !line.is_empty() {
+    lines.push(line);
+  }
+  lines
+}
+
+/// Renders the lineup card to a PDF. Without `path`, asks where to save
+/// it; returns `None` if the user cancels.
+#[tauri::command]
+pub async fn generate_lineup_pdf(
+  app: AppHandle,
+  lineup_id: String,
+  options: Option<CardOptions>,
+  path: Option<PathBuf>,
+) -> Result<Option<PathBuf>> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let card = card(&app.state::<Database>().conn(), &lineup_id)?;
+    let bytes = render(&card, &options.unwrap_or_default())?;
+    let path = match path {
+      Some(path) => path,
+      None => {
+        let file_name = format!("{}.pdf", super::file_stem(&card.title()));
+        match super::pick_save_path(&app, &file_name, "PDF document", &["pdf"]) {
+          Some(path) => path,
+          None => return Ok(None),
+        }
+      }
+    };
+    std::fs::write(&path, bytes)?;
+    log::info!("Saved lineup card to {:?}", path);
+    app.state::<Telemetry>().record(Feature::PdfExported);
+    Ok(Some(path))
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::lineups::{InningPosition, LineupInput, SlotInput};
+  use crate::db::open_in_memory;
+  use crate::db::roster::NewPlayer;
+
+  fn player(conn: &Connection, name: &str, number: u32) -> String {
+    roster::create(
+      conn,
+      NewPlayer {
+        team_id: None,
+        name: name.into(),
+        number: Some(number),
+        primary_position: "SS".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap()
+    .id
+  }
+
+  #[test]
+  fn renders_a_card_with_the_bench() {
+    let mut conn = open_in_memory();
+    let jake = player(&conn, "Jake Miller", 12);
+    let ava = player(&conn, "Ava Chen", 3);
+    player(&conn, "Sam Ortiz", 7);
+    let lineup = lineups::save(
+      &mut conn,
+      LineupInput {
+        id: None,
+        team_id: None,
+        name: "Opening Day".into(),
+        game_id: None,
+        use_dh: false,
+        innings: 3,
+        slots: vec![
+          SlotInput {
+            slot_number: 1,
+            player_id: Some(ava.clone()),
+            position: Some("CF".into()),
+          },
+          SlotInput {
+            slot_number: 2,
+            player_id: Some(jake.clone()),
+            position: Some("SS".into()),
+          },
+        ],
+        positions: vec![
+          InningPosition {
+            inning: 1,
+            position: "CF".into(),
+            player_id: ava,
+          },
+          InningPosition {
+            inning: 1,
+            position: "SS".into(),
+            player_id: jake,
+          },
+        ],
+      },
+    )
+    .unwrap();
+
+    let card = card(&conn, &lineup.id).unwrap();
+    assert_eq!(card.bench, ["#7 Sam Ortiz"]);
+    assert_eq!(card.title(), "My Team - Opening Day");
+
+    for paper_size in [PaperSize::Letter, PaperSize::HalfLetter] {
+      let options = CardOptions {
+        paper_size,
+        logo_path: None,
+      };
+      let pdf = render(&card, &options).unwrap();
+      assert!(pdf.starts_with(b"%PDF"));
+    }
+  }
+
+  #[test]
+  fn renders_the_upcoming_season_in_one_document() {
+    let mut conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let game = |id: &str, date: &str, score: Option<u32>| games::ScheduledGame {
+      id: id.into(),
+      date: date.into(),
+      opponent: format!("Opponent {id}"),
+      home_away: "home".into(),
+      score_us: score,
+      score_them: score,
+      notes: String::new(),
+      location: String::new(),
+      start_time: None,
+    };
+    for (id, date, score) in [
+      ("late", "2026-05-02", None),
+      ("played", "2026-04-01", Some(3)),
+      ("early", "2026-04-18", None),
+      ("unplanned", "2026-04-25", None),
+    ] {
+      games::upsert_scheduled(&conn, &team_id, &game(id, date, score)).unwrap();
+    }
+    let mut save = |name: &str, game_id: &str| {
+      lineups::save(
+        &mut conn,
+        LineupInput {
+          id: None,
+          team_id: None,
+          name: name.into(),
+          game_id: Some(game_id.into()),
+          use_dh: false,
+          innings: 6,
+          slots: Vec::new(),
+          positions: Vec::new(),
+        },
+      )
+      .unwrap()
+      .id
+    };
+    let late = save("Late", "late");
+    save("Played", "played");
+    save("Early draft", "early");
+    let early = save("Early final", "early");
+
+    let ids = lineups::for_scheduled_games(&conn, &team_id).unwrap();
+    assert_eq!(ids, [early, late]);
+
+    let cards: Vec<Card> = ids.iter().map(|id| card(&conn, id).unwrap()).collect();
+    let mut pages = Vec::new();
+    let pdf = render_all(&cards, &CardOptions::default(), |done| pages.push(done)).unwrap();
+    assert_eq!(pages, [1, 2]);
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("/Count 2"));
+    assert_eq!(text.matches("/Type /Page").count() - text.matches("/Type /Pages").count(), 2);
+  }
+
+  #[test]
+  fn fits_and_wraps_text() {
+    assert_eq!(encode("Jos\u{e9} \u{2014} \u{4e2d}"), b"Jos\xe9 \x97 ?");
+    assert_eq!(fit("Short", 10.0, 200.0), "Short");
+    let fitted = fit("Maximilian Alexander Featherstonehaugh", 10.0, 80.0);
+    assert!(fitted.ends_with('\u{2026}'));
+    assert!(text_width_of(&fitted, 10.0) <= 80.0);
+    assert_eq!(wrap("one two three", 10.0, 40.0), ["one two", "three"]);
+  }
+}
@@ -0,0 +1,129 @@
+//! A small RGBA canvas for the pictures the app draws itself: the lineup
+//! copied to the clipboard and the field diagram.
+//!
+//! Text uses an 8x8 bitmap font rather than a system one, so pictures look
+//! the same on every machine and need no font files. Shapes aren't
+//! antialiased, which keeps printed diagrams crisp.
+
+use font8x8::{UnicodeFonts, BASIC_FONTS, LATIN_FONTS};
+
+use crate::error::{Error, Result};
+
+/// Width and height of a glyph at scale 1.
+pub const GLYPH: usize = 8;
+
+pub type Color = [u8; 3];
+
+pub struct Canvas {
+  pub width: usize,
+  pub height: usize,
+  pub rgba: Vec<u8>,
+}
+
+impl Canvas {
+  pub fn new(width: usize, height: usize) -> Self {
+    // Opaque white
+    Self {
+      width,
+      height,
+      rgba: vec![0xff; width * height * 4],
+    }
+  }
+
+  pub fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: Color) {
+    for row in y..(y + height).min(self.height) {
+      self.span(row, x, x + width, color);
+    }
+  }
+
+  /// Colors pixels `from..to` of `row`, clipped to the canvas.
+  fn span(&mut self, row: usize, from: usize, to: usize, color: Color) {
+    for col in from..to.min(self.width) {
+      let at = (row * self.width + col) * 4;
+      self.rgba[at..at + 3].copy_from_slice(&color);
+    }
+  }
+
+  /// Draws `text` with its top left corner at `x`, `y`, each font pixel
+  /// `scale` pixels square.
+  pub fn text(&mut self, x: usize, y: usize, text: &str, scale: usize, color: Color) {
+    for (i, c) in text.chars().enumerate() {
+      let glyph = BASIC_FONTS
+        .get(c)
+        .or_else(|| LATIN_FONTS.get(c))
+        .or_else(|| BASIC_FONTS.get('?'))
+        .unwrap_or_default();
+      let left = x + i * GLYPH * scale;
+      for (row, bits) in glyph.iter().enumerate() {
+        for bit in 0..GLYPH {
+          if bits & (1 << bit) != 0 {
+            self.fill(left + bit * scale, y + row * scale, scale, scale, color);
+          }
+        }
+      }
+    }
+  }
+
+  /// Fills the polygon through `points` (in pixels), sampling each pixel
+  /// at its center.
+  pub fn polygon(&mut self, points: &[(f32, f32)], color: Color) {
+    let top = points.iter().map(|p| p.1).fold(f32::INFINITY, f32::min).max(0.0);
+    let bottom = points.iter().map(|p| p.1).fold(0.0, f32::max).min(self.height as f32);
+    let mut crossings = Vec::new();
+    for row in top as usize..bottom.ceil() as usize {
+      let y = row as f32 + 0.5;
+      crossings.clear();
+      for (i, &(x0, y0)) in points.iter().enumerate() {
+        let (x1, y1) = points[(i + 1) % points.len()];
+        if (y0 <= y) != (y1 <= y) {
+          crossings.push(x0 + (y - y0) / (y1 - y0) * (x1 - x0));
+        }
+      }
+      crossings.sort_by(f32::total_cmp);
+      for pair in crossings.chunks_exact(2) {
+        let from = (pair[0] - 0.5).ceil().max(0.0) as usize;
+        let to = (pair[1] - 0.5).ceil().max(0.0) as usize;
+        self.span(row, from, to, color);
+      }
+    }
+  }
+
+  pub fn circle(&mut self, (cx, cy): (f32, f32), radius: f32, color: Color) {
+    let top = (cy - radius).max(0.0) as usize;
+    let bottom = ((cy + radius).ceil().max(0.0) as usize).min(self.height);
+    for row in top..bottom {
+      let dy = row as f32 + 0.5 - cy;
+      let half = (radius * radius - dy * dy).max(0.0).sqrt();
+      let from = (cx - half - 0.5).ceil().max(0.0) as usize;
+      let to = (cx + half - 0.5).ceil().max(0.0) as usize;
+      self.span(row, from, to, color);
+    }
+  }
+
+  /// A straight line `width` pixels thick.
+  pub fn line(&mut self, from: (f32, f32), to: (f32, f32), width: f32, color: Color) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+    let (nx, ny) = (-dy / length * width / 2.0, dx / length * width / 2.0);
+    let corners = [
+      (from.0 + nx, from.1 + ny),
+      (to.0 + nx, to.1 + ny),
+      (to.0 - nx, to.1 - ny),
+      (from.0 - nx, from.1 - ny),
+    ];
+    self.polygon(&corners, color);
+  }
+
+  /// The canvas as a PNG file.
+  pub fn png(&self) -> Result<Vec<u8>> {
+    let encode_error = |e: png::EncodingError| Error::Io(std::io::Error::other(e));
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, self.width as u32, self.height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(encode_error)?;
+    writer.write_image_data(&self.rgba).map_err(encode_error)?;
+    writer.finish().map_err(encode_error)?;
+    Ok(out)
+  }
+}
@@ -0,0 +1,291 @@
+//! Season stats as batting, pitching and fielding CSVs with the column
+//! names league statisticians' tools expect (PA, AB, 2B, SO, IP, ER, ...).
+//!
+//! Box scores are summed per player by SQLite and each row is written out
+//! as it's read, so a long season never sits in memory. Stats the app
+//! doesn't track, like HBP and sacrifice flies, are left out rather than
+//! filled with zeros, so PA is AB plus BB.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::db::{roster, Database};
+use crate::error::{Error, Result};
+use crate::stats::{self, StatLines};
+
+const BATTING_HEADERS: [&str; 20] = [
+  "Player", "#", "G", "PA", "AB", "R", "H", "2B", "3B", "HR", "RBI", "BB", "SO", "SB", "CS", "TB",
+  "AVG", "OBP", "SLG", "OPS",
+];
+
+const PITCHING_HEADERS: [&str; 12] = [
+  "Player", "#", "G", "IP", "H", "R", "ER", "BB", "SO", "NP", "ERA", "WHIP",
+];
+
+const FIELDING_HEADERS: [&str; 8] = ["Player", "#", "G", "PO", "A", "E", "TC", "FPCT"];
+
+/// Where each file was written.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsCsvFiles {
+  pub batting: PathBuf,
+  pub pitching: PathBuf,
+  pub fielding: PathBuf,
+}
+
+type CsvFile = csv::Writer<BufWriter<File>>;
+
+fn create(path: &Path, headers: &[&str]) -> Result<CsvFile> {
+  let mut writer = csv::Writer::from_writer(BufWriter::new(File::create(path)?));
+  writer.write_record(headers)?;
+  Ok(writer)
+}
+
+fn finish(writer: CsvFile) -> Result<()> {
+  writer
+    .into_inner()
+    .map_err(|e| Error::Io(e.into_error()))?
+    .into_inner()
+    .map_err(|e| Error::Io(e.into_error()))?;
+  Ok(())
+}
+
+/// Rates with a fixed number of places, blank when there's nothing to
+/// divide by.
+pub(super) fn rate(value: Option<f64>, places: usize) -> String {
+  value.map_or(String::new(), |v| format!("{v:.places$}"))
+}
+
+/// Writes `<stem> batting.csv`, `<stem> pitching.csv` and
+/// `<stem> fielding.csv` into `dir` for `team_id`'s games in `season_id`
+/// (a year), or in every season. Pitchers are only listed on the pitching
+/// sheet; every player with a box score is on the other two.
+pub fn write(
+  conn: &Connection,
+  team_id: &str,
+  season_id: Option<&str>,
+  dir: &Path,
+  stem: &str,
+) -> Result<StatsCsvFiles> {
+  let files = StatsCsvFiles {
+    batting: dir.join(format!("{stem} batting.csv")),
+    pitching: dir.join(format!("{stem} pitching.csv")),
+    fielding: dir.join(format!("{stem} fielding.csv")),
+  };
+  let mut batting = create(&files.batting, &BATTING_HEADERS)?;
+  let mut pitching = create(&files.pitching, &PITCHING_HEADERS)?;
+  let mut fielding = create(&files.fielding, &FIELDING_HEADERS)?;
+
+  let mut stmt = conn.prepare(&format!(
+    "SELECT p.name, p.number, {} FROM game_stats s \
+     JOIN players p ON p.id = s.player_id \
+     JOIN games g ON g.id = s.game_id \
+     WHERE p.team_id = ?1 AND (?2 IS NULL OR substr(g.date, 1, 4) = ?2) \
+     GROUP BY p.id ORDER BY p.name COLLATE NOCASE, p.id",
+    stats::TOTALS
+  ))?;
+  let mut rows = stmt.query(rusqlite::params![team_id, season_id])?;
+  while let Some(row) = rows.next()? {
+    let name: String = row.get(0)?;
+    let number = row.get::<_, Option<u32>>(1)?.map_or(String::new(), |n| n.to_string());
+    let StatLines {
+      games,
+      batting: b,
+      fielding: f,
+      pitching: p,
+    } = stats::from_totals(row, 2)?;
+
+    batting.write_record([
+      name.clone(),
+      number.clone(),
+      games.to_string(),
+      b.pa.to_string(),
+      b.ab.to_string(),
+      b.r.to_string(),
+      b.h.to_string(),
+      b.doubles.to_string(),
+      b.triples.to_string(),
+      b.hr.to_string(),
+      b.rbi.to_string(),
+      b.bb.to_string(),
+      b.so.to_string(),
+      b.sb.to_string(),
+      b.cs.to_string(),
+      b.total_bases.to_string(),
+      rate(b.avg, 3),
+      rate(b.obp, 3),
+      rate(b.slg, 3),
+      rate(b.ops, 3),
+    ])?;
+    if p.appearances > 0 {
+      pitching.write_record([
+        name.clone(),
+        number.clone(),
+        p.appearances.to_string(),
+        format!("{:.1}", p.ip),
+        p.h.to_string(),
+        p.r.to_string(),
+        p.er.to_string(),
+        p.bb.to_string(),
+        p.k.to_string(),
+        p.pitches.to_string(),
+        rate(p.era, 2),
+        rate(p.whip, 2),
+      ])?;
+    }
+    fielding.write_record([
+      name,
+      number,
+      games.to_string(),
+      f.po.to_string(),
+      f.a.to_string(),
+      f.e.to_string(),
+      f.chances.to_string(),
+      rate(f.fpct, 3),
+    ])?;
+  }
+
+  finish(batting)?;
+  finish(pitching)?;
+  finish(fielding)?;
+  Ok(files)
+}
+
+/// Saves the season's batting, pitching and fielding CSVs into `dir`. Without
+/// `dir`, asks for a folder and returns `None` if the user cancels.
+#[tauri::command]
+pub async fn export_stats_csv(
+  app: AppHandle,
+  team_id: Option<String>,
+  season_id: Option<String>,
+  dir: Option<PathBuf>,
+) -> Result<Option<StatsCsvFiles>> {
+  if let Some(season) = &season_id {
+    if season.len() != 4 || !season.chars().all(|c| c.is_ascii_digit()) {
+      return Err(Error::Validation(format!("Season must be a year, not {season:?}")));
+    }
+  }
+  tauri::async_runtime::spawn_blocking(move || {
+    let db = app.state::<Database>();
+    let (team_id, team_name) = {
+      let conn = db.conn();
+      let team_id = match team_id {
+        Some(team_id) => team_id,
+        None => roster::default_team_id(&conn)?,
+      };
+      let team_name = roster::team_name(&conn, &team_id)?;
+      (team_id, team_name)
+    };
+    let dir = match dir {
+      Some(dir) => dir,
+      None => match app
+        .dialog()
+        .file()
+        .set_title("Choose a folder for the stats files")
+        .blocking_pick_folder()
+        .and_then(|path| path.into_path().ok())
+      {
+        Some(dir) => dir,
+        None => return Ok(None),
+      },
+    };
+    let season = season_id.as_deref().unwrap_or("all seasons");
+    let stem = super::file_stem(&format!("{team_name} {season}"));
+    let files = write(&db.conn(), &team_id, season_id.as_deref(), &dir, &stem)?;
+    log::info!("Exported stats CSVs to {:?}", dir);
+    Ok(Some(files))
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::games::{self, ScheduledGame};
+  use crate::db::roster::NewPlayer;
+
+  #[test]
+  fn writes_standard_columns_per_season() {
+    let conn = crate::db::open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let mut players = Vec::new();
+    for name in ["Ava Chen", "Jake Miller"] {
+      let player = roster::create(
+        &conn,
+        NewPlayer {
+          team_id: Some(team_id.clone()),
+          name: name.into(),
+          number: Some(7),
+          primary_position: "P".into(),
+          secondary_positions: Vec::new(),
+          bats: "R".into(),
+          throws: "R".into(),
+          status: None,
+          notes: None,
+        },
+      )
+      .unwrap();
+      players.push(player.id);
+    }
+    for (id, date) in [("g1", "2025-06-01"), ("g2", "2026-04-11"), ("g3", "2026-04-18")] {
+      let game = ScheduledGame {
+        id: id.into(),
+        date: date.into(),
+        opponent: "Eagles".into(),
+        home_away: "home".into(),
+        score_us: None,
+        score_them: None,
+        notes: String::new(),
+        location: String::new(),
+        start_time: None,
+      };
+      games::upsert_scheduled(&conn, &team_id, &game).unwrap();
+    }
+    let box_score = "INSERT INTO game_stats \
+       (game_id, player_id, ab, h, doubles, bb, so, ip, h_allowed, er, bb_allowed, k, pitches, \
+        po, a, e) \
+       VALUES (?1, ?2, 3, 1, 1, 1, 1, ?3, 2, 1, 1, 4, ?4, 1, 2, 1)";
+    for (game, player, ip) in [("g1", 0, 1.0), ("g2", 0, 1.2), ("g3", 0, 1.2), ("g2", 1, 0.0)] {
+      let pitches = if ip > 0.0 { 40 } else { 0 };
+      conn
+        .execute(box_score, rusqlite::params![game, players[player], ip, pitches])
+        .unwrap();
+    }
+
+    let dir = std::env::temp_dir().join(format!("dugout-stats-csv-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let files = write(&conn, &team_id, Some("2026"), &dir, "Team 2026").unwrap();
+    let read = |path: &Path| std::fs::read_to_string(path).unwrap();
+
+    let batting = read(&files.batting);
+    let mut lines = batting.lines();
+    assert_eq!(
+      lines.next().unwrap(),
+      "Player,#,G,PA,AB,R,H,2B,3B,HR,RBI,BB,SO,SB,CS,TB,AVG,OBP,SLG,OPS"
+    );
+    assert_eq!(
+      lines.next().unwrap(),
+      "Ava Chen,7,2,8,6,0,2,2,0,0,0,2,2,0,0,4,0.333,0.500,0.667,1.167"
+    );
+    assert!(lines.next().unwrap().starts_with("Jake Miller,7,1,4,3,"));
+
+    // Only Ava pitched, and 1.2 + 1.2 innings is 3.1; 2025 is left out
+    let pitching = read(&files.pitching);
+    assert_eq!(
+      pitching.lines().collect::<Vec<_>>(),
+      [
+        "Player,#,G,IP,H,R,ER,BB,SO,NP,ERA,WHIP",
+        "Ava Chen,7,2,3.1,4,0,2,2,8,80,5.40,1.80",
+      ]
+    );
+    assert!(read(&files.fielding).contains("Ava Chen,7,2,2,4,2,8,0.750"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -0,0 +1,292 @@
+//! Excel workbook with one sheet per game lineup plus season stats.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+use rust_xlsxwriter::{Format, Workbook, Worksheet};
+use tauri::{AppHandle, Manager};
+
+use crate::db::games::{self, Game, SeasonTotals};
+use crate::db::lineups::{self, Lineup};
+use crate::db::{roster, Database};
+use crate::error::Result;
+use crate::telemetry::{Feature, Telemetry};
+
+/// Excel's limit on worksheet name length.
+const MAX_SHEET_NAME: usize = 31;
+
+const STATS_SHEET: &str = "Season Stats";
+
+const STATS_HEADERS: [&str; 25] = [
+  "Player",
+  "#",
+  "G",
+  "AB",
+  "R",
+  "H",
+  "2B",
+  "3B",
+  "HR",
+  "RBI",
+  "BB",
+  "SO",
+  "SB",
+  "AVG",
+  "OBP",
+  "SLG",
+  "IP",
+  "H Allowed",
+  "R Allowed",
+  "ER",
+  "BB Allowed",
+  "K",
+  "PO",
+  "A",
+  "E",
+];
+
+struct Formats {
+  title: Format,
+  header: Format,
+  average: Format,
+  innings: Format,
+}
+
+impl Formats {
+  fn new() -> Self {
+    Self {
+      title: Format::new().set_bold().set_font_size(14),
+      header: Format::new().set_bold().set_background_color("#D9E1F2"),
+      average: Format::new().set_num_format(".000"),
+      innings: Format::new().set_num_format("0.0"),
+    }
+  }
+}
+
+/// Writes the workbook for `team_id` to `path`.
+pub fn write(conn: &Connection, team_id: &str, path: &Path) -> Result<()> {
+  let formats = Formats::new();
+  let games: HashMap<String, Game> = games::list(conn, team_id)?
+    .into_iter()
+    .map(|g| (g.id.clone(), g))
+    .collect();
+
+  let mut game_lineups: Vec<(&Game, Lineup)> = lineups::list(conn, team_id)?
+    .into_iter()
+    .filter_map(|l| Some((games.get(l.game_id.as_deref()?)?, l)))
+    .collect();
+  game_lineups.sort_by(|(a, _), (b, _)| a.date.cmp(&b.date));
+
+  let mut workbook = Workbook::new();
+  let mut used = HashSet::from([STATS_SHEET.to_lowercase()]);
+  for (game, lineup) in &game_lineups {
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(sheet_name(
+      &format!("{} vs {}", game.date, game.opponent),
+      &mut used,
+    ))?;
+    write_lineup(sheet, game, lineup, &formats)?;
+  }
+
+  let sheet = workbook.add_worksheet();
+  sheet.set_name(STATS_SHEET)?;
+  write_stats(sheet, &games::season_totals(conn, team_id)?, &formats)?;
+
+  workbook.save(path)?;
+  Ok(())
+}
+
+fn write_lineup(
+  sheet: &mut Worksheet,
+  game: &Game,
+  lineup: &Lineup,
+  formats: &Formats,
+) -> Result<()> {
+  let venue = if game.home_away == "away" { "@" } else { "vs" };
+  sheet.write_string_with_format(0, 0, format!("{venue} {}", game.opponent), &formats.title)?;
+  sheet.write_string(1, 0, &game.date)?;
+  if let (Some(us), Some(them)) = (game.score_us, game.score_them) {
+    let result = game.result.as_deref().unwrap_or("");
+    sheet.write_string(1, 2, format!("{result} {us}-{them}").trim())?;
+  }
+  if lineup.name != game.opponent {
+    sheet.write_string(2, 0, &lineup.name)?;
+  }
+
+  for (col, header) in ["Order", "#", "Player", "Pos"].into_iter().enumerate() {
+    sheet.write_string_with_format(4, col as u16, header, &formats.header)?;
+  }
+  for (i, slot) in lineup.slots.iter().enumerate() {
+    let row = 5 + i as u32;
+    sheet.write_number(row, 0, slot.slot_number)?;
+    if let Some(number) = slot.number {
+      sheet.write_number(row, 1, number)?;
+    }
+    sheet.write_string(row, 2, slot.player_name.as_deref().unwrap_or("—"))?;
+    sheet.write_string(row, 3, slot.position.as_deref().unwrap_or(""))?;
+  }
+  sheet.set_column_width(2, 24)?;
+  sheet.set_freeze_panes(5, 0)?;
+  Ok(())
+}
+
+fn write_stats(sheet: &mut Worksheet, totals: &[SeasonTotals], formats: &Formats) -> Result<()> {
+  for (col, header) in STATS_HEADERS.into_iter().enumerate() {
+    sheet.write_string_with_format(0, col as u16, header, &formats.header)?;
+  }
+  for (i, t) in totals.iter().enumerate() {
+    let row = 1 + i as u32;
+    sheet.write_string(row, 0, &t.name)?;
+    if let Some(number) = t.number {
+      sheet.write_number(row, 1, number)?;
+    }
+    let counts = [
+      t.games, t.ab, t.r, t.h, t.doubles, t.triples, t.hr, t.rbi, t.bb, t.so, t.sb,
+    ];
+    for (offset, value) in counts.into_iter().enumerate() {
+      sheet.write_number(row, 2 + offset as u16, value)?;
+    }
+    let (avg, obp, slg) = rate_stats(t);
+    sheet.write_number_with_format(row, 13, avg, &formats.average)?;
+    sheet.write_number_with_format(row, 14, obp, &formats.average)?;
+    sheet.write_number_with_format(row, 15, slg, &formats.average)?;
+    sheet.write_number_with_format(row, 16, t.ip, &formats.innings)?;
+    let pitching = [
+      t.h_allowed,
+      t.r_allowed,
+      t.er,
+      t.bb_allowed,
+      t.k,
+      t.po,
+      t.a,
+      t.e,
+    ];
+    for (offset, value) in pitching.into_iter().enumerate() {
+      sheet.write_number(row, 17 + offset as u16, value)?;
+    }
+  }
+  sheet.set_column_width(0, 24)?;
+  sheet.set_freeze_panes(1, 1)?;
+  Ok(())
+}
+
+/// Batting average, on-base and slugging percentage. Hit-by-pitch and
+/// sacrifices aren't tracked, so OBP is (H + BB) / (AB + BB).
+fn rate_stats(t: &SeasonTotals) -> (f64, f64, f64) {
+  let ratio = |num: u32, den: u32| {
+    if den == 0 {
+      0.0
+    } else {
+      f64::from(num) / f64::from(den)
+    }
+  };
+  let total_bases = t.h + t.doubles + 2 * t.triples + 3 * t.hr;
+  (
+    ratio(t.h, t.ab),
+    ratio(t.h + t.bb, t.ab + t.bb),
+    ratio(total_bases, t.ab),
+  )
+}
+
+/// Turns `raw` into a valid, unique worksheet name.
+fn sheet_name(raw: &str, used: &mut HashSet<String>) -> String {
+  let clean: String = raw
+    .chars()
+    .map(|c| match c {
+      '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '-',
+      c => c,
+    })
+    .collect();
+  let clean = clean.trim_matches('\'').trim();
+  let base: String = clean.chars().take(MAX_SHEET_NAME).collect();
+  let mut name = if base.is_empty() {
+    "Game".to_string()
+  } else {
+    base
+  };
+  let mut n = 2;
+  while !used.insert(name.to_lowercase()) {
+    let suffix = format!(" ({n})");
+    let keep = MAX_SHEET_NAME - suffix.len();
+    name = format!("{}{suffix}", clean.chars().take(keep).collect::<String>());
+    n += 1;
+  }
+  name
+}
+
+/// Exports lineups and season stats to an .xlsx file. Without `path`, asks
+/// where to save it; returns `None` if the user cancels.
+#[tauri::command]
+pub async fn export_xlsx(
+  app: AppHandle,
+  team_id: Option<String>,
+  path: Option<PathBuf>,
+) -> Result<Option<PathBuf>> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let db = app.state::<Database>();
+    let (team_id, team_name) = {
+      let conn = db.conn();
+      let team_id = match team_id {
+        Some(team_id) => team_id,
+        None => roster::default_team_id(&conn)?,
+      };
+      let team_name = roster::team_name(&conn, &team_id)?;
+      (team_id, team_name)
+    };
+    let path = match path {
+      Some(path) => path,
+      None => {
+        let file_name = format!("{}.xlsx", super::file_stem(&team_name));
+        match super::pick_save_path(&app, &file_name, "Excel workbook", &["xlsx"]) {
+          Some(path) => path,
+          None => return Ok(None),
+        }
+      }
+    };
+    write(&db.conn(), &team_id, &path)?;
+    log::info!("Exported workbook to {:?}", path);
+    app.state::<Telemetry>().record(Feature::SpreadsheetExported);
+    Ok(Some(path))
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sheet_names_are_valid_and_unique() {
+    let mut used = HashSet::new();
+    assert_eq!(
+      sheet_name("2026-05-11 vs Tigers", &mut used),
+      "2026-05-11 vs Tigers"
+    );
+    assert_eq!(
+      sheet_name("2026-05-11 vs Tigers", &mut used),
+      "2026-05-11 vs Tigers (2)"
+    );
+    assert_eq!(sheet_name("A/B: [test]?", &mut used), "A-B- -test--");
+
+    let long = sheet_name("2026-05-18 vs The Riverside Thunderbolts", &mut used);
+    assert_eq!(long.chars().count(), MAX_SHEET_NAME);
+  }
+
+  #[test]
+  fn computes_rate_stats() {
+    let totals = SeasonTotals {
+      ab: 10,
+      h: 4,
+      doubles: 1,
+      hr: 1,
+      bb: 2,
+      ..Default::default()
+    };
+    let (avg, obp, slg) = rate_stats(&totals);
+    assert!((avg - 0.4).abs() < 1e-9);
+    assert!((obp - 0.5).abs() < 1e-9);
+    assert!((slg - 0.8).abs() < 1e-9);
+    assert_eq!(rate_stats(&SeasonTotals::default()), (0.0, 0.0, 0.0));
+  }
+}
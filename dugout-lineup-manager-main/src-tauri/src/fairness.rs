@@ -0,0 +1,340 @@
+//! Season playing-time report: how innings, positions and batting spots
+//! have been shared out, built from the lineups saved for games.
+//!
+//! Seasons aren't stored on their own, so a season is a calendar year,
+//! matched against the game's date when the game is in the local schedule
+//! and the lineup's last save otherwise.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::lineups::{self, Lineup};
+use crate::db::roster::{self, Player};
+use crate::db::{games, Database};
+use crate::error::{Error, Result};
+use crate::optimizer::FIELD_POSITIONS;
+
+/// Counted as infield time; the rest of [`FIELD_POSITIONS`] is outfield.
+const INFIELD: [&str; 6] = ["P", "C", "1B", "2B", "3B", "SS"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FairnessReport {
+  pub team_id: String,
+  /// The year reported on, or `None` for every saved game.
+  pub season_id: Option<String>,
+  pub games: u32,
+  pub players: Vec<PlayerFairness>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerFairness {
+  pub player_id: String,
+  pub name: String,
+  pub number: Option<u32>,
+  /// Games the player was in the lineup for.
+  pub games: u32,
+  pub innings_played: u32,
+  pub innings_benched: u32,
+  pub infield_innings: u32,
+  pub outfield_innings: u32,
+  /// Innings at each position played.
+  pub innings_by_position: BTreeMap<String, u32>,
+  /// Times batting in each spot; index 0 is leadoff.
+  pub batting_slots: Vec<u32>,
+  pub average_batting_slot: Option<f64>,
+}
+
+impl PlayerFairness {
+  /// Share of their games' innings spent on the field, from 0 to 100.
+  fn playing_time(&self) -> Option<f64> {
+    let total = self.innings_played + self.innings_benched;
+    (total > 0).then(|| f64::from(self.innings_played) * 100.0 / f64::from(total))
+  }
+}
+
+pub fn report(
+  team_id: &str,
+  season_id: Option<String>,
+  players: &[Player],
+  played: &[Lineup],
+) -> FairnessReport {
+  let mut rows: Vec<PlayerFairness> = players
+    .iter()
+    .map(|p| PlayerFairness {
+      player_id: p.id.clone(),
+      name: p.name.clone(),
+      number: p.number,
+      ..Default::default()
+    })
+    .collect();
+
+  for lineup in played {
+    for row in &mut rows {
+      let slot = lineup
+        .slots
+        .iter()
+        .find(|s| s.player_id.as_deref() == Some(&row.player_id))
+        .map(|s| s.slot_number);
+      let fielded: Vec<&str> = lineup
+        .positions
+        .iter()
+        .filter(|p| p.player_id == row.player_id)
+        .map(|p| p.position.as_str())
+        .collect();
+      if slot.is_none() && fielded.is_empty() {
+        continue;
+      }
+
+      row.games += 1;
+      row.innings_played += fielded.len() as u32;
+      row.innings_benched += lineup.innings.saturating_sub(fielded.len() as u32);
+      for position in fielded {
+        *row.innings_by_position.entry(position.to_string()).or_default() += 1;
+        if INFIELD.contains(&position) {
+          row.infield_innings += 1;
+        } else if FIELD_POSITIONS.contains(&position) {
+          row.outfield_innings += 1;
+        }
+      }
+      if let Some(slot) = slot.filter(|&slot| slot > 0) {
+        let index = slot as usize - 1;
+        if row.batting_slots.len() <= index {
+          row.batting_slots.resize(index + 1, 0);
+        }
+        row.batting_slots[index] += 1;
+      }
+    }
+  }
+
+  for row in &mut rows {
+    let times: u32 = row.batting_slots.iter().sum();
+    let total: u32 = row
+      .batting_slots
+      .iter()
+      .enumerate()
+      .map(|(index, &count)| (index as u32 + 1) * count)
+      .sum();
+    row.average_batting_slot = (times > 0).then(|| f64::from(total) / f64::from(times));
+  }
+
+  FairnessReport {
+    team_id: team_id.to_string(),
+    season_id,
+    games: played.len() as u32,
+    players: rows,
+  }
+}
+
+fn load(
+  conn: &Connection,
+  team_id: Option<String>,
+  season_id: Option<String>,
+) -> Result<FairnessReport> {
+  if let Some(season) = &season_id {
+    if season.len() != 4 || !season.chars().all(|c| c.is_ascii_digit()) {
+      return Err(Error::Validation(format!("Season must be a year, not {season:?}")));
+    }
+  }
+  let team_id = match team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(conn)?,
+  };
+
+  let played: Vec<Lineup> = lineups::list(conn, &team_id)?
+    .into_iter()
+    .filter(|lineup| {
+      let Some(game_id) = &lineup.game_id else {
+        // Drafts and templates never got played
+        return false;
+      };
+      let Some(season) = &season_id else {
+        return true;
+      };
+      let date = games::get(conn, game_id)
+        .map(|game| game.date)
+        .unwrap_or_else(|_| lineup.updated_at.clone());
+      date.starts_with(season.as_str())
+    })
+    .collect();
+  let players = roster::list(conn, &team_id)?;
+  Ok(report(&team_id, season_id, &players, &played))
+}
+
+/// The report as CSV, one row per player, for sharing with parents.
+pub fn to_csv(report: &FairnessReport) -> Result<Vec<u8>> {
+  let slots = report
+    .players
+    .iter()
+    .map(|p| p.batting_slots.len())
+    .max()
+    .unwrap_or(0);
+  let mut writer = csv::Writer::from_writer(Vec::new());
+
+  let mut header: Vec<String> = [
+    "Player",
+    "#",
+    "Games",
+    "Innings played",
+    "Innings benched",
+    "Playing time %",
+    "Infield innings",
+    "Outfield innings",
+  ]
+  .map(String::from)
+  .to_vec();
+  header.extend(FIELD_POSITIONS.map(String::from));
+  header.push("Average batting spot".into());
+  header.extend((1..=slots).map(|slot| format!("Batted {slot}")));
+  writer.write_record(&header)?;
+
+  let one_decimal = |value: Option<f64>| value.map_or(String::new(), |v| format!("{v:.1}"));
+  for player in &report.players {
+    let mut record = vec![
+      player.name.clone(),
+      player.number.map_or(String::new(), |n| n.to_string()),
+      player.games.to_string(),
+      player.innings_played.to_string(),
+      player.innings_benched.to_string(),
+      one_decimal(player.playing_time()),
+      player.infield_innings.to_string(),
+      player.outfield_innings.to_string(),
+    ];
+    record.extend(FIELD_POSITIONS.map(|position| {
+      player.innings_by_position.get(position).copied().unwrap_or(0).to_string()
+    }));
+    record.push(one_decimal(player.average_batting_slot));
+    record.extend(
+      (0..slots).map(|i| player.batting_slots.get(i).copied().unwrap_or(0).to_string()),
+    );
+    writer.write_record(&record)?;
+  }
+  writer
+    .into_inner()
+    .map_err(|e| Error::Io(e.into_error()))
+}
+
+/// Innings, positions and batting spots per player for `season_id` (a year,
+/// e.g. `2026`), or every saved game when omitted.
+#[tauri::command]
+pub fn get_fairness_report(
+  db: State<'_, Database>,
+  team_id: Option<String>,
+  season_id: Option<String>,
+) -> Result<FairnessReport> {
+  load(&db.conn(), team_id, season_id)
+}
+
+/// Saves the report as CSV. Without `path`, asks where to save it and
+/// returns `None` if the user cancels.
+#[tauri::command]
+pub async fn export_fairness_report(
+  app: AppHandle,
+  team_id: Option<String>,
+  season_id: Option<String>,
+  path: Option<PathBuf>,
+) -> Result<Option<PathBuf>> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let report = load(&app.state::<Database>().conn(), team_id, season_id)?;
+    let bytes = to_csv(&report)?;
+    let path = match path {
+      Some(path) => path,
+      None => {
+        let season = report.season_id.as_deref().unwrap_or("season");
+        let file_name = format!("Playing time {season}.csv");
+        match crate::export::pick_save_path(&app, &file_name, "CSV file", &["csv"]) {
+          Some(path) => path,
+          None => return Ok(None),
+        }
+      }
+    };
+    std::fs::write(&path, bytes)?;
+    log::info!("Saved playing-time report to {:?}", path);
+    Ok(Some(path))
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::lineups::{InningPosition, LineupInput, SlotInput};
+  use crate::db::open_in_memory;
+  use crate::db::roster::NewPlayer;
+
+  fn player(conn: &Connection, name: &str) -> String {
+    roster::create(
+      conn,
+      NewPlayer {
+        team_id: None,
+        name: name.into(),
+        number: None,
+        primary_position: "SS".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap()
+    .id
+  }
+
+  #[test]
+  fn splits_innings_and_batting_spots_per_player() {
+    let mut conn = open_in_memory();
+    let ava = player(&conn, "Ava Chen");
+    let jake = player(&conn, "Jake Miller");
+    let position = |inning, position: &str, player_id: &str| InningPosition {
+      inning,
+      position: position.into(),
+      player_id: player_id.into(),
+    };
+    let slot = |slot_number, player_id: &str| SlotInput {
+      slot_number,
+      player_id: Some(player_id.into()),
+      position: None,
+    };
+    // The last lineup isn't tied to a game, so it doesn't count
+    let games = [(Some("g1"), &ava, &jake), (Some("g2"), &jake, &ava), (None, &ava, &jake)];
+    for (game_id, first, second) in games {
+      lineups::save(
+        &mut conn,
+        LineupInput {
+          id: None,
+          team_id: None,
+          name: "Lineup".into(),
+          game_id: game_id.map(String::from),
+          use_dh: false,
+          innings: 3,
+          slots: vec![slot(1, first), slot(2, second)],
+          positions: vec![
+            position(1, "SS", first),
+            position(2, "LF", first),
+            position(1, "P", second),
+          ],
+        },
+      )
+      .unwrap();
+    }
+
+    let report = load(&conn, None, None).unwrap();
+    assert_eq!(report.games, 2);
+    let ava = &report.players[0];
+    assert_eq!((ava.games, ava.innings_played, ava.innings_benched), (2, 3, 3));
+    assert_eq!((ava.infield_innings, ava.outfield_innings), (2, 1));
+    assert_eq!(ava.batting_slots, [1, 1]);
+    assert_eq!(ava.average_batting_slot, Some(1.5));
+
+    let csv = String::from_utf8(to_csv(&report).unwrap()).unwrap();
+    let ava_row = csv.lines().nth(1).unwrap();
+    assert_eq!(ava_row, "Ava Chen,,2,3,3,50.0,2,1,1,0,0,0,0,1,1,0,0,1.5,1,1");
+    assert!(load(&conn, None, Some("26".into())).is_err());
+  }
+}
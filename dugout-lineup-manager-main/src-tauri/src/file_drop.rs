@@ -0,0 +1,217 @@
+//! Files dropped onto the main window.
+//!
+//! Each dropped file is identified by its content rather than its
+//! extension, copied into the staging directory so the import wizards never
+//! read from a location that can change underneath them, and announced to
+//! the webview with a [`FILE_DROPPED_EVENT`].
+//!
+//! On Windows native drops would swallow the lineup card's HTML5 drag and
+//! drop, so `tauri.windows.conf.json` keeps them off there.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, DragDropEvent, Emitter, Manager, Window, WindowEvent};
+use zip::ZipArchive;
+
+use crate::archive::MANIFEST_FILE;
+use crate::error::Result;
+use crate::paths::AppPaths;
+
+/// Event carrying a [`DroppedFile`] for each recognized file.
+pub const FILE_DROPPED_EVENT: &str = "file-dropped";
+
+/// Event carrying a [`RejectedFile`] for each file that can't be imported.
+pub const FILE_REJECTED_EVENT: &str = "file-drop-rejected";
+
+/// Bytes read to tell a CSV from arbitrary binary data.
+const SNIFF_LEN: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileKind {
+  Csv,
+  Xlsx,
+  /// A `.dugout` backup archive.
+  Backup,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedFile {
+  pub kind: FileKind,
+  /// Original file name, for display.
+  pub name: String,
+  /// Staged copy the import should read.
+  pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedFile {
+  pub name: String,
+  pub reason: String,
+}
+
+/// Identifies a file from its first bytes (and, for zips, its entries).
+pub fn sniff(path: &Path) -> Result<Option<FileKind>> {
+  let mut head = Vec::with_capacity(SNIFF_LEN);
+  File::open(path)?
+    .take(SNIFF_LEN as u64)
+    .read_to_end(&mut head)?;
+
+  if head.starts_with(b"PK\x03\x04") {
+    // Both archives and workbooks are zips; tell them apart by their entries.
+    let Ok(archive) = ZipArchive::new(File::open(path)?) else {
+      return Ok(None);
+    };
+    let has = |name: &str| archive.index_for_name(name).is_some();
+    return Ok(if has(MANIFEST_FILE) {
+      Some(FileKind::Backup)
+    } else if has("xl/workbook.xml") {
+      Some(FileKind::Xlsx)
+    } else {
+      None
+    });
+  }
+
+  Ok(looks_like_csv(&head).then_some(FileKind::Csv))
+}
+
+/// UTF-8 text without NUL bytes whose first line has a comma.
+fn looks_like_csv(head: &[u8]) -> bool {
+  let head = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head);
+  if head.is_empty() || head.contains(&0) {
+    return false;
+  }
+  let text = match std::str::from_utf8(head) {
+    Ok(text) => text,
+    // The sniff window may cut a multi-byte character in half.
+    Err(e) if e.error_len().is_none() => std::str::from_utf8(&head[..e.valid_up_to()]).unwrap(),
+    Err(_) => return false,
+  };
+  text.lines().next().is_some_and(|line| line.contains(','))
+}
+
+/// Copies `path` into its own directory under `staging`, keeping the name.
+pub fn stage(path: &Path, staging: &Path) -> Result<PathBuf> {
+  let dir = staging.join(uuid::Uuid::new_v4().simple().to_string());
+  std::fs::create_dir_all(&dir)?;
+  let staged = dir.join(path.file_name().unwrap_or_default());
+  std::fs::copy(path, &staged)?;
+  Ok(staged)
+}
+
+fn accept(app: &AppHandle, path: &Path) {
+  let name = path
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  let reject = |reason: String| {
+    log::info!("Rejected dropped file {:?}: {}", path, reason);
+    let _ = app.emit(FILE_REJECTED_EVENT, RejectedFile { name: name.clone(), reason });
+  };
+
+  if !path.is_file() {
+    return reject("Only files can be imported".into());
+  }
+  let kind = match sniff(path) {
+    Ok(Some(kind)) => kind,
+    Ok(None) => return reject("Not a CSV, Excel workbook, or Dugout backup".into()),
+    Err(e) => return reject(e.to_string()),
+  };
+  let staging = app.state::<AppPaths>().staging.clone();
+  match stage(path, &staging) {
+    Ok(staged) => {
+      let file = DroppedFile {
+        kind,
+        name: name.clone(),
+        path: staged,
+      };
+      if let Err(e) = app.emit(FILE_DROPPED_EVENT, file) {
+        log::warn!("Failed to emit dropped file: {}", e);
+      }
+    }
+    Err(e) => reject(e.to_string()),
+  }
+}
+
+pub fn handle_window_event(window: &Window, event: &WindowEvent) {
+  if window.label() != "main" {
+    return;
+  }
+  if let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event {
+    let app = window.app_handle().clone();
+    let paths = paths.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+      for path in paths {
+        accept(&app, &path);
+      }
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Write;
+
+  use zip::write::SimpleFileOptions;
+  use zip::ZipWriter;
+
+  use super::*;
+
+  fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dugout-drop-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn write_zip(path: &Path, entry: &str) {
+    let mut zip = ZipWriter::new(File::create(path).unwrap());
+    zip.start_file(entry, SimpleFileOptions::default()).unwrap();
+    zip.write_all(b"{}").unwrap();
+    zip.finish().unwrap();
+  }
+
+  #[test]
+  fn sniffs_by_content_not_extension() {
+    let dir = temp_dir();
+    let backup = dir.join("roster.xlsx");
+    write_zip(&backup, MANIFEST_FILE);
+    let workbook = dir.join("season.dugout");
+    write_zip(&workbook, "xl/workbook.xml");
+    let csv = dir.join("roster.txt");
+    std::fs::write(&csv, "\u{FEFF}Name,Number\nAva Díaz,7\n").unwrap();
+    let png = dir.join("logo.csv");
+    std::fs::write(&png, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+
+    assert_eq!(sniff(&backup).unwrap(), Some(FileKind::Backup));
+    assert_eq!(sniff(&workbook).unwrap(), Some(FileKind::Xlsx));
+    assert_eq!(sniff(&csv).unwrap(), Some(FileKind::Csv));
+    assert_eq!(sniff(&png).unwrap(), None);
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn tolerates_a_character_split_by_the_sniff_window() {
+    let mut head = b"Name,Number\nJos".to_vec();
+    head.push(0xC3); // first half of "é"
+    assert!(looks_like_csv(&head));
+    assert!(!looks_like_csv(b"just some notes"));
+  }
+
+  #[test]
+  fn stages_a_copy_under_the_original_name() {
+    let dir = temp_dir();
+    let source = dir.join("Tigers.csv");
+    std::fs::write(&source, "Name\n").unwrap();
+
+    let staged = stage(&source, &dir.join("staging")).unwrap();
+    assert_eq!(staged.file_name().unwrap(), "Tigers.csv");
+    assert!(staged.starts_with(dir.join("staging")));
+    assert_eq!(std::fs::read_to_string(&staged).unwrap(), "Name\n");
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -0,0 +1,262 @@
+//! First-run setup: a demo team to try the app out with before entering a
+//! real roster, and a factory reset that wipes everything back to it.
+//!
+//! The webview's welcome wizard asks `is_first_run` at launch and, if the
+//! coach wants it, calls `seed_demo_data`. Both seeding commands report
+//! each step as a [`FIRST_RUN_PROGRESS_EVENT`].
+
+use chrono::{Days, Local, NaiveDate};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::backup::{self, BackupKind};
+use crate::db::games::{self, ScheduledGame};
+use crate::db::roster::{self, NewPlayer};
+use crate::db::{new_id, Database};
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+
+/// Emitted with a [`SeedProgress`] as each part of the demo data is added.
+pub const FIRST_RUN_PROGRESS_EVENT: &str = "first-run-progress";
+
+const DEMO_TEAM_NAME: &str = "Demo Team";
+
+/// Name, number, primary and secondary positions, bats and throws.
+type DemoPlayer = (
+  &'static str,
+  u32,
+  &'static str,
+  &'static [&'static str],
+  &'static str,
+  &'static str,
+);
+
+const DEMO_PLAYERS: [DemoPlayer; 12] = [
+  ("Alex Rivera", 1, "SS", &["2B"], "R", "R"),
+  ("Sam Carter", 3, "CF", &["LF", "RF"], "L", "L"),
+  ("Jordan Lee", 5, "C", &["1B"], "R", "R"),
+  ("Casey Brooks", 7, "1B", &["P"], "L", "L"),
+  ("Riley Nguyen", 8, "3B", &["SS"], "R", "R"),
+  ("Taylor Kim", 10, "2B", &["SS"], "S", "R"),
+  ("Morgan Diaz", 12, "LF", &["CF"], "R", "R"),
+  ("Jamie Patel", 14, "RF", &["1B"], "L", "R"),
+  ("Drew Thompson", 18, "P", &["3B"], "R", "R"),
+  ("Avery Scott", 21, "P", &["RF"], "L", "L"),
+  ("Quinn Foster", 23, "C", &["3B"], "R", "R"),
+  ("Reese Howard", 27, "DH", &["LF", "1B"], "R", "R"),
+];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SeedOptions {
+  /// Also schedules a game a week out, to try lineups and game day with.
+  pub sample_game: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedProgress {
+  pub done: usize,
+  pub total: usize,
+  /// What was just added, e.g. "Added Alex Rivera".
+  pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedReport {
+  pub team_id: String,
+  pub players: usize,
+  pub game_id: Option<String>,
+}
+
+/// Whether nothing has been entered yet. The default team is created on
+/// first use, so an empty team doesn't count.
+pub fn is_empty(conn: &Connection) -> Result<bool> {
+  let rows: i64 = conn.query_row(
+    "SELECT (SELECT COUNT(*) FROM players) + (SELECT COUNT(*) FROM games)",
+    [],
+    |row| row.get(0),
+  )?;
+  Ok(rows == 0)
+}
+
+/// Adds the demo team, its players and, if asked, a game a week after
+/// `today`, all or nothing. Calls `on_progress` after each.
+pub fn seed(
+  conn: &mut Connection,
+  options: &SeedOptions,
+  today: NaiveDate,
+  mut on_progress: impl FnMut(SeedProgress),
+) -> Result<SeedReport> {
+  let total = 1 + DEMO_PLAYERS.len() + usize::from(options.sample_game);
+  let mut done = 0;
+  let mut step = |message: String| {
+    done += 1;
+    on_progress(SeedProgress {
+      done,
+      total,
+      message,
+    });
+  };
+
+  let tx = conn.transaction()?;
+  let team_id = roster::default_team_id(&tx)?;
+  tx.execute(
+    "UPDATE teams SET name = ?2 WHERE id = ?1",
+    params![team_id, DEMO_TEAM_NAME],
+  )?;
+  step(format!("Created {DEMO_TEAM_NAME}"));
+
+  for (name, number, primary, secondary, bats, throws) in DEMO_PLAYERS {
+    roster::create(
+      &tx,
+      NewPlayer {
+        team_id: Some(team_id.clone()),
+        name: name.into(),
+        number: Some(number),
+        primary_position: primary.into(),
+        secondary_positions: secondary.iter().map(|p| p.to_string()).collect(),
+        bats: bats.into(),
+        throws: throws.into(),
+        status: None,
+        notes: None,
+      },
+    )?;
+    step(format!("Added {name}"));
+  }
+
+  let game_id = if options.sample_game {
+    let date = today
+      .checked_add_days(Days::new(7))
+      .ok_or_else(|| Error::Validation("No date a week from today".into()))?;
+    let game = ScheduledGame {
+      id: new_id(),
+      date: date.format("%Y-%m-%d").to_string(),
+      opponent: "Sample Opponent".into(),
+      home_away: "home".into(),
+      score_us: None,
+      score_them: None,
+      notes: "A sample game to try lineups with".into(),
+      location: String::new(),
+      start_time: Some("10:00".into()),
+    };
+    games::upsert_scheduled(&tx, &team_id, &game)?;
+    step("Scheduled a sample game".into());
+    Some(game.id)
+  } else {
+    None
+  };
+  tx.commit()?;
+
+  Ok(SeedReport {
+    team_id,
+    players: DEMO_PLAYERS.len(),
+    game_id,
+  })
+}
+
+fn seed_and_report(app: &AppHandle, options: &SeedOptions) -> Result<SeedReport> {
+  let db = app.state::<Database>();
+  let report = seed(&mut db.conn(), options, Local::now().date_naive(), |progress| {
+    let _ = app.emit(FIRST_RUN_PROGRESS_EVENT, progress);
+  })?;
+  app.state::<crate::stats::StatsCache>().clear();
+  app.state::<crate::sync::SyncState>().resend_snapshot();
+  log::info!("Seeded the demo team ({} players)", report.players);
+  Ok(report)
+}
+
+/// Whether the database is still empty, so the welcome wizard should show.
+#[tauri::command]
+pub fn is_first_run(db: State<'_, Database>) -> Result<bool> {
+  is_empty(&db.conn())
+}
+
+/// Adds the demo team. Only allowed while nothing has been entered, so it
+/// can't mix sample players into a real roster.
+#[tauri::command]
+pub async fn seed_demo_data(app: AppHandle, options: SeedOptions) -> Result<SeedReport> {
+  tauri::async_runtime::spawn_blocking(move || {
+    if !is_empty(&app.state::<Database>().conn())? {
+      return Err(Error::Validation("Demo data can only be added before anything else".into()));
+    }
+    seed_and_report(&app, &options)
+  })
+  .await?
+}
+
+/// After the coach confirms in a native dialog, erases every team, player,
+/// game, lineup and image and adds the demo team again. A backup of the
+/// erased data is kept; settings are left alone. Returns `None` if the
+/// coach cancels.
+#[tauri::command]
+pub async fn reset_to_factory(app: AppHandle, options: SeedOptions) -> Result<Option<SeedReport>> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let confirmed = app
+      .dialog()
+      .message(
+        "Every team, player, game and lineup will be erased and replaced with the demo team. \
+         A backup of your data is kept first.",
+      )
+      .title("Reset Dugout?")
+      .kind(MessageDialogKind::Warning)
+      .buttons(MessageDialogButtons::OkCancelCustom(
+        "Erase Everything".into(),
+        "Cancel".into(),
+      ))
+      .blocking_show();
+    if !confirmed {
+      return Ok(None);
+    }
+
+    let snapshot = backup::create(&app, BackupKind::PreRestore)?;
+    log::info!("Resetting to factory; the old data is in {:?}", snapshot.path);
+    {
+      let db = app.state::<Database>();
+      let mut conn = db.conn();
+      let data_dir = app.state::<AppPaths>().data();
+      for image in crate::images::list(&conn)? {
+        crate::images::delete(&conn, &data_dir, &image.id)?;
+      }
+      let tx = conn.transaction()?;
+      crate::json_archive::clear(&tx)?;
+      tx.commit()?;
+    }
+    seed_and_report(&app, &options).map(Some)
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn seeds_an_empty_database_once() {
+    let mut conn = crate::db::open_in_memory();
+    roster::default_team_id(&conn).unwrap();
+    assert!(is_empty(&conn).unwrap());
+
+    let today = NaiveDate::from_ymd_opt(2026, 3, 28).unwrap();
+    let mut steps = Vec::new();
+    let options = SeedOptions { sample_game: true };
+    let report = seed(&mut conn, &options, today, |p| steps.push(p)).unwrap();
+    assert_eq!(steps.len(), 14);
+    assert!(steps.iter().all(|p| p.total == 14));
+
+    assert!(!is_empty(&conn).unwrap());
+    assert_eq!(roster::team_name(&conn, &report.team_id).unwrap(), DEMO_TEAM_NAME);
+    assert_eq!(roster::list(&conn, &report.team_id).unwrap().len(), 12);
+    let game = games::get(&conn, report.game_id.as_deref().unwrap()).unwrap();
+    assert_eq!(game.date, "2026-04-04");
+
+    crate::json_archive::clear(&conn).unwrap();
+    assert!(is_empty(&conn).unwrap());
+    let report = seed(&mut conn, &SeedOptions::default(), today, |_| {}).unwrap();
+    assert_eq!(report.game_id, None);
+  }
+}
@@ -0,0 +1,53 @@
+//! Shows the app's own folders in Finder, Explorer or the Linux file
+//! manager, so nobody has to dig for `~/Library/Application Support` or a
+//! hidden `AppData` path when support asks for a file.
+
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+
+/// Opens `dir` itself, creating it first so a fresh install doesn't fail.
+fn open_dir(app: &AppHandle, dir: &Path) -> Result<PathBuf> {
+  std::fs::create_dir_all(dir)?;
+  app
+    .opener()
+    .open_path(dir.to_string_lossy(), None::<&str>)
+    .map_err(|e| Error::Validation(format!("Couldn't open {}: {e}", dir.display())))?;
+  Ok(dir.to_path_buf())
+}
+
+/// Shows the active profile's data directory selected in its parent
+/// folder, next to the app's other files. Returns its path.
+#[tauri::command]
+pub fn reveal_data_dir(app: AppHandle, paths: State<'_, AppPaths>) -> Result<PathBuf> {
+  let dir = paths.data();
+  std::fs::create_dir_all(&dir)?;
+  app
+    .opener()
+    .reveal_item_in_dir(&dir)
+    .map_err(|e| Error::Validation(format!("Couldn't show {}: {e}", dir.display())))?;
+  Ok(dir)
+}
+
+/// Opens the folder holding the app's log files. Returns its path.
+#[tauri::command]
+pub fn open_logs_dir(app: AppHandle) -> Result<PathBuf> {
+  let dir = match crate::cli::args().log_dir() {
+    Some(dir) => dir,
+    None => app
+      .path()
+      .app_log_dir()
+      .map_err(|e| Error::Validation(format!("Couldn't find the log folder: {e}")))?,
+  };
+  open_dir(&app, &dir)
+}
+
+/// Opens the active profile's backups folder. Returns its path.
+#[tauri::command]
+pub fn open_backups_dir(app: AppHandle, paths: State<'_, AppPaths>) -> Result<PathBuf> {
+  open_dir(&app, &paths.backups())
+}
@@ -0,0 +1,340 @@
+//! Game clock for leagues with a time limit ("drop-dead time").
+//!
+//! The clock lives in the shell, so it keeps counting through a webview
+//! reload; the webview reads it back with `get_game_clock` and follows
+//! [`GAME_CLOCK_TICK_EVENT`]. Time is wall-clock, so a laptop that sleeps
+//! between innings still shows the real time left. Warnings come at the
+//! minutes set for the team in [`crate::alerts`].
+
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::alerts::{self, AlertSource, AlertThresholds, ThresholdAlert};
+use crate::error::{Error, Result};
+use crate::settings::AppSettings;
+
+/// Emitted with a [`GameClockStatus`] every second while the clock runs,
+/// and whenever it is started, paused, reset or given a new limit.
+pub const GAME_CLOCK_TICK_EVENT: &str = "game-clock-tick";
+
+/// Emitted with a [`GameClockAlert`] as the time limit approaches and ends.
+pub const GAME_CLOCK_ALERT_EVENT: &str = "game-clock-alert";
+
+const TICK: Duration = Duration::from_secs(1);
+
+/// Longest time limit accepted.
+const MAX_LIMIT_MINUTES: u32 = 6 * 60;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameClockStatus {
+  pub running: bool,
+  pub elapsed_secs: u64,
+  pub time_limit_secs: Option<u64>,
+  /// Zero once the limit has passed; `None` without a limit.
+  pub remaining_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertKind {
+  /// One of the team's warning times before the limit.
+  Warning,
+  /// The limit has been reached.
+  TimeUp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameClockAlert {
+  pub kind: AlertKind,
+  pub remaining_secs: u64,
+  /// Ready to show, e.g. "10 minutes to drop-dead time".
+  pub message: String,
+}
+
+#[derive(Debug, Default)]
+struct Clock {
+  /// Time counted before the current run.
+  banked: Duration,
+  running_since: Option<DateTime<Utc>>,
+  limit: Option<Duration>,
+  /// Team whose alert thresholds apply.
+  team_id: Option<String>,
+  /// Warning times, in minutes before the limit, that have fired.
+  warned: Vec<u32>,
+  timed_up: bool,
+  /// Bumped on every start so a ticker from an earlier run stops.
+  generation: u64,
+}
+
+impl Clock {
+  fn elapsed(&self, now: DateTime<Utc>) -> Duration {
+    let running = self
+      .running_since
+      .and_then(|since| (now - since).to_std().ok())
+      .unwrap_or_default();
+    self.banked + running
+  }
+
+  fn status(&self, now: DateTime<Utc>) -> GameClockStatus {
+    let elapsed = self.elapsed(now);
+    GameClockStatus {
+      running: self.running_since.is_some(),
+      elapsed_secs: elapsed.as_secs(),
+      time_limit_secs: self.limit.map(|limit| limit.as_secs()),
+      remaining_secs: self.limit.map(|limit| limit.saturating_sub(elapsed).as_secs()),
+    }
+  }
+
+  /// Alerts that are due and haven't fired yet for this limit, warning
+  /// `warn_at` minutes before it.
+  fn due_alerts(&mut self, now: DateTime<Utc>, warn_at: &[u32]) -> Vec<GameClockAlert> {
+    let Some(limit) = self.limit else {
+      return Vec::new();
+    };
+    let remaining = limit.saturating_sub(self.elapsed(now));
+    let mut alerts = Vec::new();
+    if remaining.is_zero() {
+      if !self.timed_up {
+        self.timed_up = true;
+        alerts.push(GameClockAlert {
+          kind: AlertKind::TimeUp,
+          remaining_secs: 0,
+          message: "Drop-dead time reached".into(),
+        });
+      }
+    } else {
+      let crossed: Vec<u32> = warn_at
+        .iter()
+        .copied()
+        .filter(|&m| remaining.as_secs() <= u64::from(m) * 60 && !self.warned.contains(&m))
+        .collect();
+      if crossed.is_empty() {
+        return alerts;
+      }
+      // Several at once (a limit set late in a game) make one warning
+      self.warned.extend(crossed);
+      let minutes = remaining.as_secs().div_ceil(60);
+      alerts.push(GameClockAlert {
+        kind: AlertKind::Warning,
+        remaining_secs: remaining.as_secs(),
+        message: if minutes == 1 {
+          "1 minute to drop-dead time".into()
+        } else {
+          format!("{minutes} minutes to drop-dead time")
+        },
+      });
+    }
+    alerts
+  }
+}
+
+#[derive(Default)]
+pub struct GameClockState(Mutex<Clock>);
+
+/// Minutes before the limit to warn `team_id` at.
+fn warn_at(app: &AppHandle, team_id: Option<&str>) -> Vec<u32> {
+  match app.try_state::<RwLock<AppSettings>>() {
+    Some(settings) => settings.read().unwrap().alerts.for_team(team_id).clock_minutes.clone(),
+    None => AlertThresholds::default().clock_minutes,
+  }
+}
+
+fn publish(
+  app: &AppHandle,
+  status: &GameClockStatus,
+  alerts: Vec<GameClockAlert>,
+  team_id: Option<String>,
+) {
+  let _ = app.emit(GAME_CLOCK_TICK_EVENT, status.clone());
+  for alert in alerts {
+    let _ = app.emit(GAME_CLOCK_ALERT_EVENT, alert.clone());
+    alerts::raise(
+      app,
+      ThresholdAlert {
+        source: AlertSource::GameClock,
+        team_id: team_id.clone(),
+        message: alert.message,
+      },
+    );
+  }
+}
+
+/// Emits ticks and alerts until the clock is paused or restarted.
+fn spawn_ticker(app: AppHandle, generation: u64) {
+  tauri::async_runtime::spawn(async move {
+    let mut interval = tokio::time::interval(TICK);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    // The first tick is immediate, and start already published
+    interval.tick().await;
+    loop {
+      interval.tick().await;
+      let (status, alerts, team_id) = {
+        let state = app.state::<GameClockState>();
+        let mut clock = state.0.lock().unwrap();
+        if clock.generation != generation || clock.running_since.is_none() {
+          break;
+        }
+        let now = Utc::now();
+        let warn_at = warn_at(&app, clock.team_id.as_deref());
+        (clock.status(now), clock.due_alerts(now, &warn_at), clock.team_id.clone())
+      };
+      publish(&app, &status, alerts, team_id);
+    }
+  });
+}
+
+#[tauri::command]
+pub fn get_game_clock(state: State<'_, GameClockState>) -> GameClockStatus {
+  state.0.lock().unwrap().status(Utc::now())
+}
+
+/// Starts the clock, or resumes it from where it was paused.
+#[tauri::command]
+pub fn start_game_clock(app: AppHandle, state: State<'_, GameClockState>) -> GameClockStatus {
+  let now = Utc::now();
+  let (status, alerts, team_id, started) = {
+    let mut clock = state.0.lock().unwrap();
+    let started = clock.running_since.is_none().then(|| {
+      clock.running_since = Some(now);
+      clock.generation += 1;
+      clock.generation
+    });
+    let warn_at = warn_at(&app, clock.team_id.as_deref());
+    let alerts = clock.due_alerts(now, &warn_at);
+    (clock.status(now), alerts, clock.team_id.clone(), started)
+  };
+  publish(&app, &status, alerts, team_id);
+  // Already running means a ticker is too
+  if let Some(generation) = started {
+    spawn_ticker(app, generation);
+  }
+  status
+}
+
+#[tauri::command]
+pub fn pause_game_clock(app: AppHandle, state: State<'_, GameClockState>) -> GameClockStatus {
+  let now = Utc::now();
+  let status = {
+    let mut clock = state.0.lock().unwrap();
+    clock.banked = clock.elapsed(now);
+    clock.running_since = None;
+    clock.status(now)
+  };
+  publish(&app, &status, Vec::new(), None);
+  status
+}
+
+/// Stops the clock and sets it back to zero, keeping the time limit and
+/// team.
+#[tauri::command]
+pub fn reset_game_clock(app: AppHandle, state: State<'_, GameClockState>) -> GameClockStatus {
+  let status = {
+    let mut clock = state.0.lock().unwrap();
+    *clock = Clock {
+      limit: clock.limit,
+      team_id: clock.team_id.take(),
+      generation: clock.generation,
+      ..Default::default()
+    };
+    clock.status(Utc::now())
+  };
+  publish(&app, &status, Vec::new(), None);
+  status
+}
+
+/// Sets the game's time limit in minutes from the start, or clears it with
+/// `None`. Alerts fire again for the new limit, at the warning times set
+/// for `team_id` (or the defaults).
+#[tauri::command]
+pub fn set_time_limit(
+  app: AppHandle,
+  state: State<'_, GameClockState>,
+  minutes: Option<u32>,
+  team_id: Option<String>,
+) -> Result<GameClockStatus> {
+  if minutes.is_some_and(|m| m == 0 || m > MAX_LIMIT_MINUTES) {
+    return Err(Error::Validation(format!(
+      "Time limit must be between 1 and {MAX_LIMIT_MINUTES} minutes"
+    )));
+  }
+  let now = Utc::now();
+  let (status, alerts) = {
+    let mut clock = state.0.lock().unwrap();
+    clock.limit = minutes.map(|m| Duration::from_secs(u64::from(m) * 60));
+    clock.team_id = team_id.clone();
+    clock.warned.clear();
+    clock.timed_up = false;
+    let warn_at = warn_at(&app, team_id.as_deref());
+    (clock.status(now), clock.due_alerts(now, &warn_at))
+  };
+  publish(&app, &status, alerts, team_id);
+  Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn warns_once_then_calls_time() {
+    let start = Utc::now();
+    let at = |minutes: i64| start + chrono::Duration::minutes(minutes);
+    let mut clock = Clock {
+      running_since: Some(start),
+      limit: Some(Duration::from_secs(90 * 60)),
+      ..Default::default()
+    };
+
+    assert!(clock.due_alerts(at(30), &[10]).is_empty());
+    let warning = clock.due_alerts(at(80), &[10]);
+    assert_eq!(warning[0].kind, AlertKind::Warning);
+    assert_eq!(warning[0].message, "10 minutes to drop-dead time");
+    assert!(clock.due_alerts(at(85), &[10]).is_empty());
+    assert_eq!(clock.due_alerts(at(95), &[10])[0].kind, AlertKind::TimeUp);
+    assert!(clock.due_alerts(at(96), &[10]).is_empty());
+    assert_eq!(clock.status(at(95)).remaining_secs, Some(0));
+  }
+
+  #[test]
+  fn warns_at_each_configured_time() {
+    let start = Utc::now();
+    let at = |minutes: i64| start + chrono::Duration::minutes(minutes);
+    let mut clock = Clock {
+      running_since: Some(start),
+      limit: Some(Duration::from_secs(60 * 60)),
+      ..Default::default()
+    };
+    let warn_at = [15, 5, 1];
+
+    assert_eq!(clock.due_alerts(at(45), &warn_at)[0].message, "15 minutes to drop-dead time");
+    assert_eq!(clock.due_alerts(at(55), &warn_at)[0].message, "5 minutes to drop-dead time");
+    assert!(clock.due_alerts(at(56), &warn_at).is_empty());
+
+    // Jumping past two at once, e.g. waking from sleep, warns just once
+    clock.warned.clear();
+    assert_eq!(clock.due_alerts(at(59), &warn_at).len(), 1);
+    assert!(clock.due_alerts(at(59), &warn_at).is_empty());
+  }
+
+  #[test]
+  fn pausing_banks_the_elapsed_time() {
+    let start = Utc::now();
+    let mut clock = Clock {
+      running_since: Some(start),
+      ..Default::default()
+    };
+    let paused_at = start + chrono::Duration::minutes(20);
+    clock.banked = clock.elapsed(paused_at);
+    clock.running_since = None;
+
+    let later = start + chrono::Duration::minutes(50);
+    assert_eq!(clock.status(later).elapsed_secs, 20 * 60);
+    assert!(!clock.status(later).running);
+  }
+}
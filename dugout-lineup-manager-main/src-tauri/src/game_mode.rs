@@ -0,0 +1,123 @@
+//! "Game Mode": shrinks the main window to a compact, always-on-top panel
+//! so the lineup stays visible over GameChanger or a browser during a game.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{
+  AppHandle, Emitter, LogicalSize, Manager, PhysicalPosition, PhysicalSize, State, WebviewWindow,
+};
+
+use crate::error::{Error, Result};
+use crate::telemetry::{Feature, Telemetry};
+
+/// Event telling the webview to switch between the full and compact layouts.
+pub const GAME_MODE_EVENT: &str = "game-mode-changed";
+
+const COMPACT_WIDTH: f64 = 420.0;
+const COMPACT_HEIGHT: f64 = 640.0;
+/// Gap between the compact window and the screen edge, in logical pixels.
+const EDGE_MARGIN: f64 = 16.0;
+
+/// Main window geometry from before Game Mode, restored on exit.
+#[derive(Debug, Clone, Copy)]
+struct SavedGeometry {
+  position: PhysicalPosition<i32>,
+  size: PhysicalSize<u32>,
+  maximized: bool,
+}
+
+#[derive(Default)]
+pub struct GameModeState(Mutex<Option<SavedGeometry>>);
+
+impl GameModeState {
+  pub fn is_active(&self) -> bool {
+    self.0.lock().unwrap().is_some()
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameModeStatus {
+  pub active: bool,
+}
+
+fn main_window(app: &AppHandle) -> Result<WebviewWindow> {
+  app
+    .get_webview_window("main")
+    .ok_or_else(|| Error::NotFound("Main window".into()))
+}
+
+fn notify(app: &AppHandle, active: bool) -> Result<GameModeStatus> {
+  let status = GameModeStatus { active };
+  app.emit(GAME_MODE_EVENT, status.clone())?;
+  Ok(status)
+}
+
+/// Docks the main window as a compact overlay in the top-right corner of
+/// its current monitor.
+#[tauri::command]
+pub async fn enter_game_mode(
+  app: AppHandle,
+  state: State<'_, GameModeState>,
+) -> Result<GameModeStatus> {
+  let window = main_window(&app)?;
+  {
+    let mut saved = state.0.lock().unwrap();
+    if saved.is_some() {
+      return Ok(GameModeStatus { active: true });
+    }
+    *saved = Some(SavedGeometry {
+      position: window.outer_position()?,
+      size: window.inner_size()?,
+      maximized: window.is_maximized()?,
+    });
+  }
+
+  window.unmaximize()?;
+  window.set_always_on_top(true)?;
+  // Game Mode must stay interactive even over other apps.
+  window.set_ignore_cursor_events(false)?;
+  window.set_size(LogicalSize::new(COMPACT_WIDTH, COMPACT_HEIGHT))?;
+  if let Some(monitor) = window.current_monitor()? {
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    let width = (COMPACT_WIDTH * scale) as i32;
+    let margin = (EDGE_MARGIN * scale) as i32;
+    window.set_position(PhysicalPosition::new(
+      area.position.x + area.size.width as i32 - width - margin,
+      area.position.y + margin,
+    ))?;
+  }
+  window.show()?;
+  window.set_focus()?;
+  app.state::<Telemetry>().record(Feature::GameModeEntered);
+  notify(&app, true)
+}
+
+/// Restores the main window to where it was before Game Mode.
+#[tauri::command]
+pub async fn exit_game_mode(
+  app: AppHandle,
+  state: State<'_, GameModeState>,
+) -> Result<GameModeStatus> {
+  let window = main_window(&app)?;
+  let Some(saved) = state.0.lock().unwrap().take() else {
+    return Ok(GameModeStatus { active: false });
+  };
+
+  window.set_always_on_top(false)?;
+  window.set_size(saved.size)?;
+  window.set_position(saved.position)?;
+  if saved.maximized {
+    window.maximize()?;
+  }
+  notify(&app, false)
+}
+
+#[tauri::command]
+pub fn get_game_mode(state: State<'_, GameModeState>) -> GameModeStatus {
+  GameModeStatus {
+    active: state.is_active(),
+  }
+}
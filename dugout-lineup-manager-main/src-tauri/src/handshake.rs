@@ -0,0 +1,277 @@
+//! The version handshake with the backend, so a sidecar from another
+//! release (or a remote server that hasn't been upgraded) never gets to
+//! read or write data it doesn't understand.
+//!
+//! Once the backend is healthy the shell asks `GET /version`, which answers
+//! with the backend's release, the HTTP API version it speaks and two data
+//! schema versions: the one its code writes and the one the data directory
+//! is at. An API this build doesn't speak, or data newer than the backend,
+//! is [`Incompatible`]; data older than the backend [`NeedsMigration`], which
+//! `migrate_backend_data` runs through the backend's bundled migration
+//! after taking a backup. Until it's sorted out, the proxy refuses every
+//! request with the reason.
+//!
+//! [`Incompatible`]: CompatibilityState::Incompatible
+//! [`NeedsMigration`]: CompatibilityState::NeedsMigration
+
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::backup::{self, BackupKind};
+use crate::error::{Error, Result};
+use crate::proxy::{self, ProxyError};
+use crate::sidecar::{SidecarManager, AUTH_HEADER};
+
+/// Emitted with the [`BackendCompatibility`] after every handshake.
+pub const BACKEND_COMPATIBILITY_EVENT: &str = "backend-compatibility";
+
+/// Header telling the backend which release of the app is asking.
+pub const APP_VERSION_HEADER: &str = "X-Dugout-App-Version";
+
+/// Versions of the backend's HTTP API this build speaks.
+pub const SUPPORTED_API_VERSIONS: RangeInclusive<u32> = 1..=1;
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A season of JSON files can take a while to rewrite.
+const MIGRATE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// `GET /version`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct VersionResponse {
+  pub version: String,
+  pub api_version: u32,
+  /// What the backend's code reads and writes.
+  pub schema_version: u32,
+  /// What the data directory is at.
+  pub data_schema_version: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CompatibilityState {
+  /// No handshake yet, or the backend isn't answering.
+  #[default]
+  Pending,
+  Compatible,
+  NeedsMigration,
+  Incompatible,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendCompatibility {
+  pub state: CompatibilityState,
+  pub backend_version: Option<String>,
+  pub api_version: Option<u32>,
+  pub schema_version: Option<u32>,
+  pub data_schema_version: Option<u32>,
+  /// What's wrong and what to do about it, unless compatible.
+  pub message: Option<String>,
+}
+
+impl BackendCompatibility {
+  /// Why requests to the backend are refused, if they are.
+  pub fn blocked(&self) -> Option<String> {
+    match self.state {
+      CompatibilityState::NeedsMigration | CompatibilityState::Incompatible => {
+        self.message.clone()
+      }
+      CompatibilityState::Pending | CompatibilityState::Compatible => None,
+    }
+  }
+}
+
+/// What this build makes of the backend's answer.
+pub fn assess(version: &VersionResponse, app_version: &str) -> BackendCompatibility {
+  let (state, message) = if !SUPPORTED_API_VERSIONS.contains(&version.api_version) {
+    let newer = version.api_version > *SUPPORTED_API_VERSIONS.end();
+    let update = if newer { "Dugout" } else { "the backend" };
+    (
+      CompatibilityState::Incompatible,
+      Some(format!(
+        "The backend ({}) speaks API version {}, which Dugout {} doesn't; update {}",
+        version.version, version.api_version, app_version, update
+      )),
+    )
+  } else if version.data_schema_version > version.schema_version {
+    (
+      CompatibilityState::Incompatible,
+      Some(format!(
+        "The data was written by a newer backend (schema {}, this one reads up to {}); \
+         update Dugout to use it",
+        version.data_schema_version, version.schema_version
+      )),
+    )
+  } else if version.data_schema_version < version.schema_version {
+    (
+      CompatibilityState::NeedsMigration,
+      Some(format!(
+        "The data needs upgrading from schema {} to {} before the backend can use it",
+        version.data_schema_version, version.schema_version
+      )),
+    )
+  } else {
+    (CompatibilityState::Compatible, None)
+  };
+  BackendCompatibility {
+    state,
+    backend_version: Some(version.version.clone()),
+    api_version: Some(version.api_version),
+    schema_version: Some(version.schema_version),
+    data_schema_version: Some(version.data_schema_version),
+    message,
+  }
+}
+
+fn app_version<R: Runtime>(app: &AppHandle<R>) -> String {
+  app.package_info().version.to_string()
+}
+
+async fn fetch_version(sidecar: &SidecarManager, app_version: &str) -> BackendCompatibility {
+  let response = reqwest::Client::new()
+    .get(format!("{}/version", sidecar.base_url()))
+    .header(AUTH_HEADER, sidecar.auth_token())
+    .header(APP_VERSION_HEADER, app_version)
+    .timeout(HANDSHAKE_TIMEOUT)
+    .send()
+    .await;
+  let pending = |e: reqwest::Error| BackendCompatibility {
+    message: Some(format!("The backend didn't answer the version check: {e}")),
+    ..Default::default()
+  };
+  let response = match response {
+    Ok(response) => response,
+    Err(e) => return pending(e),
+  };
+  // Backends from before the handshake have no such endpoint
+  if response.status() == StatusCode::NOT_FOUND {
+    return BackendCompatibility {
+      state: CompatibilityState::Incompatible,
+      message: Some("The backend is too old to report its version; update it".into()),
+      ..Default::default()
+    };
+  }
+  match response.error_for_status() {
+    Ok(response) => match response.json::<VersionResponse>().await {
+      Ok(version) => assess(&version, app_version),
+      Err(e) => pending(e),
+    },
+    Err(e) => pending(e),
+  }
+}
+
+/// Asks the backend for its versions and records what that means, for the
+/// proxy and the webview.
+pub async fn run<R: Runtime>(app: &AppHandle<R>) -> BackendCompatibility {
+  let version = app_version(app);
+  let sidecar = app.state::<SidecarManager>();
+  let compatibility = fetch_version(&sidecar, &version).await;
+  match compatibility.state {
+    CompatibilityState::Compatible => log::info!(
+      "Backend {} is compatible",
+      compatibility.backend_version.as_deref().unwrap_or_default()
+    ),
+    CompatibilityState::Pending => log::warn!(
+      "{}",
+      compatibility.message.as_deref().unwrap_or("No answer to the version check")
+    ),
+    _ => log::error!("{}", compatibility.message.as_deref().unwrap_or_default()),
+  }
+  sidecar.set_compatibility(compatibility.clone());
+  if let Err(e) = app.emit(BACKEND_COMPATIBILITY_EVENT, &compatibility) {
+    log::warn!("Failed to emit the backend compatibility: {}", e);
+  }
+  compatibility
+}
+
+#[tauri::command]
+pub fn get_backend_compatibility(sidecar: State<'_, SidecarManager>) -> BackendCompatibility {
+  sidecar.compatibility()
+}
+
+/// Runs the handshake again, e.g. after the coach updated a remote backend.
+#[tauri::command]
+pub async fn check_backend_compatibility(app: AppHandle) -> BackendCompatibility {
+  run(&app).await
+}
+
+/// Upgrades the sidecar's data with the migration bundled in it, after
+/// backing everything up, then shakes hands again. A remote backend's data
+/// is for whoever runs it to migrate.
+#[tauri::command]
+pub async fn migrate_backend_data(app: AppHandle) -> Result<BackendCompatibility> {
+  let (compatibility, remote) = {
+    let sidecar = app.state::<SidecarManager>();
+    (sidecar.compatibility(), sidecar.remote_url())
+  };
+  if compatibility.state != CompatibilityState::NeedsMigration {
+    return Err(Error::Validation("The backend's data doesn't need migrating".into()));
+  }
+  if let Some(url) = remote {
+    return Err(Error::Validation(format!(
+      "The data of the backend at {url} has to be migrated on its server"
+    )));
+  }
+
+  let handle = app.clone();
+  let snapshot = tauri::async_runtime::spawn_blocking(move || {
+    backup::create(&handle, BackupKind::PreUpdate)
+  })
+  .await??;
+  log::info!("Migrating the backend's data, backed up to {:?}", snapshot.path);
+
+  let response = {
+    let sidecar = app.state::<SidecarManager>();
+    reqwest::Client::new()
+      .post(format!("{}/migrate", sidecar.base_url()))
+      .header(AUTH_HEADER, sidecar.auth_token())
+      .header(APP_VERSION_HEADER, app_version(&app))
+      .timeout(MIGRATE_TIMEOUT)
+      .send()
+      .await
+      .map_err(|e| proxy::request_error(e, MIGRATE_TIMEOUT))?
+  };
+  if !response.status().is_success() {
+    let error: ProxyError = proxy::into_status_error(response).await;
+    return Err(error.into());
+  }
+  Ok(run(&app).await)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn version(api_version: u32, schema_version: u32, data_schema_version: u32) -> VersionResponse {
+    VersionResponse {
+      version: "1.4.0".into(),
+      api_version,
+      schema_version,
+      data_schema_version,
+    }
+  }
+
+  #[test]
+  fn refuses_backends_it_cant_work_with() {
+    let compatible = assess(&version(1, 3, 3), "1.4.0");
+    assert_eq!(compatible.state, CompatibilityState::Compatible);
+    assert_eq!(compatible.blocked(), None);
+
+    let migrate = assess(&version(1, 3, 2), "1.4.0");
+    assert_eq!(migrate.state, CompatibilityState::NeedsMigration);
+    assert!(migrate.blocked().is_some());
+
+    for (api, schema, data) in [(2, 3, 3), (0, 3, 3), (1, 3, 4)] {
+      let refused = assess(&version(api, schema, data), "1.4.0");
+      assert_eq!(refused.state, CompatibilityState::Incompatible, "{api} {schema} {data}");
+    }
+    assert!(assess(&version(2, 3, 3), "1.4.0").message.unwrap().ends_with("update Dugout"));
+
+    assert_eq!(BackendCompatibility::default().blocked(), None);
+  }
+}
@@ -0,0 +1,99 @@
+//! `--headless` runs: one [`HeadlessCommand`] against the data, with no
+//! window, webview, sidecar or tray, so league admins can script exports
+//! from the same binary (on a machine without a display, too). The data is
+//! found the way the app finds it, including `--data-dir`, `--profile` and
+//! directories moved in settings, and migrated like at any launch.
+//!
+//! What was written is printed on stdout and what went wrong on stderr,
+//! with a non-zero exit code.
+
+use std::path::{Path, PathBuf};
+
+use crate::cli::{self, HeadlessCommand, LineupFormat};
+use crate::db::{self, games, lineups, Database};
+use crate::error::{Error, Result};
+use crate::export::{self, lineup_csv, pdf};
+use crate::paths::AppPaths;
+use crate::settings;
+
+/// Where the app keeps its state when it isn't given `--data-dir`: the app
+/// data directory Tauri resolves for `identifier`.
+fn app_data_dir(identifier: &str) -> Result<PathBuf> {
+  if let Some(dir) = &cli::args().data_dir {
+    return Ok(dir.clone());
+  }
+  dirs::data_dir()
+    .map(|dir| dir.join(identifier))
+    .ok_or_else(|| Error::NotFound("The app data directory".into()))
+}
+
+/// Opens the active profile's database, which must already exist.
+fn open_database(identifier: &str) -> Result<Database> {
+  let paths = AppPaths::new(app_data_dir(identifier)?);
+  let profiles = paths.select_profile(&settings::load(&paths.settings));
+  // The app only logs a warning for an unknown profile, which nobody would
+  // see here
+  if let Some(wanted) = &cli::args().profile {
+    profiles.use_for_this_run(wanted)?;
+  }
+  let data_dir = paths.data();
+  if !data_dir.join(db::DB_FILE).exists() {
+    return Err(Error::NotFound(format!("Dugout data in {}", data_dir.display())));
+  }
+  Database::open(&data_dir, &paths.backups().join("pre-migration"))
+}
+
+fn export_lineup(
+  db: &Database,
+  game_id: &str,
+  format: LineupFormat,
+  output: Option<&Path>,
+) -> Result<PathBuf> {
+  let card = {
+    let conn = db.conn();
+    games::get(&conn, game_id)?;
+    let lineup = lineups::latest_for_game(&conn, game_id)?
+      .ok_or_else(|| Error::NotFound(format!("A lineup for game {game_id}")))?;
+    pdf::card(&conn, &lineup.id)?
+  };
+  let bytes = match format {
+    LineupFormat::Pdf => pdf::render(&card, &Default::default())?,
+    LineupFormat::Csv => lineup_csv::render(&card)?,
+  };
+  let path = match output {
+    Some(path) => path.to_path_buf(),
+    None => PathBuf::from(format!(
+      "{}.{}",
+      export::file_stem(&card.title()),
+      format.extension()
+    )),
+  };
+  std::fs::write(&path, bytes)?;
+  Ok(path)
+}
+
+fn execute(identifier: &str, command: &HeadlessCommand) -> Result<PathBuf> {
+  let db = open_database(identifier)?;
+  match command {
+    HeadlessCommand::ExportLineup {
+      game_id,
+      format,
+      output,
+    } => export_lineup(&db, game_id, *format, output.as_deref()),
+  }
+}
+
+/// Runs `command` for the app with this bundle `identifier`, returning the
+/// process's exit code.
+pub fn run(identifier: &str, command: &HeadlessCommand) -> i32 {
+  match execute(identifier, command) {
+    Ok(path) => {
+      println!("{}", path.display());
+      0
+    }
+    Err(e) => {
+      eprintln!("dugout: {e}");
+      1
+    }
+  }
+}
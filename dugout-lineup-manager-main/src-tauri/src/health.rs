@@ -0,0 +1,171 @@
+//! Health checks against the backend's `/health` endpoint, whether it is
+//! the sidecar or a remote server.
+//!
+//! The main window starts hidden (see `tauri.conf.json`) and is only shown
+//! once the backend answers, so coaches don't see "AI unavailable" errors
+//! during the few seconds the sidecar takes to boot, and not before the
+//! database is open (see [`crate::boot`]). The splash in [`crate::splash`]
+//! covers the wait.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::sidecar::SidecarManager;
+use crate::splash::{self, BootStage};
+
+/// Per-request timeout for a single health probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long the window stays hidden waiting for the backend before it is
+/// shown anyway in degraded mode.
+pub const READY_TIMEOUT: Duration = Duration::from_secs(20);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Result of a health probe, returned to the frontend.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendHealth {
+  pub healthy: bool,
+  pub version: Option<String>,
+  pub uptime_seconds: Option<f64>,
+  pub ollama_connected: bool,
+  pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HealthResponse {
+  api: String,
+  version: Option<String>,
+  uptime_seconds: Option<f64>,
+  #[serde(default)]
+  ollama_connected: bool,
+}
+
+/// Probes `GET {base_url}/health` once.
+pub async fn check(base_url: &str) -> BackendHealth {
+  match fetch(base_url).await {
+    Ok(body) => BackendHealth {
+      healthy: body.api == "ok",
+      version: body.version,
+      uptime_seconds: body.uptime_seconds,
+      ollama_connected: body.ollama_connected,
+      error: None,
+    },
+    Err(e) => BackendHealth {
+      error: Some(e.to_string()),
+      ..Default::default()
+    },
+  }
+}
+
+async fn fetch(base_url: &str) -> reqwest::Result<HealthResponse> {
+  reqwest::Client::new()
+    .get(format!("{base_url}/health"))
+    .timeout(PROBE_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?
+    .json()
+    .await
+}
+
+/// Polls until the backend reports healthy or `timeout` elapses.
+pub async fn wait_until_healthy(base_url: &str, timeout: Duration) -> bool {
+  let deadline = Instant::now() + timeout;
+  while Instant::now() < deadline {
+    if check(base_url).await.healthy {
+      return true;
+    }
+    tokio::time::sleep(POLL_INTERVAL).await;
+  }
+  false
+}
+
+/// Shows the main window once the sidecar is healthy, or after
+/// `READY_TIMEOUT` if it never comes up, and closes the splash. A remote
+/// backend has no boot to wait for, so it is probed once and the window
+/// shown either way.
+pub async fn reveal_main_window_when_ready<R: Runtime>(app: AppHandle<R>) {
+  let (running, remote, base_url) = {
+    let sidecar = app.state::<SidecarManager>();
+    (sidecar.status().running, sidecar.remote_url().is_some(), sidecar.base_url())
+  };
+
+  let healthy = if remote {
+    splash::report(&app, BootStage::ConnectingBackend);
+    match check(&base_url).await {
+      BackendHealth { healthy: true, .. } => {
+        log::info!("Remote backend at {} is up", base_url);
+        // A spawned sidecar shakes hands by itself; a remote one only here
+        crate::handshake::run(&app).await;
+        true
+      }
+      BackendHealth { error, .. } => {
+        log::warn!(
+          "Remote backend at {} is not healthy ({}); showing window in degraded mode",
+          base_url,
+          error.as_deref().unwrap_or("unhealthy")
+        );
+        false
+      }
+    }
+  } else if running {
+    let started = Instant::now();
+    let healthy = wait_until_healthy(&base_url, READY_TIMEOUT).await;
+    if healthy {
+      log::info!("Backend ready after {:?}", started.elapsed());
+    } else {
+      log::warn!(
+        "Backend not healthy after {:?}; showing window in degraded mode",
+        READY_TIMEOUT
+      );
+    }
+    healthy
+  } else {
+    false
+  };
+
+  // The main window is only created once the database is open
+  if !crate::boot::wait_until_open(&app).await {
+    return;
+  }
+  match app.get_webview_window("main") {
+    Some(window) => {
+      if let Err(e) = window.show().and_then(|_| window.set_focus()) {
+        log::error!("Failed to show main window: {}", e);
+      }
+    }
+    None => log::error!("Main window not found"),
+  }
+  splash::finish(&app, healthy);
+}
+
+/// Polls the backend's `/health` endpoint and reports status, version and
+/// uptime.
+#[tauri::command]
+pub async fn backend_health(sidecar: State<'_, SidecarManager>) -> Result<BackendHealth, String> {
+  Ok(check(&sidecar.base_url()).await)
+}
+
+/// Kills and respawns the sidecar, resolving once it passes a health check.
+#[tauri::command]
+pub async fn restart_backend(app: AppHandle) -> Result<BackendHealth, String> {
+  let handle = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    handle.state::<SidecarManager>().restart(&handle)
+  })
+  .await
+  .map_err(|e| e.to_string())??;
+
+  let base_url = app.state::<SidecarManager>().base_url();
+  if !wait_until_healthy(&base_url, READY_TIMEOUT).await {
+    return Err(format!(
+      "Backend restarted but did not become healthy within {} seconds",
+      READY_TIMEOUT.as_secs()
+    ));
+  }
+  Ok(check(&base_url).await)
+}
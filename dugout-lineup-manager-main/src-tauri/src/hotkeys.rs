@@ -0,0 +1,320 @@
+//! Global shortcuts for in-game actions, so the coach can move to the next
+//! batter or start the clock while GameChanger or a browser has focus.
+//!
+//! The combos live in [`HotkeySettings`]. [`set_hotkeys`] refuses a set
+//! that clashes with itself, with a shortcut registered elsewhere in the
+//! app or with one another app already holds, and keeps the old ones. The
+//! actions run here, so they work with the main window hidden; each press
+//! is also sent to the webview as [`HOTKEY_EVENT`].
+
+use std::sync::{Mutex, RwLock};
+
+use serde::Serialize;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, State, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::error::{Error, Result};
+use crate::game_clock;
+use crate::game_mode::{self, GameModeState};
+use crate::paths::AppPaths;
+use crate::scoreboard::{self, ScoreboardState};
+use crate::settings::{self, AppSettings, HotkeySettings, SETTINGS_CHANGED_EVENT};
+
+/// Emitted with the [`HotkeyAction`] after one of the shortcuts is pressed.
+pub const HOTKEY_EVENT: &str = "hotkey-pressed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyAction {
+  /// Moves the scoreboard to the next batter in the order.
+  AdvanceBatter,
+  /// Starts the game clock, or pauses it if running.
+  ToggleGameClock,
+  /// Enters or leaves Game Mode.
+  ToggleGameMode,
+}
+
+impl HotkeyAction {
+  fn label(self) -> &'static str {
+    match self {
+      HotkeyAction::AdvanceBatter => "advance batter",
+      HotkeyAction::ToggleGameClock => "toggle game clock",
+      HotkeyAction::ToggleGameMode => "show/hide Game Mode",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hotkey {
+  pub action: HotkeyAction,
+  pub shortcut: Option<String>,
+  /// Whether it works right now; `false` if another app took it first.
+  pub registered: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Binding {
+  action: HotkeyAction,
+  /// As the coach typed it, for messages.
+  combo: String,
+  shortcut: Shortcut,
+}
+
+/// The bindings this module has registered.
+#[derive(Default)]
+pub struct HotkeyState(Mutex<Vec<Binding>>);
+
+fn combos(settings: &HotkeySettings) -> [(HotkeyAction, Option<&str>); 3] {
+  [
+    (HotkeyAction::AdvanceBatter, settings.advance_batter.as_deref()),
+    (HotkeyAction::ToggleGameClock, settings.toggle_game_clock.as_deref()),
+    (HotkeyAction::ToggleGameMode, settings.toggle_game_mode.as_deref()),
+  ]
+}
+
+/// Parses every combo, rejecting one set for two actions.
+fn parse(settings: &HotkeySettings) -> Result<Vec<Binding>> {
+  let mut bindings: Vec<Binding> = Vec::new();
+  for (action, combo) in combos(settings) {
+    let Some(combo) = combo else {
+      continue;
+    };
+    let shortcut: Shortcut = combo.parse().map_err(|e| {
+      Error::Validation(format!("Invalid shortcut for {}: {e}", action.label()))
+    })?;
+    if let Some(other) = bindings.iter().find(|b| b.shortcut == shortcut) {
+      return Err(Error::Validation(format!(
+        "{combo} is set for both {} and {}",
+        other.action.label(),
+        action.label()
+      )));
+    }
+    bindings.push(Binding {
+      action,
+      combo: combo.to_string(),
+      shortcut,
+    });
+  }
+  Ok(bindings)
+}
+
+pub fn validate(settings: &HotkeySettings) -> Result<()> {
+  parse(settings).map(|_| ())
+}
+
+pub fn init() -> TauriPlugin<Wry> {
+  tauri_plugin_global_shortcut::Builder::new()
+    .with_handler(|app, shortcut, event| {
+      if event.state() != ShortcutState::Pressed {
+        return;
+      }
+      let registered = app.state::<HotkeyState>();
+      let action = registered
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|b| b.shortcut == *shortcut)
+        .map(|b| b.action);
+      if let Some(action) = action {
+        run(app, action);
+      }
+    })
+    .build()
+}
+
+/// Replaces the registered shortcuts with `bindings`, returning the ones
+/// that couldn't be registered and why.
+fn register(app: &AppHandle, bindings: Vec<Binding>) -> Vec<(Binding, &'static str)> {
+  let manager = app.global_shortcut();
+  let state = app.state::<HotkeyState>();
+  // Not held while registering, which waits on the thread that runs the
+  // handler
+  let previous = std::mem::take(&mut *state.0.lock().unwrap());
+  for binding in previous {
+    if let Err(e) = manager.unregister(binding.shortcut) {
+      log::warn!("Failed to unregister the {} hotkey: {}", binding.action.label(), e);
+    }
+  }
+
+  let mut registered = Vec::new();
+  let mut conflicts = Vec::new();
+  for binding in bindings {
+    if manager.is_registered(binding.shortcut) {
+      conflicts.push((binding, "is already a shortcut in Dugout"));
+      continue;
+    }
+    match manager.register(binding.shortcut) {
+      Ok(()) => registered.push(binding),
+      Err(e) => {
+        log::warn!("Failed to register {}: {}", binding.combo, e);
+        conflicts.push((binding, "is taken by another app"));
+      }
+    }
+  }
+  *state.0.lock().unwrap() = registered;
+  conflicts
+}
+
+/// Registers the shortcuts in `settings`, logging any that are taken.
+/// Called at launch and when the settings change.
+pub fn apply(app: &AppHandle, settings: &HotkeySettings) {
+  let bindings = match parse(settings) {
+    Ok(bindings) => bindings,
+    Err(e) => {
+      log::warn!("Ignoring hotkey settings: {}", e);
+      Vec::new()
+    }
+  };
+  for (binding, reason) in register(app, bindings) {
+    log::warn!("The {} hotkey {} {}", binding.action.label(), binding.combo, reason);
+  }
+}
+
+fn run(app: &AppHandle, action: HotkeyAction) {
+  log::debug!("Hotkey: {}", action.label());
+  match action {
+    HotkeyAction::AdvanceBatter => advance_batter(app),
+    HotkeyAction::ToggleGameClock => {
+      if game_clock::get_game_clock(app.state()).running {
+        game_clock::pause_game_clock(app.clone(), app.state());
+      } else {
+        game_clock::start_game_clock(app.clone(), app.state());
+      }
+    }
+    HotkeyAction::ToggleGameMode => {
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move {
+        let state = app.state::<GameModeState>();
+        let result = if state.is_active() {
+          game_mode::exit_game_mode(app.clone(), state).await
+        } else {
+          game_mode::enter_game_mode(app.clone(), state).await
+        };
+        if let Err(e) = result {
+          log::warn!("Game Mode hotkey failed: {}", e);
+        }
+      });
+    }
+  }
+  let _ = app.emit(HOTKEY_EVENT, action);
+}
+
+/// The batter after `current`, back to the top after the last.
+fn next_batter(current: Option<usize>, batters: usize) -> Option<usize> {
+  if batters == 0 {
+    return None;
+  }
+  Some(current.map_or(0, |i| (i + 1) % batters))
+}
+
+fn advance_batter(app: &AppHandle) {
+  let state = app.state::<ScoreboardState>();
+  let mut board = state.current();
+  if board.batting_order.is_empty() {
+    return;
+  }
+  board.current_batter = next_batter(board.current_batter, board.batting_order.len());
+  if let Err(e) = scoreboard::update_scoreboard(app.clone(), state, board) {
+    log::warn!("Failed to advance the batter: {}", e);
+  }
+}
+
+fn status(app: &AppHandle, settings: &HotkeySettings) -> Vec<Hotkey> {
+  let state = app.state::<HotkeyState>();
+  let registered = state.0.lock().unwrap();
+  combos(settings)
+    .into_iter()
+    .map(|(action, combo)| Hotkey {
+      action,
+      shortcut: combo.map(str::to_string),
+      registered: registered.iter().any(|b| b.action == action),
+    })
+    .collect()
+}
+
+#[tauri::command]
+pub fn get_hotkeys(app: AppHandle, settings: State<'_, RwLock<AppSettings>>) -> Vec<Hotkey> {
+  let hotkeys = settings.read().unwrap().hotkeys.clone();
+  status(&app, &hotkeys)
+}
+
+/// Registers new shortcuts and saves them. If any combo is used twice or
+/// already taken, nothing changes and the error names each conflict.
+#[tauri::command]
+pub async fn set_hotkeys(
+  app: AppHandle,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+  hotkeys: HotkeySettings,
+) -> Result<Vec<Hotkey>> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  let bindings = parse(&hotkeys)?;
+  let previous = settings.read().unwrap().hotkeys.clone();
+  let conflicts = register(&app, bindings);
+  if !conflicts.is_empty() {
+    apply(&app, &previous);
+    let conflicts: Vec<String> = conflicts
+      .iter()
+      .map(|(binding, reason)| {
+        format!("{} ({}) {}", binding.combo, binding.action.label(), reason)
+      })
+      .collect();
+    return Err(Error::Validation(format!(
+      "Hotkeys not changed: {}",
+      conflicts.join("; ")
+    )));
+  }
+
+  let saved = {
+    let mut settings = settings.write().unwrap();
+    let mut new_settings = settings.clone();
+    new_settings.hotkeys = hotkeys;
+    settings::save(&paths.settings, &new_settings)?;
+    *settings = new_settings;
+    settings.clone()
+  };
+  let _ = app.emit(SETTINGS_CHANGED_EVENT, &saved);
+  Ok(status(&app, &saved.hotkeys))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_combos_and_rejects_clashes() {
+    let bindings = parse(&HotkeySettings::default()).unwrap();
+    assert_eq!(bindings.len(), 3);
+
+    let settings = HotkeySettings {
+      toggle_game_mode: None,
+      ..Default::default()
+    };
+    assert_eq!(parse(&settings).unwrap().len(), 2);
+
+    let settings = HotkeySettings {
+      toggle_game_clock: Some("Ctrl+Shift+Nope".into()),
+      ..Default::default()
+    };
+    assert!(validate(&settings).is_err());
+
+    // The same combo spelled another way
+    let settings = HotkeySettings {
+      advance_batter: Some("ctrl+shift+B".into()),
+      toggle_game_clock: Some("Shift+Control+KeyB".into()),
+      toggle_game_mode: None,
+    };
+    assert!(validate(&settings).is_err());
+  }
+
+  #[test]
+  fn advancing_wraps_to_the_top_of_the_order() {
+    assert_eq!(next_batter(None, 9), Some(0));
+    assert_eq!(next_batter(Some(3), 9), Some(4));
+    assert_eq!(next_batter(Some(8), 9), Some(0));
+    assert_eq!(next_batter(Some(2), 0), None);
+  }
+}
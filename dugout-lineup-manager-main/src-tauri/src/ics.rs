@@ -0,0 +1,625 @@
+//! Season schedule to and from iCalendar (`.ics`), the format leagues
+//! publish their calendars in and parents subscribe to.
+//!
+//! Event times give each game its day and start time. Times in a named zone
+//! (`TZID`) are kept as written, which is the league's; UTC times are moved
+//! to local time.
+//! Recurring events are expanded from their `RRULE`, minus `EXDATE`s and
+//! with moved instances (`RECURRENCE-ID`) in their new place.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{Datelike, Days, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use rusqlite::Connection;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::db::games::{self, Game, ScheduledGame};
+use crate::db::{roster, Database};
+use crate::error::{Error, Result};
+
+/// Instances generated for a rule without `COUNT` or `UNTIL`.
+const MAX_OCCURRENCES: usize = 100;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// How long exported games are shown as lasting.
+const GAME_LENGTH: chrono::Duration = chrono::Duration::hours(2);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IcsImport {
+  pub created: usize,
+  pub updated: usize,
+  /// Practices and cancelled events.
+  pub skipped: usize,
+}
+
+/// One content line: `NAME;PARAM=value:value`.
+#[derive(Debug)]
+struct Property {
+  name: String,
+  params: Vec<(String, String)>,
+  value: String,
+}
+
+impl Property {
+  fn param(&self, name: &str) -> Option<&str> {
+    self
+      .params
+      .iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+}
+
+/// Joins folded lines and splits each into name, parameters and value.
+fn properties(text: &str) -> Vec<Property> {
+  let mut lines: Vec<String> = Vec::new();
+  for line in text.lines() {
+    match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+      (Some(rest), Some(last)) => last.push_str(rest),
+      _ => lines.push(line.to_string()),
+    }
+  }
+  lines
+    .iter()
+    .filter_map(|line| {
+      // The value starts at the first colon outside a quoted parameter
+      let mut quoted = false;
+      let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+          quoted = !quoted;
+          None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+      })?;
+      let mut head = line[..colon].split(';');
+      let name = head.next()?.trim().to_uppercase();
+      let params = head
+        .filter_map(|param| {
+          let (key, value) = param.split_once('=')?;
+          Some((key.to_uppercase(), value.trim_matches('"').to_string()))
+        })
+        .collect();
+      Some(Property {
+        name,
+        params,
+        value: line[colon + 1..].to_string(),
+      })
+    })
+    .collect()
+}
+
+fn unescape(value: &str) -> String {
+  let mut out = String::with_capacity(value.len());
+  let mut chars = value.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      out.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('n' | 'N') => out.push('\n'),
+      Some(other) => out.push(other),
+      None => {}
+    }
+  }
+  out
+}
+
+fn escape(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace(';', "\\;")
+    .replace(',', "\\,")
+    .replace("\r\n", "\\n")
+    .replace('\n', "\\n")
+}
+
+/// The calendar day of a `DATE` or `DATE-TIME` value, and its time of day
+/// if it has one.
+fn moment(value: &str, tzid: Option<&str>) -> Option<(NaiveDate, Option<NaiveTime>)> {
+  let value = value.trim();
+  if value.len() == 8 {
+    return NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(|day| (day, None));
+  }
+  let (written, utc) = match value.strip_suffix('Z') {
+    Some(written) => (written, true),
+    None => (value, false),
+  };
+  let mut time = NaiveDateTime::parse_from_str(written, "%Y%m%dT%H%M%S").ok()?;
+  // A named zone wins over a stray `Z`
+  if utc && tzid.is_none() {
+    time = time.and_utc().with_timezone(&Local).naive_local();
+  }
+  Some((time.date(), Some(time.time())))
+}
+
+fn day(value: &str, tzid: Option<&str>) -> Option<NaiveDate> {
+  moment(value, tzid).map(|(day, _)| day)
+}
+
+fn days(property: &Property) -> Vec<NaiveDate> {
+  property
+    .value
+    .split(',')
+    .filter_map(|value| day(value, property.param("TZID")))
+    .collect()
+}
+
+#[derive(Debug, Default)]
+struct Event {
+  uid: String,
+  summary: String,
+  location: String,
+  description: String,
+  cancelled: bool,
+  start: Option<NaiveDate>,
+  start_time: Option<NaiveTime>,
+  rrule: Option<String>,
+  exdates: Vec<NaiveDate>,
+  recurrence_id: Option<NaiveDate>,
+}
+
+fn events(text: &str) -> Result<Vec<Event>> {
+  let properties = properties(text);
+  if !properties
+    .iter()
+    .any(|p| p.name == "BEGIN" && p.value.eq_ignore_ascii_case("VCALENDAR"))
+  {
+    return Err(Error::Validation("This isn't an iCalendar file".into()));
+  }
+  let mut events = Vec::new();
+  let mut current: Option<Event> = None;
+  // Nested components such as VALARM have their own DTSTART and UID
+  let mut depth = 0;
+  for property in properties {
+    let component = property.value.to_uppercase();
+    match (property.name.as_str(), current.as_mut()) {
+      ("BEGIN", None) if component == "VEVENT" => current = Some(Event::default()),
+      ("BEGIN", Some(_)) => depth += 1,
+      ("END", Some(_)) if depth > 0 => depth -= 1,
+      ("END", Some(_)) if component == "VEVENT" => events.extend(current.take()),
+      (_, Some(event)) if depth == 0 => match property.name.as_str() {
+        "UID" => event.uid = property.value.trim().to_string(),
+        "SUMMARY" => event.summary = unescape(&property.value).trim().to_string(),
+        "LOCATION" => event.location = unescape(&property.value).trim().to_string(),
+        "DESCRIPTION" => event.description = unescape(&property.value).trim().to_string(),
+        "STATUS" => event.cancelled = property.value.trim().eq_ignore_ascii_case("CANCELLED"),
+        "DTSTART" => {
+          let start = moment(&property.value, property.param("TZID"));
+          event.start = start.map(|(day, _)| day);
+          event.start_time = start.and_then(|(_, time)| time);
+        }
+        "RRULE" => event.rrule = Some(property.value.trim().to_uppercase()),
+        "EXDATE" => event.exdates.extend(days(&property)),
+        "RECURRENCE-ID" => event.recurrence_id = day(&property.value, property.param("TZID")),
+        _ => {}
+      },
+      _ => {}
+    }
+  }
+  Ok(events)
+}
+
+fn weekday(code: &str) -> Option<Weekday> {
+  // BYDAY may carry an ordinal, e.g. `2SA`; only the day is used
+  let code = code.trim_start_matches(|c: char| c == '+' || c == '-' || c.is_ascii_digit());
+  Some(match code {
+    "MO" => Weekday::Mon,
+    "TU" => Weekday::Tue,
+    "WE" => Weekday::Wed,
+    "TH" => Weekday::Thu,
+    "FR" => Weekday::Fri,
+    "SA" => Weekday::Sat,
+    "SU" => Weekday::Sun,
+    _ => return None,
+  })
+}
+
+/// The days a recurring event falls on, starting with `start`. Supports
+/// daily, weekly (with `BYDAY`) and monthly rules, which is what league
+/// calendars use; anything else yields just the first day.
+fn occurrences(start: NaiveDate, rule: &str) -> Vec<NaiveDate> {
+  let parts: HashMap<&str, &str> = rule.split(';').filter_map(|p| p.split_once('=')).collect();
+  let interval: u32 = parts
+    .get("INTERVAL")
+    .and_then(|i| i.parse().ok())
+    .filter(|&i| i > 0)
+    .unwrap_or(1);
+  let count = parts.get("COUNT").and_then(|c| c.parse::<usize>().ok());
+  let until = parts.get("UNTIL").and_then(|u| day(u, None));
+  let limit = count.unwrap_or(MAX_OCCURRENCES).min(MAX_OCCURRENCES * 4);
+  let mut by_day: Vec<Weekday> = parts
+    .get("BYDAY")
+    .map(|days| days.split(',').filter_map(weekday).collect())
+    .unwrap_or_default();
+  by_day.sort_by_key(|d| d.num_days_from_monday());
+
+  let mut days = Vec::new();
+  let mut push = |day: NaiveDate| {
+    if until.is_some_and(|until| day > until) || days.len() >= limit {
+      return false;
+    }
+    days.push(day);
+    true
+  };
+  match parts.get("FREQ").copied() {
+    Some("DAILY") => {
+      let mut day = start;
+      while push(day) {
+        let Some(next) = day.checked_add_days(Days::new(u64::from(interval))) else {
+          break;
+        };
+        day = next;
+      }
+    }
+    Some("WEEKLY") => {
+      if by_day.is_empty() {
+        by_day.push(start.weekday());
+      }
+      let mut week = start - Days::new(u64::from(start.weekday().num_days_from_monday()));
+      'weeks: loop {
+        for weekday in &by_day {
+          let day = week + Days::new(u64::from(weekday.num_days_from_monday()));
+          if day >= start && !push(day) {
+            break 'weeks;
+          }
+        }
+        week = week + Days::new(7 * u64::from(interval));
+      }
+    }
+    Some("MONTHLY") => {
+      // Months too short for the day are skipped, as RFC 5545 says
+      let mut step = 0;
+      while let Some(day) = start.checked_add_months(Months::new(step * interval)) {
+        if step > 12 * 10 || (day.day() == start.day() && !push(day)) {
+          break;
+        }
+        step += 1;
+      }
+    }
+    _ => {
+      push(start);
+    }
+  }
+  days
+}
+
+/// Splits "Eagles vs Tigers" or "Eagles @ Tigers" into the opponent and
+/// whether the game is at home, using `team` to tell which side is ours.
+/// Listings name the home team first with "vs" and second with "@".
+fn matchup(summary: &str, team: &str) -> (String, &'static str) {
+  let lower = summary.to_lowercase();
+  let separators = [(" vs. ", true), (" vs ", true), (" v ", true), (" @ ", false), (" at ", false)];
+  for (separator, first_is_home) in separators {
+    let Some(at) = lower.find(separator) else {
+      continue;
+    };
+    let (first, second) = (summary[..at].trim(), summary[at + separator.len()..].trim());
+    let ours_first = !team.is_empty() && first.to_lowercase().contains(&team.to_lowercase());
+    let ours_second = !team.is_empty() && second.to_lowercase().contains(&team.to_lowercase());
+    let (opponent, we_are_first) = match (ours_first, ours_second) {
+      (_, true) => (first, false),
+      // Without our name, "vs Tigers" and "@ Tigers" still read from our side
+      _ => (second, true),
+    };
+    let home = if we_are_first == first_is_home { "home" } else { "away" };
+    return (opponent.to_string(), home);
+  }
+  let bare = summary.trim_start_matches(|c: char| c == '@' || c.is_whitespace());
+  let home = if bare.len() < summary.len() { "away" } else { "home" };
+  let bare = bare.strip_prefix("vs ").or_else(|| bare.strip_prefix("vs. ")).unwrap_or(bare);
+  (bare.trim().to_string(), home)
+}
+
+fn game_id(uid: &str, day: NaiveDate) -> String {
+  let digest = Sha256::digest(format!("{uid}/{day}").as_bytes());
+  let hex: String = digest[..8].iter().map(|b| format!("{b:02x}")).collect();
+  format!("ics-{hex}")
+}
+
+/// Games from a calendar, with `team` as the name our side goes by. Events
+/// mentioning practice and cancelled events are skipped.
+pub fn parse(text: &str, team: &str) -> Result<(Vec<ScheduledGame>, usize)> {
+  let events = events(text)?;
+  let moved: HashMap<(&str, NaiveDate), &Event> = events
+    .iter()
+    .filter_map(|e| Some(((e.uid.as_str(), e.recurrence_id?), e)))
+    .collect();
+
+  let mut games = Vec::new();
+  let mut skipped = 0;
+  for event in events.iter().filter(|e| e.recurrence_id.is_none()) {
+    let Some(start) = event.start else {
+      continue;
+    };
+    let days = match &event.rrule {
+      Some(rule) => occurrences(start, rule),
+      None => vec![start],
+    };
+    for day in days.into_iter().filter(|day| !event.exdates.contains(day)) {
+      // A moved instance keeps its original id, so it stays the same game
+      let (instance, date) = match moved.get(&(event.uid.as_str(), day)) {
+        Some(moved) => (*moved, moved.start.unwrap_or(day)),
+        None => (event, day),
+      };
+      if instance.cancelled || instance.summary.to_lowercase().contains("practice") {
+        skipped += 1;
+        continue;
+      }
+      let (opponent, home_away) = matchup(&instance.summary, team);
+      games.push(ScheduledGame {
+        id: game_id(&event.uid, day),
+        date: date.format("%Y-%m-%d").to_string(),
+        opponent: if opponent.is_empty() { "TBD".into() } else { opponent },
+        home_away: home_away.to_string(),
+        score_us: None,
+        score_them: None,
+        notes: instance.description.clone(),
+        location: instance.location.clone(),
+        start_time: instance.start_time.map(|time| time.format("%H:%M").to_string()),
+      });
+    }
+  }
+  Ok((games, skipped))
+}
+
+/// Folds a content line at 75 octets without splitting a character.
+fn fold(line: &str, out: &mut String) {
+  let mut width = 0;
+  for c in line.chars() {
+    if width + c.len_utf8() > 75 {
+      out.push_str("\r\n ");
+      width = 1;
+    }
+    out.push(c);
+    width += c.len_utf8();
+  }
+  out.push_str("\r\n");
+}
+
+/// The schedule as a calendar, one event per game: at its start time when
+/// it has one, otherwise all day.
+pub fn write(team: &str, games: &[Game]) -> String {
+  let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+  let mut out = String::new();
+  let mut line = |line: String| fold(&line, &mut out);
+  line("BEGIN:VCALENDAR".into());
+  line("VERSION:2.0".into());
+  line("PRODID:-//Dugout//Lineup Manager//EN".into());
+  line("CALSCALE:GREGORIAN".into());
+  line("METHOD:PUBLISH".into());
+  line(format!("X-WR-CALNAME:{}", escape(team)));
+  for game in games {
+    let Ok(date) = NaiveDate::parse_from_str(&game.date, "%Y-%m-%d") else {
+      continue;
+    };
+    let summary = match game.home_away.as_str() {
+      "away" => format!("{team} @ {}", game.opponent),
+      _ => format!("{team} vs {}", game.opponent),
+    };
+    let mut description = Vec::new();
+    if let (Some(result), Some(us), Some(them)) = (&game.result, game.score_us, game.score_them) {
+      description.push(format!("{result} {us}-{them}"));
+    }
+    if !game.notes.is_empty() {
+      description.push(game.notes.clone());
+    }
+    line("BEGIN:VEVENT".into());
+    line(format!("UID:{}@dugout", game.id));
+    line(format!("DTSTAMP:{stamp}"));
+    let start_time = game.start_time.as_deref();
+    match start_time.and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok()) {
+      // Floating time: the game starts at the same clock time wherever the
+      // calendar is opened
+      Some(time) => {
+        let start = date.and_time(time);
+        line(format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")));
+        line(format!("DTEND:{}", (start + GAME_LENGTH).format("%Y%m%dT%H%M%S")));
+      }
+      None => {
+        line(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+        line(format!("DTEND;VALUE=DATE:{}", (date + Days::new(1)).format("%Y%m%d")));
+      }
+    }
+    line(format!("SUMMARY:{}", escape(&summary)));
+    if !game.location.is_empty() {
+      line(format!("LOCATION:{}", escape(&game.location)));
+    }
+    if !description.is_empty() {
+      line(format!("DESCRIPTION:{}", escape(&description.join("\n"))));
+    }
+    line("END:VEVENT".into());
+  }
+  line("END:VCALENDAR".into());
+  out
+}
+
+/// Reads a calendar from a file, or downloads it from an `http(s)://` or
+/// `webcal://` feed.
+async fn fetch(source: &str) -> Result<String> {
+  let url = match source.strip_prefix("webcal://") {
+    Some(rest) => format!("https://{rest}"),
+    None => source.to_string(),
+  };
+  if !(url.starts_with("https://") || url.starts_with("http://")) {
+    return Ok(tokio::fs::read_to_string(source).await?);
+  }
+  let download_error =
+    |e: reqwest::Error| Error::Validation(format!("Couldn't download the calendar: {e}"));
+  let response = reqwest::Client::new()
+    .get(&url)
+    .timeout(FETCH_TIMEOUT)
+    .send()
+    .await
+    .and_then(reqwest::Response::error_for_status)
+    .map_err(download_error)?;
+  response.text().await.map_err(download_error)
+}
+
+fn merge(conn: &mut Connection, team_id: &str, text: &str) -> Result<IcsImport> {
+  let team = roster::team_name(conn, team_id)?;
+  let (games, skipped) = parse(text, &team)?;
+  let tx = conn.transaction()?;
+  let mut summary = IcsImport {
+    skipped,
+    ..Default::default()
+  };
+  for game in &games {
+    if games::upsert_scheduled(&tx, team_id, game)? {
+      summary.created += 1;
+    } else {
+      summary.updated += 1;
+    }
+  }
+  tx.commit()?;
+  Ok(summary)
+}
+
+/// Adds the games in a calendar file or feed to the schedule of `team_id`.
+/// Importing the same calendar again updates its games in place.
+#[tauri::command]
+pub async fn import_ics(
+  app: AppHandle,
+  source: String,
+  team_id: Option<String>,
+) -> Result<IcsImport> {
+  let text = fetch(source.trim()).await?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let db = app.state::<Database>();
+    let mut conn = db.conn();
+    let team_id = match team_id {
+      Some(team_id) => team_id,
+      None => roster::default_team_id(&conn)?,
+    };
+    let summary = merge(&mut conn, &team_id, &text)?;
+    log::info!(
+      "Imported calendar: {} new games, {} updated",
+      summary.created,
+      summary.updated
+    );
+    Ok(summary)
+  })
+  .await?
+}
+
+/// Saves the schedule as an `.ics` file. Without `path`, asks where to save
+/// it and returns `None` if the user cancels.
+#[tauri::command]
+pub async fn export_ics(
+  app: AppHandle,
+  team_id: Option<String>,
+  path: Option<PathBuf>,
+) -> Result<Option<PathBuf>> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let (team, calendar) = {
+      let db = app.state::<Database>();
+      let conn = db.conn();
+      let team_id = match team_id {
+        Some(team_id) => team_id,
+        None => roster::default_team_id(&conn)?,
+      };
+      let team = roster::team_name(&conn, &team_id)?;
+      let calendar = write(&team, &games::list(&conn, &team_id)?);
+      (team, calendar)
+    };
+    let path = match path {
+      Some(path) => path,
+      None => {
+        let file_name = format!("{} schedule.ics", crate::export::file_stem(&team));
+        match crate::export::pick_save_path(&app, &file_name, "Calendar", &["ics"]) {
+          Some(path) => path,
+          None => return Ok(None),
+        }
+      }
+    };
+    std::fs::write(&path, calendar)?;
+    log::info!("Saved schedule calendar to {:?}", path);
+    Ok(Some(path))
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+
+  const FEED: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VTIMEZONE\r
+TZID:America/Chicago\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:opener@league\r
+DTSTART;TZID=America/Chicago:20260411T233000\r
+SUMMARY:Eagles vs Tigers\r
+LOCATION:Field 3\\, North Park\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:weekly@league\r
+DTSTART;TZID=\"America/Chicago\":20260414T180000\r
+RRULE:FREQ=WEEKLY;BYDAY=TU,TH;COUNT=4\r
+EXDATE;TZID=America/Chicago:20260416T180000\r
+SUMMARY:Cubs @ Eagles\r
+DESCRIPTION:Bring the white\r
+  jerseys\r
+BEGIN:VALARM\r
+TRIGGER:-PT1H\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:weekly@league\r
+RECURRENCE-ID;TZID=America/Chicago:20260421T180000\r
+DTSTART;TZID=America/Chicago:20260422T180000\r
+SUMMARY:Cubs @ Eagles (moved)\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:practice@league\r
+DTSTART;VALUE=DATE:20260415\r
+SUMMARY:Eagles practice\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+  #[test]
+  fn expands_recurring_events_in_their_own_timezone() {
+    let (games, skipped) = parse(FEED, "Eagles").unwrap();
+    assert_eq!(skipped, 1);
+    let dates: Vec<&str> = games.iter().map(|g| g.date.as_str()).collect();
+    // A late game stays on its day; the 16th is excluded and the 21st moved
+    assert_eq!(dates, ["2026-04-11", "2026-04-14", "2026-04-22", "2026-04-23"]);
+    assert_eq!((games[0].opponent.as_str(), games[0].home_away.as_str()), ("Tigers", "home"));
+    assert_eq!(games[0].location, "Field 3, North Park");
+    assert_eq!(games[0].start_time.as_deref(), Some("23:30"));
+    assert_eq!((games[1].opponent.as_str(), games[1].home_away.as_str()), ("Cubs", "home"));
+    assert_eq!(games[1].notes, "Bring the white jerseys");
+
+    assert_eq!(matchup("@ Tigers", ""), ("Tigers".to_string(), "away"));
+    assert_eq!(matchup("Eagles at Cubs", "eagles"), ("Cubs".to_string(), "away"));
+  }
+
+  #[test]
+  fn round_trips_an_exported_schedule() {
+    let mut conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    conn.execute("UPDATE teams SET name = 'Eagles'", []).unwrap();
+    let first = merge(&mut conn, &team_id, FEED).unwrap();
+    assert_eq!((first.created, first.updated), (4, 0));
+    assert_eq!(merge(&mut conn, &team_id, FEED).unwrap().updated, 4);
+
+    let calendar = write("Eagles", &games::list(&conn, &team_id).unwrap());
+    assert!(calendar.contains("DTSTART:20260411T233000\r\n"));
+    assert!(calendar.lines().all(|line| line.len() <= 75));
+    let (again, _) = parse(&calendar, "Eagles").unwrap();
+    let opponents: Vec<&str> = again.iter().map(|g| g.opponent.as_str()).collect();
+    assert_eq!(opponents, ["Tigers", "Cubs", "Cubs", "Cubs"]);
+    assert_eq!(again[0].location, "Field 3, North Park");
+  }
+}
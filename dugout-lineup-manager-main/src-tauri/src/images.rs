@@ -0,0 +1,411 @@
+//! Team logos and player photos.
+//!
+//! `import_image` copies a PNG or JPEG the coach picks into the data
+//! directory's `images/` folder, so it's in every backup, along with two
+//! smaller copies for lists and cards. Every copy is decoded and encoded
+//! again rather than copied byte for byte: that turns phone photos upright
+//! and leaves their EXIF metadata behind, GPS position included.
+//!
+//! The webview loads them from the `asset:` scheme, which
+//! [`serve_asset`] answers from the active profile's data directory. An
+//! image's URLs never change, since a new logo or photo gets a new id.
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::{new_id, roster, timestamp, Database};
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+
+/// The URI scheme images are served on.
+pub const SCHEME: &str = "asset";
+
+/// Folder under the data directory holding the files.
+const DIR: &str = "images";
+
+/// Bigger files are almost certainly not a logo or a headshot.
+const MAX_FILE_SIZE: u64 = 25 * 1024 * 1024;
+
+/// Copies kept of each image, by file name suffix, each at most this many
+/// pixels on its long side.
+const FULL_SIZE: u32 = 2048;
+const MEDIUM: (&str, u32) = ("medium", 512);
+const SMALL: (&str, u32) = ("small", 128);
+
+const JPEG_QUALITY: u8 = 88;
+
+/// Who an image belongs to. Each team has one logo and each player one
+/// photo; importing another replaces it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ImageOwner {
+  #[serde(rename_all = "camelCase")]
+  Team { team_id: String },
+  #[serde(rename_all = "camelCase")]
+  Player { player_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageAsset {
+  pub id: String,
+  pub team_id: Option<String>,
+  pub player_id: Option<String>,
+  /// Of the full-size copy.
+  pub width: u32,
+  pub height: u32,
+  pub url: String,
+  /// For cards and profiles.
+  pub medium_url: String,
+  /// For lists and avatars.
+  pub small_url: String,
+  pub created_at: String,
+}
+
+/// What's stored for an image; the URLs follow from it.
+struct Stored {
+  id: String,
+  team_id: Option<String>,
+  player_id: Option<String>,
+  extension: String,
+  width: u32,
+  height: u32,
+  created_at: String,
+}
+
+/// The URL the webview loads `file_name` from. Windows and Android
+/// webviews only allow custom schemes as `http://<scheme>.localhost`.
+pub fn asset_url(file_name: &str) -> String {
+  if cfg!(any(windows, target_os = "android")) {
+    format!("http://{SCHEME}.localhost/{DIR}/{file_name}")
+  } else {
+    format!("{SCHEME}://localhost/{DIR}/{file_name}")
+  }
+}
+
+fn file_name(id: &str, suffix: Option<&str>, extension: &str) -> String {
+  match suffix {
+    Some(suffix) => format!("{id}-{suffix}.{extension}"),
+    None => format!("{id}.{extension}"),
+  }
+}
+
+impl Stored {
+  fn file_names(&self) -> [String; 3] {
+    [None, Some(MEDIUM.0), Some(SMALL.0)].map(|s| file_name(&self.id, s, &self.extension))
+  }
+
+  fn into_asset(self) -> ImageAsset {
+    let [full, medium, small] = self.file_names();
+    ImageAsset {
+      url: asset_url(&full),
+      medium_url: asset_url(&medium),
+      small_url: asset_url(&small),
+      id: self.id,
+      team_id: self.team_id,
+      player_id: self.player_id,
+      width: self.width,
+      height: self.height,
+      created_at: self.created_at,
+    }
+  }
+}
+
+const COLUMNS: &str = "id, team_id, player_id, extension, width, height, created_at";
+
+fn stored_from_row(row: &Row) -> rusqlite::Result<Stored> {
+  Ok(Stored {
+    id: row.get(0)?,
+    team_id: row.get(1)?,
+    player_id: row.get(2)?,
+    extension: row.get(3)?,
+    width: row.get(4)?,
+    height: row.get(5)?,
+    created_at: row.get(6)?,
+  })
+}
+
+fn stored_for(conn: &Connection, owner: &ImageOwner) -> Result<Option<Stored>> {
+  let (column, id) = match owner {
+    ImageOwner::Team { team_id } => ("team_id", team_id),
+    ImageOwner::Player { player_id } => ("player_id", player_id),
+  };
+  Ok(
+    conn
+      .query_row(
+        &format!("SELECT {COLUMNS} FROM images WHERE {column} = ?1"),
+        [id],
+        stored_from_row,
+      )
+      .optional()?,
+  )
+}
+
+/// Every logo and photo.
+pub fn list(conn: &Connection) -> Result<Vec<ImageAsset>> {
+  let mut stmt = conn.prepare(&format!("SELECT {COLUMNS} FROM images ORDER BY created_at"))?;
+  let images = stmt
+    .query_map([], stored_from_row)?
+    .map(|stored| stored.map(Stored::into_asset))
+    .collect::<rusqlite::Result<_>>()?;
+  Ok(images)
+}
+
+/// Reads a PNG or JPEG, turned the way its EXIF orientation says.
+fn decode(path: &Path) -> Result<(DynamicImage, ImageFormat)> {
+  let size = std::fs::metadata(path)?.len();
+  if size > MAX_FILE_SIZE {
+    return Err(Error::Validation("Images must be smaller than 25 MB".into()));
+  }
+  let unreadable =
+    |e: image::ImageError| Error::Validation(format!("Couldn't read {}: {e}", path.display()));
+  let reader = ImageReader::open(path)?.with_guessed_format()?;
+  let format = match reader.format() {
+    Some(format @ (ImageFormat::Png | ImageFormat::Jpeg)) => format,
+    _ => {
+      return Err(Error::Validation(
+        "Only PNG and JPEG images can be imported".into(),
+      ))
+    }
+  };
+  let mut decoder = reader.into_decoder().map_err(unreadable)?;
+  let orientation = decoder.orientation().map_err(unreadable)?;
+  let mut image = DynamicImage::from_decoder(decoder).map_err(unreadable)?;
+  image.apply_orientation(orientation);
+  Ok((image, format))
+}
+
+/// `image` shrunk to fit `size` pixels on its long side, or as it is if
+/// it already fits.
+fn fit(image: &DynamicImage, size: u32) -> DynamicImage {
+  if image.width().max(image.height()) <= size {
+    return image.clone();
+  }
+  image.resize(size, size, FilterType::Lanczos3)
+}
+
+/// Encodes without any of the original's metadata. JPEGs have no alpha
+/// channel, so PNGs stay PNGs to keep transparent logos transparent.
+fn encode(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>> {
+  let mut out = Vec::new();
+  match format {
+    ImageFormat::Jpeg => {
+      let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+      rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY))?;
+    }
+    _ => image.write_to(&mut Cursor::new(&mut out), ImageFormat::Png)?,
+  }
+  Ok(out)
+}
+
+fn remove_files(dir: &Path, names: &[String]) {
+  for name in names {
+    if let Err(e) = std::fs::remove_file(dir.join(name)) {
+      if e.kind() != std::io::ErrorKind::NotFound {
+        log::warn!("Failed to remove image {}: {}", name, e);
+      }
+    }
+  }
+}
+
+/// Writes the three copies of the image at `source` under `data_dir` and
+/// makes it `owner`'s, removing the one it replaces.
+pub fn import(
+  conn: &mut Connection,
+  data_dir: &Path,
+  source: &Path,
+  owner: &ImageOwner,
+) -> Result<ImageAsset> {
+  match owner {
+    ImageOwner::Team { team_id } => {
+      roster::team_name(conn, team_id)?;
+    }
+    ImageOwner::Player { player_id } => {
+      roster::get(conn, player_id)?;
+    }
+  }
+  let (image, format) = decode(source)?;
+  let full = fit(&image, FULL_SIZE);
+  let (team_id, player_id) = match owner {
+    ImageOwner::Team { team_id } => (Some(team_id.clone()), None),
+    ImageOwner::Player { player_id } => (None, Some(player_id.clone())),
+  };
+  let stored = Stored {
+    id: new_id(),
+    team_id,
+    player_id,
+    extension: if format == ImageFormat::Jpeg { "jpg" } else { "png" }.to_string(),
+    width: full.width(),
+    height: full.height(),
+    created_at: timestamp(),
+  };
+
+  let dir = data_dir.join(DIR);
+  std::fs::create_dir_all(&dir)?;
+  let names = stored.file_names();
+  let copies = [full.clone(), fit(&full, MEDIUM.1), fit(&full, SMALL.1)];
+  let written = names.iter().zip(&copies).try_for_each(|(name, copy)| {
+    std::fs::write(dir.join(name), encode(copy, format)?)?;
+    Ok::<_, Error>(())
+  });
+
+  let replaced = written.and_then(|()| {
+    let tx = conn.transaction()?;
+    let replaced = stored_for(&tx, owner)?;
+    if let Some(old) = &replaced {
+      tx.execute("DELETE FROM images WHERE id = ?1", [&old.id])?;
+    }
+    tx.execute(
+      &format!("INSERT INTO images ({COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"),
+      params![
+        stored.id,
+        stored.team_id,
+        stored.player_id,
+        stored.extension,
+        stored.width,
+        stored.height,
+        stored.created_at
+      ],
+    )?;
+    tx.commit()?;
+    Ok(replaced)
+  });
+  match replaced {
+    Ok(replaced) => {
+      if let Some(old) = replaced {
+        remove_files(&dir, &old.file_names());
+      }
+      log::info!("Imported image {} ({}x{})", stored.id, stored.width, stored.height);
+      Ok(stored.into_asset())
+    }
+    Err(e) => {
+      remove_files(&dir, &names);
+      Err(e)
+    }
+  }
+}
+
+/// Removes an image and its files.
+pub fn delete(conn: &Connection, data_dir: &Path, id: &str) -> Result<()> {
+  let stored = conn
+    .query_row(
+      &format!("SELECT {COLUMNS} FROM images WHERE id = ?1"),
+      [id],
+      stored_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| Error::NotFound(format!("Image {id}")))?;
+  conn.execute("DELETE FROM images WHERE id = ?1", [id])?;
+  remove_files(&data_dir.join(DIR), &stored.file_names());
+  Ok(())
+}
+
+/// The file an `asset:` request path like `/images/<id>-small.png` names,
+/// if it's one of ours; anything that could step outside the folder isn't.
+fn requested_file(path: &str) -> Option<(&str, &'static str)> {
+  let name = path.strip_prefix('/')?.strip_prefix(DIR)?.strip_prefix('/')?;
+  let (stem, extension) = name.rsplit_once('.')?;
+  let content_type = match extension {
+    "png" => "image/png",
+    "jpg" => "image/jpeg",
+    _ => return None,
+  };
+  let safe = !stem.is_empty() && stem.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+  safe.then_some((name, content_type))
+}
+
+/// Answers an `asset:` request from the active profile's `images/` folder.
+pub fn serve_asset(app: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+  let empty = |status: StatusCode| {
+    let mut response = Response::new(Vec::new());
+    *response.status_mut() = status;
+    response
+  };
+  let Some((name, content_type)) = requested_file(request.uri().path()) else {
+    return empty(StatusCode::NOT_FOUND);
+  };
+  let path = app.state::<AppPaths>().data().join(DIR).join(name);
+  match std::fs::read(&path) {
+    Ok(bytes) => Response::builder()
+      .header(header::CONTENT_TYPE, content_type)
+      // Ids aren't reused, so a URL's image never changes
+      .header(header::CACHE_CONTROL, "max-age=31536000, immutable")
+      .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+      .body(bytes)
+      .unwrap_or_else(|_| empty(StatusCode::INTERNAL_SERVER_ERROR)),
+    Err(e) => {
+      if e.kind() != std::io::ErrorKind::NotFound {
+        log::warn!("Failed to read image {:?}: {}", path, e);
+      }
+      empty(StatusCode::NOT_FOUND)
+    }
+  }
+}
+
+/// Makes the PNG or JPEG at `path` a team's logo or a player's photo,
+/// replacing any it had, and returns its URLs.
+#[tauri::command]
+pub async fn import_image(app: AppHandle, path: PathBuf, owner: ImageOwner) -> Result<ImageAsset> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let data_dir = app.state::<AppPaths>().data();
+    import(&mut app.state::<Database>().conn(), &data_dir, &path, &owner)
+  })
+  .await?
+}
+
+/// Every team logo and player photo.
+#[tauri::command]
+pub fn list_images(db: State<'_, Database>) -> Result<Vec<ImageAsset>> {
+  list(&db.conn())
+}
+
+#[tauri::command]
+pub fn delete_image(db: State<'_, Database>, paths: State<'_, AppPaths>, id: String) -> Result<()> {
+  delete(&db.conn(), &paths.data(), &id)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+  use image::{Rgba, RgbaImage};
+
+  #[test]
+  fn imports_resized_copies_and_replaces_the_old_logo() {
+    let mut conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let owner = ImageOwner::Team { team_id };
+    let dir = std::env::temp_dir().join(format!("dugout-images-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("logo.png");
+    let logo = RgbaImage::from_pixel(1000, 600, Rgba([0x1f, 0x3a, 0x68, 0x80]));
+    DynamicImage::ImageRgba8(logo).save(&source).unwrap();
+    std::fs::write(dir.join("notes.png"), b"not a picture").unwrap();
+    assert!(import(&mut conn, &dir, &dir.join("notes.png"), &owner).is_err());
+
+    let first = import(&mut conn, &dir, &source, &owner).unwrap();
+    assert_eq!((first.width, first.height), (1000, 600));
+    assert!(first.small_url.ends_with(&format!("/images/{}-small.png", first.id)));
+    let small = image::open(dir.join(DIR).join(format!("{}-small.png", first.id))).unwrap();
+    assert_eq!((small.width(), small.height()), (128, 77));
+    // Transparency survives
+    assert!(small.color().has_alpha());
+
+    let second = import(&mut conn, &dir, &source, &owner).unwrap();
+    assert_eq!(list(&conn).unwrap(), vec![second]);
+    assert!(!dir.join(DIR).join(format!("{}.png", first.id)).exists());
+
+    assert_eq!(requested_file("/images/abc-1-small.png"), Some(("abc-1-small.png", "image/png")));
+    assert_eq!(requested_file("/images/../settings.json"), None);
+    assert_eq!(requested_file("/images/..%2Fdugout.db.png"), None);
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -0,0 +1,372 @@
+//! iScore Baseball's game export.
+//!
+//! iScore writes a game as CSV: a few `key,value` lines about the game,
+//! then a batting and a pitching table for each team, each under a
+//! `Batting,<team>` or `Pitching,<team>` line. A season export repeats that
+//! once per game, each starting at its `Date` line:
+//!
+//! ```text
+//! Date,04/11/2026
+//! Location,Riverside Park
+//! Visitor,Tigers,4
+//! Home,Eagles,6
+//! Batting,Eagles
+//! Order,#,Player,Pos,AB,R,H,2B,3B,HR,RBI,BB,K,SB,CS
+//! 1,12,Jake Miller,SS,3,1,2,1,0,0,2,1,0,1,0
+//! Pitching,Eagles
+//! #,Player,IP,H,R,ER,BB,K,PC
+//! 12,Jake Miller,2.1,3,1,1,0,4,47
+//! ```
+//!
+//! Substitutes are listed after the starter, under the same order number.
+
+use chrono::NaiveDate;
+
+use super::{Importer, ParsedExport, SourceGame, SourcePlayer};
+use crate::db::gamechanger::{normalize, set_stat, stat_field, Group, StatLine};
+use crate::db::roster_csv::RejectedRow;
+use crate::error::{Error, Result};
+
+pub struct IScore;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+  Order,
+  Number,
+  Name,
+  Position,
+  Stat(&'static str),
+}
+
+fn column(group: Group, header: &str) -> Option<Column> {
+  match header {
+    "order" | "bo" | "bat" => Some(Column::Order),
+    "#" | "no" | "num" | "number" => Some(Column::Number),
+    "player" | "name" => Some(Column::Name),
+    "pos" | "position" => Some(Column::Position),
+    other => stat_field(group, other).map(Column::Stat),
+  }
+}
+
+/// The table being read, and its columns once the header has been seen.
+struct Table {
+  group: Group,
+  team: String,
+  columns: Option<Vec<Option<Column>>>,
+}
+
+/// A game read so far.
+struct Draft {
+  /// Line of its `Date`.
+  line: usize,
+  date: String,
+  location: String,
+  home: Option<(String, Option<u32>)>,
+  visitor: Option<(String, Option<u32>)>,
+  /// Every team's players, with the team they're under.
+  players: Vec<(String, SourcePlayer)>,
+}
+
+fn parse_date(raw: &str, line: usize) -> Result<String> {
+  NaiveDate::parse_from_str(raw, "%m/%d/%Y")
+    .or_else(|_| NaiveDate::parse_from_str(raw, "%Y-%m-%d"))
+    .map(|date| date.format("%Y-%m-%d").to_string())
+    .map_err(|_| Error::Validation(format!("Line {line}: \"{raw}\" isn't a date")))
+}
+
+fn side(values: &[String], line: usize) -> Result<(String, Option<u32>)> {
+  let name = values.get(1).cloned().unwrap_or_default();
+  if name.is_empty() {
+    return Err(Error::Validation(format!("Line {line}: the team has no name")));
+  }
+  let score = match values.get(2).map(String::as_str) {
+    None | Some("") => None,
+    Some(raw) => Some(raw.parse().map_err(|_| {
+      Error::Validation(format!("Line {line}: score \"{raw}\" is not a whole number"))
+    })?),
+  };
+  Ok((name, score))
+}
+
+impl Draft {
+  fn finish(self, team_name: &str) -> Result<SourceGame> {
+    let (Some(home), Some(visitor)) = (self.home, self.visitor) else {
+      return Err(Error::Validation(format!(
+        "The game on line {} has no Home or Visitor line",
+        self.line
+      )));
+    };
+    let ours = |name: &str| name.trim().eq_ignore_ascii_case(team_name.trim());
+    let (home_away, us, them) = if ours(&home.0) {
+      ("home", home, visitor)
+    } else if ours(&visitor.0) {
+      ("away", visitor, home)
+    } else {
+      return Err(Error::Validation(format!(
+        "Neither {} nor {} is {team_name}; give the team's name as it is in iScore",
+        visitor.0, home.0
+      )));
+    };
+    Ok(SourceGame {
+      date: self.date,
+      opponent: them.0,
+      home_away: home_away.into(),
+      score_us: us.1,
+      score_them: them.1,
+      location: self.location,
+      players: self
+        .players
+        .into_iter()
+        .filter(|(team, _)| ours(team))
+        .map(|(_, player)| player)
+        .collect(),
+    })
+  }
+
+  /// Adds a table row, to the player's earlier row if they have one (a
+  /// pitcher's batting line, say).
+  fn add_row(
+    &mut self,
+    table: &Table,
+    columns: &[Option<Column>],
+    line: usize,
+    values: &[String],
+  ) -> std::result::Result<(), Vec<String>> {
+    let (mut name, mut number, mut order, mut position) = ("", None, None, None);
+    let mut stats = Vec::new();
+    let mut errors = Vec::new();
+    for (column, value) in columns.iter().zip(values) {
+      match column {
+        Some(Column::Name) => name = value.as_str(),
+        Some(Column::Position) if !value.is_empty() => position = Some(value.to_uppercase()),
+        Some(Column::Number) | Some(Column::Order) if value.is_empty() => {}
+        Some(Column::Number) => match value.trim_start_matches('#').parse::<u32>() {
+          Ok(n) => number = Some(n),
+          Err(_) => errors.push(format!("Jersey number \"{value}\" is not a whole number")),
+        },
+        Some(Column::Order) => match value.parse::<u32>() {
+          Ok(n) => order = Some(n),
+          Err(_) => errors.push(format!("Batting order \"{value}\" is not a whole number")),
+        },
+        Some(Column::Stat(field)) => stats.push((*field, value.as_str())),
+        _ => {}
+      }
+    }
+    if name.is_empty() {
+      errors.push("Row has no player name".into());
+    }
+    let mut line_stats = StatLine::default();
+    for (field, raw) in &stats {
+      if let Err(e) = set_stat(&mut line_stats, field, raw) {
+        errors.push(e);
+      }
+    }
+    if !errors.is_empty() {
+      return Err(errors);
+    }
+
+    let existing = self
+      .players
+      .iter_mut()
+      .find(|(team, p)| *team == table.team && p.name.eq_ignore_ascii_case(name));
+    let player = match existing {
+      Some((_, player)) => player,
+      None => {
+        self.players.push((
+          table.team.clone(),
+          SourcePlayer {
+            line,
+            name: name.into(),
+            number,
+            batting_order: None,
+            position: None,
+            stats: StatLine::default(),
+          },
+        ));
+        &mut self.players.last_mut().unwrap().1
+      }
+    };
+    player.number = player.number.or(number);
+    if table.group == Group::Batting {
+      player.batting_order = player.batting_order.or(order);
+      player.position = player.position.take().or(position);
+    }
+    for (field, raw) in stats {
+      // Already checked above
+      let _ = set_stat(&mut player.stats, field, raw);
+    }
+    Ok(())
+  }
+}
+
+impl Importer for IScore {
+  fn id(&self) -> &'static str {
+    "iscore"
+  }
+
+  fn name(&self) -> &'static str {
+    "iScore Baseball"
+  }
+
+  fn extensions(&self) -> &'static [&'static str] {
+    &["csv"]
+  }
+
+  fn parse(&self, text: &str, team_name: &str) -> Result<ParsedExport> {
+    let mut parsed = ParsedExport::default();
+    let mut draft: Option<Draft> = None;
+    let mut table: Option<Table> = None;
+    let records = csv::ReaderBuilder::new()
+      .has_headers(false)
+      .flexible(true)
+      .trim(csv::Trim::All)
+      .from_reader(text.as_bytes())
+      .into_records();
+
+    for record in records {
+      let record = record?;
+      let line = record.position().map_or(0, |p| p.line() as usize);
+      let values: Vec<String> = record.iter().map(str::to_string).collect();
+      let key = normalize(values.first().map_or("", String::as_str));
+
+      if key == "date" {
+        if let Some(done) = draft.take() {
+          parsed.games.push(done.finish(team_name)?);
+        }
+        table = None;
+        draft = Some(Draft {
+          line,
+          date: parse_date(values.get(1).map_or("", String::as_str), line)?,
+          location: String::new(),
+          home: None,
+          visitor: None,
+          players: Vec::new(),
+        });
+        continue;
+      }
+      let Some(game) = draft.as_mut() else {
+        if values.iter().all(String::is_empty) {
+          continue;
+        }
+        return Err(Error::Validation(
+          "This doesn't look like an iScore game export: it should start with a Date line".into(),
+        ));
+      };
+
+      match key.as_str() {
+        "location" => {
+          game.location = values.get(1).cloned().unwrap_or_default();
+          table = None;
+        }
+        "home" => {
+          game.home = Some(side(&values, line)?);
+          table = None;
+        }
+        "visitor" | "away" => {
+          game.visitor = Some(side(&values, line)?);
+          table = None;
+        }
+        "batting" | "pitching" => {
+          table = Some(Table {
+            group: if key == "batting" { Group::Batting } else { Group::Pitching },
+            team: values.get(1).cloned().unwrap_or_default(),
+            columns: None,
+          });
+        }
+        "totals" | "total" | "team" => {}
+        _ => {
+          let Some(table) = table.as_mut() else {
+            continue;
+          };
+          let Some(columns) = &table.columns else {
+            let columns: Vec<_> =
+              values.iter().map(|h| column(table.group, &normalize(h))).collect();
+            if !columns.contains(&Some(Column::Name)) {
+              return Err(Error::Validation(format!(
+                "Line {line}: the table has no Player column"
+              )));
+            }
+            table.columns = Some(columns);
+            continue;
+          };
+          let first = values.iter().find(|v| !v.is_empty());
+          if first.is_some_and(|v| normalize(v) == "totals") {
+            continue;
+          }
+          if let Err(errors) = game.add_row(table, columns, line, &values) {
+            parsed.rejected.push(RejectedRow {
+              line,
+              values,
+              errors,
+            });
+          }
+        }
+      }
+    }
+
+    if let Some(done) = draft {
+      parsed.games.push(done.finish(team_name)?);
+    }
+    if parsed.games.is_empty() {
+      return Err(Error::Validation("This iScore export has no games in it".into()));
+    }
+    Ok(parsed)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const EXPORT: &str = "\
+Date,04/11/2026
+Location,Riverside Park
+Visitor,Tigers,4
+Home,Eagles,6
+Batting,Eagles
+Order,#,Player,Pos,AB,R,H,2B,3B,HR,RBI,BB,K,SB,CS
+1,12,Jake Miller,SS,3,1,2,1,0,0,2,1,0,1,0
+2,3,Ava Chen,cf,4,0,1,0,0,1,1,0,2,0,0
+2,9,Sam Ortiz,CF,1,0,0,0,0,0,0,0,1,0,0
+3,7,Lee Park,1B,x,0,0,0,0,0,0,0,1,0,0
+Totals,,,11,1,3,1,0,1,3,1,4,1,0
+Pitching,Eagles
+#,Player,IP,H,R,ER,BB,K,PC
+12,Jake Miller,2.1,3,1,1,0,4,47
+Batting,Tigers
+Order,#,Player,Pos,AB,R,H,2B,3B,HR,RBI,BB,K,SB,CS
+1,5,Max Stone,C,3,1,1,0,0,0,0,0,1,0,0
+
+Date,2026-04-18
+Visitor,Eagles,
+Home,Lions,
+";
+
+  #[test]
+  fn reads_our_side_of_each_game() {
+    let parsed = IScore.parse(EXPORT, "eagles").unwrap();
+    assert_eq!(parsed.games.len(), 2);
+    let first = &parsed.games[0];
+    assert_eq!(
+      (first.date.as_str(), first.opponent.as_str(), first.home_away.as_str()),
+      ("2026-04-11", "Tigers", "home")
+    );
+    assert_eq!((first.score_us, first.score_them), (Some(6), Some(4)));
+    assert_eq!(first.location, "Riverside Park");
+
+    let names: Vec<_> = first.players.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Jake Miller", "Ava Chen", "Sam Ortiz"]);
+    let jake = &first.players[0];
+    assert_eq!((jake.batting_order, jake.position.as_deref()), (Some(1), Some("SS")));
+    assert_eq!((jake.stats.h, jake.stats.rbi, jake.stats.sb), (2, 2, 1));
+    assert_eq!((jake.stats.ip, jake.stats.k, jake.stats.pitches), (2.1, 4, 47));
+    assert_eq!(first.players[1].position.as_deref(), Some("CF"));
+    assert_eq!(parsed.rejected.len(), 1);
+    assert_eq!(parsed.rejected[0].line, 10);
+
+    let second = &parsed.games[1];
+    assert_eq!((second.home_away.as_str(), second.score_us), ("away", None));
+    assert!(IScore.parse(EXPORT, "Bears").is_err());
+    assert!(IScore.parse("Player,AB\nJake,3\n", "Eagles").is_err());
+  }
+}
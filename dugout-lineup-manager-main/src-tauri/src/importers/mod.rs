@@ -0,0 +1,448 @@
+//! Game imports from other scorekeeping apps, one adapter per app.
+//!
+//! An adapter only turns an export into [`SourceGame`]s; matching players
+//! to the roster and writing games, box scores and lineups is shared, the
+//! same way as for GameChanger files. Like those it is a two-step flow:
+//! `preview_games_import` is a dry run showing what each game would become,
+//! with suggested player matches for the coach to confirm or correct, and
+//! `import_games` then writes it all in one transaction. Importing the same
+//! game again replaces its stats and lineup rather than adding to them.
+//!
+//! To add an app, implement [`Importer`] in a submodule and list it in
+//! [`IMPORTERS`].
+
+pub mod iscore;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use tauri::State;
+
+use crate::db::gamechanger::{self, ImportGame, PlayerMatch, PlayerRow, StatLine};
+use crate::db::games::{self, Game};
+use crate::db::lineups::{self, LineupInput, SlotInput, MAX_SLOTS};
+use crate::db::roster::{self, Player};
+use crate::db::roster_csv::RejectedRow;
+use crate::db::{audit, Database};
+use crate::error::{Error, Result};
+use crate::telemetry::{Feature, Telemetry};
+
+/// A player's line in one game of an export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourcePlayer {
+  /// 1-based line in the file, which also identifies the row to
+  /// `matches`.
+  pub line: usize,
+  pub name: String,
+  pub number: Option<u32>,
+  /// Slot in the starting batting order; `None` for pitchers who didn't
+  /// bat and for substitutes.
+  pub batting_order: Option<u32>,
+  pub position: Option<String>,
+  pub stats: StatLine,
+}
+
+/// One of our team's games as an export describes it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceGame {
+  /// `YYYY-MM-DD`.
+  pub date: String,
+  pub opponent: String,
+  /// `home` or `away`.
+  pub home_away: String,
+  pub score_us: Option<u32>,
+  pub score_them: Option<u32>,
+  pub location: String,
+  pub players: Vec<SourcePlayer>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParsedExport {
+  pub games: Vec<SourceGame>,
+  pub rejected: Vec<RejectedRow>,
+}
+
+/// Reads one app's export format.
+pub trait Importer: Sync {
+  /// Names the adapter to the import commands, e.g. `iscore`.
+  fn id(&self) -> &'static str;
+  /// Shown to the coach, e.g. "iScore Baseball".
+  fn name(&self) -> &'static str;
+  /// File extensions its exports use, for the open dialog.
+  fn extensions(&self) -> &'static [&'static str];
+  /// Parses an export, keeping only the games and players of the team
+  /// called `team_name` there.
+  fn parse(&self, text: &str, team_name: &str) -> Result<ParsedExport>;
+}
+
+/// Every adapter, in the order the frontend lists them.
+pub const IMPORTERS: &[&dyn Importer] = &[&iscore::IScore];
+
+fn find(id: &str) -> Result<&'static dyn Importer> {
+  IMPORTERS
+    .iter()
+    .copied()
+    .find(|importer| importer.id() == id)
+    .ok_or_else(|| Error::NotFound(format!("Importer {id}")))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImporterInfo {
+  pub id: &'static str,
+  pub name: &'static str,
+  pub extensions: &'static [&'static str],
+}
+
+/// A batter's place in the lineup saved with the game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineupEntry {
+  /// The player's row.
+  pub line: usize,
+  pub slot_number: u32,
+  pub position: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GamePreview {
+  pub date: String,
+  pub opponent: String,
+  pub home_away: String,
+  pub score_us: Option<u32>,
+  pub score_them: Option<u32>,
+  pub location: String,
+  /// The game this merges into, if it is already on the schedule.
+  pub existing_game_id: Option<String>,
+  pub rows: Vec<PlayerRow>,
+  pub lineup: Vec<LineupEntry>,
+  /// The game as saved; `None` unless the import was committed.
+  pub game: Option<Game>,
+  /// The lineup saved with it, if any batter was matched.
+  pub lineup_id: Option<String>,
+  /// Box scores written; zero unless the import was committed.
+  pub imported: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameImportPreview {
+  pub importer: &'static str,
+  pub games: Vec<GamePreview>,
+  pub rejected: Vec<RejectedRow>,
+}
+
+/// Matches each game's players against `roster` and notes which games are
+/// already on the schedule.
+pub fn preview(
+  conn: &Connection,
+  importer: &dyn Importer,
+  text: &str,
+  team_id: &str,
+  team_name: &str,
+  roster: &[Player],
+) -> Result<GameImportPreview> {
+  let parsed = importer.parse(text, team_name)?;
+  let mut games = Vec::with_capacity(parsed.games.len());
+  for game in parsed.games {
+    let existing_game_id = gamechanger::find_game(conn, team_id, &game.date, &game.opponent)?;
+    let mut taken = HashSet::new();
+    let lineup = game
+      .players
+      .iter()
+      .filter_map(|p| {
+        let slot_number = p.batting_order.filter(|n| (1..=MAX_SLOTS).contains(n))?;
+        // A substitute listed in a starter's slot keeps it for the starter
+        taken.insert(slot_number).then(|| LineupEntry {
+          line: p.line,
+          slot_number,
+          position: p.position.clone(),
+        })
+      })
+      .collect();
+    let rows = game
+      .players
+      .into_iter()
+      .map(|p| {
+        let suggestion = gamechanger::suggest(&p.name, p.number, roster);
+        PlayerRow {
+          line: p.line,
+          name: p.name,
+          number: p.number,
+          player_id: suggestion.as_ref().map(|(id, _)| id.clone()),
+          matched_by: suggestion.map(|(_, kind)| kind),
+          stats: p.stats,
+        }
+      })
+      .collect();
+    games.push(GamePreview {
+      date: game.date,
+      opponent: game.opponent,
+      home_away: game.home_away,
+      score_us: game.score_us,
+      score_them: game.score_them,
+      location: game.location,
+      existing_game_id,
+      rows,
+      lineup,
+      game: None,
+      lineup_id: None,
+      imported: 0,
+    });
+  }
+  Ok(GameImportPreview {
+    importer: importer.id(),
+    games,
+    rejected: parsed.rejected,
+  })
+}
+
+/// Applies the coach's confirmed matches, each to the game its row is in.
+pub fn confirm(
+  preview: &mut GameImportPreview,
+  matches: &[PlayerMatch],
+  roster: &[Player],
+) -> Result<()> {
+  if let Some(stray) = matches.iter().find(|m| {
+    !preview.games.iter().any(|g| g.rows.iter().any(|r| r.line == m.line))
+  }) {
+    return Err(Error::Validation(format!("No player row on line {}", stray.line)));
+  }
+  for game in &mut preview.games {
+    let mine: Vec<PlayerMatch> = matches
+      .iter()
+      .filter(|m| game.rows.iter().any(|r| r.line == m.line))
+      .cloned()
+      .collect();
+    gamechanger::confirm(&mut game.rows, &mine, roster)?;
+  }
+  Ok(())
+}
+
+fn lineup_name(game: &GamePreview) -> String {
+  format!("vs {} {}", game.opponent, game.date)
+}
+
+/// Writes every game, its matched box scores and its starting lineup, all
+/// or nothing.
+pub fn commit(conn: &mut Connection, team_id: &str, preview: &mut GameImportPreview) -> Result<()> {
+  let tx = conn.transaction()?;
+  for game in &mut preview.games {
+    let import = ImportGame {
+      id: game.existing_game_id.clone(),
+      date: game.date.clone(),
+      opponent: game.opponent.clone(),
+      home_away: Some(game.home_away.clone()),
+      score_us: game.score_us,
+      score_them: game.score_them,
+    };
+    let (game_id, imported) = gamechanger::merge(&tx, team_id, &import, &game.rows)?;
+    if !game.location.is_empty() {
+      tx.execute(
+        "UPDATE games SET location = ?2 WHERE id = ?1 AND location = ''",
+        params![game_id, game.location],
+      )?;
+    }
+
+    let player_at = |line: usize| game.rows.iter().find(|r| r.line == line)?.player_id.clone();
+    let slots: Vec<SlotInput> = game
+      .lineup
+      .iter()
+      .filter_map(|entry| {
+        Some(SlotInput {
+          slot_number: entry.slot_number,
+          player_id: Some(player_at(entry.line)?),
+          position: entry.position.clone(),
+        })
+      })
+      .collect();
+    if !slots.is_empty() {
+      let name = lineup_name(game);
+      let existing = tx
+        .query_row(
+          "SELECT id FROM lineups WHERE team_id = ?1 AND game_id = ?2 AND name = ?3",
+          params![team_id, game_id, name],
+          |row| row.get(0),
+        )
+        .optional()?;
+      let before = existing.as_deref().map(|id| lineups::get(&tx, id)).transpose()?;
+      let use_dh = slots.iter().any(|s| s.position.as_deref() == Some("DH"));
+      let input = LineupInput {
+        id: existing,
+        team_id: Some(team_id.into()),
+        name,
+        game_id: Some(game_id.clone()),
+        use_dh,
+        innings: 6,
+        slots,
+        positions: Vec::new(),
+      };
+      let lineup_id = lineups::save_in(&tx, &input)?;
+      let after = lineups::get(&tx, &lineup_id)?;
+      audit::lineup_changed(&tx, None, before.as_ref(), Some(&after))?;
+      game.lineup_id = Some(lineup_id);
+    }
+    game.game = Some(games::get(&tx, &game_id)?);
+    game.imported = imported;
+  }
+  tx.commit()?;
+  Ok(())
+}
+
+fn run(
+  db: &Database,
+  importer: &str,
+  path: &Path,
+  team_id: Option<String>,
+  team_name: Option<String>,
+  matches: Option<Vec<PlayerMatch>>,
+  commit: bool,
+) -> Result<GameImportPreview> {
+  let importer = find(importer)?;
+  let text = String::from_utf8_lossy(&std::fs::read(path)?).into_owned();
+  let mut conn = db.conn();
+  let team_id = match team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(&conn)?,
+  };
+  let team_name = match team_name {
+    Some(name) => name,
+    None => roster::team_name(&conn, &team_id)?,
+  };
+  let roster = roster::list(&conn, &team_id)?;
+  let mut preview = preview(&conn, importer, &text, &team_id, &team_name, &roster)?;
+  confirm(&mut preview, matches.as_deref().unwrap_or_default(), &roster)?;
+  if commit {
+    self::commit(&mut conn, &team_id, &mut preview)?;
+    let imported: usize = preview.games.iter().map(|g| g.imported).sum();
+    log::info!(
+      "Imported {} games ({} box scores) from {}",
+      preview.games.len(),
+      imported,
+      importer.name()
+    );
+  }
+  Ok(preview)
+}
+
+/// The apps games can be imported from.
+#[tauri::command]
+pub fn list_importers() -> Vec<ImporterInfo> {
+  IMPORTERS
+    .iter()
+    .map(|importer| ImporterInfo {
+      id: importer.id(),
+      name: importer.name(),
+      extensions: importer.extensions(),
+    })
+    .collect()
+}
+
+/// Shows what importing `path` with `importer` would do, without writing
+/// anything. `team_name` is the team's name in the other app when it isn't
+/// the same as here; `matches` confirms or corrects suggested players.
+#[tauri::command]
+pub fn preview_games_import(
+  db: State<'_, Database>,
+  importer: String,
+  path: PathBuf,
+  team_id: Option<String>,
+  team_name: Option<String>,
+  matches: Option<Vec<PlayerMatch>>,
+) -> Result<GameImportPreview> {
+  run(&db, &importer, &path, team_id, team_name, matches, false)
+}
+
+/// Imports the games in `path` as [`preview_games_import`] showed them.
+/// Rows left without a player are skipped.
+#[tauri::command]
+pub fn import_games(
+  db: State<'_, Database>,
+  telemetry: State<'_, Telemetry>,
+  importer: String,
+  path: PathBuf,
+  team_id: Option<String>,
+  team_name: Option<String>,
+  matches: Option<Vec<PlayerMatch>>,
+) -> Result<GameImportPreview> {
+  let preview = run(&db, &importer, &path, team_id, team_name, matches, true)?;
+  telemetry.record(Feature::GamesImported);
+  Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+  use crate::db::roster::NewPlayer;
+
+  const EXPORT: &str = "\
+Date,05/02/2026
+Location,Riverside Park
+Visitor,Tigers,4
+Home,Eagles,6
+Batting,Eagles
+Order,#,Player,Pos,AB,R,H
+1,12,Jake Miller,SS,3,1,2
+2,3,Ava Chen,DH,4,0,1
+2,9,Sam Ortiz,CF,1,0,0
+Pitching,Eagles
+#,Player,IP,K,PC
+12,Jake Miller,2.1,4,47
+";
+
+  fn player(conn: &Connection, name: &str, number: u32) -> Player {
+    roster::create(
+      conn,
+      NewPlayer {
+        team_id: None,
+        name: name.into(),
+        number: Some(number),
+        primary_position: "SS".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn previews_then_imports_games_with_their_lineups() {
+    let mut conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let roster = [player(&conn, "Jake Miller", 12), player(&conn, "Ava Chen", 3)];
+    let importer = find("iscore").unwrap();
+
+    let mut dry = preview(&conn, importer, EXPORT, &team_id, "Eagles", &roster).unwrap();
+    assert_eq!(dry.games[0].existing_game_id, None);
+    // Sam subbed into Ava's slot, which stays hers
+    let slots: Vec<_> = dry.games[0].lineup.iter().map(|e| (e.line, e.slot_number)).collect();
+    assert_eq!(slots, [(7, 1), (8, 2)]);
+    let stray = [PlayerMatch {
+      line: 3,
+      player_id: None,
+    }];
+    assert!(confirm(&mut dry, &stray, &roster).is_err());
+
+    commit(&mut conn, &team_id, &mut dry).unwrap();
+    let game = dry.games[0].game.clone().unwrap();
+    assert_eq!((game.location.as_str(), game.result.as_deref()), ("Riverside Park", Some("W")));
+    assert_eq!(dry.games[0].imported, 2);
+    let lineup = lineups::get(&conn, dry.games[0].lineup_id.as_deref().unwrap()).unwrap();
+    assert!(lineup.use_dh);
+    assert_eq!(lineup.slots.len(), 2);
+
+    let mut again = preview(&conn, importer, EXPORT, &team_id, "Eagles", &roster).unwrap();
+    assert_eq!(again.games[0].existing_game_id.as_deref(), Some(game.id.as_str()));
+    commit(&mut conn, &team_id, &mut again).unwrap();
+    assert_eq!(again.games[0].lineup_id, dry.games[0].lineup_id);
+    let totals = games::season_totals(&conn, &team_id).unwrap();
+    assert_eq!(totals.iter().map(|t| t.h).sum::<u32>(), 3);
+  }
+}
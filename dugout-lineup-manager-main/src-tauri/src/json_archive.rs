@@ -0,0 +1,435 @@
+//! Plain JSON exports of everything a coach has entered, for other tools
+//! and for leaving the app without losing their seasons.
+//!
+//! The document is an object with a header and a `data` object:
+//!
+//! ```json
+//! {
+//!   "format": "dugout-json",
+//!   "formatVersion": 1,
+//!   "appVersion": "1.4.0",
+//!   "schemaVersion": 9,
+//!   "exportedAt": "2026-04-11T17:30:00.000Z",
+//!   "data": {
+//!     "teams": [{ "id": "…", "name": "Eagles", "createdAt": "…" }],
+//!     "players": [{ "id": "…", "teamId": "…", "secondaryPositions": ["2B"], … }],
+//!     …
+//!   }
+//! }
+//! ```
+//!
+//! `data` holds one array per entity, parents first: each row is an
+//! object keyed by its database column in camelCase, with list columns
+//! (positions) as JSON arrays. Timestamps are RFC 3339 strings and dates
+//! `YYYY-MM-DD`. Caches, the offline AI queue and reminder state are left
+//! out; the app rebuilds them. Logos and photos are left out too, as their
+//! files can't travel in the document. Archived seasons are kept as they
+//! are stored, compressed, under `seasonArchives`.
+//!
+//! `formatVersion` is bumped if this layout changes incompatibly. Columns
+//! added by later schema versions are only ever added, so older exports
+//! import with their defaults.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::backup::{self, BackupKind};
+use crate::db::{migrations, versions, Database};
+use crate::error::{Error, Result};
+
+/// Value of `format`, identifying the document.
+const FORMAT: &str = "dugout-json";
+
+/// Bumped whenever the document layout changes incompatibly.
+const FORMAT_VERSION: u32 = 1;
+
+pub(crate) struct Table {
+  /// Key under `data`.
+  key: &'static str,
+  name: &'static str,
+  /// Columns holding JSON text, written out as JSON rather than strings.
+  json_columns: &'static [&'static str],
+}
+
+/// The exported tables, parents before the rows that refer to them.
+const TABLES: [Table; 13] = [
+  Table {
+    key: "teams",
+    name: "teams",
+    json_columns: &[],
+  },
+  Table {
+    key: "players",
+    name: "players",
+    json_columns: &["secondary_positions"],
+  },
+  Table {
+    key: "playerContacts",
+    name: "player_contacts",
+    json_columns: &[],
+  },
+  Table {
+    key: "games",
+    name: "games",
+    json_columns: &[],
+  },
+  Table {
+    key: "gameStats",
+    name: "game_stats",
+    json_columns: &["positions_played"],
+  },
+  Table {
+    key: "pitchCounts",
+    name: "pitch_counts",
+    json_columns: &[],
+  },
+  Table {
+    key: "scorebookEvents",
+    name: "scorebook_events",
+    json_columns: &["event"],
+  },
+  Table {
+    key: "lineups",
+    name: "lineups",
+    json_columns: &["versions"],
+  },
+  Table {
+    key: "lineupSlots",
+    name: "lineup_slots",
+    json_columns: &[],
+  },
+  Table {
+    key: "lineupPositions",
+    name: "lineup_positions",
+    json_columns: &[],
+  },
+  Table {
+    key: "drills",
+    name: "drills",
+    json_columns: &[],
+  },
+  Table {
+    key: "practicePlans",
+    name: "practice_plans",
+    json_columns: &["focus", "drill_ids"],
+  },
+  Table {
+    key: "seasonArchives",
+    name: "season_archives",
+    json_columns: &[],
+  },
+];
+
+pub type Row = Map<String, Value>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonArchive {
+  pub format: String,
+  pub format_version: u32,
+  pub app_version: String,
+  pub schema_version: u32,
+  pub exported_at: String,
+  pub data: BTreeMap<String, Vec<Row>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonImportReport {
+  /// Rows imported under each `data` key.
+  pub counts: BTreeMap<String, usize>,
+  /// Backup of the data that was replaced.
+  pub snapshot_path: PathBuf,
+}
+
+fn camel_case(column: &str) -> String {
+  let mut key = String::with_capacity(column.len());
+  let mut upper = false;
+  for c in column.chars() {
+    match c {
+      '_' => upper = true,
+      c if upper => {
+        key.push(c.to_ascii_uppercase());
+        upper = false;
+      }
+      c => key.push(c),
+    }
+  }
+  key
+}
+
+fn snake_case(key: &str) -> String {
+  let mut column = String::with_capacity(key.len() + 4);
+  for c in key.chars() {
+    if c.is_ascii_uppercase() {
+      column.push('_');
+    }
+    column.push(c.to_ascii_lowercase());
+  }
+  column
+}
+
+fn columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+  let mut stmt = conn.prepare(&format!("SELECT name FROM pragma_table_info('{table}')"))?;
+  let names = stmt.query_map([], |row| row.get(0))?;
+  Ok(names.collect::<rusqlite::Result<_>>()?)
+}
+
+fn to_json(table: &Table, column: &str, value: ValueRef<'_>) -> Result<Value> {
+  Ok(match value {
+    ValueRef::Null => Value::Null,
+    ValueRef::Integer(n) => n.into(),
+    ValueRef::Real(n) => serde_json::Number::from_f64(n).map_or(Value::Null, Value::Number),
+    ValueRef::Text(text) => {
+      let text = String::from_utf8_lossy(text);
+      if table.json_columns.contains(&column) {
+        serde_json::from_str(&text).unwrap_or(Value::String(text.into_owned()))
+      } else {
+        Value::String(text.into_owned())
+      }
+    }
+    ValueRef::Blob(_) => {
+      return Err(Error::Validation(format!(
+        "{}.{} holds binary data, which JSON exports don't support",
+        table.name, column
+      )))
+    }
+  })
+}
+
+fn to_sql(table: &Table, column: &str, value: &Value) -> Result<SqlValue> {
+  Ok(match value {
+    Value::Null => SqlValue::Null,
+    Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+    Value::Number(n) => match n.as_i64() {
+      Some(n) => SqlValue::Integer(n),
+      None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+    },
+    Value::String(text) => SqlValue::Text(text.clone()),
+    Value::Array(_) | Value::Object(_) if table.json_columns.contains(&column) => {
+      SqlValue::Text(value.to_string())
+    }
+    _ => {
+      return Err(Error::Validation(format!(
+        "{}.{} must be a plain value, not a list or object",
+        table.key,
+        camel_case(column)
+      )))
+    }
+  })
+}
+
+/// The exported table stored as `name`.
+pub(crate) fn table(name: &str) -> &'static Table {
+  TABLES
+    .iter()
+    .find(|table| table.name == name)
+    .unwrap_or_else(|| panic!("{name} isn't an exported table"))
+}
+
+/// `table`'s rows matching `filter`, an SQL condition, in insertion order.
+pub(crate) fn read_rows(
+  conn: &Connection,
+  table: &Table,
+  filter: &str,
+  params: impl rusqlite::Params,
+) -> Result<Vec<Row>> {
+  let columns = columns(conn, table.name)?;
+  let keys: Vec<String> = columns.iter().map(|c| camel_case(c)).collect();
+  let mut stmt = conn.prepare(&format!(
+    "SELECT * FROM {} WHERE {filter} ORDER BY rowid",
+    table.name
+  ))?;
+  let mut rows = stmt.query(params)?;
+  let mut out = Vec::new();
+  while let Some(row) = rows.next()? {
+    let mut object = Row::new();
+    for (i, column) in columns.iter().enumerate() {
+      object.insert(keys[i].clone(), to_json(table, column, row.get_ref(i)?)?);
+    }
+    out.push(object);
+  }
+  Ok(out)
+}
+
+/// Inserts `rows` into `table`, stopping at the first that doesn't fit.
+pub(crate) fn insert_rows(conn: &Connection, table: &Table, rows: &[Row]) -> Result<()> {
+  let known: HashSet<String> = columns(conn, table.name)?.into_iter().collect();
+  for (i, row) in rows.iter().enumerate() {
+    let mut names = Vec::with_capacity(row.len());
+    let mut values = Vec::with_capacity(row.len());
+    for (key, value) in row {
+      let column = snake_case(key);
+      if !known.contains(&column) {
+        return Err(Error::Validation(format!("Unknown field {}.{key}", table.key)));
+      }
+      values.push(to_sql(table, &column, value)?);
+      names.push(column);
+    }
+    let placeholders = vec!["?"; names.len()].join(", ");
+    let sql = format!(
+      "INSERT INTO {} ({}) VALUES ({placeholders})",
+      table.name,
+      names.join(", ")
+    );
+    conn.execute(&sql, rusqlite::params_from_iter(values)).map_err(|e| {
+      Error::Validation(format!("Couldn't import {} row {}: {e}", table.key, i + 1))
+    })?;
+  }
+  Ok(())
+}
+
+/// Reads every exported table into a [`JsonArchive`].
+pub fn dump(conn: &Connection, app_version: &str) -> Result<JsonArchive> {
+  let mut data = BTreeMap::new();
+  for table in &TABLES {
+    data.insert(table.key.to_string(), read_rows(conn, table, "1", [])?);
+  }
+  Ok(JsonArchive {
+    format: FORMAT.into(),
+    format_version: FORMAT_VERSION,
+    app_version: app_version.into(),
+    schema_version: migrations::current_version(conn)?,
+    exported_at: crate::db::timestamp(),
+    data,
+  })
+}
+
+/// Checks that `archive` is an export this version can read.
+fn validate(archive: &JsonArchive) -> Result<()> {
+  if archive.format != FORMAT {
+    return Err(Error::Validation("This isn't a Dugout JSON export".into()));
+  }
+  let newer = archive.schema_version > migrations::latest_version();
+  if archive.format_version > FORMAT_VERSION || newer {
+    return Err(Error::Validation(format!(
+      "This export is from a newer version of Dugout ({})",
+      archive.app_version
+    )));
+  }
+  if let Some(key) = archive.data.keys().find(|k| !TABLES.iter().any(|t| t.key == *k)) {
+    return Err(Error::Validation(format!("Unknown data in export: {key}")));
+  }
+  Ok(())
+}
+
+/// Deletes every exported table's rows, children first. The caller runs
+/// it in a transaction.
+pub fn clear(conn: &Connection) -> Result<()> {
+  for table in TABLES.iter().rev() {
+    conn.execute(&format!("DELETE FROM {}", table.name), [])?;
+  }
+  // Its entries are for lineups that are now gone
+  conn.execute("DELETE FROM undo_log", [])?;
+  versions::new_replica(conn)?;
+  Ok(())
+}
+
+/// Replaces every exported table's rows with those in `archive`, all or
+/// nothing. Returns how many rows went into each.
+pub fn load(conn: &mut Connection, archive: &JsonArchive) -> Result<BTreeMap<String, usize>> {
+  validate(archive)?;
+  let tx = conn.transaction()?;
+  clear(&tx)?;
+
+  let mut counts = BTreeMap::new();
+  for table in &TABLES {
+    let rows = archive.data.get(table.key).map(Vec::as_slice).unwrap_or_default();
+    insert_rows(&tx, table, rows)?;
+    counts.insert(table.key.to_string(), rows.len());
+  }
+  tx.commit()?;
+  Ok(counts)
+}
+
+/// Writes every team, player, game, box score and lineup to `path` as JSON.
+#[tauri::command]
+pub async fn export_json_archive(app: AppHandle, path: PathBuf) -> Result<JsonArchive> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let version = app.package_info().version.to_string();
+    let archive = dump(&app.state::<Database>().conn(), &version)?;
+    write(&archive, &path)?;
+    // The header is enough for the webview; the file can be large
+    Ok(JsonArchive {
+      data: BTreeMap::new(),
+      ..archive
+    })
+  })
+  .await?
+}
+
+fn write(archive: &JsonArchive, path: &Path) -> Result<()> {
+  let partial = path.with_extension("json.partial");
+  std::fs::write(&partial, serde_json::to_vec_pretty(archive)?)?;
+  std::fs::rename(&partial, path)?;
+  Ok(())
+}
+
+/// Rebuilds the database from a JSON export at `path`, after backing up
+/// the current data.
+#[tauri::command]
+pub async fn import_json_archive(app: AppHandle, path: PathBuf) -> Result<JsonImportReport> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let archive: JsonArchive = serde_json::from_slice(&std::fs::read(&path)?)?;
+    validate(&archive)?;
+    let snapshot = backup::create(&app, BackupKind::PreRestore)?;
+    let counts = load(&mut app.state::<Database>().conn(), &archive)?;
+    app.state::<crate::stats::StatsCache>().clear();
+    app.state::<crate::sync::SyncState>().resend_snapshot();
+    Ok(JsonImportReport {
+      counts,
+      snapshot_path: snapshot.path,
+    })
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn round_trips_every_table() {
+    let conn = crate::db::open_in_memory();
+    conn
+      .execute_batch(
+        "INSERT INTO teams (id, name, created_at) VALUES ('t2', 'Eagles', 'now');
+         INSERT INTO players (id, team_id, name, number, primary_position, secondary_positions,
+           bats, throws, created_at, updated_at)
+         VALUES ('p1', 't2', 'Jake Miller', 12, 'SS', '[\"2B\"]', 'R', 'R', 'now', 'now');
+         INSERT INTO games (id, team_id, date, opponent, created_at, updated_at)
+         VALUES ('g1', 't2', '2026-04-11', 'Tigers', 'now', 'now');
+         INSERT INTO game_stats (game_id, player_id, ab, h, ip) VALUES ('g1', 'p1', 3, 2, 1.5);
+         INSERT INTO lineups (id, team_id, name, game_id, created_at, updated_at)
+         VALUES ('l1', 't2', 'Game 1', 'g1', 'now', 'now');
+         INSERT INTO lineup_slots (lineup_id, slot_number, player_id, position)
+         VALUES ('l1', 1, 'p1', 'SS');",
+      )
+      .unwrap();
+
+    let archive = dump(&conn, "1.0.0").unwrap();
+    let player = &archive.data["players"][0];
+    assert_eq!(player["secondaryPositions"], serde_json::json!(["2B"]));
+    assert_eq!(archive.data["gameStats"][0]["ip"], 1.5);
+
+    let text = serde_json::to_string(&archive).unwrap();
+    let mut other = crate::db::open_in_memory();
+    let counts = load(&mut other, &serde_json::from_str(&text).unwrap()).unwrap();
+    assert_eq!(counts["lineupSlots"], 1);
+    let reloaded = dump(&other, "1.0.0").unwrap();
+    assert_eq!(reloaded.data, archive.data);
+
+    // A bad row leaves what was there alone
+    let mut broken = archive.clone();
+    broken.data.get_mut("players").unwrap()[0].insert("nickname".into(), "JJ".into());
+    assert!(load(&mut other, &broken).is_err());
+    assert_eq!(dump(&other, "1.0.0").unwrap().data, archive.data);
+  }
+}
@@ -0,0 +1,282 @@
+//! Kiosk mode, for a laptop left at the check-in table of a tournament:
+//! the main window is locked full screen and can't be closed, the tray
+//! (with its quit and update items) is hidden, and settings, updates,
+//! imports and profile switches are refused until someone enters the PIN.
+//!
+//! It starts at launch when `kiosk.enabled` is set or the app is started
+//! with [`KIOSK_ARG`], and only if a PIN has been set with `set_kiosk_pin`.
+//! Release builds have no devtools; debug builds lose theirs when started
+//! with the flag, since that is decided before the window is created.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager, State, Window, WindowEvent};
+
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+use crate::settings::{self, AppSettings};
+
+/// Starts the app locked down, as if `kiosk.enabled` were set.
+pub const KIOSK_ARG: &str = "--kiosk";
+
+/// Emitted with the [`KioskStatus`] when the app locks or unlocks.
+pub const KIOSK_EVENT: &str = "kiosk-changed";
+
+/// Wrong PINs allowed before unlocking is refused for [`LOCKOUT`].
+const MAX_ATTEMPTS: u32 = 5;
+const LOCKOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct KioskSettings {
+  /// Lock down at every launch.
+  pub enabled: bool,
+  /// A random salt and the SHA-256 of it followed by the PIN, both in hex
+  /// and separated by `$`. Only `set_kiosk_pin` changes it.
+  pub pin_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KioskStatus {
+  pub locked: bool,
+  pub pin_set: bool,
+}
+
+#[derive(Default)]
+pub struct KioskState {
+  locked: AtomicBool,
+  /// Wrong PINs in a row, and when the last one was entered.
+  failures: Mutex<(u32, Option<Instant>)>,
+}
+
+/// Whether this launch asked for kiosk mode on the command line.
+pub fn launched_with_flag() -> bool {
+  std::env::args().skip(1).any(|arg| arg == KIOSK_ARG)
+}
+
+pub fn validate(settings: &KioskSettings) -> Result<()> {
+  if settings.enabled && settings.pin_hash.is_none() {
+    return Err(Error::Validation("Kiosk mode needs a PIN to exit it".into()));
+  }
+  Ok(())
+}
+
+fn validate_pin(pin: &str) -> Result<()> {
+  if !(4..=12).contains(&pin.len()) || !pin.bytes().all(|b| b.is_ascii_digit()) {
+    return Err(Error::Validation("The PIN must be 4 to 12 digits".into()));
+  }
+  Ok(())
+}
+
+fn digest(salt: &str, pin: &str) -> String {
+  format!("{:x}", Sha256::digest(format!("{salt}{pin}")))
+}
+
+fn hash_pin(pin: &str) -> String {
+  let salt = uuid::Uuid::new_v4().simple().to_string();
+  format!("{}${}", salt, digest(&salt, pin))
+}
+
+fn verify_pin(pin: &str, hash: &str) -> bool {
+  hash
+    .split_once('$')
+    .is_some_and(|(salt, expected)| digest(salt, pin) == expected)
+}
+
+impl KioskState {
+  fn ensure_unlocked(&self) -> Result<()> {
+    if self.locked.load(Ordering::Relaxed) {
+      return Err(Error::Validation("That's not available in kiosk mode".into()));
+    }
+    Ok(())
+  }
+}
+
+pub fn is_locked(app: &AppHandle) -> bool {
+  app
+    .try_state::<KioskState>()
+    .is_some_and(|state| state.locked.load(Ordering::Relaxed))
+}
+
+/// For commands kiosk mode puts out of reach: those that change settings,
+/// install or roll back an update, or replace or move the data.
+pub fn ensure_unlocked(app: &AppHandle) -> Result<()> {
+  app
+    .try_state::<KioskState>()
+    .map_or(Ok(()), |state| state.ensure_unlocked())
+}
+
+fn apply(app: &AppHandle, locked: bool) -> Result<()> {
+  if let Some(window) = app.get_webview_window("main") {
+    window.set_fullscreen(locked)?;
+    window.set_closable(!locked)?;
+    window.set_minimizable(!locked)?;
+    #[cfg(debug_assertions)]
+    if locked {
+      window.close_devtools();
+    }
+  }
+  if let Some(tray) = app.tray_by_id("main") {
+    tray.set_visible(!locked)?;
+  }
+  Ok(())
+}
+
+fn set_locked(
+  app: &AppHandle,
+  state: &KioskState,
+  settings: &AppSettings,
+  locked: bool,
+) -> Result<KioskStatus> {
+  apply(app, locked)?;
+  state.locked.store(locked, Ordering::Relaxed);
+  let status = KioskStatus {
+    locked,
+    pin_set: settings.kiosk.pin_hash.is_some(),
+  };
+  app.emit(KIOSK_EVENT, status.clone())?;
+  Ok(status)
+}
+
+/// Locks down at launch if asked to, once the main window exists.
+pub fn init(app: &AppHandle, settings: &AppSettings) {
+  let requested = settings.kiosk.enabled || launched_with_flag();
+  app.manage(KioskState::default());
+  if !requested {
+    return;
+  }
+  if settings.kiosk.pin_hash.is_none() {
+    log::warn!("Not starting in kiosk mode, since no PIN is set to exit it");
+    return;
+  }
+  log::info!("Starting in kiosk mode");
+  if let Err(e) = set_locked(app, &app.state::<KioskState>(), settings, true) {
+    log::error!("Failed to lock down the main window: {}", e);
+  }
+}
+
+/// Keeps the main window full screen and open while locked.
+pub fn handle_window_event(window: &Window, event: &WindowEvent) {
+  if window.label() != "main" || !is_locked(window.app_handle()) {
+    return;
+  }
+  match event {
+    WindowEvent::CloseRequested { api, .. } => api.prevent_close(),
+    WindowEvent::Resized(_) if !window.is_fullscreen().unwrap_or(true) => {
+      let _ = window.set_fullscreen(true);
+    }
+    _ => {}
+  }
+}
+
+#[tauri::command]
+pub fn get_kiosk_status(
+  state: State<'_, KioskState>,
+  settings: State<'_, RwLock<AppSettings>>,
+) -> KioskStatus {
+  KioskStatus {
+    locked: state.locked.load(Ordering::Relaxed),
+    pin_set: settings.read().unwrap().kiosk.pin_hash.is_some(),
+  }
+}
+
+/// Sets the PIN that exits kiosk mode. Can't be changed while locked.
+#[tauri::command]
+pub fn set_kiosk_pin(
+  app: AppHandle,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+  pin: String,
+) -> Result<KioskStatus> {
+  ensure_unlocked(&app)?;
+  validate_pin(&pin)?;
+  let mut settings = settings.write().unwrap();
+  let mut updated = settings.clone();
+  updated.kiosk.pin_hash = Some(hash_pin(&pin));
+  settings::save(&paths.settings, &updated)?;
+  *settings = updated;
+  let _ = app.emit(settings::SETTINGS_CHANGED_EVENT, settings.clone());
+  Ok(KioskStatus {
+    locked: false,
+    pin_set: true,
+  })
+}
+
+/// Locks down now, for this run only; `kiosk.enabled` does it at launch.
+#[tauri::command]
+pub fn enter_kiosk_mode(
+  app: AppHandle,
+  state: State<'_, KioskState>,
+  settings: State<'_, RwLock<AppSettings>>,
+) -> Result<KioskStatus> {
+  let settings = settings.read().unwrap().clone();
+  if settings.kiosk.pin_hash.is_none() {
+    return Err(Error::Validation("Set a PIN before entering kiosk mode".into()));
+  }
+  set_locked(&app, &state, &settings, true)
+}
+
+/// Unlocks with the PIN until the next launch. After a few wrong ones it
+/// refuses to check for a while.
+#[tauri::command]
+pub fn exit_kiosk_mode(
+  app: AppHandle,
+  state: State<'_, KioskState>,
+  settings: State<'_, RwLock<AppSettings>>,
+  pin: String,
+) -> Result<KioskStatus> {
+  let settings = settings.read().unwrap().clone();
+  {
+    let mut failures = state.failures.lock().unwrap();
+    let (count, last) = *failures;
+    if count >= MAX_ATTEMPTS && last.is_some_and(|t| t.elapsed() < LOCKOUT) {
+      return Err(Error::Validation("Too many wrong PINs; try again shortly".into()));
+    }
+    let hash = settings.kiosk.pin_hash.as_deref().unwrap_or_default();
+    if !verify_pin(&pin, hash) {
+      // A fresh run of attempts once the lockout is over
+      let count = if count >= MAX_ATTEMPTS { 1 } else { count + 1 };
+      *failures = (count, Some(Instant::now()));
+      return Err(Error::Validation("Wrong PIN".into()));
+    }
+    *failures = (0, None);
+  }
+  log::info!("Kiosk mode unlocked");
+  set_locked(&app, &state, &settings, false)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn checks_pins_against_salted_hashes() {
+    let hash = hash_pin("2468");
+    assert!(verify_pin("2468", &hash));
+    assert!(!verify_pin("2469", &hash));
+    assert_ne!(hash, hash_pin("2468"));
+    assert!(!verify_pin("2468", "not a hash"));
+
+    assert!(validate_pin("2468").is_ok());
+    assert!(validate_pin("246").is_err());
+    assert!(validate_pin("24a8").is_err());
+  }
+
+  #[test]
+  fn refuses_locked_out_commands_until_unlocked() {
+    let state = KioskState::default();
+    assert!(state.ensure_unlocked().is_ok());
+
+    state.locked.store(true, Ordering::Relaxed);
+    let err = state.ensure_unlocked().unwrap_err();
+    assert_eq!(err.to_string(), "That's not available in kiosk mode");
+
+    state.locked.store(false, Ordering::Relaxed);
+    assert!(state.ensure_unlocked().is_ok());
+  }
+}
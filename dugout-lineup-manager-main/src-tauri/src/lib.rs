@@ -1,9 +1,154 @@
+mod ai_stream;
+mod alerts;
+#[cfg(desktop)]
+mod api_keys;
+mod archive;
+#[cfg(desktop)]
+mod audio;
+#[cfg(desktop)]
+mod autostart;
+mod backend_migration;
+mod backup;
+mod batting_order;
+mod boot;
+mod cli;
+mod clipboard;
+#[cfg(desktop)]
+mod cloud_backup;
+mod crash;
+#[cfg(desktop)]
+mod data_watcher;
+mod db;
+mod deep_link;
+mod defense;
+#[cfg(desktop)]
+mod devices;
+mod error;
+mod export;
+mod fairness;
+mod file_drop;
+mod first_run;
+mod folders;
+mod game_clock;
+mod game_mode;
+mod handshake;
+#[cfg(desktop)]
+mod headless;
+mod health;
+#[cfg(desktop)]
+mod hotkeys;
+mod ics;
+mod images;
+mod importers;
+mod json_archive;
+mod kiosk;
+mod logging;
+mod model_manager;
+mod network;
+mod notes_index;
+#[cfg(desktop)]
+mod oauth;
+mod optimizer;
+mod overlay;
+mod paths;
+mod pitching;
+mod practice;
+mod presentation;
+mod print;
+mod profiles;
+mod proxy;
+mod qr;
+mod relay;
+mod relocate;
+mod reminders;
+mod resources;
+mod rules;
+mod scoreboard;
+mod scorebook;
+mod search;
+mod settings;
+mod share;
+mod sidecar;
+mod splash;
+mod stats;
+mod substitutions;
+mod sync;
+mod system;
+#[cfg(desktop)]
+mod teamsnap;
+mod telemetry;
+mod theme;
+mod tray;
+#[cfg(desktop)]
+mod updater;
+mod weather;
+mod window_state;
+
+use paths::AppPaths;
+use sidecar::{SidecarConfig, SidecarManager};
+use std::sync::RwLock;
+
+use tauri::{Manager, RunEvent};
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-  tauri::Builder::default()
+  crash::install_panic_hook();
+  cli::init();
+  let context = context();
+
+  // Before anything that would open a window
+  #[cfg(desktop)]
+  if let Some(command) = &cli::args().headless {
+    std::process::exit(headless::run(&context.config().identifier, command));
+  }
+
+  let mut builder = tauri::Builder::default();
+
+  // Must be the first plugin: a second launch hands its arguments to the
+  // running instance and exits before starting another sidecar. One with
+  // its own data directory shares nothing with it, so it runs alongside
+  #[cfg(desktop)]
+  if cli::args().data_dir.is_none() {
+    builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+      tray::show_main_window(app);
+      let args = cli::forwarded_args(args.into_iter().skip(1));
+      deep_link::handle_args(app, args, std::path::Path::new(&cwd));
+    }));
+  }
+
+  builder
     .plugin(tauri_plugin_fs::init())
     .plugin(tauri_plugin_shell::init())
+    .plugin(tauri_plugin_dialog::init())
+    .plugin(tauri_plugin_deep_link::init())
+    .plugin(tauri_plugin_opener::init())
+    .plugin(tauri_plugin_notification::init())
+    .plugin(tauri_plugin_clipboard_manager::init())
+    .register_asynchronous_uri_scheme_protocol(images::SCHEME, |ctx, request, responder| {
+      let app = ctx.app_handle().clone();
+      tauri::async_runtime::spawn_blocking(move || {
+        responder.respond(images::serve_asset(&app, &request))
+      });
+    })
     .setup(|app| {
+      // First, so the rest of setup is logged
+      logging::init(app)?;
+
+      // A login launch stays in the tray until the coach opens it
+      #[cfg(desktop)]
+      let reveal = !autostart::launched_minimized();
+      #[cfg(not(desktop))]
+      let reveal = true;
+      app.manage(splash::BootState::default());
+      #[cfg(desktop)]
+      let splash_loaded = if reveal {
+        Some(splash::open(app.handle())?)
+      } else {
+        None
+      };
+      #[cfg(not(desktop))]
+      let splash_loaded = None;
+
       // Initialize the updater plugin (desktop only)
       #[cfg(desktop)]
       app.handle().plugin(tauri_plugin_updater::Builder::new().build())?;
@@ -12,59 +157,453 @@ pub fn run() {
       #[cfg(desktop)]
       app.handle().plugin(tauri_plugin_process::init())?;
 
+      #[cfg(desktop)]
+      app.handle().plugin(autostart::init())?;
+
       #[cfg(desktop)]
       {
-        use tauri::Manager;
-        use tauri_plugin_shell::ShellExt;
-
-        // Get or create the writable data directory for the backend
-        let app_data_dir = app.path().app_data_dir().unwrap_or_else(|_| {
-            std::env::current_dir().unwrap_or_default()
-        });
-        let dugout_data_dir = app_data_dir.join("data");
-        
-        // Ensure the directory exists
-        if let Err(e) = std::fs::create_dir_all(&dugout_data_dir) {
-            log::error!("Failed to create data directory: {}", e);
+        app.manage(hotkeys::HotkeyState::default());
+        app.handle().plugin(hotkeys::init())?;
+      }
+
+      // Get or create the writable data directory for the backend. On
+      // mobile only the app's sandbox is writable, so there is no fallback
+      #[cfg(desktop)]
+      let app_data_dir = match &cli::args().data_dir {
+        Some(dir) => {
+          log::info!("Using the data directory {:?} from the command line", dir);
+          dir.clone()
         }
+        None => app
+          .path()
+          .app_data_dir()
+          .unwrap_or_else(|_| std::env::current_dir().unwrap_or_default()),
+      };
+      #[cfg(mobile)]
+      let app_data_dir = app.path().app_data_dir()?;
+      let paths = AppPaths::new(app_data_dir);
+      crash::set_dir(paths.crashes.clone());
 
-        match app
-          .shell()
-          .sidecar("backend-sidecar")
-        {
-          Ok(cmd) => {
-            let cmd = cmd
-                .env("DUGOUT_BACKEND_PORT", "8100")
-                .env("DUGOUT_DATA_DIR", dugout_data_dir.to_string_lossy().to_string());
-            
-            match cmd.spawn() {
-              Ok((_rx, child)) => {
-                log::info!("Backend sidecar started successfully (Data: {:?})", dugout_data_dir);
-                // We must store or 'leak' the child handle so it isn't dropped and killed immediately
-                // In a real app, you might store this in a tauri::State, 
-                // but for a sidecar we want to run as long as the app, this is effective:
-                std::mem::forget(child);
-              }
-              Err(e) => {
-                log::warn!("Failed to spawn backend sidecar: {}. AI features will be unavailable.", e);
-              }
-            }
+      let app_settings = settings::load(&paths.settings);
+      logging::configure(&app_settings.log);
+      app.manage(paths.select_profile(&app_settings));
+
+      // Ensure the directories exist
+      if let Err(e) = paths.ensure() {
+        log::error!("Failed to create data directory: {}", e);
+      }
+      let dugout_data_dir = paths.data();
+
+      // The database (and its migrations) must be ready before the main
+      // window is created, which happens once the splash can show them
+      boot::start(
+        app.handle(),
+        dugout_data_dir.clone(),
+        paths.backups().join("pre-migration"),
+        splash_loaded,
+      );
+      app.manage(db::integrity::IntegrityState::default());
+
+      app.manage(SidecarManager::new(SidecarConfig {
+        port: cli::args()
+          .port
+          .unwrap_or_else(|| sidecar::pick_port(&app_settings.backend_port)),
+        data_dir: dugout_data_dir.clone(),
+        models_dir: paths.models.clone(),
+        remote: sidecar::remote_for(&app_settings),
+      }));
+      app.manage(telemetry::Telemetry::new(
+        paths.telemetry.clone(),
+        app_settings.telemetry.enabled,
+      ));
+      #[cfg(desktop)]
+      hotkeys::apply(app.handle(), &app_settings.hotkeys);
+      app.manage(RwLock::new(app_settings));
+      app.manage(scoreboard::ScoreboardState::default());
+      app.manage(presentation::PresentationState::default());
+      app.manage(overlay::OverlayState::default());
+      app.manage(relay::RelayState::default());
+      app.manage(game_clock::GameClockState::default());
+      app.manage(stats::StatsCache::default());
+      app.manage(notes_index::NotesIndex::default());
+      app.manage(game_mode::GameModeState::default());
+      app.manage(deep_link::DeepLinkState::default());
+      app.manage(network::NetworkState::default());
+      app.manage(ai_stream::AiStreams::default());
+      app.manage(model_manager::ModelDownloads::default());
+      app.manage(resources::ResourceMonitor::default());
+      app.manage(sync::SyncState::default());
+      app.manage(theme::SystemTheme::default());
+      #[cfg(desktop)]
+      app.manage(updater::PendingUpdate::default());
+      #[cfg(desktop)]
+      app.manage(teamsnap::TeamSnapState::default());
+      #[cfg(desktop)]
+      app.manage(cloud_backup::CloudBackupState::default());
+      #[cfg(desktop)]
+      app.manage(audio::VoiceCapture::default());
+      #[cfg(desktop)]
+      app.manage(audio::announcer::Announcer::default());
+      #[cfg(desktop)]
+      app.manage(devices::RadarState::default());
+      #[cfg(desktop)]
+      app.manage(devices::scoreboard::ScoreboardOutput::default());
+      #[cfg(desktop)]
+      app.manage(data_watcher::DataWatcher::default());
+
+      #[cfg(desktop)]
+      deep_link::init(app)?;
+      app.manage(window_state::WindowStates::load(&paths.window_state));
+      app.manage(paths);
+      resources::start_monitor(app.handle().clone());
+      telemetry::start_uploader(app.handle().clone());
+
+      // Mobile builds have no sidecar and always use remote mode. A sidecar
+      // from an update upgrades its data first, and reveals the window after
+      let remote_url = app.state::<SidecarManager>().remote_url();
+      let version = app.package_info().version.to_string();
+      let no_sidecar = cli::args().no_sidecar;
+      let migrate_from = match backend_migration::pending(&dugout_data_dir, &version) {
+        Ok(from) if remote_url.is_none() && !no_sidecar && cfg!(desktop) => from,
+        Ok(_) => None,
+        Err(e) => {
+          log::warn!("Failed to check whether the backend's data needs migrating: {}", e);
+          None
+        }
+      };
+      let migrating = migrate_from.is_some();
+      if let Some(url) = remote_url {
+        log::info!("Using the backend at {}", url);
+      } else if no_sidecar {
+        log::info!("Not starting the backend (--no-sidecar). AI features will be unavailable.");
+      } else if let Some(from) = migrate_from {
+        tauri::async_runtime::spawn(backend_migration::migrate_then_spawn(
+          app.handle().clone(),
+          from,
+          reveal,
+        ));
+      } else if cfg!(desktop) {
+        splash::report(app.handle(), splash::BootStage::StartingBackend);
+        match app.state::<SidecarManager>().spawn(app.handle()) {
+          Ok(pid) => {
+            log::info!(
+              "Backend sidecar started successfully (PID: {}, Data: {:?})",
+              pid,
+              dugout_data_dir
+            );
           }
           Err(e) => {
-            log::warn!("Backend sidecar binary not found: {}. AI features will be unavailable.", e);
+            log::warn!("{}. AI features will be unavailable.", e);
           }
         }
+      } else {
+        log::warn!("No remote backend is configured. AI features will be unavailable.");
       }
 
-      if cfg!(debug_assertions) {
-        app.handle().plugin(
-          tauri_plugin_log::Builder::default()
-            .level(log::LevelFilter::Info)
-            .build(),
-        )?;
+      #[cfg(desktop)]
+      tray::create(app)?;
+
+      if reveal && !migrating {
+        tauri::async_runtime::spawn(health::reveal_main_window_when_ready(
+          app.handle().clone(),
+        ));
       }
       Ok(())
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .on_window_event(|window, event| {
+      // Before the tray hides a closing window, so its geometry is kept
+      window_state::handle_window_event(window, event);
+      kiosk::handle_window_event(window, event);
+      tray::handle_window_event(window, event);
+      file_drop::handle_window_event(window, event);
+      theme::handle_window_event(window, event);
+      presentation::handle_window_event(window, event);
+    })
+    .invoke_handler(tauri::generate_handler![
+      ai_stream::cancel_ai_request,
+      ai_stream::stream_ai_request,
+      #[cfg(desktop)]
+      api_keys::delete_api_key,
+      #[cfg(desktop)]
+      api_keys::get_api_key_status,
+      #[cfg(desktop)]
+      api_keys::set_api_key,
+      archive::export_backup,
+      archive::import_backup,
+      archive::inspect_backup,
+      #[cfg(desktop)]
+      audio::announcer::announce_batting_order,
+      #[cfg(desktop)]
+      audio::announcer::get_announcer_status,
+      #[cfg(desktop)]
+      audio::announcer::skip_announcement,
+      #[cfg(desktop)]
+      audio::announcer::stop_announcements,
+      #[cfg(desktop)]
+      audio::start_voice_capture,
+      #[cfg(desktop)]
+      audio::stop_voice_capture,
+      #[cfg(desktop)]
+      autostart::get_autostart,
+      #[cfg(desktop)]
+      autostart::set_autostart,
+      backup::create_backup,
+      backup::list_backups,
+      backup::restore_backup,
+      batting_order::rotate_batting_order,
+      clipboard::copy_lineup_to_clipboard,
+      #[cfg(desktop)]
+      cloud_backup::link_cloud_backup,
+      #[cfg(desktop)]
+      cloud_backup::list_cloud_backups,
+      #[cfg(desktop)]
+      cloud_backup::restore_from_cloud,
+      #[cfg(desktop)]
+      cloud_backup::unlink_cloud_backup,
+      crash::dismiss_crash_report,
+      crash::get_pending_crash_reports,
+      crash::submit_crash_report,
+      db::ai_queue::dismiss_ai_request,
+      db::ai_queue::list_ai_queue,
+      db::audit::get_audit_log,
+      db::contacts::get_player_contacts,
+      db::contacts::set_player_contacts,
+      db::drills::delete_drill,
+      db::drills::list_drills,
+      db::drills::save_drill,
+      db::encryption::enable_encryption,
+      db::encryption::get_encryption_status,
+      db::gamechanger::import_gamechanger,
+      db::integrity::check_data_integrity,
+      db::integrity::get_integrity_report,
+      db::lineups::delete_lineup,
+      db::lineups::get_lineup,
+      db::lineups::list_lineups,
+      db::lineups::save_lineup,
+      db::migrations::get_schema_version,
+      db::practice_plans::delete_practice_plan,
+      db::practice_plans::get_practice_plan,
+      db::practice_plans::list_practice_plans,
+      db::practice_plans::update_practice_plan,
+      db::roster::create_player,
+      db::roster::delete_player,
+      db::roster::list_roster,
+      db::roster::update_player,
+      db::roster_csv::import_roster_csv,
+      db::seasons::archive_season,
+      db::seasons::list_archived_seasons,
+      db::seasons::restore_season,
+      db::undo::get_history,
+      db::undo::redo,
+      db::undo::undo,
+      deep_link::take_pending_deep_links,
+      defense::generate_defense_rotation,
+      #[cfg(desktop)]
+      devices::connect_radar,
+      #[cfg(desktop)]
+      devices::disconnect_radar,
+      #[cfg(desktop)]
+      devices::get_radar_status,
+      #[cfg(desktop)]
+      devices::list_radars,
+      #[cfg(desktop)]
+      devices::list_serial_ports,
+      #[cfg(desktop)]
+      devices::scoreboard::connect_scoreboard,
+      #[cfg(desktop)]
+      devices::scoreboard::disconnect_scoreboard,
+      #[cfg(desktop)]
+      devices::scoreboard::get_scoreboard_output,
+      #[cfg(desktop)]
+      devices::scoreboard::list_scoreboard_protocols,
+      #[cfg(desktop)]
+      devices::set_radar_game,
+      export::field::render_field_diagram,
+      export::parent_site::export_parent_site,
+      export::pdf::generate_lineup_pdf,
+      export::stats_csv::export_stats_csv,
+      export::xlsx::export_xlsx,
+      fairness::export_fairness_report,
+      fairness::get_fairness_report,
+      first_run::is_first_run,
+      first_run::reset_to_factory,
+      first_run::seed_demo_data,
+      folders::open_backups_dir,
+      folders::open_logs_dir,
+      folders::reveal_data_dir,
+      game_clock::get_game_clock,
+      game_clock::pause_game_clock,
+      game_clock::reset_game_clock,
+      game_clock::set_time_limit,
+      game_clock::start_game_clock,
+      game_mode::enter_game_mode,
+      game_mode::exit_game_mode,
+      game_mode::get_game_mode,
+      handshake::check_backend_compatibility,
+      handshake::get_backend_compatibility,
+      handshake::migrate_backend_data,
+      health::backend_health,
+      health::restart_backend,
+      #[cfg(desktop)]
+      hotkeys::get_hotkeys,
+      #[cfg(desktop)]
+      hotkeys::set_hotkeys,
+      ics::export_ics,
+      ics::import_ics,
+      images::delete_image,
+      images::import_image,
+      images::list_images,
+      importers::import_games,
+      importers::list_importers,
+      importers::preview_games_import,
+      json_archive::export_json_archive,
+      json_archive::import_json_archive,
+      kiosk::enter_kiosk_mode,
+      kiosk::exit_kiosk_mode,
+      kiosk::get_kiosk_status,
+      kiosk::set_kiosk_pin,
+      logging::get_log_config,
+      logging::set_log_level,
+      model_manager::delete_model,
+      model_manager::download_model,
+      model_manager::list_models,
+      network::enqueue_ai_request,
+      network::get_network_status,
+      network::replay_ai_queue,
+      notes_index::search_notes,
+      optimizer::generate_lineup,
+      overlay::get_overlay_status,
+      overlay::start_overlay_server,
+      overlay::stop_overlay_server,
+      pitching::get_pitcher_availability,
+      pitching::list_pitch_rule_presets,
+      pitching::record_pitches,
+      practice::generate_practice_plan,
+      presentation::get_presentation,
+      presentation::list_displays,
+      presentation::show_slide,
+      presentation::start_presentation,
+      presentation::stop_presentation,
+      presentation::update_presentation,
+      print::list_printers,
+      print::print_lineup,
+      print::print_season_cards,
+      profiles::create_profile,
+      profiles::list_profiles,
+      profiles::switch_profile,
+      proxy::backend_request,
+      proxy::backend_stream,
+      qr::generate_qr,
+      relay::get_live_share_status,
+      relay::start_live_share,
+      relay::stop_live_share,
+      relocate::set_data_dir,
+      reminders::snooze_reminder,
+      resources::get_backend_resource_usage,
+      rules::get_league_rules,
+      rules::set_league_rules,
+      rules::validate_lineup,
+      scoreboard::close_scoreboard_window,
+      scoreboard::get_scoreboard,
+      scoreboard::open_scoreboard_window,
+      scoreboard::update_scoreboard,
+      scorebook::get_box_score,
+      scorebook::list_plays,
+      scorebook::record_play,
+      scorebook::undo_last_play,
+      search::global_search,
+      settings::get_settings,
+      settings::update_settings,
+      share::share_lineup_email,
+      sidecar::get_backend_logs,
+      sidecar::get_backend_port,
+      sidecar::get_backend_status,
+      splash::get_boot_progress,
+      stats::get_player_stats,
+      stats::get_team_stats,
+      substitutions::plan_substitutions,
+      sync::conflicts::get_sync_conflicts,
+      sync::conflicts::resolve_conflict,
+      sync::discover_sync_peers,
+      sync::get_sync_status,
+      sync::pair_with_peer,
+      sync::start_sync_hosting,
+      sync::stop_sync,
+      system::get_system_capabilities,
+      #[cfg(desktop)]
+      teamsnap::import_teamsnap_roster,
+      #[cfg(desktop)]
+      teamsnap::import_teamsnap_schedule,
+      #[cfg(desktop)]
+      teamsnap::link_teamsnap,
+      #[cfg(desktop)]
+      teamsnap::list_teamsnap_teams,
+      #[cfg(desktop)]
+      teamsnap::unlink_teamsnap,
+      telemetry::get_pending_telemetry,
+      telemetry::set_telemetry_enabled,
+      theme::get_system_theme,
+      theme::set_theme,
+      tray::set_minimize_to_tray,
+      #[cfg(desktop)]
+      updater::check_for_updates,
+      #[cfg(desktop)]
+      updater::download_update,
+      #[cfg(desktop)]
+      updater::get_rollback_version,
+      #[cfg(desktop)]
+      updater::get_update_channel,
+      #[cfg(desktop)]
+      updater::install_update,
+      #[cfg(desktop)]
+      updater::rollback_update,
+      #[cfg(desktop)]
+      updater::set_update_channel,
+      weather::get_game_weather,
+    ])
+    .build(context)
+    .expect("error while building tauri application")
+    .run(|app, event| {
+      // Stop the sidecar so it doesn't outlive the app and keep the data
+      // directory locked. `shutdown` is idempotent, so handling both events
+      // covers quitting via the window as well as `app.exit()`.
+      // Quitting from the OS (Cmd+Q, logging out) is refused in kiosk mode
+      if let RunEvent::ExitRequested { api, code: None, .. } = &event {
+        if kiosk::is_locked(app) {
+          api.prevent_exit();
+          return;
+        }
+      }
+      if let RunEvent::ExitRequested { .. } | RunEvent::Exit = event {
+        if let Some(manager) = app.try_state::<SidecarManager>() {
+          manager.shutdown(sidecar::SHUTDOWN_TIMEOUT);
+        }
+        window_state::save(app);
+      }
+      if let RunEvent::Exit = event {
+        if let Some(paths) = app.try_state::<AppPaths>() {
+          db::integrity::mark_stopped(&paths.running);
+        }
+      }
+
+      // macOS delivers double-clicked `.dugout` files as an event rather
+      // than as launch arguments
+      #[cfg(target_os = "macos")]
+      if let RunEvent::Opened { urls } = event {
+        deep_link::handle_opened(app, urls);
+      }
+    });
+}
+
+/// The app's configuration, with the main window starting full screen and
+/// without devtools when launched in kiosk mode.
+fn context() -> tauri::Context {
+  let mut context = tauri::generate_context!();
+  if kiosk::launched_with_flag() {
+    for window in &mut context.config_mut().app.windows {
+      window.fullscreen = true;
+      window.devtools = Some(false);
+    }
+  }
+  context
 }
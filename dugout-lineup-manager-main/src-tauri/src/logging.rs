@@ -0,0 +1,300 @@
+//! The process-wide logger. It keeps the most recent lines in memory for
+//! crash reports and forwards records to tauri-plugin-log's targets: a
+//! size-capped, rotating file in the app log directory in every build,
+//! plus stdout and the webview console in debug builds.
+//!
+//! Filtering happens here rather than in the plugin, so the level and
+//! per-module overrides in [`LogSettings`] can change without a restart.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, RwLock};
+
+use log::{LevelFilter, Log, Metadata, Record};
+use tauri::{AppHandle, State};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind, TimezoneStrategy};
+
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+use crate::settings::{self, AppSettings, LogLevel, LogSettings};
+
+/// Lines kept for crash reports.
+pub const RECENT_LINES: usize = 200;
+
+/// Size at which the log file is rotated.
+const MAX_FILE_SIZE: u128 = 5 * 1024 * 1024;
+
+/// Rotated log files kept besides the current one.
+const KEEP_FILES: usize = 5;
+
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+static FILTER: RwLock<Filter> = RwLock::new(Filter {
+  level: LevelFilter::Info,
+  modules: Vec::new(),
+});
+
+impl From<LogLevel> for LevelFilter {
+  fn from(level: LogLevel) -> Self {
+    match level {
+      LogLevel::Off => LevelFilter::Off,
+      LogLevel::Error => LevelFilter::Error,
+      LogLevel::Warn => LevelFilter::Warn,
+      LogLevel::Info => LevelFilter::Info,
+      LogLevel::Debug => LevelFilter::Debug,
+      LogLevel::Trace => LevelFilter::Trace,
+    }
+  }
+}
+
+/// [`LogSettings`] resolved for lookups on every record.
+#[derive(Debug)]
+struct Filter {
+  level: LevelFilter,
+  /// Module path prefixes, longest first.
+  modules: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+  fn new(settings: &LogSettings) -> Self {
+    let mut modules: Vec<_> = settings
+      .modules
+      .iter()
+      .map(|(module, level)| (module.clone(), LevelFilter::from(*level)))
+      .collect();
+    modules.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+    Self {
+      level: settings.level.into(),
+      modules,
+    }
+  }
+
+  fn level_for(&self, target: &str) -> LevelFilter {
+    let own = target
+      .strip_prefix(CRATE)
+      .and_then(|rest| rest.strip_prefix("::"));
+    let within = |path: &str, module: &str| {
+      path
+        .strip_prefix(module)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    };
+    self
+      .modules
+      .iter()
+      .find(|(module, _)| within(target, module) || own.is_some_and(|own| within(own, module)))
+      .map_or(self.level, |(_, level)| *level)
+  }
+
+  fn max_level(&self) -> LevelFilter {
+    self
+      .modules
+      .iter()
+      .map(|(_, level)| *level)
+      .fold(self.level, Ord::max)
+  }
+}
+
+/// This crate's name as it appears in record targets.
+const CRATE: &str = env!("CARGO_CRATE_NAME");
+
+fn filter() -> std::sync::RwLockReadGuard<'static, Filter> {
+  FILTER.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Applies new level settings to every following record.
+pub fn configure(settings: &LogSettings) {
+  let filter = Filter::new(settings);
+  log::set_max_level(filter.max_level());
+  *FILTER.write().unwrap_or_else(|e| e.into_inner()) = filter;
+}
+
+struct Logger {
+  /// tauri-plugin-log's dispatcher.
+  inner: Box<dyn Log>,
+}
+
+impl Log for Logger {
+  fn enabled(&self, metadata: &Metadata) -> bool {
+    metadata.level() <= filter().level_for(metadata.target())
+  }
+
+  fn log(&self, record: &Record) {
+    if !self.enabled(record.metadata()) {
+      return;
+    }
+    remember(record);
+    self.inner.log(record);
+  }
+
+  fn flush(&self) {
+    self.inner.flush();
+  }
+}
+
+fn remember(record: &Record) {
+  let line = format!(
+    "{} {:<5} {}: {}",
+    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+    record.level(),
+    record.target(),
+    record.args()
+  );
+  // A panic while logging must not poison crash reporting.
+  let mut recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+  if recent.len() == RECENT_LINES {
+    recent.pop_front();
+  }
+  recent.push_back(line);
+}
+
+/// The most recent log lines, oldest first.
+pub fn recent_lines() -> Vec<String> {
+  let recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+  recent.iter().cloned().collect()
+}
+
+/// Installs the logger at the default level; `configure` applies the
+/// user's settings once they are loaded.
+pub fn init(app: &tauri::App) -> std::result::Result<(), Box<dyn std::error::Error>> {
+  let file_name = Some("dugout".into());
+  let mut targets = vec![Target::new(match crate::cli::args().log_dir() {
+    Some(path) => TargetKind::Folder { path, file_name },
+    None => TargetKind::LogDir { file_name },
+  })];
+  if cfg!(debug_assertions) {
+    targets.push(Target::new(TargetKind::Stdout));
+    targets.push(Target::new(TargetKind::Webview));
+  }
+  let (plugin, _, inner) = tauri_plugin_log::Builder::new()
+    .clear_targets()
+    .targets(targets)
+    .level(LevelFilter::Trace)
+    .max_file_size(MAX_FILE_SIZE)
+    .rotation_strategy(RotationStrategy::KeepSome(KEEP_FILES))
+    .timezone_strategy(TimezoneStrategy::UseLocal)
+    .split(app.handle())?;
+  app.handle().plugin(plugin)?;
+  log::set_boxed_logger(Box::new(Logger { inner }))?;
+  log::set_max_level(filter().max_level());
+  Ok(())
+}
+
+pub(crate) fn validate_module(module: &str) -> Result<()> {
+  let valid = !module.is_empty()
+    && module
+      .split("::")
+      .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+  if valid {
+    Ok(())
+  } else {
+    Err(Error::Validation(format!("Invalid module path {module:?}")))
+  }
+}
+
+#[tauri::command]
+pub fn get_log_config(settings: State<'_, RwLock<AppSettings>>) -> LogSettings {
+  settings.read().unwrap().log.clone()
+}
+
+/// Sets the default level, or with `module`, that module's override. A
+/// module with no `level` goes back to the default. Takes effect
+/// immediately and is saved to settings.
+#[tauri::command]
+pub fn set_log_level(
+  app: AppHandle,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+  level: Option<LogLevel>,
+  module: Option<String>,
+) -> Result<LogSettings> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  let mut settings = settings.write().unwrap();
+  match (module, level) {
+    (Some(module), level) => {
+      validate_module(&module)?;
+      match level {
+        Some(level) => settings.log.modules.insert(module, level),
+        None => settings.log.modules.remove(&module),
+      };
+    }
+    (None, Some(level)) => settings.log.level = level,
+    (None, None) => return Err(Error::Validation("A level or module is required".into())),
+  }
+  configure(&settings.log);
+  log::info!("Log levels changed: {:?}", settings.log);
+  settings::save(&paths.settings, &settings)?;
+  Ok(settings.log.clone())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct Discard;
+
+  impl Log for Discard {
+    fn enabled(&self, _: &Metadata) -> bool {
+      true
+    }
+    fn log(&self, _: &Record) {}
+    fn flush(&self) {}
+  }
+
+  #[test]
+  fn resolves_the_most_specific_module_override() {
+    let settings = LogSettings {
+      level: LogLevel::Warn,
+      modules: [
+        ("sidecar".to_string(), LogLevel::Debug),
+        ("reqwest".to_string(), LogLevel::Error),
+        ("reqwest::connect".to_string(), LogLevel::Trace),
+      ]
+      .into(),
+    };
+    let filter = Filter::new(&settings);
+    let own = |module: &str| format!("{CRATE}::{module}");
+
+    assert_eq!(filter.level_for(&own("sidecar")), LevelFilter::Debug);
+    assert_eq!(filter.level_for(&own("sidecar::health")), LevelFilter::Debug);
+    assert_eq!(filter.level_for(&own("sidecars")), LevelFilter::Warn);
+    assert_eq!(filter.level_for("reqwest::async_impl"), LevelFilter::Error);
+    assert_eq!(filter.level_for("reqwest::connect"), LevelFilter::Trace);
+    assert_eq!(filter.level_for("tao::platform"), LevelFilter::Warn);
+    assert_eq!(filter.max_level(), LevelFilter::Trace);
+  }
+
+  #[test]
+  fn validates_module_paths() {
+    assert!(validate_module("sidecar").is_ok());
+    assert!(validate_module("app_lib::db::roster").is_ok());
+    assert!(validate_module("").is_err());
+    assert!(validate_module("db::").is_err());
+    assert!(validate_module("db roster").is_err());
+  }
+
+  #[test]
+  fn keeps_only_the_most_recent_lines() {
+    let logger = Logger {
+      inner: Box::new(Discard),
+    };
+    for i in 0..RECENT_LINES + 5 {
+      logger.log(
+        &Record::builder()
+          .args(format_args!("line {i}"))
+          .level(log::Level::Info)
+          .target("app_lib::test")
+          .build(),
+      );
+    }
+    logger.log(
+      &Record::builder()
+        .args(format_args!("hidden"))
+        .level(log::Level::Debug)
+        .build(),
+    );
+
+    let lines = recent_lines();
+    assert_eq!(lines.len(), RECENT_LINES);
+    assert!(lines[0].ends_with("app_lib::test: line 5"));
+    assert!(lines.last().unwrap().ends_with(&format!("line {}", RECENT_LINES + 4)));
+  }
+}
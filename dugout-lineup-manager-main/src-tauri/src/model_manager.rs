@@ -0,0 +1,310 @@
+//! Local AI model files, downloaded into `AppPaths::models` and handed to
+//! the sidecar as `DUGOUT_MODELS_DIR`.
+//!
+//! Downloads go to `<name>.part` first and resume from its length with a
+//! `Range` request, so a dropped connection at the field doesn't start a
+//! multi-gigabyte download over. The file only gets its real name once its
+//! SHA-256 matches the one the caller asked for.
+
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::AsyncWriteExt;
+
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+
+/// Event carrying [`ModelDownloadProgress`] while a model downloads.
+pub const MODEL_DOWNLOAD_EVENT: &str = "model-download-progress";
+
+/// Suffix of a download that hasn't finished or been verified.
+const PARTIAL_SUFFIX: &str = ".part";
+
+/// Progress events are sent at most this often.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+  pub name: String,
+  /// Bytes on disk so far.
+  pub size: u64,
+  /// `false` for a download that was interrupted and can be resumed.
+  pub complete: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDownloadProgress {
+  pub name: String,
+  pub downloaded: u64,
+  /// `None` if the server didn't say.
+  pub total: Option<u64>,
+}
+
+/// Names of the models currently downloading, so the same one isn't
+/// fetched twice at once.
+#[derive(Default)]
+pub struct ModelDownloads(Mutex<HashSet<String>>);
+
+/// Removes the name from [`ModelDownloads`] however the download ends.
+struct DownloadGuard<'a> {
+  downloads: &'a ModelDownloads,
+  name: String,
+}
+
+impl Drop for DownloadGuard<'_> {
+  fn drop(&mut self) {
+    self.downloads.0.lock().unwrap().remove(&self.name);
+  }
+}
+
+/// Model names become file names, so only plain ones are allowed.
+fn validate_name(name: &str) -> Result<()> {
+  let valid = !name.is_empty()
+    && name.len() <= 128
+    && !name.starts_with('.')
+    && !name.ends_with(PARTIAL_SUFFIX)
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+  if valid {
+    Ok(())
+  } else {
+    Err(Error::Validation(format!("Invalid model name: {name}")))
+  }
+}
+
+fn partial_path(dir: &Path, name: &str) -> PathBuf {
+  dir.join(format!("{name}{PARTIAL_SUFFIX}"))
+}
+
+pub fn list(dir: &Path) -> Result<Vec<ModelInfo>> {
+  let entries = match std::fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(e.into()),
+  };
+  let mut models = Vec::new();
+  for entry in entries {
+    let entry = entry?;
+    if !entry.file_type()?.is_file() {
+      continue;
+    }
+    let file_name = entry.file_name().to_string_lossy().into_owned();
+    let (name, complete) = match file_name.strip_suffix(PARTIAL_SUFFIX) {
+      Some(name) => (name.to_string(), false),
+      None => (file_name, true),
+    };
+    if validate_name(&name).is_err() {
+      continue;
+    }
+    models.push(ModelInfo {
+      name,
+      size: entry.metadata()?.len(),
+      complete,
+    });
+  }
+  models.sort_by(|a, b| a.name.cmp(&b.name));
+  Ok(models)
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+  let mut file = std::fs::File::open(path)?;
+  let mut hasher = Sha256::new();
+  let mut buffer = vec![0; 1 << 16];
+  loop {
+    let read = file.read(&mut buffer)?;
+    if read == 0 {
+      break;
+    }
+    hasher.update(&buffer[..read]);
+  }
+  Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Fetches `url` into the partial file, picking up where it left off.
+async fn fetch(app: &AppHandle, url: &str, partial: &Path, name: &str) -> Result<()> {
+  let resume_from = tokio::fs::metadata(partial).await.map(|m| m.len()).unwrap_or(0);
+  let mut request = reqwest::Client::new().get(url);
+  if resume_from > 0 {
+    request = request.header(RANGE, format!("bytes={resume_from}-"));
+  }
+  let mut response = request.send().await.map_err(download_error)?;
+
+  let status = response.status();
+  // Already fully downloaded; the checksum decides if it's any good
+  if status == StatusCode::RANGE_NOT_SATISFIABLE && resume_from > 0 {
+    return Ok(());
+  }
+  if !status.is_success() {
+    return Err(Error::Validation(format!("Model download failed: server returned {status}")));
+  }
+  // A server that ignores the range sends the whole file again
+  let resumed = status == StatusCode::PARTIAL_CONTENT;
+  let mut downloaded = if resumed { resume_from } else { 0 };
+  let total = response.content_length().map(|length| length + downloaded);
+  if resume_from > 0 && !resumed {
+    log::info!("Server can't resume {}; downloading it again", name);
+  }
+
+  let mut file = tokio::fs::OpenOptions::new()
+    .create(true)
+    .write(true)
+    .append(resumed)
+    .truncate(!resumed)
+    .open(partial)
+    .await?;
+  let mut last_progress = Instant::now();
+  while let Some(chunk) = response.chunk().await.map_err(download_error)? {
+    file.write_all(&chunk).await?;
+    downloaded += chunk.len() as u64;
+    if last_progress.elapsed() >= PROGRESS_INTERVAL {
+      last_progress = Instant::now();
+      emit_progress(app, name, downloaded, total);
+    }
+  }
+  file.flush().await?;
+  emit_progress(app, name, downloaded, total);
+  Ok(())
+}
+
+fn download_error(e: reqwest::Error) -> Error {
+  Error::Validation(format!("Model download failed: {e}"))
+}
+
+fn emit_progress(app: &AppHandle, name: &str, downloaded: u64, total: Option<u64>) {
+  let _ = app.emit(
+    MODEL_DOWNLOAD_EVENT,
+    ModelDownloadProgress {
+      name: name.to_string(),
+      downloaded,
+      total,
+    },
+  );
+}
+
+#[tauri::command]
+pub fn list_models(paths: State<'_, AppPaths>) -> Result<Vec<ModelInfo>> {
+  list(&paths.models)
+}
+
+/// Downloads a model from `url`, resuming an earlier partial download of
+/// the same name, and keeps it only if its SHA-256 is `sha256`.
+#[tauri::command]
+pub async fn download_model(
+  app: AppHandle,
+  paths: State<'_, AppPaths>,
+  downloads: State<'_, ModelDownloads>,
+  name: String,
+  url: String,
+  sha256: String,
+) -> Result<ModelInfo> {
+  validate_name(&name)?;
+  let sha256 = sha256.to_ascii_lowercase();
+  if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+    return Err(Error::Validation("Checksum must be a SHA-256 hex digest".into()));
+  }
+  if !(url.starts_with("https://") || url.starts_with("http://")) {
+    return Err(Error::Validation(format!("Unsupported model URL: {url}")));
+  }
+  if !downloads.0.lock().unwrap().insert(name.clone()) {
+    return Err(Error::Validation(format!("{name} is already downloading")));
+  }
+  let _guard = DownloadGuard {
+    downloads: &downloads,
+    name: name.clone(),
+  };
+
+  let dir = paths.models.clone();
+  tokio::fs::create_dir_all(&dir).await?;
+  let partial = partial_path(&dir, &name);
+  log::info!("Downloading model {} from {}", name, url);
+  fetch(&app, &url, &partial, &name).await?;
+
+  let hashed = partial.clone();
+  let actual = tauri::async_runtime::spawn_blocking(move || sha256_file(&hashed))
+    .await
+    .map_err(|e| Error::Validation(format!("Checksum failed: {e}")))??;
+  if actual != sha256 {
+    // Resuming a corrupt file would only fail again
+    let _ = tokio::fs::remove_file(&partial).await;
+    return Err(Error::Validation(format!(
+      "Downloaded {name} doesn't match its checksum; try again"
+    )));
+  }
+
+  let path = dir.join(&name);
+  tokio::fs::rename(&partial, &path).await?;
+  log::info!("Model {} downloaded and verified", name);
+  Ok(ModelInfo {
+    name,
+    size: tokio::fs::metadata(&path).await?.len(),
+    complete: true,
+  })
+}
+
+/// Deletes a model, or the unfinished download of one.
+#[tauri::command]
+pub fn delete_model(
+  paths: State<'_, AppPaths>,
+  downloads: State<'_, ModelDownloads>,
+  name: String,
+) -> Result<()> {
+  validate_name(&name)?;
+  if downloads.0.lock().unwrap().contains(&name) {
+    return Err(Error::Validation(format!("{name} is still downloading")));
+  }
+  let mut removed = false;
+  for path in [paths.models.join(&name), partial_path(&paths.models, &name)] {
+    match std::fs::remove_file(&path) {
+      Ok(()) => removed = true,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+      Err(e) => return Err(e.into()),
+    }
+  }
+  if removed {
+    Ok(())
+  } else {
+    Err(Error::NotFound(format!("Model {name}")))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lists_finished_and_partial_models() {
+    let dir = std::env::temp_dir().join(format!("dugout-models-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("lyra-7b.gguf"), [0; 4]).unwrap();
+    std::fs::write(dir.join("lyra-13b.gguf.part"), [0; 2]).unwrap();
+    std::fs::write(dir.join(".DS_Store"), []).unwrap();
+
+    let models = list(&dir).unwrap();
+    let summary: Vec<_> = models.iter().map(|m| (m.name.as_str(), m.size, m.complete)).collect();
+    assert_eq!(summary, [("lyra-13b.gguf", 2, false), ("lyra-7b.gguf", 4, true)]);
+    assert_eq!(
+      sha256_file(&dir.join("lyra-7b.gguf")).unwrap(),
+      "df3f619804a92fdb4057192dc43dd748ea778adc52bc498ce80524c014b81119"
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn rejects_names_that_are_not_plain_files() {
+    for name in ["", "../secrets", "a/b", ".hidden", "model.part", "a b"] {
+      assert!(validate_name(name).is_err(), "{name}");
+    }
+    assert!(validate_name("lyra-coach_7B.Q4_K_M.gguf").is_ok());
+  }
+}
@@ -0,0 +1,223 @@
+//! Connectivity watcher and replay of the offline AI queue.
+//!
+//! Reachability of the cloud AI providers is probed on an interval and
+//! reported with `network-status` events. While online, requests queued in
+//! `db::ai_queue` are sent to the sidecar oldest first, so a coach at a
+//! field with no Wi-Fi gets their answers once the connection comes back.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::db::ai_queue::{self, QueuedKind, QueuedRequest};
+use crate::db::Database;
+use crate::error::Result;
+use crate::health;
+use crate::sidecar::{self, SidecarManager};
+
+/// Emitted with a `NetworkStatus` when connectivity changes.
+pub const NETWORK_STATUS_EVENT: &str = "network-status";
+
+/// Emitted with the whole queue after a replay changes it.
+pub const AI_QUEUE_EVENT: &str = "ai-queue-updated";
+
+/// Any HTTP response from one of these counts as online.
+const PROBE_URLS: &[&str] = &["https://api.openai.com", "https://api.anthropic.com"];
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+const ONLINE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Probed more often while offline, so queued work goes out soon after the
+/// connection returns.
+const OFFLINE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Replayed requests wait for the whole model response.
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStatus {
+  pub online: bool,
+}
+
+pub struct NetworkState {
+  online: AtomicBool,
+  replaying: AtomicBool,
+}
+
+/// Assumes online until the first probe says otherwise, so nothing is
+/// queued needlessly at startup.
+impl Default for NetworkState {
+  fn default() -> Self {
+    Self {
+      online: AtomicBool::new(true),
+      replaying: AtomicBool::new(false),
+    }
+  }
+}
+
+impl NetworkState {
+  pub fn is_online(&self) -> bool {
+    self.online.load(Ordering::SeqCst)
+  }
+}
+
+async fn probe(client: &reqwest::Client) -> bool {
+  for url in PROBE_URLS {
+    if client.head(*url).timeout(PROBE_TIMEOUT).send().await.is_ok() {
+      return true;
+    }
+  }
+  false
+}
+
+/// Starts the background task that tracks connectivity and drains the
+/// queue while online.
+pub fn start_watcher(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let client = reqwest::Client::new();
+    loop {
+      let online = probe(&client).await;
+      let was_online = app.state::<NetworkState>().online.swap(online, Ordering::SeqCst);
+      if online != was_online {
+        log::info!("Network is {}", if online { "back" } else { "unavailable" });
+        let _ = app.emit(NETWORK_STATUS_EVENT, NetworkStatus { online });
+      }
+
+      if online {
+        replay(&app).await;
+      }
+      tokio::time::sleep(if online { ONLINE_INTERVAL } else { OFFLINE_INTERVAL }).await;
+    }
+  });
+}
+
+/// Sends pending requests unless a replay is already running.
+async fn replay(app: &AppHandle) {
+  let state = app.state::<NetworkState>();
+  if state.replaying.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  if let Err(e) = replay_pending(app).await {
+    log::warn!("Failed to replay queued AI requests: {}", e);
+  }
+  state.replaying.store(false, Ordering::SeqCst);
+}
+
+async fn replay_pending(app: &AppHandle) -> Result<()> {
+  let pending = ai_queue::pending(&app.state::<Database>().conn())?;
+  if pending.is_empty() {
+    return Ok(());
+  }
+  let sidecar = app.state::<SidecarManager>();
+  if !health::check(&sidecar.base_url()).await.healthy {
+    return Ok(());
+  }
+
+  log::info!("Replaying {} queued AI request(s)", pending.len());
+  let client = reqwest::Client::new();
+  for request in pending {
+    let outcome = send(&client, &sidecar, &request).await;
+    let db = app.state::<Database>();
+    let conn = db.conn();
+    match outcome {
+      Ok(response) => ai_queue::complete(&conn, &request.id, &response)?,
+      Err(failure) => {
+        log::warn!("Queued AI request {} failed: {}", request.id, failure.message);
+        ai_queue::record_failure(&conn, &request.id, &failure.message, failure.retry)?;
+        // Likely offline again; keep the rest in order for next time
+        if failure.retry {
+          break;
+        }
+      }
+    }
+  }
+
+  let queue = ai_queue::list(&app.state::<Database>().conn())?;
+  let _ = app.emit(AI_QUEUE_EVENT, queue);
+  Ok(())
+}
+
+struct Failure {
+  message: String,
+  /// Worth trying again later, as opposed to rejected by the backend.
+  retry: bool,
+}
+
+impl Failure {
+  fn transient(e: impl std::fmt::Display) -> Self {
+    Self {
+      message: e.to_string(),
+      retry: true,
+    }
+  }
+}
+
+async fn send(
+  client: &reqwest::Client,
+  sidecar: &SidecarManager,
+  request: &QueuedRequest,
+) -> std::result::Result<String, Failure> {
+  // Kept for once the backend has been updated or its data migrated
+  if let Some(reason) = sidecar.compatibility().blocked() {
+    return Err(Failure::transient(reason));
+  }
+  let response = client
+    .post(format!("{}{}", sidecar.base_url(), request.kind.path()))
+    .header(sidecar::AUTH_HEADER, sidecar.auth_token())
+    .json(&request.payload)
+    .timeout(REPLAY_TIMEOUT)
+    .send()
+    .await
+    .map_err(Failure::transient)?;
+  let status = response.status();
+  let body = response.text().await.map_err(Failure::transient)?;
+
+  if !status.is_success() {
+    return Err(Failure {
+      message: format!("Backend returned {status}: {body}"),
+      retry: status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+    });
+  }
+  // The chat stream answers 200 before it knows whether the provider is
+  // reachable, so an empty body is a failed attempt
+  if body.trim().is_empty() {
+    return Err(Failure::transient("Empty response from the AI provider"));
+  }
+  Ok(body)
+}
+
+#[tauri::command]
+pub fn get_network_status(state: State<'_, NetworkState>) -> NetworkStatus {
+  NetworkStatus {
+    online: state.is_online(),
+  }
+}
+
+/// Queues a request that needs the AI provider. If the network is already
+/// back, it is sent straight away.
+#[tauri::command]
+pub fn enqueue_ai_request(
+  app: AppHandle,
+  db: State<'_, Database>,
+  state: State<'_, NetworkState>,
+  kind: QueuedKind,
+  payload: serde_json::Value,
+) -> Result<QueuedRequest> {
+  let request = ai_queue::enqueue(&db.conn(), kind, &payload)?;
+  log::info!("Queued {:?} request {} until the network is back", kind, request.id);
+  if state.is_online() {
+    tauri::async_runtime::spawn(async move { replay(&app).await });
+  }
+  Ok(request)
+}
+
+/// Sends pending requests now instead of waiting for the next check.
+#[tauri::command]
+pub async fn replay_ai_queue(app: AppHandle) -> Result<Vec<QueuedRequest>> {
+  replay(&app).await;
+  ai_queue::list(&app.state::<Database>().conn())
+}
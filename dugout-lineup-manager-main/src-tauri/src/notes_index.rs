@@ -0,0 +1,519 @@
+//! Full-text search over player and game notes, for when a season of them
+//! is more than matching with `LIKE` can handle. There are no separate
+//! scouting reports; notes on a game are where an opponent gets scouted.
+//!
+//! The index is kept in the data directory's [`INDEX_DIR`] and is only a
+//! cache of the database. The `note_changes` triggers record which players
+//! and games were written, and each search first re-indexes just those. An
+//! index built from another copy of the database, e.g. before a restore or
+//! import, or one that can't be opened is rebuilt from scratch.
+
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{Datelike, NaiveDate};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{BooleanQuery, Occur, Query, QueryParser, RangeQuery, TermQuery};
+use tantivy::schema::{Field, IndexRecordOption, Schema, Value, FAST, INDEXED, STORED, STRING, TEXT};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::{doc, Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
+use tauri::{AppHandle, Manager};
+
+use crate::db::{versions, Database};
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+
+/// Folder of the data directory the index is kept in.
+pub const INDEX_DIR: &str = "search-index";
+
+const DEFAULT_LIMIT: usize = 20;
+
+/// The smallest indexing buffer tantivy accepts; notes are short.
+const WRITER_MEMORY: usize = 15_000_000;
+
+/// Characters of the notes shown around the matches.
+const SNIPPET_CHARS: usize = 160;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NoteKind {
+  Player,
+  Game,
+}
+
+impl NoteKind {
+  fn as_str(self) -> &'static str {
+    match self {
+      NoteKind::Player => "player",
+      NoteKind::Game => "game",
+    }
+  }
+
+  fn parse(kind: &str) -> Option<Self> {
+    match kind {
+      "player" => Some(NoteKind::Player),
+      "game" => Some(NoteKind::Game),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NoteFilters {
+  /// Only players' or only games' notes.
+  pub kind: Option<NoteKind>,
+  pub team_id: Option<String>,
+  /// Earliest game date, `YYYY-MM-DD`. Player notes have no date, so
+  /// either date leaves them out.
+  pub from: Option<String>,
+  /// Latest game date, `YYYY-MM-DD`, inclusive.
+  pub to: Option<String>,
+  /// Defaults to 20.
+  pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteHit {
+  pub kind: NoteKind,
+  /// The player or game.
+  pub id: String,
+  pub team_id: String,
+  /// The player's name, or the opponent as in "@ Eagles".
+  pub title: String,
+  /// The game's date.
+  pub date: Option<String>,
+  /// The notes around the matches, HTML-escaped with the matches in `<b>`.
+  pub snippet: String,
+  /// Webview route that opens the player or game.
+  pub route: String,
+  /// Higher is better; only meaningful within one search.
+  pub score: f32,
+}
+
+struct Fields {
+  /// `player:<id>` or `game:<id>`, as in `note_changes`.
+  doc_id: Field,
+  kind: Field,
+  id: Field,
+  team_id: Field,
+  title: Field,
+  /// Days since the common era, for games.
+  date: Field,
+  notes: Field,
+}
+
+fn schema() -> (Schema, Fields) {
+  let mut builder = Schema::builder();
+  let fields = Fields {
+    doc_id: builder.add_text_field("doc_id", STRING | STORED),
+    kind: builder.add_text_field("kind", STRING | STORED),
+    id: builder.add_text_field("id", STORED),
+    team_id: builder.add_text_field("team_id", STRING | STORED),
+    title: builder.add_text_field("title", STORED),
+    date: builder.add_i64_field("date", INDEXED | FAST | STORED),
+    notes: builder.add_text_field("notes", TEXT | STORED),
+  };
+  (builder.build(), fields)
+}
+
+fn day_number(date: &str) -> Option<i64> {
+  let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+  Some(i64::from(date.num_days_from_ce()))
+}
+
+fn date_bound(date: Option<&str>, field: Field) -> Result<Bound<Term>> {
+  match date {
+    None => Ok(Bound::Unbounded),
+    Some(date) => day_number(date)
+      .map(|day| Bound::Included(Term::from_field_i64(field, day)))
+      .ok_or_else(|| Error::Validation(format!("Invalid date {date:?}, expected YYYY-MM-DD"))),
+  }
+}
+
+/// A player's or game's notes as indexed.
+struct NoteDoc {
+  kind: NoteKind,
+  id: String,
+  team_id: String,
+  title: String,
+  date: Option<String>,
+  notes: String,
+}
+
+/// Players with notes, or just `only` if it has any.
+fn player_docs(conn: &Connection, only: Option<&str>) -> Result<Vec<NoteDoc>> {
+  let mut stmt = conn.prepare_cached(
+    "SELECT id, team_id, name, notes FROM players
+     WHERE notes != '' AND (?1 IS NULL OR id = ?1)",
+  )?;
+  let docs = stmt
+    .query_map([only], |row| {
+      Ok(NoteDoc {
+        kind: NoteKind::Player,
+        id: row.get(0)?,
+        team_id: row.get(1)?,
+        title: row.get(2)?,
+        date: None,
+        notes: row.get(3)?,
+      })
+    })?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(docs)
+}
+
+/// Games with notes, or just `only` if it has any.
+fn game_docs(conn: &Connection, only: Option<&str>) -> Result<Vec<NoteDoc>> {
+  let mut stmt = conn.prepare_cached(
+    "SELECT id, team_id, date, opponent, home_away, notes FROM games
+     WHERE notes != '' AND (?1 IS NULL OR id = ?1)",
+  )?;
+  let docs = stmt
+    .query_map([only], |row| {
+      let opponent: String = row.get(3)?;
+      let home_away: String = row.get(4)?;
+      let venue = if home_away == "away" { "@" } else { "vs" };
+      Ok(NoteDoc {
+        kind: NoteKind::Game,
+        id: row.get(0)?,
+        team_id: row.get(1)?,
+        title: format!("{venue} {opponent}"),
+        date: Some(row.get(2)?),
+        notes: row.get(5)?,
+      })
+    })?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(docs)
+}
+
+/// How far the index is up to date, saved with each commit.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Applied {
+  /// The database copy it was built from.
+  replica: String,
+  /// The last `note_changes` version indexed.
+  version: i64,
+}
+
+struct Open {
+  dir: PathBuf,
+  index: Index,
+  writer: IndexWriter,
+  reader: IndexReader,
+  fields: Fields,
+  applied: Applied,
+}
+
+impl Open {
+  fn new(index: Index, dir: PathBuf) -> Result<Self> {
+    let applied = index
+      .load_metas()?
+      .payload
+      .and_then(|payload| serde_json::from_str(&payload).ok())
+      .unwrap_or_default();
+    Ok(Open {
+      dir,
+      writer: index.writer_with_num_threads(1, WRITER_MEMORY)?,
+      reader: index.reader_builder().reload_policy(ReloadPolicy::Manual).try_into()?,
+      fields: schema().1,
+      index,
+      applied,
+    })
+  }
+
+  fn add(&self, note: NoteDoc) -> Result<()> {
+    let f = &self.fields;
+    let mut document = doc!(
+      f.doc_id => format!("{}:{}", note.kind.as_str(), note.id),
+      f.kind => note.kind.as_str(),
+      f.id => note.id,
+      f.team_id => note.team_id,
+      f.title => note.title,
+      f.notes => note.notes,
+    );
+    if let Some(day) = note.date.as_deref().and_then(day_number) {
+      document.add_i64(f.date, day);
+    }
+    self.writer.add_document(document)?;
+    Ok(())
+  }
+
+  /// Indexes the notes written since the last search, or all of them if
+  /// the database was swapped for another copy.
+  fn sync(&mut self, conn: &Connection) -> Result<()> {
+    let replica = versions::replica_id(conn)?;
+    let latest: i64 =
+      conn.query_row("SELECT COALESCE(MAX(version), 0) FROM note_changes", [], |row| row.get(0))?;
+    let applied = Applied {
+      replica,
+      version: latest,
+    };
+    if applied == self.applied {
+      return Ok(());
+    }
+
+    if applied.replica != self.applied.replica {
+      self.writer.delete_all_documents()?;
+      for note in player_docs(conn, None)?.into_iter().chain(game_docs(conn, None)?) {
+        self.add(note)?;
+      }
+    } else {
+      let mut stmt = conn.prepare_cached("SELECT doc_id FROM note_changes WHERE version > ?1")?;
+      let changed = stmt
+        .query_map([self.applied.version], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+      for doc_id in changed {
+        self.writer.delete_term(Term::from_field_text(self.fields.doc_id, &doc_id));
+        let notes = match doc_id.split_once(':') {
+          Some(("player", id)) => player_docs(conn, Some(id))?,
+          Some(("game", id)) => game_docs(conn, Some(id))?,
+          _ => Vec::new(),
+        };
+        for note in notes {
+          self.add(note)?;
+        }
+      }
+    }
+
+    let mut commit = self.writer.prepare_commit()?;
+    commit.set_payload(&serde_json::to_string(&applied)?);
+    commit.commit()?;
+    self.reader.reload()?;
+    self.applied = applied;
+    Ok(())
+  }
+
+  fn search(
+    &mut self,
+    conn: &Connection,
+    query: &str,
+    filters: &NoteFilters,
+  ) -> Result<Vec<NoteHit>> {
+    let limit = filters.limit.unwrap_or(DEFAULT_LIMIT);
+    if query.trim().is_empty() || limit == 0 {
+      return Ok(Vec::new());
+    }
+    self.sync(conn)?;
+    let f = &self.fields;
+
+    // Every word has to appear; quotes match a phrase. Half-typed syntax
+    // like an unclosed quote searches for what can be made sense of.
+    let mut parser = QueryParser::for_index(&self.index, vec![f.notes]);
+    parser.set_conjunction_by_default();
+    let (text, _) = parser.parse_query_lenient(query);
+
+    let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Must, text.box_clone())];
+    let mut term = |field: Field, value: &str| {
+      let term = Term::from_field_text(field, value);
+      clauses.push((Occur::Must, Box::new(TermQuery::new(term, IndexRecordOption::Basic))));
+    };
+    if let Some(kind) = filters.kind {
+      term(f.kind, kind.as_str());
+    }
+    if let Some(team_id) = &filters.team_id {
+      term(f.team_id, team_id);
+    }
+    let from = date_bound(filters.from.as_deref(), f.date)?;
+    let to = date_bound(filters.to.as_deref(), f.date)?;
+    if from != Bound::Unbounded || to != Bound::Unbounded {
+      clauses.push((Occur::Must, Box::new(RangeQuery::new(from, to))));
+    }
+
+    let searcher = self.reader.searcher();
+    let top = searcher.search(&BooleanQuery::new(clauses), &TopDocs::with_limit(limit))?;
+    let mut snippets = SnippetGenerator::create(&searcher, &*text, f.notes)?;
+    snippets.set_max_num_chars(SNIPPET_CHARS);
+
+    let mut hits = Vec::with_capacity(top.len());
+    for (score, address) in top {
+      let doc: TantivyDocument = searcher.doc(address)?;
+      let text_of = |field: Field| {
+        doc.get_first(field).and_then(|v| v.as_str()).unwrap_or_default().to_string()
+      };
+      let Some(kind) = NoteKind::parse(&text_of(f.kind)) else {
+        continue;
+      };
+      let id = text_of(f.id);
+      let route = match kind {
+        NoteKind::Player => format!("/?player={id}"),
+        NoteKind::Game => format!("/games/{id}/stats"),
+      };
+      let date = doc
+        .get_first(f.date)
+        .and_then(|v| v.as_i64())
+        .and_then(|day| NaiveDate::from_num_days_from_ce_opt(i32::try_from(day).ok()?))
+        .map(|date| date.format("%Y-%m-%d").to_string());
+      hits.push(NoteHit {
+        kind,
+        team_id: text_of(f.team_id),
+        title: text_of(f.title),
+        date,
+        snippet: snippets.snippet_from_doc(&doc).to_html(),
+        route,
+        score,
+        id,
+      });
+    }
+    Ok(hits)
+  }
+}
+
+/// Opens the index in `dir`, starting over if it's damaged or was made
+/// with a different schema.
+fn open_dir(dir: &Path) -> Result<Index> {
+  let open = || -> Result<Index> {
+    std::fs::create_dir_all(dir)?;
+    let directory = MmapDirectory::open(dir).map_err(tantivy::TantivyError::from)?;
+    Ok(Index::open_or_create(directory, schema().0)?)
+  };
+  open().or_else(|e| {
+    log::warn!("Rebuilding the notes search index in {:?}: {}", dir, e);
+    std::fs::remove_dir_all(dir)?;
+    open()
+  })
+}
+
+/// The open index, for whichever data directory is in use.
+#[derive(Default)]
+pub struct NotesIndex(Mutex<Option<Open>>);
+
+impl NotesIndex {
+  /// Lets go of the index's files so they can be moved; the next search
+  /// opens it again.
+  pub fn close(&self) {
+    *self.0.lock().unwrap() = None;
+  }
+
+  /// Notes matching `query`, best first, using the index in `dir`.
+  pub fn search(
+    &self,
+    dir: &Path,
+    conn: &Connection,
+    query: &str,
+    filters: &NoteFilters,
+  ) -> Result<Vec<NoteHit>> {
+    let mut open = self.0.lock().unwrap();
+    let index = match open.take() {
+      Some(index) if index.dir == dir => open.insert(index),
+      // Another profile, or the data directory moved
+      _ => open.insert(Open::new(open_dir(dir)?, dir.to_path_buf())?),
+    };
+    index.search(conn, query, filters)
+  }
+}
+
+/// Notes matching `query`, best first. All of its words have to appear,
+/// and a quoted phrase has to appear as written, e.g. `"slow to first"`.
+#[tauri::command]
+pub async fn search_notes(
+  app: AppHandle,
+  query: String,
+  filters: Option<NoteFilters>,
+) -> Result<Vec<NoteHit>> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let dir = app.state::<AppPaths>().data().join(INDEX_DIR);
+    let db = app.state::<Database>();
+    let conn = db.conn();
+    app.state::<NotesIndex>().search(&dir, &conn, &query, &filters.unwrap_or_default())
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::games::{self, ScheduledGame};
+  use crate::db::roster::{self, NewPlayer};
+
+  fn game(id: &str, date: &str, opponent: &str, notes: &str) -> ScheduledGame {
+    ScheduledGame {
+      id: id.into(),
+      date: date.into(),
+      opponent: opponent.into(),
+      home_away: "away".into(),
+      score_us: None,
+      score_them: None,
+      notes: notes.into(),
+      location: String::new(),
+      start_time: None,
+    }
+  }
+
+  #[test]
+  fn finds_phrases_within_dates_and_follows_edits() {
+    let conn = crate::db::open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let player = roster::create(
+      &conn,
+      NewPlayer {
+        team_id: Some(team_id.clone()),
+        name: "Sam Carter".into(),
+        number: Some(3),
+        primary_position: "CF".into(),
+        secondary_positions: Vec::new(),
+        bats: "L".into(),
+        throws: "L".into(),
+        status: None,
+        notes: Some("Slow to first out of the box".into()),
+      },
+    )
+    .unwrap();
+    for (id, date, notes) in [
+      ("g1", "2026-04-11", "Their catcher is slow to first; steal on him"),
+      ("g2", "2026-05-09", "Catcher throws well, slow first baseman"),
+    ] {
+      games::upsert_scheduled(&conn, &team_id, &game(id, date, "Eagles", notes)).unwrap();
+    }
+
+    let (schema, _) = schema();
+    let mut index = Open::new(Index::create_in_ram(schema), PathBuf::new()).unwrap();
+    let mut ids = |query: &str, filters: &NoteFilters| -> Vec<String> {
+      let mut ids: Vec<String> =
+        index.search(&conn, query, filters).unwrap().into_iter().map(|h| h.id).collect();
+      ids.sort();
+      ids
+    };
+    let all = NoteFilters::default();
+
+    let mut expected = vec!["g1".to_string(), "g2".to_string(), player.id.clone()];
+    expected.sort();
+    assert_eq!(ids("slow first", &all), expected);
+    let mut expected = vec!["g1".to_string(), player.id.clone()];
+    expected.sort();
+    assert_eq!(ids("\"slow to first\"", &all), expected);
+
+    let april = NoteFilters {
+      from: Some("2026-04-01".into()),
+      to: Some("2026-04-30".into()),
+      ..Default::default()
+    };
+    assert_eq!(ids("catcher", &april), ["g1"]);
+    let games_only = NoteFilters {
+      kind: Some(NoteKind::Game),
+      ..Default::default()
+    };
+    assert_eq!(ids("\"slow to first\"", &games_only), ["g1"]);
+
+    // Edits and deletes are picked up on the next search
+    games::upsert_scheduled(&conn, &team_id, &game("g2", "2026-05-09", "Eagles", "Rain out"))
+      .unwrap();
+    conn.execute("DELETE FROM games WHERE id = 'g1'", []).unwrap();
+    assert_eq!(ids("catcher", &all), Vec::<String>::new());
+    assert_eq!(ids("rain", &all), ["g2"]);
+
+    // So is a replaced database
+    crate::json_archive::clear(&conn).unwrap();
+    assert_eq!(ids("rain", &all), Vec::<String>::new());
+
+    let bad = NoteFilters {
+      from: Some("April".into()),
+      ..Default::default()
+    };
+    assert!(index.search(&conn, "rain", &bad).is_err());
+  }
+}
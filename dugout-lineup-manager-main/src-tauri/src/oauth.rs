@@ -0,0 +1,237 @@
+//! Signing the coach in to another service through the browser, for
+//! TeamSnap and the cloud backup services.
+//!
+//! [`Provider::start_link`] opens the service's sign-in page with a PKCE
+//! challenge, and the service sends the browser back to a `dugout://` link,
+//! which [`Provider::finish_link`] trades for a token kept in the OS
+//! keychain. [`Provider::access_token`] refreshes it as it expires, so
+//! linking is a one-time step.
+
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Url};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::error::{Error, Result};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Where a service signs the coach in and issues tokens.
+pub struct Endpoints {
+  pub auth_url: &'static str,
+  pub token_url: &'static str,
+  /// Set when building release bundles; each service issues them per app.
+  pub client_id: Option<&'static str>,
+  pub client_secret: Option<&'static str>,
+  /// Added to the sign-in URL, e.g. the scope or asking for a refresh
+  /// token.
+  pub auth_params: &'static [(&'static str, &'static str)],
+}
+
+/// A service to sign in to, and what the caller needs to know about it.
+pub struct Provider {
+  /// Shown to the coach, e.g. "Dropbox".
+  pub name: &'static str,
+  pub endpoints: Endpoints,
+  /// The `dugout://` link the service sends the browser back to.
+  pub redirect_uri: &'static str,
+  /// Keychain account holding the token, under the app's service name.
+  pub keychain_account: String,
+  /// Wraps what went wrong talking to the service, e.g. `Error::TeamSnap`.
+  pub error: fn(String) -> Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Token {
+  access_token: String,
+  refresh_token: Option<String>,
+  expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+  access_token: String,
+  refresh_token: Option<String>,
+  expires_in: Option<i64>,
+}
+
+impl From<TokenResponse> for Token {
+  fn from(response: TokenResponse) -> Self {
+    Self {
+      access_token: response.access_token,
+      refresh_token: response.refresh_token,
+      expires_at: response
+        .expires_in
+        .map(|secs| Utc::now() + chrono::Duration::seconds(secs)),
+    }
+  }
+}
+
+/// A sign-in waiting for the browser to come back.
+pub struct PendingLink {
+  state: String,
+  verifier: String,
+}
+
+/// PKCE challenge for `verifier`, so the code is useless to anyone who
+/// intercepts the redirect.
+fn challenge(verifier: &str) -> String {
+  URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+fn keychain(account: &str) -> Result<keyring::Entry> {
+  Ok(keyring::Entry::new(crate::api_keys::SERVICE, account)?)
+}
+
+impl Provider {
+  fn client_id(&self) -> Result<&'static str> {
+    self
+      .endpoints
+      .client_id
+      .ok_or_else(|| Error::Validation(format!("{} isn't available in this build", self.name)))
+  }
+
+  fn request_error(&self, e: reqwest::Error) -> Error {
+    (self.error)(e.to_string())
+  }
+
+  /// Opens the service's sign-in page in the browser. Keep the returned
+  /// link for [`finish_link`](Self::finish_link).
+  pub fn start_link(&self, app: &AppHandle) -> Result<PendingLink> {
+    let pending = PendingLink {
+      state: uuid::Uuid::new_v4().simple().to_string(),
+      verifier: format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple()),
+    };
+    let challenge = challenge(&pending.verifier);
+    let params = [
+      ("client_id", self.client_id()?),
+      ("redirect_uri", self.redirect_uri),
+      ("response_type", "code"),
+      ("state", pending.state.as_str()),
+      ("code_challenge", challenge.as_str()),
+      ("code_challenge_method", "S256"),
+    ];
+    let url = Url::parse_with_params(
+      self.endpoints.auth_url,
+      params.iter().chain(self.endpoints.auth_params),
+    )
+    .map_err(|e| (self.error)(e.to_string()))?;
+    app
+      .opener()
+      .open_url(url.as_str(), None::<&str>)
+      .map_err(|e| Error::Validation(format!("Couldn't open the browser: {e}")))?;
+    Ok(pending)
+  }
+
+  /// Trades the code in the callback `url` for a token and stores it.
+  pub async fn finish_link(&self, url: &Url, pending: &PendingLink) -> Result<()> {
+    let param = |name: &str| {
+      url
+        .query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+    };
+    if let Some(error) = param("error") {
+      let reason = param("error_description").unwrap_or(error);
+      return Err((self.error)(format!("Sign-in was cancelled: {reason}")));
+    }
+    if param("state").as_deref() != Some(pending.state.as_str()) {
+      return Err(Error::Validation(format!("{} sign-in didn't match; try again", self.name)));
+    }
+    let code = param("code").ok_or_else(|| (self.error)("No sign-in code returned".into()))?;
+
+    let token = self
+      .request_token(&[
+        ("grant_type", "authorization_code"),
+        ("code", code.as_str()),
+        ("redirect_uri", self.redirect_uri),
+        ("code_verifier", pending.verifier.as_str()),
+      ])
+      .await?;
+    self.store_token(&token).await
+  }
+
+  async fn load_token(&self) -> Result<Option<Token>> {
+    let account = self.keychain_account.clone();
+    tauri::async_runtime::spawn_blocking(move || match keychain(&account)?.get_password() {
+      Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
+      Err(keyring::Error::NoEntry) => Ok(None),
+      Err(e) => Err(e.into()),
+    })
+    .await?
+  }
+
+  async fn store_token(&self, token: &Token) -> Result<()> {
+    let (account, json) = (self.keychain_account.clone(), serde_json::to_string(token)?);
+    tauri::async_runtime::spawn_blocking(move || Ok(keychain(&account)?.set_password(&json)?))
+      .await?
+  }
+
+  async fn request_token(&self, form: &[(&str, &str)]) -> Result<Token> {
+    let mut form = form.to_vec();
+    form.push(("client_id", self.client_id()?));
+    if let Some(secret) = self.endpoints.client_secret {
+      form.push(("client_secret", secret));
+    }
+    let response = reqwest::Client::new()
+      .post(self.endpoints.token_url)
+      .form(&form)
+      .timeout(REQUEST_TIMEOUT)
+      .send()
+      .await
+      .map_err(|e| self.request_error(e))?;
+    if !response.status().is_success() {
+      let status = response.status();
+      return Err((self.error)(format!("{} sign-in was refused ({status})", self.name)));
+    }
+    let token: TokenResponse = response.json().await.map_err(|e| self.request_error(e))?;
+    Ok(token.into())
+  }
+
+  /// A current access token, refreshed first if it has expired.
+  pub async fn access_token(&self) -> Result<String> {
+    let Some(token) = self.load_token().await? else {
+      return Err(Error::Validation(format!("Link your {} account first", self.name)));
+    };
+    let expired = token
+      .expires_at
+      .is_some_and(|at| at <= Utc::now() + chrono::Duration::minutes(1));
+    match (&token.refresh_token, expired) {
+      (Some(refresh), true) => {
+        let form = [("grant_type", "refresh_token"), ("refresh_token", refresh.as_str())];
+        let mut fresh = self.request_token(&form).await?;
+        // Refresh responses may leave out the refresh token
+        fresh.refresh_token = fresh.refresh_token.or(token.refresh_token);
+        self.store_token(&fresh).await?;
+        Ok(fresh.access_token)
+      }
+      _ => Ok(token.access_token),
+    }
+  }
+
+  /// Forgets the stored token.
+  pub async fn unlink(&self) -> Result<()> {
+    let account = self.keychain_account.clone();
+    tauri::async_runtime::spawn_blocking(move || match keychain(&account)?.delete_credential() {
+      Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+      Err(e) => Err(Error::from(e)),
+    })
+    .await?
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn builds_the_pkce_challenge() {
+    let verifier = "dBjftJeZ4CVP-mJ92K1kY5ReSteMuLKlyi_aKquL7Kg";
+    assert_eq!(challenge(verifier), "i035ICxvG92SLJJSJpDJ_0bHxqyT03AAPYJDROLyEPM");
+  }
+}
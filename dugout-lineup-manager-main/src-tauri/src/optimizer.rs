@@ -0,0 +1,407 @@
+//! Fair-playing-time lineup generation, run natively so it's instant and
+//! works offline.
+//!
+//! Each inning the nine fielding positions go to the players who have
+//! played the fewest innings so far, with players who sat out the inning
+//! before going first, so nobody sits twice before everyone has sat once.
+//! A player is only put on the field at a position they're eligible for
+//! (their primary and secondary positions unless the coach overrides it);
+//! which players can field together is settled by bipartite matching, so a
+//! thin spot such as catcher doesn't leave the field short. Within those
+//! limits players rotate through the positions they've played least.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::lineups::InningPosition;
+use crate::db::roster::{self, Player};
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::telemetry::{Feature, Telemetry};
+
+/// Positions filled each inning. `DH` only bats, so it isn't one.
+pub const FIELD_POSITIONS: [&str; 9] = ["P", "C", "1B", "2B", "3B", "SS", "LF", "CF", "RF"];
+
+/// Most innings a rotation can plan for, matching saved lineups.
+const MAX_INNINGS: u32 = 12;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateLineupRequest {
+  /// Defaults to the first team.
+  pub team_id: Option<String>,
+  #[serde(default = "default_innings")]
+  pub innings: u32,
+  /// Ids of the players at the game, in batting order. Defaults to every
+  /// active player in roster order.
+  pub attendance: Option<Vec<String>>,
+  /// Positions a player may field, replacing their roster positions.
+  #[serde(default)]
+  pub eligibility: HashMap<String, Vec<String>>,
+}
+
+fn default_innings() -> u32 {
+  6
+}
+
+/// A generated rotation. `battingOrder` and `positions` can be saved as a
+/// lineup as they are.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineupPlan {
+  pub innings: u32,
+  pub batting_order: Vec<String>,
+  pub positions: Vec<InningPosition>,
+  pub playing_time: Vec<PlayingTime>,
+  /// Problems the coach should know about, e.g. an empty position.
+  pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayingTime {
+  pub player_id: String,
+  pub name: String,
+  pub innings_played: u32,
+  pub innings_benched: u32,
+}
+
+/// A player at the game and where they can field.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+  pub id: String,
+  pub name: String,
+  /// Indexes into [`FIELD_POSITIONS`], most preferred first.
+  pub eligible: Vec<usize>,
+}
+
+impl Candidate {
+  pub fn new(id: &str, name: &str, positions: &[&str]) -> Self {
+    Self {
+      id: id.to_string(),
+      name: name.to_string(),
+      eligible: positions
+        .iter()
+        .filter_map(|p| FIELD_POSITIONS.iter().position(|f| f == p))
+        .collect(),
+    }
+  }
+}
+
+/// Assigns positions to `players` (indexes into `candidates`) by augmenting
+/// paths, trying each player's positions in `preference` order. Returns
+/// the player holding each position.
+pub(crate) fn assign(
+  players: &[usize],
+  preference: impl Fn(usize) -> Vec<usize>,
+) -> [Option<usize>; FIELD_POSITIONS.len()] {
+  fn augment(
+    player: usize,
+    preference: &dyn Fn(usize) -> Vec<usize>,
+    seen: &mut [bool; FIELD_POSITIONS.len()],
+    holder: &mut [Option<usize>; FIELD_POSITIONS.len()],
+  ) -> bool {
+    for position in preference(player) {
+      if seen[position] {
+        continue;
+      }
+      seen[position] = true;
+      let free = match holder[position] {
+        None => true,
+        Some(other) => augment(other, preference, seen, holder),
+      };
+      if free {
+        holder[position] = Some(player);
+        return true;
+      }
+    }
+    false
+  }
+
+  let mut holder = [None; FIELD_POSITIONS.len()];
+  for &player in players {
+    augment(player, &preference, &mut [false; FIELD_POSITIONS.len()], &mut holder);
+  }
+  holder
+}
+
+pub(crate) fn filled(holder: &[Option<usize>]) -> usize {
+  holder.iter().filter(|h| h.is_some()).count()
+}
+
+/// Takes players from `order` as long as each one lets another position
+/// be covered with their `allowed` positions, until the field is full.
+pub(crate) fn pick_fielders(
+  order: &[usize],
+  allowed: impl Fn(usize) -> Vec<usize>,
+) -> Vec<usize> {
+  let mut fielders: Vec<usize> = Vec::new();
+  for &player in order {
+    if fielders.len() == FIELD_POSITIONS.len() {
+      break;
+    }
+    let before = filled(&assign(&fielders, &allowed));
+    fielders.push(player);
+    if filled(&assign(&fielders, &allowed)) == before {
+      fielders.pop();
+    }
+  }
+  fielders
+}
+
+/// A rotation so far: who has played and sat, which decides who fields
+/// next, and the positions given out.
+pub(crate) struct Tally<'a> {
+  candidates: &'a [Candidate],
+  pub(crate) played: Vec<u32>,
+  pub(crate) sat_last: Vec<bool>,
+  /// Innings each candidate has played at each position.
+  pub(crate) at_position: Vec<[u32; FIELD_POSITIONS.len()]>,
+  /// Times anyone who can field sat the inning right after sitting one.
+  pub(crate) repeat_benchings: u32,
+  pub(crate) positions: Vec<InningPosition>,
+  short_innings: Vec<u32>,
+}
+
+impl<'a> Tally<'a> {
+  pub(crate) fn new(candidates: &'a [Candidate]) -> Self {
+    Self {
+      candidates,
+      played: vec![0; candidates.len()],
+      sat_last: vec![false; candidates.len()],
+      at_position: vec![[0; FIELD_POSITIONS.len()]; candidates.len()],
+      repeat_benchings: 0,
+      positions: Vec::new(),
+      short_innings: Vec::new(),
+    }
+  }
+
+  /// Records `inning`'s `fielders` and the player holding each position.
+  pub(crate) fn record(
+    &mut self,
+    inning: u32,
+    fielders: &[usize],
+    holder: &[Option<usize>; FIELD_POSITIONS.len()],
+  ) {
+    if filled(holder) < FIELD_POSITIONS.len() {
+      self.short_innings.push(inning);
+    }
+    for (position, player) in holder.iter().enumerate() {
+      if let Some(player) = *player {
+        self.at_position[player][position] += 1;
+        self.positions.push(InningPosition {
+          inning,
+          position: FIELD_POSITIONS[position].to_string(),
+          player_id: self.candidates[player].id.clone(),
+        });
+      }
+    }
+    for (i, sat) in self.sat_last.iter_mut().enumerate() {
+      let sits = !fielders.contains(&i);
+      if sits && *sat && !self.candidates[i].eligible.is_empty() {
+        self.repeat_benchings += 1;
+      }
+      *sat = sits;
+      if !sits {
+        self.played[i] += 1;
+      }
+    }
+  }
+
+  pub(crate) fn playing_time(&self, innings: u32) -> Vec<PlayingTime> {
+    self
+      .candidates
+      .iter()
+      .zip(&self.played)
+      .map(|(c, &played)| PlayingTime {
+        player_id: c.id.clone(),
+        name: c.name.clone(),
+        innings_played: played,
+        innings_benched: innings - played,
+      })
+      .collect()
+  }
+
+  /// Innings with a position left empty, and players with nowhere to field.
+  pub(crate) fn warnings(&self) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if !self.short_innings.is_empty() {
+      let list: Vec<String> = self.short_innings.iter().map(u32::to_string).collect();
+      warnings.push(format!(
+        "Not every position could be filled in inning {}",
+        list.join(", ")
+      ));
+    }
+    for candidate in self.candidates.iter().filter(|c| c.eligible.is_empty()) {
+      warnings.push(format!("{} has no fielding positions and only bats", candidate.name));
+    }
+    warnings
+  }
+}
+
+pub fn generate(candidates: &[Candidate], innings: u32) -> LineupPlan {
+  let mut tally = Tally::new(candidates);
+  for inning in 1..=innings {
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by_key(|&i| (tally.played[i], !tally.sat_last[i], i));
+    let fielders = pick_fielders(&order, |i| candidates[i].eligible.clone());
+
+    // Same fielders, now rotated towards positions they've played least
+    let holder = assign(&fielders, |i| {
+      let mut options = candidates[i].eligible.clone();
+      // Stable, so ties keep the player's own preference order
+      options.sort_by_key(|&p| tally.at_position[i][p]);
+      options
+    });
+    tally.record(inning, &fielders, &holder);
+  }
+
+  let (playing_time, warnings) = (tally.playing_time(innings), tally.warnings());
+  LineupPlan {
+    innings,
+    batting_order: candidates.iter().map(|c| c.id.clone()).collect(),
+    positions: tally.positions,
+    playing_time,
+    warnings,
+  }
+}
+
+/// The players at the game: those in `attendance`, in its order, or every
+/// active player on the `roster`.
+pub(crate) fn attending<'a>(
+  roster: &'a [Player],
+  attendance: Option<&[String]>,
+) -> Result<Vec<&'a Player>> {
+  match attendance {
+    Some(ids) => ids
+      .iter()
+      .map(|id| {
+        roster
+          .iter()
+          .find(|p| &p.id == id)
+          .ok_or_else(|| Error::NotFound(format!("Player {id}")))
+      })
+      .collect(),
+    None => Ok(roster.iter().filter(|p| p.status == "active").collect()),
+  }
+}
+
+pub(crate) fn candidate(player: &Player, eligibility: &HashMap<String, Vec<String>>) -> Candidate {
+  let positions: Vec<&str> = match eligibility.get(&player.id) {
+    Some(positions) => positions.iter().map(String::as_str).collect(),
+    None => std::iter::once(player.primary_position.as_str())
+      .chain(player.secondary_positions.iter().map(String::as_str))
+      .collect(),
+  };
+  Candidate::new(&player.id, &player.name, &positions)
+}
+
+/// Generates a fair rotation for the players at a game from the roster.
+#[tauri::command]
+pub fn generate_lineup(
+  db: State<'_, Database>,
+  telemetry: State<'_, Telemetry>,
+  request: GenerateLineupRequest,
+) -> Result<LineupPlan> {
+  if request.innings == 0 || request.innings > MAX_INNINGS {
+    return Err(Error::Validation(format!(
+      "Innings must be between 1 and {MAX_INNINGS}"
+    )));
+  }
+  let conn = db.conn();
+  let team_id = match request.team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(&conn)?,
+  };
+  let roster = roster::list(&conn, &team_id)?;
+
+  let players = attending(&roster, request.attendance.as_deref())?;
+  if players.is_empty() {
+    return Err(Error::Validation("No players to build a lineup from".into()));
+  }
+
+  let candidates: Vec<Candidate> = players
+    .iter()
+    .map(|p| candidate(p, &request.eligibility))
+    .collect();
+  telemetry.record(Feature::LineupGenerated);
+  Ok(generate(&candidates, request.innings))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ANYWHERE: [&str; 9] = FIELD_POSITIONS;
+
+  fn at(plan: &LineupPlan, inning: u32, position: &str) -> Option<String> {
+    plan
+      .positions
+      .iter()
+      .find(|p| p.inning == inning && p.position == position)
+      .map(|p| p.player_id.clone())
+  }
+
+  #[test]
+  fn spreads_innings_evenly_and_never_benches_twice_in_a_row() {
+    let candidates: Vec<Candidate> = (0..12)
+      .map(|i| Candidate::new(&format!("p{i}"), &format!("Player {i}"), &ANYWHERE))
+      .collect();
+    let plan = generate(&candidates, 6);
+
+    assert_eq!(plan.positions.len(), 6 * 9);
+    assert!(plan.warnings.is_empty());
+    let played: Vec<u32> = plan.playing_time.iter().map(|p| p.innings_played).collect();
+    assert!(played.iter().max().unwrap() - played.iter().min().unwrap() <= 1);
+
+    for inning in 2..=6 {
+      let fielding = |inning| {
+        plan
+          .positions
+          .iter()
+          .filter(move |p| p.inning == inning)
+          .map(|p| p.player_id.clone())
+          .collect::<Vec<_>>()
+      };
+      let (before, now) = (fielding(inning - 1), fielding(inning));
+      for candidate in &candidates {
+        assert!(
+          before.contains(&candidate.id) || now.contains(&candidate.id),
+          "{} benched in innings {} and {}",
+          candidate.name,
+          inning - 1,
+          inning
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn keeps_the_only_catcher_behind_the_plate() {
+    let fielder: Vec<&str> = FIELD_POSITIONS.into_iter().filter(|&p| p != "C").collect();
+    let mut candidates = vec![Candidate::new("catcher", "Cat", &["C", "1B"])];
+    candidates.extend((0..9).map(|i| Candidate::new(&format!("p{i}"), "Fielder", &fielder)));
+    let plan = generate(&candidates, 4);
+
+    for inning in 1..=4 {
+      assert_eq!(at(&plan, inning, "C").as_deref(), Some("catcher"));
+    }
+    let catcher = &plan.playing_time[0];
+    assert_eq!(catcher.innings_benched, 0);
+  }
+
+  #[test]
+  fn warns_when_the_field_cannot_be_filled() {
+    let candidates: Vec<Candidate> = (0..8)
+      .map(|i| Candidate::new(&format!("p{i}"), "Player", &ANYWHERE))
+      .chain(std::iter::once(Candidate::new("dh", "Slugger", &["DH"])))
+      .collect();
+    let plan = generate(&candidates, 2);
+
+    assert_eq!(plan.positions.len(), 2 * 8);
+    assert_eq!(plan.warnings.len(), 2);
+    assert_eq!(plan.playing_time[8].innings_played, 0);
+  }
+}
@@ -0,0 +1,303 @@
+//! Local web server for following the game from outside the app: parents'
+//! phones on the same Wi-Fi, or an OBS browser source on a streaming PC.
+//!
+//! It serves a read-only scoreboard page at `/`, the current [`Scoreboard`]
+//! as JSON at `/scoreboard`, and a WebSocket at `/ws` that sends the
+//! scoreboard again after every `update_scoreboard`. Add `?transparent` to
+//! the page URL for an overlay without a background.
+
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::sync::Mutex;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::{Html, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+
+use crate::error::Result;
+use crate::scoreboard::{Scoreboard, ScoreboardState};
+
+/// Port tried first, so the URL (and a printed QR code) stays the same from
+/// game to game. Any free port is used if it's taken.
+const DEFAULT_PORT: u16 = 4790;
+
+const PAGE: &str = include_str!("scoreboard.html");
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayStatus {
+  pub running: bool,
+  /// Page URL on this machine's LAN address.
+  pub url: Option<String>,
+  /// QR code of `url` as an SVG document.
+  pub qr_svg: Option<String>,
+}
+
+struct Running {
+  status: OverlayStatus,
+  /// Set to `true` to stop the server and close every feed.
+  shutdown: watch::Sender<bool>,
+}
+
+#[derive(Default)]
+pub struct OverlayState(Mutex<Option<Running>>);
+
+impl OverlayState {
+  fn status(&self) -> OverlayStatus {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .as_ref()
+      .map(|running| running.status.clone())
+      .unwrap_or_default()
+  }
+}
+
+/// What each request handler needs.
+#[derive(Clone)]
+struct Shared {
+  scoreboard: watch::Receiver<Scoreboard>,
+  shutdown: watch::Receiver<bool>,
+}
+
+/// The address other devices on the LAN can reach us on. Connecting a UDP
+/// socket sends nothing; it only picks the interface with a route out.
+fn lan_ip() -> IpAddr {
+  UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+    .and_then(|socket| {
+      socket.connect((Ipv4Addr::new(192, 0, 2, 1), 80))?;
+      socket.local_addr()
+    })
+    .map(|addr| addr.ip())
+    .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+async fn bind(port: Option<u16>) -> Result<TcpListener> {
+  if let Some(port) = port {
+    return Ok(TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).await?);
+  }
+  match TcpListener::bind((Ipv4Addr::UNSPECIFIED, DEFAULT_PORT)).await {
+    Ok(listener) => Ok(listener),
+    Err(e) => {
+      log::info!("Overlay port {} unavailable ({}), using any free port", DEFAULT_PORT, e);
+      Ok(TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0)).await?)
+    }
+  }
+}
+
+async fn page() -> Html<&'static str> {
+  Html(PAGE)
+}
+
+async fn snapshot(State(shared): State<Shared>) -> Json<Scoreboard> {
+  Json(shared.scoreboard.borrow().clone())
+}
+
+async fn feed(upgrade: WebSocketUpgrade, State(shared): State<Shared>) -> Response {
+  upgrade.on_upgrade(move |socket| stream_scoreboard(socket, shared))
+}
+
+/// Sends the scoreboard now and after every update until the viewer leaves
+/// or the server stops. Anything the viewer sends is ignored.
+async fn stream_scoreboard(mut socket: WebSocket, mut shared: Shared) {
+  loop {
+    let text = serde_json::to_string(&*shared.scoreboard.borrow_and_update())
+      .expect("scoreboard serializes");
+    if socket.send(Message::Text(text.into())).await.is_err() {
+      return;
+    }
+
+    loop {
+      tokio::select! {
+        changed = shared.scoreboard.changed() => {
+          if changed.is_err() {
+            return;
+          }
+          break;
+        }
+        // Only ever changes to `true`
+        _ = shared.shutdown.changed() => {
+          let _ = socket.send(Message::Close(None)).await;
+          return;
+        }
+        incoming = socket.recv() => match incoming {
+          Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+          Some(Ok(_)) => {}
+        },
+      }
+    }
+  }
+}
+
+/// The page, the JSON snapshot and the feed, as described above.
+fn router(shared: Shared) -> Router {
+  Router::new()
+    .route("/", get(page))
+    .route("/scoreboard", get(snapshot))
+    .route("/ws", get(feed))
+    .with_state(shared)
+}
+
+#[tauri::command]
+pub fn get_overlay_status(state: tauri::State<'_, OverlayState>) -> OverlayStatus {
+  state.status()
+}
+
+/// Starts the overlay server on `port` (or the usual port) and returns its
+/// URL and a QR code to show. A no-op if it's already running.
+#[tauri::command]
+pub async fn start_overlay_server(
+  state: tauri::State<'_, OverlayState>,
+  scoreboard: tauri::State<'_, ScoreboardState>,
+  port: Option<u16>,
+) -> Result<OverlayStatus> {
+  if state.0.lock().unwrap().is_some() {
+    return Ok(state.status());
+  }
+
+  let listener = bind(port).await?;
+  let url = format!("http://{}:{}/", lan_ip(), listener.local_addr()?.port());
+  let status = OverlayStatus {
+    running: true,
+    qr_svg: Some(crate::qr::svg(&url)?),
+    url: Some(url),
+  };
+
+  let (shutdown, stopped) = watch::channel(false);
+  let router = router(Shared {
+    scoreboard: scoreboard.subscribe(),
+    shutdown: stopped.clone(),
+  });
+
+  let mut signal = stopped;
+  tauri::async_runtime::spawn(async move {
+    let served = axum::serve(listener, router)
+      .with_graceful_shutdown(async move {
+        let _ = signal.wait_for(|stopped| *stopped).await;
+      })
+      .await;
+    if let Err(e) = served {
+      log::warn!("Overlay server stopped: {}", e);
+    }
+  });
+
+  let mut running = state.0.lock().unwrap();
+  // Another call may have won the race while we were binding
+  if running.is_none() {
+    log::info!("Overlay server running at {}", status.url.as_deref().unwrap_or_default());
+    *running = Some(Running {
+      status: status.clone(),
+      shutdown,
+    });
+    return Ok(status);
+  }
+  shutdown.send_replace(true);
+  Ok(running.as_ref().map(|running| running.status.clone()).unwrap_or_default())
+}
+
+/// Stops the overlay server and disconnects every viewer.
+#[tauri::command]
+pub fn stop_overlay_server(state: tauri::State<'_, OverlayState>) -> OverlayStatus {
+  if let Some(running) = state.0.lock().unwrap().take() {
+    running.shutdown.send_replace(true);
+    log::info!("Overlay server stopped");
+  }
+  OverlayStatus::default()
+}
+
+#[cfg(test)]
+mod tests {
+  use futures_util::{Stream, StreamExt};
+  use tokio_tungstenite::tungstenite::{self, Message as Frame};
+
+  use super::*;
+
+  /// Serves the overlay on a free loopback port. Returns its address and
+  /// the senders for the scoreboard it shows and for stopping it.
+  async fn serve() -> (String, watch::Sender<Scoreboard>, watch::Sender<bool>) {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let (scoreboard, watching) = watch::channel(Scoreboard::default());
+    let (shutdown, stopped) = watch::channel(false);
+    let router = router(Shared {
+      scoreboard: watching,
+      shutdown: stopped,
+    });
+    tauri::async_runtime::spawn(async move { axum::serve(listener, router).await });
+    (addr, scoreboard, shutdown)
+  }
+
+  async fn get(addr: &str, path: &str) -> reqwest::Response {
+    let client = reqwest::Client::builder().no_proxy().build().unwrap();
+    client.get(format!("http://{addr}{path}")).send().await.unwrap()
+  }
+
+  /// The next scoreboard on the feed, or `None` once it closes.
+  async fn next<S>(ws: &mut S) -> Option<Scoreboard>
+  where
+    S: Stream<Item = tungstenite::Result<Frame>> + Unpin,
+  {
+    match ws.next().await {
+      Some(Ok(Frame::Text(text))) => Some(serde_json::from_str(&text).unwrap()),
+      Some(Ok(Frame::Close(_))) | None => None,
+      other => panic!("unexpected frame {other:?}"),
+    }
+  }
+
+  fn tigers() -> Scoreboard {
+    Scoreboard {
+      team_name: "Tigers".into(),
+      opponent: "Cubs".into(),
+      score_us: 3,
+      score_them: 1,
+      inning: 4,
+      top_of_inning: true,
+      batting_order: vec!["Jake Miller".into(), "Ava Chen".into()],
+      current_batter: Some(1),
+      ..Scoreboard::default()
+    }
+  }
+
+  #[test]
+  fn serves_the_page_and_the_current_scoreboard() {
+    tauri::async_runtime::block_on(async {
+      let (addr, scoreboard, _shutdown) = serve().await;
+      let page = get(&addr, "/").await;
+      assert!(page.status().is_success());
+      assert_eq!(page.text().await.unwrap(), PAGE);
+
+      scoreboard.send_replace(tigers());
+      let json: serde_json::Value = get(&addr, "/scoreboard").await.json().await.unwrap();
+      assert_eq!(json["teamName"], "Tigers");
+      assert_eq!(json["scoreUs"], 3);
+      assert_eq!(json["scoreThem"], 1);
+      assert_eq!(json["topOfInning"], true);
+      assert_eq!(json["battingOrder"][1], "Ava Chen");
+      assert_eq!(json["currentBatter"], 1);
+
+      let missing = get(&addr, "/roster").await;
+      assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+    });
+  }
+
+  #[test]
+  fn sends_every_update_until_stopped() {
+    tauri::async_runtime::block_on(async {
+      let (addr, scoreboard, shutdown) = serve().await;
+      let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws")).await.unwrap();
+      assert_eq!(next(&mut ws).await.unwrap().team_name, "");
+
+      scoreboard.send_replace(tigers());
+      let update = next(&mut ws).await.unwrap();
+      assert_eq!((update.team_name.as_str(), update.score_us), ("Tigers", 3));
+
+      shutdown.send_replace(true);
+      assert!(next(&mut ws).await.is_none());
+    });
+  }
+}
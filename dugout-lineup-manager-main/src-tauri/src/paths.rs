@@ -0,0 +1,163 @@
+//! Locations of the app's on-disk state, resolved once in `setup()`.
+
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use serde::Serialize;
+
+use crate::profiles::{Profiles, DEFAULT_PROFILE};
+use crate::settings::AppSettings;
+
+/// The directories that belong to one profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileDirs {
+  /// Data shared with the backend sidecar (`DUGOUT_DATA_DIR`).
+  pub data: PathBuf,
+  /// Backup archives and pre-migration database copies.
+  pub backups: PathBuf,
+}
+
+impl ProfileDirs {
+  pub fn ensure(&self) -> std::io::Result<()> {
+    std::fs::create_dir_all(&self.data)?;
+    std::fs::create_dir_all(&self.backups)
+  }
+}
+
+#[derive(Debug)]
+pub struct AppPaths {
+  /// The default profile's directories, where data lived before there
+  /// were profiles. Settings can move them elsewhere.
+  default_profile: RwLock<ProfileDirs>,
+  /// The profile list (`profiles.json`) and every other profile's
+  /// directories.
+  pub profiles: PathBuf,
+  /// Shell preferences (`settings.json`).
+  pub settings: PathBuf,
+  /// Where each window was left (`window-state.json`).
+  pub window_state: PathBuf,
+  /// Copies of files dropped on the window, waiting to be imported.
+  pub staging: PathBuf,
+  /// Installed update bundles, kept so an update can be rolled back.
+  pub updates: PathBuf,
+  /// Crash reports written by the panic hook.
+  pub crashes: PathBuf,
+  /// Present while the app runs (`running`), so finding it at launch means
+  /// the last run didn't exit cleanly.
+  pub running: PathBuf,
+  /// Usage counts waiting to be uploaded (`telemetry.json`), if opted in.
+  pub telemetry: PathBuf,
+  /// Local AI models (`DUGOUT_MODELS_DIR`). Kept out of `data` so
+  /// gigabytes of weights don't end up in every backup.
+  pub models: PathBuf,
+  /// The active profile's directories.
+  active: RwLock<ProfileDirs>,
+}
+
+impl AppPaths {
+  /// Lays the directories out under the per-user `app_data_dir`, with the
+  /// default profile active.
+  pub fn new(app_data: PathBuf) -> Self {
+    let default_profile = ProfileDirs {
+      data: app_data.join("data"),
+      backups: app_data.join("backups"),
+    };
+    Self {
+      active: RwLock::new(default_profile.clone()),
+      default_profile: RwLock::new(default_profile),
+      profiles: app_data.join("profiles"),
+      settings: app_data.join("settings.json"),
+      window_state: app_data.join("window-state.json"),
+      staging: app_data.join("staging"),
+      updates: app_data.join("updates"),
+      crashes: app_data.join("crashes"),
+      running: app_data.join("running"),
+      telemetry: app_data.join("telemetry.json"),
+      models: app_data.join("models"),
+    }
+  }
+
+  /// The active profile's data directory.
+  pub fn data(&self) -> PathBuf {
+    self.active.read().unwrap().data.clone()
+  }
+
+  /// The active profile's backups directory.
+  pub fn backups(&self) -> PathBuf {
+    self.active.read().unwrap().backups.clone()
+  }
+
+  pub fn default_profile(&self) -> ProfileDirs {
+    self.default_profile.read().unwrap().clone()
+  }
+
+  /// Moves the default profile to `dirs`, taking the active directories
+  /// along if it is the profile in use.
+  pub fn set_default_profile(&self, dirs: ProfileDirs) {
+    let mut default_profile = self.default_profile.write().unwrap();
+    let mut active = self.active.write().unwrap();
+    if *active == *default_profile {
+      *active = dirs.clone();
+    }
+    *default_profile = dirs;
+  }
+
+  /// Where the profile `id` keeps its data.
+  pub fn profile_dirs(&self, id: &str) -> ProfileDirs {
+    if id == DEFAULT_PROFILE {
+      return self.default_profile();
+    }
+    let dir = self.profiles.join(id);
+    ProfileDirs {
+      data: dir.join("data"),
+      backups: dir.join("backups"),
+    }
+  }
+
+  /// Points [`data`](Self::data) and [`backups`](Self::backups) at another
+  /// profile's directories.
+  pub fn activate(&self, dirs: ProfileDirs) {
+    *self.active.write().unwrap() = dirs;
+  }
+
+  /// Moves the default profile to where `settings` keep it and activates
+  /// the profile in use, or the one `--profile` asks for. Returns the
+  /// profile list.
+  pub fn select_profile(&self, settings: &AppSettings) -> Profiles {
+    // Mobile apps can only write inside their sandbox
+    let moved = settings.data_dir.is_some() || settings.backups_dir.is_some();
+    if moved && cfg!(desktop) {
+      let mut dirs = self.default_profile();
+      if let Some(dir) = &settings.data_dir {
+        dirs.data = dir.clone();
+      }
+      if let Some(dir) = &settings.backups_dir {
+        dirs.backups = dir.clone();
+      }
+      self.set_default_profile(dirs);
+    } else if moved {
+      log::warn!("Ignoring data and backups directories outside the sandbox");
+    }
+    let profiles = Profiles::load(self);
+    if let Some(wanted) = &crate::cli::args().profile {
+      match profiles.use_for_this_run(wanted) {
+        Ok(profile) => log::info!("Using profile {} for this run", profile.name),
+        Err(e) => log::warn!("{}; using the last profile instead", e),
+      }
+    }
+    self.activate(self.profile_dirs(&profiles.active()));
+    profiles
+  }
+
+  /// Creates every directory that must exist before the app starts.
+  /// Staged drops only live for one session, so leftovers are cleared.
+  pub fn ensure(&self) -> std::io::Result<()> {
+    self.active.read().unwrap().ensure()?;
+    std::fs::create_dir_all(&self.models)?;
+    if self.staging.exists() {
+      std::fs::remove_dir_all(&self.staging)?;
+    }
+    std::fs::create_dir_all(&self.staging)
+  }
+}
@@ -0,0 +1,444 @@
+//! Pitch counts and the rest they require.
+//!
+//! Leagues cap the pitches a player throws in a day and require days of
+//! rest that grow with the count, e.g. Little League's 66+ pitches means
+//! four days off. The table in use is [`PitchRules`] in settings. Counts
+//! from the same day are added together before it is applied, so a
+//! doubleheader counts as one outing.
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::alerts::{self, AlertSource, ThresholdAlert};
+use crate::db::pitch_counts::{self, PitchCount};
+use crate::db::roster::{self, Player};
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::settings::AppSettings;
+
+/// Emitted with a [`PitchThreshold`] when a recorded count moves a pitcher
+/// into a longer rest requirement, up to the daily limit, or past one of the
+/// team's alert counts.
+pub const PITCH_THRESHOLD_EVENT: &str = "pitch-count-threshold";
+
+/// A league's pitch limits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PitchRules {
+  pub name: String,
+  /// Most pitches allowed in one day.
+  pub daily_max: u32,
+  /// Rest required from each count upwards, in ascending order.
+  pub rest: Vec<RestTier>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestTier {
+  pub min_pitches: u32,
+  /// Calendar days off before pitching again.
+  pub rest_days: u32,
+}
+
+const fn tier(min_pitches: u32, rest_days: u32) -> RestTier {
+  RestTier {
+    min_pitches,
+    rest_days,
+  }
+}
+
+/// Little League's rest table for pitchers 14 and under.
+const LITTLE_LEAGUE_REST: [RestTier; 4] = [tier(21, 1), tier(36, 2), tier(51, 3), tier(66, 4)];
+
+/// PONY's table for 13-14 year olds, which allows more pitches per tier.
+const PONY_REST: [RestTier; 4] = [tier(31, 1), tier(46, 2), tier(61, 3), tier(76, 4)];
+
+impl Default for PitchRules {
+  fn default() -> Self {
+    Self::preset("Little League (11-12)", 85, &LITTLE_LEAGUE_REST)
+  }
+}
+
+impl PitchRules {
+  fn preset(name: &str, daily_max: u32, rest: &[RestTier]) -> Self {
+    Self {
+      name: name.to_string(),
+      daily_max,
+      rest: rest.to_vec(),
+    }
+  }
+
+  /// Starting points for common leagues. Leagues amend their tables, so
+  /// coaches should check them against the current rulebook.
+  pub fn presets() -> Vec<Self> {
+    vec![
+      Self::preset("Little League (9-10)", 75, &LITTLE_LEAGUE_REST),
+      Self::default(),
+      Self::preset("Little League (13-14)", 95, &LITTLE_LEAGUE_REST),
+      Self::preset("PONY (11-12)", 85, &PONY_REST),
+      Self::preset("PONY (13-14)", 95, &PONY_REST),
+    ]
+  }
+
+  /// Days of rest required after throwing `pitches` in a day.
+  pub fn rest_days(&self, pitches: u32) -> u32 {
+    self
+      .rest
+      .iter()
+      .rev()
+      .find(|tier| pitches >= tier.min_pitches)
+      .map_or(0, |tier| tier.rest_days)
+  }
+
+  /// The longest rest any count can require.
+  fn max_rest(&self) -> u32 {
+    self.rest.iter().map(|tier| tier.rest_days).max().unwrap_or(0)
+  }
+}
+
+/// Rejects tables the availability check can't apply.
+pub fn validate(rules: &PitchRules) -> Result<()> {
+  if rules.daily_max == 0 {
+    return Err(Error::Validation("The daily pitch limit must be at least 1".into()));
+  }
+  let ascending = rules
+    .rest
+    .windows(2)
+    .all(|pair| pair[0].min_pitches < pair[1].min_pitches);
+  if !ascending || rules.rest.first().is_some_and(|tier| tier.min_pitches == 0) {
+    return Err(Error::Validation(
+      "Rest tiers must start above 0 pitches and go up in order".into(),
+    ));
+  }
+  if rules.max_rest() > 30 {
+    return Err(Error::Validation("Rest can't be longer than 30 days".into()));
+  }
+  Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PitcherAvailability {
+  pub player_id: String,
+  pub name: String,
+  /// The most recent day they pitched, if within the rest window.
+  pub last_pitched: Option<NaiveDate>,
+  pub last_pitches: u32,
+  /// Rest required by that outing.
+  pub rest_days: u32,
+  /// Pitches already thrown on the day being checked.
+  pub pitches_today: u32,
+  /// Pitches left before the daily limit on that day.
+  pub remaining_today: u32,
+  /// First day they may pitch.
+  pub available_on: NaiveDate,
+  pub available: bool,
+}
+
+/// A pitcher crossing into a longer rest requirement, reaching the daily
+/// limit or passing an alert count.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PitchThreshold {
+  pub player_id: String,
+  pub name: String,
+  pub game_id: String,
+  /// Pitches thrown that day.
+  pub pitches: u32,
+  pub rest_days: u32,
+  pub limit_reached: bool,
+  /// Ready to show, e.g. "Jake Miller needs 2 days rest".
+  pub message: String,
+}
+
+fn add_days(date: NaiveDate, days: u32) -> NaiveDate {
+  date.checked_add_days(Days::new(days.into())).unwrap_or(date)
+}
+
+/// Where `player` stands on `today`, given their counts from the rest
+/// window before it.
+pub fn availability(
+  rules: &PitchRules,
+  player: &Player,
+  counts: &[PitchCount],
+  today: NaiveDate,
+) -> PitcherAvailability {
+  let mut by_day: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+  for count in counts.iter().filter(|c| c.player_id == player.id) {
+    if let Ok(date) = pitch_counts::parse_date(&count.date) {
+      *by_day.entry(date).or_default() += count.pitches;
+    }
+  }
+
+  let pitches_today = by_day.get(&today).copied().unwrap_or(0);
+  let mut available_on = today;
+  for (&date, &pitches) in by_day.range(..today) {
+    let rest = rules.rest_days(pitches);
+    if rest > 0 {
+      available_on = available_on.max(add_days(date, rest + 1));
+    }
+  }
+  let remaining_today = rules.daily_max.saturating_sub(pitches_today);
+  if remaining_today == 0 {
+    available_on = available_on.max(add_days(today, rules.rest_days(pitches_today).max(1) + 1));
+  }
+
+  let last = by_day.range(..=today).next_back();
+  PitcherAvailability {
+    player_id: player.id.clone(),
+    name: player.name.clone(),
+    last_pitched: last.map(|(&date, _)| date),
+    last_pitches: last.map_or(0, |(_, &pitches)| pitches),
+    rest_days: last.map_or(0, |(_, &pitches)| rules.rest_days(pitches)),
+    pitches_today,
+    remaining_today,
+    available: available_on <= today,
+    available_on,
+  }
+}
+
+/// The threshold crossed by going from `before` to `after` pitches in a
+/// day, if any. `alert_at` are the team's extra alert counts; the limit and
+/// rest rules take precedence when several are crossed at once.
+pub fn crossed(
+  rules: &PitchRules,
+  alert_at: &[u32],
+  player: &Player,
+  game_id: &str,
+  before: u32,
+  after: u32,
+) -> Option<PitchThreshold> {
+  let rest_days = rules.rest_days(after);
+  let limit_reached = after >= rules.daily_max && before < rules.daily_max;
+  let more_rest = rest_days > rules.rest_days(before);
+  let alert_count = alert_at
+    .iter()
+    .copied()
+    .filter(|&count| before < count && after >= count)
+    .max();
+  let message = if limit_reached {
+    format!("{} has reached the limit of {} pitches", player.name, rules.daily_max)
+  } else if more_rest && rest_days == 1 {
+    format!("{} needs 1 day rest", player.name)
+  } else if more_rest {
+    format!("{} needs {rest_days} days rest", player.name)
+  } else if let Some(count) = alert_count {
+    format!("{} is at {count} pitches", player.name)
+  } else {
+    return None;
+  };
+  Some(PitchThreshold {
+    player_id: player.id.clone(),
+    name: player.name.clone(),
+    game_id: game_id.to_string(),
+    pitches: after,
+    rest_days,
+    limit_reached,
+    message,
+  })
+}
+
+/// The player's total on `date` before and after `game_id` is set to
+/// `pitches`. The game's earlier count only counts towards `before` if it
+/// was on `date` too, since recording it again can move it to another day.
+fn day_totals(
+  counts: &[PitchCount],
+  player_id: &str,
+  game_id: &str,
+  date: &str,
+  pitches: u32,
+) -> (u32, u32) {
+  let (mut previous, mut others) = (0, 0);
+  for count in counts.iter().filter(|c| c.player_id == player_id && c.date == date) {
+    if count.game_id == game_id {
+      previous += count.pitches;
+    } else {
+      others += count.pitches;
+    }
+  }
+  (others + previous, others + pitches)
+}
+
+fn today() -> NaiveDate {
+  chrono::Local::now().date_naive()
+}
+
+/// Counts from far enough back to still matter on `date`.
+fn recent_counts(
+  conn: &rusqlite::Connection,
+  rules: &PitchRules,
+  team_id: &str,
+  date: NaiveDate,
+) -> Result<Vec<PitchCount>> {
+  let from = date
+    .checked_sub_days(Days::new(u64::from(rules.max_rest()) + 1))
+    .unwrap_or(date);
+  pitch_counts::since(conn, team_id, &from.format("%Y-%m-%d").to_string())
+}
+
+#[tauri::command]
+pub fn list_pitch_rule_presets() -> Vec<PitchRules> {
+  PitchRules::presets()
+}
+
+/// Sets how many pitches a player threw in a game, replacing the earlier
+/// count so the frontend can send the running total as the game goes.
+/// Emits [`PITCH_THRESHOLD_EVENT`] and raises an alert when the new count
+/// crosses a threshold.
+#[tauri::command]
+pub fn record_pitches(
+  app: AppHandle,
+  db: State<'_, Database>,
+  settings: State<'_, RwLock<AppSettings>>,
+  player_id: String,
+  game_id: String,
+  date: String,
+  pitches: u32,
+) -> Result<PitcherAvailability> {
+  let (rules, alert_settings) = {
+    let settings = settings.read().unwrap();
+    (settings.pitching.clone(), settings.alerts.clone())
+  };
+  let day = pitch_counts::parse_date(&date)?;
+  let conn = db.conn();
+  let player = roster::get(&conn, &player_id)?;
+  let alert_at = &alert_settings.for_team(Some(&player.team_id)).pitch_counts;
+
+  let earlier = recent_counts(&conn, &rules, &player.team_id, day)?;
+  let (before, after) = day_totals(&earlier, &player_id, &game_id, &date, pitches);
+  pitch_counts::record(&conn, &player_id, &game_id, &date, pitches)?;
+
+  if let Some(threshold) = crossed(&rules, alert_at, &player, &game_id, before, after) {
+    let _ = app.emit(PITCH_THRESHOLD_EVENT, &threshold);
+    alerts::raise(
+      &app,
+      ThresholdAlert {
+        source: AlertSource::PitchCount,
+        team_id: Some(player.team_id.clone()),
+        message: threshold.message,
+      },
+    );
+  }
+  let counts = recent_counts(&conn, &rules, &player.team_id, day)?;
+  Ok(availability(&rules, &player, &counts, day))
+}
+
+/// Who can pitch on `date` (default today), for every active player on the
+/// team.
+#[tauri::command]
+pub fn get_pitcher_availability(
+  db: State<'_, Database>,
+  settings: State<'_, RwLock<AppSettings>>,
+  team_id: Option<String>,
+  date: Option<String>,
+) -> Result<Vec<PitcherAvailability>> {
+  let rules = settings.read().unwrap().pitching.clone();
+  let day = match date {
+    Some(date) => pitch_counts::parse_date(&date)?,
+    None => today(),
+  };
+  let conn = db.conn();
+  let team_id = match team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(&conn)?,
+  };
+  let counts = recent_counts(&conn, &rules, &team_id, day)?;
+  Ok(
+    roster::list(&conn, &team_id)?
+      .iter()
+      .filter(|p| p.status == "active")
+      .map(|p| availability(&rules, p, &counts, day))
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn player() -> Player {
+    Player {
+      id: "jake".into(),
+      team_id: "t1".into(),
+      name: "Jake".into(),
+      number: Some(12),
+      primary_position: "P".into(),
+      secondary_positions: Vec::new(),
+      bats: "R".into(),
+      throws: "R".into(),
+      status: "active".into(),
+      notes: String::new(),
+      created_at: String::new(),
+      updated_at: String::new(),
+    }
+  }
+
+  fn count(game_id: &str, date: &str, pitches: u32) -> PitchCount {
+    PitchCount {
+      player_id: "jake".into(),
+      game_id: game_id.into(),
+      date: date.into(),
+      pitches,
+    }
+  }
+
+  fn day(date: &str) -> NaiveDate {
+    pitch_counts::parse_date(date).unwrap()
+  }
+
+  #[test]
+  fn rests_for_the_days_the_table_requires() {
+    let rules = PitchRules::default();
+    assert_eq!(rules.rest_days(20), 0);
+    assert_eq!(rules.rest_days(21), 1);
+    assert_eq!(rules.rest_days(66), 4);
+
+    // A doubleheader adds up to 56 pitches: three days off, back on Friday
+    let counts = [count("g1", "2026-04-06", 30), count("g2", "2026-04-06", 26)];
+    let thursday = availability(&rules, &player(), &counts, day("2026-04-09"));
+    assert!(!thursday.available);
+    assert_eq!(thursday.rest_days, 3);
+    assert_eq!(thursday.available_on, day("2026-04-10"));
+    assert!(availability(&rules, &player(), &counts, day("2026-04-10")).available);
+
+    let game_day = availability(&rules, &player(), &counts, day("2026-04-06"));
+    assert_eq!(game_day.remaining_today, 85 - 56);
+    assert!(game_day.available);
+  }
+
+  #[test]
+  fn reports_only_newly_crossed_thresholds() {
+    let rules = PitchRules::default();
+    let jake = player();
+    assert_eq!(crossed(&rules, &[], &jake, "g1", 10, 20), None);
+    let rest = crossed(&rules, &[], &jake, "g1", 30, 40).unwrap();
+    assert_eq!(rest.message, "Jake needs 2 days rest");
+    assert_eq!(crossed(&rules, &[], &jake, "g1", 40, 45), None);
+    assert!(crossed(&rules, &[], &jake, "g1", 80, 85).unwrap().limit_reached);
+
+    // The team's own counts alert too, but rest rules speak first
+    let alert = |before, after| crossed(&rules, &[45, 75], &jake, "g1", before, after).unwrap();
+    assert_eq!(alert(40, 45).message, "Jake is at 45 pitches");
+    assert_eq!(alert(60, 80).message, "Jake needs 4 days rest");
+    assert_eq!(alert(70, 80).message, "Jake is at 75 pitches");
+
+    assert!(validate(&rules).is_ok());
+    let mut unordered = rules.clone();
+    unordered.rest.swap(0, 1);
+    assert!(validate(&unordered).is_err());
+  }
+
+  #[test]
+  fn re_recording_a_game_under_another_date_starts_that_day_afresh() {
+    let counts = [count("g1", "2026-04-06", 50), count("g2", "2026-04-07", 10)];
+    // The running total within the same game only adds the difference
+    assert_eq!(day_totals(&counts, "jake", "g1", "2026-04-06", 55), (50, 55));
+    // Corrected to the next day, where only the other game counts yet
+    assert_eq!(day_totals(&counts, "jake", "g1", "2026-04-07", 50), (10, 60));
+    assert_eq!(day_totals(&counts, "jake", "g1", "2026-04-08", 50), (0, 50));
+  }
+}
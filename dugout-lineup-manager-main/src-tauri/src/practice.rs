@@ -0,0 +1,382 @@
+//! Practice plans written by the AI from what the team has been getting
+//! wrong lately and the drills the coach keeps.
+//!
+//! The prompt is filled in from a template here, using the box scores of
+//! the team's last few games and its drill library, so nothing but the
+//! prompt leaves the device. It's streamed through the sidecar like any
+//! chat (see [`crate::ai_stream`]) so the plan shows up as it's written,
+//! and saved as a [`PracticePlan`] once it's finished. A cancelled plan
+//! isn't kept.
+
+use serde::Deserialize;
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+
+use crate::ai_stream;
+use crate::db::ai_queue::QueuedKind;
+use crate::db::drills::{self, Drill};
+use crate::db::practice_plans::{self, NewPracticePlan, PracticePlan};
+use crate::db::{roster, Database};
+use crate::error::{Error, Result};
+use crate::proxy::BackendRequest;
+use crate::stats::{self, StatLines};
+
+/// Games of box scores the weaknesses come from.
+const RECENT_GAMES: u32 = 5;
+
+/// Most drills offered in one prompt.
+const MAX_DRILLS: usize = 25;
+
+/// Longest wait for each piece of the plan; a local model can be slow to
+/// start.
+const CHUNK_TIMEOUT_MS: u64 = 120_000;
+
+/// Striking out in more of the plate appearances than this is a weakness.
+const STRIKEOUT_RATE: f64 = 0.25;
+const TEAM_AVERAGE: f64 = 0.250;
+const FIELDING_PERCENTAGE: f64 = 0.900;
+/// Fewer chances than this say little about the fielding.
+const MIN_CHANCES: u32 = 10;
+const WALKS_PER_INNING: f64 = 0.6;
+const CAUGHT_STEALING_RATE: f64 = 0.34;
+const MIN_STEAL_ATTEMPTS: u32 = 3;
+
+const SYSTEM_PROMPT: &str = "You are an experienced youth baseball coach. You write practice \
+   plans a volunteer coach can run from a clipboard: short instructions, no jargon, and \
+   nothing that needs equipment a youth team wouldn't have.";
+
+const TEMPLATE: &str = "Plan a {minutes}-minute practice for {team}.
+
+{recent}
+
+Drills the coach already runs:
+{drills}
+
+{notes}Lay the practice out as numbered blocks, each with its length in minutes, adding up to \
+{minutes} minutes. Use the drills above by name where they fit and spend the most time on the \
+weaknesses. Start with a warm-up and finish with something fun.";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PracticePlanRequest {
+  /// Defaults to the first team.
+  pub team_id: Option<String>,
+  #[serde(default = "default_minutes")]
+  pub minutes: u32,
+  /// Areas to work on besides the ones the stats point to.
+  #[serde(default)]
+  pub focus: Vec<String>,
+  /// Anything else the AI should know, e.g. "only half the field".
+  pub notes: Option<String>,
+  /// Defaults to the team and today's date.
+  pub title: Option<String>,
+  /// Chat model, as for `/lyra/chat/stream`.
+  pub model: String,
+}
+
+fn default_minutes() -> u32 {
+  90
+}
+
+/// Something the box scores say the team should work on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weakness {
+  /// One of [`drills::FOCUSES`].
+  pub focus: &'static str,
+  pub summary: String,
+}
+
+/// The prompt and what went into it, ready to send.
+#[derive(Debug, Clone)]
+pub struct Draft {
+  pub team_id: String,
+  pub title: String,
+  pub focus: Vec<String>,
+  pub drill_ids: Vec<String>,
+  pub prompt: String,
+}
+
+fn percent(rate: f64) -> String {
+  format!("{:.0}%", rate * 100.0)
+}
+
+/// Three places without the leading zero, the way averages are read out.
+fn average(rate: f64) -> String {
+  format!("{rate:.3}").trim_start_matches('0').to_string()
+}
+
+/// Weaknesses in the team's summed stat `lines`.
+pub fn weaknesses(lines: &StatLines) -> Vec<Weakness> {
+  let mut found = Vec::new();
+  let (b, f, p) = (&lines.batting, &lines.fielding, &lines.pitching);
+  if b.pa > 0 {
+    let rate = f64::from(b.so) / f64::from(b.pa);
+    if rate > STRIKEOUT_RATE {
+      found.push(Weakness {
+        focus: "hitting",
+        summary: format!("Striking out in {} of plate appearances", percent(rate)),
+      });
+    }
+  }
+  if let Some(avg) = b.avg.filter(|&avg| avg < TEAM_AVERAGE) {
+    found.push(Weakness {
+      focus: "hitting",
+      summary: format!("Batting {} as a team", average(avg)),
+    });
+  }
+  let attempts = b.sb + b.cs;
+  let caught = f64::from(b.cs) / f64::from(attempts.max(1));
+  if attempts >= MIN_STEAL_ATTEMPTS && caught > CAUGHT_STEALING_RATE {
+    found.push(Weakness {
+      focus: "baserunning",
+      summary: format!("Caught stealing {} times in {attempts} attempts", b.cs),
+    });
+  }
+  let fpct = f.fpct.filter(|_| f.chances >= MIN_CHANCES);
+  if let Some(fpct) = fpct.filter(|&fpct| fpct < FIELDING_PERCENTAGE) {
+    found.push(Weakness {
+      focus: "fielding",
+      summary: format!("{} errors, fielding {}", f.e, average(fpct)),
+    });
+  }
+  if p.outs > 0 {
+    let walks = f64::from(p.bb) / (f64::from(p.outs) / 3.0);
+    if walks > WALKS_PER_INNING {
+      found.push(Weakness {
+        focus: "pitching",
+        summary: format!("{} walks in {:.1} innings pitched", p.bb, p.ip),
+      });
+    }
+  }
+  found
+}
+
+/// The number of recent games with box scores and the team's stats
+/// summed over them.
+fn recent_lines(conn: &rusqlite::Connection, team_id: &str) -> Result<(u32, StatLines)> {
+  let sql = format!(
+    "WITH recent AS (SELECT g.id FROM games g WHERE g.team_id = ?1 \
+       AND EXISTS (SELECT 1 FROM game_stats s WHERE s.game_id = g.id) \
+       ORDER BY g.date DESC LIMIT ?2) \
+     SELECT (SELECT COUNT(*) FROM recent), {} FROM game_stats \
+     WHERE game_id IN (SELECT id FROM recent)",
+    stats::TOTALS
+  );
+  Ok(conn.query_row(&sql, rusqlite::params![team_id, RECENT_GAMES], |row| {
+    Ok((row.get(0)?, stats::from_totals(row, 1)?))
+  })?)
+}
+
+fn drill_line(drill: &Drill) -> String {
+  let mut line = format!("- {} ({}, {} min)", drill.name, drill.focus, drill.minutes);
+  let description = drill.description.trim();
+  if !description.is_empty() {
+    line.push_str(": ");
+    line.push_str(&description.replace('\n', " "));
+  }
+  line
+}
+
+/// Fills in the prompt for `request` from the team's recent games and
+/// drills.
+pub fn compose(conn: &rusqlite::Connection, request: &PracticePlanRequest) -> Result<Draft> {
+  drills::validate_minutes(request.minutes)?;
+  for focus in &request.focus {
+    drills::validate_focus(focus)?;
+  }
+  let team_id = match &request.team_id {
+    Some(team_id) => team_id.clone(),
+    None => roster::default_team_id(conn)?,
+  };
+  let team = roster::team_name(conn, &team_id)?;
+
+  let (games, lines) = recent_lines(conn, &team_id)?;
+  let found = weaknesses(&lines);
+  let mut focus: Vec<String> = Vec::new();
+  for area in request.focus.iter().map(String::as_str).chain(found.iter().map(|w| w.focus)) {
+    if !focus.iter().any(|f| f == area) {
+      focus.push(area.to_string());
+    }
+  }
+
+  // Drills for the areas to work on first, then the general ones
+  let mut library = drills::list(conn, &team_id)?;
+  library.sort_by_key(|d| {
+    let rank = focus.iter().position(|f| *f == d.focus).unwrap_or(focus.len());
+    (rank, d.focus != "general")
+  });
+  library.truncate(MAX_DRILLS);
+
+  let mut recent = vec![match games {
+    0 => "There are no box scores yet.".to_string(),
+    1 => "What the last game showed:".to_string(),
+    n => format!("What the last {n} games showed:"),
+  }];
+  recent.extend(found.iter().map(|w| format!("- {}", w.summary)));
+  if games > 0 && found.is_empty() {
+    recent.push("- Nothing stands out; keep the practice balanced".to_string());
+  }
+  recent.extend(request.focus.iter().map(|area| format!("- The coach wants to work on {area}")));
+  let drill_lines: Vec<String> = if library.is_empty() {
+    vec!["- None yet; suggest some".to_string()]
+  } else {
+    library.iter().map(drill_line).collect()
+  };
+  let notes = match request.notes.as_deref().map(str::trim) {
+    Some(notes) if !notes.is_empty() => format!("Coach's notes: {notes}\n\n"),
+    _ => String::new(),
+  };
+  let prompt = TEMPLATE
+    .replace("{minutes}", &request.minutes.to_string())
+    .replace("{team}", &team)
+    .replace("{recent}", &recent.join("\n"))
+    .replace("{drills}", &drill_lines.join("\n"))
+    .replace("{notes}", &notes);
+
+  let title = match request.title.as_deref().map(str::trim) {
+    Some(title) if !title.is_empty() => title.to_string(),
+    _ => format!("{team} practice, {}", chrono::Local::now().format("%B %-d")),
+  };
+  Ok(Draft {
+    team_id,
+    title,
+    focus,
+    drill_ids: library.into_iter().map(|d| d.id).collect(),
+    prompt,
+  })
+}
+
+/// Writes a practice plan for the team with the AI and saves it. The plan
+/// streams as `ai-stream:{request_id}` events while it's written, and
+/// `cancel_ai_request` stops it, in which case this returns `None`.
+#[tauri::command]
+pub async fn generate_practice_plan(
+  app: AppHandle,
+  request_id: String,
+  request: PracticePlanRequest,
+) -> Result<Option<PracticePlan>> {
+  let draft = {
+    let db = app.state::<Database>();
+    let conn = db.conn();
+    compose(&conn, &request)?
+  };
+  let chat = BackendRequest {
+    method: "POST".into(),
+    path: QueuedKind::Chat.path().into(),
+    body: Some(json!({
+      "messages": [
+        { "role": "system", "content": SYSTEM_PROMPT },
+        { "role": "user", "content": draft.prompt },
+      ],
+      "model": request.model,
+    })),
+    timeout_ms: Some(CHUNK_TIMEOUT_MS),
+  };
+
+  let mut content = String::new();
+  if !ai_stream::stream(&app, &request_id, &chat, |text| content.push_str(text)).await? {
+    return Ok(None);
+  }
+  let content = content.trim().to_string();
+  if content.is_empty() {
+    return Err(Error::Validation("The AI didn't write a plan; try again".into()));
+  }
+
+  let db = app.state::<Database>();
+  let plan = practice_plans::create(
+    &db.conn(),
+    NewPracticePlan {
+      team_id: draft.team_id,
+      title: draft.title,
+      minutes: request.minutes,
+      focus: draft.focus,
+      drill_ids: draft.drill_ids,
+      prompt: draft.prompt,
+      model: request.model,
+      content,
+    },
+  )?;
+  log::info!("Saved practice plan {}", plan.id);
+  Ok(Some(plan))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::drills::DrillInput;
+  use crate::db::games::{self, ScheduledGame};
+
+  #[test]
+  fn prompts_with_recent_weaknesses_and_matching_drills_first() {
+    let conn = crate::db::open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    for (name, focus) in [("Long toss", "throwing"), ("Tee work", "hitting"), ("Tag", "general")]
+    {
+      let drill = DrillInput {
+        id: None,
+        team_id: None,
+        name: name.into(),
+        focus: focus.into(),
+        minutes: 10,
+        description: String::new(),
+      };
+      drills::save(&conn, drill).unwrap();
+    }
+    conn
+      .execute(
+        "INSERT INTO players (id, team_id, name, primary_position, bats, throws, created_at, \
+         updated_at) VALUES ('p1', ?1, 'Ava Chen', 'SS', 'R', 'R', 'now', 'now')",
+        [&team_id],
+      )
+      .unwrap();
+    // Six games, and only the last five count: the first was a good one
+    for (day, so) in [(1, 0), (2, 2), (3, 2), (4, 2), (5, 1), (6, 2)] {
+      let game = ScheduledGame {
+        id: format!("g{day}"),
+        date: format!("2026-04-0{day}"),
+        opponent: "Eagles".into(),
+        home_away: "home".into(),
+        score_us: None,
+        score_them: None,
+        notes: String::new(),
+        location: String::new(),
+        start_time: None,
+      };
+      games::upsert_scheduled(&conn, &team_id, &game).unwrap();
+      let hits = if day == 1 { 4 } else { 1 };
+      conn
+        .execute(
+          "INSERT INTO game_stats (game_id, player_id, ab, h, so, po, a) \
+           VALUES (?1, 'p1', 4, ?2, ?3, 2, 2)",
+          rusqlite::params![game.id, hits, so],
+        )
+        .unwrap();
+    }
+
+    let request = PracticePlanRequest {
+      team_id: None,
+      minutes: 60,
+      focus: vec!["throwing".into()],
+      notes: Some("Only half the field".into()),
+      title: None,
+      model: "gpt-4o".into(),
+    };
+    let draft = compose(&conn, &request).unwrap();
+    assert_eq!(draft.focus, ["throwing", "hitting"]);
+    assert!(draft.prompt.starts_with("Plan a 60-minute practice for My Team."));
+    assert!(draft.prompt.contains("last 5 games showed"));
+    assert!(draft.prompt.contains("- Striking out in 45% of plate appearances"));
+    // .250 exactly isn't a slump
+    assert!(!draft.prompt.contains("as a team"));
+    assert!(draft.prompt.contains("Coach's notes: Only half the field"));
+    let drills = draft.prompt.split("already runs:\n").nth(1).unwrap();
+    assert!(drills.starts_with("- Long toss (throwing, 10 min)\n- Tee work"));
+    assert_eq!(draft.drill_ids.len(), 3);
+
+    let bad = PracticePlanRequest {
+      focus: vec!["napping".into()],
+      ..request
+    };
+    assert!(compose(&conn, &bad).is_err());
+  }
+}
@@ -0,0 +1,286 @@
+//! Presentation mode: a slideshow of lineups and awards, full screen and
+//! borderless on a second display (the TV in the team room, a projector at
+//! the banquet), while the controls stay in the main window on the
+//! primary display.
+//!
+//! The main window hands over the slides with `start_presentation` and
+//! moves between them with `show_slide`; the presentation window only shows
+//! whatever [`Presentation`] is current, as the scoreboard window does.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+  AppHandle, Emitter, Manager, Monitor, PhysicalPosition, State, WebviewUrl, WebviewWindowBuilder,
+  Window, WindowEvent,
+};
+
+use crate::error::{Error, Result};
+
+pub const PRESENTATION_WINDOW: &str = "presentation";
+
+/// Emitted with the [`Presentation`] whenever it starts, stops or changes
+/// slide.
+pub const PRESENTATION_EVENT: &str = "presentation";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Display {
+  /// The monitor's name, or `display-` and its position in the list for
+  /// one without.
+  pub id: String,
+  pub name: String,
+  /// Physical pixels, across the whole desktop.
+  pub x: i32,
+  pub y: i32,
+  pub width: u32,
+  pub height: u32,
+  pub scale_factor: f64,
+  pub primary: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SlideKind {
+  Lineup,
+  Award,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Slide {
+  pub kind: SlideKind,
+  pub title: String,
+  #[serde(default)]
+  pub subtitle: Option<String>,
+  /// The batting order for a lineup, or who won and why for an award.
+  #[serde(default)]
+  pub lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Presentation {
+  /// The display it's on, while it's running.
+  pub display_id: Option<String>,
+  pub slides: Vec<Slide>,
+  pub index: usize,
+  /// Seconds before the presentation window moves on by itself, or `None`
+  /// to leave it to the controls.
+  pub advance_secs: Option<u32>,
+}
+
+#[derive(Default)]
+pub struct PresentationState(Mutex<Presentation>);
+
+fn displays(app: &AppHandle) -> Result<Vec<(Display, Monitor)>> {
+  let primary = app.primary_monitor()?.map(|m| *m.position());
+  Ok(
+    app
+      .available_monitors()?
+      .into_iter()
+      .enumerate()
+      .map(|(index, monitor)| {
+        let (position, size) = (*monitor.position(), *monitor.size());
+        let id = monitor.name().cloned().unwrap_or_else(|| format!("display-{}", index + 1));
+        let display = Display {
+          name: monitor.name().cloned().unwrap_or_else(|| format!("Display {}", index + 1)),
+          id,
+          x: position.x,
+          y: position.y,
+          width: size.width,
+          height: size.height,
+          scale_factor: monitor.scale_factor(),
+          // Monitors don't overlap, so where one starts is enough to tell
+          primary: primary == Some(position),
+        };
+        (display, monitor)
+      })
+      .collect(),
+  )
+}
+
+fn emit(app: &AppHandle, presentation: &Presentation) -> Result<()> {
+  app.emit(PRESENTATION_EVENT, presentation)?;
+  Ok(())
+}
+
+fn validate_slides(slides: &[Slide]) -> Result<()> {
+  if slides.is_empty() {
+    return Err(Error::Validation("A presentation needs at least one slide".into()));
+  }
+  if slides.iter().any(|s| s.title.trim().is_empty()) {
+    return Err(Error::Validation("Every slide needs a title".into()));
+  }
+  Ok(())
+}
+
+/// Moves the main window onto `primary` if it's on the display being
+/// presented on, so the controls aren't hidden behind the slides.
+fn keep_controls_on(app: &AppHandle, presenting: &Monitor, primary: &Monitor) -> Result<()> {
+  let Some(main) = app.get_webview_window("main") else {
+    return Ok(());
+  };
+  let on_presenting = main
+    .current_monitor()?
+    .is_some_and(|m| m.position() == presenting.position());
+  if !on_presenting {
+    return Ok(());
+  }
+  let maximized = main.is_maximized()?;
+  main.unmaximize()?;
+  let size = main.outer_size()?;
+  let area = primary.work_area();
+  main.set_position(PhysicalPosition::new(
+    area.position.x + (area.size.width.saturating_sub(size.width) / 2) as i32,
+    area.position.y + (area.size.height.saturating_sub(size.height) / 2) as i32,
+  ))?;
+  if maximized {
+    main.maximize()?;
+  }
+  Ok(())
+}
+
+/// Monitors attached now, to pick the one to present on.
+#[tauri::command]
+pub fn list_displays(app: AppHandle) -> Result<Vec<Display>> {
+  Ok(displays(&app)?.into_iter().map(|(display, _)| display).collect())
+}
+
+/// Opens the slideshow full screen on `display_id`, or moves it there if
+/// it's already showing. The primary display keeps the controls, so it
+/// can't be presented on.
+#[tauri::command]
+pub async fn start_presentation(
+  app: AppHandle,
+  state: State<'_, PresentationState>,
+  display_id: String,
+  slides: Vec<Slide>,
+  advance_secs: Option<u32>,
+) -> Result<Presentation> {
+  validate_slides(&slides)?;
+  let displays = displays(&app)?;
+  let (display, monitor) = displays
+    .iter()
+    .find(|(d, _)| d.id == display_id)
+    .ok_or_else(|| Error::NotFound(format!("Display {display_id}")))?;
+  if display.primary {
+    return Err(Error::Validation(
+      "The controls stay on the primary display, so present on another one".into(),
+    ));
+  }
+  if let Some((_, primary)) = displays.iter().find(|(d, _)| d.primary) {
+    keep_controls_on(&app, monitor, primary)?;
+  }
+
+  let window = match app.get_webview_window(PRESENTATION_WINDOW) {
+    Some(window) => {
+      // Fullscreen sticks to the monitor it started on
+      window.set_fullscreen(false)?;
+      window
+    }
+    None => WebviewWindowBuilder::new(
+      &app,
+      PRESENTATION_WINDOW,
+      WebviewUrl::App("presentation".into()),
+    )
+    .title("Dugout Presentation")
+    .decorations(false)
+    .skip_taskbar(true)
+    .visible(false)
+    .build()?,
+  };
+  window.set_position(*monitor.position())?;
+  window.set_fullscreen(true)?;
+  window.show()?;
+  // Showing it takes focus, and the operator's keyboard belongs on the controls
+  if let Some(main) = app.get_webview_window("main") {
+    main.set_focus()?;
+  }
+
+  let presentation = {
+    let mut current = state.0.lock().unwrap();
+    *current = Presentation {
+      display_id: Some(display.id.clone()),
+      slides,
+      index: 0,
+      advance_secs,
+    };
+    current.clone()
+  };
+  log::info!("Presenting on {}", display.name);
+  emit(&app, &presentation)?;
+  Ok(presentation)
+}
+
+/// Replaces the slides while presenting, staying on the same one where it
+/// still exists.
+#[tauri::command]
+pub fn update_presentation(
+  app: AppHandle,
+  state: State<'_, PresentationState>,
+  slides: Vec<Slide>,
+  advance_secs: Option<u32>,
+) -> Result<Presentation> {
+  validate_slides(&slides)?;
+  let presentation = {
+    let mut current = state.0.lock().unwrap();
+    current.index = current.index.min(slides.len() - 1);
+    current.slides = slides;
+    current.advance_secs = advance_secs;
+    current.clone()
+  };
+  emit(&app, &presentation)?;
+  Ok(presentation)
+}
+
+#[tauri::command]
+pub fn show_slide(
+  app: AppHandle,
+  state: State<'_, PresentationState>,
+  index: usize,
+) -> Result<Presentation> {
+  let presentation = {
+    let mut current = state.0.lock().unwrap();
+    if index >= current.slides.len() {
+      return Err(Error::NotFound(format!("Slide {}", index + 1)));
+    }
+    current.index = index;
+    current.clone()
+  };
+  emit(&app, &presentation)?;
+  Ok(presentation)
+}
+
+#[tauri::command]
+pub fn stop_presentation(app: AppHandle) -> Result<()> {
+  if let Some(window) = app.get_webview_window(PRESENTATION_WINDOW) {
+    window.close()?;
+  }
+  // The window's `Destroyed` event clears the state
+  Ok(())
+}
+
+#[tauri::command]
+pub fn get_presentation(state: State<'_, PresentationState>) -> Presentation {
+  state.0.lock().unwrap().clone()
+}
+
+/// Ends the presentation however its window went away, e.g. the display
+/// being unplugged.
+pub fn handle_window_event(window: &Window, event: &WindowEvent) {
+  if window.label() != PRESENTATION_WINDOW || !matches!(event, WindowEvent::Destroyed) {
+    return;
+  }
+  let Some(state) = window.try_state::<PresentationState>() else {
+    return;
+  };
+  let presentation = {
+    let mut current = state.0.lock().unwrap();
+    current.display_id = None;
+    current.clone()
+  };
+  if let Err(e) = emit(window.app_handle(), &presentation) {
+    log::warn!("Failed to emit the presentation: {}", e);
+  }
+}
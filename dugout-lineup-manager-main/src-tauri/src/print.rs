@@ -0,0 +1,298 @@
+//! Sends lineup cards straight to the OS print spooler.
+//!
+//! macOS and Linux go through CUPS (`lp`/`lpstat`). Windows hands the PDF to
+//! the default PDF handler's "PrintTo" verb, which has no copy count, so the
+//! job is submitted once per copy.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::{lineups, roster, Database};
+use crate::export::pdf::{self, CardOptions};
+use crate::error::{Error, Result};
+use crate::telemetry::{Feature, Telemetry};
+
+/// Upper bound on copies per job, a guard against a typo emptying the tray.
+const MAX_COPIES: u32 = 20;
+
+/// Event carrying [`SeasonPrintProgress`] while season cards render.
+pub const SEASON_PRINT_PROGRESS_EVENT: &str = "season-print-progress";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Printer {
+  pub name: String,
+  pub is_default: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonPrintProgress {
+  pub done: usize,
+  pub total: usize,
+}
+
+/// Where [`print_season_cards`] sends the combined cards.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SeasonOutput {
+  Print {
+    printer: Option<String>,
+    copies: Option<u32>,
+  },
+  /// Without a path, asks where to save it.
+  Pdf { path: Option<PathBuf> },
+}
+
+/// [`SeasonOutput`] once the copies are checked and a save path chosen.
+enum Target {
+  Printer(Option<String>, u32),
+  File(PathBuf),
+}
+
+fn run(command: &mut Command) -> Result<String> {
+  #[cfg(windows)]
+  {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    command.creation_flags(CREATE_NO_WINDOW);
+  }
+  let output = command.output()?;
+  if !output.status.success() {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    return Err(Error::Print(stderr.trim().to_string()));
+  }
+  Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(not(windows))]
+pub fn printers() -> Result<Vec<Printer>> {
+  let names = run(Command::new("lpstat").arg("-e"))?;
+  // `lpstat -d` fails when no default is configured; that's not an error.
+  let default = run(Command::new("lpstat").arg("-d")).unwrap_or_default();
+  Ok(parse_lpstat(&names, &default))
+}
+
+#[cfg(windows)]
+pub fn printers() -> Result<Vec<Printer>> {
+  let output = run(Command::new("powershell").args([
+    "-NoProfile",
+    "-Command",
+    "Get-CimInstance Win32_Printer | ForEach-Object { \"$($_.Default)`t$($_.Name)\" }",
+  ]))?;
+  Ok(parse_win32_printers(&output))
+}
+
+#[cfg(any(not(windows), test))]
+fn parse_lpstat(names: &str, default: &str) -> Vec<Printer> {
+  let default = default
+    .lines()
+    .find_map(|line| line.split_once("destination:"))
+    .map(|(_, name)| name.trim());
+  names
+    .lines()
+    .map(str::trim)
+    .filter(|name| !name.is_empty())
+    .map(|name| Printer {
+      name: name.to_string(),
+      is_default: Some(name) == default,
+    })
+    .collect()
+}
+
+#[cfg(any(windows, test))]
+fn parse_win32_printers(output: &str) -> Vec<Printer> {
+  output
+    .lines()
+    .filter_map(|line| line.trim_end().split_once('\t'))
+    .map(|(default, name)| Printer {
+      name: name.to_string(),
+      is_default: default.eq_ignore_ascii_case("true"),
+    })
+    .collect()
+}
+
+/// Queues `copies` of the PDF at `path` on `printer`, or the default
+/// printer.
+#[cfg(not(windows))]
+pub fn print_file(path: &Path, printer: Option<&str>, copies: u32) -> Result<()> {
+  let mut command = Command::new("lp");
+  if let Some(printer) = printer {
+    command.arg("-d").arg(printer);
+  }
+  command.arg("-n").arg(copies.to_string()).arg(path);
+  let output = run(&mut command)?;
+  log::info!("Submitted print job: {}", output.trim());
+  Ok(())
+}
+
+#[cfg(windows)]
+pub fn print_file(path: &Path, printer: Option<&str>, copies: u32) -> Result<()> {
+  let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+  let file = quote(&path.to_string_lossy());
+  let script = match printer {
+    Some(printer) => format!(
+      "Start-Process -FilePath {file} -Verb PrintTo -ArgumentList {} -WindowStyle Hidden",
+      quote(&format!("\"{printer}\""))
+    ),
+    None => format!("Start-Process -FilePath {file} -Verb Print -WindowStyle Hidden"),
+  };
+  for _ in 0..copies {
+    run(Command::new("powershell").args(["-NoProfile", "-Command", &script]))?;
+  }
+  log::info!("Submitted {} print job(s) for {:?}", copies, path);
+  Ok(())
+}
+
+/// Lists installed printers, flagging the system default.
+#[tauri::command]
+pub async fn list_printers() -> Result<Vec<Printer>> {
+  tauri::async_runtime::spawn_blocking(printers).await?
+}
+
+/// Renders the lineup card and prints `copies` of it (default 1) on
+/// `printer`, or the default printer.
+#[tauri::command]
+pub async fn print_lineup(
+  app: AppHandle,
+  lineup_id: String,
+  options: Option<CardOptions>,
+  printer: Option<String>,
+  copies: Option<u32>,
+) -> Result<()> {
+  let copies = check_copies(copies)?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let card = pdf::card(&app.state::<Database>().conn(), &lineup_id)?;
+    let bytes = pdf::render(&card, &options.unwrap_or_default())?;
+    print_bytes(&app, bytes, printer.as_deref(), copies)
+  })
+  .await?
+}
+
+fn check_copies(copies: Option<u32>) -> Result<u32> {
+  let copies = copies.unwrap_or(1);
+  if !(1..=MAX_COPIES).contains(&copies) {
+    return Err(Error::Validation(format!(
+      "Copies must be between 1 and {MAX_COPIES}"
+    )));
+  }
+  Ok(copies)
+}
+
+/// Prints a rendered PDF through a temporary file.
+fn print_bytes(app: &AppHandle, bytes: Vec<u8>, printer: Option<&str>, copies: u32) -> Result<()> {
+  let path = std::env::temp_dir().join(format!("dugout-lineup-{}.pdf", uuid::Uuid::new_v4()));
+  std::fs::write(&path, bytes)?;
+  let result = print_file(&path, printer, copies);
+  // CUPS has spooled its own copy by the time `lp` returns; on Windows the
+  // PDF handler may still be reading the file, so leave it in temp.
+  if cfg!(not(windows)) {
+    let _ = std::fs::remove_file(&path);
+  }
+  if result.is_ok() {
+    app.state::<Telemetry>().record(Feature::LineupPrinted);
+  }
+  result
+}
+
+/// Renders the card for every upcoming game with a saved lineup, a page
+/// each, and prints them as one job or saves them as one PDF. Emits
+/// [`SEASON_PRINT_PROGRESS_EVENT`] as each card renders. Returns the
+/// number of cards, or `None` if the save dialog was cancelled.
+#[tauri::command]
+pub async fn print_season_cards(
+  app: AppHandle,
+  team_id: Option<String>,
+  options: Option<CardOptions>,
+  output: SeasonOutput,
+) -> Result<Option<usize>> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let db = app.state::<Database>();
+    let (team_name, lineup_ids) = {
+      let conn = db.conn();
+      let team_id = match team_id {
+        Some(team_id) => team_id,
+        None => roster::default_team_id(&conn)?,
+      };
+      (
+        roster::team_name(&conn, &team_id)?,
+        lineups::for_scheduled_games(&conn, &team_id)?,
+      )
+    };
+    if lineup_ids.is_empty() {
+      return Err(Error::Validation("No upcoming games have a saved lineup".into()));
+    }
+    // Ask before rendering so a long season isn't rendered for nothing.
+    let target = match output {
+      SeasonOutput::Print { printer, copies } => Target::Printer(printer, check_copies(copies)?),
+      SeasonOutput::Pdf { path: Some(path) } => Target::File(path),
+      SeasonOutput::Pdf { path: None } => {
+        let file_name = format!("{} season cards.pdf", crate::export::file_stem(&team_name));
+        match crate::export::pick_save_path(&app, &file_name, "PDF document", &["pdf"]) {
+          Some(path) => Target::File(path),
+          None => return Ok(None),
+        }
+      }
+    };
+
+    let cards = lineup_ids
+      .iter()
+      .map(|id| pdf::card(&db.conn(), id))
+      .collect::<Result<Vec<_>>>()?;
+    let total = cards.len();
+    let bytes = pdf::render_all(&cards, &options.unwrap_or_default(), |done| {
+      let _ = app.emit(SEASON_PRINT_PROGRESS_EVENT, SeasonPrintProgress { done, total });
+    })?;
+
+    match target {
+      Target::Printer(printer, copies) => {
+        print_bytes(&app, bytes, printer.as_deref(), copies)?;
+        log::info!("Printed {total} season lineup cards");
+      }
+      Target::File(path) => {
+        std::fs::write(&path, bytes)?;
+        log::info!("Saved {total} season lineup cards to {:?}", path);
+        app.state::<Telemetry>().record(Feature::PdfExported);
+      }
+    }
+    Ok(Some(total))
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_printer_lists() {
+    let printers = parse_lpstat(
+      "Office_Laser\nDugout_Inkjet\n",
+      "system default destination: Dugout_Inkjet\n",
+    );
+    assert_eq!(printers.len(), 2);
+    assert!(!printers[0].is_default);
+    assert!(printers[1].is_default);
+    assert!(parse_lpstat("Office_Laser\n", "no system default destination\n")
+      .iter()
+      .all(|p| !p.is_default));
+
+    let printers = parse_win32_printers("False\tMicrosoft Print to PDF\r\nTrue\tHP LaserJet\r\n");
+    assert_eq!(
+      printers,
+      [
+        Printer {
+          name: "Microsoft Print to PDF".into(),
+          is_default: false
+        },
+        Printer {
+          name: "HP LaserJet".into(),
+          is_default: true
+        },
+      ]
+    );
+  }
+}
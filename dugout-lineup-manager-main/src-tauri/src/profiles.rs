@@ -0,0 +1,260 @@
+//! Separate workspaces for coaches who run more than one team. Each profile
+//! has its own data directory (the database and the backend's files) and
+//! its own backups; settings, window state and AI models are shared.
+//!
+//! The default profile is the data directory the app has always used, so
+//! existing installs carry on as they were. Profiles added later live in
+//! `profiles/<id>/`, next to the list of them in `profiles.json`.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::db::{self, Database};
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+use crate::sidecar::SidecarManager;
+
+/// Id of the profile using the original data directory.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Emitted with the new [`Profile`] after `switch_profile`, so the webview
+/// reloads everything it shows.
+pub const PROFILE_CHANGED_EVENT: &str = "profile-changed";
+
+const INDEX_FILE: &str = "profiles.json";
+
+const MAX_NAME_LENGTH: usize = 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+  pub id: String,
+  pub name: String,
+  pub created_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProfileList {
+  /// Id of the profile in use.
+  pub active: String,
+  pub profiles: Vec<Profile>,
+}
+
+impl Default for ProfileList {
+  fn default() -> Self {
+    Self {
+      active: DEFAULT_PROFILE.into(),
+      profiles: vec![Profile {
+        id: DEFAULT_PROFILE.into(),
+        name: "My Team".into(),
+        created_at: String::new(),
+      }],
+    }
+  }
+}
+
+impl ProfileList {
+  /// Reads the list from `path`, starting with just the default profile if
+  /// it is missing or unreadable.
+  fn load(path: &Path) -> Self {
+    let mut list: Self = match std::fs::read_to_string(path) {
+      Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+        log::warn!("Ignoring invalid profile list {:?}: {}", path, e);
+        Self::default()
+      }),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+      Err(e) => {
+        log::warn!("Failed to read profile list {:?}: {}", path, e);
+        Self::default()
+      }
+    };
+    if list.get(DEFAULT_PROFILE).is_none() {
+      list.profiles.insert(0, Self::default().profiles.remove(0));
+    }
+    if list.get(&list.active).is_none() {
+      log::warn!("Unknown active profile {}; using the default", list.active);
+      list.active = DEFAULT_PROFILE.into();
+    }
+    list
+  }
+
+  fn save(&self, path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+  }
+
+  fn get(&self, id: &str) -> Option<&Profile> {
+    self.profiles.iter().find(|p| p.id == id)
+  }
+
+  /// Adds a profile called `name`, which must not already be taken.
+  fn add(&mut self, name: &str) -> Result<Profile> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+      return Err(Error::Validation(format!(
+        "Profile names must be 1 to {MAX_NAME_LENGTH} characters"
+      )));
+    }
+    if self.profiles.iter().any(|p| p.name.to_lowercase() == name.to_lowercase()) {
+      return Err(Error::Validation(format!("There is already a profile called {name}")));
+    }
+    let profile = Profile {
+      id: db::new_id(),
+      name: name.into(),
+      created_at: db::timestamp(),
+    };
+    self.profiles.push(profile.clone());
+    Ok(profile)
+  }
+}
+
+pub struct Profiles(Mutex<ProfileList>);
+
+impl Profiles {
+  /// Loads the profile list kept under `paths.profiles`.
+  pub fn load(paths: &AppPaths) -> Self {
+    Self(Mutex::new(ProfileList::load(&paths.profiles.join(INDEX_FILE))))
+  }
+
+  /// Id of the profile in use.
+  pub fn active(&self) -> String {
+    self.0.lock().unwrap().active.clone()
+  }
+
+  /// Makes the profile with this id or name the active one for this run
+  /// (`--profile`). It isn't saved as the choice for later launches unless
+  /// the list is saved meanwhile, by creating a profile. Only for use before
+  /// anything has opened the active profile's directories.
+  pub fn use_for_this_run(&self, wanted: &str) -> Result<Profile> {
+    let mut list = self.0.lock().unwrap();
+    let profile = list
+      .get(wanted)
+      .or_else(|| list.profiles.iter().find(|p| p.name.eq_ignore_ascii_case(wanted)))
+      .cloned()
+      .ok_or_else(|| Error::NotFound(format!("Profile {wanted}")))?;
+    list.active = profile.id.clone();
+    Ok(profile)
+  }
+}
+
+/// Makes `id` the active profile: reopens the database from its directory,
+/// points backups and the backend sidecar there, and restarts the sidecar.
+pub fn switch(app: &AppHandle, id: &str) -> Result<Profile> {
+  let paths = app.state::<AppPaths>();
+  let state = app.state::<Profiles>();
+  let mut list = state.0.lock().unwrap();
+  let profile = list
+    .get(id)
+    .cloned()
+    .ok_or_else(|| Error::NotFound(format!("Profile {id}")))?;
+  if list.active == id {
+    return Ok(profile);
+  }
+
+  // Opened before anything else moves, so a profile that can't be opened
+  // leaves the current one in use
+  let dirs = paths.profile_dirs(id);
+  dirs.ensure()?;
+  app
+    .state::<Database>()
+    .reopen(&dirs.data, &dirs.backups.join("pre-migration"))?;
+  log::info!("Switched to profile {} ({:?})", profile.name, dirs.data);
+  paths.activate(dirs.clone());
+  list.active = id.into();
+  if let Err(e) = list.save(&paths.profiles.join(INDEX_FILE)) {
+    log::warn!("Failed to save the active profile: {}", e);
+  }
+  drop(list);
+  // The other database has its own change history
+  app.state::<crate::stats::StatsCache>().clear();
+  #[cfg(desktop)]
+  if let Err(e) = crate::data_watcher::watch(app, &dirs.data) {
+    log::warn!("Failed to watch the profile's data directory: {}", e);
+  }
+
+  let sidecar = app.state::<SidecarManager>();
+  sidecar.set_data_dir(dirs.data);
+  if sidecar.status().running {
+    if let Err(e) = sidecar.restart(app) {
+      log::warn!("Failed to restart backend after switching profile: {}", e);
+    }
+  }
+  let _ = app.emit(PROFILE_CHANGED_EVENT, &profile);
+  Ok(profile)
+}
+
+/// Every profile, and which one is in use.
+#[tauri::command]
+pub fn list_profiles(profiles: State<'_, Profiles>) -> ProfileList {
+  profiles.0.lock().unwrap().clone()
+}
+
+/// Adds an empty profile. It isn't switched to.
+#[tauri::command]
+pub fn create_profile(
+  profiles: State<'_, Profiles>,
+  paths: State<'_, AppPaths>,
+  name: String,
+) -> Result<Profile> {
+  let mut list = profiles.0.lock().unwrap();
+  let mut updated = list.clone();
+  let profile = updated.add(&name)?;
+  paths.profile_dirs(&profile.id).ensure()?;
+  updated.save(&paths.profiles.join(INDEX_FILE))?;
+  *list = updated;
+  Ok(profile)
+}
+
+/// Switches to another profile's data. The backend restarts on the way,
+/// so this can take a few seconds.
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, id: String) -> Result<Profile> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  tauri::async_runtime::spawn_blocking(move || switch(&app, &id)).await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn keeps_each_profile_in_its_own_directory() {
+    let dir = std::env::temp_dir().join(format!("dugout-profiles-{}", uuid::Uuid::new_v4()));
+    let paths = AppPaths::new(dir.clone());
+    let index = paths.profiles.join(INDEX_FILE);
+
+    let mut list = ProfileList::load(&index);
+    assert_eq!(list, ProfileList::default());
+    let majors = list.add(" Majors ").unwrap();
+    assert_eq!(majors.name, "Majors");
+    assert!(list.add("majors").is_err());
+    assert!(list.add("  ").is_err());
+
+    list.active = majors.id.clone();
+    list.save(&index).unwrap();
+    assert_eq!(ProfileList::load(&index), list);
+
+    // The default profile keeps the original directories
+    assert_eq!(paths.profile_dirs(DEFAULT_PROFILE).data, dir.join("data"));
+    let dirs = paths.profile_dirs(&majors.id);
+    assert_eq!(dirs.data, dir.join("profiles").join(&majors.id).join("data"));
+    paths.activate(dirs.clone());
+    assert_eq!((paths.data(), paths.backups()), (dirs.data, dirs.backups));
+
+    // A profile missing from the list falls back to the default
+    list.profiles.retain(|p| p.id == DEFAULT_PROFILE);
+    list.save(&index).unwrap();
+    assert_eq!(ProfileList::load(&index).active, DEFAULT_PROFILE);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
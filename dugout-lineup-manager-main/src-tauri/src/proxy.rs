@@ -0,0 +1,322 @@
+//! Forwards the webview's backend calls to the sidecar, or to the remote
+//! backend configured in its place.
+//!
+//! The webview never talks to `localhost` itself: that runs into CORS and
+//! mixed-content rules, and the API would be open to any local process.
+//! Instead it calls `backend_request` (or `backend_stream` for streamed
+//! responses), which adds the token the current sidecar requires and
+//! turns failures into a [`ProxyError`] the frontend can tell apart.
+
+use std::time::Duration;
+
+use reqwest::{Method, StatusCode};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use tauri::ipc::Channel;
+use tauri::State;
+
+use crate::sidecar::{SidecarManager, AUTH_HEADER};
+
+/// Used when the caller doesn't pass `timeoutMs`.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Local model analysis can take minutes, but nothing should hang forever.
+const MAX_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendRequest {
+  /// `GET`, `POST`, `PUT`, `PATCH` or `DELETE`.
+  pub method: String,
+  /// Path and query under the backend root, e.g. `/players?status=active`.
+  pub path: String,
+  /// Sent as the JSON body.
+  pub body: Option<Value>,
+  /// When streaming, the longest wait for each chunk rather than for the
+  /// whole response.
+  pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendResponse {
+  pub status: u16,
+  /// Parsed JSON, the raw text if it isn't JSON, or `null` when empty.
+  pub body: Value,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ProxyError {
+  #[error("Invalid backend request: {0}")]
+  InvalidRequest(String),
+  #[error("The backend is not running")]
+  Unavailable,
+  /// The version handshake found a backend this build can't use.
+  #[error("{0}")]
+  Incompatible(String),
+  #[error("The backend did not answer within {} seconds", .0.as_secs())]
+  Timeout(Duration),
+  #[error("Could not reach the backend: {0}")]
+  Connection(String),
+  /// The backend answered with a non-2xx status.
+  #[error("Backend returned {status}")]
+  Status { status: u16, body: Value },
+}
+
+/// Reaches the webview as `{ kind, message, status?, body? }`.
+impl Serialize for ProxyError {
+  fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    let kind = match self {
+      Self::InvalidRequest(_) => "invalidRequest",
+      Self::Unavailable => "unavailable",
+      Self::Incompatible(_) => "incompatible",
+      Self::Timeout(_) => "timeout",
+      Self::Connection(_) => "connection",
+      Self::Status { .. } => "status",
+    };
+    let mut state = serializer.serialize_struct("ProxyError", 4)?;
+    state.serialize_field("kind", kind)?;
+    state.serialize_field("message", &self.to_string())?;
+    if let Self::Status { status, body } = self {
+      state.serialize_field("status", status)?;
+      state.serialize_field("body", body)?;
+    }
+    state.end()
+  }
+}
+
+type Result<T> = std::result::Result<T, ProxyError>;
+
+impl BackendRequest {
+  fn method(&self) -> Result<Method> {
+    match self.method.to_ascii_uppercase().as_str() {
+      "GET" => Ok(Method::GET),
+      "POST" => Ok(Method::POST),
+      "PUT" => Ok(Method::PUT),
+      "PATCH" => Ok(Method::PATCH),
+      "DELETE" => Ok(Method::DELETE),
+      other => Err(ProxyError::InvalidRequest(format!("unsupported method {other}"))),
+    }
+  }
+
+  /// Only paths on the backend itself; nothing that could point the
+  /// request at another host.
+  fn checked_path(&self) -> Result<&str> {
+    let path = self.path.as_str();
+    let valid = path.starts_with('/')
+      && !path.starts_with("//")
+      && !path.contains("..")
+      && !path.contains('#')
+      && !path.chars().any(|c| c.is_whitespace() || c.is_control());
+    if valid {
+      Ok(path)
+    } else {
+      Err(ProxyError::InvalidRequest(format!("bad path {path:?}")))
+    }
+  }
+
+  fn timeout(&self) -> Duration {
+    self
+      .timeout_ms
+      .map(Duration::from_millis)
+      .unwrap_or(DEFAULT_TIMEOUT)
+      .min(MAX_TIMEOUT)
+  }
+}
+
+pub(crate) fn request_error(e: reqwest::Error, timeout: Duration) -> ProxyError {
+  if e.is_timeout() {
+    ProxyError::Timeout(timeout)
+  } else {
+    ProxyError::Connection(e.to_string())
+  }
+}
+
+fn parse_body(text: &str) -> Value {
+  if text.trim().is_empty() {
+    Value::Null
+  } else {
+    serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+  }
+}
+
+/// Sends the request with the auth token, without a timeout of its own.
+async fn send(sidecar: &SidecarManager, request: &BackendRequest) -> Result<reqwest::Response> {
+  let method = request.method()?;
+  let path = request.checked_path()?;
+  if !sidecar.available() {
+    return Err(ProxyError::Unavailable);
+  }
+  if let Some(reason) = sidecar.compatibility().blocked() {
+    return Err(ProxyError::Incompatible(reason));
+  }
+
+  let mut builder = reqwest::Client::new()
+    .request(method, format!("{}{}", sidecar.base_url(), path))
+    .header(AUTH_HEADER, sidecar.auth_token());
+  if let Some(body) = &request.body {
+    builder = builder.json(body);
+  }
+  builder
+    .send()
+    .await
+    .map_err(|e| request_error(e, request.timeout()))
+}
+
+pub(crate) async fn into_status_error(response: reqwest::Response) -> ProxyError {
+  let status = response.status().as_u16();
+  let body = response.text().await.map(|text| parse_body(&text)).unwrap_or_default();
+  ProxyError::Status { status, body }
+}
+
+/// Sends one request to the backend and returns its JSON response.
+#[tauri::command]
+pub async fn backend_request(
+  sidecar: State<'_, SidecarManager>,
+  request: BackendRequest,
+) -> Result<BackendResponse> {
+  let timeout = request.timeout();
+  let exchange = async {
+    let response = send(&sidecar, &request).await?;
+    if !response.status().is_success() {
+      return Err(into_status_error(response).await);
+    }
+    let status = response.status();
+    let text = response.text().await.map_err(|e| request_error(e, timeout))?;
+    Ok(BackendResponse {
+      status: status.as_u16(),
+      body: if status == StatusCode::NO_CONTENT {
+        Value::Null
+      } else {
+        parse_body(&text)
+      },
+    })
+  };
+  tokio::time::timeout(timeout, exchange)
+    .await
+    .map_err(|_| ProxyError::Timeout(timeout))?
+}
+
+/// Sends one request and hands the response body to `on_text` as it
+/// arrives, until the body ends or `on_text` returns `false`. `timeout`
+/// bounds the wait for each chunk rather than the whole response.
+pub(crate) async fn stream_text(
+  sidecar: &SidecarManager,
+  request: &BackendRequest,
+  mut on_text: impl FnMut(String) -> bool,
+) -> Result<()> {
+  let timeout = request.timeout();
+  let mut response = tokio::time::timeout(timeout, send(sidecar, request))
+    .await
+    .map_err(|_| ProxyError::Timeout(timeout))??;
+  if !response.status().is_success() {
+    return Err(into_status_error(response).await);
+  }
+
+  // Chunks can split a UTF-8 sequence; hold the tail back until it's whole
+  let mut pending = Vec::new();
+  loop {
+    let chunk = tokio::time::timeout(timeout, response.chunk())
+      .await
+      .map_err(|_| ProxyError::Timeout(timeout))?
+      .map_err(|e| request_error(e, timeout))?;
+    let Some(chunk) = chunk else {
+      break;
+    };
+    pending.extend_from_slice(&chunk);
+    let end = match std::str::from_utf8(&pending) {
+      Err(e) if e.error_len().is_none() => e.valid_up_to(),
+      _ => pending.len(),
+    };
+    if end > 0 {
+      let text = String::from_utf8_lossy(&pending[..end]).into_owned();
+      pending.drain(..end);
+      if !on_text(text) {
+        return Ok(());
+      }
+    }
+  }
+  if !pending.is_empty() {
+    on_text(String::from_utf8_lossy(&pending).into_owned());
+  }
+  Ok(())
+}
+
+/// Posts `bytes` as a raw `content_type` body to `path` and returns the
+/// parsed response, for uploads the JSON-only [`BackendRequest`] can't carry.
+pub(crate) async fn upload(
+  sidecar: &SidecarManager,
+  path: &str,
+  content_type: &str,
+  bytes: Vec<u8>,
+  timeout: Duration,
+) -> Result<Value> {
+  if !sidecar.available() {
+    return Err(ProxyError::Unavailable);
+  }
+  if let Some(reason) = sidecar.compatibility().blocked() {
+    return Err(ProxyError::Incompatible(reason));
+  }
+  let exchange = async {
+    let response = reqwest::Client::new()
+      .post(format!("{}{}", sidecar.base_url(), path))
+      .header(AUTH_HEADER, sidecar.auth_token())
+      .header(reqwest::header::CONTENT_TYPE, content_type)
+      .body(bytes)
+      .send()
+      .await
+      .map_err(|e| request_error(e, timeout))?;
+    if !response.status().is_success() {
+      return Err(into_status_error(response).await);
+    }
+    let text = response.text().await.map_err(|e| request_error(e, timeout))?;
+    Ok(parse_body(&text))
+  };
+  tokio::time::timeout(timeout, exchange)
+    .await
+    .map_err(|_| ProxyError::Timeout(timeout))?
+}
+
+/// Sends one request and passes the response body to `on_chunk` as text as
+/// it arrives.
+#[tauri::command]
+pub async fn backend_stream(
+  sidecar: State<'_, SidecarManager>,
+  request: BackendRequest,
+  on_chunk: Channel<String>,
+) -> Result<()> {
+  // Stops early if the webview went away and nobody is reading the rest
+  stream_text(&sidecar, &request, |text| on_chunk.send(text).is_ok()).await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn request(method: &str, path: &str) -> BackendRequest {
+    BackendRequest {
+      method: method.into(),
+      path: path.into(),
+      body: None,
+      timeout_ms: None,
+    }
+  }
+
+  #[test]
+  fn only_forwards_backend_paths() {
+    assert!(request("get", "/players?status=active").checked_path().is_ok());
+    for path in ["players", "//evil.example/x", "/../etc", "/players#x", "/a b"] {
+      assert!(request("GET", path).checked_path().is_err(), "{path}");
+    }
+    assert!(request("TRACE", "/players").method().is_err());
+  }
+
+  #[test]
+  fn caps_the_timeout() {
+    assert_eq!(request("GET", "/").timeout(), DEFAULT_TIMEOUT);
+    let mut slow = request("GET", "/");
+    slow.timeout_ms = Some(60 * 60 * 1000);
+    assert_eq!(slow.timeout(), MAX_TIMEOUT);
+  }
+}
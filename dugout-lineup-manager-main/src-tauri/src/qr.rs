@@ -0,0 +1,101 @@
+//! QR codes for sharing links at the field: the LAN scoreboard, exported
+//! lineups, anything a parent should open on their phone.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use qrcode::render::svg;
+use qrcode::{Color, QrCode};
+
+use crate::error::{Error, Result};
+
+const DEFAULT_SIZE: u32 = 512;
+const MAX_SIZE: u32 = 2048;
+/// Light border around the code, in modules; scanners need four.
+const QUIET_ZONE: usize = 4;
+
+/// A QR code of `data` as an SVG document.
+pub fn svg(data: &str) -> Result<String> {
+  let code = QrCode::new(data.as_bytes())?;
+  Ok(
+    code
+      .render::<svg::Color>()
+      .min_dimensions(240, 240)
+      .quiet_zone(true)
+      .build(),
+  )
+}
+
+/// A QR code of `data` as a black and white PNG `size` pixels square. Each
+/// module is a whole number of pixels so the code stays sharp; the spare
+/// pixels go to the border.
+pub fn png(data: &str, size: u32) -> Result<Vec<u8>> {
+  let code = QrCode::new(data.as_bytes())?;
+  let colors = code.to_colors();
+  let modules = code.width() + 2 * QUIET_ZONE;
+  let side = (size as usize).max(modules);
+  let scale = side / modules;
+  let offset = (side - modules * scale) / 2 + QUIET_ZONE * scale;
+
+  let mut pixels = vec![255u8; side * side];
+  for (i, color) in colors.iter().enumerate() {
+    if *color != Color::Dark {
+      continue;
+    }
+    let (x, y) = (i % code.width(), i / code.width());
+    for row in 0..scale {
+      let start = (offset + y * scale + row) * side + offset + x * scale;
+      pixels[start..start + scale].fill(0);
+    }
+  }
+
+  let encode_error = |e: png::EncodingError| Error::Io(std::io::Error::other(e));
+  let mut out = Vec::new();
+  let mut encoder = png::Encoder::new(&mut out, side as u32, side as u32);
+  encoder.set_color(png::ColorType::Grayscale);
+  encoder.set_depth(png::BitDepth::Eight);
+  let mut writer = encoder.write_header().map_err(encode_error)?;
+  writer.write_image_data(&pixels).map_err(encode_error)?;
+  writer.finish().map_err(encode_error)?;
+  Ok(out)
+}
+
+/// A QR code of `data` as a `data:image/png;base64,…` URL, ready for an
+/// `<img>` or to print. `size` is in pixels and defaults to 512; codes too
+/// dense to fit are made larger.
+#[tauri::command]
+pub fn generate_qr(data: String, size: Option<u32>) -> Result<String> {
+  if data.is_empty() {
+    return Err(Error::Validation("Nothing to put in the QR code".into()));
+  }
+  let size = size.unwrap_or(DEFAULT_SIZE).min(MAX_SIZE);
+  let image = png(&data, size)?;
+  Ok(format!("data:image/png;base64,{}", STANDARD.encode(image)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn renders_sharp_codes_at_the_requested_size() {
+    let image = png("DUGOUT", 300).unwrap();
+    let mut reader = png::Decoder::new(image.as_slice()).read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!((info.width, info.height, info.color_type), (300, 300, png::ColorType::Grayscale));
+
+    // A version 1 code is 21 modules, 29 with the border, so 10 pixels
+    // each with 10 spare: 5 on each side, then the quiet zone
+    assert_eq!(QrCode::new("DUGOUT").unwrap().width(), 21);
+    let at = |x: usize, y: usize| pixels[y * 300 + x];
+    let corner = 5 + 4 * 10;
+    assert_eq!(at(corner - 1, corner - 1), 255);
+    assert_eq!(at(corner, corner), 0, "finder pattern starts after the quiet zone");
+
+    // Dense data still gets a readable code rather than a squashed one
+    let tiny = png(&"x".repeat(500), 50).unwrap();
+    let reader = png::Decoder::new(tiny.as_slice()).read_info().unwrap();
+    assert!(reader.info().width > 50);
+    assert!(generate_qr(String::new(), None).is_err());
+  }
+}
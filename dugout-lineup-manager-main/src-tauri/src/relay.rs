@@ -0,0 +1,337 @@
+//! Live game sharing through a relay server, for family who can't make it
+//! to the field.
+//!
+//! The overlay server only reaches phones on the same Wi-Fi. Sharing is
+//! opt-in: when the coach starts it, the shell connects to the relay set in
+//! settings and publishes the [`Scoreboard`] (score, inning, outs and
+//! batting order) after every update. The relay passes it on to anyone
+//! with the watch link and never receives anything else from the app.
+//!
+//! Each share gets a new random channel, so a link stops working once
+//! sharing stops, and a publish key that proves to the relay the updates
+//! are ours. The relay protocol, version 1:
+//!
+//! - Publish: a WebSocket to `{server}/v1/channels/{channel}/publish` with
+//!   `Authorization: Bearer {key}`, sending text frames of
+//!   `{"type":"scoreboard","scoreboard":{…}}`. The relay may send
+//!   `{"type":"viewers","count":3}` back.
+//! - Watch: `{server}/watch/{channel}` in a browser, over `https` for a
+//!   `wss` relay.
+
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
+use tokio_tungstenite::tungstenite::{Error as TungsteniteError, Message as Frame};
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+
+use crate::error::{Error, Result};
+use crate::scoreboard::{Scoreboard, ScoreboardState};
+use crate::settings::AppSettings;
+
+/// Event carrying the [`LiveShareStatus`] whenever it changes.
+pub const LIVE_SHARE_EVENT: &str = "live-share-status";
+
+/// Wait before reconnecting after the relay drops, doubling up to the max.
+const RETRY_MIN: Duration = Duration::from_secs(1);
+const RETRY_MAX: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RelaySettings {
+  /// Base `wss://` URL of the relay. Live sharing is off without one.
+  pub server: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveShareStatus {
+  pub active: bool,
+  /// Whether the relay connection is up right now.
+  pub connected: bool,
+  /// Link to send to family.
+  pub url: Option<String>,
+  /// QR code of `url` as an SVG document.
+  pub qr_svg: Option<String>,
+  /// People watching, when the relay says.
+  pub viewers: Option<u32>,
+  /// Why the last connection attempt failed.
+  pub error: Option<String>,
+}
+
+struct Sharing {
+  channel: String,
+  status: LiveShareStatus,
+  /// Set to `true` to stop publishing.
+  shutdown: watch::Sender<bool>,
+}
+
+#[derive(Default)]
+pub struct RelayState(Mutex<Option<Sharing>>);
+
+impl RelayState {
+  fn status(&self) -> LiveShareStatus {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .as_ref()
+      .map(|sharing| sharing.status.clone())
+      .unwrap_or_default()
+  }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Outgoing<'a> {
+  Scoreboard { scoreboard: &'a Scoreboard },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Incoming {
+  Viewers { count: u32 },
+}
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+pub fn validate(settings: &RelaySettings) -> Result<()> {
+  if let Some(server) = &settings.server {
+    endpoints(server, "channel")?;
+  }
+  Ok(())
+}
+
+/// The publish and watch URLs for `channel` on `server`.
+fn endpoints(server: &str, channel: &str) -> Result<(String, String)> {
+  let url = reqwest::Url::parse(server)
+    .map_err(|e| Error::Validation(format!("Invalid relay URL: {e}")))?;
+  let web_scheme = match url.scheme() {
+    "wss" => "https",
+    "ws" => "http",
+    _ => return Err(Error::Validation("The relay URL must be a ws(s) address".into())),
+  };
+  if url.host().is_none() || url.query().is_some() || url.fragment().is_some() {
+    return Err(Error::Validation(format!("{server} is not a plain base URL")));
+  }
+  let base = url.as_str().trim_end_matches('/');
+  let web_base = format!("{web_scheme}{}", &base[url.scheme().len()..]);
+  Ok((
+    format!("{base}/v1/channels/{channel}/publish"),
+    format!("{web_base}/watch/{channel}"),
+  ))
+}
+
+/// TLS with the system's trusted roots and an explicit crypto provider,
+/// since more than one is compiled in.
+fn connector() -> Connector {
+  let mut roots = rustls::RootCertStore::empty();
+  let native = rustls_native_certs::load_native_certs();
+  for e in &native.errors {
+    log::warn!("Skipping system certificates: {}", e);
+  }
+  roots.add_parsable_certificates(native.certs);
+  let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+  let config = rustls::ClientConfig::builder_with_provider(provider)
+    .with_safe_default_protocol_versions()
+    .expect("aws-lc-rs supports the default TLS versions")
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+  Connector::Rustls(Arc::new(config))
+}
+
+async fn connect(endpoint: &str, key: &str) -> Result<Socket> {
+  let mut request = endpoint.into_client_request()?;
+  let bearer = format!("Bearer {key}").parse().expect("hex keys are valid headers");
+  request.headers_mut().insert(AUTHORIZATION, bearer);
+  let connector = Some(connector());
+  let (socket, _) =
+    tokio_tungstenite::connect_async_tls_with_config(request, None, false, connector).await?;
+  Ok(socket)
+}
+
+/// Applies `change` to the status of the share on `channel`, if it's still
+/// the current one, and broadcasts it.
+fn update(app: &AppHandle, channel: &str, change: impl FnOnce(&mut LiveShareStatus)) {
+  let state = app.state::<RelayState>();
+  let status = {
+    let mut sharing = state.0.lock().unwrap();
+    match sharing.as_mut() {
+      Some(sharing) if sharing.channel == channel => {
+        change(&mut sharing.status);
+        sharing.status.clone()
+      }
+      _ => return,
+    }
+  };
+  let _ = app.emit(LIVE_SHARE_EVENT, status);
+}
+
+/// Keeps publishing to the relay, reconnecting with backoff, until sharing
+/// stops.
+async fn publish(
+  app: AppHandle,
+  channel: String,
+  endpoint: String,
+  key: String,
+  mut shutdown: watch::Receiver<bool>,
+) {
+  let mut scoreboard = app.state::<ScoreboardState>().subscribe();
+  let mut delay = RETRY_MIN;
+  loop {
+    let error = match connect(&endpoint, &key).await {
+      Ok(socket) => {
+        delay = RETRY_MIN;
+        update(&app, &channel, |status| {
+          status.connected = true;
+          status.error = None;
+        });
+        let streamed = stream(socket, &app, &channel, &mut scoreboard, &mut shutdown).await;
+        update(&app, &channel, |status| {
+          status.connected = false;
+          status.viewers = None;
+        });
+        streamed.err()
+      }
+      Err(e) => Some(e),
+    };
+    if *shutdown.borrow() {
+      return;
+    }
+    if let Some(e) = error {
+      log::warn!("Live share relay connection failed: {}", e);
+      update(&app, &channel, |status| status.error = Some(e.to_string()));
+    }
+    tokio::select! {
+      _ = tokio::time::sleep(delay) => {}
+      _ = shutdown.changed() => return,
+    }
+    delay = (delay * 2).min(RETRY_MAX);
+  }
+}
+
+/// Sends the scoreboard now and after every update until sharing stops or
+/// the relay hangs up.
+async fn stream(
+  mut socket: Socket,
+  app: &AppHandle,
+  channel: &str,
+  scoreboard: &mut watch::Receiver<Scoreboard>,
+  shutdown: &mut watch::Receiver<bool>,
+) -> Result<()> {
+  loop {
+    let text = serde_json::to_string(&Outgoing::Scoreboard {
+      scoreboard: &scoreboard.borrow_and_update(),
+    })?;
+    socket.send(Frame::Text(text.into())).await?;
+
+    loop {
+      tokio::select! {
+        changed = scoreboard.changed() => {
+          if changed.is_err() {
+            return Ok(());
+          }
+          break;
+        }
+        // Only ever changes to `true`
+        _ = shutdown.changed() => {
+          let _ = socket.close(None).await;
+          return Ok(());
+        }
+        incoming = socket.next() => match incoming {
+          Some(Ok(Frame::Text(text))) => {
+            if let Ok(Incoming::Viewers { count }) = serde_json::from_str(&text) {
+              update(app, channel, |status| status.viewers = Some(count));
+            }
+          }
+          Some(Ok(Frame::Close(_))) | None => return Err(TungsteniteError::ConnectionClosed.into()),
+          Some(Ok(_)) => {}
+          Some(Err(e)) => return Err(e.into()),
+        },
+      }
+    }
+  }
+}
+
+#[tauri::command]
+pub fn get_live_share_status(state: State<'_, RelayState>) -> LiveShareStatus {
+  state.status()
+}
+
+/// Starts publishing the scoreboard to the relay and returns the link and
+/// a QR code to send to family. A no-op if already sharing.
+#[tauri::command]
+pub fn start_live_share(
+  app: AppHandle,
+  state: State<'_, RelayState>,
+  settings: State<'_, RwLock<AppSettings>>,
+) -> Result<LiveShareStatus> {
+  let mut sharing = state.0.lock().unwrap();
+  if let Some(sharing) = sharing.as_ref() {
+    return Ok(sharing.status.clone());
+  }
+  let server = settings.read().unwrap().relay.server.clone().ok_or_else(|| {
+    Error::Validation("Set a relay server in settings to share games live".into())
+  })?;
+  let channel = uuid::Uuid::new_v4().simple().to_string();
+  let key = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+  let (endpoint, url) = endpoints(&server, &channel)?;
+  let status = LiveShareStatus {
+    active: true,
+    qr_svg: Some(crate::qr::svg(&url)?),
+    url: Some(url),
+    ..Default::default()
+  };
+
+  let (shutdown, stopped) = watch::channel(false);
+  *sharing = Some(Sharing {
+    channel: channel.clone(),
+    status: status.clone(),
+    shutdown,
+  });
+  log::info!("Live sharing at {}", status.url.as_deref().unwrap_or_default());
+  let _ = app.emit(LIVE_SHARE_EVENT, &status);
+  tauri::async_runtime::spawn(publish(app, channel, endpoint, key, stopped));
+  Ok(status)
+}
+
+/// Stops publishing. The link stops updating and won't come back.
+#[tauri::command]
+pub fn stop_live_share(app: AppHandle, state: State<'_, RelayState>) -> LiveShareStatus {
+  if let Some(sharing) = state.0.lock().unwrap().take() {
+    sharing.shutdown.send_replace(true);
+    log::info!("Live sharing stopped");
+  }
+  let _ = app.emit(LIVE_SHARE_EVENT, LiveShareStatus::default());
+  LiveShareStatus::default()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn derives_publish_and_watch_links() {
+    assert_eq!(
+      endpoints("wss://relay.example.com/", "abc").unwrap(),
+      (
+        "wss://relay.example.com/v1/channels/abc/publish".to_string(),
+        "https://relay.example.com/watch/abc".to_string()
+      )
+    );
+    let (_, watch) = endpoints("ws://192.168.1.20:8080/dugout", "abc").unwrap();
+    assert_eq!(watch, "http://192.168.1.20:8080/dugout/watch/abc");
+    for server in ["https://relay.example.com", "wss://relay.example.com/?a=1", "relay"] {
+      let settings = RelaySettings {
+        server: Some(server.into()),
+      };
+      assert!(validate(&settings).is_err(), "{server}");
+    }
+  }
+}
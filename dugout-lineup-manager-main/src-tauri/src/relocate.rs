@@ -0,0 +1,258 @@
+//! Moving the default profile's data and backups to a folder the coach
+//! picks, e.g. inside Dropbox so another computer can restore from it.
+//!
+//! Everything is copied before anything is switched over: the database (with
+//! its write-ahead log folded in first), the backend's files and images,
+//! and the backups. Only once the copy is open, the new location is saved in
+//! settings and the sidecar is restarted there are the old folders removed.
+//! A failure along the way deletes the partial copy and leaves the app
+//! running from where it was.
+
+use std::path::Path;
+use std::sync::RwLock;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::{Database, DB_FILE};
+use crate::error::{Error, Result};
+use crate::notes_index::NotesIndex;
+use crate::paths::{AppPaths, ProfileDirs};
+use crate::settings::{self, AppSettings, SETTINGS_CHANGED_EVENT};
+use crate::sidecar::{SidecarManager, SHUTDOWN_TIMEOUT};
+
+/// The folders the default profile would use under `target`.
+pub fn dirs_under(target: &Path) -> ProfileDirs {
+  ProfileDirs {
+    data: target.join("data"),
+    backups: target.join("backups"),
+  }
+}
+
+fn is_empty_or_missing(dir: &Path) -> Result<bool> {
+  match std::fs::read_dir(dir) {
+    Ok(mut entries) => Ok(entries.next().is_none()),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(true),
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// Rejects a move that would overwrite files or copy a folder into itself.
+fn check_target(from: &ProfileDirs, to: &ProfileDirs) -> Result<()> {
+  if cfg!(mobile) {
+    return Err(Error::Validation(
+      "The data directory can't be moved on this device".into(),
+    ));
+  }
+  if !to.data.is_absolute() {
+    return Err(Error::Validation("Data directory must be an absolute path".into()));
+  }
+  for dir in [&to.data, &to.backups] {
+    if [&from.data, &from.backups].iter().any(|old| dir.starts_with(old) || old.starts_with(dir)) {
+      return Err(Error::Validation(format!(
+        "{} overlaps the current data folders",
+        dir.display()
+      )));
+    }
+    if !is_empty_or_missing(dir)? {
+      return Err(Error::Validation(format!("{} already has files in it", dir.display())));
+    }
+  }
+  Ok(())
+}
+
+/// Copies `from` into `to`, leaving out SQLite's side files: the database
+/// is copied with its log already folded in.
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+  std::fs::create_dir_all(to)?;
+  let entries = match std::fs::read_dir(from) {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+    Err(e) => return Err(e.into()),
+  };
+  for entry in entries {
+    let entry = entry?;
+    let name = entry.file_name();
+    let side_file = name
+      .to_str()
+      .and_then(|name| name.strip_prefix(DB_FILE))
+      .is_some_and(|rest| rest.starts_with('-'));
+    if entry.file_type()?.is_dir() {
+      copy_dir(&entry.path(), &to.join(&name))?;
+    } else if !side_file {
+      std::fs::copy(entry.path(), to.join(&name))?;
+    }
+  }
+  Ok(())
+}
+
+fn remove_copy(to: &ProfileDirs) {
+  for dir in [&to.data, &to.backups] {
+    if let Err(e) = std::fs::remove_dir_all(dir) {
+      if e.kind() != std::io::ErrorKind::NotFound {
+        log::warn!("Failed to remove partial copy {:?}: {}", dir, e);
+      }
+    }
+  }
+}
+
+/// Copies the profile's folders from `from` to `to`. With `db`, the
+/// profile is in use and its database switches to the copy. A failure
+/// removes whatever was copied.
+fn copy_profile(db: Option<&Database>, from: &ProfileDirs, to: &ProfileDirs) -> Result<()> {
+  let copy = || {
+    copy_dir(&from.data, &to.data)?;
+    copy_dir(&from.backups, &to.backups)
+  };
+  let copied = match db {
+    Some(db) => db.move_to(&to.data, &to.backups.join("pre-migration"), copy),
+    None => copy(),
+  };
+  if copied.is_err() {
+    remove_copy(to);
+  }
+  copied
+}
+
+/// Moves the default profile's data and backups into `target`. Blocks
+/// while the sidecar restarts.
+pub fn relocate(app: &AppHandle, target: &Path) -> Result<ProfileDirs> {
+  let paths = app.state::<AppPaths>();
+  let settings_state = app.state::<RwLock<AppSettings>>();
+  let from = paths.default_profile();
+  let to = dirs_under(target);
+  if to == from {
+    return Ok(to);
+  }
+  check_target(&from, &to)?;
+
+  let db = app.state::<Database>();
+  let active = paths.data() == from.data;
+  let sidecar = app.state::<SidecarManager>();
+  // The backend writes into the data directory too
+  let restart = active && sidecar.status().running;
+  if restart {
+    sidecar.stop(SHUTDOWN_TIMEOUT);
+  }
+  if active {
+    // Its files are held open; the next search opens the moved copy
+    app.state::<NotesIndex>().close();
+  }
+  let restart_sidecar = || {
+    if restart {
+      if let Err(e) = sidecar.restart(app) {
+        log::warn!("Failed to restart backend after moving the data: {}", e);
+      }
+    }
+  };
+
+  if let Err(e) = copy_profile(active.then_some(&*db), &from, &to) {
+    restart_sidecar();
+    return Err(e);
+  }
+
+  let updated = {
+    let mut settings = settings_state.write().unwrap();
+    let mut updated = settings.clone();
+    updated.data_dir = Some(to.data.clone());
+    updated.backups_dir = Some(to.backups.clone());
+    settings::save(&paths.settings, &updated).map(|()| {
+      *settings = updated.clone();
+      updated
+    })
+  };
+  let updated = match updated {
+    Ok(updated) => updated,
+    Err(e) => {
+      if active {
+        if let Err(e) = db.reopen(&from.data, &from.backups.join("pre-migration")) {
+          log::error!("Failed to reopen the database where it was: {}", e);
+        }
+      }
+      remove_copy(&to);
+      restart_sidecar();
+      return Err(e);
+    }
+  };
+
+  paths.set_default_profile(to.clone());
+  if active {
+    sidecar.set_data_dir(to.data.clone());
+    // Before the old folder is removed, which would look like outside changes
+    #[cfg(desktop)]
+    if let Err(e) = crate::data_watcher::watch(app, &to.data) {
+      log::warn!("Failed to watch the moved data directory: {}", e);
+    }
+  }
+  restart_sidecar();
+  log::info!("Moved the data directory from {:?} to {:?}", from.data, to.data);
+
+  for dir in [&from.data, &from.backups] {
+    if let Err(e) = std::fs::remove_dir_all(dir) {
+      log::warn!("Failed to remove the old folder {:?}: {}", dir, e);
+    }
+  }
+  let _ = app.emit(SETTINGS_CHANGED_EVENT, updated);
+  Ok(to)
+}
+
+/// Moves the database, the backend's files and the backups into
+/// `data/` and `backups/` under `new_path`, and keeps using them from
+/// there. The backend restarts on the way.
+#[tauri::command]
+pub async fn set_data_dir(
+  app: AppHandle,
+  new_path: std::path::PathBuf,
+) -> Result<ProfileDirs> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  tauri::async_runtime::spawn_blocking(move || relocate(&app, &new_path)).await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::roster::{self, NewPlayer};
+
+  #[test]
+  fn copies_everything_and_switches_the_database() {
+    let root = std::env::temp_dir().join(format!("dugout-relocate-{}", uuid::Uuid::new_v4()));
+    let from = dirs_under(&root.join("old"));
+    let to = dirs_under(&root.join("Dropbox").join("Dugout"));
+    std::fs::create_dir_all(from.data.join("images")).unwrap();
+    std::fs::create_dir_all(&from.backups).unwrap();
+    std::fs::write(from.data.join("images").join("logo.png"), b"png").unwrap();
+    std::fs::write(from.backups.join("auto.zip"), b"zip").unwrap();
+
+    let db = Database::open(&from.data, &from.backups.join("pre-migration")).unwrap();
+    let player = NewPlayer {
+      team_id: None,
+      name: "Jake Miller".into(),
+      number: Some(12),
+      primary_position: "SS".into(),
+      secondary_positions: Vec::new(),
+      bats: "R".into(),
+      throws: "R".into(),
+      status: None,
+      notes: None,
+    };
+    roster::create(&db.conn(), player).unwrap();
+
+    assert!(check_target(&from, &dirs_under(&from.data)).is_err());
+    check_target(&from, &to).unwrap();
+    copy_profile(Some(&db), &from, &to).unwrap();
+    assert_eq!(std::fs::read(to.data.join("images").join("logo.png")).unwrap(), b"png");
+    assert_eq!(std::fs::read(to.backups.join("auto.zip")).unwrap(), b"zip");
+    let path: String = db
+      .conn()
+      .query_row("SELECT file FROM pragma_database_list WHERE name = 'main'", [], |row| {
+        row.get(0)
+      })
+      .unwrap();
+    assert!(Path::new(&path).starts_with(&to.data));
+    let team = roster::default_team_id(&db.conn()).unwrap();
+    assert_eq!(roster::list(&db.conn(), &team).unwrap().len(), 1);
+
+    // The copy isn't empty any more, so nothing can be moved on top of it
+    assert!(check_target(&from, &to).is_err());
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
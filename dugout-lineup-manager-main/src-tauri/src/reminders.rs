@@ -0,0 +1,355 @@
+//! Game reminders, shown as OS notifications so they reach the coach even
+//! with the window hidden in the tray.
+//!
+//! A background task checks the schedule every minute. Each scheduled game
+//! gets one reminder, [`ReminderSettings::lead_minutes`] before it starts
+//! (or on the morning of game day when the schedule has no start time),
+//! saying whether its lineup still needs setting. `snooze_reminder` brings
+//! it back later. Shown reminders are kept in the database, so a restart
+//! doesn't repeat them, while a rescheduled game is reminded about again.
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+use chrono::{Days, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::db::games::{self, Game};
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::settings::AppSettings;
+
+/// Emitted with a [`GameReminder`] whenever one is shown, so the app can
+/// offer to snooze it.
+pub const GAME_REMINDER_EVENT: &str = "game-reminder";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const DEFAULT_SNOOZE_MINUTES: u32 = 15;
+const MAX_SNOOZE_MINUTES: u32 = 4 * 60;
+
+/// Furthest ahead a reminder can be, which bounds how far ahead the
+/// schedule is checked.
+const MAX_LEAD_MINUTES: u32 = 24 * 60;
+
+/// Written to the database without seconds, like `HH:MM` start times.
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReminderSettings {
+  pub enabled: bool,
+  /// How long before a game's start time to remind.
+  pub lead_minutes: u32,
+}
+
+impl Default for ReminderSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      lead_minutes: 120,
+    }
+  }
+}
+
+pub fn validate(settings: &ReminderSettings) -> Result<()> {
+  if settings.lead_minutes == 0 || settings.lead_minutes > MAX_LEAD_MINUTES {
+    return Err(Error::Validation(
+      "Game reminders must be between 1 minute and 24 hours before the game".into(),
+    ));
+  }
+  Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameReminder {
+  pub game_id: String,
+  pub lineup_set: bool,
+  /// Ready to show, e.g. "Game vs Tigers in 2 hours — lineup not set!".
+  pub message: String,
+}
+
+/// When games without a start time are reminded about.
+fn morning() -> NaiveTime {
+  NaiveTime::from_hms_opt(9, 0, 0).expect("valid time")
+}
+
+/// When the game starts, or `None` when only its day is known.
+fn start_of(game: &Game) -> Option<NaiveDateTime> {
+  let date = NaiveDate::parse_from_str(&game.date, "%Y-%m-%d").ok()?;
+  let time = NaiveTime::parse_from_str(game.start_time.as_deref()?, "%H:%M").ok()?;
+  Some(date.and_time(time))
+}
+
+/// The span a game's reminder can be shown in: from the lead time until
+/// the game starts, or all of game day from the morning.
+fn window(game: &Game, lead_minutes: u32) -> Option<(NaiveDateTime, NaiveDateTime)> {
+  if let Some(start) = start_of(game) {
+    return Some((start - chrono::Duration::minutes(i64::from(lead_minutes)), start));
+  }
+  let date = NaiveDate::parse_from_str(&game.date, "%Y-%m-%d").ok()?;
+  Some((date.and_time(morning()), (date + Days::new(1)).and_time(NaiveTime::MIN)))
+}
+
+/// "in 45 minutes", "in 1 hour", "in 90 minutes", "in 3 hours".
+fn from_now(minutes: i64) -> String {
+  match minutes {
+    ..=1 => "in a minute".into(),
+    60 => "in 1 hour".into(),
+    m if m < 120 && m % 60 != 0 => format!("in {m} minutes"),
+    m => format!("in {} hours", (m + 30) / 60),
+  }
+}
+
+fn message(game: &Game, lineup_set: bool, now: NaiveDateTime) -> String {
+  let venue = if game.home_away == "away" { "at" } else { "vs" };
+  let when = match start_of(game) {
+    Some(start) => from_now((start - now).num_minutes()),
+    None => "today".into(),
+  };
+  let mut message = format!("Game {venue} {} {when}", game.opponent);
+  if !lineup_set {
+    message.push_str(" \u{2014} lineup not set!");
+  }
+  message
+}
+
+fn lineup_set(conn: &Connection, game_id: &str) -> Result<bool> {
+  Ok(conn.query_row(
+    "SELECT EXISTS (SELECT 1 FROM lineups l JOIN lineup_slots s ON s.lineup_id = l.id \
+       WHERE l.game_id = ?1 AND s.player_id IS NOT NULL)",
+    [game_id],
+    |row| row.get(0),
+  )?)
+}
+
+/// Reminders due at `now` (local time), recorded as shown.
+fn due(
+  conn: &Connection,
+  settings: &ReminderSettings,
+  now: NaiveDateTime,
+) -> Result<Vec<GameReminder>> {
+  let today = now.date();
+  let horizon = today + Days::new(u64::from(MAX_LEAD_MINUTES / (24 * 60)) + 1);
+  let ids: Vec<String> = conn
+    .prepare("SELECT id FROM games WHERE status = 'scheduled' AND date BETWEEN ?1 AND ?2")?
+    .query_map(
+      params![today.format("%Y-%m-%d").to_string(), horizon.format("%Y-%m-%d").to_string()],
+      |row| row.get(0),
+    )?
+    .collect::<rusqlite::Result<_>>()?;
+
+  let mut reminders = Vec::new();
+  for id in ids {
+    let game = games::get(conn, &id)?;
+    let Some((from, until)) = window(&game, settings.lead_minutes) else {
+      continue;
+    };
+    if now < from || now >= until {
+      continue;
+    }
+    let start = start_of(&game).unwrap_or_else(|| from.date().and_time(NaiveTime::MIN));
+    let starts_at = start.format(TIME_FORMAT).to_string();
+    let shown: Option<(String, Option<String>)> = conn
+      .query_row(
+        "SELECT starts_at, snoozed_until FROM game_reminders WHERE game_id = ?1",
+        [&id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+      )
+      .optional()?;
+    let snooze_over = |until: &str| {
+      NaiveDateTime::parse_from_str(until, TIME_FORMAT).map_or(true, |until| now >= until)
+    };
+    let is_due = match &shown {
+      Some((shown_for, snoozed)) if *shown_for == starts_at => {
+        snoozed.as_deref().is_some_and(snooze_over)
+      }
+      _ => true,
+    };
+    if !is_due {
+      continue;
+    }
+    conn.execute(
+      "INSERT OR REPLACE INTO game_reminders (game_id, starts_at, fired_at, snoozed_until) \
+       VALUES (?1, ?2, ?3, NULL)",
+      params![id, starts_at, now.format(TIME_FORMAT).to_string()],
+    )?;
+    let lineup_set = lineup_set(conn, &id)?;
+    reminders.push(GameReminder {
+      message: message(&game, lineup_set, now),
+      game_id: id,
+      lineup_set,
+    });
+  }
+  Ok(reminders)
+}
+
+fn snooze(conn: &Connection, game_id: &str, minutes: u32, now: NaiveDateTime) -> Result<()> {
+  let until = now + chrono::Duration::minutes(i64::from(minutes));
+  let updated = conn.execute(
+    "UPDATE game_reminders SET snoozed_until = ?2 WHERE game_id = ?1",
+    params![game_id, until.format(TIME_FORMAT).to_string()],
+  )?;
+  if updated == 0 {
+    return Err(Error::NotFound(format!("Reminder for game {game_id}")));
+  }
+  Ok(())
+}
+
+fn show(app: &AppHandle, reminder: &GameReminder) {
+  log::info!("Game reminder: {}", reminder.message);
+  let _ = app.emit(GAME_REMINDER_EVENT, reminder.clone());
+  let shown = app
+    .notification()
+    .builder()
+    .title("Game reminder")
+    .body(&reminder.message)
+    .show();
+  if let Err(e) = shown {
+    log::warn!("Failed to show game reminder: {}", e);
+  }
+}
+
+/// Starts the background task that shows game reminders.
+pub fn start_scheduler(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+      interval.tick().await;
+      let settings = app.state::<RwLock<AppSettings>>().read().unwrap().reminders.clone();
+      if !settings.enabled {
+        continue;
+      }
+      let handle = app.clone();
+      let reminders = tauri::async_runtime::spawn_blocking(move || {
+        let db = handle.state::<Database>();
+        let conn = db.conn();
+        due(&conn, &settings, Local::now().naive_local())
+      })
+      .await
+      .unwrap_or_else(|e| Err(e.into()));
+      match reminders {
+        Ok(reminders) => reminders.iter().for_each(|reminder| show(&app, reminder)),
+        Err(e) => log::error!("Checking game reminders failed: {}", e),
+      }
+    }
+  });
+}
+
+/// Shows a game's reminder again in `minutes` (default 15), as long as the
+/// game hasn't started by then.
+#[tauri::command]
+pub fn snooze_reminder(
+  db: State<'_, Database>,
+  game_id: String,
+  minutes: Option<u32>,
+) -> Result<()> {
+  let minutes = minutes.unwrap_or(DEFAULT_SNOOZE_MINUTES);
+  if minutes == 0 || minutes > MAX_SNOOZE_MINUTES {
+    return Err(Error::Validation(format!(
+      "Snooze must be between 1 and {MAX_SNOOZE_MINUTES} minutes"
+    )));
+  }
+  snooze(&db.conn(), &game_id, minutes, Local::now().naive_local())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::games::ScheduledGame;
+  use crate::db::lineups::{self, LineupInput, SlotInput};
+  use crate::db::roster::{self, NewPlayer};
+  use crate::db::open_in_memory;
+
+  fn at(time: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(time, TIME_FORMAT).unwrap()
+  }
+
+  fn game(id: &str, opponent: &str, start_time: Option<&str>) -> ScheduledGame {
+    ScheduledGame {
+      id: id.into(),
+      date: "2026-04-11".into(),
+      opponent: opponent.into(),
+      home_away: "home".into(),
+      score_us: None,
+      score_them: None,
+      notes: String::new(),
+      location: String::new(),
+      start_time: start_time.map(String::from),
+    }
+  }
+
+  #[test]
+  fn reminds_once_until_snoozed() {
+    let conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    games::upsert_scheduled(&conn, &team_id, &game("g1", "Tigers", Some("18:00"))).unwrap();
+    let settings = ReminderSettings::default();
+
+    assert!(due(&conn, &settings, at("2026-04-11T15:59")).unwrap().is_empty());
+    let reminders = due(&conn, &settings, at("2026-04-11T16:30")).unwrap();
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].message, "Game vs Tigers in 90 minutes \u{2014} lineup not set!");
+    assert!(due(&conn, &settings, at("2026-04-11T16:31")).unwrap().is_empty());
+
+    snooze(&conn, "g1", 15, at("2026-04-11T16:31")).unwrap();
+    assert!(due(&conn, &settings, at("2026-04-11T16:45")).unwrap().is_empty());
+    let reminders = due(&conn, &settings, at("2026-04-11T16:46")).unwrap();
+    assert_eq!(reminders[0].message, "Game vs Tigers in 74 minutes \u{2014} lineup not set!");
+    assert!(snooze(&conn, "g2", 15, at("2026-04-11T16:46")).is_err());
+
+    // Moved to later in the day: remind again for the new time
+    games::upsert_scheduled(&conn, &team_id, &game("g1", "Tigers", Some("19:30"))).unwrap();
+    assert!(due(&conn, &settings, at("2026-04-11T17:00")).unwrap().is_empty());
+    assert_eq!(due(&conn, &settings, at("2026-04-11T17:30")).unwrap().len(), 1);
+  }
+
+  #[test]
+  fn games_without_a_start_time_are_reminded_that_morning() {
+    let mut conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    games::upsert_scheduled(&conn, &team_id, &game("g1", "Cubs", None)).unwrap();
+    let player = roster::create(
+      &conn,
+      NewPlayer {
+        team_id: None,
+        name: "Jake Miller".into(),
+        number: Some(12),
+        primary_position: "SS".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap();
+    let input = LineupInput {
+      id: None,
+      team_id: None,
+      name: "Cubs".into(),
+      game_id: Some("g1".into()),
+      use_dh: false,
+      innings: 6,
+      slots: vec![SlotInput {
+        slot_number: 1,
+        player_id: Some(player.id),
+        position: Some("SS".into()),
+      }],
+      positions: Vec::new(),
+    };
+    lineups::save(&mut conn, input).unwrap();
+
+    let settings = ReminderSettings::default();
+    assert!(due(&conn, &settings, at("2026-04-11T08:59")).unwrap().is_empty());
+    let reminders = due(&conn, &settings, at("2026-04-11T09:00")).unwrap();
+    assert_eq!(reminders[0].message, "Game vs Cubs today");
+    assert!(reminders[0].lineup_set);
+    assert!(validate(&ReminderSettings { lead_minutes: 0, ..settings }).is_err());
+  }
+}
@@ -0,0 +1,189 @@
+//! Keeps an eye on how much CPU and memory the backend sidecar uses.
+//!
+//! The sidecar is sampled every few seconds. Past the warning cap the
+//! webview is told once, so it can suggest a restart; past the hard limit
+//! (a leak, usually after a long day of AI requests) the sidecar is
+//! restarted before it slows the whole machine down mid-game.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::{Error, Result};
+use crate::settings::AppSettings;
+use crate::sidecar::{self, SidecarManager};
+
+const MIB: u64 = 1 << 20;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Emitted with a [`ResourceUsage`] when the sidecar first goes over the
+/// warning cap, and again each time it does after dropping back under.
+pub const BACKEND_MEMORY_EVENT: &str = "backend-memory-warning";
+
+/// Memory caps for the sidecar's resident set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MemoryLimits {
+  pub warn_mib: u64,
+  /// Restart the sidecar past this; `None` never does.
+  pub restart_mib: Option<u64>,
+}
+
+impl Default for MemoryLimits {
+  fn default() -> Self {
+    Self {
+      warn_mib: 1024,
+      restart_mib: Some(2048),
+    }
+  }
+}
+
+pub fn validate(limits: &MemoryLimits) -> Result<()> {
+  if limits.warn_mib < 64 {
+    return Err(Error::Validation("The memory warning must be at least 64 MiB".into()));
+  }
+  if limits.restart_mib.is_some_and(|restart| restart <= limits.warn_mib) {
+    return Err(Error::Validation(
+      "The restart limit must be above the memory warning".into(),
+    ));
+  }
+  Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+  Normal,
+  Warn,
+  Restart,
+}
+
+fn level(memory: u64, limits: &MemoryLimits) -> Level {
+  if limits.restart_mib.is_some_and(|restart| memory > restart * MIB) {
+    Level::Restart
+  } else if memory > limits.warn_mib * MIB {
+    Level::Warn
+  } else {
+    Level::Normal
+  }
+}
+
+/// One sample of the sidecar process.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUsage {
+  pub pid: u32,
+  /// Percent of one core, so above 100 when it keeps several busy.
+  pub cpu_percent: f32,
+  /// Resident set size in bytes.
+  pub memory: u64,
+  /// Unix timestamp (milliseconds).
+  pub sampled_at: u64,
+  pub limits: MemoryLimits,
+}
+
+#[derive(Default)]
+pub struct ResourceMonitor {
+  latest: Mutex<Option<ResourceUsage>>,
+  /// Over the warning cap as of the last sample, so the event isn't
+  /// repeated every time.
+  warned: AtomicBool,
+}
+
+/// Samples the sidecar, or `None` if it isn't running.
+fn sample(system: &mut System, sidecar: &SidecarManager) -> Option<(u32, f32, u64)> {
+  let pid = sidecar.status().pid?;
+  let refresh = ProcessRefreshKind::nothing().with_cpu().with_memory();
+  let process = Pid::from_u32(pid);
+  system.refresh_processes_specifics(ProcessesToUpdate::Some(&[process]), true, refresh);
+  let process = system.process(process)?;
+  Some((pid, process.cpu_usage(), process.memory()))
+}
+
+/// Starts the background task that samples the sidecar.
+pub fn start_monitor(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let mut system = System::new();
+    loop {
+      tokio::time::sleep(SAMPLE_INTERVAL).await;
+      check(&app, &mut system).await;
+    }
+  });
+}
+
+async fn check(app: &AppHandle, system: &mut System) {
+  let monitor = app.state::<ResourceMonitor>();
+  let Some((pid, cpu_percent, memory)) = sample(system, &app.state::<SidecarManager>()) else {
+    *monitor.latest.lock().unwrap() = None;
+    return;
+  };
+  let limits = app.state::<RwLock<AppSettings>>().read().unwrap().backend_memory.clone();
+  let level = level(memory, &limits);
+  let usage = ResourceUsage {
+    pid,
+    cpu_percent,
+    memory,
+    sampled_at: sidecar::now_millis(),
+    limits,
+  };
+  *monitor.latest.lock().unwrap() = Some(usage.clone());
+
+  let over = level != Level::Normal;
+  if over && !monitor.warned.swap(true, Ordering::SeqCst) {
+    log::warn!("Backend sidecar is using {} MiB of memory", memory / MIB);
+    let _ = app.emit(BACKEND_MEMORY_EVENT, &usage);
+  } else if !over {
+    monitor.warned.store(false, Ordering::SeqCst);
+  }
+
+  if level == Level::Restart {
+    log::warn!("Restarting backend sidecar after it grew to {} MiB", memory / MIB);
+    let handle = app.clone();
+    let restarted = tauri::async_runtime::spawn_blocking(move || {
+      handle.state::<SidecarManager>().restart(&handle)
+    })
+    .await;
+    match restarted {
+      Ok(Ok(_)) => monitor.warned.store(false, Ordering::SeqCst),
+      Ok(Err(e)) => log::error!("{}", e),
+      Err(e) => log::error!("Failed to restart backend sidecar: {}", e),
+    }
+  }
+}
+
+/// The sidecar's most recent CPU and memory sample; `None` while it isn't
+/// running or before the first sample.
+#[tauri::command]
+pub fn get_backend_resource_usage(monitor: State<'_, ResourceMonitor>) -> Option<ResourceUsage> {
+  monitor.latest.lock().unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn warns_then_restarts_as_memory_grows() {
+    let limits = MemoryLimits::default();
+    assert_eq!(level(512 * MIB, &limits), Level::Normal);
+    assert_eq!(level(1500 * MIB, &limits), Level::Warn);
+    assert_eq!(level(3000 * MIB, &limits), Level::Restart);
+
+    let never_restart = MemoryLimits {
+      restart_mib: None,
+      ..limits.clone()
+    };
+    assert_eq!(level(3000 * MIB, &never_restart), Level::Warn);
+
+    assert!(validate(&limits).is_ok());
+    let backwards = MemoryLimits {
+      warn_mib: 4096,
+      ..limits
+    };
+    assert!(validate(&backwards).is_err());
+  }
+}
@@ -0,0 +1,622 @@
+//! League rules for playing time and positions, kept as data in settings
+//! so each league's constraints can be set up without code changes, and
+//! checked against a saved or proposed lineup with `validate_lineup`.
+//!
+//! Besides the league's rules, every lineup gets a few checks no game can
+//! be played without, like one player per position and a catcher. Each
+//! violation has a severity: errors should stop a lineup from being
+//! printed, warnings are for the coach to decide on.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::db::lineups::{self, Lineup, LineupInput, LineupSlot, MAX_INNINGS};
+use crate::db::roster::{self, Player};
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::optimizer::FIELD_POSITIONS;
+use crate::paths::AppPaths;
+use crate::settings::{self, AppSettings};
+
+/// One league constraint, e.g. `{ "kind": "maxBenchInnings", "max": 2 }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Rule {
+  /// Nobody in the batting order sits more than `max` innings.
+  MaxBenchInnings { max: u32 },
+  /// Nobody sits more than `max` innings in a row.
+  MaxConsecutiveBenchInnings { max: u32 },
+  /// Nobody plays `position` for more than `max` innings, e.g. catcher.
+  MaxInningsAtPosition { position: String, max: u32 },
+  /// Once a player leaves `position` they can't go back to it, e.g. a
+  /// pitcher who has been pulled.
+  NoReturnToPosition { position: String },
+  /// A player who comes off the field goes back on at most `max` times;
+  /// with 0, once out means out for the game.
+  MaxReentries { max: u32 },
+  /// Players only field their primary or secondary positions.
+  EligiblePositionsOnly,
+  /// Every fielding position is filled in every inning.
+  FullField,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+  /// The lineup can't be used as it is.
+  Error,
+  /// Allowed, but worth a second look.
+  Warning,
+}
+
+/// A broken rule. `rule` indexes the rules the lineup was checked against,
+/// and is `None` for the checks every lineup gets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Violation {
+  pub rule: Option<usize>,
+  pub kind: &'static str,
+  pub severity: Severity,
+  /// `None` for rules about the whole field rather than one player.
+  pub player_id: Option<String>,
+  pub innings: Vec<u32>,
+  pub message: String,
+}
+
+impl Rule {
+  fn kind(&self) -> &'static str {
+    match self {
+      Self::MaxBenchInnings { .. } => "maxBenchInnings",
+      Self::MaxConsecutiveBenchInnings { .. } => "maxConsecutiveBenchInnings",
+      Self::MaxInningsAtPosition { .. } => "maxInningsAtPosition",
+      Self::NoReturnToPosition { .. } => "noReturnToPosition",
+      Self::MaxReentries { .. } => "maxReentries",
+      Self::EligiblePositionsOnly => "eligiblePositionsOnly",
+      Self::FullField => "fullField",
+    }
+  }
+
+  /// Playing someone out of position is often a coaching choice rather
+  /// than against league rules.
+  fn severity(&self) -> Severity {
+    match self {
+      Self::EligiblePositionsOnly => Severity::Warning,
+      _ => Severity::Error,
+    }
+  }
+}
+
+/// Rejects rules that name an unknown position or can never be met.
+pub fn validate_rules(rules: &[Rule]) -> Result<()> {
+  for rule in rules {
+    match rule {
+      Rule::MaxInningsAtPosition { position, .. } | Rule::NoReturnToPosition { position }
+        if !FIELD_POSITIONS.contains(&position.as_str()) =>
+      {
+        return Err(Error::Validation(format!("{position} is not a fielding position")));
+      }
+      Rule::MaxInningsAtPosition { max: 0, .. } => {
+        return Err(Error::Validation("A position limit must allow at least one inning".into()));
+      }
+      _ => {}
+    }
+  }
+  Ok(())
+}
+
+/// Where each player is in each inning, `None` when on the bench.
+struct Grid<'a> {
+  innings: u32,
+  players: Vec<&'a str>,
+  lineup: &'a Lineup,
+}
+
+impl<'a> Grid<'a> {
+  /// The batting order, plus anyone fielding who isn't in it.
+  fn new(lineup: &'a Lineup) -> Self {
+    let mut players: Vec<&str> = Vec::new();
+    let ids = lineup
+      .slots
+      .iter()
+      .filter_map(|s| s.player_id.as_deref())
+      .chain(lineup.positions.iter().map(|p| p.player_id.as_str()));
+    for id in ids {
+      if !players.contains(&id) {
+        players.push(id);
+      }
+    }
+    Self {
+      innings: lineup.innings,
+      players,
+      lineup,
+    }
+  }
+
+  fn at(&self, player: &str, inning: u32) -> Option<&'a str> {
+    self.lineup.position_in(player, inning)
+  }
+
+  fn benched(&self, player: &str) -> Vec<u32> {
+    (1..=self.innings).filter(|&i| self.at(player, i).is_none()).collect()
+  }
+}
+
+/// Checks `lineup` against `rules`. `players` supplies names and
+/// eligibility; anyone missing from it is reported by id and not checked
+/// for eligibility.
+pub fn check(lineup: &Lineup, players: &[Player], rules: &[Rule]) -> Vec<Violation> {
+  let grid = Grid::new(lineup);
+  let player = |id: &str| players.iter().find(|p| p.id == id);
+  let name = |id: &str| player(id).map_or_else(|| id.to_string(), |p| p.name.clone());
+  let mut violations = Vec::new();
+
+  for (index, rule) in rules.iter().enumerate() {
+    let mut report = |player_id: Option<&str>, innings: Vec<u32>, message: String| {
+      violations.push(Violation {
+        rule: Some(index),
+        kind: rule.kind(),
+        severity: rule.severity(),
+        player_id: player_id.map(str::to_string),
+        innings,
+        message,
+      })
+    };
+
+    match rule {
+      Rule::MaxBenchInnings { max } => {
+        for &id in &grid.players {
+          let benched = grid.benched(id);
+          if benched.len() as u32 > *max {
+            let message = format!("{} sits {} innings (limit {max})", name(id), benched.len());
+            report(Some(id), benched, message);
+          }
+        }
+      }
+      Rule::MaxConsecutiveBenchInnings { max } => {
+        for &id in &grid.players {
+          let benched = grid.benched(id);
+          // Runs of consecutive innings, reported once each
+          for run in benched.chunk_by(|a, b| b - a == 1) {
+            if run.len() as u32 > *max {
+              let message = format!(
+                "{} sits {} innings in a row (limit {max})",
+                name(id),
+                run.len()
+              );
+              report(Some(id), run.to_vec(), message);
+            }
+          }
+        }
+      }
+      Rule::MaxInningsAtPosition { position, max } => {
+        for &id in &grid.players {
+          let innings: Vec<u32> = (1..=grid.innings)
+            .filter(|&i| grid.at(id, i) == Some(position.as_str()))
+            .collect();
+          if innings.len() as u32 > *max {
+            let message = format!(
+              "{} plays {position} for {} innings (limit {max})",
+              name(id),
+              innings.len()
+            );
+            report(Some(id), innings, message);
+          }
+        }
+      }
+      Rule::NoReturnToPosition { position } => {
+        for &id in &grid.players {
+          let mut left = false;
+          let mut returns = Vec::new();
+          for inning in 1..=grid.innings {
+            let here = grid.at(id, inning) == Some(position.as_str());
+            let before = inning > 1 && grid.at(id, inning - 1) == Some(position.as_str());
+            if before && !here {
+              left = true;
+            } else if here && !before && left {
+              returns.push(inning);
+            }
+          }
+          if !returns.is_empty() {
+            let message = format!("{} returns to {position} after leaving it", name(id));
+            report(Some(id), returns, message);
+          }
+        }
+      }
+      Rule::MaxReentries { max } => {
+        for &id in &grid.players {
+          let reentries: Vec<u32> = (2..=grid.innings)
+            .filter(|&i| grid.at(id, i).is_some() && grid.at(id, i - 1).is_none())
+            .filter(|&i| (1..i).any(|before| grid.at(id, before).is_some()))
+            .collect();
+          if reentries.len() as u32 > *max {
+            let message = match max {
+              0 => format!("{} goes back in after coming out", name(id)),
+              max => format!(
+                "{} goes back in {} times (limit {max})",
+                name(id),
+                reentries.len()
+              ),
+            };
+            report(Some(id), reentries[*max as usize..].to_vec(), message);
+          }
+        }
+      }
+      Rule::EligiblePositionsOnly => {
+        for &id in &grid.players {
+          let Some(player) = player(id) else {
+            continue;
+          };
+          let eligible = |position: &str| {
+            player.primary_position == position
+              || player.secondary_positions.iter().any(|p| p == position)
+          };
+          let mut by_position: Vec<(&str, Vec<u32>)> = Vec::new();
+          for inning in 1..=grid.innings {
+            match grid.at(id, inning) {
+              Some(position) if !eligible(position) => {
+                match by_position.iter_mut().find(|(p, _)| *p == position) {
+                  Some((_, innings)) => innings.push(inning),
+                  None => by_position.push((position, vec![inning])),
+                }
+              }
+              _ => {}
+            }
+          }
+          for (position, innings) in by_position {
+            let message = format!("{} isn't eligible to play {position}", player.name);
+            report(Some(id), innings, message);
+          }
+        }
+      }
+      Rule::FullField => {
+        let mut empty: HashMap<&str, Vec<u32>> = HashMap::new();
+        for inning in 1..=grid.innings {
+          for position in FIELD_POSITIONS {
+            let filled = lineup
+              .positions
+              .iter()
+              .any(|p| p.inning == inning && p.position == position);
+            if !filled {
+              empty.entry(position).or_default().push(inning);
+            }
+          }
+        }
+        // In field order rather than the map's
+        for position in FIELD_POSITIONS {
+          if let Some(innings) = empty.remove(position) {
+            let list: Vec<String> = innings.iter().map(u32::to_string).collect();
+            let message = format!("Nobody plays {position} in inning {}", list.join(", "));
+            report(None, innings, message);
+          }
+        }
+      }
+    }
+  }
+  violations
+}
+
+/// The checks every lineup gets whatever the league's rules: one player per
+/// position, one position per player, nobody batting twice and someone
+/// catching. A missing catcher isn't reported again when `rules` already
+/// include [`Rule::FullField`], or in innings nobody has been placed in yet.
+fn check_basics(lineup: &Lineup, players: &[Player], rules: &[Rule]) -> Vec<Violation> {
+  let name = |id: &str| {
+    players
+      .iter()
+      .find(|p| p.id == id)
+      .map_or_else(|| id.to_string(), |p| p.name.clone())
+  };
+  let mut violations = Vec::new();
+  let mut report = |kind: &'static str, player_id: Option<&str>, innings, message| {
+    violations.push(Violation {
+      rule: None,
+      kind,
+      severity: Severity::Error,
+      player_id: player_id.map(str::to_string),
+      innings,
+      message,
+    })
+  };
+
+  for position in FIELD_POSITIONS {
+    let crowded: Vec<u32> = (1..=lineup.innings)
+      .filter(|&inning| {
+        let at = lineup.positions.iter().filter(|p| p.inning == inning && p.position == position);
+        at.count() > 1
+      })
+      .collect();
+    if !crowded.is_empty() {
+      let message = format!("More than one player is at {position}");
+      report("duplicatePosition", None, crowded, message);
+    }
+  }
+
+  let grid = Grid::new(lineup);
+  for &id in &grid.players {
+    let doubled: Vec<u32> = (1..=lineup.innings)
+      .filter(|&inning| {
+        let at = lineup.positions.iter().filter(|p| p.inning == inning && p.player_id == id);
+        at.count() > 1
+      })
+      .collect();
+    if !doubled.is_empty() {
+      let message = format!("{} is at two positions at once", name(id));
+      report("twoPositions", Some(id), doubled, message);
+    }
+    let spots = lineup
+      .slots
+      .iter()
+      .filter(|s| s.player_id.as_deref() == Some(id))
+      .count();
+    if spots > 1 {
+      let message = format!("{} is in the batting order {spots} times", name(id));
+      report("duplicateBatter", Some(id), Vec::new(), message);
+    }
+  }
+
+  if !rules.contains(&Rule::FullField) {
+    let uncaught: Vec<u32> = (1..=lineup.innings)
+      .filter(|&inning| {
+        let mut placed = lineup.positions.iter().filter(|p| p.inning == inning).peekable();
+        placed.peek().is_some() && !placed.any(|p| p.position == "C")
+      })
+      .collect();
+    if !uncaught.is_empty() {
+      let list: Vec<String> = uncaught.iter().map(u32::to_string).collect();
+      let message = format!("Nobody catches in inning {}", list.join(", "));
+      report("missingCatcher", None, uncaught, message);
+    }
+  }
+  violations
+}
+
+/// [`check`], after the checks every lineup gets.
+pub fn check_all(lineup: &Lineup, players: &[Player], rules: &[Rule]) -> Vec<Violation> {
+  let mut violations = check_basics(lineup, players, rules);
+  violations.extend(check(lineup, players, rules));
+  violations
+}
+
+/// `rules` if given and sensible, otherwise the league rules in settings.
+pub(crate) fn rules_or_league(
+  settings: &RwLock<AppSettings>,
+  rules: Option<Vec<Rule>>,
+) -> Result<Vec<Rule>> {
+  match rules {
+    Some(rules) => {
+      validate_rules(&rules)?;
+      Ok(rules)
+    }
+    None => Ok(settings.read().unwrap().league_rules.clone()),
+  }
+}
+
+/// The proposed `lineup` if there is one, otherwise the saved lineup `id`.
+pub(crate) fn load(
+  conn: &Connection,
+  id: Option<String>,
+  lineup: Option<LineupInput>,
+) -> Result<Lineup> {
+  match (lineup, id) {
+    (Some(input), _) => proposed(conn, input),
+    (None, Some(id)) => lineups::get(conn, &id),
+    (None, None) => Err(Error::Validation("No lineup to check".into())),
+  }
+}
+
+/// A lineup that hasn't been saved, in the shape [`check`] takes.
+fn proposed(conn: &Connection, input: LineupInput) -> Result<Lineup> {
+  if !(1..=MAX_INNINGS).contains(&input.innings) {
+    return Err(Error::Validation(format!("Innings must be between 1 and {MAX_INNINGS}")));
+  }
+  let team_id = match input.team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(conn)?,
+  };
+  Ok(Lineup {
+    id: input.id.unwrap_or_default(),
+    team_id,
+    name: input.name,
+    game_id: input.game_id,
+    use_dh: input.use_dh,
+    innings: input.innings,
+    slots: input
+      .slots
+      .into_iter()
+      .map(|slot| LineupSlot {
+        slot_number: slot.slot_number,
+        player_id: slot.player_id,
+        player_name: None,
+        number: None,
+        position: slot.position,
+      })
+      .collect(),
+    positions: input.positions,
+    updated_at: String::new(),
+    versions: Default::default(),
+  })
+}
+
+#[tauri::command]
+pub fn get_league_rules(settings: State<'_, RwLock<AppSettings>>) -> Vec<Rule> {
+  settings.read().unwrap().league_rules.clone()
+}
+
+/// Replaces the league rules lineups are checked against.
+#[tauri::command]
+pub fn set_league_rules(
+  app: AppHandle,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+  rules: Vec<Rule>,
+) -> Result<Vec<Rule>> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  validate_rules(&rules)?;
+  let mut settings = settings.write().unwrap();
+  settings.league_rules = rules;
+  settings::save(&paths.settings, &settings)?;
+  Ok(settings.league_rules.clone())
+}
+
+/// Checks the saved lineup `id`, or a proposed `lineup` that hasn't been
+/// saved yet, against `rules`, or the league rules in settings when
+/// omitted. An empty list means the lineup follows every rule.
+#[tauri::command]
+pub fn validate_lineup(
+  db: State<'_, Database>,
+  settings: State<'_, RwLock<AppSettings>>,
+  id: Option<String>,
+  lineup: Option<LineupInput>,
+  rules: Option<Vec<Rule>>,
+) -> Result<Vec<Violation>> {
+  let rules = rules_or_league(&settings, rules)?;
+  let conn = db.conn();
+  let lineup = load(&conn, id, lineup)?;
+  let players = roster::list(&conn, &lineup.team_id)?;
+  Ok(check_all(&lineup, &players, &rules))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::lineups::InningPosition;
+
+  fn lineup(innings: u32, positions: &[(u32, &str, &str)]) -> Lineup {
+    Lineup {
+      id: "l1".into(),
+      team_id: "t1".into(),
+      name: "Game 1".into(),
+      game_id: None,
+      use_dh: false,
+      innings,
+      slots: Vec::new(),
+      positions: positions
+        .iter()
+        .map(|&(inning, position, player_id)| InningPosition {
+          inning,
+          position: position.into(),
+          player_id: player_id.into(),
+        })
+        .collect(),
+      updated_at: String::new(),
+      versions: Default::default(),
+    }
+  }
+
+  fn kinds(violations: &[Violation]) -> Vec<(&str, Option<&str>, Vec<u32>)> {
+    violations
+      .iter()
+      .map(|v| (v.kind, v.player_id.as_deref(), v.innings.clone()))
+      .collect()
+  }
+
+  #[test]
+  fn reports_bench_and_position_limits() {
+    let plan = lineup(
+      5,
+      &[
+        (1, "C", "cat"),
+        (2, "C", "cat"),
+        (3, "C", "cat"),
+        (4, "C", "cat"),
+        (1, "1B", "sub"),
+        (5, "1B", "sub"),
+      ],
+    );
+    let rules = [
+      Rule::MaxBenchInnings { max: 2 },
+      Rule::MaxConsecutiveBenchInnings { max: 2 },
+      Rule::MaxInningsAtPosition {
+        position: "C".into(),
+        max: 3,
+      },
+    ];
+    assert_eq!(
+      kinds(&check(&plan, &[], &rules)),
+      [
+        ("maxBenchInnings", Some("sub"), vec![2, 3, 4]),
+        ("maxConsecutiveBenchInnings", Some("sub"), vec![2, 3, 4]),
+        ("maxInningsAtPosition", Some("cat"), vec![1, 2, 3, 4]),
+      ]
+    );
+  }
+
+  #[test]
+  fn a_pulled_pitcher_cannot_return() {
+    let plan = lineup(
+      4,
+      &[(1, "P", "ace"), (2, "SS", "ace"), (3, "P", "ace"), (4, "P", "ace")],
+    );
+    let rules = [
+      Rule::NoReturnToPosition {
+        position: "P".into(),
+      },
+      Rule::FullField,
+    ];
+    let violations = check(&plan, &[], &rules);
+    assert_eq!(violations[0].innings, [3]);
+    assert_eq!(violations[0].rule, Some(0));
+    // Everything but P and, in inning 2, SS is empty
+    assert_eq!(violations.iter().filter(|v| v.kind == "fullField").count(), 9);
+    assert!(violations.iter().all(|v| v.severity == Severity::Error));
+    assert!(validate_rules(&[Rule::NoReturnToPosition {
+      position: "DH".into()
+    }])
+    .is_err());
+  }
+
+  #[test]
+  fn every_lineup_gets_the_basic_checks() {
+    let mut plan = lineup(
+      3,
+      &[
+        (1, "C", "cat"),
+        (1, "SS", "ace"),
+        (1, "SS", "sub"),
+        (2, "1B", "ace"),
+        (2, "2B", "ace"),
+        (3, "C", "cat"),
+        (3, "P", "sub"),
+      ],
+    );
+    plan.slots = ["ace", "cat", "ace"]
+      .iter()
+      .enumerate()
+      .map(|(i, id)| LineupSlot {
+        slot_number: i as u32 + 1,
+        player_id: Some(id.to_string()),
+        player_name: None,
+        number: None,
+        position: None,
+      })
+      .collect();
+    assert_eq!(
+      kinds(&check_basics(&plan, &[], &[])),
+      [
+        ("duplicatePosition", None, vec![1]),
+        ("twoPositions", Some("ace"), vec![2]),
+        ("duplicateBatter", Some("ace"), vec![]),
+        ("missingCatcher", None, vec![2]),
+      ]
+    );
+    // Left to the full-field rule when the league has it
+    assert_eq!(check_basics(&plan, &[], &[Rule::FullField]).len(), 3);
+
+    // The catcher and the sub sit the second inning and go back in
+    let rules = [Rule::MaxReentries { max: 0 }, Rule::EligiblePositionsOnly];
+    let violations = check(&plan, &[], &rules);
+    assert_eq!(
+      kinds(&violations),
+      [
+        ("maxReentries", Some("cat"), vec![3]),
+        ("maxReentries", Some("sub"), vec![3]),
+      ]
+    );
+    assert_eq!(violations[0].severity, Severity::Error);
+    assert_eq!(Rule::EligiblePositionsOnly.severity(), Severity::Warning);
+  }
+}
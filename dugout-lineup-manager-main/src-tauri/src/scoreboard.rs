@@ -0,0 +1,277 @@
+//! Live scoreboard shared between the main window and a secondary,
+//! frameless window meant for a TV or projector.
+//!
+//! The main window pushes updates with `update_scoreboard`; the latest state
+//! is kept here so a scoreboard opened mid-game starts out current. The
+//! overlay server watches it too, for viewers outside the app, and so does
+//! the serial output to a scoreboard controller.
+//!
+//! Each controller takes its own frame format, described by a
+//! [`ScoreboardProtocol`] template. A few generic ones are built in and
+//! vendors' are added in the settings.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use tokio::sync::watch;
+
+use crate::error::{Error, Result};
+
+pub const SCOREBOARD_WINDOW: &str = "scoreboard";
+
+/// Event carrying the full [`Scoreboard`] after every update.
+pub const SCOREBOARD_EVENT: &str = "scoreboard-update";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Scoreboard {
+  pub team_name: String,
+  pub opponent: String,
+  pub score_us: u32,
+  pub score_them: u32,
+  pub inning: u32,
+  /// `true` in the top half of the inning.
+  pub top_of_inning: bool,
+  /// Whether we're the home team, for scoreboards that show home and guest.
+  pub home: bool,
+  pub balls: u8,
+  pub strikes: u8,
+  pub outs: u8,
+  /// Player names in batting order.
+  pub batting_order: Vec<String>,
+  /// Index into `batting_order` of the player at bat.
+  pub current_batter: Option<usize>,
+}
+
+/// How a scoreboard controller expects the score. `template` is sent as
+/// is except for placeholders: `{us}`, `{them}`, `{home}`, `{guest}`,
+/// `{inning}`, `{balls}`, `{strikes}` and `{outs}`, optionally padded to a
+/// width with zeros as in `{home:2}`; `{half}` for `T` or `B`; and `{stx}`,
+/// `{etx}`, `{cr}` and `{lf}` for control characters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreboardProtocol {
+  pub id: String,
+  pub name: String,
+  pub baud_rate: u32,
+  pub template: String,
+  /// Sends the frame again this often even when nothing changed, for
+  /// controllers that blank out without a steady signal.
+  #[serde(default)]
+  pub resend_ms: Option<u64>,
+}
+
+/// Controllers that take plain text over the wire.
+pub fn built_in_protocols() -> Vec<ScoreboardProtocol> {
+  vec![
+    ScoreboardProtocol {
+      id: "text-line".into(),
+      name: "Text line".into(),
+      baud_rate: 9600,
+      template: "H{home:2} G{guest:2} I{inning:2}{half} B{balls} S{strikes} O{outs}{cr}{lf}"
+        .into(),
+      resend_ms: None,
+    },
+    ScoreboardProtocol {
+      id: "stx-frame".into(),
+      name: "Fixed-width STX/ETX frame".into(),
+      baud_rate: 9600,
+      template: "{stx}{home:3}{guest:3}{inning:2}{balls}{strikes}{outs}{etx}".into(),
+      resend_ms: Some(1000),
+    },
+  ]
+}
+
+/// The protocol named `id`, from the settings or built in.
+pub fn protocol(custom: &[ScoreboardProtocol], id: &str) -> Result<ScoreboardProtocol> {
+  custom
+    .iter()
+    .cloned()
+    .chain(built_in_protocols())
+    .find(|p| p.id == id)
+    .ok_or_else(|| Error::NotFound(format!("Scoreboard protocol {id}")))
+}
+
+/// The frame for `board`, which fails only on a template that
+/// [`validate_protocols`] would reject.
+pub fn render(template: &str, board: &Scoreboard) -> Result<Vec<u8>> {
+  let mut frame = Vec::new();
+  let mut rest = template;
+  while let Some(open) = rest.find('{') {
+    frame.extend_from_slice(&rest.as_bytes()[..open]);
+    let close = rest[open..]
+      .find('}')
+      .ok_or_else(|| Error::Validation(format!("Unclosed placeholder in {template:?}")))?;
+    let placeholder = &rest[open + 1..open + close];
+    rest = &rest[open + close + 1..];
+
+    let (name, width) = match placeholder.split_once(':') {
+      Some((name, width)) => match width.parse::<usize>() {
+        Ok(width @ 1..=4) => (name, Some(width)),
+        _ => return Err(Error::Validation(format!("Bad width in {{{placeholder}}}"))),
+      },
+      None => (placeholder, None),
+    };
+    let (home, guest) = if board.home {
+      (board.score_us, board.score_them)
+    } else {
+      (board.score_them, board.score_us)
+    };
+    let number = match name {
+      "us" => board.score_us,
+      "them" => board.score_them,
+      "home" => home,
+      "guest" => guest,
+      "inning" => board.inning,
+      "balls" => u32::from(board.balls),
+      "strikes" => u32::from(board.strikes),
+      "outs" => u32::from(board.outs),
+      _ => {
+        let control = match name {
+          "half" if board.top_of_inning => b'T',
+          "half" => b'B',
+          "stx" => 0x02,
+          "etx" => 0x03,
+          "cr" => b'\r',
+          "lf" => b'\n',
+          _ => return Err(Error::Validation(format!("Unknown placeholder {{{placeholder}}}"))),
+        };
+        if width.is_some() {
+          return Err(Error::Validation(format!("{{{name}}} has no width")));
+        }
+        frame.push(control);
+        continue;
+      }
+    };
+    let digits = match width {
+      // Only the low digits fit, as on a two-digit scoreboard past 99
+      Some(width) => {
+        let text = format!("{number:0width$}");
+        text[text.len() - width..].to_string()
+      }
+      None => number.to_string(),
+    };
+    frame.extend_from_slice(digits.as_bytes());
+  }
+  frame.extend_from_slice(rest.as_bytes());
+  Ok(frame)
+}
+
+pub fn validate_protocols(protocols: &[ScoreboardProtocol]) -> Result<()> {
+  for protocol in protocols {
+    if protocol.id.trim().is_empty() || protocol.name.trim().is_empty() {
+      return Err(Error::Validation("Scoreboard protocols need an id and a name".into()));
+    }
+    if protocol.baud_rate == 0 {
+      return Err(Error::Validation(format!("{} needs a baud rate", protocol.name)));
+    }
+    if protocol.template.is_empty() || !protocol.template.is_ascii() {
+      return Err(Error::Validation(format!("{} needs an ASCII template", protocol.name)));
+    }
+    if protocol.resend_ms.is_some_and(|ms| ms < 100) {
+      return Err(Error::Validation(format!(
+        "{} can't be resent more than 10 times a second",
+        protocol.name
+      )));
+    }
+    render(&protocol.template, &Scoreboard::default())?;
+  }
+  Ok(())
+}
+
+pub struct ScoreboardState(watch::Sender<Scoreboard>);
+
+impl Default for ScoreboardState {
+  fn default() -> Self {
+    Self(watch::channel(Scoreboard::default()).0)
+  }
+}
+
+impl ScoreboardState {
+  pub fn current(&self) -> Scoreboard {
+    self.0.borrow().clone()
+  }
+
+  /// A receiver that sees every later update.
+  pub fn subscribe(&self) -> watch::Receiver<Scoreboard> {
+    self.0.subscribe()
+  }
+}
+
+#[tauri::command]
+pub fn get_scoreboard(state: State<'_, ScoreboardState>) -> Scoreboard {
+  state.current()
+}
+
+/// Replaces the scoreboard and broadcasts it to every window.
+#[tauri::command]
+pub fn update_scoreboard(
+  app: AppHandle,
+  state: State<'_, ScoreboardState>,
+  scoreboard: Scoreboard,
+) -> Result<()> {
+  state.0.send_replace(scoreboard.clone());
+  app.emit(SCOREBOARD_EVENT, scoreboard)?;
+  Ok(())
+}
+
+/// Opens the scoreboard window, or brings it forward if already open.
+#[tauri::command]
+pub async fn open_scoreboard_window(app: AppHandle, always_on_top: Option<bool>) -> Result<()> {
+  let always_on_top = always_on_top.unwrap_or(false);
+  if let Some(window) = app.get_webview_window(SCOREBOARD_WINDOW) {
+    window.set_always_on_top(always_on_top)?;
+    window.show()?;
+    window.set_focus()?;
+    return Ok(());
+  }
+  let window =
+    WebviewWindowBuilder::new(&app, SCOREBOARD_WINDOW, WebviewUrl::App("scoreboard".into()))
+      .title("Dugout Scoreboard")
+      .inner_size(960.0, 540.0)
+      .min_inner_size(320.0, 180.0)
+      .decorations(false)
+      .always_on_top(always_on_top)
+      .visible(false)
+      .build()?;
+  // Back on the TV or projector it was on last time, if still attached
+  crate::window_state::restore(&window);
+  window.show()?;
+  Ok(())
+}
+
+#[tauri::command]
+pub fn close_scoreboard_window(app: AppHandle) -> Result<()> {
+  if let Some(window) = app.get_webview_window(SCOREBOARD_WINDOW) {
+    window.close()?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn renders_protocol_templates() {
+    let board = Scoreboard {
+      score_us: 104,
+      score_them: 3,
+      inning: 7,
+      top_of_inning: false,
+      home: true,
+      balls: 2,
+      strikes: 1,
+      outs: 2,
+      ..Default::default()
+    };
+    let [text, stx] = built_in_protocols().try_into().unwrap();
+    assert_eq!(render(&text.template, &board).unwrap(), b"H04 G03 I07B B2 S1 O2\r\n");
+    assert_eq!(render(&stx.template, &board).unwrap(), b"\x0210400307212\x03");
+    assert_eq!(render("{us}-{them}", &board).unwrap(), b"104-3");
+
+    assert!(validate_protocols(&built_in_protocols()).is_ok());
+    for template in ["{home", "{score}", "{half:2}", "{outs:9}"] {
+      assert!(render(template, &board).is_err(), "{template}");
+    }
+  }
+}
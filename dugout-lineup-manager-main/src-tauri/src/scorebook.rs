@@ -0,0 +1,968 @@
+//! Play-by-play scorekeeping, done natively so a game can be scored with
+//! the AI sidecar down.
+//!
+//! Each game's plays are an append-only log in `scorebook_events`. The
+//! count, base runners, line score and box score are never stored; they're
+//! replayed from the log, so undoing a play just drops the newest entry.
+//! The first entry snapshots the game's batting order, so editing the saved
+//! lineup afterwards doesn't rewrite a game in progress.
+//!
+//! Our batters are tracked by player. The opponent's half innings only
+//! count outs, runs, hits and errors.
+
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::db::{games, lineups, roster, timestamp, Database};
+use crate::error::{Error, Result};
+
+/// Event carrying the [`BoxScore`] after every recorded or undone play.
+pub const SCOREBOOK_EVENT: &str = "scorebook-updated";
+
+const OUTS_PER_HALF: u8 = 3;
+
+/// Fastest pitch speed accepted, in mph; faster is a misread.
+pub(crate) const MAX_PITCH_MPH: f64 = 110.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Base {
+  First,
+  Second,
+  Third,
+}
+
+impl Base {
+  const ALL: [Base; 3] = [Base::First, Base::Second, Base::Third];
+
+  fn index(self) -> usize {
+    self as usize
+  }
+
+  fn name(self) -> &'static str {
+    match self {
+      Self::First => "first",
+      Self::Second => "second",
+      Self::Third => "third",
+    }
+  }
+}
+
+/// Where a runner ended up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Destination {
+  First,
+  Second,
+  Third,
+  Home,
+  Out,
+}
+
+impl Destination {
+  /// Bases from home plate, 4 for scoring; `None` for an out.
+  fn bases(self) -> Option<usize> {
+    match self {
+      Self::First => Some(1),
+      Self::Second => Some(2),
+      Self::Third => Some(3),
+      Self::Home => Some(4),
+      Self::Out => None,
+    }
+  }
+
+  fn after(bases: usize) -> Self {
+    match bases {
+      1 => Self::First,
+      2 => Self::Second,
+      3 => Self::Third,
+      _ => Self::Home,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerMove {
+  pub from: Base,
+  pub to: Destination,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlayResult {
+  Single,
+  Double,
+  Triple,
+  HomeRun,
+  Walk,
+  HitByPitch,
+  Strikeout,
+  /// A ground ball, fly or line drive caught for an out.
+  Out,
+  SacrificeFly,
+  SacrificeBunt,
+  FieldersChoice,
+  ReachedOnError,
+}
+
+impl PlayResult {
+  /// Bases the batter takes, or `None` when the batter is out.
+  fn batter_bases(self) -> Option<usize> {
+    match self {
+      Self::Single
+      | Self::Walk
+      | Self::HitByPitch
+      | Self::FieldersChoice
+      | Self::ReachedOnError => Some(1),
+      Self::Double => Some(2),
+      Self::Triple => Some(3),
+      Self::HomeRun => Some(4),
+      Self::Strikeout | Self::Out | Self::SacrificeFly | Self::SacrificeBunt => None,
+    }
+  }
+
+  fn is_hit(self) -> bool {
+    matches!(self, Self::Single | Self::Double | Self::Triple | Self::HomeRun)
+  }
+
+  fn is_at_bat(self) -> bool {
+    !matches!(
+      self,
+      Self::Walk | Self::HitByPitch | Self::SacrificeFly | Self::SacrificeBunt
+    )
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RunnerPlay {
+  StolenBase,
+  CaughtStealing,
+  PickedOff,
+  WildPitch,
+  PassedBall,
+  Balk,
+  /// Anything else, e.g. taking an extra base on a throw.
+  Advance,
+}
+
+/// One entry in a game's log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Event {
+  /// Player ids in batting order, written as a game's first entry.
+  StartingLineup { batting_order: Vec<String> },
+  PlateAppearance {
+    /// Our batter; filled in from the batting order when left out.
+    #[serde(default)]
+    batter_id: Option<String>,
+    result: PlayResult,
+    /// Where runners ended up, replacing the usual advances for the
+    /// result. Runners that aren't listed stay put.
+    #[serde(default)]
+    runners: Option<Vec<RunnerMove>>,
+    /// Overrides the runs batted in, which default to the runs scored on
+    /// anything but an error.
+    #[serde(default)]
+    rbi: Option<u32>,
+  },
+  /// A runner moving between plate appearances.
+  Baserunning {
+    play: RunnerPlay,
+    from: Base,
+    to: Destination,
+  },
+  /// `player_in` takes `player_out`'s place in the batting order, and on
+  /// base for a pinch runner.
+  Substitution {
+    player_in: String,
+    player_out: String,
+    #[serde(default)]
+    position: Option<String>,
+  },
+  /// A pitch clocked by a radar gun. It doesn't change the count or score.
+  Pitch { mph: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Play {
+  pub seq: u32,
+  pub event: Event,
+  pub created_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Runner {
+  /// `None` for the opponent's runners.
+  pub player_id: Option<String>,
+}
+
+/// Runs, hits and errors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamLine {
+  pub runs: u32,
+  pub hits: u32,
+  pub errors: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InningScore {
+  pub inning: u32,
+  pub us: u32,
+  pub them: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatterLine {
+  pub player_id: String,
+  pub name: String,
+  pub number: Option<u32>,
+  pub pa: u32,
+  pub ab: u32,
+  pub r: u32,
+  pub h: u32,
+  pub doubles: u32,
+  pub triples: u32,
+  pub hr: u32,
+  pub rbi: u32,
+  pub bb: u32,
+  pub so: u32,
+  pub sb: u32,
+  pub cs: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoxScore {
+  pub game_id: String,
+  pub inning: u32,
+  pub top_of_inning: bool,
+  pub outs: u8,
+  /// Runners on first, second and third.
+  pub bases: [Option<Runner>; 3],
+  /// Whether our team is at bat.
+  pub batting: bool,
+  /// Our next batter.
+  pub due_up: Option<String>,
+  pub us: TeamLine,
+  pub them: TeamLine,
+  /// Runs by inning, for every inning started.
+  pub innings: Vec<InningScore>,
+  /// Starters in batting order, then substitutes as they came in.
+  pub batters: Vec<BatterLine>,
+  /// Plays recorded, not counting the starting lineup or pitch speeds.
+  pub plays: usize,
+  pub last_pitch_mph: Option<f64>,
+  pub top_pitch_mph: Option<f64>,
+}
+
+/// The state of a game partway through its log.
+struct Replay {
+  /// Whether we bat in the bottom half.
+  home: bool,
+  inning: u32,
+  top: bool,
+  outs: u8,
+  bases: [Option<Runner>; 3],
+  order: Vec<String>,
+  /// Index into `order` of the next batter.
+  next: usize,
+  us: TeamLine,
+  them: TeamLine,
+  innings: Vec<InningScore>,
+  batters: Vec<BatterLine>,
+  plays: usize,
+  last_pitch_mph: Option<f64>,
+  top_pitch_mph: Option<f64>,
+}
+
+impl Replay {
+  fn new(home: bool) -> Self {
+    Self {
+      home,
+      inning: 1,
+      top: true,
+      outs: 0,
+      bases: Default::default(),
+      order: Vec::new(),
+      next: 0,
+      us: TeamLine::default(),
+      them: TeamLine::default(),
+      innings: vec![InningScore {
+        inning: 1,
+        us: 0,
+        them: 0,
+      }],
+      batters: Vec::new(),
+      plays: 0,
+      last_pitch_mph: None,
+      top_pitch_mph: None,
+    }
+  }
+
+  fn run(home: bool, events: &[Event]) -> Result<Self> {
+    let mut replay = Self::new(home);
+    for event in events {
+      replay.apply(event)?;
+    }
+    Ok(replay)
+  }
+
+  /// Whether our team is at bat; the visitors bat in the top half.
+  fn batting(&self) -> bool {
+    self.top != self.home
+  }
+
+  fn due_up(&self) -> Option<&String> {
+    self.order.get(self.next)
+  }
+
+  fn batter(&mut self, player_id: &str) -> &mut BatterLine {
+    let at = match self.batters.iter().position(|b| b.player_id == player_id) {
+      Some(at) => at,
+      None => {
+        self.batters.push(BatterLine {
+          player_id: player_id.to_string(),
+          ..Default::default()
+        });
+        self.batters.len() - 1
+      }
+    };
+    &mut self.batters[at]
+  }
+
+  fn side(&mut self, batting: bool) -> &mut TeamLine {
+    if batting == self.batting() {
+      &mut self.us
+    } else {
+      &mut self.them
+    }
+  }
+
+  fn score(&mut self, runner: Runner) {
+    let batting = self.batting();
+    let inning = self.innings.last_mut().expect("innings start with the first");
+    if batting {
+      inning.us += 1;
+      self.us.runs += 1;
+    } else {
+      inning.them += 1;
+      self.them.runs += 1;
+    }
+    if let Some(player_id) = runner.player_id {
+      self.batter(&player_id).r += 1;
+    }
+  }
+
+  fn end_half_if_over(&mut self) {
+    if self.outs < OUTS_PER_HALF {
+      return;
+    }
+    self.outs = 0;
+    self.bases = Default::default();
+    if self.top {
+      self.top = false;
+    } else {
+      self.top = true;
+      self.inning += 1;
+      self.innings.push(InningScore {
+        inning: self.inning,
+        us: 0,
+        them: 0,
+      });
+    }
+  }
+
+  fn apply(&mut self, event: &Event) -> Result<()> {
+    match event {
+      Event::StartingLineup { batting_order } => {
+        self.order = batting_order.clone();
+        for player_id in batting_order {
+          self.batter(player_id);
+        }
+        return Ok(());
+      }
+      Event::PlateAppearance {
+        batter_id,
+        result,
+        runners,
+        rbi,
+      } => self.plate_appearance(batter_id.as_deref(), *result, runners.as_deref(), *rbi)?,
+      Event::Baserunning { play, from, to } => self.baserunning(*play, *from, *to)?,
+      Event::Substitution {
+        player_in,
+        player_out,
+        ..
+      } => self.substitute(player_in, player_out)?,
+      Event::Pitch { mph } => {
+        if !(*mph > 0.0 && *mph <= MAX_PITCH_MPH) {
+          return Err(Error::Validation(format!("{mph} mph isn't a pitch speed")));
+        }
+        self.last_pitch_mph = Some(*mph);
+        self.top_pitch_mph = Some(self.top_pitch_mph.map_or(*mph, |top| top.max(*mph)));
+        return Ok(());
+      }
+    }
+    self.plays += 1;
+    self.end_half_if_over();
+    Ok(())
+  }
+
+  fn plate_appearance(
+    &mut self,
+    batter_id: Option<&str>,
+    result: PlayResult,
+    runners: Option<&[RunnerMove]>,
+    rbi: Option<u32>,
+  ) -> Result<()> {
+    let batting = self.batting();
+    let batter_id = match batter_id {
+      Some(id) if batting => Some(id.to_string()),
+      None if batting => {
+        return Err(Error::Validation("Choose who is batting".into()));
+      }
+      _ => None,
+    };
+    let moves = match runners {
+      Some(moves) => moves.to_vec(),
+      None => default_moves(result, &self.bases)?,
+    };
+
+    let mut on_base = std::mem::take(&mut self.bases);
+    let mut bases: [Option<Runner>; 3] = Default::default();
+    let mut outs = 0;
+    let mut scored = Vec::new();
+    for m in &moves {
+      let runner = on_base[m.from.index()]
+        .take()
+        .ok_or_else(|| Error::Validation(format!("No runner is on {}", m.from.name())))?;
+      match m.to.bases() {
+        None => outs += 1,
+        Some(4) => scored.push(runner),
+        Some(to) if to <= m.from.index() => {
+          return Err(Error::Validation("Runners can't go back a base".into()));
+        }
+        Some(to) => place(&mut bases, to - 1, runner)?,
+      }
+    }
+    for (at, runner) in on_base.into_iter().enumerate() {
+      if let Some(runner) = runner {
+        place(&mut bases, at, runner)?;
+      }
+    }
+    let batter = Runner {
+      player_id: batter_id.clone(),
+    };
+    match result.batter_bases() {
+      None => outs += 1,
+      Some(4) => scored.push(batter),
+      Some(to) => place(&mut bases, to - 1, batter)?,
+    }
+
+    // No run counts when the third out is the batter or a force.
+    let force_ends_half = self.outs + outs >= OUTS_PER_HALF
+      && (result.batter_bases().is_none() || result == PlayResult::FieldersChoice);
+    let runs = if force_ends_half { 0 } else { scored.len() as u32 };
+    for runner in scored.into_iter().take(runs as usize) {
+      self.score(runner);
+    }
+    self.outs += outs;
+    self.bases = bases;
+    if result.is_hit() {
+      self.side(true).hits += 1;
+    }
+    if result == PlayResult::ReachedOnError {
+      self.side(false).errors += 1;
+    }
+
+    if let Some(batter_id) = batter_id {
+      let line = self.batter(&batter_id);
+      line.pa += 1;
+      line.ab += u32::from(result.is_at_bat());
+      line.h += u32::from(result.is_hit());
+      line.doubles += u32::from(result == PlayResult::Double);
+      line.triples += u32::from(result == PlayResult::Triple);
+      line.hr += u32::from(result == PlayResult::HomeRun);
+      line.bb += u32::from(result == PlayResult::Walk);
+      line.so += u32::from(result == PlayResult::Strikeout);
+      line.rbi += rbi.unwrap_or(if result == PlayResult::ReachedOnError {
+        0
+      } else {
+        runs
+      });
+      // Batting out of turn moves the order along from whoever batted.
+      if let Some(at) = self.order.iter().position(|id| *id == batter_id) {
+        self.next = (at + 1) % self.order.len();
+      }
+    }
+    Ok(())
+  }
+
+  fn baserunning(&mut self, play: RunnerPlay, from: Base, to: Destination) -> Result<()> {
+    let out = to == Destination::Out;
+    let must_be_out = matches!(play, RunnerPlay::CaughtStealing | RunnerPlay::PickedOff);
+    if play != RunnerPlay::Advance && out != must_be_out {
+      return Err(Error::Validation(if must_be_out {
+        "A runner caught stealing or picked off is out".into()
+      } else {
+        "Only a runner caught stealing or picked off is out".into()
+      }));
+    }
+    let runner = self.bases[from.index()]
+      .take()
+      .ok_or_else(|| Error::Validation(format!("No runner is on {}", from.name())))?;
+    let player_id = runner.player_id.clone();
+    match to.bases() {
+      None => self.outs += 1,
+      Some(4) => self.score(runner),
+      Some(to) if to <= from.index() + 1 => {
+        return Err(Error::Validation("A runner has to move up a base".into()));
+      }
+      Some(to) => place(&mut self.bases, to - 1, runner)?,
+    }
+    if let Some(player_id) = player_id {
+      match play {
+        RunnerPlay::StolenBase => self.batter(&player_id).sb += 1,
+        RunnerPlay::CaughtStealing => self.batter(&player_id).cs += 1,
+        _ => {}
+      }
+    }
+    Ok(())
+  }
+
+  fn substitute(&mut self, player_in: &str, player_out: &str) -> Result<()> {
+    let slot = self
+      .order
+      .iter()
+      .position(|id| id == player_out)
+      .ok_or_else(|| Error::Validation("That player isn't in the batting order".into()))?;
+    if self.order.iter().any(|id| id == player_in) {
+      return Err(Error::Validation("That player is already in the game".into()));
+    }
+    self.order[slot] = player_in.to_string();
+    for runner in self.bases.iter_mut().flatten() {
+      if runner.player_id.as_deref() == Some(player_out) {
+        runner.player_id = Some(player_in.to_string());
+      }
+    }
+    self.batter(player_in);
+    Ok(())
+  }
+}
+
+fn place(bases: &mut [Option<Runner>; 3], at: usize, runner: Runner) -> Result<()> {
+  if bases[at].is_some() {
+    return Err(Error::Validation(format!(
+      "Two runners can't share {} base",
+      Base::ALL[at].name()
+    )));
+  }
+  bases[at] = Some(runner);
+  Ok(())
+}
+
+/// The usual advances for a result: everyone moves up as many bases as the
+/// batter on a hit or one on a bunt, and only forced runners move on a
+/// walk or error. A fielder's choice puts out the runner from first.
+fn default_moves(result: PlayResult, bases: &[Option<Runner>; 3]) -> Result<Vec<RunnerMove>> {
+  let occupied: Vec<Base> = Base::ALL
+    .into_iter()
+    .filter(|base| bases[base.index()].is_some())
+    .collect();
+  let advance = |by: usize| {
+    occupied
+      .iter()
+      .map(|&from| RunnerMove {
+        from,
+        to: Destination::after(from.index() + 1 + by),
+      })
+      .collect()
+  };
+  let forced = || {
+    Base::ALL
+      .into_iter()
+      .take_while(|base| bases[base.index()].is_some())
+      .map(|from| RunnerMove {
+        from,
+        to: Destination::after(from.index() + 2),
+      })
+      .collect()
+  };
+  Ok(match result {
+    PlayResult::Single => advance(1),
+    PlayResult::Double => advance(2),
+    PlayResult::Triple => advance(3),
+    PlayResult::HomeRun => advance(4),
+    PlayResult::SacrificeBunt => advance(1),
+    PlayResult::Walk | PlayResult::HitByPitch | PlayResult::ReachedOnError => forced(),
+    PlayResult::SacrificeFly if bases[Base::Third.index()].is_some() => vec![RunnerMove {
+      from: Base::Third,
+      to: Destination::Home,
+    }],
+    PlayResult::FieldersChoice if bases[Base::First.index()].is_some() => vec![RunnerMove {
+      from: Base::First,
+      to: Destination::Out,
+    }],
+    PlayResult::FieldersChoice => {
+      return Err(Error::Validation(
+        "Say which runner was put out on the fielder's choice".into(),
+      ));
+    }
+    PlayResult::SacrificeFly | PlayResult::Strikeout | PlayResult::Out => Vec::new(),
+  })
+}
+
+fn load(conn: &Connection, game_id: &str) -> Result<Vec<Play>> {
+  let mut stmt = conn.prepare(
+    "SELECT seq, event, created_at FROM scorebook_events WHERE game_id = ?1 ORDER BY seq",
+  )?;
+  let rows = stmt
+    .query_map([game_id], |row| {
+      Ok((
+        row.get::<_, u32>(0)?,
+        row.get::<_, String>(1)?,
+        row.get::<_, String>(2)?,
+      ))
+    })?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  rows
+    .into_iter()
+    .map(|(seq, event, created_at)| {
+      Ok(Play {
+        seq,
+        event: serde_json::from_str(&event)?,
+        created_at,
+      })
+    })
+    .collect()
+}
+
+fn replay(conn: &Connection, game: &games::Game) -> Result<Replay> {
+  let events: Vec<Event> = load(conn, &game.id)?.into_iter().map(|p| p.event).collect();
+  Replay::run(game.home_away == "home", &events)
+}
+
+fn box_score(conn: &Connection, game: &games::Game, replay: Replay) -> Result<BoxScore> {
+  let players: HashMap<String, roster::Player> = roster::list(conn, &game.team_id)?
+    .into_iter()
+    .map(|p| (p.id.clone(), p))
+    .collect();
+  let batting = replay.batting();
+  let due_up = replay.due_up().cloned();
+  let batters = replay
+    .batters
+    .into_iter()
+    .map(|mut line| {
+      if let Some(player) = players.get(&line.player_id) {
+        line.name = player.name.clone();
+        line.number = player.number;
+      }
+      line
+    })
+    .collect();
+  Ok(BoxScore {
+    game_id: game.id.clone(),
+    inning: replay.inning,
+    top_of_inning: replay.top,
+    outs: replay.outs,
+    batting,
+    due_up,
+    bases: replay.bases,
+    us: replay.us,
+    them: replay.them,
+    innings: replay.innings,
+    batters,
+    plays: replay.plays,
+    last_pitch_mph: replay.last_pitch_mph,
+    top_pitch_mph: replay.top_pitch_mph,
+  })
+}
+
+/// The game's live box score, replayed from its log.
+pub fn get(conn: &Connection, game_id: &str) -> Result<BoxScore> {
+  let game = games::get(conn, game_id)?;
+  let replay = replay(conn, &game)?;
+  box_score(conn, &game, replay)
+}
+
+/// Our next `count` batters, wrapping around the order. Before the first
+/// play that's the top of the game's lineup.
+pub fn upcoming(conn: &Connection, game_id: &str, count: usize) -> Result<Vec<String>> {
+  let game = games::get(conn, game_id)?;
+  let replay = replay(conn, &game)?;
+  let (order, next) = if replay.order.is_empty() {
+    let order: Vec<String> = lineups::latest_for_game(conn, game_id)?
+      .map(|lineup| lineup.slots.into_iter().filter_map(|s| s.player_id).collect())
+      .unwrap_or_default();
+    (order, 0)
+  } else {
+    (replay.order, replay.next)
+  };
+  let count = count.min(order.len());
+  Ok(order.iter().cycle().skip(next).take(count).cloned().collect())
+}
+
+fn append(conn: &Connection, game_id: &str, event: &Event) -> Result<()> {
+  conn.execute(
+    "INSERT INTO scorebook_events (game_id, seq, event, created_at) \
+     VALUES (?1, (SELECT COALESCE(MAX(seq), 0) + 1 FROM scorebook_events WHERE game_id = ?1), \
+       ?2, ?3)",
+    params![game_id, serde_json::to_string(event)?, timestamp()],
+  )?;
+  Ok(())
+}
+
+/// Appends a play to the game's log, starting it with the batting order of
+/// the game's lineup if this is the first. Plays that don't fit the game
+/// so far, like a steal with nobody on, are rejected.
+pub fn record(conn: &mut Connection, game_id: &str, mut event: Event) -> Result<BoxScore> {
+  if matches!(event, Event::StartingLineup { .. }) {
+    return Err(Error::Validation(
+      "The batting order comes from the game's lineup".into(),
+    ));
+  }
+  let game = games::get(conn, game_id)?;
+  let tx = conn.transaction()?;
+  let mut events: Vec<Event> = load(&tx, game_id)?.into_iter().map(|p| p.event).collect();
+  if events.is_empty() {
+    let batting_order = lineups::latest_for_game(&tx, game_id)?
+      .map(|lineup| lineup.slots.into_iter().filter_map(|s| s.player_id).collect())
+      .unwrap_or_default();
+    let start = Event::StartingLineup { batting_order };
+    append(&tx, game_id, &start)?;
+    events.push(start);
+  }
+
+  let home = game.home_away == "home";
+  let mut replay = Replay::run(home, &events)?;
+  match &mut event {
+    Event::PlateAppearance { batter_id, .. } if batter_id.is_none() && replay.batting() => {
+      *batter_id = Some(replay.due_up().cloned().ok_or_else(|| {
+        Error::Validation("The game has no batting order; choose who is batting".into())
+      })?);
+    }
+    Event::Substitution { player_in, .. }
+      if roster::get(&tx, player_in)?.team_id != game.team_id =>
+    {
+      return Err(Error::Validation("The substitute isn't on this team".into()));
+    }
+    _ => {}
+  }
+  replay.apply(&event)?;
+  append(&tx, game_id, &event)?;
+  tx.commit()?;
+  box_score(conn, &game, replay)
+}
+
+/// Drops the newest play from the game's log. The starting lineup stays.
+pub fn undo(conn: &Connection, game_id: &str) -> Result<BoxScore> {
+  // The starting lineup is always entry 1.
+  let removed = conn.execute(
+    "DELETE FROM scorebook_events WHERE game_id = ?1 AND seq > 1 \
+       AND seq = (SELECT MAX(seq) FROM scorebook_events WHERE game_id = ?1)",
+    [game_id],
+  )?;
+  if removed == 0 {
+    return Err(Error::Validation("There's no play to undo".into()));
+  }
+  get(conn, game_id)
+}
+
+#[tauri::command]
+pub fn get_box_score(db: State<'_, Database>, game_id: String) -> Result<BoxScore> {
+  get(&db.conn(), &game_id)
+}
+
+/// The game's log, oldest first.
+#[tauri::command]
+pub fn list_plays(db: State<'_, Database>, game_id: String) -> Result<Vec<Play>> {
+  load(&db.conn(), &game_id)
+}
+
+/// Records a play and broadcasts the new box score.
+#[tauri::command]
+pub fn record_play(
+  app: AppHandle,
+  db: State<'_, Database>,
+  game_id: String,
+  event: Event,
+) -> Result<BoxScore> {
+  let box_score = record(&mut db.conn(), &game_id, event)?;
+  let _ = app.emit(SCOREBOOK_EVENT, &box_score);
+  Ok(box_score)
+}
+
+/// Takes back the last play and broadcasts the new box score.
+#[tauri::command]
+pub fn undo_last_play(
+  app: AppHandle,
+  db: State<'_, Database>,
+  game_id: String,
+) -> Result<BoxScore> {
+  let box_score = undo(&db.conn(), &game_id)?;
+  let _ = app.emit(SCOREBOOK_EVENT, &box_score);
+  Ok(box_score)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::lineups::{LineupInput, SlotInput};
+  use crate::db::open_in_memory;
+  use crate::db::roster::NewPlayer;
+
+  fn player(conn: &Connection, name: &str) -> String {
+    roster::create(
+      conn,
+      NewPlayer {
+        team_id: None,
+        name: name.into(),
+        number: None,
+        primary_position: "CF".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap()
+    .id
+  }
+
+  fn pa(result: PlayResult) -> Event {
+    Event::PlateAppearance {
+      batter_id: None,
+      result,
+      runners: None,
+      rbi: None,
+    }
+  }
+
+  #[test]
+  fn replays_plays_into_the_box_score() {
+    let mut conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let order: Vec<String> = ["Ava", "Ben", "Cal"].iter().map(|n| player(&conn, n)).collect();
+    let sub = player(&conn, "Dee");
+    let game = games::ScheduledGame {
+      id: "g1".into(),
+      date: "2026-04-18".into(),
+      opponent: "Tigers".into(),
+      home_away: "home".into(),
+      score_us: None,
+      score_them: None,
+      notes: String::new(),
+      location: String::new(),
+      start_time: None,
+    };
+    games::upsert_scheduled(&conn, &team_id, &game).unwrap();
+    lineups::save(
+      &mut conn,
+      LineupInput {
+        id: None,
+        team_id: None,
+        name: "Game day".into(),
+        game_id: Some("g1".into()),
+        use_dh: false,
+        innings: 6,
+        slots: (0..3)
+          .map(|i| SlotInput {
+            slot_number: i as u32 + 1,
+            player_id: Some(order[i].clone()),
+            position: None,
+          })
+          .collect(),
+        positions: Vec::new(),
+      },
+    )
+    .unwrap();
+
+    // Top of the first: the visitors score on a steal and a double.
+    for event in [
+      pa(PlayResult::Strikeout),
+      pa(PlayResult::Single),
+      Event::Baserunning {
+        play: RunnerPlay::StolenBase,
+        from: Base::First,
+        to: Destination::Second,
+      },
+      pa(PlayResult::Double),
+      pa(PlayResult::Out),
+      pa(PlayResult::Out),
+    ] {
+      record(&mut conn, "g1", event).unwrap();
+    }
+    let steal_from_third = Event::Baserunning {
+      play: RunnerPlay::StolenBase,
+      from: Base::Third,
+      to: Destination::Home,
+    };
+    assert!(record(&mut conn, "g1", steal_from_third).is_err());
+
+    // Bottom: walk, single, three-run homer, then a pinch hitter.
+    for event in [
+      pa(PlayResult::Walk),
+      pa(PlayResult::Single),
+      pa(PlayResult::HomeRun),
+      Event::Substitution {
+        player_in: sub.clone(),
+        player_out: order[1].clone(),
+        position: None,
+      },
+      pa(PlayResult::Strikeout),
+    ] {
+      record(&mut conn, "g1", event).unwrap();
+    }
+
+    let score = get(&conn, "g1").unwrap();
+    assert_eq!((score.inning, score.top_of_inning, score.outs), (1, false, 1));
+    assert!(score.batting);
+    assert_eq!(score.due_up.as_ref(), Some(&sub));
+    assert_eq!(
+      upcoming(&conn, "g1", 3).unwrap(),
+      [sub.clone(), order[2].clone(), order[0].clone()]
+    );
+    assert_eq!((score.us.runs, score.us.hits), (3, 2));
+    assert_eq!((score.them.runs, score.them.hits), (1, 2));
+    assert_eq!(
+      score.innings,
+      [InningScore {
+        inning: 1,
+        us: 3,
+        them: 1
+      }]
+    );
+    let names: Vec<&str> = score.batters.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, ["Ava", "Ben", "Cal", "Dee"]);
+    let ava = &score.batters[0];
+    assert_eq!((ava.pa, ava.ab, ava.bb, ava.so, ava.r), (2, 1, 1, 1, 1));
+    let cal = &score.batters[2];
+    assert_eq!((cal.h, cal.hr, cal.rbi, cal.r), (1, 1, 3, 1));
+    assert_eq!(score.plays, 11);
+
+    // Radar readings ride along in the log without changing the count
+    record(&mut conn, "g1", Event::Pitch { mph: 61.5 }).unwrap();
+    let score = record(&mut conn, "g1", Event::Pitch { mph: 58.0 }).unwrap();
+    assert_eq!((score.last_pitch_mph, score.top_pitch_mph), (Some(58.0), Some(61.5)));
+    assert_eq!((score.outs, score.plays), (1, 11));
+    assert!(record(&mut conn, "g1", Event::Pitch { mph: 250.0 }).is_err());
+    undo(&conn, "g1").unwrap();
+    undo(&conn, "g1").unwrap();
+
+    let score = undo(&conn, "g1").unwrap();
+    assert_eq!((score.outs, score.due_up.as_ref()), (0, Some(&order[0])));
+    undo(&conn, "g1").unwrap();
+    assert_eq!(get(&conn, "g1").unwrap().batters.len(), 3);
+    assert_eq!(load(&conn, "g1").unwrap().len(), 10);
+  }
+}
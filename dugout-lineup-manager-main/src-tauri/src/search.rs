@@ -0,0 +1,305 @@
+//! Search across players, teams, games and notes for the command palette.
+//!
+//! A whole club's data is small, so every name is scored in memory on each
+//! keystroke instead of keeping an index in sync. Names match on a prefix,
+//! a substring, the query's letters in order ("jlee" for Jordan Lee) or,
+//! for typos, on shared trigrams. Notes only match on whole words, since
+//! loose matches in long text are mostly noise.
+
+use std::collections::HashSet;
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::State;
+
+use crate::db::{games, roster, Database};
+use crate::error::Result;
+
+const DEFAULT_LIMIT: usize = 20;
+
+/// Trigram overlap below which a name isn't shown as a typo match.
+const MIN_SIMILARITY: f64 = 0.25;
+
+/// Characters of a note shown around the match.
+const SNIPPET_CHARS: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HitKind {
+  Player,
+  Team,
+  Game,
+  /// A player's or game's notes; `route` opens the player or game.
+  Note,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+  pub kind: HitKind,
+  /// The player, team or game.
+  pub id: String,
+  pub title: String,
+  pub subtitle: String,
+  /// Webview route that opens it, e.g. `/games/<id>/stats`.
+  pub route: String,
+  /// Higher is better; only meaningful within one search.
+  pub score: u32,
+}
+
+fn normalize(text: &str) -> String {
+  text.trim().to_lowercase()
+}
+
+fn trigrams(text: &str) -> HashSet<[char; 3]> {
+  let padded: Vec<char> = format!("  {text} ").chars().collect();
+  padded.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+/// Jaccard similarity of the two strings' trigrams.
+fn similarity(a: &str, b: &str) -> f64 {
+  let (a, b) = (trigrams(a), trigrams(b));
+  let shared = a.intersection(&b).count();
+  shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+/// Whether `query`'s characters appear in `text` in order, and how spread
+/// out they are.
+fn subsequence_gaps(query: &str, text: &str) -> Option<usize> {
+  let mut rest = text.char_indices();
+  let mut gaps = 0;
+  let mut last = None;
+  for q in query.chars().filter(|c| !c.is_whitespace()) {
+    let (i, _) = rest.by_ref().find(|(_, c)| *c == q)?;
+    if let Some(last) = last {
+      gaps += i - last - 1;
+    }
+    last = Some(i);
+  }
+  Some(gaps)
+}
+
+/// How well `text` matches an already normalized `query`, if at all.
+fn score(query: &str, text: &str) -> Option<u32> {
+  let text = normalize(text);
+  if query.is_empty() || text.is_empty() {
+    return None;
+  }
+  if text == query {
+    return Some(1000);
+  }
+  if text.starts_with(query) {
+    return Some(900);
+  }
+  if text.split_whitespace().any(|word| word.starts_with(query)) {
+    return Some(800);
+  }
+  if let Some(at) = text.find(query) {
+    return Some(700 - at.min(100) as u32);
+  }
+  if let Some(gaps) = subsequence_gaps(query, &text) {
+    return Some(500 - gaps.min(100) as u32);
+  }
+  let similar = similarity(query, &text);
+  (similar >= MIN_SIMILARITY).then_some((similar * 400.0) as u32)
+}
+
+/// A whole-word match in `notes`, with the text around it.
+fn note_match(query: &str, notes: &str) -> Option<(u32, String)> {
+  let lower = notes.to_lowercase();
+  let at = lower
+    .match_indices(query)
+    .map(|(i, _)| i)
+    .find(|&i| i == 0 || !lower[..i].ends_with(char::is_alphanumeric))?;
+  // Byte offsets in the lowercase copy may not line up with the original
+  let chars: Vec<char> = notes.chars().collect();
+  let at = lower[..at].chars().count().min(chars.len());
+  let start = at.saturating_sub(SNIPPET_CHARS / 3);
+  let end = (start + SNIPPET_CHARS).min(chars.len());
+  let mut snippet: String = chars[start..end].iter().collect();
+  if start > 0 {
+    snippet.insert(0, '…');
+  }
+  if end < chars.len() {
+    snippet.push('…');
+  }
+  Some((600, snippet.trim().to_string()))
+}
+
+fn teams(conn: &Connection) -> Result<Vec<(String, String)>> {
+  let mut stmt = conn.prepare("SELECT id, name FROM teams ORDER BY created_at, rowid")?;
+  let teams = stmt
+    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(teams)
+}
+
+/// Every hit for `query`, best first, at most `limit` of them.
+pub fn search(conn: &Connection, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+  let query = normalize(query);
+  if query.is_empty() {
+    return Ok(Vec::new());
+  }
+  // "#7" or "7" finds the player wearing it
+  let number: Option<u32> = query.trim_start_matches('#').parse().ok();
+
+  let mut hits = Vec::new();
+  for (team_id, team_name) in teams(conn)? {
+    if let Some(score) = score(&query, &team_name) {
+      hits.push(SearchHit {
+        kind: HitKind::Team,
+        id: team_id.clone(),
+        title: team_name.clone(),
+        subtitle: "Team".into(),
+        route: format!("/?team={team_id}"),
+        score,
+      });
+    }
+
+    for player in roster::list(conn, &team_id)? {
+      let route = format!("/?player={}", player.id);
+      let subtitle = match player.number {
+        Some(n) => format!("#{n} · {} · {team_name}", player.primary_position),
+        None => format!("{} · {team_name}", player.primary_position),
+      };
+      let by_number = (number.is_some() && number == player.number).then_some(950);
+      if let Some(score) = by_number.max(score(&query, &player.name)) {
+        hits.push(SearchHit {
+          kind: HitKind::Player,
+          id: player.id.clone(),
+          title: player.name.clone(),
+          subtitle: subtitle.clone(),
+          route: route.clone(),
+          score,
+        });
+      }
+      if let Some((score, snippet)) = note_match(&query, &player.notes) {
+        hits.push(SearchHit {
+          kind: HitKind::Note,
+          id: player.id,
+          title: format!("Note on {}", player.name),
+          subtitle: snippet,
+          route,
+          score,
+        });
+      }
+    }
+
+    for game in games::list(conn, &team_id)? {
+      let venue = if game.home_away == "away" { "@" } else { "vs" };
+      let title = format!("{venue} {}", game.opponent);
+      let route = format!("/games/{}/stats", game.id);
+      let best = [&game.opponent, &game.date, &game.location]
+        .into_iter()
+        .filter_map(|text| score(&query, text))
+        .max();
+      if let Some(score) = best {
+        hits.push(SearchHit {
+          kind: HitKind::Game,
+          id: game.id.clone(),
+          title: title.clone(),
+          subtitle: format!("{} · {team_name}", game.date),
+          route: route.clone(),
+          score,
+        });
+      }
+      if let Some((score, snippet)) = note_match(&query, &game.notes) {
+        hits.push(SearchHit {
+          kind: HitKind::Note,
+          id: game.id,
+          title: format!("Note on {title}, {}", game.date),
+          subtitle: snippet,
+          route,
+          score,
+        });
+      }
+    }
+  }
+
+  hits.sort_by(|a, b| {
+    b.score
+      .cmp(&a.score)
+      .then(a.kind.cmp(&b.kind))
+      .then_with(|| a.title.cmp(&b.title))
+  });
+  hits.truncate(limit);
+  Ok(hits)
+}
+
+/// Ranked hits for the command palette, `limit` (default 20) at most.
+#[tauri::command]
+pub fn global_search(
+  db: State<'_, Database>,
+  query: String,
+  limit: Option<usize>,
+) -> Result<Vec<SearchHit>> {
+  search(&db.conn(), &query, limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::games::ScheduledGame;
+  use crate::db::roster::NewPlayer;
+
+  #[test]
+  fn ranks_hits_across_players_teams_games_and_notes() {
+    let conn = crate::db::open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    for (name, number, notes) in [
+      ("Jordan Lee", 5, ""),
+      ("Jordana Price", 9, "Bunts well"),
+      ("Sam Carter", 3, "Sore elbow, no pitching"),
+    ] {
+      roster::create(
+        &conn,
+        NewPlayer {
+          team_id: Some(team_id.clone()),
+          name: name.into(),
+          number: Some(number),
+          primary_position: "SS".into(),
+          secondary_positions: Vec::new(),
+          bats: "R".into(),
+          throws: "R".into(),
+          status: None,
+          notes: Some(notes.into()),
+        },
+      )
+      .unwrap();
+    }
+    games::upsert_scheduled(
+      &conn,
+      &team_id,
+      &ScheduledGame {
+        id: "g1".into(),
+        date: "2026-05-11".into(),
+        opponent: "Eagles".into(),
+        home_away: "away".into(),
+        score_us: None,
+        score_them: None,
+        notes: "Bring the pitching machine".into(),
+        location: String::new(),
+        start_time: None,
+      },
+    )
+    .unwrap();
+
+    let titles = |query: &str| -> Vec<String> {
+      search(&conn, query, 10).unwrap().into_iter().map(|h| h.title).collect()
+    };
+    assert_eq!(titles("jordan"), ["Jordan Lee", "Jordana Price"]);
+    assert_eq!(titles("#3"), ["Sam Carter"]);
+    assert_eq!(titles("jlee"), ["Jordan Lee"]);
+    // A typo still finds the game
+    let hits = search(&conn, "eagels", 10).unwrap();
+    assert_eq!(hits[0].kind, HitKind::Game);
+    assert_eq!(hits[0].title, "@ Eagles");
+    assert_eq!(hits[0].route, "/games/g1/stats");
+
+    let notes: Vec<SearchHit> = search(&conn, "pitching", 10).unwrap();
+    assert!(notes.iter().all(|h| h.kind == HitKind::Note));
+    assert_eq!(notes.len(), 2);
+    assert!(search(&conn, "itching", 10).unwrap().is_empty());
+    assert!(search(&conn, "  ", 10).unwrap().is_empty());
+  }
+}
@@ -0,0 +1,390 @@
+//! User preferences for the desktop shell, stored in `settings.json` under
+//! the app data directory (outside the data directory, so backups and
+//! restores never overwrite them).
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+
+/// Emitted with the new `AppSettings` after `update_settings` saves them.
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AppSettings {
+  pub theme: Theme,
+  pub backend_port: PortSettings,
+  /// Whether to start the bundled sidecar or use `remote_backend`. Takes
+  /// effect at the next launch.
+  pub backend_mode: BackendMode,
+  /// The backend used in remote mode. Mobile builds have no sidecar and
+  /// always use it, so without one their AI features are unavailable.
+  pub remote_backend: Option<RemoteBackend>,
+  /// When the sidecar's memory use raises a warning or a restart.
+  pub backend_memory: crate::resources::MemoryLimits,
+  pub backup: BackupSettings,
+  /// Closing or minimizing the main window hides it to the tray instead.
+  pub minimize_to_tray: bool,
+  pub update_channel: UpdateChannel,
+  pub log: LogSettings,
+  /// Overrides where the default profile's data directory lives (e.g. a
+  /// synced folder). `set_data_dir` moves it at once; edited here, it takes
+  /// effect at the next launch. Not available on mobile, where the app can
+  /// only write inside its own sandbox.
+  pub data_dir: Option<PathBuf>,
+  /// Overrides where the default profile's backups go, like `data_dir`.
+  pub backups_dir: Option<PathBuf>,
+  pub sync: SyncSettings,
+  /// Constraints lineups are checked against by `validate_lineup`.
+  pub league_rules: Vec<crate::rules::Rule>,
+  /// Pitch limits and rest table for `get_pitcher_availability`.
+  pub pitching: crate::pitching::PitchRules,
+  /// When pitch counts and the game clock raise alerts, for each team.
+  pub alerts: crate::alerts::AlertSettings,
+  /// Where game-day forecasts come from, and the home field to use for
+  /// games without an address.
+  pub weather: crate::weather::WeatherSettings,
+  pub reminders: crate::reminders::ReminderSettings,
+  /// Anonymous feature-usage counts; off unless the coach opts in.
+  pub telemetry: crate::telemetry::TelemetrySettings,
+  /// Where live game sharing publishes to; off without a server.
+  pub relay: crate::relay::RelaySettings,
+  /// Global shortcuts for in-game actions. Desktop only; change them with
+  /// `set_hotkeys` to find out about conflicts.
+  pub hotkeys: HotkeySettings,
+  /// Vendors' scoreboard controller formats, alongside the built-in ones.
+  pub scoreboard_protocols: Vec<crate::scoreboard::ScoreboardProtocol>,
+  /// Locks the app down for a shared machine; see `kiosk`.
+  pub kiosk: crate::kiosk::KioskSettings,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Theme {
+  /// Follow the OS appearance.
+  #[default]
+  System,
+  Light,
+  Dark,
+}
+
+/// Port the backend sidecar listens on. Takes effect at the next launch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PortSettings {
+  pub preferred: u16,
+  /// Use an OS-assigned port when `preferred` is taken, rather than
+  /// failing to start the backend.
+  pub fallback: bool,
+}
+
+impl Default for PortSettings {
+  fn default() -> Self {
+    Self {
+      preferred: crate::sidecar::DEFAULT_PORT,
+      fallback: true,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackendMode {
+  /// Spawn the backend bundled with the app.
+  #[default]
+  Sidecar,
+  /// Use a hosted backend, e.g. a team account's.
+  Remote,
+}
+
+/// A backend running somewhere the app can reach: hosted for a team
+/// account, or on the coach's laptop.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RemoteBackend {
+  /// Base URL, e.g. `http://192.168.1.20:8100`.
+  pub url: String,
+  /// The `DUGOUT_BACKEND_TOKEN` it was started with, if any.
+  pub token: Option<String>,
+}
+
+/// Release channel the updater follows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UpdateChannel {
+  #[default]
+  Stable,
+  /// Prereleases as well as stable releases.
+  Beta,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BackupSettings {
+  /// Take automatic backups on a schedule.
+  pub enabled: bool,
+  pub interval_hours: u32,
+  /// Automatic backups to keep; older ones are pruned.
+  pub keep_count: usize,
+  /// Automatic backups older than this are pruned.
+  pub keep_days: u32,
+  /// Where each automatic backup is uploaded once it's written. Set when
+  /// an account is linked with `link_cloud_backup`.
+  pub cloud: Option<CloudProvider>,
+}
+
+impl Default for BackupSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      interval_hours: 24,
+      keep_count: 14,
+      keep_days: 30,
+      cloud: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CloudProvider {
+  Dropbox,
+  GoogleDrive,
+}
+
+/// Identity shown to paired devices during LAN sync.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SyncSettings {
+  /// Created the first time sync is used.
+  pub device_id: String,
+  /// Defaults to the computer's name.
+  pub device_name: Option<String>,
+}
+
+/// Key combos like `CmdOrCtrl+Shift+B`, or `None` to leave an action
+/// without one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HotkeySettings {
+  pub advance_batter: Option<String>,
+  pub toggle_game_clock: Option<String>,
+  pub toggle_game_mode: Option<String>,
+}
+
+impl Default for HotkeySettings {
+  fn default() -> Self {
+    Self {
+      advance_batter: Some("CmdOrCtrl+Shift+B".into()),
+      toggle_game_clock: Some("CmdOrCtrl+Shift+K".into()),
+      toggle_game_mode: Some("CmdOrCtrl+Shift+G".into()),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LogSettings {
+  /// Level for modules without an override.
+  pub level: LogLevel,
+  /// Per-module overrides, keyed by module path: `sidecar` for the app's
+  /// own modules, or e.g. `reqwest` for a dependency.
+  pub modules: BTreeMap<String, LogLevel>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+  Off,
+  Error,
+  Warn,
+  #[default]
+  Info,
+  Debug,
+  Trace,
+}
+
+/// Reads settings from `path`, falling back to defaults if the file is
+/// missing or unreadable.
+pub fn load(path: &Path) -> AppSettings {
+  match std::fs::read_to_string(path) {
+    Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+      log::warn!("Ignoring invalid settings file {:?}: {}", path, e);
+      AppSettings::default()
+    }),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => AppSettings::default(),
+    Err(e) => {
+      log::warn!("Failed to read settings file {:?}: {}", path, e);
+      AppSettings::default()
+    }
+  }
+}
+
+/// Writes settings to `path` atomically, so a crash mid-write never leaves
+/// a truncated file behind.
+pub fn save(path: &Path, settings: &AppSettings) -> Result<()> {
+  let tmp = path.with_extension("json.tmp");
+  std::fs::write(&tmp, serde_json::to_vec_pretty(settings)?)?;
+  std::fs::rename(&tmp, path)?;
+  Ok(())
+}
+
+/// Rejects settings the shell can't act on.
+pub fn validate(settings: &AppSettings) -> Result<()> {
+  if settings.backend_port.preferred < 1024 {
+    return Err(Error::Validation(
+      "Backend port must be between 1024 and 65535".into(),
+    ));
+  }
+
+  let backup = &settings.backup;
+  if backup.interval_hours == 0 {
+    return Err(Error::Validation("Backup interval must be at least an hour".into()));
+  }
+  if backup.keep_count == 0 || backup.keep_days == 0 {
+    return Err(Error::Validation("At least one automatic backup must be kept".into()));
+  }
+  if backup.cloud.is_some() && cfg!(mobile) {
+    return Err(Error::Validation(
+      "Cloud backups aren't available on this device".into(),
+    ));
+  }
+
+  match &settings.remote_backend {
+    Some(remote) => crate::sidecar::validate_remote(remote)?,
+    None if settings.backend_mode == BackendMode::Remote => {
+      return Err(Error::Validation("Remote backend mode needs a backend URL".into()));
+    }
+    None => {}
+  }
+
+  for module in settings.log.modules.keys() {
+    crate::logging::validate_module(module)?;
+  }
+
+  crate::rules::validate_rules(&settings.league_rules)?;
+  crate::pitching::validate(&settings.pitching)?;
+  crate::reminders::validate(&settings.reminders)?;
+  crate::alerts::validate(&settings.alerts)?;
+  crate::resources::validate(&settings.backend_memory)?;
+  crate::relay::validate(&settings.relay)?;
+  crate::scoreboard::validate_protocols(&settings.scoreboard_protocols)?;
+  crate::kiosk::validate(&settings.kiosk)?;
+  #[cfg(desktop)]
+  crate::hotkeys::validate(&settings.hotkeys)?;
+
+  for (label, dir) in [("Data", &settings.data_dir), ("Backups", &settings.backups_dir)] {
+    let Some(dir) = dir else {
+      continue;
+    };
+    if cfg!(mobile) {
+      return Err(Error::Validation(
+        "The data directory can't be moved on this device".into(),
+      ));
+    }
+    if !dir.is_absolute() {
+      return Err(Error::Validation(format!("{label} directory must be an absolute path")));
+    }
+    if dir.exists() && !dir.is_dir() {
+      return Err(Error::Validation(format!("{} is not a directory", dir.display())));
+    }
+  }
+  Ok(())
+}
+
+#[tauri::command]
+pub fn get_settings(settings: State<'_, RwLock<AppSettings>>) -> AppSettings {
+  settings.read().unwrap().clone()
+}
+
+/// Replaces every setting at once. Changes apply immediately except the
+/// backend port, backend mode and data and backups directories, which
+/// are read at launch. Refused in kiosk mode, and the kiosk PIN is kept
+/// whatever comes in, since only `set_kiosk_pin` changes it.
+#[tauri::command]
+pub fn update_settings(
+  app: AppHandle,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+  mut new_settings: AppSettings,
+) -> Result<AppSettings> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  let mut settings = settings.write().unwrap();
+  new_settings.kiosk.pin_hash = settings.kiosk.pin_hash.clone();
+  validate(&new_settings)?;
+
+  save(&paths.settings, &new_settings)?;
+  if settings.log != new_settings.log {
+    crate::logging::configure(&new_settings.log);
+  }
+  if settings.theme != new_settings.theme {
+    crate::theme::apply(&app, new_settings.theme);
+  }
+  if settings.telemetry != new_settings.telemetry {
+    use tauri::Manager;
+    let telemetry = app.state::<crate::telemetry::Telemetry>();
+    telemetry.set_enabled(new_settings.telemetry.enabled);
+  }
+  #[cfg(desktop)]
+  if settings.update_channel != new_settings.update_channel {
+    use tauri::Manager;
+    app.state::<crate::updater::PendingUpdate>().clear();
+  }
+  #[cfg(desktop)]
+  if settings.hotkeys != new_settings.hotkeys {
+    crate::hotkeys::apply(&app, &new_settings.hotkeys);
+  }
+  *settings = new_settings;
+
+  let _ = app.emit(SETTINGS_CHANGED_EVENT, settings.clone());
+  Ok(settings.clone())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn accepts_the_defaults() {
+    assert!(validate(&AppSettings::default()).is_ok());
+  }
+
+  #[test]
+  fn rejects_unusable_values() {
+    let mut settings = AppSettings::default();
+    settings.backend_port.preferred = 80;
+    assert!(validate(&settings).is_err());
+
+    let mut settings = AppSettings::default();
+    settings.backup.interval_hours = 0;
+    assert!(validate(&settings).is_err());
+
+    let settings = AppSettings {
+      data_dir: Some(PathBuf::from("relative/dir")),
+      ..Default::default()
+    };
+    assert!(validate(&settings).is_err());
+
+    let mut settings = AppSettings::default();
+    settings.log.modules.insert("bad module".into(), LogLevel::Debug);
+    assert!(validate(&settings).is_err());
+
+    let mut settings = AppSettings {
+      remote_backend: Some(RemoteBackend {
+        url: "localhost:8100".into(),
+        token: None,
+      }),
+      ..Default::default()
+    };
+    assert!(validate(&settings).is_err());
+    settings.remote_backend = None;
+    settings.backend_mode = BackendMode::Remote;
+    assert!(validate(&settings).is_err());
+  }
+}
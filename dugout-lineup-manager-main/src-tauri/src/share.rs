@@ -0,0 +1,382 @@
+//! Sending a lineup home by email, through whichever mail app the coach
+//! already uses.
+//!
+//! There's no portable way to hand a mail app an attachment. Linux goes
+//! through `xdg-email` and Windows through Simple MAPI (what Explorer's
+//! "Send to > Mail recipient" uses). Anywhere else, macOS included, or when
+//! those fail, the message opens from a `mailto:` link and the file is shown
+//! in the file manager to drag in.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::db::{contacts, Database};
+use crate::error::{Error, Result};
+use crate::export::pdf::{self, Card, CardOptions};
+use crate::export::{file_stem, xlsx};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShareFormat {
+  /// The printable lineup card.
+  #[default]
+  Pdf,
+  /// The team workbook: every lineup plus season stats.
+  Xlsx,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailShare {
+  /// Where the attachment was written.
+  pub path: PathBuf,
+  pub recipients: usize,
+  /// Whether the mail app was given the file. When not, it's shown in the
+  /// file manager to attach by hand.
+  pub attached: bool,
+}
+
+struct Email {
+  /// Parents go in Bcc so their addresses aren't shared with each other.
+  bcc: Vec<String>,
+  subject: String,
+  body: String,
+  attachment: PathBuf,
+}
+
+/// The batting order as text, so the email is useful even before the
+/// attachment is opened (or if it never gets attached).
+fn body(card: &Card) -> String {
+  let mut body = String::from("Here's the lineup");
+  if let Some(game) = &card.game {
+    body.push_str(&format!(" for {game}"));
+  }
+  body.push_str(".\n\n");
+  for line in card.batting_order() {
+    body.push_str(&line);
+    body.push('\n');
+  }
+  if !card.bench.is_empty() {
+    body.push_str(&format!("\nBench: {}\n", card.bench.join(", ")));
+  }
+  body
+}
+
+/// Percent-encodes everything but unreserved characters, plus any in
+/// `keep`. Line breaks become CRLF, as `mailto:` requires.
+fn encode(text: &str, keep: &[u8]) -> String {
+  let text = text.replace("\r\n", "\n").replace('\n', "\r\n");
+  let mut out = String::with_capacity(text.len());
+  for byte in text.bytes() {
+    if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) || keep.contains(&byte) {
+      out.push(byte as char);
+    } else {
+      out.push_str(&format!("%{byte:02X}"));
+    }
+  }
+  out
+}
+
+fn mailto(email: &Email) -> String {
+  let mut url = String::from("mailto:?");
+  if !email.bcc.is_empty() {
+    url.push_str(&format!("bcc={}&", encode(&email.bcc.join(","), b"@,")));
+  }
+  url.push_str(&format!(
+    "subject={}&body={}",
+    encode(&email.subject, b""),
+    encode(&email.body, b"")
+  ));
+  url
+}
+
+/// Opens a new message with the attachment in the desktop's mail app.
+#[cfg(target_os = "linux")]
+fn compose(email: &Email) -> Result<bool> {
+  let mut command = std::process::Command::new("xdg-email");
+  command.arg("--utf8").arg("--subject").arg(&email.subject).arg("--body").arg(&email.body);
+  for address in &email.bcc {
+    command.arg("--bcc").arg(address);
+  }
+  let output = command.arg("--attach").arg(&email.attachment).output()?;
+  if !output.status.success() {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    return Err(Error::Validation(stderr.trim().to_string()));
+  }
+  Ok(true)
+}
+
+#[cfg(windows)]
+fn compose(email: &Email) -> Result<bool> {
+  mapi::send(email)
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn compose(_email: &Email) -> Result<bool> {
+  Ok(false)
+}
+
+#[cfg(windows)]
+mod mapi {
+  //! Simple MAPI, which the default mail app on Windows implements. The
+  //! Unicode entry point needs Windows 8.
+
+  use std::ffi::c_void;
+
+  use super::Email;
+  use crate::error::{Error, Result};
+
+  #[repr(C)]
+  struct MapiRecipDescW {
+    reserved: u32,
+    recip_class: u32,
+    name: *mut u16,
+    address: *mut u16,
+    entry_id_size: u32,
+    entry_id: *mut c_void,
+  }
+
+  #[repr(C)]
+  struct MapiFileDescW {
+    reserved: u32,
+    flags: u32,
+    position: u32,
+    path_name: *mut u16,
+    file_name: *mut u16,
+    file_type: *mut c_void,
+  }
+
+  #[repr(C)]
+  struct MapiMessageW {
+    reserved: u32,
+    subject: *mut u16,
+    note_text: *mut u16,
+    message_type: *mut u16,
+    date_received: *mut u16,
+    conversation_id: *mut u16,
+    flags: u32,
+    originator: *mut MapiRecipDescW,
+    recip_count: u32,
+    recips: *mut MapiRecipDescW,
+    file_count: u32,
+    files: *mut MapiFileDescW,
+  }
+
+  #[link(name = "mapi32")]
+  extern "system" {
+    fn MAPISendMailW(
+      session: usize,
+      ui_param: usize,
+      message: *mut MapiMessageW,
+      flags: u32,
+      reserved: u32,
+    ) -> u32;
+  }
+
+  const MAPI_BCC: u32 = 3;
+  const MAPI_LOGON_UI: u32 = 0x1;
+  const MAPI_DIALOG: u32 = 0x8;
+  const MAPI_USER_ABORT: u32 = 1;
+
+  fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().filter(|&c| c != 0).chain([0]).collect()
+  }
+
+  /// Shows the compose window and returns once it's sent or closed.
+  pub fn send(email: &Email) -> Result<bool> {
+    let mut subject = wide(&email.subject);
+    let mut body = wide(&email.body.replace('\n', "\r\n"));
+    let mut path = wide(&email.attachment.to_string_lossy());
+    let mut addresses: Vec<Vec<u16>> =
+      email.bcc.iter().map(|a| wide(&format!("SMTP:{a}"))).collect();
+    let mut names: Vec<Vec<u16>> = email.bcc.iter().map(|a| wide(a)).collect();
+    let mut recips: Vec<MapiRecipDescW> = addresses
+      .iter_mut()
+      .zip(names.iter_mut())
+      .map(|(address, name)| MapiRecipDescW {
+        reserved: 0,
+        recip_class: MAPI_BCC,
+        name: name.as_mut_ptr(),
+        address: address.as_mut_ptr(),
+        entry_id_size: 0,
+        entry_id: std::ptr::null_mut(),
+      })
+      .collect();
+    let mut file = MapiFileDescW {
+      reserved: 0,
+      flags: 0,
+      // Attached rather than placed in the body text
+      position: u32::MAX,
+      path_name: path.as_mut_ptr(),
+      file_name: std::ptr::null_mut(),
+      file_type: std::ptr::null_mut(),
+    };
+    let mut message = MapiMessageW {
+      reserved: 0,
+      subject: subject.as_mut_ptr(),
+      note_text: body.as_mut_ptr(),
+      message_type: std::ptr::null_mut(),
+      date_received: std::ptr::null_mut(),
+      conversation_id: std::ptr::null_mut(),
+      flags: 0,
+      originator: std::ptr::null_mut(),
+      recip_count: recips.len() as u32,
+      recips: if recips.is_empty() { std::ptr::null_mut() } else { recips.as_mut_ptr() },
+      file_count: 1,
+      files: &mut file,
+    };
+    // SAFETY: every pointer is to a local that outlives the call, and the
+    // strings are NUL-terminated
+    let status = unsafe { MAPISendMailW(0, 0, &mut message, MAPI_LOGON_UI | MAPI_DIALOG, 0) };
+    match status {
+      0 | MAPI_USER_ABORT => Ok(true),
+      code => Err(Error::Validation(format!("MAPI error {code}"))),
+    }
+  }
+}
+
+/// Writes the attachment into a folder of its own in temp, so it keeps a
+/// readable name. It's left there: the mail app reads it when sending.
+fn write_attachment(
+  conn: &rusqlite::Connection,
+  card: &Card,
+  format: ShareFormat,
+  options: &CardOptions,
+) -> Result<PathBuf> {
+  let dir = std::env::temp_dir().join(format!("dugout-share-{}", uuid::Uuid::new_v4()));
+  std::fs::create_dir_all(&dir)?;
+  let stem = file_stem(&card.title());
+  match format {
+    ShareFormat::Pdf => {
+      let path = dir.join(format!("{stem}.pdf"));
+      std::fs::write(&path, pdf::render(card, options)?)?;
+      Ok(path)
+    }
+    ShareFormat::Xlsx => {
+      let path = dir.join(format!("{}.xlsx", file_stem(&card.team_name)));
+      xlsx::write(conn, &card.lineup.team_id, &path)?;
+      Ok(path)
+    }
+  }
+}
+
+fn open_mailto(app: &AppHandle, email: &Email, attachment: &Path) -> Result<()> {
+  app
+    .opener()
+    .open_url(mailto(email), None::<&str>)
+    .map_err(|e| Error::Validation(format!("Couldn't open the mail app: {e}")))?;
+  if let Err(e) = app.opener().reveal_item_in_dir(attachment) {
+    log::warn!("Couldn't show the lineup attachment: {}", e);
+  }
+  Ok(())
+}
+
+/// Opens a new message in the default mail app with the lineup attached (as
+/// its PDF card by default), addressed to the team's parents or to
+/// `recipients`.
+#[tauri::command]
+pub async fn share_lineup_email(
+  app: AppHandle,
+  lineup_id: String,
+  format: Option<ShareFormat>,
+  options: Option<CardOptions>,
+  recipients: Option<Vec<String>>,
+) -> Result<EmailShare> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let (email, count) = {
+      let db = app.state::<Database>();
+      let conn = db.conn();
+      let card = pdf::card(&conn, &lineup_id)?;
+      let bcc = match recipients {
+        Some(recipients) => recipients.into_iter().map(|r| r.trim().to_string()).collect(),
+        None => contacts::for_team(&conn, &card.lineup.team_id)?,
+      };
+      if let Some(invalid) = bcc.iter().find(|r| !contacts::is_valid_email(r)) {
+        return Err(Error::Validation(format!("{invalid} isn't an email address")));
+      }
+      let attachment =
+        write_attachment(&conn, &card, format.unwrap_or_default(), &options.unwrap_or_default())?;
+      let count = bcc.len();
+      let email = Email {
+        bcc,
+        subject: card.heading(),
+        body: body(&card),
+        attachment,
+      };
+      (email, count)
+    };
+
+    let attached = match compose(&email) {
+      Ok(attached) => attached,
+      Err(e) => {
+        log::warn!("Mail app didn't take the attachment ({}); using a mailto link", e);
+        false
+      }
+    };
+    if !attached {
+      open_mailto(&app, &email, &email.attachment)?;
+    }
+    log::info!("Opened lineup email to {} recipient(s)", count);
+    Ok(EmailShare {
+      path: email.attachment,
+      recipients: count,
+      attached,
+    })
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::lineups::{Lineup, LineupSlot};
+
+  #[test]
+  fn builds_mailto_links_with_the_batting_order() {
+    let slot = |n: u32, name: &str, number: Option<u32>, position: &str| LineupSlot {
+      slot_number: n,
+      player_id: Some(format!("p{n}")),
+      player_name: Some(name.into()),
+      number,
+      position: Some(position.into()),
+    };
+    let card = Card {
+      team_name: "Eagles".into(),
+      game: Some("vs Tigers \u{b7} 2026-04-11".into()),
+      lineup: Lineup {
+        id: "l1".into(),
+        team_id: "t1".into(),
+        name: "Game 1".into(),
+        game_id: Some("g1".into()),
+        use_dh: false,
+        innings: 6,
+        slots: vec![slot(1, "Jake Miller", Some(12), "SS"), slot(2, "Ava Chen", None, "CF")],
+        positions: Vec::new(),
+        updated_at: String::new(),
+        versions: Default::default(),
+      },
+      bench: vec!["#4 Sam Ortiz".into()],
+    };
+    assert_eq!(
+      body(&card),
+      "Here's the lineup for vs Tigers \u{b7} 2026-04-11.\n\n\
+       1. #12 Jake Miller, SS\n2. Ava Chen, CF\n\nBench: #4 Sam Ortiz\n"
+    );
+
+    let email = Email {
+      bcc: vec!["lin@example.com".into(), "o'neil+dad@example.com".into()],
+      subject: card.heading(),
+      body: "1. Jake\n2. Ava & Sam".into(),
+      attachment: PathBuf::new(),
+    };
+    assert_eq!(
+      mailto(&email),
+      "mailto:?bcc=lin@example.com,o%27neil%2Bdad@example.com\
+       &subject=Eagles%20lineup%20vs%20Tigers%20%C2%B7%202026-04-11\
+       &body=1.%20Jake%0D%0A2.%20Ava%20%26%20Sam"
+    );
+  }
+}
@@ -0,0 +1,710 @@
+//! Lifecycle management for the bundled FastAPI backend sidecar.
+//!
+//! The child handle lives in `SidecarManager`, which is registered as
+//! `tauri::State` so commands can inspect (and later stop or restart) the
+//! process instead of leaking it for the lifetime of the app.
+//!
+//! In remote mode (always on mobile, which has no sidecar to spawn)
+//! nothing is started, and requests go to the configured backend with its
+//! token instead.
+
+use std::collections::VecDeque;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::async_runtime::Receiver;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use tauri_plugin_shell::process::{Command, CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+use crate::error::{Error, Result as AppResult};
+use crate::handshake::{self, BackendCompatibility};
+use crate::settings::{AppSettings, BackendMode, PortSettings, RemoteBackend};
+
+/// Name of the sidecar binary declared in `bundle.externalBin`.
+pub const SIDECAR_NAME: &str = "backend-sidecar";
+
+/// Runs the sidecar's data migration instead of its server.
+pub const MIGRATE_ARG: &str = "--migrate";
+
+/// Port the backend prefers; another free port is used if it is taken.
+pub const DEFAULT_PORT: u16 = 8100;
+
+/// How long the sidecar gets to exit on its own before it is force-killed.
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long asking the sidecar to stop over HTTP may take (on Windows).
+#[cfg(any(not(unix), test))]
+const SHUTDOWN_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Crash restarts attempted before the supervisor gives up.
+pub const MAX_RESTARTS: u32 = 5;
+
+/// Delay before the first restart; doubled on each consecutive crash.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A child that stays up this long is considered healthy again and resets
+/// the restart counter.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+/// Event emitted whenever the sidecar's supervised state changes.
+pub const BACKEND_STATUS_EVENT: &str = "backend-status";
+
+/// Header carrying the token the backend requires on every request except
+/// `/health`.
+pub const AUTH_HEADER: &str = "X-Dugout-Token";
+
+/// Lines of sidecar output kept in memory for `get_backend_logs`.
+const LOG_BUFFER_LINES: usize = 500;
+
+/// Environment handed to the sidecar every time it is spawned.
+#[derive(Debug, Clone)]
+pub struct SidecarConfig {
+  pub port: u16,
+  pub data_dir: PathBuf,
+  pub models_dir: PathBuf,
+  /// Used instead of spawning the sidecar.
+  pub remote: Option<RemoteBackend>,
+}
+
+/// Snapshot of the sidecar process returned to the frontend.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SidecarStatus {
+  pub running: bool,
+  pub pid: Option<u32>,
+  /// Unix timestamp (milliseconds) of the most recent successful spawn.
+  pub started_at: Option<u64>,
+  pub last_exit_code: Option<i32>,
+  /// The remote backend in use instead of the sidecar.
+  pub remote_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendState {
+  Running,
+  Restarting,
+  Failed,
+}
+
+/// Payload of the `backend-status` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendStatusEvent {
+  pub state: BackendState,
+  /// Consecutive crash restarts so far (0 for a clean start).
+  pub attempt: u32,
+  pub max_attempts: u32,
+  pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+  Stdout,
+  Stderr,
+}
+
+/// One line of sidecar output.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendLogLine {
+  /// Unix timestamp (milliseconds) when the line was received.
+  pub timestamp: u64,
+  pub stream: LogStream,
+  pub line: String,
+}
+
+#[derive(Default)]
+struct SidecarInner {
+  child: Option<CommandChild>,
+  pid: Option<u32>,
+  started_at: Option<u64>,
+  last_exit_code: Option<i32>,
+  /// Incremented on every spawn so events from a previous child are ignored.
+  generation: u64,
+  /// Set once the app is exiting; no further spawns are allowed.
+  shutting_down: bool,
+  /// Set while the current child is being stopped on purpose.
+  stopping: bool,
+  /// Consecutive crash restarts since the last stable run.
+  restart_count: u32,
+  /// Token the current child requires on requests. A new one is made for
+  /// every spawn and never shown to the webview, so only the shell can
+  /// talk to the backend.
+  auth_token: String,
+}
+
+/// Owns the sidecar child process.
+pub struct SidecarManager {
+  config: Mutex<SidecarConfig>,
+  inner: Mutex<SidecarInner>,
+  logs: Mutex<VecDeque<BackendLogLine>>,
+  /// What the last version handshake made of the backend.
+  compatibility: Mutex<BackendCompatibility>,
+}
+
+impl SidecarManager {
+  pub fn new(config: SidecarConfig) -> Self {
+    Self {
+      config: Mutex::new(config),
+      inner: Mutex::new(SidecarInner::default()),
+      logs: Mutex::new(VecDeque::with_capacity(LOG_BUFFER_LINES)),
+      compatibility: Mutex::new(BackendCompatibility::default()),
+    }
+  }
+
+  /// Base URL the backend serves its HTTP API on.
+  pub fn base_url(&self) -> String {
+    match self.remote_url() {
+      Some(url) => url,
+      None => format!("http://127.0.0.1:{}", self.port()),
+    }
+  }
+
+  /// The configured remote backend's base URL, without a trailing slash.
+  pub fn remote_url(&self) -> Option<String> {
+    let config = self.config.lock().unwrap();
+    let remote = config.remote.as_ref()?;
+    Some(remote.url.trim_end_matches('/').to_string())
+  }
+
+  /// Whether there is a backend to send requests to: the sidecar is
+  /// running, or a remote one is configured.
+  pub fn available(&self) -> bool {
+    self.config.lock().unwrap().remote.is_some() || self.inner.lock().unwrap().child.is_some()
+  }
+
+  fn port(&self) -> u16 {
+    self.config.lock().unwrap().port
+  }
+
+  /// Points the sidecar at another data directory, from its next spawn.
+  pub fn set_data_dir(&self, dir: PathBuf) {
+    self.config.lock().unwrap().data_dir = dir;
+  }
+
+  /// Value for [`AUTH_HEADER`] on requests to the current backend.
+  pub fn auth_token(&self) -> String {
+    if let Some(remote) = &self.config.lock().unwrap().remote {
+      return remote.token.clone().unwrap_or_default();
+    }
+    self.inner.lock().unwrap().auth_token.clone()
+  }
+
+  pub fn compatibility(&self) -> BackendCompatibility {
+    self.compatibility.lock().unwrap().clone()
+  }
+
+  pub fn set_compatibility(&self, compatibility: BackendCompatibility) {
+    *self.compatibility.lock().unwrap() = compatibility;
+  }
+
+  /// The sidecar with its environment, ready to spawn.
+  fn command<R: Runtime>(&self, app: &AppHandle<R>, auth_token: &str) -> Result<Command, String> {
+    let config = self.config.lock().unwrap().clone();
+    let cmd = app
+      .shell()
+      .sidecar(SIDECAR_NAME)
+      .map_err(|e| format!("Backend sidecar binary not found: {e}"))?
+      .env("DUGOUT_BACKEND_PORT", config.port.to_string())
+      .env(
+        "DUGOUT_DATA_DIR",
+        config.data_dir.to_string_lossy().to_string(),
+      )
+      .env(
+        "DUGOUT_MODELS_DIR",
+        config.models_dir.to_string_lossy().to_string(),
+      )
+      .env("DUGOUT_BACKEND_TOKEN", auth_token)
+      .env("DUGOUT_APP_VERSION", app.package_info().version.to_string())
+      .env(
+        "DUGOUT_API_VERSION",
+        handshake::SUPPORTED_API_VERSIONS.end().to_string(),
+      );
+    // Provider keys live in the keychain and only ever reach the backend
+    // through its environment
+    #[cfg(desktop)]
+    let cmd = cmd.envs(crate::api_keys::sidecar_env());
+    Ok(cmd)
+  }
+
+  /// The sidecar in its migration mode, which upgrades the data directory
+  /// and exits instead of serving.
+  pub fn migration_command<R: Runtime>(&self, app: &AppHandle<R>) -> Result<Command, String> {
+    let auth_token = uuid::Uuid::new_v4().simple().to_string();
+    Ok(self.command(app, &auth_token)?.args([MIGRATE_ARG]))
+  }
+
+  pub fn data_dir(&self) -> PathBuf {
+    self.config.lock().unwrap().data_dir.clone()
+  }
+
+  /// Spawns the sidecar and starts watching its event channel. It's
+  /// shaken hands with once healthy, as it may be reading another data
+  /// directory than the last child.
+  pub fn spawn<R: Runtime>(&self, app: &AppHandle<R>) -> Result<u32, String> {
+    if self.inner.lock().unwrap().shutting_down {
+      return Err("Backend sidecar is shutting down".into());
+    }
+    if let Some(url) = self.remote_url() {
+      return Err(format!("The app is using the backend at {url} instead of the sidecar"));
+    }
+
+    let auth_token = uuid::Uuid::new_v4().simple().to_string();
+    let cmd = self.command(app, &auth_token)?;
+
+    let (rx, child) = cmd
+      .spawn()
+      .map_err(|e| format!("Failed to spawn backend sidecar: {e}"))?;
+    let pid = child.pid();
+
+    let (generation, attempt) = {
+      let mut inner = self.inner.lock().unwrap();
+      inner.generation += 1;
+      inner.child = Some(child);
+      inner.pid = Some(pid);
+      inner.started_at = Some(now_millis());
+      inner.last_exit_code = None;
+      inner.stopping = false;
+      inner.auth_token = auth_token;
+      (inner.generation, inner.restart_count)
+    };
+    self.set_compatibility(BackendCompatibility::default());
+
+    emit_status(app, BackendState::Running, attempt, None);
+    tauri::async_runtime::spawn(supervise(app.clone(), rx, generation));
+    tauri::async_runtime::spawn(shake_hands_when_healthy(app.clone(), generation));
+
+    Ok(pid)
+  }
+
+  pub fn status(&self) -> SidecarStatus {
+    let remote_url = self.remote_url();
+    let inner = self.inner.lock().unwrap();
+    SidecarStatus {
+      running: inner.child.is_some(),
+      pid: inner.pid,
+      started_at: inner.started_at,
+      last_exit_code: inner.last_exit_code,
+      remote_url,
+    }
+  }
+
+  /// Stops the sidecar for good as the app exits.
+  ///
+  /// Safe to call more than once; later calls are no-ops.
+  pub fn shutdown(&self, timeout: Duration) {
+    self.inner.lock().unwrap().shutting_down = true;
+    self.stop(timeout);
+  }
+
+  /// Stops the current child and spawns a fresh one, which picks up a
+  /// changed data directory. Blocks while the old process exits.
+  pub fn restart<R: Runtime>(&self, app: &AppHandle<R>) -> Result<u32, String> {
+    self.stop(SHUTDOWN_TIMEOUT);
+    self.inner.lock().unwrap().restart_count = 0;
+    self.spawn(app)
+  }
+
+  /// Asks the sidecar to exit, waiting up to `timeout` before killing it.
+  /// The supervisor does not restart a child stopped this way.
+  pub fn stop(&self, timeout: Duration) {
+    let (pid, auth_token) = {
+      let mut inner = self.inner.lock().unwrap();
+      match inner.pid {
+        Some(pid) if inner.child.is_some() => {
+          inner.stopping = true;
+          (pid, inner.auth_token.clone())
+        }
+        _ => return,
+      }
+    };
+
+    log::info!("Stopping backend sidecar (PID: {})", pid);
+    if request_termination(pid, self.port(), &auth_token) {
+      let deadline = Instant::now() + timeout;
+      while Instant::now() < deadline {
+        if self.inner.lock().unwrap().child.is_none() {
+          log::info!("Backend sidecar exited cleanly");
+          return;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+      }
+      log::warn!(
+        "Backend sidecar did not exit within {:?}; killing it",
+        timeout
+      );
+    }
+
+    let child = {
+      let mut inner = self.inner.lock().unwrap();
+      inner.pid = None;
+      inner.child.take()
+    };
+    if let Some(child) = child {
+      if let Err(e) = child.kill() {
+        log::error!("Failed to kill backend sidecar: {}", e);
+      }
+    }
+  }
+
+  /// Returns up to `limit` of the most recent output lines, oldest first.
+  pub fn recent_logs(&self, limit: usize) -> Vec<BackendLogLine> {
+    let logs = self.logs.lock().unwrap();
+    logs.iter().skip(logs.len().saturating_sub(limit)).cloned().collect()
+  }
+
+  pub(crate) fn record_output(&self, stream: LogStream, bytes: &[u8]) {
+    let text = String::from_utf8_lossy(bytes);
+    let line = text.trim_end();
+    if line.is_empty() {
+      return;
+    }
+    match stream {
+      LogStream::Stdout => log::info!("[backend] {}", line),
+      LogStream::Stderr => log::warn!("[backend] {}", line),
+    }
+
+    let mut logs = self.logs.lock().unwrap();
+    if logs.len() == LOG_BUFFER_LINES {
+      logs.pop_front();
+    }
+    logs.push_back(BackendLogLine {
+      timestamp: now_millis(),
+      stream,
+      line: line.to_string(),
+    });
+  }
+
+  /// Records that the child from `generation` exited. Returns the restart
+  /// attempt number if the supervisor should bring it back.
+  fn mark_exited(&self, generation: u64, code: Option<i32>) -> Option<u32> {
+    let mut inner = self.inner.lock().unwrap();
+    if inner.generation != generation {
+      return None;
+    }
+    let uptime = inner
+      .started_at
+      .map(|started| Duration::from_millis(now_millis().saturating_sub(started)))
+      .unwrap_or_default();
+    inner.child = None;
+    inner.pid = None;
+    inner.last_exit_code = code;
+    if inner.shutting_down || inner.stopping {
+      return None;
+    }
+    if uptime >= STABLE_UPTIME {
+      inner.restart_count = 0;
+    }
+    inner.restart_count += 1;
+    Some(inner.restart_count)
+  }
+
+  /// True if nothing has replaced or stopped the child from `generation`.
+  fn is_current(&self, generation: u64) -> bool {
+    let inner = self.inner.lock().unwrap();
+    inner.generation == generation && !inner.shutting_down && !inner.stopping
+  }
+}
+
+/// Runs the version handshake with the child from `generation` once it
+/// answers health checks, unless it has been replaced by then.
+async fn shake_hands_when_healthy<R: Runtime>(app: AppHandle<R>, generation: u64) {
+  let base_url = app.state::<SidecarManager>().base_url();
+  if !crate::health::wait_until_healthy(&base_url, crate::health::READY_TIMEOUT).await {
+    return;
+  }
+  if app.state::<SidecarManager>().is_current(generation) {
+    handshake::run(&app).await;
+  }
+}
+
+/// Watches a child's event channel, forwarding its output to the app log and
+/// restarting it with exponential backoff if it dies unexpectedly.
+async fn supervise<R: Runtime>(
+  app: AppHandle<R>,
+  mut rx: Receiver<CommandEvent>,
+  generation: u64,
+) {
+  while let Some(event) = rx.recv().await {
+    let payload = match event {
+      CommandEvent::Stdout(bytes) => {
+        app
+          .state::<SidecarManager>()
+          .record_output(LogStream::Stdout, &bytes);
+        continue;
+      }
+      CommandEvent::Stderr(bytes) => {
+        app
+          .state::<SidecarManager>()
+          .record_output(LogStream::Stderr, &bytes);
+        continue;
+      }
+      CommandEvent::Error(e) => {
+        log::error!("[backend] {}", e);
+        continue;
+      }
+      CommandEvent::Terminated(payload) => payload,
+      _ => continue,
+    };
+    log::warn!(
+      "Backend sidecar exited (code: {:?}, signal: {:?})",
+      payload.code,
+      payload.signal
+    );
+
+    let manager = app.state::<SidecarManager>();
+    let Some(attempt) = manager.mark_exited(generation, payload.code) else {
+      return;
+    };
+    if attempt > MAX_RESTARTS {
+      log::error!(
+        "Backend sidecar crashed {} times in a row; giving up",
+        MAX_RESTARTS
+      );
+      emit_status(
+        &app,
+        BackendState::Failed,
+        MAX_RESTARTS,
+        Some("The AI backend keeps crashing and was not restarted.".into()),
+      );
+      return;
+    }
+
+    let delay = backoff(attempt);
+    log::info!(
+      "Restarting backend sidecar in {:?} (attempt {}/{})",
+      delay,
+      attempt,
+      MAX_RESTARTS
+    );
+    emit_status(&app, BackendState::Restarting, attempt, None);
+    tokio::time::sleep(delay).await;
+
+    let manager = app.state::<SidecarManager>();
+    if !manager.is_current(generation) {
+      return;
+    }
+    if let Err(e) = manager.spawn(&app) {
+      log::error!("{}", e);
+      emit_status(&app, BackendState::Failed, attempt, Some(e));
+    }
+    break;
+  }
+}
+
+fn backoff(attempt: u32) -> Duration {
+  INITIAL_BACKOFF
+    .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+    .min(MAX_BACKOFF)
+}
+
+pub(crate) fn emit_status<R: Runtime>(
+  app: &AppHandle<R>,
+  state: BackendState,
+  attempt: u32,
+  message: Option<String>,
+) {
+  let payload = BackendStatusEvent {
+    state,
+    attempt,
+    max_attempts: MAX_RESTARTS,
+    message,
+  };
+  if let Err(e) = app.emit(BACKEND_STATUS_EVENT, payload) {
+    log::warn!("Failed to emit {}: {}", BACKEND_STATUS_EVENT, e);
+  }
+}
+
+/// Picks the port for the sidecar: the preferred port (by default
+/// `DEFAULT_PORT`, so the browser dev setup keeps working) when it is free,
+/// otherwise one assigned by the OS unless fallback is turned off.
+pub fn pick_port(settings: &PortSettings) -> u16 {
+  let preferred = settings.preferred;
+  if TcpListener::bind(("127.0.0.1", preferred)).is_ok() {
+    return preferred;
+  }
+  if !settings.fallback {
+    log::warn!("Port {} is in use; the backend may fail to start", preferred);
+    return preferred;
+  }
+  match TcpListener::bind(("127.0.0.1", 0)).and_then(|l| l.local_addr()) {
+    Ok(addr) => {
+      log::info!(
+        "Port {} is in use; backend will listen on {}",
+        preferred,
+        addr.port()
+      );
+      addr.port()
+    }
+    Err(e) => {
+      log::warn!("Failed to find a free port ({}); using {}", e, preferred);
+      preferred
+    }
+  }
+}
+
+/// The backend to use instead of spawning the sidecar, if any.
+pub fn remote_for(settings: &AppSettings) -> Option<RemoteBackend> {
+  let remote = settings.backend_mode == BackendMode::Remote || cfg!(mobile);
+  settings.remote_backend.clone().filter(|_| remote)
+}
+
+/// Rejects a remote backend the proxy couldn't send requests to.
+pub fn validate_remote(remote: &RemoteBackend) -> AppResult<()> {
+  let url = reqwest::Url::parse(remote.url.trim())
+    .map_err(|e| Error::Validation(format!("Invalid backend URL: {e}")))?;
+  if !matches!(url.scheme(), "http" | "https") || url.host().is_none() {
+    return Err(Error::Validation("The backend URL must be an http(s) address".into()));
+  }
+  if url.query().is_some() || url.fragment().is_some() || remote.url.trim() != remote.url {
+    return Err(Error::Validation(format!("{} is not a plain base URL", remote.url)));
+  }
+  Ok(())
+}
+
+/// Sends SIGTERM so uvicorn can flush and close the data files.
+#[cfg(unix)]
+fn request_termination(pid: u32, _port: u16, _auth_token: &str) -> bool {
+  // SAFETY: kill(2) has no memory-safety preconditions.
+  unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) == 0 }
+}
+
+/// Windows has no SIGTERM equivalent for console-less children, so the
+/// backend is asked to stop itself over HTTP instead.
+#[cfg(not(unix))]
+fn request_termination(_pid: u32, port: u16, auth_token: &str) -> bool {
+  match request_shutdown(port, auth_token) {
+    Ok(accepted) => accepted,
+    Err(e) => {
+      log::warn!("Failed to ask the backend sidecar to stop: {}", e);
+      false
+    }
+  }
+}
+
+/// Sends the backend on `port` an authenticated `POST /shutdown`, returning
+/// whether it agreed to stop. Plain HTTP/1.1, since `stop` blocks and runs
+/// while the app is exiting, when there may be no async runtime to use.
+#[cfg(any(not(unix), test))]
+fn request_shutdown(port: u16, auth_token: &str) -> std::io::Result<bool> {
+  use std::io::{BufRead, BufReader, Write};
+
+  let address = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+  let mut stream = std::net::TcpStream::connect_timeout(&address, SHUTDOWN_REQUEST_TIMEOUT)?;
+  stream.set_read_timeout(Some(SHUTDOWN_REQUEST_TIMEOUT))?;
+  write!(
+    stream,
+    "POST /shutdown HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\n{AUTH_HEADER}: {auth_token}\r\n\
+     Content-Length: 0\r\nConnection: close\r\n\r\n"
+  )?;
+  let mut status_line = String::new();
+  BufReader::new(stream).read_line(&mut status_line)?;
+  Ok(status_line.split_whitespace().nth(1) == Some("202"))
+}
+
+pub(crate) fn now_millis() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or_default()
+}
+
+/// Returns the port the backend sidecar was told to listen on.
+#[tauri::command]
+pub fn get_backend_port(sidecar: State<'_, SidecarManager>) -> u16 {
+  sidecar.port()
+}
+
+/// Returns the most recent sidecar output lines (default 200) for support.
+#[tauri::command]
+pub fn get_backend_logs(
+  sidecar: State<'_, SidecarManager>,
+  limit: Option<usize>,
+) -> Vec<BackendLogLine> {
+  sidecar.recent_logs(limit.unwrap_or(200))
+}
+
+/// Reports whether the backend sidecar is running, with its PID and start
+/// time, or which remote backend is used instead.
+#[tauri::command]
+pub fn get_backend_status(sidecar: State<'_, SidecarManager>) -> SidecarStatus {
+  sidecar.status()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn remote(url: &str) -> RemoteBackend {
+    RemoteBackend {
+      url: url.into(),
+      token: Some("secret".into()),
+    }
+  }
+
+  #[test]
+  fn sends_requests_to_a_configured_remote_backend() {
+    let manager = SidecarManager::new(SidecarConfig {
+      port: 8123,
+      data_dir: PathBuf::from("data"),
+      models_dir: PathBuf::from("models"),
+      remote: None,
+    });
+    assert_eq!(manager.base_url(), "http://127.0.0.1:8123");
+    assert!(!manager.available());
+
+    manager.config.lock().unwrap().remote = Some(remote("https://dugout.example/api/"));
+    assert_eq!(manager.base_url(), "https://dugout.example/api");
+    assert_eq!(manager.auth_token(), "secret");
+    assert!(manager.available());
+
+    let mut settings = AppSettings {
+      remote_backend: Some(remote("https://dugout.example")),
+      ..Default::default()
+    };
+    assert_eq!(remote_for(&settings).is_some(), cfg!(mobile));
+    settings.backend_mode = BackendMode::Remote;
+    assert!(remote_for(&settings).is_some());
+
+    assert!(validate_remote(&remote("http://192.168.1.20:8100")).is_ok());
+    for url in ["192.168.1.20:8100", "ftp://host", "http://host/?a=1", " http://host"] {
+      assert!(validate_remote(&remote(url)).is_err(), "{url}");
+    }
+  }
+
+  #[test]
+  fn asks_the_backend_to_shut_down_with_its_token() {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let backend = std::thread::spawn(move || {
+      let (stream, _) = listener.accept().unwrap();
+      let mut reader = BufReader::new(stream);
+      let mut request = Vec::new();
+      loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line == "\r\n" {
+          break;
+        }
+        request.push(line.trim_end().to_string());
+      }
+      let mut stream = reader.into_inner();
+      stream.write_all(b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n").unwrap();
+      request
+    });
+
+    assert!(request_shutdown(port, "secret").unwrap());
+    let request = backend.join().unwrap();
+    assert_eq!(request[0], "POST /shutdown HTTP/1.1");
+    assert!(request.contains(&format!("{AUTH_HEADER}: secret")));
+  }
+}
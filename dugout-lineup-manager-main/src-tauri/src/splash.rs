@@ -0,0 +1,143 @@
+//! Boot splash shown while the shell starts up, so a launch never looks
+//! like nothing happened during the seconds the sidecar takes to boot.
+//!
+//! Setup reports each [`BootStage`] as it reaches it; the splash reads the
+//! latest with `get_boot_progress` when it loads and follows
+//! [`BOOT_PROGRESS_EVENT`] after that. [`crate::health`] closes it once the
+//! main window is shown. The database is only opened once the splash has
+//! loaded (see [`crate::boot`]), so it can count through the migrations.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+pub const SPLASH_WINDOW: &str = "splash";
+
+/// Event carrying the [`BootProgress`] at every stage.
+pub const BOOT_PROGRESS_EVENT: &str = "boot-progress";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BootStage {
+  #[default]
+  Starting,
+  RunningMigrations,
+  /// Upgrading the sidecar's files for the one an update brought.
+  MigratingBackendData,
+  StartingBackend,
+  /// Probing a remote backend, which has no boot to wait for.
+  ConnectingBackend,
+  Ready,
+  /// The backend didn't come up in time; the app opens without AI.
+  Degraded,
+}
+
+impl BootStage {
+  fn message(self) -> &'static str {
+    match self {
+      BootStage::Starting => "Starting Dugout",
+      BootStage::RunningMigrations => "Running migrations",
+      BootStage::MigratingBackendData => "Upgrading your data",
+      BootStage::StartingBackend => "Starting AI engine",
+      BootStage::ConnectingBackend => "Connecting to the AI backend",
+      BootStage::Ready => "Ready",
+      BootStage::Degraded => "AI features are unavailable",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootProgress {
+  pub stage: BootStage,
+  /// Ready to show under the logo.
+  pub message: String,
+}
+
+impl From<BootStage> for BootProgress {
+  fn from(stage: BootStage) -> Self {
+    Self {
+      stage,
+      message: stage.message().into(),
+    }
+  }
+}
+
+impl Default for BootProgress {
+  fn default() -> Self {
+    BootStage::default().into()
+  }
+}
+
+#[derive(Default)]
+pub struct BootState(Mutex<BootProgress>);
+
+/// Records that setup has reached `stage` and tells every window.
+pub fn report<R: Runtime>(app: &AppHandle<R>, stage: BootStage) {
+  publish(app, stage.into());
+}
+
+/// Records that migration `step` of `total` is being applied, e.g.
+/// "Running migrations (2 of 5)".
+pub fn report_migration<R: Runtime>(app: &AppHandle<R>, step: usize, total: usize) {
+  let stage = BootStage::RunningMigrations;
+  publish(
+    app,
+    BootProgress {
+      stage,
+      message: format!("{} ({step} of {total})", stage.message()),
+    },
+  );
+}
+
+fn publish<R: Runtime>(app: &AppHandle<R>, progress: BootProgress) {
+  log::debug!("Boot: {}", progress.message);
+  if let Some(state) = app.try_state::<BootState>() {
+    *state.0.lock().unwrap() = progress.clone();
+  }
+  let _ = app.emit(BOOT_PROGRESS_EVENT, progress);
+}
+
+/// Opens the splash, centered and undecorated. It loads the `/splash`
+/// route, which renders without the main window's startup hooks. The
+/// returned receiver is told once the page has loaded.
+#[cfg(desktop)]
+pub fn open<R: Runtime>(
+  app: &AppHandle<R>,
+) -> tauri::Result<tokio::sync::oneshot::Receiver<()>> {
+  let (loaded, on_loaded) = tokio::sync::oneshot::channel();
+  let loaded = Mutex::new(Some(loaded));
+  tauri::WebviewWindowBuilder::new(app, SPLASH_WINDOW, tauri::WebviewUrl::App("splash".into()))
+    .on_page_load(move |_, payload| {
+      if payload.event() == tauri::webview::PageLoadEvent::Finished {
+        if let Some(loaded) = loaded.lock().unwrap().take() {
+          let _ = loaded.send(());
+        }
+      }
+    })
+    .title("Dugout")
+    .inner_size(360.0, 220.0)
+    .resizable(false)
+    .decorations(false)
+    .center()
+    .build()?;
+  Ok(on_loaded)
+}
+
+/// Records the final stage and closes the splash, if it's open.
+pub fn finish<R: Runtime>(app: &AppHandle<R>, healthy: bool) {
+  report(app, if healthy { BootStage::Ready } else { BootStage::Degraded });
+  if let Some(window) = app.get_webview_window(SPLASH_WINDOW) {
+    // Not `close`, so its geometry isn't saved with the real windows'
+    if let Err(e) = window.destroy() {
+      log::warn!("Failed to close the splash window: {}", e);
+    }
+  }
+}
+
+/// The latest stage, for a splash that loads after setup has moved on.
+#[tauri::command]
+pub fn get_boot_progress(state: State<'_, BootState>) -> BootProgress {
+  state.0.lock().unwrap().clone()
+}
@@ -0,0 +1,432 @@
+//! Batting, fielding and pitching stats computed natively from the box
+//! scores in `game_stats`, so they render without a sidecar round trip.
+//!
+//! Each player's line is cached after it is first computed. Triggers on
+//! `game_stats` bump the player's version in `stat_changes`, and only
+//! players with a newer version than the cache has seen are recomputed.
+//! Rates are rounded the way the backend rounds them.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use rusqlite::{Connection, Row};
+use serde::Serialize;
+use tauri::State;
+
+use crate::db::roster::{self, Player};
+use crate::db::Database;
+use crate::error::Result;
+
+/// Innings ERA is scaled to.
+const ERA_INNINGS: f64 = 9.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BattingLine {
+  pub pa: u32,
+  pub ab: u32,
+  pub h: u32,
+  pub doubles: u32,
+  pub triples: u32,
+  pub hr: u32,
+  pub r: u32,
+  pub rbi: u32,
+  pub bb: u32,
+  pub so: u32,
+  pub sb: u32,
+  pub cs: u32,
+  pub total_bases: u32,
+  pub avg: Option<f64>,
+  pub obp: Option<f64>,
+  pub slg: Option<f64>,
+  pub ops: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldingLine {
+  pub po: u32,
+  pub a: u32,
+  pub e: u32,
+  /// Total chances: putouts, assists and errors.
+  pub chances: u32,
+  pub fpct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PitchingLine {
+  /// Games with any innings or pitches.
+  pub appearances: u32,
+  pub outs: u32,
+  /// In baseball notation, e.g. `5.2` for five and two-thirds.
+  pub ip: f64,
+  pub h: u32,
+  pub r: u32,
+  pub er: u32,
+  pub bb: u32,
+  pub k: u32,
+  pub pitches: u32,
+  pub era: Option<f64>,
+  pub whip: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatLines {
+  pub games: u32,
+  pub batting: BattingLine,
+  pub fielding: FieldingLine,
+  pub pitching: PitchingLine,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerStats {
+  pub player_id: String,
+  pub name: String,
+  pub number: Option<u32>,
+  #[serde(flatten)]
+  pub lines: StatLines,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamStats {
+  pub team_id: String,
+  /// Games with at least one box score.
+  pub games: u32,
+  pub batting: BattingLine,
+  pub fielding: FieldingLine,
+  pub pitching: PitchingLine,
+  pub players: Vec<PlayerStats>,
+}
+
+fn round(value: f64, places: i32) -> f64 {
+  let scale = 10f64.powi(places);
+  (value * scale).round() / scale
+}
+
+fn ratio(numerator: u32, denominator: u32, places: i32) -> Option<f64> {
+  (denominator > 0).then(|| round(f64::from(numerator) / f64::from(denominator), places))
+}
+
+impl BattingLine {
+  fn add(&mut self, other: &Self) {
+    self.ab += other.ab;
+    self.h += other.h;
+    self.doubles += other.doubles;
+    self.triples += other.triples;
+    self.hr += other.hr;
+    self.r += other.r;
+    self.rbi += other.rbi;
+    self.bb += other.bb;
+    self.so += other.so;
+    self.sb += other.sb;
+    self.cs += other.cs;
+  }
+
+  /// Fills in total bases and the rates from the counting stats.
+  fn finish(mut self) -> Self {
+    let singles = self.h.saturating_sub(self.doubles + self.triples + self.hr);
+    self.pa = self.ab + self.bb;
+    self.total_bases = singles + 2 * self.doubles + 3 * self.triples + 4 * self.hr;
+    self.avg = ratio(self.h, self.ab, 3);
+    self.obp = ratio(self.h + self.bb, self.pa, 3);
+    self.slg = ratio(self.total_bases, self.ab, 3);
+    self.ops = match (self.obp, self.slg) {
+      (Some(obp), Some(slg)) => Some(round(obp + slg, 3)),
+      _ => None,
+    };
+    self
+  }
+}
+
+impl FieldingLine {
+  fn add(&mut self, other: &Self) {
+    self.po += other.po;
+    self.a += other.a;
+    self.e += other.e;
+  }
+
+  fn finish(mut self) -> Self {
+    self.chances = self.po + self.a + self.e;
+    self.fpct = ratio(self.po + self.a, self.chances, 3);
+    self
+  }
+}
+
+impl PitchingLine {
+  fn add(&mut self, other: &Self) {
+    self.appearances += other.appearances;
+    self.outs += other.outs;
+    self.h += other.h;
+    self.r += other.r;
+    self.er += other.er;
+    self.bb += other.bb;
+    self.k += other.k;
+    self.pitches += other.pitches;
+  }
+
+  fn finish(mut self) -> Self {
+    self.ip = f64::from(self.outs / 3) + f64::from(self.outs % 3) / 10.0;
+    let innings = f64::from(self.outs) / 3.0;
+    if self.outs > 0 {
+      self.era = Some(round(f64::from(self.er) * ERA_INNINGS / innings, 2));
+      self.whip = Some(round(f64::from(self.h + self.bb) / innings, 2));
+    }
+    self
+  }
+}
+
+/// Sums of a set of `game_stats` rows, read back by [`from_totals`].
+/// Innings are turned into outs per game before adding, since `1.2 + 1.2`
+/// innings is three, not 2.4.
+pub(crate) const TOTALS: &str = "COUNT(*), \
+   TOTAL(ab), TOTAL(h), TOTAL(doubles), TOTAL(triples), TOTAL(hr), TOTAL(r), \
+   TOTAL(rbi), TOTAL(bb), TOTAL(so), TOTAL(sb), TOTAL(cs), \
+   TOTAL(po), TOTAL(a), TOTAL(e), \
+   TOTAL(ip > 0 OR pitches > 0), \
+   TOTAL(CAST(ip AS INTEGER) * 3 + ROUND((ip - CAST(ip AS INTEGER)) * 10)), \
+   TOTAL(h_allowed), TOTAL(r_allowed), TOTAL(er), TOTAL(bb_allowed), TOTAL(k), \
+   TOTAL(pitches)";
+
+/// The stat lines from [`TOTALS`] selected starting at column `first`.
+pub(crate) fn from_totals(row: &Row<'_>, first: usize) -> rusqlite::Result<StatLines> {
+  // TOTAL() always returns a float, even over integer columns.
+  let count = |i: usize| row.get::<_, f64>(first + i).map(|v| v as u32);
+  Ok(StatLines {
+    games: row.get(first)?,
+    batting: BattingLine {
+      ab: count(1)?,
+      h: count(2)?,
+      doubles: count(3)?,
+      triples: count(4)?,
+      hr: count(5)?,
+      r: count(6)?,
+      rbi: count(7)?,
+      bb: count(8)?,
+      so: count(9)?,
+      sb: count(10)?,
+      cs: count(11)?,
+      ..Default::default()
+    }
+    .finish(),
+    fielding: FieldingLine {
+      po: count(12)?,
+      a: count(13)?,
+      e: count(14)?,
+      ..Default::default()
+    }
+    .finish(),
+    pitching: PitchingLine {
+      appearances: count(15)?,
+      outs: count(16)?,
+      h: count(17)?,
+      r: count(18)?,
+      er: count(19)?,
+      bb: count(20)?,
+      k: count(21)?,
+      pitches: count(22)?,
+      ..Default::default()
+    }
+    .finish(),
+  })
+}
+
+/// Sums a player's box scores.
+pub fn compute(conn: &Connection, player_id: &str) -> Result<StatLines> {
+  let sql = format!("SELECT {TOTALS} FROM game_stats WHERE player_id = ?1");
+  Ok(conn.query_row(&sql, [player_id], |row| from_totals(row, 0))?)
+}
+
+#[derive(Default)]
+struct Cache {
+  /// Highest `stat_changes` version already applied.
+  version: i64,
+  lines: HashMap<String, StatLines>,
+}
+
+/// Computed stat lines by player, kept until their box scores change.
+#[derive(Default)]
+pub struct StatsCache(Mutex<Cache>);
+
+impl StatsCache {
+  /// Forgets everything, e.g. after the database is restored from a
+  /// backup with its own change versions.
+  pub fn clear(&self) {
+    *self.0.lock().unwrap() = Cache::default();
+  }
+
+  /// Stat lines for each of `players`, recomputing only those whose box
+  /// scores changed since they were cached.
+  pub fn lines(&self, conn: &Connection, players: &[&Player]) -> Result<Vec<StatLines>> {
+    let mut cache = self.0.lock().unwrap();
+    let mut stmt =
+      conn.prepare_cached("SELECT player_id, version FROM stat_changes WHERE version > ?1")?;
+    let changed = stmt
+      .query_map([cache.version], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?
+      .collect::<rusqlite::Result<Vec<_>>>()?;
+    for (player_id, version) in changed {
+      cache.lines.remove(&player_id);
+      cache.version = cache.version.max(version);
+    }
+
+    players
+      .iter()
+      .map(|player| match cache.lines.get(&player.id) {
+        Some(lines) => Ok(lines.clone()),
+        None => {
+          let lines = compute(conn, &player.id)?;
+          cache.lines.insert(player.id.clone(), lines.clone());
+          Ok(lines)
+        }
+      })
+      .collect()
+  }
+}
+
+fn player_stats(player: &Player, lines: StatLines) -> PlayerStats {
+  PlayerStats {
+    player_id: player.id.clone(),
+    name: player.name.clone(),
+    number: player.number,
+    lines,
+  }
+}
+
+#[tauri::command]
+pub fn get_player_stats(
+  db: State<'_, Database>,
+  cache: State<'_, StatsCache>,
+  player_id: String,
+) -> Result<PlayerStats> {
+  let conn = db.conn();
+  let player = roster::get(&conn, &player_id)?;
+  let lines = cache.lines(&conn, &[&player])?.remove(0);
+  Ok(player_stats(&player, lines))
+}
+
+/// Team totals plus every player's line, defaulting to the first team.
+#[tauri::command]
+pub fn get_team_stats(
+  db: State<'_, Database>,
+  cache: State<'_, StatsCache>,
+  team_id: Option<String>,
+) -> Result<TeamStats> {
+  let conn = db.conn();
+  let team_id = match team_id {
+    Some(team_id) => team_id,
+    None => roster::default_team_id(&conn)?,
+  };
+  let roster = roster::list(&conn, &team_id)?;
+  let players: Vec<&Player> = roster.iter().collect();
+  let lines = cache.lines(&conn, &players)?;
+
+  let mut batting = BattingLine::default();
+  let mut fielding = FieldingLine::default();
+  let mut pitching = PitchingLine::default();
+  for line in &lines {
+    batting.add(&line.batting);
+    fielding.add(&line.fielding);
+    pitching.add(&line.pitching);
+  }
+  let games = conn.query_row(
+    "SELECT COUNT(DISTINCT s.game_id) FROM game_stats s \
+     JOIN players p ON p.id = s.player_id WHERE p.team_id = ?1",
+    [&team_id],
+    |row| row.get(0),
+  )?;
+
+  Ok(TeamStats {
+    team_id,
+    games,
+    batting: batting.finish(),
+    fielding: fielding.finish(),
+    pitching: pitching.finish(),
+    players: players
+      .iter()
+      .zip(lines)
+      .map(|(player, lines)| player_stats(player, lines))
+      .collect(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+  use crate::db::roster::NewPlayer;
+
+  fn pitcher(conn: &Connection) -> Player {
+    roster::create(
+      conn,
+      NewPlayer {
+        team_id: None,
+        name: "Jake Miller".into(),
+        number: Some(12),
+        primary_position: "P".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap()
+  }
+
+  fn box_score(conn: &Connection, game_id: &str, player_id: &str, columns: &str, values: &str) {
+    conn
+      .execute(
+        "INSERT OR IGNORE INTO games (id, team_id, date, opponent, created_at, updated_at) \
+         SELECT ?1, team_id, '2026-04-01', 'Tigers', '', '' FROM players WHERE id = ?2",
+        [game_id, player_id],
+      )
+      .unwrap();
+    conn
+      .execute(
+        &format!(
+          "INSERT INTO game_stats (game_id, player_id, {columns}) VALUES (?1, ?2, {values})"
+        ),
+        [game_id, player_id],
+      )
+      .unwrap();
+  }
+
+  #[test]
+  fn computes_rates_and_adds_innings_as_outs() {
+    let conn = open_in_memory();
+    let jake = pitcher(&conn);
+    let columns = "ab, h, doubles, bb, ip, er, h_allowed";
+    box_score(&conn, "g1", &jake.id, columns, "4, 2, 1, 1, 1.2, 1, 2");
+    box_score(&conn, "g2", &jake.id, "ab, h, hr, ip, po, e", "3, 1, 1, 1.2, 2, 1");
+
+    let lines = compute(&conn, &jake.id).unwrap();
+    assert_eq!(lines.games, 2);
+    assert_eq!(lines.batting.avg, Some(0.429));
+    assert_eq!(lines.batting.obp, Some(0.5));
+    assert_eq!(lines.batting.total_bases, 7);
+    assert_eq!(lines.pitching.outs, 10);
+    assert_eq!(lines.pitching.ip, 3.1);
+    assert_eq!(lines.pitching.era, Some(2.7));
+    assert_eq!(lines.fielding.fpct, Some(0.667));
+  }
+
+  #[test]
+  fn recomputes_only_after_box_scores_change() {
+    let conn = open_in_memory();
+    let jake = pitcher(&conn);
+    let cache = StatsCache::default();
+    box_score(&conn, "g1", &jake.id, "ab, h", "3, 1");
+    assert_eq!(cache.lines(&conn, &[&jake]).unwrap()[0].batting.h, 1);
+
+    conn.execute("UPDATE game_stats SET h = 3", []).unwrap();
+    assert_eq!(cache.lines(&conn, &[&jake]).unwrap()[0].batting.h, 3);
+
+    // Without a recorded change the cached line is served as is
+    conn.execute("UPDATE game_stats SET h = 0", []).unwrap();
+    conn.execute("DELETE FROM stat_changes", []).unwrap();
+    assert_eq!(cache.lines(&conn, &[&jake]).unwrap()[0].batting.h, 3);
+  }
+}
@@ -0,0 +1,369 @@
+//! Substitutions planned before the game: given the starting lineup and
+//! the changes the coach means to make, when each player can still come in
+//! or go out under the league's re-entry and bench rules, and which planned
+//! changes would break them.
+//!
+//! The starting lineup is its first inning. Each later inning carries the
+//! field forward and applies the changes planned for it, so any positions
+//! saved for later innings are replaced by the plan.
+
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::lineups::{InningPosition, Lineup, LineupInput};
+use crate::db::roster::{self, Player};
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::optimizer::FIELD_POSITIONS;
+use crate::rules::{self, Rule, Severity, Violation};
+use crate::settings::AppSettings;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedSub {
+  /// The change is made before this inning starts.
+  pub inning: u32,
+  pub out_player_id: String,
+  pub in_player_id: String,
+  /// Defaults to the position of the player coming out.
+  pub position: Option<String>,
+}
+
+/// When a player can be changed, given the plan.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubWindow {
+  pub player_id: String,
+  pub name: String,
+  /// Innings they could come into the game, from the bench.
+  pub can_enter: Vec<u32>,
+  /// Innings they could come out for the rest of the game without sitting
+  /// longer than the bench limits allow.
+  pub can_leave: Vec<u32>,
+  /// Positions they've left and can't go back to.
+  pub barred_positions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanViolation {
+  /// Index of the planned change that causes it, or `None` if the lineup
+  /// breaks the rule without any changes.
+  pub sub: Option<usize>,
+  #[serde(flatten)]
+  pub violation: Violation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubstitutionPlan {
+  /// Who plays where in each inning once the changes are made.
+  pub positions: Vec<InningPosition>,
+  /// Everyone in the lineup or active on the roster.
+  pub windows: Vec<SubWindow>,
+  pub violations: Vec<PlanViolation>,
+}
+
+fn problem(
+  sub: usize,
+  kind: &'static str,
+  player_id: Option<&str>,
+  inning: u32,
+  message: String,
+) -> PlanViolation {
+  PlanViolation {
+    sub: Some(sub),
+    violation: Violation {
+      rule: None,
+      kind,
+      severity: Severity::Error,
+      player_id: player_id.map(str::to_string),
+      innings: vec![inning],
+      message,
+    },
+  }
+}
+
+/// `lineup` with `subs` made, and the changes that couldn't be made.
+fn apply(
+  lineup: &Lineup,
+  subs: &[PlannedSub],
+  name: impl Fn(&str) -> String,
+) -> (Lineup, Vec<PlanViolation>) {
+  let mut field: Vec<InningPosition> =
+    lineup.positions.iter().filter(|p| p.inning == 1).cloned().collect();
+  let mut positions = Vec::new();
+  let mut problems = Vec::new();
+
+  for (index, sub) in subs.iter().enumerate() {
+    if !(1..=lineup.innings).contains(&sub.inning) {
+      let message = format!(
+        "Inning {} is outside the {}-inning game",
+        sub.inning, lineup.innings
+      );
+      problems.push(problem(index, "inningOutOfRange", None, sub.inning, message));
+    }
+  }
+  for inning in 1..=lineup.innings {
+    for (index, sub) in subs.iter().enumerate().filter(|(_, s)| s.inning == inning) {
+      if field.iter().any(|p| p.player_id == sub.in_player_id) {
+        let message = format!("{} is already on the field", name(&sub.in_player_id));
+        let player = Some(sub.in_player_id.as_str());
+        problems.push(problem(index, "alreadyOnField", player, inning, message));
+        continue;
+      }
+      let Some(spot) = field.iter_mut().find(|p| p.player_id == sub.out_player_id) else {
+        let message = format!("{} isn't on the field to come out", name(&sub.out_player_id));
+        let player = Some(sub.out_player_id.as_str());
+        problems.push(problem(index, "notOnField", player, inning, message));
+        continue;
+      };
+      spot.player_id = sub.in_player_id.clone();
+      if let Some(position) = &sub.position {
+        spot.position = position.clone();
+      }
+    }
+    positions.extend(field.iter().map(|p| InningPosition {
+      inning,
+      ..p.clone()
+    }));
+  }
+
+  let planned = Lineup {
+    positions,
+    ..lineup.clone()
+  };
+  (planned, problems)
+}
+
+/// Substitution windows for `player_id` in the `planned` lineup.
+fn window(planned: &Lineup, rules: &[Rule], player_id: &str, name: String) -> SubWindow {
+  let at = |inning: u32| planned.position_in(player_id, inning);
+  let innings = planned.innings;
+  let played_before = |inning: u32| (1..inning).any(|i| at(i).is_some());
+  // Going back on after having come off
+  let reentries_before = |inning: u32| {
+    (2..inning)
+      .filter(|&i| at(i).is_some() && at(i - 1).is_none() && played_before(i))
+      .count() as u32
+  };
+  let max_reentries = rules
+    .iter()
+    .filter_map(|rule| match rule {
+      Rule::MaxReentries { max } => Some(*max),
+      _ => None,
+    })
+    .min();
+
+  let can_enter = (2..=innings)
+    .filter(|&i| at(i - 1).is_none())
+    .filter(|&i| {
+      !played_before(i) || max_reentries.map_or(true, |max| reentries_before(i) < max)
+    })
+    .collect();
+
+  let can_leave = (2..=innings)
+    .filter(|&i| at(i - 1).is_some())
+    .filter(|&i| {
+      let sat = (1..i).filter(|&b| at(b).is_none()).count() as u32;
+      let rest = innings - i + 1;
+      rules.iter().all(|rule| match rule {
+        Rule::MaxBenchInnings { max } => sat + rest <= *max,
+        Rule::MaxConsecutiveBenchInnings { max } => rest <= *max,
+        _ => true,
+      })
+    })
+    .collect();
+
+  let barred_positions = rules
+    .iter()
+    .filter_map(|rule| match rule {
+      Rule::NoReturnToPosition { position } => Some(position.as_str()),
+      _ => None,
+    })
+    .filter(|&position| {
+      (2..=innings).any(|i| at(i - 1) == Some(position) && at(i) != Some(position))
+    })
+    .map(str::to_string)
+    .collect();
+
+  SubWindow {
+    player_id: player_id.to_string(),
+    name,
+    can_enter,
+    can_leave,
+    barred_positions,
+  }
+}
+
+/// Makes the planned `subs` to `lineup` and checks the result against
+/// `rules`. Rule violations are put down to the first change that brings
+/// in or takes out the player involved in one of the innings named.
+pub fn plan(
+  lineup: &Lineup,
+  players: &[Player],
+  subs: &[PlannedSub],
+  rules: &[Rule],
+) -> Result<SubstitutionPlan> {
+  for position in subs.iter().filter_map(|s| s.position.as_deref()) {
+    if !FIELD_POSITIONS.contains(&position) {
+      return Err(Error::Validation(format!("{position} is not a fielding position")));
+    }
+  }
+  let name = |id: &str| {
+    players
+      .iter()
+      .find(|p| p.id == id)
+      .map_or_else(|| id.to_string(), |p| p.name.clone())
+  };
+
+  let (planned, mut violations) = apply(lineup, subs, name);
+  for violation in rules::check_all(&planned, players, rules) {
+    let sub = subs.iter().position(|s| {
+      violation.innings.contains(&s.inning)
+        && violation
+          .player_id
+          .as_deref()
+          .map_or(true, |id| id == s.in_player_id || id == s.out_player_id)
+    });
+    violations.push(PlanViolation { sub, violation });
+  }
+  violations.sort_by_key(|v| v.sub.map_or(0, |sub| sub + 1));
+
+  // The batting order and fielders, then the rest of the active roster
+  let mut ids: Vec<&str> = Vec::new();
+  let candidates = planned
+    .slots
+    .iter()
+    .filter_map(|s| s.player_id.as_deref())
+    .chain(planned.positions.iter().map(|p| p.player_id.as_str()))
+    .chain(players.iter().filter(|p| p.status == "active").map(|p| p.id.as_str()));
+  for id in candidates {
+    if !ids.contains(&id) {
+      ids.push(id);
+    }
+  }
+  let windows = ids
+    .into_iter()
+    .map(|id| window(&planned, rules, id, name(id)))
+    .collect();
+
+  Ok(SubstitutionPlan {
+    positions: planned.positions.clone(),
+    windows,
+    violations,
+  })
+}
+
+/// Plans `subs` for the saved lineup `id` or a proposed `lineup`, under
+/// `rules` or the league rules in settings when omitted.
+#[tauri::command]
+pub fn plan_substitutions(
+  db: State<'_, Database>,
+  settings: State<'_, RwLock<AppSettings>>,
+  id: Option<String>,
+  lineup: Option<LineupInput>,
+  subs: Vec<PlannedSub>,
+  rules: Option<Vec<Rule>>,
+) -> Result<SubstitutionPlan> {
+  let rules = rules::rules_or_league(&settings, rules)?;
+  let conn = db.conn();
+  let lineup = rules::load(&conn, id, lineup)?;
+  let players = roster::list(&conn, &lineup.team_id)?;
+  plan(&lineup, &players, &subs, &rules)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn starting(innings: u32, positions: &[(&str, &str)]) -> Lineup {
+    Lineup {
+      id: "l1".into(),
+      team_id: "t1".into(),
+      name: "Game 1".into(),
+      game_id: None,
+      use_dh: false,
+      innings,
+      slots: Vec::new(),
+      positions: positions
+        .iter()
+        .map(|&(position, player_id)| InningPosition {
+          inning: 1,
+          position: position.into(),
+          player_id: player_id.into(),
+        })
+        .collect(),
+      updated_at: String::new(),
+      versions: Default::default(),
+    }
+  }
+
+  fn sub(inning: u32, out: &str, into: &str, position: Option<&str>) -> PlannedSub {
+    PlannedSub {
+      inning,
+      out_player_id: out.into(),
+      in_player_id: into.into(),
+      position: position.map(str::to_string),
+    }
+  }
+
+  #[test]
+  fn carries_the_field_forward_and_flags_illegal_changes() {
+    let lineup = starting(6, &[("P", "ace"), ("C", "cat"), ("SS", "sub")]);
+    let rules = [
+      Rule::MaxReentries { max: 0 },
+      Rule::NoReturnToPosition {
+        position: "P".into(),
+      },
+      Rule::MaxBenchInnings { max: 4 },
+    ];
+    let subs = [
+      // The ace moves to short and the shortstop pitches
+      sub(3, "sub", "ace", Some("SS")),
+      sub(3, "ace", "sub", Some("P")),
+      // Pulled in the fourth, then brought back in the sixth
+      sub(4, "cat", "rook", None),
+      sub(6, "rook", "cat", None),
+    ];
+
+    let planned = plan(&lineup, &[], &subs, &rules).unwrap();
+    let at = |inning: u32, player: &str| {
+      planned
+        .positions
+        .iter()
+        .find(|p| p.inning == inning && p.player_id == player)
+        .map(|p| p.position.as_str())
+    };
+    assert_eq!(at(2, "cat"), Some("C"));
+    assert_eq!(at(5, "rook"), Some("C"));
+    assert_eq!(at(6, "cat"), Some("C"));
+
+    // Swapping two fielders isn't a substitution
+    let kinds: Vec<(Option<usize>, &str)> =
+      planned.violations.iter().map(|v| (v.sub, v.violation.kind)).collect();
+    assert_eq!(
+      kinds,
+      [
+        (Some(0), "alreadyOnField"),
+        (Some(1), "alreadyOnField"),
+        (Some(3), "maxReentries"),
+      ]
+    );
+
+    let window = |id: &str| planned.windows.iter().find(|w| w.player_id == id).unwrap();
+    // Re-entry isn't allowed, so once pulled the catcher stays out
+    assert_eq!(window("cat").can_enter, Vec::<u32>::new());
+    // A rookie who hasn't played can go in whenever they're on the bench
+    assert_eq!(window("rook").can_enter, [2, 3, 4]);
+    // Pulling the ace before the third sits them too long
+    assert_eq!(window("ace").can_leave, [3, 4, 5, 6]);
+
+    let pitcher_pulled = plan(&lineup, &[], &[sub(2, "ace", "rook", None)], &rules).unwrap();
+    let ace = pitcher_pulled.windows.iter().find(|w| w.player_id == "ace").unwrap();
+    assert_eq!(ace.barred_positions, ["P"]);
+    assert!(plan(&lineup, &[], &[sub(2, "ace", "rook", Some("DH"))], &rules).is_err());
+  }
+}
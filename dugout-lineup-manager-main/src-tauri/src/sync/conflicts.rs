@@ -0,0 +1,262 @@
+//! Lineup edits from a paired device that clashed with one made here.
+//!
+//! When both devices changed a lineup without seeing each other's edit, the
+//! incoming copy is set aside instead of overwriting ours, and the coach
+//! picks what to keep: their own version, the other device's, or their own
+//! with some innings of fielding taken from the other. Whatever they pick is
+//! saved as a new edit that has seen both, so the other device takes it
+//! without a conflict of its own.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::{Change, SyncState};
+use crate::db::lineups::{self, Lineup};
+use crate::db::undo::{self, Action};
+use crate::db::{audit, timestamp, versions, Database};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Strategy {
+  KeepMine,
+  KeepTheirs,
+  /// Ours, with some innings' fielding from theirs.
+  MergeInnings,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+  pub lineup_id: String,
+  /// The device the clashing edit came from.
+  pub device: String,
+  pub detected_at: String,
+  pub mine: Lineup,
+  pub theirs: Lineup,
+}
+
+/// Sets aside `theirs`, replacing an earlier edit of the same lineup that
+/// is still waiting.
+pub(super) fn record(conn: &Connection, theirs: &Lineup, device: &str) -> Result<()> {
+  conn.execute(
+    "INSERT OR REPLACE INTO sync_conflicts (lineup_id, device, theirs, detected_at) \
+     VALUES (?1, ?2, ?3, ?4)",
+    params![theirs.id, device, serde_json::to_string(theirs)?, timestamp()],
+  )?;
+  log::info!("Lineup {} conflicts with an edit from {}", theirs.id, device);
+  Ok(())
+}
+
+/// Drops a waiting conflict, e.g. once the other device has resolved it.
+pub(super) fn clear(conn: &Connection, lineup_id: &str) -> Result<()> {
+  conn.execute("DELETE FROM sync_conflicts WHERE lineup_id = ?1", [lineup_id])?;
+  Ok(())
+}
+
+/// Whether two copies of a lineup show the coach the same thing, whatever
+/// their save history.
+pub(super) fn same_content(a: &Lineup, b: &Lineup) -> bool {
+  let slots = |l: &Lineup| -> Vec<_> {
+    l.slots
+      .iter()
+      .map(|s| (s.slot_number, s.player_id.clone(), s.position.clone()))
+      .collect()
+  };
+  let positions = |l: &Lineup| {
+    let mut positions = l.positions.clone();
+    positions.sort_by(|x, y| (x.inning, &x.position).cmp(&(y.inning, &y.position)));
+    positions
+  };
+  a.name == b.name
+    && a.game_id == b.game_id
+    && a.use_dh == b.use_dh
+    && a.innings == b.innings
+    && slots(a) == slots(b)
+    && positions(a) == positions(b)
+}
+
+fn get(conn: &Connection, lineup_id: &str) -> Result<SyncConflict> {
+  let (device, theirs, detected_at): (String, String, String) = conn
+    .query_row(
+      "SELECT device, theirs, detected_at FROM sync_conflicts WHERE lineup_id = ?1",
+      [lineup_id],
+      |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+    .optional()?
+    .ok_or_else(|| Error::NotFound(format!("Conflict for lineup {lineup_id}")))?;
+  Ok(SyncConflict {
+    lineup_id: lineup_id.into(),
+    device,
+    detected_at,
+    mine: lineups::get(conn, lineup_id)?,
+    theirs: serde_json::from_str(&theirs)?,
+  })
+}
+
+/// Every waiting conflict, oldest first.
+pub fn list(conn: &Connection) -> Result<Vec<SyncConflict>> {
+  let ids = conn
+    .prepare("SELECT lineup_id FROM sync_conflicts ORDER BY detected_at")?
+    .query_map([], |row| row.get::<_, String>(0))?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  ids.iter().map(|id| get(conn, id)).collect()
+}
+
+/// Ours, with the fielding for `theirs_innings` taken from theirs. Without
+/// a list, the innings ours leaves empty are filled from theirs.
+fn merge_innings(mine: &Lineup, theirs: &Lineup, theirs_innings: Option<&[u32]>) -> Lineup {
+  let take_theirs = |inning: u32| match theirs_innings {
+    Some(innings) => innings.contains(&inning),
+    None => !mine.positions.iter().any(|p| p.inning == inning),
+  };
+  let mut positions: Vec<_> = mine
+    .positions
+    .iter()
+    .filter(|p| !take_theirs(p.inning))
+    .chain(theirs.positions.iter().filter(|p| take_theirs(p.inning)))
+    .cloned()
+    .collect();
+  positions.sort_by(|x, y| (x.inning, &x.position).cmp(&(y.inning, &y.position)));
+  Lineup {
+    innings: mine.innings.max(theirs.innings),
+    positions,
+    ..mine.clone()
+  }
+}
+
+/// Settles a conflict with `strategy`, saving the result as a new edit.
+pub fn resolve(
+  conn: &mut Connection,
+  lineup_id: &str,
+  strategy: Strategy,
+  theirs_innings: Option<&[u32]>,
+) -> Result<Lineup> {
+  let tx = conn.transaction()?;
+  let SyncConflict { mine, theirs, .. } = get(&tx, lineup_id)?;
+  let mut resolved = match strategy {
+    Strategy::KeepMine => mine.clone(),
+    Strategy::KeepTheirs => Lineup {
+      id: mine.id.clone(),
+      team_id: mine.team_id.clone(),
+      ..theirs.clone()
+    },
+    Strategy::MergeInnings => {
+      let innings = mine.innings.max(theirs.innings);
+      let outside = |inning: &&u32| !(1..=innings).contains(*inning);
+      if let Some(bad) = theirs_innings.into_iter().flatten().find(outside) {
+        return Err(Error::Validation(format!(
+          "Inning {bad} is outside the {innings}-inning game"
+        )));
+      }
+      merge_innings(&mine, &theirs, theirs_innings)
+    }
+  };
+  resolved.versions = versions::merged(&mine.versions, &theirs.versions);
+  versions::bump(&tx, &mut resolved.versions)?;
+  resolved.updated_at = timestamp();
+  lineups::write(&tx, &resolved)?;
+  clear(&tx, lineup_id)?;
+
+  let resolved = lineups::get(&tx, lineup_id)?;
+  undo::record(&tx, Action::SaveLineup, Some(&mine), Some(&resolved))?;
+  audit::lineup_changed(&tx, None, Some(&mine), Some(&resolved))?;
+  tx.commit()?;
+  Ok(resolved)
+}
+
+#[tauri::command]
+pub fn get_sync_conflicts(db: State<'_, Database>) -> Result<Vec<SyncConflict>> {
+  list(&db.conn())
+}
+
+/// Settles the conflict on `lineup_id` and sends the result to paired
+/// devices. `theirs_innings` picks the innings `mergeInnings` takes from the
+/// other device.
+#[tauri::command]
+pub fn resolve_conflict(
+  db: State<'_, Database>,
+  sync: State<'_, SyncState>,
+  lineup_id: String,
+  strategy: Strategy,
+  theirs_innings: Option<Vec<u32>>,
+) -> Result<Lineup> {
+  let lineup = resolve(&mut db.conn(), &lineup_id, strategy, theirs_innings.as_deref())?;
+  sync.broadcast(Change::LineupSaved {
+    lineup: lineup.clone(),
+  });
+  Ok(lineup)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::lineups::{InningPosition, LineupInput};
+  use crate::db::open_in_memory;
+  use crate::db::roster::{self, NewPlayer};
+  use crate::sync::merge::{self, Outcome};
+
+  fn at(inning: u32, position: &str, player_id: &str) -> InningPosition {
+    InningPosition {
+      inning,
+      position: position.into(),
+      player_id: player_id.into(),
+    }
+  }
+
+  #[test]
+  fn sets_aside_a_concurrent_edit_and_merges_innings() {
+    let mut conn = open_in_memory();
+    let player = roster::create(
+      &conn,
+      NewPlayer {
+        team_id: None,
+        name: "Jake Miller".into(),
+        number: Some(12),
+        primary_position: "SS".into(),
+        secondary_positions: Vec::new(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      },
+    )
+    .unwrap();
+    let mut input = LineupInput {
+      id: None,
+      team_id: None,
+      name: "vs Tigers".into(),
+      game_id: None,
+      use_dh: false,
+      innings: 6,
+      slots: Vec::new(),
+      positions: vec![at(1, "SS", &player.id)],
+    };
+    let synced = lineups::save(&mut conn, input.clone()).unwrap();
+
+    // The assistant fills in the second inning while the coach renames it
+    let mut theirs = synced.clone();
+    theirs.positions.push(at(2, "C", &player.id));
+    theirs.versions.insert("assistant".into(), 1);
+    input.id = Some(synced.id.clone());
+    input.name = "vs Tigers (G1)".into();
+    lineups::save(&mut conn, input).unwrap();
+
+    let change = Change::LineupSaved {
+      lineup: theirs.clone(),
+    };
+    assert_eq!(merge::apply(&mut conn, &change, "iPad").unwrap(), Outcome::Conflict);
+    let waiting = list(&conn).unwrap();
+    assert_eq!((waiting.len(), waiting[0].device.as_str()), (1, "iPad"));
+    assert_eq!(waiting[0].mine.name, "vs Tigers (G1)");
+
+    let resolved = resolve(&mut conn, &synced.id, Strategy::MergeInnings, None).unwrap();
+    assert_eq!(resolved.name, "vs Tigers (G1)");
+    assert_eq!(resolved.positions.len(), 2);
+    assert_eq!(versions::compare(&theirs.versions, &resolved.versions), versions::Order::Before);
+    assert!(list(&conn).unwrap().is_empty());
+    assert_eq!(merge::apply(&mut conn, &change, "iPad").unwrap(), Outcome::Unchanged);
+    assert!(resolve(&mut conn, &synced.id, Strategy::KeepMine, None).is_err());
+  }
+}
@@ -0,0 +1,222 @@
+//! One WebSocket connection to a paired device: the pairing handshake, then
+//! snapshots and live edits in both directions.
+
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::tungstenite::Message as Frame;
+use tokio_tungstenite::WebSocketStream;
+
+use super::merge::{self, Outcome};
+use super::protocol::{Change, Message, PROTOCOL_VERSION};
+use super::{ConnectedPeer, SyncState, SYNC_CONFLICT_EVENT, SYNC_DATA_EVENT};
+use crate::db::Database;
+use crate::error::{Error, Result};
+
+/// How long either side waits for the other's half of the handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+async fn send<S>(ws: &mut WebSocketStream<S>, message: &Message) -> Result<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  ws.send(Frame::text(message.to_text())).await?;
+  Ok(())
+}
+
+/// Waits for the next message, skipping control frames. `None` once the
+/// peer has closed the connection.
+async fn recv<S>(ws: &mut WebSocketStream<S>) -> Result<Option<Message>>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  while let Some(frame) = ws.next().await {
+    match frame? {
+      Frame::Text(text) => return Ok(Some(Message::from_text(&text)?)),
+      Frame::Close(_) => return Ok(None),
+      _ => {}
+    }
+  }
+  Ok(None)
+}
+
+async fn recv_handshake<S>(ws: &mut WebSocketStream<S>) -> Result<Message>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  tokio::time::timeout(HANDSHAKE_TIMEOUT, recv(ws))
+    .await
+    .map_err(|_| Error::Sync("Timed out waiting to pair".into()))??
+    .ok_or_else(|| Error::Sync("Device disconnected while pairing".into()))
+}
+
+/// Serves a device that connected to our sync server.
+pub async fn accept(app: AppHandle, stream: TcpStream) {
+  if let Err(e) = serve(&app, stream).await {
+    log::warn!("Sync connection ended: {}", e);
+  }
+}
+
+async fn serve(app: &AppHandle, stream: TcpStream) -> Result<()> {
+  let addr = stream.peer_addr()?.ip();
+  let mut ws = tokio_tungstenite::accept_async(stream).await?;
+  let Message::Hello {
+    version,
+    device_id,
+    device_name,
+    code,
+  } = recv_handshake(&mut ws).await?
+  else {
+    return Err(Error::Sync("Expected a pairing request".into()));
+  };
+
+  let state = app.state::<SyncState>();
+  let rejection = if version != PROTOCOL_VERSION {
+    Some("The other device is running a different version of Dugout")
+  } else {
+    state.check_code(app, addr, &code).err()
+  };
+  if let Some(reason) = rejection {
+    send(&mut ws, &Message::Rejected { reason: reason.into() }).await?;
+    let _ = ws.close(None).await;
+    return Err(Error::Sync(format!("Refused {device_name}: {reason}")));
+  }
+
+  let (own_id, own_name) = state.identity();
+  send(
+    &mut ws,
+    &Message::Welcome {
+      device_id: own_id,
+      device_name: own_name,
+    },
+  )
+  .await?;
+  log::info!("Paired with {} ({})", device_name, device_id);
+  run(app, ws, ConnectedPeer { device_id, device_name }).await
+}
+
+/// Connects to a device's sync server and pairs with `code`. Returns once
+/// the pairing is accepted; the connection then runs in the background.
+pub async fn connect(app: &AppHandle, address: &str, code: String) -> Result<()> {
+  let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{address}")).await?;
+  let (device_id, device_name) = app.state::<SyncState>().identity();
+  send(
+    &mut ws,
+    &Message::Hello {
+      version: PROTOCOL_VERSION,
+      device_id,
+      device_name,
+      code,
+    },
+  )
+  .await?;
+
+  match recv_handshake(&mut ws).await? {
+    Message::Welcome {
+      device_id,
+      device_name,
+    } => {
+      log::info!("Paired with {} ({})", device_name, device_id);
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move {
+        if let Err(e) = run(&app, ws, ConnectedPeer { device_id, device_name }).await {
+          log::warn!("Sync connection ended: {}", e);
+        }
+      });
+      Ok(())
+    }
+    Message::Rejected { reason } => Err(Error::Validation(reason)),
+    _ => Err(Error::Sync("Unexpected reply to pairing request".into())),
+  }
+}
+
+async fn run<S>(app: &AppHandle, mut ws: WebSocketStream<S>, peer: ConnectedPeer) -> Result<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  let state = app.state::<SyncState>();
+  let connection = state.connected(app, peer);
+  let result = exchange(app, &mut ws, connection).await;
+  state.disconnected(app, connection);
+  result
+}
+
+async fn exchange<S>(app: &AppHandle, ws: &mut WebSocketStream<S>, connection: u64) -> Result<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  let state = app.state::<SyncState>();
+  let mut changes = state.subscribe();
+  let mut shutdown = state.shutdown_signal();
+  let mut resync = state.resync_signal();
+  send_snapshot(app, ws).await?;
+
+  loop {
+    tokio::select! {
+      incoming = recv(ws) => match incoming? {
+        Some(message) => handle(app, connection, message)?,
+        None => return Ok(()),
+      },
+      outgoing = changes.recv() => match outgoing {
+        Ok(outgoing) if outgoing.origin != Some(connection) => {
+          send(ws, &Message::Change { change: outgoing.change }).await?;
+        }
+        Ok(_) => {}
+        // Fell behind and missed edits; the snapshot covers them
+        Err(RecvError::Lagged(_)) => send_snapshot(app, ws).await?,
+        Err(RecvError::Closed) => return Ok(()),
+      },
+      Ok(()) = resync.changed() => send_snapshot(app, ws).await?,
+      _ = shutdown.changed() => {
+        let _ = ws.close(None).await;
+        return Ok(());
+      }
+    }
+  }
+}
+
+async fn send_snapshot<S>(app: &AppHandle, ws: &mut WebSocketStream<S>) -> Result<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  let (players, lineups) = merge::snapshot(&app.state::<Database>().conn())?;
+  send(ws, &Message::Snapshot { players, lineups }).await
+}
+
+fn handle(app: &AppHandle, connection: u64, message: Message) -> Result<()> {
+  let changes = match message {
+    // Players first, so lineups can refer to them
+    Message::Snapshot { players, lineups } => players
+      .into_iter()
+      .map(|player| Change::PlayerSaved { player })
+      .chain(lineups.into_iter().map(|lineup| Change::LineupSaved { lineup }))
+      .collect(),
+    Message::Change { change } => vec![change],
+    _ => return Err(Error::Sync("Unexpected message from paired device".into())),
+  };
+
+  let state = app.state::<SyncState>();
+  let device = state.peer_name(connection);
+  for change in changes {
+    let applied = merge::apply(&mut app.state::<Database>().conn(), &change, &device);
+    match applied {
+      Ok(Outcome::Applied) => {
+        let _ = app.emit(SYNC_DATA_EVENT, &change);
+        state.relay(connection, change);
+      }
+      Ok(Outcome::Conflict) => {
+        if let Change::LineupSaved { lineup } = &change {
+          let _ = app.emit(SYNC_CONFLICT_EVENT, &lineup.id);
+        }
+      }
+      Ok(Outcome::Unchanged) => {}
+      // One bad row shouldn't end the session
+      Err(e) => log::warn!("Skipped a synced change: {}", e),
+    }
+  }
+  Ok(())
+}
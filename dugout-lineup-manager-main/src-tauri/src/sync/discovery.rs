@@ -0,0 +1,82 @@
+//! Finding other Dugout devices on the LAN over mDNS (`_dugout._tcp`).
+
+use std::collections::HashMap;
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use super::SyncState;
+use crate::error::Result;
+
+pub const SERVICE_TYPE: &str = "_dugout._tcp.local.";
+
+/// TXT record keys.
+const DEVICE_ID_KEY: &str = "id";
+const DEVICE_NAME_KEY: &str = "name";
+
+/// Another device advertising sync, as found by browsing.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredPeer {
+  pub device_id: String,
+  pub device_name: String,
+  /// `ip:port` of its sync server.
+  pub address: String,
+  #[serde(skip)]
+  pub fullname: String,
+}
+
+pub fn daemon() -> Result<ServiceDaemon> {
+  Ok(ServiceDaemon::new()?)
+}
+
+/// Advertises this device's sync server on every interface.
+pub fn advertise(daemon: &ServiceDaemon, device_id: &str, device_name: &str, port: u16) -> Result<String> {
+  let properties = HashMap::from([
+    (DEVICE_ID_KEY.to_string(), device_id.to_string()),
+    (DEVICE_NAME_KEY.to_string(), device_name.to_string()),
+  ]);
+  let host_name = format!("dugout-{}.local.", &device_id[..8.min(device_id.len())]);
+  let info = ServiceInfo::new(SERVICE_TYPE, device_id, &host_name, (), port, properties)?
+    .enable_addr_auto();
+  let fullname = info.get_fullname().to_string();
+  daemon.register(info)?;
+  Ok(fullname)
+}
+
+/// Browses for other devices until the daemon shuts down, keeping
+/// `SyncState`'s peer list current.
+pub fn browse(app: AppHandle, daemon: &ServiceDaemon, own_id: String) -> Result<()> {
+  let events = daemon.browse(SERVICE_TYPE)?;
+  std::thread::spawn(move || {
+    while let Ok(event) = events.recv() {
+      let state = app.state::<SyncState>();
+      match event {
+        ServiceEvent::ServiceResolved(service) => {
+          let device_id = service.get_property_val_str(DEVICE_ID_KEY).unwrap_or_default();
+          let Some(ip) = service.get_addresses_v4().into_iter().next() else {
+            continue;
+          };
+          if device_id.is_empty() || device_id == own_id {
+            continue;
+          }
+          let peer = DiscoveredPeer {
+            device_id: device_id.to_string(),
+            device_name: service
+              .get_property_val_str(DEVICE_NAME_KEY)
+              .unwrap_or(device_id)
+              .to_string(),
+            address: format!("{ip}:{}", service.get_port()),
+            fullname: service.get_fullname().to_string(),
+          };
+          log::debug!("Found sync peer {} at {}", peer.device_name, peer.address);
+          state.peer_found(&app, peer);
+        }
+        ServiceEvent::ServiceRemoved(_, fullname) => state.peer_lost(&app, &fullname),
+        _ => {}
+      }
+    }
+  });
+  Ok(())
+}
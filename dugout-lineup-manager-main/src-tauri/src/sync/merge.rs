@@ -0,0 +1,210 @@
+//! Applying a paired device's roster and lineups to the local database.
+//!
+//! Each device has its own default team, so incoming rows are moved onto
+//! the local one. Players are settled by `updated_at`: the most recent
+//! write wins on both sides. Lineups carry version vectors instead, so an
+//! edit made here while the other device made its own isn't overwritten:
+//! it is kept and the other side's set aside as a conflict for the coach
+//! (see `conflicts`). Whatever is applied is audited under the name of the
+//! device it came from.
+
+use rusqlite::Connection;
+
+use super::conflicts;
+use super::protocol::Change;
+use crate::db::audit;
+use crate::db::lineups::{self, Lineup};
+use crate::db::roster::{self, Player};
+use crate::db::versions::{self, Order};
+use crate::error::{Error, Result};
+
+/// The local default team's players and lineups, for a `Snapshot`.
+pub fn snapshot(conn: &Connection) -> Result<(Vec<Player>, Vec<Lineup>)> {
+  let team_id = roster::default_team_id(conn)?;
+  Ok((roster::list(conn, &team_id)?, lineups::list(conn, &team_id)?))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+  /// Something local changed, so the change should be relayed to other
+  /// peers and shown in the UI.
+  Applied,
+  /// Already had it, or something newer.
+  Unchanged,
+  /// A lineup edit clashed with one made here and was set aside.
+  Conflict,
+}
+
+/// Applies one change from `device`.
+pub fn apply(conn: &mut Connection, change: &Change, device: &str) -> Result<Outcome> {
+  let tx = conn.transaction()?;
+  let outcome = match change {
+    Change::PlayerSaved { player } => apply_player(&tx, player.clone(), device)?,
+    Change::PlayerDeleted { id } => match ignore_missing(roster::get(&tx, id))? {
+      Some(local) => {
+        roster::delete_in(&tx, id)?;
+        audit::player_changed(&tx, Some(device), Some(&local), None)?;
+        Outcome::Applied
+      }
+      None => Outcome::Unchanged,
+    },
+    Change::LineupSaved { lineup } => apply_lineup(&tx, lineup.clone(), device)?,
+    Change::LineupDeleted { id } => match ignore_missing(lineups::get(&tx, id))? {
+      Some(local) => {
+        lineups::delete_in(&tx, id)?;
+        audit::lineup_changed(&tx, Some(device), Some(&local), None)?;
+        Outcome::Applied
+      }
+      None => Outcome::Unchanged,
+    },
+  };
+  tx.commit()?;
+  Ok(outcome)
+}
+
+fn apply_player(conn: &Connection, mut player: Player, device: &str) -> Result<Outcome> {
+  roster::validate(&player)?;
+  player.team_id = roster::default_team_id(conn)?;
+  let local = ignore_missing(roster::get(conn, &player.id))?;
+  match &local {
+    Some(local) if local.updated_at >= player.updated_at => return Ok(Outcome::Unchanged),
+    Some(_) => roster::overwrite(conn, &player)?,
+    None => roster::insert(conn, &player)?,
+  }
+  audit::player_changed(conn, Some(device), local.as_ref(), Some(&player))?;
+  Ok(Outcome::Applied)
+}
+
+fn apply_lineup(conn: &Connection, mut lineup: Lineup, device: &str) -> Result<Outcome> {
+  lineup.team_id = roster::default_team_id(conn)?;
+  let local = ignore_missing(lineups::get(conn, &lineup.id))?;
+  if let Some(local) = &local {
+    let unversioned = local.versions.is_empty() && lineup.versions.is_empty();
+    match versions::compare(&local.versions, &lineup.versions) {
+      // Both last saved before lineups were versioned
+      _ if unversioned => {
+        if local.updated_at >= lineup.updated_at {
+          return Ok(Outcome::Unchanged);
+        }
+      }
+      Order::Same | Order::After => return Ok(Outcome::Unchanged),
+      Order::Before => {}
+      Order::Concurrent if conflicts::same_content(local, &lineup) => {
+        // Both made the same edit; only the counts need catching up
+        let caught_up = Lineup {
+          versions: versions::merged(&local.versions, &lineup.versions),
+          ..local.clone()
+        };
+        lineups::write(conn, &caught_up)?;
+        return Ok(Outcome::Unchanged);
+      }
+      Order::Concurrent => {
+        conflicts::record(conn, &lineup, device)?;
+        return Ok(Outcome::Conflict);
+      }
+    }
+  }
+  lineups::write(conn, &lineup)?;
+  // Theirs has seen ours, so any clash it had with an older edit is settled
+  conflicts::clear(conn, &lineup.id)?;
+  let applied = lineups::get(conn, &lineup.id)?;
+  audit::lineup_changed(conn, Some(device), local.as_ref(), Some(&applied))?;
+  Ok(Outcome::Applied)
+}
+
+/// Something this device never had (or already removed) is `None` rather
+/// than an error, so deleting it is a no-op.
+fn ignore_missing<T>(result: Result<T>) -> Result<Option<T>> {
+  match result {
+    Ok(found) => Ok(Some(found)),
+    Err(Error::NotFound(_)) => Ok(None),
+    Err(e) => Err(e),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::lineups::{InningPosition, LineupSlot};
+  use crate::db::open_in_memory;
+
+  const PEER: &str = "Assistant's iPad";
+
+  fn remote_player(id: &str, name: &str, updated_at: &str) -> Player {
+    Player {
+      id: id.into(),
+      team_id: "remote-team".into(),
+      name: name.into(),
+      number: Some(7),
+      primary_position: "SS".into(),
+      secondary_positions: vec![],
+      bats: "R".into(),
+      throws: "R".into(),
+      status: "active".into(),
+      notes: String::new(),
+      created_at: "2026-03-01T00:00:00.000Z".into(),
+      updated_at: updated_at.into(),
+    }
+  }
+
+  #[test]
+  fn keeps_the_most_recent_edit_of_a_player() {
+    let mut conn = open_in_memory();
+    let player = remote_player("p1", "Ava Chen", "2026-03-02T00:00:00.000Z");
+    assert_eq!(apply(&mut conn, &Change::PlayerSaved { player }, PEER).unwrap(), Outcome::Applied);
+
+    let local = roster::get(&conn, "p1").unwrap();
+    assert_eq!(local.team_id, roster::default_team_id(&conn).unwrap());
+
+    let stale = remote_player("p1", "Ava Old", "2026-03-01T12:00:00.000Z");
+    let stale = Change::PlayerSaved { player: stale };
+    assert_eq!(apply(&mut conn, &stale, PEER).unwrap(), Outcome::Unchanged);
+    let newer = remote_player("p1", "Ava Chen-Lee", "2026-03-03T00:00:00.000Z");
+    let newer = Change::PlayerSaved { player: newer };
+    assert_eq!(apply(&mut conn, &newer, PEER).unwrap(), Outcome::Applied);
+    assert_eq!(roster::get(&conn, "p1").unwrap().name, "Ava Chen-Lee");
+
+    let delete = Change::PlayerDeleted { id: "p1".into() };
+    assert_eq!(apply(&mut conn, &delete, PEER).unwrap(), Outcome::Applied);
+    assert_eq!(apply(&mut conn, &delete, PEER).unwrap(), Outcome::Unchanged);
+  }
+
+  #[test]
+  fn drops_lineup_spots_for_unknown_players() {
+    let mut conn = open_in_memory();
+    let player = remote_player("p1", "Ava Chen", "2026-03-02T00:00:00.000Z");
+    apply(&mut conn, &Change::PlayerSaved { player }, PEER).unwrap();
+
+    let slot = |n, player_id: &str| LineupSlot {
+      slot_number: n,
+      player_id: Some(player_id.into()),
+      player_name: None,
+      number: None,
+      position: None,
+    };
+    let lineup = Lineup {
+      id: "l1".into(),
+      team_id: "remote-team".into(),
+      name: "Opening Day".into(),
+      game_id: Some("remote-game".into()),
+      use_dh: true,
+      innings: 6,
+      slots: vec![slot(1, "p1"), slot(2, "missing")],
+      positions: vec![
+        InningPosition { inning: 1, position: "SS".into(), player_id: "p1".into() },
+        InningPosition { inning: 1, position: "C".into(), player_id: "missing".into() },
+      ],
+      updated_at: "2026-03-02T00:00:00.000Z".into(),
+      versions: [("remote".to_string(), 1)].into(),
+    };
+    let saved = Change::LineupSaved { lineup };
+    assert_eq!(apply(&mut conn, &saved, PEER).unwrap(), Outcome::Applied);
+
+    let local = lineups::get(&conn, "l1").unwrap();
+    assert_eq!(local.game_id, None);
+    assert_eq!(local.slots[0].player_name.as_deref(), Some("Ava Chen"));
+    assert_eq!(local.slots[1].player_id, None);
+    assert_eq!(local.positions.len(), 1);
+    assert_eq!(local.updated_at, "2026-03-02T00:00:00.000Z");
+  }
+}
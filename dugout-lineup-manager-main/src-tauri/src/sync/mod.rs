@@ -0,0 +1,515 @@
+//! LAN sync of the roster and lineups between two coaches' devices.
+//!
+//! One device hosts: it listens for WebSocket connections, advertises itself
+//! over mDNS and shows a short pairing code. The other finds it by browsing
+//! and connects with that code. Once paired, each side sends a snapshot of
+//! its roster and lineups, then every edit as it is made, so a head coach
+//! and an assistant can share changes at the field without internet.
+//! Pairing lasts until either device stops syncing or quits.
+//!
+//! Changes too big to send one by one, such as imports, restores and the
+//! demo team, have every connection send a fresh snapshot instead.
+//!
+//! Deletes made while the devices aren't connected are not carried by the
+//! snapshot, so the other device brings those rows back. The same goes for
+//! rows a restore or reset removes.
+
+pub mod conflicts;
+mod connection;
+mod discovery;
+mod merge;
+mod protocol;
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use mdns_sd::ServiceDaemon;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::{broadcast, watch};
+
+pub use discovery::DiscoveredPeer;
+pub use protocol::Change;
+
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+use crate::settings::{self, AppSettings};
+use crate::telemetry::{Feature, Telemetry};
+
+/// Emitted with a `SyncStatus` whenever hosting, discovery or the set of
+/// connected devices changes.
+pub const SYNC_STATUS_EVENT: &str = "sync-status";
+
+/// Emitted with the `Change` whenever an edit from a paired device is
+/// applied locally.
+pub const SYNC_DATA_EVENT: &str = "sync-data-changed";
+
+/// Emitted with a lineup's id when an edit to it from a paired device
+/// conflicts with one made here; see `conflicts`.
+pub const SYNC_CONFLICT_EVENT: &str = "sync-conflict";
+
+/// Digits in a pairing code.
+const CODE_LENGTH: u32 = 6;
+
+/// Wrong codes tolerated before the code is replaced, so it can't be
+/// guessed by trying them all.
+const MAX_CODE_ATTEMPTS: u32 = 5;
+
+/// How long an address waits after a wrong pairing code before its next
+/// one is checked, doubling with each wrong code up to
+/// [`MAX_PAIRING_BACKOFF`].
+const PAIRING_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_PAIRING_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Edits buffered per connection before a slow one falls back to a fresh
+/// snapshot.
+const CHANGE_BUFFER: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+  pub device_name: String,
+  /// Set while hosting: the code the other device has to enter.
+  pub pairing_code: Option<String>,
+  pub discovering: bool,
+  pub discovered: Vec<DiscoveredPeer>,
+  pub connected: Vec<ConnectedPeer>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectedPeer {
+  pub device_id: String,
+  pub device_name: String,
+}
+
+/// An edit on its way to paired devices, tagged with the connection it
+/// arrived on (if any) so it isn't echoed back.
+#[derive(Debug, Clone)]
+struct Outgoing {
+  origin: Option<u64>,
+  change: Change,
+}
+
+pub struct SyncState {
+  changes: broadcast::Sender<Outgoing>,
+  /// Bumped to close the server and every connection.
+  shutdown: watch::Sender<u64>,
+  /// Bumped to have every connection send a fresh snapshot.
+  resync: watch::Sender<u64>,
+  inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+  device_id: String,
+  device_name: String,
+  daemon: Option<ServiceDaemon>,
+  hosting: Option<Hosting>,
+  discovering: bool,
+  discovered: Vec<DiscoveredPeer>,
+  connected: BTreeMap<u64, ConnectedPeer>,
+  next_connection: u64,
+}
+
+struct Hosting {
+  code: String,
+  failed_attempts: u32,
+  failures: PairingFailures,
+  /// mDNS name of the advertised service, for unregistering it.
+  fullname: String,
+}
+
+/// Wrong pairing codes from each address: how many in a row, and when the
+/// last one came.
+#[derive(Default)]
+struct PairingFailures(HashMap<IpAddr, (u32, Instant)>);
+
+impl PairingFailures {
+  /// How much longer `addr` has to wait before its next code is checked.
+  fn remaining(&self, addr: IpAddr, now: Instant) -> Option<Duration> {
+    let &(count, last) = self.0.get(&addr)?;
+    let backoff = PAIRING_BACKOFF
+      .saturating_mul(1 << (count - 1).min(16))
+      .min(MAX_PAIRING_BACKOFF);
+    backoff
+      .checked_sub(now.saturating_duration_since(last))
+      .filter(|remaining| !remaining.is_zero())
+  }
+
+  fn record(&mut self, addr: IpAddr, now: Instant) {
+    let (count, last) = self.0.entry(addr).or_insert((0, now));
+    *count += 1;
+    *last = now;
+  }
+
+  fn clear(&mut self, addr: IpAddr) {
+    self.0.remove(&addr);
+  }
+}
+
+impl Default for SyncState {
+  fn default() -> Self {
+    Self {
+      changes: broadcast::channel(CHANGE_BUFFER).0,
+      shutdown: watch::channel(0).0,
+      resync: watch::channel(0).0,
+      inner: Mutex::default(),
+    }
+  }
+}
+
+impl SyncState {
+  /// Sends a local edit to every paired device. A no-op when none are
+  /// connected.
+  pub fn broadcast(&self, change: Change) {
+    let _ = self.changes.send(Outgoing { origin: None, change });
+  }
+
+  /// Has every paired device sent a fresh snapshot, after a change to many
+  /// rows at once. A no-op when none are connected.
+  pub fn resend_snapshot(&self) {
+    self.resync.send_modify(|generation| *generation += 1);
+  }
+
+  /// Passes an edit from one device on to the others.
+  fn relay(&self, origin: u64, change: Change) {
+    let _ = self.changes.send(Outgoing {
+      origin: Some(origin),
+      change,
+    });
+  }
+
+  fn subscribe(&self) -> broadcast::Receiver<Outgoing> {
+    self.changes.subscribe()
+  }
+
+  fn shutdown_signal(&self) -> watch::Receiver<u64> {
+    self.shutdown.subscribe()
+  }
+
+  fn resync_signal(&self) -> watch::Receiver<u64> {
+    self.resync.subscribe()
+  }
+
+  /// Name of the device on `connection`, which its edits are audited
+  /// under.
+  fn peer_name(&self, connection: u64) -> String {
+    let inner = self.inner.lock().unwrap();
+    inner
+      .connected
+      .get(&connection)
+      .map_or_else(|| "Paired device".into(), |peer| peer.device_name.clone())
+  }
+
+  fn identity(&self) -> (String, String) {
+    let inner = self.inner.lock().unwrap();
+    (inner.device_id.clone(), inner.device_name.clone())
+  }
+
+  fn status(&self) -> SyncStatus {
+    let inner = self.inner.lock().unwrap();
+    SyncStatus {
+      device_name: inner.device_name.clone(),
+      pairing_code: inner.hosting.as_ref().map(|h| h.code.clone()),
+      discovering: inner.discovering,
+      discovered: inner.discovered.clone(),
+      connected: inner.connected.values().cloned().collect(),
+    }
+  }
+
+  fn emit_status(&self, app: &AppHandle) {
+    let _ = app.emit(SYNC_STATUS_EVENT, self.status());
+  }
+
+  /// Checks a pairing code from `addr`, replacing it after too many wrong
+  /// guesses. Each wrong code makes `addr` wait longer before its next one
+  /// is checked. Returns why it was refused, if it was.
+  fn check_code(
+    &self,
+    app: &AppHandle,
+    addr: IpAddr,
+    code: &str,
+  ) -> std::result::Result<(), &'static str> {
+    const WRONG_CODE: &str = "Wrong pairing code";
+    let now = Instant::now();
+    let rotated = {
+      let mut inner = self.inner.lock().unwrap();
+      let Some(hosting) = inner.hosting.as_mut() else {
+        return Err(WRONG_CODE);
+      };
+      if hosting.failures.remaining(addr, now).is_some() {
+        return Err("Too many wrong pairing codes; try again shortly");
+      }
+      if hosting.code == code.trim() {
+        hosting.failures.clear(addr);
+        return Ok(());
+      }
+      hosting.failures.record(addr, now);
+      hosting.failed_attempts += 1;
+      let rotate = hosting.failed_attempts >= MAX_CODE_ATTEMPTS;
+      if rotate {
+        hosting.code = pairing_code();
+        hosting.failed_attempts = 0;
+      }
+      rotate
+    };
+    if rotated {
+      log::warn!("Too many wrong pairing codes; issued a new one");
+      self.emit_status(app);
+    }
+    Err(WRONG_CODE)
+  }
+
+  fn connected(&self, app: &AppHandle, peer: ConnectedPeer) -> u64 {
+    let id = {
+      let mut inner = self.inner.lock().unwrap();
+      inner.next_connection += 1;
+      let id = inner.next_connection;
+      inner.connected.insert(id, peer);
+      id
+    };
+    self.emit_status(app);
+    id
+  }
+
+  fn disconnected(&self, app: &AppHandle, connection: u64) {
+    if let Some(peer) = self.inner.lock().unwrap().connected.remove(&connection) {
+      log::info!("Sync with {} ended", peer.device_name);
+    }
+    self.emit_status(app);
+  }
+
+  fn peer_found(&self, app: &AppHandle, peer: DiscoveredPeer) {
+    {
+      let mut inner = self.inner.lock().unwrap();
+      inner.discovered.retain(|p| p.device_id != peer.device_id);
+      inner.discovered.push(peer);
+    }
+    self.emit_status(app);
+  }
+
+  fn peer_lost(&self, app: &AppHandle, fullname: &str) {
+    self
+      .inner
+      .lock()
+      .unwrap()
+      .discovered
+      .retain(|p| p.fullname != fullname);
+    self.emit_status(app);
+  }
+
+  /// Loads this install's identity, creating its device id the first time
+  /// sync is used, and starts the mDNS daemon if it isn't running.
+  fn prepare(&self, settings: &RwLock<AppSettings>, paths: &AppPaths) -> Result<ServiceDaemon> {
+    let (device_id, device_name) = {
+      let mut settings = settings.write().unwrap();
+      if settings.sync.device_id.is_empty() {
+        settings.sync.device_id = crate::db::new_id();
+        settings::save(&paths.settings, &settings)?;
+      }
+      let name = settings.sync.device_name.clone().unwrap_or_else(default_device_name);
+      (settings.sync.device_id.clone(), name)
+    };
+
+    let mut inner = self.inner.lock().unwrap();
+    inner.device_id = device_id;
+    inner.device_name = device_name;
+    if inner.daemon.is_none() {
+      inner.daemon = Some(discovery::daemon()?);
+    }
+    Ok(inner.daemon.clone().unwrap())
+  }
+}
+
+/// A random code of `CODE_LENGTH` digits.
+fn pairing_code() -> String {
+  let bytes = uuid::Uuid::new_v4().into_bytes();
+  let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) % 10u32.pow(CODE_LENGTH);
+  format!("{value:0width$}", width = CODE_LENGTH as usize)
+}
+
+/// The computer's name, for showing to the other coach.
+fn default_device_name() -> String {
+  std::env::var("COMPUTERNAME")
+    .or_else(|_| std::env::var("HOSTNAME"))
+    .ok()
+    .or_else(hostname)
+    .map(|name| name.trim_end_matches(".local").to_string())
+    .filter(|name| !name.is_empty())
+    .unwrap_or_else(|| "Dugout".into())
+}
+
+#[cfg(unix)]
+fn hostname() -> Option<String> {
+  let mut buf = [0u8; 256];
+  // SAFETY: the buffer outlives the call and its length is passed with it.
+  if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+    return None;
+  }
+  let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+  Some(String::from_utf8_lossy(&buf[..len]).into_owned())
+}
+
+#[cfg(not(unix))]
+fn hostname() -> Option<String> {
+  None
+}
+
+async fn accept_loop(app: AppHandle, listener: tokio::net::TcpListener, mut shutdown: watch::Receiver<u64>) {
+  loop {
+    tokio::select! {
+      accepted = listener.accept() => match accepted {
+        Ok((stream, addr)) => {
+          log::info!("Sync connection from {}", addr);
+          tauri::async_runtime::spawn(connection::accept(app.clone(), stream));
+        }
+        Err(e) => log::warn!("Failed to accept sync connection: {}", e),
+      },
+      _ = shutdown.changed() => return,
+    }
+  }
+}
+
+#[tauri::command]
+pub fn get_sync_status(state: State<'_, SyncState>) -> SyncStatus {
+  state.status()
+}
+
+/// Starts accepting pairings: opens the sync server, advertises it on the
+/// LAN and returns the pairing code to show.
+#[tauri::command]
+pub async fn start_sync_hosting(
+  app: AppHandle,
+  state: State<'_, SyncState>,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+) -> Result<SyncStatus> {
+  let daemon = state.prepare(&settings, &paths)?;
+  if state.inner.lock().unwrap().hosting.is_some() {
+    return Ok(state.status());
+  }
+
+  let listener = tokio::net::TcpListener::bind(("0.0.0.0", 0)).await?;
+  let port = listener.local_addr()?.port();
+  let (device_id, device_name) = state.identity();
+  let fullname = discovery::advertise(&daemon, &device_id, &device_name, port)?;
+  log::info!("Hosting sync as {} on port {}", device_name, port);
+
+  state.inner.lock().unwrap().hosting = Some(Hosting {
+    code: pairing_code(),
+    failed_attempts: 0,
+    failures: PairingFailures::default(),
+    fullname,
+  });
+  tauri::async_runtime::spawn(accept_loop(app.clone(), listener, state.shutdown_signal()));
+  state.emit_status(&app);
+  Ok(state.status())
+}
+
+/// Starts looking for hosting devices; results arrive as `sync-status`
+/// events.
+#[tauri::command]
+pub fn discover_sync_peers(
+  app: AppHandle,
+  state: State<'_, SyncState>,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+) -> Result<SyncStatus> {
+  let daemon = state.prepare(&settings, &paths)?;
+  if !state.inner.lock().unwrap().discovering {
+    let (device_id, _) = state.identity();
+    discovery::browse(app.clone(), &daemon, device_id)?;
+    state.inner.lock().unwrap().discovering = true;
+    state.emit_status(&app);
+  }
+  Ok(state.status())
+}
+
+/// Pairs with a discovered device using the code shown on its screen.
+#[tauri::command]
+pub async fn pair_with_peer(
+  app: AppHandle,
+  state: State<'_, SyncState>,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+  device_id: String,
+  code: String,
+) -> Result<SyncStatus> {
+  state.prepare(&settings, &paths)?;
+  let address = state
+    .inner
+    .lock()
+    .unwrap()
+    .discovered
+    .iter()
+    .find(|peer| peer.device_id == device_id)
+    .map(|peer| peer.address.clone())
+    .ok_or_else(|| Error::NotFound(format!("Device {device_id}")))?;
+  connection::connect(&app, &address, code).await?;
+  app.state::<Telemetry>().record(Feature::SyncPaired);
+  Ok(state.status())
+}
+
+/// Stops hosting and discovery and disconnects every paired device.
+#[tauri::command]
+pub fn stop_sync(app: AppHandle, state: State<'_, SyncState>) -> Result<SyncStatus> {
+  state.shutdown.send_modify(|generation| *generation += 1);
+  let (daemon, advertised) = {
+    let mut inner = state.inner.lock().unwrap();
+    inner.discovering = false;
+    inner.discovered.clear();
+    (inner.daemon.take(), inner.hosting.take().map(|hosting| hosting.fullname))
+  };
+  if let Some(daemon) = daemon {
+    if let Some(fullname) = advertised {
+      let _ = daemon.unregister(&fullname);
+    }
+    if let Err(e) = daemon.shutdown() {
+      log::warn!("Failed to stop mDNS: {}", e);
+    }
+  }
+  state.emit_status(&app);
+  Ok(state.status())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn pairing_codes_are_fixed_length_digits() {
+    for _ in 0..100 {
+      let code = pairing_code();
+      assert_eq!(code.len(), CODE_LENGTH as usize);
+      assert!(code.chars().all(|c| c.is_ascii_digit()));
+    }
+  }
+
+  #[test]
+  fn backs_off_each_address_after_wrong_codes() {
+    let mut failures = PairingFailures::default();
+    let (guesser, other) = (IpAddr::from([192, 168, 1, 20]), IpAddr::from([192, 168, 1, 21]));
+    let start = Instant::now();
+    assert_eq!(failures.remaining(guesser, start), None);
+
+    failures.record(guesser, start);
+    assert_eq!(failures.remaining(guesser, start), Some(PAIRING_BACKOFF));
+    assert_eq!(failures.remaining(guesser, start + PAIRING_BACKOFF), None);
+    assert_eq!(failures.remaining(other, start), None);
+
+    let later = start + PAIRING_BACKOFF;
+    failures.record(guesser, later);
+    assert_eq!(failures.remaining(guesser, later), Some(PAIRING_BACKOFF * 2));
+
+    for _ in 0..40 {
+      failures.record(guesser, later);
+    }
+    assert_eq!(failures.remaining(guesser, later), Some(MAX_PAIRING_BACKOFF));
+
+    failures.clear(guesser);
+    assert_eq!(failures.remaining(guesser, later), None);
+  }
+}
@@ -0,0 +1,53 @@
+//! Messages exchanged between paired devices, sent as JSON text frames.
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::lineups::Lineup;
+use crate::db::roster::Player;
+
+/// Bumped whenever a message changes shape; devices on different versions
+/// refuse to pair.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Message {
+  /// First message from the connecting device.
+  Hello {
+    version: u32,
+    device_id: String,
+    device_name: String,
+    code: String,
+  },
+  /// The host accepted the pairing code.
+  Welcome { device_id: String, device_name: String },
+  /// The host refused the pairing and is closing the connection.
+  Rejected { reason: String },
+  /// Everything the sender has, sent by both sides once paired.
+  Snapshot {
+    players: Vec<Player>,
+    lineups: Vec<Lineup>,
+  },
+  /// A single edit made after the snapshot.
+  Change { change: Change },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Change {
+  PlayerSaved { player: Player },
+  PlayerDeleted { id: String },
+  LineupSaved { lineup: Lineup },
+  LineupDeleted { id: String },
+}
+
+impl Message {
+  pub fn to_text(&self) -> String {
+    // Only plain data in here, so serializing can't fail
+    serde_json::to_string(self).expect("sync message serializes")
+  }
+
+  pub fn from_text(text: &str) -> serde_json::Result<Self> {
+    serde_json::from_str(text)
+  }
+}
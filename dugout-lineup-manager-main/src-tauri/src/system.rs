@@ -0,0 +1,193 @@
+//! What the machine can handle: enough to suggest a local AI model size and
+//! to warn before a backup fills the data disk.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sysinfo::{CpuRefreshKind, Disks, MemoryRefreshKind, System};
+use tauri::State;
+
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+
+const GIB: u64 = 1 << 30;
+
+/// Below this much free space the data disk counts as nearly full...
+const LOW_DISK_BYTES: u64 = 2 * GIB;
+/// ...or below this share of its size, whichever is larger.
+const LOW_DISK_FRACTION: f64 = 0.05;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemCapabilities {
+  pub os: String,
+  pub arch: String,
+  pub cpu_brand: Option<String>,
+  pub logical_cores: usize,
+  pub physical_cores: Option<usize>,
+  /// Bytes of RAM.
+  pub total_memory: u64,
+  pub available_memory: u64,
+  pub gpu: GpuSupport,
+  /// The disk holding the data directory, if it could be found.
+  pub data_disk: Option<DiskSpace>,
+  pub recommended_model: ModelSize,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuSupport {
+  pub metal: bool,
+  /// Apple Silicon shares its RAM with the GPU, so all of it is usable for
+  /// a model.
+  pub unified_memory: bool,
+  /// An NVIDIA driver with CUDA is installed.
+  pub cuda: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskSpace {
+  pub mount_point: PathBuf,
+  pub total: u64,
+  pub available: u64,
+  pub nearly_full: bool,
+}
+
+/// Rough local model classes by parameter count, for 4-bit quantized
+/// weights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelSize {
+  /// ~1-3B parameters; runs anywhere, slowly on old machines.
+  Small,
+  /// ~7-8B parameters.
+  Medium,
+  /// ~13B+ parameters; wants a GPU and plenty of memory.
+  Large,
+}
+
+fn cuda_available() -> bool {
+  #[cfg(target_os = "linux")]
+  {
+    Path::new("/proc/driver/nvidia/version").exists()
+  }
+  #[cfg(windows)]
+  {
+    std::env::var_os("SystemRoot")
+      .map(|root| Path::new(&root).join("System32").join("nvcuda.dll").exists())
+      .unwrap_or(false)
+  }
+  #[cfg(not(any(target_os = "linux", windows)))]
+  {
+    false
+  }
+}
+
+fn gpu_support() -> GpuSupport {
+  GpuSupport {
+    metal: cfg!(target_os = "macos"),
+    unified_memory: cfg!(all(target_os = "macos", target_arch = "aarch64")),
+    cuda: cuda_available(),
+  }
+}
+
+pub fn recommend(total_memory: u64, gpu: GpuSupport) -> ModelSize {
+  let accelerated = gpu.cuda || gpu.unified_memory;
+  if total_memory >= 16 * GIB && accelerated {
+    ModelSize::Large
+  } else if total_memory >= 8 * GIB {
+    ModelSize::Medium
+  } else {
+    ModelSize::Small
+  }
+}
+
+fn nearly_full(total: u64, available: u64) -> bool {
+  let threshold = LOW_DISK_BYTES.max((total as f64 * LOW_DISK_FRACTION) as u64);
+  available < threshold
+}
+
+/// The disk whose mount point is the longest prefix of `path`.
+fn disk_for<'a>(path: &Path, mounts: impl IntoIterator<Item = &'a Path>) -> Option<&'a Path> {
+  mounts
+    .into_iter()
+    .filter(|mount| path.starts_with(mount))
+    .max_by_key(|mount| mount.components().count())
+}
+
+fn data_disk(data_dir: &Path) -> Option<DiskSpace> {
+  // The directory may not exist yet (e.g. a new custom data dir)
+  let path = data_dir
+    .ancestors()
+    .find_map(|dir| dir.canonicalize().ok())?;
+  let disks = Disks::new_with_refreshed_list();
+  let mount = disk_for(&path, disks.list().iter().map(|disk| disk.mount_point()))?;
+  let disk = disks.list().iter().find(|disk| disk.mount_point() == mount)?;
+  Some(DiskSpace {
+    mount_point: mount.to_path_buf(),
+    total: disk.total_space(),
+    available: disk.available_space(),
+    nearly_full: nearly_full(disk.total_space(), disk.available_space()),
+  })
+}
+
+fn detect(data_dir: &Path) -> SystemCapabilities {
+  let mut system = System::new();
+  system.refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram());
+  system.refresh_cpu_list(CpuRefreshKind::nothing());
+  let gpu = gpu_support();
+  SystemCapabilities {
+    os: std::env::consts::OS.to_string(),
+    arch: std::env::consts::ARCH.to_string(),
+    cpu_brand: system
+      .cpus()
+      .first()
+      .map(|cpu| cpu.brand().trim().to_string())
+      .filter(|brand| !brand.is_empty()),
+    logical_cores: std::thread::available_parallelism().map_or(1, |n| n.get()),
+    physical_cores: System::physical_core_count(),
+    total_memory: system.total_memory(),
+    available_memory: system.available_memory(),
+    gpu,
+    data_disk: data_disk(data_dir),
+    recommended_model: recommend(system.total_memory(), gpu),
+  }
+}
+
+#[tauri::command]
+pub async fn get_system_capabilities(paths: State<'_, AppPaths>) -> Result<SystemCapabilities> {
+  let data_dir = paths.data();
+  tauri::async_runtime::spawn_blocking(move || detect(&data_dir))
+    .await
+    .map_err(|e| Error::Validation(format!("Hardware detection failed: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn recommends_larger_models_with_memory_and_a_gpu() {
+    let cpu_only = GpuSupport::default();
+    let cuda = GpuSupport {
+      cuda: true,
+      ..Default::default()
+    };
+    assert_eq!(recommend(4 * GIB, cuda), ModelSize::Small);
+    assert_eq!(recommend(8 * GIB, cpu_only), ModelSize::Medium);
+    assert_eq!(recommend(32 * GIB, cpu_only), ModelSize::Medium);
+    assert_eq!(recommend(32 * GIB, cuda), ModelSize::Large);
+  }
+
+  #[test]
+  fn finds_the_disk_holding_the_data_dir() {
+    let mounts = [Path::new("/"), Path::new("/home"), Path::new("/home/coach/ext")];
+    let found = disk_for(Path::new("/home/coach/.local/share/dugout"), mounts);
+    assert_eq!(found, Some(Path::new("/home")));
+
+    assert!(nearly_full(100 * GIB, GIB));
+    assert!(nearly_full(1000 * GIB, 40 * GIB));
+    assert!(!nearly_full(100 * GIB, 20 * GIB));
+  }
+}
@@ -0,0 +1,220 @@
+//! Reading TeamSnap's API responses.
+//!
+//! The API answers in Collection+JSON: each item's fields are a list of
+//! `{ "name", "value" }` pairs, flattened here into a map before being read
+//! into players and games.
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::db::games::ScheduledGame;
+use crate::db::roster::{NewPlayer, POSITIONS};
+use crate::error::{Error, Result};
+
+pub type Item = Map<String, Value>;
+
+/// The items of a collection response, or the error it carries.
+pub fn items(body: &Value) -> Result<Vec<Item>> {
+  let collection = &body["collection"];
+  if let Some(message) = collection["error"]["message"].as_str() {
+    return Err(Error::TeamSnap(message.to_string()));
+  }
+  let Some(items) = collection["items"].as_array() else {
+    return Err(Error::TeamSnap("Unexpected response from TeamSnap".into()));
+  };
+  Ok(
+    items
+      .iter()
+      .map(|item| {
+        item["data"]
+          .as_array()
+          .into_iter()
+          .flatten()
+          .filter_map(|field| Some((field["name"].as_str()?.to_string(), field["value"].clone())))
+          .collect()
+      })
+      .collect(),
+  )
+}
+
+pub fn text(item: &Item, name: &str) -> Option<String> {
+  match item.get(name)? {
+    Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+    Value::Number(n) => Some(n.to_string()),
+    _ => None,
+  }
+}
+
+fn number(item: &Item, name: &str) -> Option<u32> {
+  match item.get(name)? {
+    Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+    Value::String(s) => s.trim().trim_start_matches('#').parse().ok(),
+    _ => None,
+  }
+}
+
+fn flag(item: &Item, name: &str) -> bool {
+  item.get(name).and_then(Value::as_bool).unwrap_or(false)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Team {
+  pub id: String,
+  pub name: String,
+  pub season: Option<String>,
+}
+
+pub fn team(item: &Item) -> Option<Team> {
+  Some(Team {
+    id: text(item, "id")?,
+    name: text(item, "name").unwrap_or_default(),
+    season: text(item, "season_name"),
+  })
+}
+
+/// The position code for TeamSnap's free-text position, which may be a
+/// code ("SS") or spelled out ("Shortstop").
+fn position_code(raw: &str) -> Option<&'static str> {
+  let raw = raw.trim().to_lowercase();
+  let code = match raw.as_str() {
+    "pitcher" => "P",
+    "catcher" => "C",
+    "first base" | "first" => "1B",
+    "second base" | "second" => "2B",
+    "third base" | "third" => "3B",
+    "shortstop" | "short stop" => "SS",
+    "left field" | "left" => "LF",
+    "center field" | "centre field" | "center" => "CF",
+    "right field" | "right" => "RF",
+    "designated hitter" => "DH",
+    _ => return POSITIONS.iter().copied().find(|p| p.eq_ignore_ascii_case(&raw)),
+  };
+  Some(code)
+}
+
+/// Players from a team's members, leaving out coaches and other
+/// non-players. Members without a usable position are put at DH until the
+/// coach sets one.
+pub fn players(members: &[Item], team_id: &str) -> Vec<NewPlayer> {
+  members
+    .iter()
+    .filter(|member| !flag(member, "is_non_player"))
+    .filter_map(|member| {
+      let name = format!(
+        "{} {}",
+        text(member, "first_name").unwrap_or_default(),
+        text(member, "last_name").unwrap_or_default()
+      )
+      .trim()
+      .to_string();
+      if name.is_empty() {
+        return None;
+      }
+      let mut positions: Vec<&str> = text(member, "position")
+        .unwrap_or_default()
+        .split(['/', ',', ';'])
+        .filter_map(position_code)
+        .collect();
+      positions.dedup();
+      let primary = if positions.is_empty() { "DH" } else { positions.remove(0) };
+      Some(NewPlayer {
+        team_id: Some(team_id.to_string()),
+        name,
+        number: number(member, "jersey_number").filter(|n| (1..=99).contains(n)),
+        primary_position: primary.to_string(),
+        secondary_positions: positions.into_iter().map(String::from).collect(),
+        bats: "R".into(),
+        throws: "R".into(),
+        status: None,
+        notes: None,
+      })
+    })
+    .collect()
+}
+
+/// Games from a team's events. Practices and cancelled games are left out.
+pub fn games(events: &[Item]) -> Vec<ScheduledGame> {
+  events
+    .iter()
+    .filter(|event| flag(event, "is_game") && !flag(event, "is_canceled"))
+    .filter_map(|event| {
+      let start = DateTime::parse_from_rfc3339(&text(event, "start_date")?).ok()?;
+      let start = start.with_timezone(&Local);
+      let home_away = match text(event, "game_type").map(|t| t.to_lowercase()).as_deref() {
+        Some("away") => "away",
+        _ => "home",
+      };
+      Some(ScheduledGame {
+        // Keyed by TeamSnap's id, so importing again updates the game
+        id: format!("teamsnap-{}", text(event, "id")?),
+        date: start.format("%Y-%m-%d").to_string(),
+        // TeamSnap gives games without a set time a placeholder one
+        start_time: (!flag(event, "is_tbd")).then(|| start.format("%H:%M").to_string()),
+        opponent: text(event, "opponent_name").unwrap_or_else(|| "TBD".into()),
+        home_away: home_away.to_string(),
+        score_us: number(event, "points_for_team"),
+        score_them: number(event, "points_for_opponent"),
+        notes: text(event, "notes").unwrap_or_default(),
+        location: text(event, "location_name").unwrap_or_default(),
+      })
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn collection(items: &[Value]) -> Value {
+    let items: Vec<Value> = items
+      .iter()
+      .map(|fields| {
+        let data: Vec<Value> = fields
+          .as_object()
+          .unwrap()
+          .iter()
+          .map(|(name, value)| json!({ "name": name, "value": value }))
+          .collect();
+        json!({ "data": data })
+      })
+      .collect();
+    json!({ "collection": { "version": "3.866.0", "items": items } })
+  }
+
+  #[test]
+  fn reads_players_and_games_from_collections() {
+    let members = items(&collection(&[
+      json!({ "first_name": "Jake", "last_name": "Miller", "jersey_number": "12",
+        "position": "Shortstop/P", "is_non_player": false }),
+      json!({ "first_name": "Ava", "last_name": "Chen", "jersey_number": null,
+        "position": "", "is_non_player": false }),
+      json!({ "first_name": "Coach", "last_name": "Pat", "is_non_player": true }),
+    ]))
+    .unwrap();
+    let players = players(&members, "t1");
+    assert_eq!(players.len(), 2);
+    assert_eq!((players[0].number, players[0].primary_position.as_str()), (Some(12), "SS"));
+    assert_eq!(players[0].secondary_positions, ["P"]);
+    assert_eq!((players[1].number, players[1].primary_position.as_str()), (None, "DH"));
+
+    let events = items(&collection(&[
+      json!({ "id": 901, "is_game": true, "start_date": "2026-04-11T16:00:00Z",
+        "opponent_name": "Tigers", "game_type": "Away", "location_name": "Field 3",
+        "points_for_team": 6, "points_for_opponent": 4 }),
+      json!({ "id": 902, "is_game": false, "start_date": "2026-04-13T16:00:00Z" }),
+      json!({ "id": 903, "is_game": true, "is_canceled": true,
+        "start_date": "2026-04-18T16:00:00Z", "opponent_name": "Cubs" }),
+    ]))
+    .unwrap();
+    let games = games(&events);
+    assert_eq!(games.len(), 1);
+    assert_eq!((games[0].id.as_str(), games[0].home_away.as_str()), ("teamsnap-901", "away"));
+    assert_eq!((games[0].score_us, games[0].location.as_str()), (Some(6), "Field 3"));
+
+    let error = json!({ "collection": { "error": { "message": "Not authorized" } } });
+    assert!(items(&error).is_err());
+  }
+}
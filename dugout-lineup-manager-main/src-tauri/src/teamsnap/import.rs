@@ -0,0 +1,121 @@
+//! Merging TeamSnap's roster and schedule into the local database.
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::db::games::{self, ScheduledGame};
+use crate::db::roster::{self, NewPlayer};
+use crate::error::Result;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSnapImport {
+  pub created: usize,
+  pub updated: usize,
+  /// Rows already in the app, or that couldn't be imported.
+  pub skipped: usize,
+}
+
+/// Adds players that aren't on the team yet, matched by name. Players
+/// already here are left alone, since the coach may have edited them.
+pub fn merge_roster(
+  conn: &mut Connection,
+  team_id: &str,
+  players: Vec<NewPlayer>,
+) -> Result<TeamSnapImport> {
+  let tx = conn.transaction()?;
+  let existing = roster::list(&tx, team_id)?;
+  let mut summary = TeamSnapImport::default();
+  for mut player in players {
+    if existing.iter().any(|p| p.name.eq_ignore_ascii_case(&player.name)) {
+      summary.skipped += 1;
+      continue;
+    }
+    // TeamSnap allows shared numbers; the roster doesn't
+    if player.number.is_some_and(|n| existing.iter().any(|p| p.number == Some(n))) {
+      player.number = None;
+    }
+    let name = player.name.clone();
+    match roster::create(&tx, player) {
+      Ok(_) => summary.created += 1,
+      Err(e) => {
+        log::warn!("Skipped TeamSnap player {}: {}", name, e);
+        summary.skipped += 1;
+      }
+    }
+  }
+  tx.commit()?;
+  Ok(summary)
+}
+
+/// Adds or updates games from the schedule. Scores entered in TeamSnap
+/// mark the game completed; scores only kept here are not cleared.
+pub fn merge_schedule(
+  conn: &mut Connection,
+  team_id: &str,
+  games: &[ScheduledGame],
+) -> Result<TeamSnapImport> {
+  let tx = conn.transaction()?;
+  let mut summary = TeamSnapImport::default();
+  for game in games {
+    if games::upsert_scheduled(&tx, team_id, game)? {
+      summary.created += 1;
+    } else {
+      summary.updated += 1;
+    }
+  }
+  tx.commit()?;
+  Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::open_in_memory;
+
+  fn new_player(name: &str, number: Option<u32>) -> NewPlayer {
+    NewPlayer {
+      team_id: None,
+      name: name.into(),
+      number,
+      primary_position: "SS".into(),
+      secondary_positions: Vec::new(),
+      bats: "R".into(),
+      throws: "R".into(),
+      status: None,
+      notes: None,
+    }
+  }
+
+  #[test]
+  fn imports_are_idempotent() {
+    let mut conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    roster::create(&conn, new_player("Ava Chen", Some(3))).unwrap();
+
+    let players = vec![new_player("ava chen", Some(8)), new_player("Jake Miller", Some(3))];
+    let summary = merge_roster(&mut conn, &team_id, players).unwrap();
+    assert_eq!((summary.created, summary.skipped), (1, 1));
+    let jake = roster::list(&conn, &team_id).unwrap().into_iter().find(|p| p.name == "Jake Miller");
+    assert_eq!(jake.unwrap().number, None);
+
+    let mut game = ScheduledGame {
+      id: "teamsnap-901".into(),
+      date: "2026-04-11".into(),
+      opponent: "Tigers".into(),
+      home_away: "away".into(),
+      score_us: None,
+      score_them: None,
+      notes: String::new(),
+      location: String::new(),
+      start_time: None,
+    };
+    merge_schedule(&mut conn, &team_id, std::slice::from_ref(&game)).unwrap();
+    game.score_us = Some(2);
+    game.score_them = Some(5);
+    let summary = merge_schedule(&mut conn, &team_id, &[game]).unwrap();
+    assert_eq!((summary.created, summary.updated), (0, 1));
+    let saved = games::get(&conn, "teamsnap-901").unwrap();
+    assert_eq!((saved.result.as_deref(), saved.status.as_str()), (Some("L"), "completed"));
+  }
+}
@@ -0,0 +1,225 @@
+//! TeamSnap import, so a team's roster and schedule don't have to be typed
+//! in again.
+//!
+//! `link_teamsnap` opens TeamSnap's sign-in page in the browser, which
+//! sends the coach back to `dugout://teamsnap/callback`. Once [`oauth`] has
+//! traded the code there for a token, [`TEAMSNAP_LINK_EVENT`] tells the
+//! webview which teams it can import.
+
+mod api;
+mod import;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State, Url};
+
+pub use api::Team;
+pub use import::TeamSnapImport;
+
+use crate::db::{roster, Database};
+use crate::error::{Error, Result};
+use crate::oauth::{self, PendingLink, Provider};
+
+/// Emitted with a [`TeamSnapLink`] when sign-in in the browser finishes.
+pub const TEAMSNAP_LINK_EVENT: &str = "teamsnap-linked";
+
+const AUTH_URL: &str = "https://auth.teamsnap.com/oauth/authorize";
+const TOKEN_URL: &str = "https://auth.teamsnap.com/oauth/token";
+const API_URL: &str = "https://api.teamsnap.com/v3";
+const REDIRECT_URI: &str = "dugout://teamsnap/callback";
+
+/// Set when building release bundles; TeamSnap issues them per app.
+const CLIENT_ID: Option<&str> = option_env!("TEAMSNAP_CLIENT_ID");
+const CLIENT_SECRET: Option<&str> = option_env!("TEAMSNAP_CLIENT_SECRET");
+
+/// Keychain account holding the token, under the app's service name.
+const KEYCHAIN_ACCOUNT: &str = "teamsnap";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSnapLink {
+  pub linked: bool,
+  pub teams: Vec<Team>,
+  /// Why linking failed, ready to show.
+  pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct TeamSnapState(Mutex<Option<PendingLink>>);
+
+fn provider() -> Provider {
+  Provider {
+    name: "TeamSnap",
+    endpoints: oauth::Endpoints {
+      auth_url: AUTH_URL,
+      token_url: TOKEN_URL,
+      client_id: CLIENT_ID,
+      client_secret: CLIENT_SECRET,
+      auth_params: &[("scope", "read")],
+    },
+    redirect_uri: REDIRECT_URI,
+    keychain_account: KEYCHAIN_ACCOUNT.into(),
+    error: Error::TeamSnap,
+  }
+}
+
+fn request_error(e: reqwest::Error) -> Error {
+  Error::TeamSnap(e.to_string())
+}
+
+async fn get(path: &str, query: &[(&str, &str)]) -> Result<Vec<api::Item>> {
+  let url = Url::parse_with_params(&format!("{API_URL}{path}"), query)
+    .map_err(|e| Error::TeamSnap(e.to_string()))?;
+  let response = reqwest::Client::new()
+    .get(url)
+    .bearer_auth(provider().access_token().await?)
+    .header(reqwest::header::ACCEPT, "application/vnd.collection+json")
+    .timeout(REQUEST_TIMEOUT)
+    .send()
+    .await
+    .map_err(request_error)?;
+  if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+    return Err(Error::TeamSnap("TeamSnap sign-in has expired; link it again".into()));
+  }
+  let body: serde_json::Value = response.json().await.map_err(request_error)?;
+  api::items(&body)
+}
+
+async fn teams() -> Result<Vec<Team>> {
+  let me = get("/me", &[]).await?;
+  let user_id = me
+    .first()
+    .and_then(|user| api::text(user, "id"))
+    .ok_or_else(|| Error::TeamSnap("TeamSnap didn't say who is signed in".into()))?;
+  let teams = get("/teams/search", &[("user_id", user_id.as_str())]).await?;
+  Ok(teams.iter().filter_map(api::team).collect())
+}
+
+/// Whether `url` is TeamSnap sending the browser back after sign-in.
+pub fn is_callback(url: &Url) -> bool {
+  url.scheme() == crate::deep_link::SCHEME && url.host_str() == Some("teamsnap")
+}
+
+/// Finishes a sign-in from the callback link and reports the outcome with
+/// [`TEAMSNAP_LINK_EVENT`].
+pub fn handle_callback(app: &AppHandle, url: Url) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let link = match finish_link(&app, &url).await {
+      Ok(teams) => TeamSnapLink {
+        linked: true,
+        teams,
+        error: None,
+      },
+      Err(e) => {
+        log::warn!("TeamSnap sign-in failed: {}", e);
+        TeamSnapLink {
+          linked: false,
+          teams: Vec::new(),
+          error: Some(e.to_string()),
+        }
+      }
+    };
+    let _ = app.emit(TEAMSNAP_LINK_EVENT, link);
+  });
+}
+
+async fn finish_link(app: &AppHandle, url: &Url) -> Result<Vec<Team>> {
+  let pending = app
+    .state::<TeamSnapState>()
+    .0
+    .lock()
+    .unwrap()
+    .take()
+    .ok_or_else(|| Error::Validation("No TeamSnap sign-in is in progress".into()))?;
+  provider().finish_link(url, &pending).await?;
+  log::info!("Linked TeamSnap account");
+  teams().await
+}
+
+/// Opens TeamSnap's sign-in page in the browser. The result arrives as
+/// [`TEAMSNAP_LINK_EVENT`].
+#[tauri::command]
+pub fn link_teamsnap(app: AppHandle, state: State<'_, TeamSnapState>) -> Result<()> {
+  *state.0.lock().unwrap() = Some(provider().start_link(&app)?);
+  Ok(())
+}
+
+/// The linked account's teams, to pick one to import from.
+#[tauri::command]
+pub async fn list_teamsnap_teams() -> Result<Vec<Team>> {
+  teams().await
+}
+
+/// Forgets the stored TeamSnap token.
+#[tauri::command]
+pub async fn unlink_teamsnap() -> Result<()> {
+  provider().unlink().await?;
+  log::info!("Unlinked TeamSnap account");
+  Ok(())
+}
+
+/// Adds the players on a TeamSnap team to `team_id`, skipping any already
+/// on it by name.
+#[tauri::command]
+pub async fn import_teamsnap_roster(
+  app: AppHandle,
+  teamsnap_team_id: String,
+  team_id: Option<String>,
+) -> Result<TeamSnapImport> {
+  let members = get("/members/search", &[("team_id", teamsnap_team_id.as_str())]).await?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let db = app.state::<Database>();
+    let mut conn = db.conn();
+    let team_id = match team_id {
+      Some(team_id) => team_id,
+      None => roster::default_team_id(&conn)?,
+    };
+    let summary = import::merge_roster(&mut conn, &team_id, api::players(&members, &team_id))?;
+    app.state::<crate::sync::SyncState>().resend_snapshot();
+    log::info!("Imported {} players from TeamSnap", summary.created);
+    Ok(summary)
+  })
+  .await?
+}
+
+/// Adds a TeamSnap team's games to the schedule of `team_id`, updating
+/// games imported before.
+#[tauri::command]
+pub async fn import_teamsnap_schedule(
+  app: AppHandle,
+  teamsnap_team_id: String,
+  team_id: Option<String>,
+) -> Result<TeamSnapImport> {
+  let events = get("/events/search", &[("team_id", teamsnap_team_id.as_str())]).await?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let db = app.state::<Database>();
+    let mut conn = db.conn();
+    let team_id = match team_id {
+      Some(team_id) => team_id,
+      None => roster::default_team_id(&conn)?,
+    };
+    let summary = import::merge_schedule(&mut conn, &team_id, &api::games(&events))?;
+    log::info!(
+      "Imported TeamSnap schedule: {} new, {} updated",
+      summary.created,
+      summary.updated
+    );
+    Ok(summary)
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn recognizes_the_callback() {
+    assert!(is_callback(&Url::parse("dugout://teamsnap/callback?code=x").unwrap()));
+  }
+}
@@ -0,0 +1,302 @@
+//! Opt-in, anonymous counts of which features get used.
+//!
+//! Nothing is recorded until the coach opts in. After that, each use of a
+//! tracked feature bumps a per-day counter in `telemetry.json` under the
+//! app data directory: the feature, the day and how often, never names,
+//! rosters or anything typed into the app. `get_pending_telemetry` shows
+//! exactly what the next upload will send. Batches go every few hours to
+//! the collector baked in at build time (`DUGOUT_TELEMETRY_URL`); builds
+//! without one keep the queue local. Opting out deletes the queue along
+//! with the random install id that ties batches together.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::Result;
+use crate::paths::AppPaths;
+use crate::settings::{self, AppSettings};
+
+/// Where batches are sent, if this build has a collector.
+const UPLOAD_URL: Option<&str> = option_env!("DUGOUT_TELEMETRY_URL");
+
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+const UPLOAD_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TelemetrySettings {
+  /// Off until the coach opts in.
+  pub enabled: bool,
+}
+
+/// The features that are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Feature {
+  LineupSaved,
+  LineupGenerated,
+  LineupPrinted,
+  PdfExported,
+  SpreadsheetExported,
+  GamesImported,
+  GameModeEntered,
+  SyncPaired,
+  AiRequest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageCount {
+  pub feature: Feature,
+  pub day: NaiveDate,
+  pub count: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Queue {
+  install_id: String,
+  counts: Vec<UsageCount>,
+}
+
+impl Queue {
+  fn bump(&mut self, feature: Feature, day: NaiveDate) {
+    match self.counts.iter_mut().find(|c| c.feature == feature && c.day == day) {
+      Some(usage) => usage.count += 1,
+      None => self.counts.push(UsageCount {
+        feature,
+        day,
+        count: 1,
+      }),
+    }
+  }
+
+  /// Takes off what a successful upload sent, keeping anything counted
+  /// while it was in flight.
+  fn acknowledge(&mut self, sent: &[UsageCount]) {
+    for sent in sent {
+      if let Some(usage) = self
+        .counts
+        .iter_mut()
+        .find(|c| c.feature == sent.feature && c.day == sent.day)
+      {
+        usage.count = usage.count.saturating_sub(sent.count);
+      }
+    }
+    self.counts.retain(|c| c.count > 0);
+  }
+}
+
+/// Everything an upload sends.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Batch {
+  pub install_id: String,
+  pub app_version: String,
+  pub os: String,
+  pub counts: Vec<UsageCount>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTelemetry {
+  pub enabled: bool,
+  /// Where the batch goes; `None` when this build never uploads.
+  pub upload_url: Option<String>,
+  #[serde(flatten)]
+  pub batch: Batch,
+}
+
+pub struct Telemetry {
+  path: PathBuf,
+  enabled: AtomicBool,
+  queue: Mutex<Queue>,
+}
+
+fn load(path: &Path) -> Queue {
+  match std::fs::read_to_string(path) {
+    Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+      log::warn!("Discarding invalid telemetry queue {:?}: {}", path, e);
+      Queue::default()
+    }),
+    Err(_) => Queue::default(),
+  }
+}
+
+impl Telemetry {
+  pub fn new(path: PathBuf, enabled: bool) -> Self {
+    let queue = if enabled { load(&path) } else { Queue::default() };
+    Self {
+      path,
+      enabled: AtomicBool::new(enabled),
+      queue: Mutex::new(queue),
+    }
+  }
+
+  /// Counts one use of `feature`, if the coach opted in.
+  pub fn record(&self, feature: Feature) {
+    if !self.enabled.load(Ordering::SeqCst) {
+      return;
+    }
+    let mut queue = self.queue.lock().unwrap();
+    if queue.install_id.is_empty() {
+      queue.install_id = uuid::Uuid::new_v4().to_string();
+    }
+    queue.bump(feature, Utc::now().date_naive());
+    self.save(&queue);
+  }
+
+  /// Starts or stops counting. Opting out forgets everything.
+  pub fn set_enabled(&self, enabled: bool) {
+    if self.enabled.swap(enabled, Ordering::SeqCst) == enabled {
+      return;
+    }
+    let mut queue = self.queue.lock().unwrap();
+    *queue = Queue::default();
+    if enabled {
+      queue.install_id = uuid::Uuid::new_v4().to_string();
+      self.save(&queue);
+    } else if let Err(e) = std::fs::remove_file(&self.path) {
+      if e.kind() != std::io::ErrorKind::NotFound {
+        log::warn!("Failed to delete the telemetry queue: {}", e);
+      }
+    }
+  }
+
+  fn save(&self, queue: &Queue) {
+    let tmp = self.path.with_extension("json.tmp");
+    let written = serde_json::to_vec(queue)
+      .map_err(std::io::Error::from)
+      .and_then(|bytes| std::fs::write(&tmp, bytes))
+      .and_then(|()| std::fs::rename(&tmp, &self.path));
+    if let Err(e) = written {
+      log::warn!("Failed to save the telemetry queue: {}", e);
+    }
+  }
+
+  pub fn pending(&self) -> PendingTelemetry {
+    let queue = self.queue.lock().unwrap();
+    PendingTelemetry {
+      enabled: self.enabled.load(Ordering::SeqCst),
+      upload_url: UPLOAD_URL.map(str::to_string),
+      batch: Batch {
+        install_id: queue.install_id.clone(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        counts: queue.counts.clone(),
+      },
+    }
+  }
+}
+
+/// Starts the background task that uploads batches while opted in.
+pub fn start_uploader(app: AppHandle) {
+  let Some(url) = UPLOAD_URL else {
+    return;
+  };
+  tauri::async_runtime::spawn(async move {
+    tokio::time::sleep(STARTUP_DELAY).await;
+    let client = reqwest::Client::new();
+    loop {
+      if let Err(e) = upload(&client, url, &app.state::<Telemetry>()).await {
+        log::debug!("Telemetry upload failed: {}", e);
+      }
+      tokio::time::sleep(UPLOAD_INTERVAL).await;
+    }
+  });
+}
+
+async fn upload(
+  client: &reqwest::Client,
+  url: &str,
+  telemetry: &Telemetry,
+) -> reqwest::Result<()> {
+  let pending = telemetry.pending();
+  if !pending.enabled || pending.batch.counts.is_empty() {
+    return Ok(());
+  }
+  client
+    .post(url)
+    .json(&pending.batch)
+    .timeout(UPLOAD_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?;
+
+  // Opting out mid-upload already cleared the queue
+  if telemetry.enabled.load(Ordering::SeqCst) {
+    let mut queue = telemetry.queue.lock().unwrap();
+    queue.acknowledge(&pending.batch.counts);
+    telemetry.save(&queue);
+  }
+  Ok(())
+}
+
+/// Opts in to or out of usage counts, returning what is queued.
+#[tauri::command]
+pub fn set_telemetry_enabled(
+  app: AppHandle,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+  telemetry: State<'_, Telemetry>,
+  enabled: bool,
+) -> Result<PendingTelemetry> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  {
+    let mut settings = settings.write().unwrap();
+    settings.telemetry.enabled = enabled;
+    settings::save(&paths.settings, &settings)?;
+  }
+  telemetry.set_enabled(enabled);
+  Ok(telemetry.pending())
+}
+
+/// Exactly what the next upload would send.
+#[tauri::command]
+pub fn get_pending_telemetry(telemetry: State<'_, Telemetry>) -> PendingTelemetry {
+  telemetry.pending()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn counts_per_day_only_while_opted_in() {
+    let dir = std::env::temp_dir().join(format!("dugout-telemetry-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("telemetry.json");
+
+    let telemetry = Telemetry::new(path.clone(), false);
+    telemetry.record(Feature::LineupSaved);
+    assert!(telemetry.pending().batch.counts.is_empty());
+    assert!(!path.exists());
+
+    telemetry.set_enabled(true);
+    telemetry.record(Feature::LineupSaved);
+    telemetry.record(Feature::LineupSaved);
+    telemetry.record(Feature::PdfExported);
+    let reloaded = Telemetry::new(path.clone(), true).pending().batch;
+    assert!(!reloaded.install_id.is_empty());
+    let counts: Vec<_> = reloaded.counts.iter().map(|c| (c.feature, c.count)).collect();
+    assert_eq!(counts, [(Feature::LineupSaved, 2), (Feature::PdfExported, 1)]);
+
+    let mut queue = load(&path);
+    let sent = queue.counts.clone();
+    queue.bump(Feature::LineupSaved, sent[0].day);
+    queue.acknowledge(&sent);
+    assert_eq!(queue.counts.len(), 1);
+    assert_eq!((queue.counts[0].feature, queue.counts[0].count), (Feature::LineupSaved, 1));
+
+    telemetry.set_enabled(false);
+    assert!(!path.exists());
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,159 @@
+//! Light and dark appearance. The preference is `theme` in settings: with
+//! [`Theme::System`] the windows follow the OS, and the webview hears when
+//! the OS switches through [`THEME_CHANGED_EVENT`].
+
+use std::sync::{Mutex, RwLock};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State, Window, WindowEvent};
+
+use crate::error::Result;
+use crate::paths::AppPaths;
+use crate::settings::{self, AppSettings, Theme};
+
+/// Emitted with a [`ThemeStatus`] when the preference is changed or the OS
+/// switches appearance while the app follows it.
+pub const THEME_CHANGED_EVENT: &str = "theme-changed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Appearance {
+  Light,
+  Dark,
+}
+
+impl From<tauri::Theme> for Appearance {
+  fn from(theme: tauri::Theme) -> Self {
+    match theme {
+      tauri::Theme::Dark => Self::Dark,
+      _ => Self::Light,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeStatus {
+  pub preference: Theme,
+  pub system: Appearance,
+  /// What the app is showing.
+  pub effective: Appearance,
+}
+
+impl ThemeStatus {
+  fn new(preference: Theme, system: Appearance) -> Self {
+    Self {
+      preference,
+      system,
+      effective: match preference {
+        Theme::System => system,
+        Theme::Light => Appearance::Light,
+        Theme::Dark => Appearance::Dark,
+      },
+    }
+  }
+}
+
+/// The OS appearance as last seen. Windows report the forced theme while
+/// one is set, so this is only updated while the app follows the OS.
+#[derive(Default)]
+pub struct SystemTheme(Mutex<Option<Appearance>>);
+
+fn preference(app: &AppHandle) -> Theme {
+  app
+    .try_state::<RwLock<AppSettings>>()
+    .map_or(Theme::System, |settings| settings.read().unwrap().theme)
+}
+
+fn window_theme(app: &AppHandle) -> Option<Appearance> {
+  let window = app.get_webview_window("main")?;
+  window.theme().ok().map(Appearance::from)
+}
+
+fn system_theme(app: &AppHandle) -> Appearance {
+  let state = app.state::<SystemTheme>();
+  let mut system = state.0.lock().unwrap();
+  if preference(app) == Theme::System {
+    if let Some(theme) = window_theme(app) {
+      *system = Some(theme);
+    }
+  }
+  system.unwrap_or(Appearance::Light)
+}
+
+/// Switches every window to `theme`.
+pub fn apply(app: &AppHandle, theme: Theme) {
+  app.set_theme(match theme {
+    Theme::System => None,
+    Theme::Light => Some(tauri::Theme::Light),
+    Theme::Dark => Some(tauri::Theme::Dark),
+  });
+}
+
+/// Notes the OS appearance before anything is forced, then applies the
+/// saved preference. Call once the main window exists.
+pub fn init(app: &AppHandle) {
+  *app.state::<SystemTheme>().0.lock().unwrap() = window_theme(app);
+  apply(app, preference(app));
+}
+
+/// Passes OS appearance changes on to the webview.
+pub fn handle_window_event(window: &Window, event: &WindowEvent) {
+  let WindowEvent::ThemeChanged(theme) = event else {
+    return;
+  };
+  let app = window.app_handle();
+  // While a theme is forced the change is our own doing, not the OS's
+  if window.label() != "main" || preference(app) != Theme::System {
+    return;
+  }
+  let system = Appearance::from(*theme);
+  let previous = app.state::<SystemTheme>().0.lock().unwrap().replace(system);
+  if previous != Some(system) {
+    let _ = app.emit(THEME_CHANGED_EVENT, ThemeStatus::new(Theme::System, system));
+  }
+}
+
+/// The OS appearance, whatever the app is showing.
+#[tauri::command]
+pub fn get_system_theme(app: AppHandle) -> Appearance {
+  system_theme(&app)
+}
+
+/// Saves the theme preference and applies it to every window.
+#[tauri::command]
+pub fn set_theme(
+  app: AppHandle,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+  theme: Theme,
+) -> Result<ThemeStatus> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  // Before the preference changes, while it still says whether the
+  // windows show the OS appearance
+  let system = system_theme(&app);
+  {
+    let mut settings = settings.write().unwrap();
+    settings.theme = theme;
+    settings::save(&paths.settings, &settings)?;
+  }
+  apply(&app, theme);
+
+  let status = ThemeStatus::new(theme, system);
+  let _ = app.emit(THEME_CHANGED_EVENT, status);
+  Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn follows_the_os_only_when_asked() {
+    let status = ThemeStatus::new(Theme::System, Appearance::Dark);
+    assert_eq!(status.effective, Appearance::Dark);
+    let status = ThemeStatus::new(Theme::Light, Appearance::Dark);
+    assert_eq!((status.system, status.effective), (Appearance::Dark, Appearance::Light));
+    assert_eq!(Appearance::from(tauri::Theme::Dark), Appearance::Dark);
+  }
+}
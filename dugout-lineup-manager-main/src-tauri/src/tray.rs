@@ -0,0 +1,198 @@
+//! Tray icon with quick actions, and hiding the main window to the tray.
+
+use std::sync::RwLock;
+
+use serde::Serialize;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{App, AppHandle, Emitter, Manager, State, Window, WindowEvent};
+
+use crate::error::Result;
+use crate::paths::AppPaths;
+use crate::settings::{self, AppSettings};
+use crate::{db, health, updater};
+
+/// Event carrying quick actions the webview has to carry out.
+pub const TRAY_ACTION_EVENT: &str = "tray-action";
+
+const OPEN_TODAYS_GAME: &str = "open-todays-game";
+const START_GAME_TIMER: &str = "start-game-timer";
+const RESTART_BACKEND: &str = "restart-backend";
+const ROLLBACK_UPDATE: &str = "rollback-update";
+const QUIT: &str = "quit";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum TrayAction {
+  #[serde(rename_all = "camelCase")]
+  OpenTodaysGame {
+    /// Today's date, `YYYY-MM-DD`, in local time.
+    date: String,
+    /// Today's game in the local database, if one is scheduled.
+    game_id: Option<String>,
+  },
+  StartGameTimer,
+}
+
+pub fn create(app: &App) -> tauri::Result<()> {
+  let menu = Menu::with_items(
+    app,
+    &[
+      &MenuItem::with_id(app, OPEN_TODAYS_GAME, "Open Today's Game", true, None::<&str>)?,
+      &MenuItem::with_id(app, START_GAME_TIMER, "Start Game Timer", true, None::<&str>)?,
+      &PredefinedMenuItem::separator(app)?,
+      &MenuItem::with_id(app, RESTART_BACKEND, "Restart AI Backend", true, None::<&str>)?,
+      &PredefinedMenuItem::separator(app)?,
+      &MenuItem::with_id(app, QUIT, "Quit Dugout", true, None::<&str>)?,
+    ],
+  )?;
+  if let Some(version) = updater::get_rollback_version(app.handle().clone()) {
+    let label = format!("Roll Back to v{version}");
+    let item = MenuItem::with_id(app, ROLLBACK_UPDATE, label, true, None::<&str>)?;
+    menu.insert(&item, 4)?;
+  }
+
+  let mut tray = TrayIconBuilder::with_id("main")
+    .tooltip("Dugout")
+    .menu(&menu)
+    .show_menu_on_left_click(false)
+    .on_menu_event(handle_menu_event)
+    .on_tray_icon_event(|tray, event| {
+      if let TrayIconEvent::Click {
+        button: MouseButton::Left,
+        button_state: MouseButtonState::Up,
+        ..
+      } = event
+      {
+        show_main_window(tray.app_handle());
+      }
+    });
+  if let Some(icon) = app.default_window_icon() {
+    tray = tray.icon(icon.clone());
+  }
+  tray.build(app)?;
+  Ok(())
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+  match event.id.as_ref() {
+    OPEN_TODAYS_GAME => {
+      show_main_window(app);
+      let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+      let game_id = todays_game(app, &date).unwrap_or_else(|e| {
+        log::warn!("Failed to look up today's game: {}", e);
+        None
+      });
+      emit(app, TrayAction::OpenTodaysGame { date, game_id });
+    }
+    START_GAME_TIMER => {
+      show_main_window(app);
+      emit(app, TrayAction::StartGameTimer);
+    }
+    RESTART_BACKEND => {
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move {
+        if let Err(e) = health::restart_backend(app).await {
+          log::error!("Restart from tray failed: {}", e);
+        }
+      });
+    }
+    ROLLBACK_UPDATE => confirm_rollback(app),
+    QUIT => app.exit(0),
+    _ => {}
+  }
+}
+
+fn confirm_rollback(app: &AppHandle) {
+  use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+  app
+    .dialog()
+    .message("Dugout will reinstall the previous version and restart. Your team data is not affected.")
+    .title("Roll back update?")
+    .kind(MessageDialogKind::Warning)
+    .buttons(MessageDialogButtons::OkCancelCustom(
+      "Roll Back".into(),
+      "Cancel".into(),
+    ))
+    .show({
+      let app = app.clone();
+      move |confirmed| {
+        if !confirmed {
+          return;
+        }
+        tauri::async_runtime::spawn(async move {
+          match updater::rollback_update(app.clone()).await {
+            Ok(()) => app.restart(),
+            Err(e) => log::error!("Rollback from tray failed: {}", e),
+          }
+        });
+      }
+    });
+}
+
+fn todays_game(app: &AppHandle, date: &str) -> Result<Option<String>> {
+  // The tray is up before the database is open
+  let Some(db) = app.try_state::<db::Database>() else {
+    return Ok(None);
+  };
+  let conn = db.conn();
+  let team_id = db::roster::default_team_id(&conn)?;
+  Ok(
+    db::games::list(&conn, &team_id)?
+      .into_iter()
+      .find(|game| game.date == date)
+      .map(|game| game.id),
+  )
+}
+
+fn emit(app: &AppHandle, action: TrayAction) {
+  if let Err(e) = app.emit(TRAY_ACTION_EVENT, action) {
+    log::warn!("Failed to emit tray action: {}", e);
+  }
+}
+
+pub fn show_main_window(app: &AppHandle) {
+  if let Some(window) = app.get_webview_window("main") {
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+  }
+}
+
+/// Hides the main window instead of closing or minimizing it when the
+/// user has opted into minimize-to-tray, except in kiosk mode.
+pub fn handle_window_event(window: &Window, event: &WindowEvent) {
+  if window.label() != "main" || crate::kiosk::is_locked(window.app_handle()) {
+    return;
+  }
+  let enabled = window
+    .try_state::<RwLock<AppSettings>>()
+    .is_some_and(|settings| settings.read().unwrap().minimize_to_tray);
+  if !enabled {
+    return;
+  }
+  match event {
+    WindowEvent::CloseRequested { api, .. } => {
+      api.prevent_close();
+      let _ = window.hide();
+    }
+    WindowEvent::Resized(_) if window.is_minimized().unwrap_or(false) => {
+      let _ = window.hide();
+    }
+    _ => {}
+  }
+}
+
+#[tauri::command]
+pub fn set_minimize_to_tray(
+  app: AppHandle,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+  enabled: bool,
+) -> Result<()> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  let mut settings = settings.write().unwrap();
+  settings.minimize_to_tray = enabled;
+  settings::save(&paths.settings, &settings)
+}
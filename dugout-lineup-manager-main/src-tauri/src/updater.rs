@@ -0,0 +1,368 @@
+//! In-app updates from GitHub Releases on the channel picked in settings.
+//!
+//! Checks go through Rust rather than the updater's JS API because the
+//! plugin only knows the endpoint in `tauri.conf.json`, while the channel
+//! can change at runtime.
+//!
+//! Each installed bundle is kept in the updates directory along with the
+//! version it replaced, so `rollback_update` can reinstall the previous
+//! release without downloading it again.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State, Url};
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+
+use crate::error::{Error, Result};
+use crate::paths::AppPaths;
+use crate::settings::{self, AppSettings, UpdateChannel};
+
+/// Manifest of the newest stable release. GitHub's `latest` skips
+/// prereleases.
+const STABLE_ENDPOINT: &str =
+  "https://github.com/jordanheckler-HMM/dugout-app/releases/latest/download/latest.json";
+
+/// Manifest of the newest release of any kind, re-uploaded to the rolling
+/// `beta-channel` release by the release workflow.
+const BETA_ENDPOINT: &str =
+  "https://github.com/jordanheckler-HMM/dugout-app/releases/download/beta-channel/latest.json";
+
+/// Records what the last install or rollback replaced, in `history.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct InstallHistory {
+  /// Version the last update replaced.
+  previous: Option<String>,
+  /// Version rolled back from; checks don't offer it again.
+  skipped: Option<String>,
+}
+
+fn history_path(dir: &Path) -> PathBuf {
+  dir.join("history.json")
+}
+
+fn bundle_path(dir: &Path, version: &str) -> PathBuf {
+  dir.join(format!("{version}.bundle"))
+}
+
+fn load_history(dir: &Path) -> InstallHistory {
+  std::fs::read(history_path(dir))
+    .ok()
+    .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    .unwrap_or_default()
+}
+
+fn save_history(dir: &Path, history: &InstallHistory) -> Result<()> {
+  std::fs::create_dir_all(dir)?;
+  std::fs::write(history_path(dir), serde_json::to_vec_pretty(history)?)?;
+  Ok(())
+}
+
+/// Keeps `bytes` as the bundle for `version`, replacing `current`, and
+/// deletes bundles of any other version.
+fn keep_bundle(dir: &Path, current: &str, version: &str, bytes: &[u8]) -> Result<()> {
+  std::fs::create_dir_all(dir)?;
+  std::fs::write(bundle_path(dir, version), bytes)?;
+  let keep = [bundle_path(dir, current), bundle_path(dir, version)];
+  for entry in std::fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.extension().is_some_and(|ext| ext == "bundle") && !keep.contains(&path) {
+      std::fs::remove_file(&path)?;
+    }
+  }
+  save_history(
+    dir,
+    &InstallHistory {
+      previous: Some(current.to_string()),
+      skipped: None,
+    },
+  )
+}
+
+pub fn endpoint(channel: UpdateChannel) -> Url {
+  let url = match channel {
+    UpdateChannel::Stable => STABLE_ENDPOINT,
+    UpdateChannel::Beta => BETA_ENDPOINT,
+  };
+  Url::parse(url).expect("update endpoints are valid URLs")
+}
+
+/// Manifest published with a specific release.
+fn release_endpoint(version: &str) -> Url {
+  let url = format!(
+    "https://github.com/jordanheckler-HMM/dugout-app/releases/download/v{version}/latest.json"
+  );
+  Url::parse(&url).expect("update endpoints are valid URLs")
+}
+
+/// An updater pointed at the selected channel's manifest. A version the
+/// user rolled back from is not offered again.
+pub fn updater(app: &AppHandle) -> Result<Updater> {
+  let channel = app
+    .state::<RwLock<AppSettings>>()
+    .read()
+    .unwrap()
+    .update_channel;
+  let skipped = load_history(&app.state::<AppPaths>().updates).skipped;
+  let handle = app.clone();
+  Ok(
+    app
+      .updater_builder()
+      .endpoints(vec![endpoint(channel)])?
+      .version_comparator(move |current, remote| {
+        remote.version > current && skipped.as_deref() != Some(&remote.version.to_string())
+      })
+      // On Windows `install` starts the installer and exits instead of
+      // returning, once the bundle has been unpacked.
+      .on_before_exit(move || {
+        let dir = handle.state::<AppPaths>().updates.clone();
+        handle.state::<PendingUpdate>().record_install(&dir);
+      })
+      .build()?,
+  )
+}
+
+/// Event carrying [`DownloadProgress`] while an update downloads.
+pub const DOWNLOAD_PROGRESS_EVENT: &str = "update-download-progress";
+
+struct Pending {
+  update: Update,
+  /// Verified bundle, once `download_update` has fetched it.
+  bytes: Option<Vec<u8>>,
+}
+
+/// A bundle handed to the installer, and the version it replaces.
+struct Installing {
+  current: String,
+  version: String,
+  bytes: Arc<Vec<u8>>,
+}
+
+/// The update found by the last check, kept for `download_update` and
+/// `install_update`.
+#[derive(Default)]
+pub struct PendingUpdate {
+  found: Mutex<Option<Pending>>,
+  installing: Mutex<Option<Installing>>,
+}
+
+impl PendingUpdate {
+  /// Forgets the update found by the last check, e.g. after the channel
+  /// changes.
+  pub fn clear(&self) {
+    *self.found.lock().unwrap() = None;
+  }
+
+  /// Keeps the bundle that was just installed for rollback, replacing the
+  /// history and the other bundles. Does nothing if there is none, or it
+  /// was already kept.
+  fn record_install(&self, dir: &Path) {
+    let Some(installing) = self.installing.lock().unwrap().take() else {
+      return;
+    };
+    let Installing {
+      current,
+      version,
+      bytes,
+    } = &installing;
+    if let Err(e) = keep_bundle(dir, current, version, bytes) {
+      log::warn!("Failed to keep update bundle for rollback: {}", e);
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+  pub version: String,
+  pub current_version: String,
+  /// Release notes.
+  pub notes: Option<String>,
+  pub date: Option<String>,
+}
+
+impl From<&Update> for UpdateInfo {
+  fn from(update: &Update) -> Self {
+    Self {
+      version: update.version.clone(),
+      current_version: update.current_version.clone(),
+      notes: update.body.clone(),
+      date: update.date.map(|date| date.to_string()),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+  /// Bytes downloaded so far.
+  pub bytes: u64,
+  /// Absent when the server doesn't send a content length.
+  pub total: Option<u64>,
+}
+
+#[tauri::command]
+pub fn get_update_channel(settings: State<'_, RwLock<AppSettings>>) -> UpdateChannel {
+  settings.read().unwrap().update_channel
+}
+
+/// Switches channel. A pending update found on the old channel is dropped.
+#[tauri::command]
+pub fn set_update_channel(
+  app: AppHandle,
+  settings: State<'_, RwLock<AppSettings>>,
+  paths: State<'_, AppPaths>,
+  pending: State<'_, PendingUpdate>,
+  channel: UpdateChannel,
+) -> Result<()> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  let mut settings = settings.write().unwrap();
+  settings.update_channel = channel;
+  settings::save(&paths.settings, &settings)?;
+  pending.clear();
+  Ok(())
+}
+
+/// Checks the selected channel for a newer version.
+#[tauri::command]
+pub async fn check_for_updates(
+  app: AppHandle,
+  pending: State<'_, PendingUpdate>,
+) -> Result<Option<UpdateInfo>> {
+  let update = updater(&app)?.check().await?;
+  let info = update.as_ref().map(UpdateInfo::from);
+  *pending.found.lock().unwrap() = update.map(|update| Pending {
+    update,
+    bytes: None,
+  });
+  Ok(info)
+}
+
+async fn download(app: &AppHandle, update: &Update) -> Result<Vec<u8>> {
+  let mut bytes = 0;
+  let bundle = update
+    .download(
+      |chunk, total| {
+        bytes += chunk as u64;
+        let _ = app.emit(DOWNLOAD_PROGRESS_EVENT, DownloadProgress { bytes, total });
+      },
+      || {},
+    )
+    .await?;
+  Ok(bundle)
+}
+
+fn pending_update(pending: &PendingUpdate) -> Result<Update> {
+  let pending = pending.found.lock().unwrap();
+  let pending = pending
+    .as_ref()
+    .ok_or_else(|| Error::NotFound("Pending update".into()))?;
+  Ok(pending.update.clone())
+}
+
+/// Downloads and verifies the pending update and holds on to its bundle.
+/// It is only kept on disk for rollback once it has been installed.
+async fn fetch(app: &AppHandle, pending: &PendingUpdate) -> Result<()> {
+  let update = pending_update(pending)?;
+  let bytes = download(app, &update).await?;
+  let mut pending = pending.found.lock().unwrap();
+  match pending.as_mut() {
+    // The channel may have changed while downloading.
+    Some(current) if current.update.version == update.version => current.bytes = Some(bytes),
+    _ => return Err(Error::NotFound("Pending update".into())),
+  }
+  Ok(())
+}
+
+/// Downloads the update found by the last check, emitting
+/// [`DOWNLOAD_PROGRESS_EVENT`]s. Nothing is installed until
+/// `install_update`.
+#[tauri::command]
+pub async fn download_update(app: AppHandle, pending: State<'_, PendingUpdate>) -> Result<()> {
+  fetch(&app, &pending).await
+}
+
+/// Installs the downloaded update, downloading it first if needed. The
+/// caller relaunches afterwards.
+#[tauri::command]
+pub async fn install_update(app: AppHandle, pending: State<'_, PendingUpdate>) -> Result<()> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  let downloaded = pending
+    .found
+    .lock()
+    .unwrap()
+    .as_ref()
+    .is_some_and(|pending| pending.bytes.is_some());
+  if !downloaded {
+    fetch(&app, &pending).await?;
+  }
+  let Some(Pending {
+    update,
+    bytes: Some(bytes),
+  }) = pending.found.lock().unwrap().take()
+  else {
+    return Err(Error::NotFound("Pending update".into()));
+  };
+  let bytes = Arc::new(bytes);
+  *pending.installing.lock().unwrap() = Some(Installing {
+    current: update.current_version.clone(),
+    version: update.version.clone(),
+    bytes: bytes.clone(),
+  });
+  let installed = update.install(bytes.as_slice());
+  if installed.is_ok() {
+    pending.record_install(&app.state::<AppPaths>().updates);
+  } else {
+    *pending.installing.lock().unwrap() = None;
+  }
+  installed?;
+  Ok(())
+}
+
+/// The version `rollback_update` would reinstall, if any.
+#[tauri::command]
+pub fn get_rollback_version(app: AppHandle) -> Option<String> {
+  let current = app.package_info().version.to_string();
+  load_history(&app.state::<AppPaths>().updates)
+    .previous
+    .filter(|previous| *previous != current)
+}
+
+/// Reinstalls the version the last update replaced. The caller relaunches
+/// afterwards. The release's manifest is still fetched for its install
+/// metadata, but a kept bundle skips the download.
+#[tauri::command]
+pub async fn rollback_update(app: AppHandle) -> Result<()> {
+  crate::kiosk::ensure_unlocked(&app)?;
+  let current = app.package_info().version.to_string();
+  let previous =
+    get_rollback_version(app.clone()).ok_or_else(|| Error::NotFound("A previous version".into()))?;
+
+  let target = previous.clone();
+  let update = app
+    .updater_builder()
+    .endpoints(vec![release_endpoint(&previous)])?
+    .version_comparator(move |_, remote| remote.version.to_string() == target)
+    .build()?
+    .check()
+    .await?
+    .ok_or_else(|| Error::NotFound(format!("Release {previous}")))?;
+
+  let dir = app.state::<AppPaths>().updates.clone();
+  let bytes = match std::fs::read(bundle_path(&dir, &previous)) {
+    Ok(bytes) => bytes,
+    Err(_) => download(&app, &update).await?,
+  };
+  log::info!("Rolling back from {} to {}", current, previous);
+  save_history(
+    &dir,
+    &InstallHistory {
+      previous: None,
+      skipped: Some(current),
+    },
+  )?;
+  update.install(bytes)?;
+  Ok(())
+}
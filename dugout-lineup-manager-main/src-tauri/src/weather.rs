@@ -0,0 +1,658 @@
+//! Game-day forecasts for the pre-game checklist.
+//!
+//! The game's field address (or the home field from settings) is looked up
+//! with OpenStreetMap's Nominatim, then the day's forecast comes from the
+//! provider chosen in settings: Open-Meteo anywhere in the world, or the US
+//! National Weather Service. Neither needs an API key. Addresses and
+//! forecasts are cached in SQLite, so opening the checklist again doesn't
+//! refetch, and the last forecast is still shown (marked stale) offline.
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Url};
+
+use crate::db::games::{self, Game};
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::settings::AppSettings;
+
+const GEOCODE_URL: &str = "https://nominatim.openstreetmap.org/search";
+const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const NWS_URL: &str = "https://api.weather.gov";
+/// Both Nominatim and the Weather Service turn away requests without one.
+const USER_AGENT: &str = concat!("DugoutLineupManager/", env!("CARGO_PKG_VERSION"));
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// How long a forecast is reused before fetching it again.
+const FORECAST_TTL: chrono::Duration = chrono::Duration::hours(1);
+
+/// Chance of rain, in percent, from which a rainout is worth warning about.
+const RAINOUT_CHANCE: u8 = 60;
+const SHOWERS_CHANCE: u8 = 30;
+const RAINOUT_INCHES: f64 = 0.25;
+const WINDY_MPH: f64 = 20.0;
+const HOT_F: f64 = 90.0;
+const COLD_F: f64 = 45.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WeatherProvider {
+  #[default]
+  OpenMeteo,
+  /// The US National Weather Service; only covers the United States.
+  Nws,
+}
+
+impl WeatherProvider {
+  fn as_str(self) -> &'static str {
+    match self {
+      Self::OpenMeteo => "openMeteo",
+      Self::Nws => "nws",
+    }
+  }
+
+  /// How many days ahead the provider forecasts.
+  fn days_ahead(self) -> i64 {
+    match self {
+      Self::OpenMeteo => 15,
+      Self::Nws => 6,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Units {
+  /// °F, mph and inches.
+  #[default]
+  Imperial,
+  /// °C, km/h and millimetres.
+  Metric,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WeatherSettings {
+  pub provider: WeatherProvider,
+  /// Address used for home games that don't have a location of their own.
+  pub home_field: String,
+  pub units: Units,
+}
+
+/// Where an address is, as found by the geocoder.
+#[derive(Debug, Clone, PartialEq)]
+struct Place {
+  latitude: f64,
+  longitude: f64,
+  name: String,
+}
+
+/// A day's forecast, always in imperial units so changing the units in
+/// settings doesn't need a refetch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Forecast {
+  place: String,
+  high_f: f64,
+  low_f: f64,
+  precipitation_chance: Option<u8>,
+  precipitation_in: Option<f64>,
+  wind_mph: f64,
+  gusts_mph: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameWeather {
+  pub game_id: String,
+  pub date: String,
+  pub provider: WeatherProvider,
+  /// The address the forecast is for, and the place it was found at.
+  pub address: String,
+  pub place: String,
+  pub units: Units,
+  pub temperature_high: f64,
+  pub temperature_low: f64,
+  /// Highest chance of rain during the day, in percent.
+  pub precipitation_chance: Option<u8>,
+  /// Expected rain over the day. The Weather Service doesn't give one.
+  pub precipitation: Option<f64>,
+  pub wind_speed: f64,
+  pub wind_gusts: Option<f64>,
+  /// Rain heavy or likely enough that the game may be called off.
+  pub rainout_risk: bool,
+  /// Rain, wind, heat and cold worth telling the coach about, ready to show.
+  pub warnings: Vec<String>,
+  pub fetched_at: DateTime<Utc>,
+  /// Served from the cache because a fresh forecast couldn't be fetched.
+  pub stale: bool,
+}
+
+/// The last forecast fetched for a game, and what it was asked for.
+#[derive(Debug, Clone, PartialEq)]
+struct Cached {
+  provider: String,
+  address: String,
+  date: String,
+  forecast: Forecast,
+  fetched_at: DateTime<Utc>,
+}
+
+impl Cached {
+  /// Whether this is the forecast for the game as it is now, rather than
+  /// for a date, field or provider it has since changed from.
+  fn matches(&self, provider: WeatherProvider, address: &str, date: &str) -> bool {
+    self.provider == provider.as_str() && self.address == address && self.date == date
+  }
+
+  fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+    now - self.fetched_at < FORECAST_TTL
+  }
+}
+
+/// The address to forecast for, falling back to the home field for home
+/// games without one.
+fn address_for(game: &Game, settings: &WeatherSettings) -> Result<String> {
+  let address = match game.location.trim() {
+    "" if game.home_away == "home" => settings.home_field.trim(),
+    location => location,
+  };
+  if address.is_empty() {
+    return Err(Error::Validation(
+      "Add the field's address to this game (or a home field in settings) to get its forecast"
+        .into(),
+    ));
+  }
+  Ok(address.to_string())
+}
+
+/// Addresses to try in turn: the full one, then with leading parts dropped
+/// ("Field 3, North Park, Springfield" → "North Park, Springfield" →
+/// "Springfield"), since the town alone is close enough for a forecast.
+fn candidates(address: &str) -> Vec<String> {
+  let parts: Vec<&str> = address.split(',').map(str::trim).filter(|p| !p.is_empty()).collect();
+  (0..parts.len()).map(|i| parts[i..].join(", ")).collect()
+}
+
+fn request_error(e: reqwest::Error) -> Error {
+  Error::Weather(e.to_string())
+}
+
+async fn get_json(url: Url) -> Result<Value> {
+  let response = reqwest::Client::new()
+    .get(url)
+    .header(reqwest::header::USER_AGENT, USER_AGENT)
+    .timeout(REQUEST_TIMEOUT)
+    .send()
+    .await
+    .map_err(request_error)?;
+  if response.status() == reqwest::StatusCode::NOT_FOUND {
+    return Err(Error::Weather("The provider has no forecast for this field".into()));
+  }
+  response
+    .error_for_status()
+    .map_err(request_error)?
+    .json()
+    .await
+    .map_err(request_error)
+}
+
+fn url(base: &str, query: &[(&str, &str)]) -> Result<Url> {
+  Url::parse_with_params(base, query).map_err(|e| Error::Weather(e.to_string()))
+}
+
+async fn geocode(address: &str) -> Result<Place> {
+  for (i, candidate) in candidates(address).into_iter().enumerate() {
+    // Nominatim allows one request a second
+    if i > 0 {
+      tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    let query = [("q", candidate.as_str()), ("format", "jsonv2"), ("limit", "1")];
+    let results = get_json(url(GEOCODE_URL, &query)?).await?;
+    let Some(first) = results.as_array().and_then(|r| r.first()) else {
+      continue;
+    };
+    // Nominatim gives coordinates as strings
+    let coordinate = |key: &str| first[key].as_str().and_then(|v| v.parse::<f64>().ok());
+    if let (Some(latitude), Some(longitude)) = (coordinate("lat"), coordinate("lon")) {
+      return Ok(Place {
+        latitude,
+        longitude,
+        name: first["display_name"].as_str().unwrap_or(&candidate).to_string(),
+      });
+    }
+  }
+  Err(Error::Validation(format!("Couldn't find {address} on the map")))
+}
+
+/// The day's values from an Open-Meteo daily forecast.
+fn read_open_meteo(body: &Value, date: &str, place: &str) -> Option<Forecast> {
+  let daily = &body["daily"];
+  let day = daily["time"].as_array()?.iter().position(|d| d.as_str() == Some(date))?;
+  let value = |key: &str| daily[key][day].as_f64();
+  Some(Forecast {
+    place: place.to_string(),
+    high_f: value("temperature_2m_max")?,
+    low_f: value("temperature_2m_min")?,
+    precipitation_chance: value("precipitation_probability_max").map(|p| p.round() as u8),
+    precipitation_in: value("precipitation_sum"),
+    wind_mph: value("wind_speed_10m_max").unwrap_or(0.0),
+    gusts_mph: value("wind_gusts_10m_max"),
+  })
+}
+
+/// The fastest speed in a Weather Service wind like "5 to 10 mph".
+fn wind_mph(text: &str) -> Option<f64> {
+  text.split_whitespace().filter_map(|word| word.parse::<f64>().ok()).reduce(f64::max)
+}
+
+/// The day's playing hours (8am to 9pm at the field) from a Weather Service
+/// hourly forecast, reduced to a daily forecast.
+fn read_nws_hourly(body: &Value, date: &str, place: &str) -> Option<Forecast> {
+  let periods = body["properties"]["periods"].as_array()?;
+  let mut forecast: Option<Forecast> = None;
+  for period in periods {
+    // Start times carry the field's own offset, so the text is local time
+    let Some(start) = period["startTime"].as_str() else {
+      continue;
+    };
+    let hour: Option<u32> = start.get(11..13).and_then(|h| h.parse().ok());
+    if !start.starts_with(date) || !hour.is_some_and(|h| (8..=21).contains(&h)) {
+      continue;
+    }
+    let Some(mut temperature) = period["temperature"].as_f64() else {
+      continue;
+    };
+    if period["temperatureUnit"].as_str() == Some("C") {
+      temperature = temperature * 9.0 / 5.0 + 32.0;
+    }
+    let chance = period["probabilityOfPrecipitation"]["value"].as_f64().map(|p| p.round() as u8);
+    let wind = period["windSpeed"].as_str().and_then(wind_mph).unwrap_or(0.0);
+    let gust = period["windGust"].as_str().and_then(wind_mph);
+    let day = forecast.get_or_insert_with(|| Forecast {
+      place: place.to_string(),
+      high_f: temperature,
+      low_f: temperature,
+      precipitation_chance: None,
+      precipitation_in: None,
+      wind_mph: 0.0,
+      gusts_mph: None,
+    });
+    day.high_f = day.high_f.max(temperature);
+    day.low_f = day.low_f.min(temperature);
+    day.precipitation_chance = day.precipitation_chance.max(chance);
+    day.wind_mph = day.wind_mph.max(wind);
+    day.gusts_mph = match (day.gusts_mph, gust) {
+      (Some(a), Some(b)) => Some(a.max(b)),
+      (a, b) => a.or(b),
+    };
+  }
+  forecast
+}
+
+async fn fetch_forecast(provider: WeatherProvider, place: &Place, date: &str) -> Result<Forecast> {
+  let forecast = match provider {
+    WeatherProvider::OpenMeteo => {
+      let (latitude, longitude) = (place.latitude.to_string(), place.longitude.to_string());
+      let query = [
+        ("latitude", latitude.as_str()),
+        ("longitude", longitude.as_str()),
+        (
+          "daily",
+          "temperature_2m_max,temperature_2m_min,precipitation_probability_max,\
+           precipitation_sum,wind_speed_10m_max,wind_gusts_10m_max",
+        ),
+        ("temperature_unit", "fahrenheit"),
+        ("wind_speed_unit", "mph"),
+        ("precipitation_unit", "inch"),
+        ("timezone", "auto"),
+        ("start_date", date),
+        ("end_date", date),
+      ];
+      let body = get_json(url(OPEN_METEO_URL, &query)?).await?;
+      read_open_meteo(&body, date, &place.name)
+    }
+    WeatherProvider::Nws => {
+      let point = format!("{NWS_URL}/points/{:.4},{:.4}", place.latitude, place.longitude);
+      let points = get_json(url(&point, &[])?).await.map_err(|_| {
+        Error::Weather(
+          "The National Weather Service only covers the US; try Open-Meteo in settings".into(),
+        )
+      })?;
+      let Some(hourly) = points["properties"]["forecastHourly"].as_str() else {
+        return Err(Error::Weather("The Weather Service has no forecast for this field".into()));
+      };
+      let body = get_json(url(hourly, &[])?).await?;
+      read_nws_hourly(&body, date, &place.name)
+    }
+  };
+  forecast.ok_or_else(|| Error::Weather(format!("No forecast for {date} yet")))
+}
+
+fn cached_place(conn: &Connection, address: &str) -> Result<Option<Place>> {
+  Ok(
+    conn
+      .query_row(
+        "SELECT latitude, longitude, name FROM geocode_cache WHERE address = ?1",
+        [address.to_lowercase()],
+        |row| {
+          Ok(Place {
+            latitude: row.get(0)?,
+            longitude: row.get(1)?,
+            name: row.get(2)?,
+          })
+        },
+      )
+      .optional()?,
+  )
+}
+
+fn cached_forecast(conn: &Connection, game_id: &str) -> Result<Option<Cached>> {
+  let row = conn
+    .query_row(
+      "SELECT provider, address, date, forecast, fetched_at FROM weather_cache WHERE game_id = ?1",
+      [game_id],
+      |row| {
+        Ok((
+          row.get::<_, String>(0)?,
+          row.get::<_, String>(1)?,
+          row.get::<_, String>(2)?,
+          row.get::<_, String>(3)?,
+          row.get::<_, String>(4)?,
+        ))
+      },
+    )
+    .optional()?;
+  let Some((provider, address, date, forecast, fetched_at)) = row else {
+    return Ok(None);
+  };
+  // A row this version can't read is as good as none
+  let (Ok(forecast), Ok(fetched_at)) = (
+    serde_json::from_str(&forecast),
+    DateTime::parse_from_rfc3339(&fetched_at),
+  ) else {
+    return Ok(None);
+  };
+  Ok(Some(Cached {
+    provider,
+    address,
+    date,
+    forecast,
+    fetched_at: fetched_at.with_timezone(&Utc),
+  }))
+}
+
+fn store(conn: &Connection, game_id: &str, place: &Place, cached: &Cached) -> Result<()> {
+  let fetched_at = cached.fetched_at.to_rfc3339();
+  conn.execute(
+    "INSERT OR REPLACE INTO geocode_cache (address, latitude, longitude, name, fetched_at) \
+     VALUES (?1, ?2, ?3, ?4, ?5)",
+    params![cached.address.to_lowercase(), place.latitude, place.longitude, place.name, fetched_at],
+  )?;
+  conn.execute(
+    "INSERT OR REPLACE INTO weather_cache (game_id, provider, address, date, forecast, fetched_at) \
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    params![
+      game_id,
+      cached.provider,
+      cached.address,
+      cached.date,
+      serde_json::to_string(&cached.forecast)?,
+      fetched_at
+    ],
+  )?;
+  Ok(())
+}
+
+/// The forecast as shown to the coach, in their units and with warnings.
+fn report(game: &Game, settings: &WeatherSettings, cached: Cached, stale: bool) -> GameWeather {
+  let forecast = &cached.forecast;
+  let metric = settings.units == Units::Metric;
+  let temperature = |f: f64| if metric { (f - 32.0) * 5.0 / 9.0 } else { f };
+  let speed = |mph: f64| if metric { mph * 1.609_344 } else { mph };
+  let (degrees, speed_unit) = if metric { ("°C", "km/h") } else { ("°F", "mph") };
+  let round = |v: f64| (v * 10.0).round() / 10.0;
+
+  let chance = forecast.precipitation_chance.unwrap_or(0);
+  let rainout_risk = chance >= RAINOUT_CHANCE
+    || forecast.precipitation_in.is_some_and(|inches| inches >= RAINOUT_INCHES);
+  let mut warnings = Vec::new();
+  if rainout_risk {
+    warnings.push(format!("Rain likely ({chance}% chance); check whether the game is still on"));
+  } else if chance >= SHOWERS_CHANCE {
+    warnings.push(format!("Showers possible ({chance}% chance)"));
+  }
+  let windiest = forecast.gusts_mph.unwrap_or(0.0).max(forecast.wind_mph);
+  if windiest >= WINDY_MPH {
+    warnings.push(format!("Windy, up to {:.0} {speed_unit}", speed(windiest)));
+  }
+  if forecast.high_f >= HOT_F {
+    warnings.push(format!(
+      "Hot, up to {:.0}{degrees}; plan water breaks",
+      temperature(forecast.high_f)
+    ));
+  }
+  if forecast.low_f <= COLD_F {
+    warnings.push(format!(
+      "Cold, down to {:.0}{degrees}; bring layers",
+      temperature(forecast.low_f)
+    ));
+  }
+
+  GameWeather {
+    game_id: game.id.clone(),
+    date: game.date.clone(),
+    provider: settings.provider,
+    address: cached.address,
+    place: forecast.place.clone(),
+    units: settings.units,
+    temperature_high: round(temperature(forecast.high_f)),
+    temperature_low: round(temperature(forecast.low_f)),
+    precipitation_chance: forecast.precipitation_chance,
+    precipitation: forecast
+      .precipitation_in
+      .map(|inches| if metric { round(inches * 25.4) } else { (inches * 100.0).round() / 100.0 }),
+    wind_speed: round(speed(forecast.wind_mph)),
+    wind_gusts: forecast.gusts_mph.map(|mph| round(speed(mph))),
+    rainout_risk,
+    warnings,
+    fetched_at: cached.fetched_at,
+    stale,
+  }
+}
+
+/// The forecast for a game's field on game day: temperature, rain and wind,
+/// with warnings for the pre-game checklist. Forecasts are cached for an
+/// hour; when a new one can't be fetched, the last one is returned with
+/// `stale` set.
+#[tauri::command]
+pub async fn get_game_weather(app: AppHandle, game_id: String) -> Result<GameWeather> {
+  let settings = app.state::<RwLock<AppSettings>>().read().unwrap().weather.clone();
+  let (game, address, place, cached) = {
+    let (app, settings) = (app.clone(), settings.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+      let db = app.state::<Database>();
+      let conn = db.conn();
+      let game = games::get(&conn, &game_id)?;
+      let address = address_for(&game, &settings)?;
+      let place = cached_place(&conn, &address)?;
+      let cached = cached_forecast(&conn, &game.id)?
+        .filter(|c| c.matches(settings.provider, &address, &game.date));
+      Ok::<_, Error>((game, address, place, cached))
+    })
+    .await??
+  };
+  if let Some(cached) = cached.clone().filter(|c| c.is_fresh(Utc::now())) {
+    return Ok(report(&game, &settings, cached, false));
+  }
+
+  let date = NaiveDate::parse_from_str(&game.date, "%Y-%m-%d")
+    .map_err(|_| Error::Validation(format!("Game date {} isn't a date", game.date)))?;
+  let days = (date - Local::now().date_naive()).num_days();
+  if days < 0 {
+    return Err(Error::Validation("Forecasts are only available for upcoming games".into()));
+  }
+  if days > settings.provider.days_ahead() {
+    return Err(Error::Validation(format!(
+      "The forecast for {} isn't out yet; check again within {} days of the game",
+      game.date,
+      settings.provider.days_ahead()
+    )));
+  }
+
+  let fetched = async {
+    let place = match place {
+      Some(place) => place,
+      None => geocode(&address).await?,
+    };
+    let forecast = fetch_forecast(settings.provider, &place, &game.date).await?;
+    Ok::<_, Error>((place, forecast))
+  }
+  .await;
+  let (place, forecast) = match (fetched, cached) {
+    (Ok(fetched), _) => fetched,
+    (Err(e), Some(cached)) => {
+      log::warn!("Using the cached forecast for game {}: {}", game.id, e);
+      return Ok(report(&game, &settings, cached, true));
+    }
+    (Err(e), None) => return Err(e),
+  };
+
+  let cached = Cached {
+    provider: settings.provider.as_str().to_string(),
+    address,
+    date: game.date.clone(),
+    forecast,
+    fetched_at: Utc::now(),
+  };
+  let saved = cached.clone();
+  let game_id = game.id.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    let db = app.state::<Database>();
+    let conn = db.conn();
+    store(&conn, &game_id, &place, &saved)
+  })
+  .await??;
+  Ok(report(&game, &settings, cached, false))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::db::games::ScheduledGame;
+  use crate::db::{open_in_memory, roster};
+  use serde_json::json;
+
+  #[test]
+  fn reads_forecasts_and_warns_about_rainouts() {
+    let open_meteo = json!({ "daily": {
+      "time": ["2026-04-10", "2026-04-11"],
+      "temperature_2m_max": [70.0, 58.3], "temperature_2m_min": [50.0, 44.1],
+      "precipitation_probability_max": [10, 80], "precipitation_sum": [0.0, 0.41],
+      "wind_speed_10m_max": [5.0, 12.4], "wind_gusts_10m_max": [9.0, 24.0],
+    }});
+    let rainy = read_open_meteo(&open_meteo, "2026-04-11", "North Park").unwrap();
+    assert_eq!((rainy.high_f, rainy.precipitation_chance), (58.3, Some(80)));
+    assert!(read_open_meteo(&open_meteo, "2026-04-12", "North Park").is_none());
+
+    let nws = json!({ "properties": { "periods": [
+      { "startTime": "2026-04-11T06:00:00-05:00", "temperature": 40, "temperatureUnit": "F",
+        "probabilityOfPrecipitation": { "value": 90 }, "windSpeed": "30 mph" },
+      { "startTime": "2026-04-11T13:00:00-05:00", "temperature": 72, "temperatureUnit": "F",
+        "probabilityOfPrecipitation": { "value": 20 }, "windSpeed": "5 to 10 mph" },
+      { "startTime": "2026-04-11T18:00:00-05:00", "temperature": 64, "temperatureUnit": "F",
+        "probabilityOfPrecipitation": { "value": null }, "windSpeed": "15 mph" },
+    ]}});
+    let hourly = read_nws_hourly(&nws, "2026-04-11", "North Park").unwrap();
+    // The early morning hour is outside playing hours
+    assert_eq!((hourly.high_f, hourly.low_f, hourly.wind_mph), (72.0, 64.0, 15.0));
+    assert_eq!(hourly.precipitation_chance, Some(20));
+
+    let game = Game {
+      id: "g1".into(),
+      team_id: "t1".into(),
+      date: "2026-04-11".into(),
+      opponent: "Tigers".into(),
+      home_away: "home".into(),
+      result: None,
+      score_us: None,
+      score_them: None,
+      status: "scheduled".into(),
+      notes: String::new(),
+      location: String::new(),
+      start_time: None,
+    };
+    let settings = WeatherSettings {
+      home_field: "Field 3, North Park, Springfield".into(),
+      units: Units::Metric,
+      ..Default::default()
+    };
+    let address = address_for(&game, &settings).unwrap();
+    assert_eq!(candidates(&address)[1..], ["North Park, Springfield", "Springfield"]);
+    let cached = Cached {
+      provider: "openMeteo".into(),
+      address,
+      date: game.date.clone(),
+      forecast: rainy,
+      fetched_at: Utc::now(),
+    };
+    let weather = report(&game, &settings, cached, false);
+    assert!(weather.rainout_risk);
+    assert_eq!((weather.temperature_high, weather.precipitation), (14.6, Some(10.4)));
+    assert_eq!(weather.warnings.len(), 3, "{:?}", weather.warnings);
+    assert!(weather.warnings[1].contains("39 km/h"));
+  }
+
+  #[test]
+  fn caches_forecasts_per_game() {
+    let conn = open_in_memory();
+    let team_id = roster::default_team_id(&conn).unwrap();
+    let game = ScheduledGame {
+      id: "g1".into(),
+      date: "2026-04-11".into(),
+      opponent: "Tigers".into(),
+      home_away: "away".into(),
+      score_us: None,
+      score_them: None,
+      notes: String::new(),
+      location: "Memorial Field, Springfield".into(),
+      start_time: None,
+    };
+    games::upsert_scheduled(&conn, &team_id, &game).unwrap();
+    let place = Place {
+      latitude: 39.8,
+      longitude: -89.65,
+      name: "Memorial Field".into(),
+    };
+    let cached = Cached {
+      provider: "nws".into(),
+      address: game.location.clone(),
+      date: game.date.clone(),
+      forecast: Forecast {
+        place: place.name.clone(),
+        high_f: 75.0,
+        low_f: 60.0,
+        precipitation_chance: Some(10),
+        precipitation_in: None,
+        wind_mph: 8.0,
+        gusts_mph: None,
+      },
+      fetched_at: "2026-04-10T12:00:00Z".parse().unwrap(),
+    };
+    store(&conn, "g1", &place, &cached).unwrap();
+
+    assert_eq!(cached_place(&conn, "memorial field, springfield").unwrap(), Some(place));
+    let saved = cached_forecast(&conn, "g1").unwrap().unwrap();
+    assert_eq!(saved, cached);
+    assert!(saved.matches(WeatherProvider::Nws, &game.location, "2026-04-11"));
+    // A rescheduled game or a new provider needs a new forecast
+    assert!(!saved.matches(WeatherProvider::Nws, &game.location, "2026-04-12"));
+    assert!(!saved.matches(WeatherProvider::OpenMeteo, &game.location, "2026-04-11"));
+    assert!(saved.is_fresh("2026-04-10T12:59:00Z".parse().unwrap()));
+    assert!(!saved.is_fresh("2026-04-10T13:01:00Z".parse().unwrap()));
+  }
+}
@@ -0,0 +1,290 @@
+//! Window size, position and maximized state, remembered per window label
+//! across launches in `window-state.json`.
+//!
+//! Geometry is kept in physical pixels along with the name of the monitor
+//! the window was on. If that monitor is no longer attached (a laptop away
+//! from its desk screen, or the TV the scoreboard went on) the window is
+//! centred on the primary monitor instead, shrunk to fit if need be, rather
+//! than opening somewhere it can't be seen.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+  Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow, Window, WindowEvent,
+};
+
+use crate::error::Result;
+use crate::game_mode::GameModeState;
+use crate::paths::AppPaths;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowState {
+  pub x: i32,
+  pub y: i32,
+  pub width: u32,
+  pub height: u32,
+  /// Position and size are from before it was maximized, so un-maximizing
+  /// goes back to them.
+  pub maximized: bool,
+  pub monitor: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+  x: i32,
+  y: i32,
+  width: u32,
+  height: u32,
+}
+
+impl Rect {
+  fn contains(&self, x: i32, y: i32) -> bool {
+    x >= self.x
+      && y >= self.y
+      && i64::from(x) < i64::from(self.x) + i64::from(self.width)
+      && i64::from(y) < i64::from(self.y) + i64::from(self.height)
+  }
+}
+
+/// A monitor's name and the part of it windows can use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Screen {
+  name: Option<String>,
+  area: Rect,
+}
+
+impl From<&Monitor> for Screen {
+  fn from(monitor: &Monitor) -> Self {
+    let area = monitor.work_area();
+    Self {
+      name: monitor.name().cloned(),
+      area: Rect {
+        x: area.position.x,
+        y: area.position.y,
+        width: area.size.width,
+        height: area.size.height,
+      },
+    }
+  }
+}
+
+#[derive(Default)]
+pub struct WindowStates(Mutex<BTreeMap<String, WindowState>>);
+
+impl WindowStates {
+  /// Reads saved states from `path`, starting fresh if it is missing or
+  /// unreadable.
+  pub fn load(path: &Path) -> Self {
+    let states = match std::fs::read_to_string(path) {
+      Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+        log::warn!("Ignoring invalid window state file {:?}: {}", path, e);
+        BTreeMap::new()
+      }),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+      Err(e) => {
+        log::warn!("Failed to read window state file {:?}: {}", path, e);
+        BTreeMap::new()
+      }
+    };
+    Self(Mutex::new(states))
+  }
+
+  fn save(&self, path: &Path) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(&*self.0.lock().unwrap())?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+  }
+}
+
+/// Where to put a window saved as `saved`, given the attached screens with
+/// the primary one first. `None` when there are no screens to go on.
+fn place(saved: &WindowState, screens: &[Screen]) -> Option<Rect> {
+  // Monitor names aren't available everywhere, so fall back to the screen
+  // under the middle of the title bar
+  let title_bar = (saved.x.saturating_add((saved.width / 2) as i32), saved.y.saturating_add(16));
+  let same = screens
+    .iter()
+    .find(|s| saved.monitor.is_some() && s.name == saved.monitor)
+    .or_else(|| screens.iter().find(|s| s.area.contains(title_bar.0, title_bar.1)));
+  let (screen, centre) = match same {
+    Some(screen) => (screen, false),
+    None => (screens.first()?, true),
+  };
+
+  let area = screen.area;
+  let width = saved.width.min(area.width);
+  let height = saved.height.min(area.height);
+  let (spare_x, spare_y) = ((area.width - width) as i32, (area.height - height) as i32);
+  let (x, y) = if centre {
+    (area.x + spare_x / 2, area.y + spare_y / 2)
+  } else {
+    (
+      saved.x.clamp(area.x, area.x + spare_x),
+      saved.y.clamp(area.y, area.y + spare_y),
+    )
+  };
+  Some(Rect {
+    x,
+    y,
+    width,
+    height,
+  })
+}
+
+/// Moves and sizes `window` to how it was last left. Call before showing
+/// it, so it doesn't jump.
+pub fn restore<R: Runtime>(window: &WebviewWindow<R>) {
+  let Some(states) = window.try_state::<WindowStates>() else {
+    return;
+  };
+  let Some(saved) = states.0.lock().unwrap().get(window.label()).cloned() else {
+    return;
+  };
+  let mut screens: Vec<Screen> = window
+    .available_monitors()
+    .unwrap_or_default()
+    .iter()
+    .map(Screen::from)
+    .collect();
+  if let Some(primary) = window.primary_monitor().ok().flatten().map(|m| Screen::from(&m)) {
+    screens.retain(|s| *s != primary);
+    screens.insert(0, primary);
+  }
+  let Some(rect) = place(&saved, &screens) else {
+    return;
+  };
+
+  let placed = window
+    .set_size(PhysicalSize::new(rect.width, rect.height))
+    .and_then(|_| window.set_position(PhysicalPosition::new(rect.x, rect.y)))
+    .and_then(|_| if saved.maximized { window.maximize() } else { Ok(()) });
+  if let Err(e) = placed {
+    log::warn!("Failed to restore {} window: {}", window.label(), e);
+  }
+}
+
+/// The window's geometry now, or `None` when it shouldn't be remembered:
+/// minimized, hidden in the tray, or docked in Game Mode.
+fn capture<R: Runtime>(window: &Window<R>, previous: Option<&WindowState>) -> Option<WindowState> {
+  if window.is_minimized().unwrap_or(true) || !window.is_visible().unwrap_or(false) {
+    return None;
+  }
+  let docked = window.label() == "main"
+    && window.try_state::<GameModeState>().is_some_and(|state| state.is_active());
+  if docked {
+    return None;
+  }
+  let maximized = window.is_maximized().ok()?;
+  let mut state = match previous {
+    Some(previous) if maximized => previous.clone(),
+    _ => {
+      let position = window.outer_position().ok()?;
+      let size = window.inner_size().ok()?;
+      WindowState {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized,
+        monitor: None,
+      }
+    }
+  };
+  state.maximized = maximized;
+  state.monitor = window.current_monitor().ok().flatten().and_then(|m| m.name().cloned());
+  Some(state)
+}
+
+/// Keeps track of windows as they move, and writes the file when one
+/// closes.
+pub fn handle_window_event<R: Runtime>(window: &Window<R>, event: &WindowEvent) {
+  let closing = matches!(event, WindowEvent::CloseRequested { .. });
+  if !closing && !matches!(event, WindowEvent::Moved(_) | WindowEvent::Resized(_)) {
+    return;
+  }
+  let Some(states) = window.try_state::<WindowStates>() else {
+    return;
+  };
+  {
+    let mut saved = states.0.lock().unwrap();
+    let label = window.label().to_string();
+    if let Some(state) = capture(window, saved.get(&label)) {
+      saved.insert(label, state);
+    }
+  }
+  if closing {
+    save(window.app_handle());
+  }
+}
+
+/// Writes every window's state to disk, e.g. on the way out.
+pub fn save<R: Runtime, M: Manager<R>>(manager: &M) {
+  let (Some(states), Some(paths)) =
+    (manager.try_state::<WindowStates>(), manager.try_state::<AppPaths>())
+  else {
+    return;
+  };
+  if let Err(e) = states.save(&paths.window_state) {
+    log::warn!("Failed to save window state: {}", e);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn screen(name: &str, x: i32, width: u32, height: u32) -> Screen {
+    Screen {
+      name: Some(name.into()),
+      area: Rect {
+        x,
+        y: 0,
+        width,
+        height,
+      },
+    }
+  }
+
+  #[test]
+  fn keeps_windows_on_an_attached_monitor() {
+    let laptop = screen("Built-in", 0, 1440, 900);
+    let tv = screen("TV", 1440, 1920, 1080);
+    let scoreboard = WindowState {
+      x: 1500,
+      y: 40,
+      width: 960,
+      height: 540,
+      maximized: true,
+      monitor: Some("TV".into()),
+    };
+
+    let at = |x, y, width, height| Rect {
+      x,
+      y,
+      width,
+      height,
+    };
+    let placed = place(&scoreboard, &[laptop.clone(), tv.clone()]);
+    assert_eq!(placed, Some(at(1500, 40, 960, 540)));
+
+    // Hanging off the edge is pulled back on
+    let hanging = WindowState {
+      x: 3000,
+      ..scoreboard.clone()
+    };
+    assert_eq!(place(&hanging, &[laptop.clone(), tv]), Some(at(2400, 40, 960, 540)));
+
+    // With the TV gone it's centred on the laptop, shrunk to fit
+    let big = WindowState {
+      width: 1600,
+      ..scoreboard
+    };
+    assert_eq!(place(&big, &[laptop]), Some(at(0, 180, 1440, 540)));
+    assert_eq!(place(&big, &[]), None);
+  }
+}
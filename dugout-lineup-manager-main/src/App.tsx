@@ -2,18 +2,47 @@ import { Toaster } from "@/components/ui/toaster";
 import { Toaster as Sonner } from "@/components/ui/sonner";
 import { TooltipProvider } from "@/components/ui/tooltip";
 import { QueryClient, QueryClientProvider } from "@tanstack/react-query";
+import { useCallback, useState } from "react";
 import { BrowserRouter, Routes, Route } from "react-router-dom";
 import { HelmetProvider } from "react-helmet-async";
 import { useEcosystemInit } from "@/hooks/useEcosystemInit";
 import { useAppUpdater } from "@/hooks/useAppUpdater";
+import { useTrayActions } from "@/hooks/useTrayActions";
+import { useDeepLinks } from "@/hooks/useDeepLinks";
+import { useFileDrops, DroppedFile } from "@/hooks/useFileDrops";
 import { UpdateBanner } from "@/components/UpdateBanner";
+import { ImportBackupDialog } from "@/components/ImportBackupDialog";
+import { CrashReportDialog } from "@/components/CrashReportDialog";
+import { BackendCompatibilityDialog } from "@/components/BackendCompatibilityDialog";
+import { KioskUnlock } from "@/components/KioskUnlock";
+import { useKiosk } from "@/hooks/useKiosk";
 import Index from "./pages/Index";
 import Games from "./pages/Games";
 import GameStats from "./pages/GameStats";
+import Presentation from "./pages/Presentation";
+import Scoreboard from "./pages/Scoreboard";
+import Splash from "./pages/Splash";
 import NotFound from "./pages/NotFound";
 
 const queryClient = new QueryClient();
 
+/**
+ * Routes tray menu quick actions, dugout:// links, and opened or dropped
+ * .dugout files; rendered inside the router
+ */
+const ShellEvents = () => {
+  const [importFile, setImportFile] = useState<string | null>(null);
+  const closeImport = useCallback(() => setImportFile(null), []);
+  const handleDrop = useCallback((file: DroppedFile) => {
+    if (file.kind === "backup") setImportFile(file.path);
+  }, []);
+
+  useTrayActions();
+  useDeepLinks(setImportFile);
+  useFileDrops(handleDrop);
+  return <ImportBackupDialog file={importFile} onClose={closeImport} />;
+};
+
 /**
  * Inner app component that uses hooks
  */
@@ -22,21 +51,32 @@ const AppContent = () => {
   useEcosystemInit();
 
   // Check for app updates
-  const { status: updateStatus, installUpdate, dismissUpdate, checkForUpdate } = useAppUpdater();
+  const { status: updateStatus, downloadUpdate, installUpdate, dismissUpdate, checkForUpdate } = useAppUpdater();
+  // Updates wait until kiosk mode is unlocked
+  const { locked: kiosk } = useKiosk();
 
   return (
     <>
-      <UpdateBanner
-        status={updateStatus}
-        onInstall={installUpdate}
-        onDismiss={dismissUpdate}
-        onRetry={checkForUpdate}
-      />
+      {!kiosk && (
+        <UpdateBanner
+          status={updateStatus}
+          onDownload={downloadUpdate}
+          onInstall={installUpdate}
+          onDismiss={dismissUpdate}
+          onRetry={checkForUpdate}
+        />
+      )}
+      <CrashReportDialog />
+      <BackendCompatibilityDialog />
+      <KioskUnlock />
       <BrowserRouter>
+        <ShellEvents />
         <Routes>
           <Route path="/" element={<Index />} />
           <Route path="/games" element={<Games />} />
           <Route path="/games/:gameId/stats" element={<GameStats />} />
+          <Route path="/presentation" element={<Presentation />} />
+          <Route path="/scoreboard" element={<Scoreboard />} />
           {/* ADD ALL CUSTOM ROUTES ABOVE THE CATCH-ALL "*" ROUTE */}
           <Route path="*" element={<NotFound />} />
         </Routes>
@@ -45,13 +85,18 @@ const AppContent = () => {
   );
 };
 
+// The scoreboard and splash windows render on their own, without the main
+// window's startup hooks (updater, ecosystem init).
+const isScoreboardWindow = window.location.pathname === "/scoreboard";
+const isSplashWindow = window.location.pathname === "/splash";
+
 const App = () => (
   <HelmetProvider>
     <QueryClientProvider client={queryClient}>
       <TooltipProvider>
         <Toaster />
         <Sonner />
-        <AppContent />
+        {isScoreboardWindow ? <Scoreboard /> : isSplashWindow ? <Splash /> : <AppContent />}
       </TooltipProvider>
     </QueryClientProvider>
   </HelmetProvider>
@@ -1,14 +1,27 @@
 /**
  * API Client for Dugout Baseball Coaching Backend
  * 
- * Connects to the local FastAPI backend running at http://localhost:8100
- * All operations are local-first with no authentication.
+ * Connects to the local FastAPI backend, by default at http://localhost:8100.
+ * Inside the desktop app requests go through the Rust shell instead (the
+ * `backend_request`/`stream_ai_request` commands), which adds the session
+ * token the backend requires there. All operations are local-first.
  * 
  * CHANGES: Created new file to handle all backend communication
  */
 
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
 // Backend configuration
-const API_BASE = "http://localhost:8100";
+const DEFAULT_API_BASE = "http://localhost:8100";
+
+/**
+ * Backend base URL outside the desktop app, where requests go straight to
+ * the backend rather than through the shell.
+ */
+function getApiBase(): Promise<string> {
+  return Promise.resolve(DEFAULT_API_BASE);
+}
 
 // Type definitions matching backend models
 export interface BackendPlayer {
@@ -204,13 +217,62 @@ class ApiError extends Error {
   }
 }
 
+/** Error returned by the shell's backend proxy commands. */
+interface ProxyError {
+  kind: 'invalidRequest' | 'unavailable' | 'incompatible' | 'timeout' | 'connection' | 'status';
+  message: string;
+  status?: number;
+  body?: unknown;
+}
+
+interface ProxyResponse {
+  status: number;
+  body: unknown;
+}
+
+/** Payload of the shell's `ai-stream:{requestId}` events. */
+type AIStreamEvent =
+  | { type: 'chunk'; text: string }
+  | { type: 'done' }
+  | { type: 'cancelled' }
+  | { type: 'error'; error: ProxyError };
+
+function proxyRequest(endpoint: string, options?: RequestInit) {
+  return {
+    method: options?.method ?? 'GET',
+    path: endpoint,
+    body: typeof options?.body === 'string' ? JSON.parse(options.body) : undefined,
+  };
+}
+
+function fromProxyError(error: unknown): Error {
+  const proxyError = error as ProxyError;
+  if (proxyError?.kind === 'status' && proxyError.status !== undefined) {
+    const body = typeof proxyError.body === 'string' ? proxyError.body : JSON.stringify(proxyError.body);
+    return new ApiError(proxyError.status, `API Error: ${proxyError.status} - ${body}`);
+  }
+  return new Error(proxyError?.message ?? String(error));
+}
+
 // Generic fetch wrapper with error handling
 async function fetchApi<T>(
   endpoint: string,
   options?: RequestInit
 ): Promise<T> {
+  if (isTauri()) {
+    try {
+      const response = await invoke<ProxyResponse>('backend_request', {
+        request: proxyRequest(endpoint, options),
+      });
+      return response.body as T;
+    } catch (error) {
+      throw fromProxyError(error);
+    }
+  }
+
+  const apiBase = await getApiBase();
   try {
-    const response = await fetch(`${API_BASE}${endpoint}`, {
+    const response = await fetch(`${apiBase}${endpoint}`, {
       ...options,
       headers: {
         'Content-Type': 'application/json',
@@ -238,7 +300,7 @@ async function fetchApi<T>(
     }
     // Network or other errors
     throw new Error(
-      `Failed to connect to backend at ${API_BASE}. Make sure the backend is running.`
+      `Failed to connect to backend at ${apiBase}. Make sure the backend is running.`
     );
   }
 }
@@ -400,8 +462,35 @@ export const lyraApi = {
     onError?: (err: unknown) => void,
     signal?: AbortSignal
   ): Promise<void> {
+    if (isTauri()) {
+      // Subscribe before starting so no tokens are missed
+      const requestId = crypto.randomUUID();
+      const unlisten = await listen<AIStreamEvent>(`ai-stream:${requestId}`, ({ payload }) => {
+        if (payload.type === 'chunk') onChunk(payload.text);
+      });
+      const cancel = () => invoke('cancel_ai_request', { requestId }).catch(() => {});
+      signal?.addEventListener('abort', cancel);
+      try {
+        await invoke('stream_ai_request', {
+          requestId,
+          request: proxyRequest('/lyra/chat/stream', {
+            method: 'POST',
+            body: JSON.stringify({ messages, model }),
+          }),
+        });
+        if (onDone) onDone();
+      } catch (err) {
+        if (onError) onError(fromProxyError(err));
+      } finally {
+        signal?.removeEventListener('abort', cancel);
+        unlisten();
+      }
+      return;
+    }
+
     try {
-      const response = await fetch(`${API_BASE}/lyra/chat/stream`, {
+      const apiBase = await getApiBase();
+      const response = await fetch(`${apiBase}/lyra/chat/stream`, {
         method: 'POST',
         headers: {
           'Content-Type': 'application/json',
@@ -0,0 +1,61 @@
+import { toast } from 'sonner';
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from '@/components/ui/alert-dialog';
+import { useBackendCompatibility } from '@/hooks/useBackendCompatibility';
+
+/**
+ * Explains why the backend can't be used when the version handshake fails,
+ * and offers to upgrade its data when that's all it takes. The shell
+ * refuses backend requests meanwhile, so this stays up until it's sorted.
+ */
+export function BackendCompatibilityDialog() {
+  const { compatibility, migrating, recheck, migrate } = useBackendCompatibility();
+
+  const state = compatibility?.state;
+  if (state !== 'needsMigration' && state !== 'incompatible') return null;
+
+  const run = async (event: React.MouseEvent, action: () => Promise<unknown>) => {
+    // Closing is up to the new result, not the button
+    event.preventDefault();
+    try {
+      await action();
+    } catch (error) {
+      toast.error(`${error}`);
+    }
+  };
+
+  return (
+    <AlertDialog open>
+      <AlertDialogContent>
+        <AlertDialogHeader>
+          <AlertDialogTitle>
+            {state === 'needsMigration' ? 'Your data needs upgrading' : "The backend can't be used"}
+          </AlertDialogTitle>
+          <AlertDialogDescription>
+            {`${compatibility.message}.`}
+            {state === 'needsMigration' &&
+              ' Everything is backed up before the upgrade, and AI features stay off until it has run.'}
+          </AlertDialogDescription>
+        </AlertDialogHeader>
+        <AlertDialogFooter>
+          <AlertDialogCancel disabled={migrating} onClick={(e) => run(e, recheck)}>
+            Check Again
+          </AlertDialogCancel>
+          {state === 'needsMigration' && (
+            <AlertDialogAction disabled={migrating} onClick={(e) => run(e, migrate)}>
+              {migrating ? 'Upgrading…' : 'Upgrade Data'}
+            </AlertDialogAction>
+          )}
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  );
+}
@@ -0,0 +1,67 @@
+import { useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { toast } from 'sonner';
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from '@/components/ui/alert-dialog';
+
+interface CrashReport {
+  id: string;
+  createdAt: string;
+  appVersion: string;
+  message: string;
+  location: string | null;
+}
+
+/**
+ * Offers crash reports left by earlier runs for review, one at a time.
+ * Sending opens a prefilled GitHub issue; either choice removes the report.
+ */
+export function CrashReportDialog() {
+  const [reports, setReports] = useState<CrashReport[]>([]);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+    invoke<CrashReport[]>('get_pending_crash_reports')
+      .then(setReports)
+      .catch((error) => console.warn('[Crash] Failed to load crash reports:', error));
+  }, []);
+
+  const report = reports[0];
+
+  const resolve = async (command: 'submit_crash_report' | 'dismiss_crash_report') => {
+    if (!report) return;
+    try {
+      await invoke(command, { id: report.id });
+    } catch (error) {
+      toast.error(`${error}`);
+    }
+    setReports((prev) => prev.slice(1));
+  };
+
+  return (
+    <AlertDialog open={!!report}>
+      <AlertDialogContent>
+        <AlertDialogHeader>
+          <AlertDialogTitle>Dugout closed unexpectedly</AlertDialogTitle>
+          <AlertDialogDescription>
+            {report
+              ? `Dugout ${report.appVersion} crashed on ${new Date(report.createdAt).toLocaleString()}: "${report.message}". Sending the report opens a GitHub issue with the error details; your team data is not included.`
+              : ''}
+          </AlertDialogDescription>
+        </AlertDialogHeader>
+        <AlertDialogFooter>
+          <AlertDialogCancel onClick={() => resolve('dismiss_crash_report')}>Dismiss</AlertDialogCancel>
+          <AlertDialogAction onClick={() => resolve('submit_crash_report')}>Send Report</AlertDialogAction>
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  );
+}
@@ -0,0 +1,81 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { toast } from 'sonner';
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from '@/components/ui/alert-dialog';
+
+interface BackupManifest {
+  appVersion: string;
+  createdAt: string;
+}
+
+interface ImportBackupDialogProps {
+  /** Archive to import; the dialog is open while this is set. */
+  file: string | null;
+  onClose: () => void;
+}
+
+/**
+ * Confirms restoring a .dugout archive opened from the OS, then reloads the
+ * app so every view picks up the restored data.
+ */
+export function ImportBackupDialog({ file, onClose }: ImportBackupDialogProps) {
+  const [manifest, setManifest] = useState<BackupManifest | null>(null);
+  const [importing, setImporting] = useState(false);
+
+  useEffect(() => {
+    setManifest(null);
+    if (!file) return;
+    invoke<BackupManifest>('inspect_backup', { path: file })
+      .then(setManifest)
+      .catch((error) => {
+        toast.error(`Can't open backup: ${error}`);
+        onClose();
+      });
+  }, [file, onClose]);
+
+  const handleImport = async () => {
+    if (!file) return;
+    setImporting(true);
+    try {
+      await invoke('import_backup', { path: file });
+      toast.success('Backup restored');
+      window.location.reload();
+    } catch (error) {
+      toast.error(`Restore failed: ${error}`);
+      setImporting(false);
+      onClose();
+    }
+  };
+
+  const fileName = file?.split(/[\\/]/).pop();
+
+  return (
+    <AlertDialog open={!!manifest} onOpenChange={(open) => !open && !importing && onClose()}>
+      <AlertDialogContent>
+        <AlertDialogHeader>
+          <AlertDialogTitle>Restore backup?</AlertDialogTitle>
+          <AlertDialogDescription>
+            {manifest
+              ? `Replace your current team data with ${fileName}, saved ${new Date(manifest.createdAt).toLocaleString()} by Dugout ${manifest.appVersion}? A snapshot of your current data is kept first.`
+              : ''}
+          </AlertDialogDescription>
+        </AlertDialogHeader>
+        <AlertDialogFooter>
+          <AlertDialogCancel disabled={importing}>Cancel</AlertDialogCancel>
+          <AlertDialogAction disabled={importing} onClick={handleImport}>
+            {importing ? 'Restoring...' : 'Restore'}
+          </AlertDialogAction>
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  );
+}
@@ -0,0 +1,88 @@
+import { useState } from 'react';
+import { isTauri } from '@tauri-apps/api/core';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+import { Lock } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { useKiosk } from '@/hooks/useKiosk';
+
+/**
+ * A lock button in the corner while kiosk mode is on, asking for the PIN
+ * that exits it. The PIN is checked by the shell. Only the main window is
+ * locked, so the scoreboard and presentation windows don't get one.
+ */
+export function KioskUnlock() {
+  const { locked, exit } = useKiosk();
+  const [open, setOpen] = useState(false);
+  const [pin, setPin] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  if (!locked || !isTauri() || getCurrentWindow().label !== 'main') return null;
+
+  const close = () => {
+    setOpen(false);
+    setPin('');
+    setError(null);
+  };
+
+  const submit = async (event: React.FormEvent) => {
+    event.preventDefault();
+    try {
+      await exit(pin);
+      close();
+    } catch (e) {
+      setError(`${e}`);
+      setPin('');
+    }
+  };
+
+  return (
+    <>
+      <button
+        type="button"
+        onClick={() => setOpen(true)}
+        aria-label="Exit kiosk mode"
+        className="fixed bottom-3 right-3 z-50 rounded-full p-2 text-muted-foreground/60 hover:bg-muted hover:text-foreground"
+      >
+        <Lock className="h-4 w-4" />
+      </button>
+      <Dialog open={open} onOpenChange={(next) => (next ? setOpen(true) : close())}>
+        <DialogContent>
+          <form onSubmit={submit}>
+            <DialogHeader>
+              <DialogTitle>Exit kiosk mode</DialogTitle>
+              <DialogDescription>Enter the PIN to unlock this computer until Dugout restarts.</DialogDescription>
+            </DialogHeader>
+            <Input
+              type="password"
+              inputMode="numeric"
+              autoComplete="off"
+              autoFocus
+              value={pin}
+              onChange={(e) => setPin(e.target.value)}
+              className="my-4"
+              aria-label="PIN"
+            />
+            {error && <p className="mb-4 text-sm text-destructive">{error}</p>}
+            <DialogFooter>
+              <Button type="button" variant="outline" onClick={close}>
+                Cancel
+              </Button>
+              <Button type="submit" disabled={!pin}>
+                Unlock
+              </Button>
+            </DialogFooter>
+          </form>
+        </DialogContent>
+      </Dialog>
+    </>
+  );
+}
@@ -7,6 +7,7 @@ import { UpdateBanner } from "./UpdateBanner";
 
 describe("UpdateBanner", () => {
   it("handles critical updater state transitions and actions", () => {
+    const onDownload = vi.fn();
     const onInstall = vi.fn();
     const onDismiss = vi.fn();
     const onRetry = vi.fn();
@@ -19,6 +20,7 @@ describe("UpdateBanner", () => {
           downloading: false,
           progress: 0,
         }}
+        onDownload={onDownload}
         onInstall={onInstall}
         onDismiss={onDismiss}
         onRetry={onRetry}
@@ -37,6 +39,7 @@ describe("UpdateBanner", () => {
           version: "1.2.3",
           notes: "Important stability fixes and lineup sync improvements.",
         }}
+        onDownload={onDownload}
         onInstall={onInstall}
         onDismiss={onDismiss}
         onRetry={onRetry}
@@ -46,7 +49,7 @@ describe("UpdateBanner", () => {
     expect(screen.getByText(/Dugout v1.2.3/i)).toBeTruthy();
     fireEvent.click(screen.getByRole("button", { name: "Update Now" }));
     fireEvent.click(screen.getByRole("button", { name: "Later" }));
-    expect(onInstall).toHaveBeenCalledTimes(1);
+    expect(onDownload).toHaveBeenCalledTimes(1);
     expect(onDismiss).toHaveBeenCalledTimes(1);
 
     rerender(
@@ -58,6 +61,7 @@ describe("UpdateBanner", () => {
           progress: 25,
           version: "1.2.3",
         }}
+        onDownload={onDownload}
         onInstall={onInstall}
         onDismiss={onDismiss}
         onRetry={onRetry}
@@ -68,6 +72,27 @@ describe("UpdateBanner", () => {
     expect(screen.queryByRole("button", { name: "Update Now" })).toBeNull();
     expect(screen.queryByRole("button", { name: "Later" })).toBeNull();
 
+    rerender(
+      <UpdateBanner
+        status={{
+          checking: false,
+          available: false,
+          downloading: false,
+          ready: true,
+          progress: 100,
+          version: "1.2.3",
+        }}
+        onDownload={onDownload}
+        onInstall={onInstall}
+        onDismiss={onDismiss}
+        onRetry={onRetry}
+      />,
+    );
+
+    expect(screen.getByText(/ready to install/i)).toBeTruthy();
+    fireEvent.click(screen.getByRole("button", { name: "Install & Relaunch" }));
+    expect(onInstall).toHaveBeenCalledTimes(1);
+
     rerender(
       <UpdateBanner
         status={{
@@ -77,6 +102,7 @@ describe("UpdateBanner", () => {
           progress: 0,
           error: "Temporary updater outage",
         }}
+        onDownload={onDownload}
         onInstall={onInstall}
         onDismiss={onDismiss}
         onRetry={onRetry}
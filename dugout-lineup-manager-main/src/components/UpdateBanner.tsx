@@ -2,6 +2,7 @@ import { UpdateStatus } from '@/hooks/useAppUpdater';
 
 interface UpdateBannerProps {
     status: UpdateStatus;
+    onDownload: () => void;
     onInstall: () => void;
     onDismiss: () => void;
     onRetry: () => void;
@@ -9,11 +10,12 @@ interface UpdateBannerProps {
 
 /**
  * A non-intrusive banner shown at the top of the app when an update is available.
- * Shows download progress when the user clicks "Update Now".
+ * Shows download progress when the user clicks "Update Now", then offers
+ * "Install & Relaunch" once the download is verified.
  */
-export function UpdateBanner({ status, onInstall, onDismiss, onRetry }: UpdateBannerProps) {
-    // Show only when there is an available update, an active download, or an updater error
-    if (!status.available && !status.downloading && !status.error) return null;
+export function UpdateBanner({ status, onDownload, onInstall, onDismiss, onRetry }: UpdateBannerProps) {
+    // Show only when there is an available or downloaded update, an active download, or an updater error
+    if (!status.available && !status.downloading && !status.ready && !status.error) return null;
 
     return (
         <div
@@ -68,11 +70,15 @@ export function UpdateBanner({ status, onInstall, onDismiss, onRetry }: UpdateBa
                             />
                         </div>
                     </div>
+                ) : status.ready ? (
+                    <span>
+                        <strong>Dugout v{status.version}</strong> is ready to install.
+                    </span>
                 ) : status.available ? (
                     <span>
                         <strong>Dugout v{status.version}</strong> is available!
                         {status.notes && (
-                            <span style={{ opacity: 0.85, marginLeft: '8px' }}>
+                            <span title={status.notes} style={{ opacity: 0.85, marginLeft: '8px' }}>
                                 — {status.notes.length > 80 ? status.notes.substring(0, 80) + '...' : status.notes}
                             </span>
                         )}
@@ -90,7 +96,7 @@ export function UpdateBanner({ status, onInstall, onDismiss, onRetry }: UpdateBa
             {/* Actions */}
             {!status.downloading && (
                 <div style={{ display: 'flex', gap: '8px', flexShrink: 0 }}>
-                    {status.available ? (
+                    {status.ready ? (
                         <button
                             onClick={onInstall}
                             style={{
@@ -106,6 +112,25 @@ export function UpdateBanner({ status, onInstall, onDismiss, onRetry }: UpdateBa
                             }}
                             onMouseEnter={(e) => (e.currentTarget.style.opacity = '0.9')}
                             onMouseLeave={(e) => (e.currentTarget.style.opacity = '1')}
+                        >
+                            Install &amp; Relaunch
+                        </button>
+                    ) : status.available ? (
+                        <button
+                            onClick={onDownload}
+                            style={{
+                                background: 'white',
+                                color: '#1a5fb4',
+                                border: 'none',
+                                borderRadius: '6px',
+                                padding: '6px 16px',
+                                fontSize: '13px',
+                                fontWeight: 600,
+                                cursor: 'pointer',
+                                transition: 'opacity 0.2s',
+                            }}
+                            onMouseEnter={(e) => (e.currentTarget.style.opacity = '0.9')}
+                            onMouseLeave={(e) => (e.currentTarget.style.opacity = '1')}
                         >
                             Update Now
                         </button>
@@ -3,6 +3,7 @@ import { Settings, Save, CheckCircle, AlertCircle, RefreshCw } from 'lucide-reac
 import { useAIStore } from '@/store/aiStore';
 import { healthApi, settingsApi } from '@/api/client';
 import { cn } from '@/lib/utils';
+import { useApiKeys, KeyProvider } from '@/hooks/useApiKeys';
 import { AIProvider, AIConfig, AIMode } from '@/types/ai';
 import {
     Select,
@@ -32,6 +33,7 @@ export function AISettingsPanel() {
     const [availableOllamaModels, setAvailableOllamaModels] = useState<string[]>([]);
     const [modelsLoading, setModelsLoading] = useState(false);
     const [modelsError, setModelsError] = useState<string | null>(null);
+    const apiKeys = useApiKeys();
 
     // Sync local state when store changes
     useEffect(() => {
@@ -120,6 +122,24 @@ export function AISettingsPanel() {
         setStatus('saving');
         setErrorMessage(null);
         try {
+            // In the desktop app keys go to the OS keychain, never to
+            // localStorage or the backend config
+            let openaiKeyToPersist = localOpenaiKey;
+            let anthropicKeyToPersist = localAnthropicKey;
+            if (apiKeys.supported) {
+                const typed: [KeyProvider, string][] = [
+                    ['openai', localOpenaiKey.trim()],
+                    ['anthropic', localAnthropicKey.trim()],
+                ];
+                for (const [keyProvider, key] of typed) {
+                    if (key) await apiKeys.saveKey(keyProvider, key);
+                }
+                openaiKeyToPersist = '';
+                anthropicKeyToPersist = '';
+                setLocalOpenaiKey('');
+                setLocalAnthropicKey('');
+            }
+
             // Update store (frontend persistence)
             updateSettings({
                 mode: localMode,
@@ -127,8 +147,8 @@ export function AISettingsPanel() {
                 cloudProvider: localCloudProvider,
                 ollamaUrl: localOllamaUrl,
                 preferredModel: localModel,
-                openaiKey: localOpenaiKey,
-                anthropicKey: localAnthropicKey
+                openaiKey: openaiKeyToPersist,
+                anthropicKey: anthropicKeyToPersist
             });
             setTheme(localTheme);
 
@@ -139,8 +159,8 @@ export function AISettingsPanel() {
                 cloudProvider: localCloudProvider,
                 ollamaUrl: localOllamaUrl,
                 preferredModel: localModel,
-                openaiKey: localOpenaiKey,
-                anthropicKey: localAnthropicKey
+                openaiKey: openaiKeyToPersist,
+                anthropicKey: anthropicKeyToPersist
             });
 
             setStatus('saved');
@@ -152,6 +172,32 @@ export function AISettingsPanel() {
         }
     };
 
+    const handleRemoveKey = async (keyProvider: KeyProvider) => {
+        try {
+            await apiKeys.deleteKey(keyProvider);
+        } catch (err) {
+            console.error('Failed to remove API key:', err);
+            setErrorMessage('Failed to remove API key from keychain');
+        }
+    };
+
+    const renderKeychainStatus = (keyProvider: KeyProvider) => {
+        const keyStatus = apiKeys.status[keyProvider];
+        if (!apiKeys.supported || !keyStatus?.set) return null;
+        return (
+            <div className="flex items-center justify-between text-xs text-muted-foreground">
+                <span>Saved in your system keychain ({keyStatus.hint})</span>
+                <button
+                    type="button"
+                    onClick={() => void handleRemoveKey(keyProvider)}
+                    className="text-destructive hover:underline"
+                >
+                    Remove
+                </button>
+            </div>
+        );
+    };
+
     return (
         <div className="p-4 space-y-6 bg-card text-card-foreground rounded-lg border shadow-sm">
             <div className="flex items-center gap-2 border-b pb-3">
@@ -254,11 +300,14 @@ export function AISettingsPanel() {
                                 value={localOpenaiKey}
                                 onChange={(e) => setLocalOpenaiKey(e.target.value)}
                                 className="flex w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background placeholder:text-muted-foreground focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
-                                placeholder="sk-..."
+                                placeholder={apiKeys.status.openai?.set ? 'Enter a new key to replace it' : 'sk-...'}
                             />
                         </div>
+                        {renderKeychainStatus('openai')}
                         <p className="text-xs text-muted-foreground">
-                            Stored locally. Used for direct API calls.
+                            {apiKeys.supported
+                                ? 'Stored in your system keychain. Used for direct API calls.'
+                                : 'Stored locally. Used for direct API calls.'}
                         </p>
                     </div>
                 )}
@@ -272,9 +321,10 @@ export function AISettingsPanel() {
                                 value={localAnthropicKey}
                                 onChange={(e) => setLocalAnthropicKey(e.target.value)}
                                 className="flex w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background placeholder:text-muted-foreground focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
-                                placeholder="sk-ant-..."
+                                placeholder={apiKeys.status.anthropic?.set ? 'Enter a new key to replace it' : 'sk-ant-...'}
                             />
                         </div>
+                        {renderKeychainStatus('anthropic')}
                     </div>
                 )}
 
@@ -8,6 +8,8 @@ import { Player, LineupSlot, FieldPosition } from '@/types/player';
 import { healthApi, lyraApi, settingsApi, gamesApi } from '@/api/client';
 import { mapFrontendPlayerToBackend, mapFrontendLineupToBackend, mapFrontendFieldToBackend } from '@/api/mappers';
 import { useAIStore } from '@/store/aiStore';
+import { useAIQueue } from '@/hooks/useAIQueue';
+import { useKiosk } from '@/hooks/useKiosk';
 import { AISettingsPanel } from './AISettingsPanel';
 import { ChatMessage } from '@/types/ai';
 
@@ -24,6 +26,13 @@ interface LyraPanelProps {
   players: Player[];
 }
 
+/** The question a queued chat request was asking. */
+function queuedQuestion(payload: unknown): string | null {
+  const history = (payload as { messages?: ChatMessage[] } | null)?.messages ?? [];
+  const question = [...history].reverse().find((message) => message.role === 'user');
+  return question?.content ?? null;
+}
+
 const examplePrompts = [
   "What stands out in this lineup?",
   "What tradeoffs am I making here?",
@@ -46,7 +55,12 @@ export function LyraPanel({ lineup, fieldPositions, players }: LyraPanelProps) {
   const [input, setInput] = useState('');
   const [isTyping, setIsTyping] = useState(false);
   const [error, setError] = useState<string | null>(null);
-  const [showSettings, setShowSettings] = useState(false);
+  const [showSettingsPanel, setShowSettingsPanel] = useState(false);
+  // Settings are off limits on a locked-down check-in laptop
+  const { locked: kiosk } = useKiosk();
+  const showSettings = showSettingsPanel && !kiosk;
+  const aiQueue = useAIQueue();
+  const { queue, dismiss: dismissQueued } = aiQueue;
 
   const scrollRef = useRef<HTMLDivElement>(null);
   const abortControllerRef = useRef<AbortController | null>(null);
@@ -60,6 +74,33 @@ export function LyraPanel({ lineup, fieldPositions, players }: LyraPanelProps) {
     }
   }, [messages, isTyping]);
 
+  // Answers to questions asked while offline, possibly in an earlier session
+  useEffect(() => {
+    const settled = queue.filter((request) => request.kind === 'chat' && request.status !== 'pending');
+    if (settled.length === 0) return;
+
+    setMessages((previous) => {
+      let next = previous;
+      for (const request of settled) {
+        const content = request.status === 'done'
+          ? request.response ?? ''
+          : `Couldn't get an answer to this question: ${request.lastError ?? 'unknown error'}`;
+        if (next.some((message) => message.id === request.id)) {
+          next = next.map((message) => (message.id === request.id ? { ...message, content } : message));
+          continue;
+        }
+        const question = queuedQuestion(request.payload);
+        const restored: Message[] = question
+          ? [{ id: `${request.id}-question`, role: 'user', content: question, timestamp: new Date(request.createdAt) }]
+          : [];
+        restored.push({ id: request.id, role: 'lyra', content, timestamp: new Date(request.updatedAt) });
+        next = [...next, ...restored];
+      }
+      return next;
+    });
+    settled.forEach((request) => void dismissQueued(request.id));
+  }, [queue, dismissQueued]);
+
   // Abort active generation when component unmounts
   useEffect(() => {
     return () => {
@@ -200,6 +241,24 @@ export function LyraPanel({ lineup, fieldPositions, players }: LyraPanelProps) {
     const conversationHistory = await buildConversationHistory(nextMessages);
     let modelToUse = preferredModel;
 
+    // No connection: keep the question and answer it once we're back online
+    if (mode === 'cloud' && aiQueue.supported && !aiQueue.online) {
+      try {
+        const queued = await aiQueue.enqueue('chat', { messages: conversationHistory, model: modelToUse });
+        setMessages(prev => [...prev, {
+          id: queued.id,
+          role: 'lyra',
+          content: "_You're offline. Lyra will answer this as soon as the connection is back._",
+          timestamp: new Date()
+        }]);
+      } catch (err) {
+        console.error('Failed to queue question:', err);
+        setError("You're offline and the question couldn't be saved for later.");
+      }
+      setIsTyping(false);
+      return;
+    }
+
     // Ensure local Ollama mode always uses an installed model.
     if (mode === 'local' && provider === 'ollama') {
       try {
@@ -341,6 +400,11 @@ export function LyraPanel({ lineup, fieldPositions, players }: LyraPanelProps) {
               <span className="text-[10px] bg-lyra-border/30 px-1.5 py-0.5 rounded text-lyra-foreground/70">
                 {provider}
               </span>
+              {mode === 'cloud' && !aiQueue.online && (
+                <span className="text-[9px] px-1.5 py-0.5 rounded-full border uppercase tracking-wider font-bold bg-amber-500/10 text-amber-500 border-amber-500/20">
+                  offline
+                </span>
+              )}
             </div>
           </div>
           <p className="text-[10px] text-lyra-foreground/60">
@@ -358,19 +422,21 @@ export function LyraPanel({ lineup, fieldPositions, players }: LyraPanelProps) {
           >
             <Trash2 className="w-4 h-4" />
           </button>
-          <button
-            type="button"
-            onClick={() => setShowSettings(!showSettings)}
-            aria-label={showSettings ? "Hide AI settings" : "Show AI settings"}
-            aria-pressed={showSettings}
-            className={cn(
-              "p-1.5 rounded-md transition-colors hover:bg-lyra-muted focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring",
-              showSettings && "bg-lyra-muted text-gold"
-            )}
-            title="AI settings"
-          >
-            <SettingsIcon className="w-4 h-4" />
-          </button>
+          {!kiosk && (
+            <button
+              type="button"
+              onClick={() => setShowSettingsPanel(!showSettings)}
+              aria-label={showSettings ? "Hide AI settings" : "Show AI settings"}
+              aria-pressed={showSettings}
+              className={cn(
+                "p-1.5 rounded-md transition-colors hover:bg-lyra-muted focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring",
+                showSettings && "bg-lyra-muted text-gold"
+              )}
+              title="AI settings"
+            >
+              <SettingsIcon className="w-4 h-4" />
+            </button>
+          )}
         </div>
       </div>
 
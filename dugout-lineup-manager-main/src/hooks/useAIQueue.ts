@@ -0,0 +1,84 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type QueuedKind = 'chat' | 'analyze';
+
+export interface QueuedRequest {
+    id: string;
+    kind: QueuedKind;
+    payload: unknown;
+    status: 'pending' | 'done' | 'failed';
+    attempts: number;
+    response: string | null;
+    lastError: string | null;
+    createdAt: string;
+    updatedAt: string;
+}
+
+interface NetworkStatus {
+    online: boolean;
+}
+
+/**
+ * Connectivity and the offline AI queue kept by the desktop shell.
+ *
+ * While offline, cloud AI requests can be queued with `enqueue`; the shell
+ * sends them when the network returns and updates `queue` with the
+ * responses. In a plain browser `supported` is false and `online` follows
+ * `navigator.onLine`.
+ */
+export function useAIQueue() {
+    const supported = isTauri();
+    const [online, setOnline] = useState(() => (typeof navigator === 'undefined' ? true : navigator.onLine));
+    const [queue, setQueue] = useState<QueuedRequest[]>([]);
+
+    useEffect(() => {
+        if (supported) return;
+        const update = () => setOnline(navigator.onLine);
+        window.addEventListener('online', update);
+        window.addEventListener('offline', update);
+        return () => {
+            window.removeEventListener('online', update);
+            window.removeEventListener('offline', update);
+        };
+    }, [supported]);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<NetworkStatus>('get_network_status')
+            .then((status) => !disposed && setOnline(status.online))
+            .catch((err) => console.warn('[AIQueue] Failed to read network status:', err));
+        invoke<QueuedRequest[]>('list_ai_queue')
+            .then((items) => !disposed && setQueue(items))
+            .catch((err) => console.warn('[AIQueue] Failed to load queue:', err));
+
+        const unlistenStatus = listen<NetworkStatus>('network-status', ({ payload }) => setOnline(payload.online));
+        const unlistenQueue = listen<QueuedRequest[]>('ai-queue-updated', ({ payload }) => setQueue(payload));
+
+        return () => {
+            disposed = true;
+            unlistenStatus.then((fn) => fn());
+            unlistenQueue.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const enqueue = useCallback(async (kind: QueuedKind, payload: object) => {
+        const request = await invoke<QueuedRequest>('enqueue_ai_request', { kind, payload });
+        setQueue((prev) => [...prev, request]);
+        return request;
+    }, []);
+
+    const dismiss = useCallback(async (id: string) => {
+        setQueue((prev) => prev.filter((request) => request.id !== id));
+        try {
+            await invoke('dismiss_ai_request', { id });
+        } catch (err) {
+            console.warn('[AIQueue] Failed to dismiss request:', err);
+        }
+    }, []);
+
+    return { supported, online, queue, enqueue, dismiss };
+}
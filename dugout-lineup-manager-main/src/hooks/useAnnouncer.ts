@@ -0,0 +1,49 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface AnnouncerStatus {
+    speaking: string | null;
+    queued: string[];
+    /** Why the last line couldn't be spoken. The queue is dropped with it. */
+    error: string | null;
+}
+
+const IDLE: AnnouncerStatus = { speaking: null, queued: [], error: null };
+
+/**
+ * Reads the next three batters ("Now batting, number 12, ...") aloud in the
+ * OS's voice, over the speaker or a PA plugged into it. Announcements wait
+ * their turn behind anything still being read. Only available in the
+ * desktop app.
+ */
+export function useAnnouncer() {
+    const supported = isTauri();
+    const [status, setStatus] = useState<AnnouncerStatus>(IDLE);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<AnnouncerStatus>('get_announcer_status')
+            .then((current) => !disposed && setStatus(current))
+            .catch(() => {});
+
+        const unlisten = listen<AnnouncerStatus>('announcer', ({ payload }) => setStatus(payload));
+        return () => {
+            disposed = true;
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const announceBattingOrder = useCallback(
+        (gameId: string) => invoke<AnnouncerStatus>('announce_batting_order', { gameId }),
+        [],
+    );
+
+    const skip = useCallback(() => invoke<AnnouncerStatus>('skip_announcement'), []);
+
+    const stop = useCallback(() => invoke<AnnouncerStatus>('stop_announcements'), []);
+
+    return { supported, status, announceBattingOrder, skip, stop };
+}
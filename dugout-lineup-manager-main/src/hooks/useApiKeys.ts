@@ -0,0 +1,47 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export type KeyProvider = 'openai' | 'anthropic';
+
+export interface ApiKeyStatus {
+    provider: KeyProvider;
+    set: boolean;
+    hint: string | null;
+}
+
+/**
+ * Provider API keys held in the OS keychain by the desktop shell.
+ *
+ * `supported` is false in a plain browser, where keys stay in the AI store
+ * and go to the backend config as before.
+ */
+export function useApiKeys() {
+    const supported = isTauri();
+    const [status, setStatus] = useState<Partial<Record<KeyProvider, ApiKeyStatus>>>({});
+
+    const refresh = useCallback(async () => {
+        if (!supported) return;
+        try {
+            const statuses = await invoke<ApiKeyStatus[]>('get_api_key_status');
+            setStatus(Object.fromEntries(statuses.map((s) => [s.provider, s])));
+        } catch (err) {
+            console.warn('[ApiKeys] Failed to read keychain:', err);
+        }
+    }, [supported]);
+
+    useEffect(() => {
+        void refresh();
+    }, [refresh]);
+
+    const saveKey = useCallback(async (provider: KeyProvider, key: string) => {
+        const saved = await invoke<ApiKeyStatus>('set_api_key', { provider, key });
+        setStatus((prev) => ({ ...prev, [provider]: saved }));
+    }, []);
+
+    const deleteKey = useCallback(async (provider: KeyProvider) => {
+        await invoke('delete_api_key', { provider });
+        setStatus((prev) => ({ ...prev, [provider]: { provider, set: false, hint: null } }));
+    }, []);
+
+    return { supported, status, saveKey, deleteKey };
+}
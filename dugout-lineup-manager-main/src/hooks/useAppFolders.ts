@@ -0,0 +1,40 @@
+import { useCallback } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+type FolderCommand = 'reveal_data_dir' | 'open_logs_dir' | 'open_backups_dir';
+
+export interface DataLocation {
+    data: string;
+    backups: string;
+}
+
+/**
+ * Opens the app's data, log and backups folders in the OS file manager.
+ * Each resolves to the folder's path, e.g. to show or copy for support.
+ * `moveDataDir` moves the data and backups into `data/` and `backups/`
+ * under a chosen folder, restarting the AI backend on the way. Only
+ * available in the desktop app.
+ */
+export function useAppFolders() {
+    const supported = isTauri();
+
+    const open = useCallback(async (command: FolderCommand) => {
+        try {
+            return await invoke<string>(command);
+        } catch (err) {
+            console.warn('[AppFolders] Failed to open folder:', err);
+            throw err;
+        }
+    }, []);
+
+    const revealDataDir = useCallback(() => open('reveal_data_dir'), [open]);
+    const openLogsDir = useCallback(() => open('open_logs_dir'), [open]);
+    const openBackupsDir = useCallback(() => open('open_backups_dir'), [open]);
+
+    const moveDataDir = useCallback(
+        (newPath: string) => invoke<DataLocation>('set_data_dir', { newPath }),
+        [],
+    );
+
+    return { supported, revealDataDir, openLogsDir, openBackupsDir, moveDataDir };
+}
@@ -0,0 +1,56 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type ThemePreference = 'system' | 'light' | 'dark';
+export type Appearance = 'light' | 'dark';
+
+export interface ThemeStatus {
+    preference: ThemePreference;
+    /** The OS appearance, whatever the app is showing. */
+    system: Appearance;
+    /** What the app is showing. */
+    effective: Appearance;
+}
+
+function effectiveFor(preference: ThemePreference, system: Appearance): Appearance {
+    return preference === 'system' ? system : preference;
+}
+
+/**
+ * The light/dark theme saved in the desktop shell's settings, following the
+ * OS as it switches when the preference is `system`. Only available in the
+ * desktop app; elsewhere `status` stays `null`.
+ */
+export function useAppTheme() {
+    const supported = isTauri();
+    const [status, setStatus] = useState<ThemeStatus | null>(null);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        Promise.all([
+            invoke<{ theme: ThemePreference }>('get_settings'),
+            invoke<Appearance>('get_system_theme'),
+        ])
+            .then(([{ theme }, system]) => {
+                if (disposed) return;
+                setStatus({ preference: theme, system, effective: effectiveFor(theme, system) });
+            })
+            .catch((err) => console.warn('[Theme] Failed to read theme:', err));
+
+        const unlisten = listen<ThemeStatus>('theme-changed', ({ payload }) => setStatus(payload));
+
+        return () => {
+            disposed = true;
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const setTheme = useCallback(async (theme: ThemePreference) => {
+        setStatus(await invoke<ThemeStatus>('set_theme', { theme }));
+    }, []);
+
+    return { supported, status, setTheme };
+}
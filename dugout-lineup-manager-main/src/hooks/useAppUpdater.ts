@@ -1,20 +1,36 @@
 import { useEffect, useState, useCallback, useRef } from 'react';
-import { check, Update } from '@tauri-apps/plugin-updater';
 import { relaunch } from '@tauri-apps/plugin-process';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 export interface UpdateStatus {
     checking: boolean;
     available: boolean;
     downloading: boolean;
     progress: number; // 0-100
+    /** Downloaded and waiting for "Install & Relaunch". */
+    ready?: boolean;
     version?: string;
     notes?: string;
     error?: string;
 }
 
+interface DownloadProgress {
+    bytes: number;
+    total: number | null;
+}
+
+interface UpdateInfo {
+    version: string;
+    currentVersion: string;
+    notes: string | null;
+    date: string | null;
+}
+
 /**
  * Hook to check for app updates on startup and provide update controls.
- * Uses the Tauri updater plugin to check GitHub Releases for new versions.
+ * Checks GitHub Releases on the channel selected in settings (see
+ * `set_update_channel`).
  */
 export function useAppUpdater() {
     const [status, setStatus] = useState<UpdateStatus>({
@@ -23,8 +39,9 @@ export function useAppUpdater() {
         downloading: false,
         progress: 0,
     });
-    const updateRef = useRef<Update | null>(null);
+    const updateRef = useRef<UpdateInfo | null>(null);
     const hasChecked = useRef(false);
+    const [rollbackVersion, setRollbackVersion] = useState<string | null>(null);
 
     /**
      * Check for available updates
@@ -33,7 +50,7 @@ export function useAppUpdater() {
         try {
             setStatus((prev) => ({ ...prev, checking: true, error: undefined }));
 
-            const update = await check();
+            const update = await invoke<UpdateInfo | null>('check_for_updates');
 
             if (update) {
                 updateRef.current = update;
@@ -43,7 +60,7 @@ export function useAppUpdater() {
                     downloading: false,
                     progress: 0,
                     version: update.version,
-                    notes: update.body ?? undefined,
+                    notes: update.notes ?? undefined,
                 });
                 console.log(`[Updater] Update available: v${update.version}`);
             } else {
@@ -62,51 +79,81 @@ export function useAppUpdater() {
                 available: false,
                 downloading: false,
                 progress: 0,
-                error: err instanceof Error ? err.message : 'Failed to check for updates',
+                error: err instanceof Error ? err.message : String(err),
             });
         }
     }, []);
 
     /**
-     * Download and install the available update, then relaunch
+     * Download the available update, showing progress, without installing it
      */
-    const installUpdate = useCallback(async () => {
+    const downloadUpdate = useCallback(async () => {
         const update = updateRef.current;
         if (!update) return;
 
         try {
             setStatus((prev) => ({ ...prev, downloading: true, progress: 0, error: undefined }));
 
-            let downloaded = 0;
-            let contentLength = 0;
-
-            await update.downloadAndInstall((event) => {
-                switch (event.event) {
-                    case 'Started':
-                        contentLength = event.data.contentLength ?? 0;
-                        console.log(`[Updater] Downloading ${contentLength} bytes`);
-                        break;
-                    case 'Progress': {
-                        downloaded += event.data.chunkLength;
-                        const pct = contentLength > 0 ? Math.round((downloaded / contentLength) * 100) : 0;
-                        setStatus((prev) => ({ ...prev, progress: pct }));
-                        break;
-                    }
-                    case 'Finished':
-                        setStatus((prev) => ({ ...prev, progress: 100 }));
-                        console.log('[Updater] Download complete');
-                        break;
-                }
+            try {
+                await invoke('create_backup', { kind: 'preUpdate' });
+            } catch (err) {
+                console.warn('[Updater] Pre-update backup failed:', err);
+            }
+
+            const unlisten = await listen<DownloadProgress>('update-download-progress', ({ payload }) => {
+                const pct = payload.total ? Math.round((payload.bytes / payload.total) * 100) : 0;
+                setStatus((prev) => ({ ...prev, progress: pct }));
             });
+            try {
+                await invoke('download_update');
+            } finally {
+                unlisten();
+            }
+            setStatus((prev) => ({ ...prev, available: false, downloading: false, ready: true, progress: 100 }));
+            console.log('[Updater] Download complete');
+        } catch (err) {
+            console.error('[Updater] Failed to download update:', err);
+            setStatus((prev) => ({
+                ...prev,
+                downloading: false,
+                error: err instanceof Error ? err.message : String(err),
+            }));
+        }
+    }, []);
 
+    /**
+     * Install the downloaded update, then relaunch
+     */
+    const installUpdate = useCallback(async () => {
+        try {
+            await invoke('install_update');
             console.log('[Updater] Update installed, relaunching...');
             await relaunch();
         } catch (err) {
             console.error('[Updater] Failed to install update:', err);
+            setStatus((prev) => ({
+                ...prev,
+                ready: false,
+                error: err instanceof Error ? err.message : String(err),
+            }));
+        }
+    }, []);
+
+    /**
+     * Reinstall the version the last update replaced, then relaunch
+     */
+    const rollbackUpdate = useCallback(async () => {
+        try {
+            setStatus((prev) => ({ ...prev, downloading: true, progress: 0, error: undefined }));
+            await invoke('rollback_update');
+            console.log('[Updater] Rolled back, relaunching...');
+            await relaunch();
+        } catch (err) {
+            console.error('[Updater] Failed to roll back:', err);
             setStatus((prev) => ({
                 ...prev,
                 downloading: false,
-                error: err instanceof Error ? err.message : 'Failed to install update',
+                error: err instanceof Error ? err.message : String(err),
             }));
         }
     }, []);
@@ -129,6 +176,10 @@ export function useAppUpdater() {
         if (hasChecked.current) return;
         hasChecked.current = true;
 
+        invoke<string | null>('get_rollback_version')
+            .then(setRollbackVersion)
+            .catch(() => setRollbackVersion(null));
+
         // Slight delay so the app loads before we hit the network
         const timer = setTimeout(() => {
             checkForUpdate();
@@ -140,7 +191,10 @@ export function useAppUpdater() {
     return {
         status,
         checkForUpdate,
+        downloadUpdate,
         installUpdate,
         dismissUpdate,
+        rollbackVersion,
+        rollbackUpdate,
     };
 }
@@ -0,0 +1,54 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export interface AuditEntry {
+    id: number;
+    changedAt: string;
+    /** The paired device the change was synced from; `null` for this one. */
+    device: string | null;
+    entity: 'player' | 'lineup';
+    entityId: string;
+    entityName: string;
+    action: 'created' | 'updated' | 'deleted';
+    /** The field an update changed; `null` for creates and deletes. */
+    field: string | null;
+    oldValue: string | null;
+    newValue: string | null;
+}
+
+export interface AuditFilter {
+    entity?: 'player' | 'lineup';
+    entityId?: string;
+    /** A paired device's name, or `''` for this device's own edits. */
+    device?: string;
+    /** ISO timestamps bounding when the change was made. */
+    since?: string;
+    until?: string;
+    limit?: number;
+}
+
+/**
+ * Who changed which roster and lineup fields, and when, newest first: edits
+ * synced from an assistant's device are listed under its name. Only
+ * available in the desktop app.
+ */
+export function useAuditLog(filter: AuditFilter = {}) {
+    const supported = isTauri();
+    const [entries, setEntries] = useState<AuditEntry[]>([]);
+    const key = JSON.stringify(filter);
+
+    const refresh = useCallback(async () => {
+        if (!supported) return;
+        try {
+            setEntries(await invoke<AuditEntry[]>('get_audit_log', { filter: JSON.parse(key) }));
+        } catch (err) {
+            console.warn('[AuditLog] Failed to load the audit log:', err);
+        }
+    }, [supported, key]);
+
+    useEffect(() => {
+        refresh();
+    }, [refresh]);
+
+    return { supported, entries, refresh };
+}
@@ -0,0 +1,31 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+/**
+ * Whether the desktop app starts at login. It comes up in the tray, so
+ * game reminders keep arriving without a window opening every morning.
+ * Only available in the desktop app.
+ */
+export function useAutostart() {
+    const supported = isTauri();
+    const [enabled, setEnabled] = useState(false);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<boolean>('get_autostart')
+            .then((current) => !disposed && setEnabled(current))
+            .catch((err) => console.warn('[Autostart] Failed to read login item:', err));
+
+        return () => {
+            disposed = true;
+        };
+    }, [supported]);
+
+    const setAutostart = useCallback(async (enable: boolean) => {
+        setEnabled(await invoke<boolean>('set_autostart', { enabled: enable }));
+    }, []);
+
+    return { supported, enabled, setAutostart };
+}
@@ -0,0 +1,63 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type CompatibilityState = 'pending' | 'compatible' | 'needsMigration' | 'incompatible';
+
+export interface BackendCompatibility {
+    state: CompatibilityState;
+    backendVersion: string | null;
+    apiVersion: number | null;
+    schemaVersion: number | null;
+    dataSchemaVersion: number | null;
+    /** What's wrong and what to do about it, unless compatible. */
+    message: string | null;
+}
+
+/**
+ * The result of the shell's version handshake with the backend. While it's
+ * `needsMigration` or `incompatible` every backend request is refused with
+ * the message; `migrate` upgrades the sidecar's data (after a backup) and
+ * resolves to the new result. Only available in the desktop app.
+ */
+export function useBackendCompatibility() {
+    const supported = isTauri();
+    const [compatibility, setCompatibility] = useState<BackendCompatibility | null>(null);
+    const [migrating, setMigrating] = useState(false);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<BackendCompatibility>('get_backend_compatibility')
+            .then((current) => !disposed && setCompatibility(current))
+            .catch(() => {});
+
+        const unlisten = listen<BackendCompatibility>('backend-compatibility', ({ payload }) =>
+            setCompatibility(payload),
+        );
+        return () => {
+            disposed = true;
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const recheck = useCallback(async () => {
+        const checked = await invoke<BackendCompatibility>('check_backend_compatibility');
+        setCompatibility(checked);
+        return checked;
+    }, []);
+
+    const migrate = useCallback(async () => {
+        setMigrating(true);
+        try {
+            const migrated = await invoke<BackendCompatibility>('migrate_backend_data');
+            setCompatibility(migrated);
+            return migrated;
+        } finally {
+            setMigrating(false);
+        }
+    }, []);
+
+    return { supported, compatibility, migrating, recheck, migrate };
+}
@@ -0,0 +1,57 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface MemoryLimits {
+    warnMib: number;
+    /** `null` never restarts the backend. */
+    restartMib: number | null;
+}
+
+export interface ResourceUsage {
+    pid: number;
+    /** Percent of one core, so above 100 when it keeps several busy. */
+    cpuPercent: number;
+    /** Resident memory in bytes. */
+    memory: number;
+    sampledAt: number;
+    limits: MemoryLimits;
+}
+
+/**
+ * CPU and memory use of the AI backend, refreshed every `intervalMs`.
+ * `warning` holds the sample that last crossed the memory cap, until
+ * `dismissWarning` clears it; past the hard limit the shell restarts the
+ * backend on its own. Only available in the desktop app.
+ */
+export function useBackendResources(intervalMs = 10_000) {
+    const supported = isTauri();
+    const [usage, setUsage] = useState<ResourceUsage | null>(null);
+    const [warning, setWarning] = useState<ResourceUsage | null>(null);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        const refresh = () =>
+            invoke<ResourceUsage | null>('get_backend_resource_usage')
+                .then((sample) => !disposed && setUsage(sample))
+                .catch((err) => console.warn('[BackendResources] Failed to read usage:', err));
+        refresh();
+        const timer = window.setInterval(refresh, intervalMs);
+        const unlisten = listen<ResourceUsage>('backend-memory-warning', ({ payload }) => {
+            setUsage(payload);
+            setWarning(payload);
+        });
+
+        return () => {
+            disposed = true;
+            window.clearInterval(timer);
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, intervalMs]);
+
+    const dismissWarning = useCallback(() => setWarning(null), []);
+
+    return { supported, usage, warning, dismissWarning };
+}
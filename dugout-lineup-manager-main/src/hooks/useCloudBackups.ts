@@ -0,0 +1,72 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type CloudProvider = 'dropbox' | 'googleDrive';
+
+export interface CloudLink {
+    provider: CloudProvider;
+    linked: boolean;
+    /** Why linking failed, ready to show. */
+    error: string | null;
+}
+
+export interface CloudBackup {
+    /** The service's id for the file, to pass to `restore`. */
+    id: string;
+    fileName: string;
+    kind: 'auto' | 'preUpdate' | 'preRestore' | 'manual';
+    createdAt: string;
+    size: number;
+}
+
+/**
+ * Uploading automatic backups to Dropbox or Google Drive. `link` opens the
+ * service's sign-in in the browser; once the coach comes back it becomes
+ * the upload target and its backups are listed. Only available in the
+ * desktop app.
+ */
+export function useCloudBackups() {
+    const supported = isTauri();
+    const [backups, setBackups] = useState<CloudBackup[]>([]);
+    const [error, setError] = useState<string | null>(null);
+
+    const refresh = useCallback(async () => {
+        if (!supported) return;
+        try {
+            setBackups(await invoke<CloudBackup[]>('list_cloud_backups'));
+        } catch (err) {
+            // Also fails until an account is linked, which is the common case
+            console.warn('[CloudBackups] Failed to list backups:', err);
+            setBackups([]);
+        }
+    }, [supported]);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        refresh();
+        const unlisten = listen<CloudLink>('cloud-backup-linked', ({ payload }) => {
+            setError(payload.error);
+            if (payload.linked) refresh();
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, refresh]);
+
+    const link = useCallback(async (provider: CloudProvider) => {
+        setError(null);
+        await invoke('link_cloud_backup', { provider });
+    }, []);
+
+    const unlink = useCallback(async (provider: CloudProvider) => {
+        await invoke('unlink_cloud_backup', { provider });
+        setBackups([]);
+    }, []);
+
+    const restore = useCallback((id: string) => invoke('restore_from_cloud', { id }), []);
+
+    return { supported, backups, error, refresh, link, unlink, restore };
+}
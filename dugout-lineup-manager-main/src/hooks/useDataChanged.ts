@@ -0,0 +1,32 @@
+import { useEffect } from 'react';
+import { isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface DataChanged {
+    /** Another program wrote to the database. */
+    database: boolean;
+    /** Other files that changed, relative to the data folder, e.g. `images/logo.png`. */
+    files: string[];
+}
+
+/**
+ * Runs `onChanged` when something outside the app changes the data folder,
+ * such as a sync client bringing in edits from another computer, so the
+ * caller can reload what it shows. Changes arrive in batches once the folder
+ * has been quiet for a moment. Only available in the desktop app.
+ */
+export function useDataChanged(onChanged: (change: DataChanged) => void) {
+    const supported = isTauri();
+
+    useEffect(() => {
+        if (!supported) return;
+
+        const unlisten = listen<DataChanged>('data-changed', ({ payload }) => onChanged(payload));
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, onChanged]);
+
+    return { supported };
+}
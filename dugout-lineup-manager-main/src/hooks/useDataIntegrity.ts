@@ -0,0 +1,67 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface QuarantinedRow {
+    table: string;
+    rowid: number;
+    /** The table it pointed at without finding the row. */
+    parent: string;
+}
+
+export interface IntegrityReport {
+    checkedAt: string;
+    /** The check ran at launch because the last run didn't exit cleanly. */
+    afterUncleanShutdown: boolean;
+    /** What SQLite found, before any repair. */
+    problems: string[];
+    reindexed: boolean;
+    /** Damage left after repairing; only restoring a backup fixes it. */
+    unrepaired: string[];
+    /** Rows moved into `quarantined_rows` because their parent is gone. */
+    quarantined: QuarantinedRow[];
+    clearedSlots: number;
+    unlinkedLineups: number;
+    droppedHistory: number;
+    healthy: boolean;
+}
+
+/**
+ * Database integrity checks. The shell checks by itself at launch after a
+ * crash or power cut; `report` is the latest result, from then or from
+ * `check`. Repairs keep every row, moving the ones that can't stay into a
+ * quarantine table. Only available in the desktop app.
+ */
+export function useDataIntegrity() {
+    const supported = isTauri();
+    const [report, setReport] = useState<IntegrityReport | null>(null);
+    const [checking, setChecking] = useState(false);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<IntegrityReport | null>('get_integrity_report')
+            .then((last) => !disposed && last && setReport(last))
+            .catch(() => {});
+
+        const unlisten = listen<IntegrityReport>('integrity-report', ({ payload }) => setReport(payload));
+        return () => {
+            disposed = true;
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const check = useCallback(async () => {
+        setChecking(true);
+        try {
+            const checked = await invoke<IntegrityReport>('check_data_integrity');
+            setReport(checked);
+            return checked;
+        } finally {
+            setChecking(false);
+        }
+    }, []);
+
+    return { supported, report, checking, check };
+}
@@ -0,0 +1,43 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export interface EncryptionStatus {
+    enabled: boolean;
+    /** Whether this platform has a keychain to hold the key. */
+    available: boolean;
+}
+
+/**
+ * At-rest encryption of the local database. Turning it on is one-way: the
+ * key lives in the OS keychain, and backups taken afterwards only restore on
+ * a computer that has it. Only available in the desktop app.
+ */
+export function useDatabaseEncryption() {
+    const supported = isTauri();
+    const [status, setStatus] = useState<EncryptionStatus | null>(null);
+    const [enabling, setEnabling] = useState(false);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<EncryptionStatus>('get_encryption_status')
+            .then((current) => !disposed && setStatus(current))
+            .catch((err) => console.warn('[Encryption] Failed to read status:', err));
+
+        return () => {
+            disposed = true;
+        };
+    }, [supported]);
+
+    const enableEncryption = useCallback(async () => {
+        setEnabling(true);
+        try {
+            setStatus(await invoke<EncryptionStatus>('enable_encryption'));
+        } finally {
+            setEnabling(false);
+        }
+    }, []);
+
+    return { supported, status, enabling, enableEncryption };
+}
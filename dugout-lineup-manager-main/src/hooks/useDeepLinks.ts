@@ -0,0 +1,46 @@
+import { useEffect } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type DeepLink =
+    | { route: 'home' }
+    | { route: 'game'; date: string }
+    | { route: 'import'; file: string };
+
+/**
+ * Hook to follow dugout:// links, including the one that launched the app.
+ * Import links are passed to `onImport`. Must be used inside the router.
+ */
+export function useDeepLinks(onImport?: (file: string) => void) {
+    const navigate = useNavigate();
+
+    useEffect(() => {
+        if (!isTauri()) return;
+
+        const open = (link: DeepLink) => {
+            switch (link.route) {
+                case 'home':
+                    navigate('/');
+                    break;
+                case 'game':
+                    navigate(`/games?date=${link.date}`);
+                    break;
+                case 'import':
+                    onImport?.(link.file);
+                    break;
+            }
+        };
+
+        // Subscribe first so nothing slips between the two calls.
+        const unlisten = listen<DeepLink>('deep-link', ({ payload }) => open(payload));
+        unlisten
+            .then(() => invoke<DeepLink[]>('take_pending_deep_links'))
+            .then((pending) => pending.forEach(open))
+            .catch(console.error);
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [navigate, onImport]);
+}
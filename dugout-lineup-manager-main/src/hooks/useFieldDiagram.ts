@@ -0,0 +1,39 @@
+import { useCallback } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export type HitResult = 'out' | 'single' | 'double' | 'triple' | 'homeRun';
+
+/** Where a ball in play landed, in feet from home plate. */
+export interface SprayHit {
+    /** Toward the first-base side; negative toward third. */
+    x: number;
+    /** Toward center field. */
+    y: number;
+    result: HitResult;
+}
+
+export interface DiagramOptions {
+    format?: 'png' | 'svg';
+    /** Defaults to the first inning. */
+    inning?: number;
+    /** PNG width in pixels, 1200 by default. */
+    width?: number;
+    spray?: SprayHit[];
+}
+
+/**
+ * Saving a lineup's defensive alignment as a field diagram to print or put
+ * on a card. Resolves to the saved path, or `null` if the save dialog was
+ * cancelled. Only available in the desktop app.
+ */
+export function useFieldDiagram() {
+    const supported = isTauri();
+
+    const saveDiagram = useCallback(
+        (lineupId: string, options?: DiagramOptions, path?: string) =>
+            invoke<string | null>('render_field_diagram', { lineupId, options, path }),
+        [],
+    );
+
+    return { supported, saveDiagram };
+}
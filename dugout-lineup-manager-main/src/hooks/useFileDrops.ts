@@ -0,0 +1,38 @@
+import { useEffect } from 'react';
+import { isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { toast } from 'sonner';
+
+export interface DroppedFile {
+    kind: 'csv' | 'xlsx' | 'backup';
+    /** Original file name, for display. */
+    name: string;
+    /** Staged copy the import should read. */
+    path: string;
+}
+
+interface RejectedFile {
+    name: string;
+    reason: string;
+}
+
+/**
+ * Hook to receive files dropped onto the window, already identified by
+ * content and staged by the Rust side. Unsupported files are reported with
+ * a toast.
+ */
+export function useFileDrops(onDrop: (file: DroppedFile) => void) {
+    useEffect(() => {
+        if (!isTauri()) return;
+
+        const unlistenDropped = listen<DroppedFile>('file-dropped', ({ payload }) => onDrop(payload));
+        const unlistenRejected = listen<RejectedFile>('file-drop-rejected', ({ payload }) => {
+            toast.error(`Can't import ${payload.name}: ${payload.reason}`);
+        });
+
+        return () => {
+            unlistenDropped.then((fn) => fn());
+            unlistenRejected.then((fn) => fn());
+        };
+    }, [onDrop]);
+}
@@ -0,0 +1,63 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface SeedOptions {
+    /** Also schedules a game a week out. */
+    sampleGame?: boolean;
+}
+
+export interface SeedProgress {
+    done: number;
+    total: number;
+    message: string;
+}
+
+export interface SeedReport {
+    teamId: string;
+    players: number;
+    gameId: string | null;
+}
+
+/**
+ * The welcome wizard's backend: whether nothing has been entered yet, a
+ * demo team to start with, and a factory reset back to it. `progress`
+ * follows whichever is running. Only available in the desktop app.
+ */
+export function useFirstRun() {
+    const supported = isTauri();
+    const [firstRun, setFirstRun] = useState<boolean | null>(null);
+    const [progress, setProgress] = useState<SeedProgress | null>(null);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        invoke<boolean>('is_first_run')
+            .then(setFirstRun)
+            .catch((err) => console.warn('[FirstRun] Failed to check for a first run:', err));
+        const unlisten = listen<SeedProgress>('first-run-progress', ({ payload }) => {
+            setProgress(payload);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const seedDemoData = useCallback(async (options: SeedOptions = {}) => {
+        setProgress(null);
+        const report = await invoke<SeedReport>('seed_demo_data', { options });
+        setFirstRun(false);
+        return report;
+    }, []);
+
+    /** Asks for confirmation natively; resolves `null` if the coach cancels. */
+    const resetToFactory = useCallback(async (options: SeedOptions = {}) => {
+        setProgress(null);
+        const report = await invoke<SeedReport | null>('reset_to_factory', { options });
+        if (report) setFirstRun(false);
+        return report;
+    }, []);
+
+    return { supported, firstRun, progress, seedDemoData, resetToFactory };
+}
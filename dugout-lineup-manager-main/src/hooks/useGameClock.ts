@@ -0,0 +1,80 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface GameClockStatus {
+    running: boolean;
+    elapsedSecs: number;
+    timeLimitSecs: number | null;
+    /** Zero once the limit has passed; `null` without a limit. */
+    remainingSecs: number | null;
+}
+
+export interface GameClockAlert {
+    kind: 'warning' | 'timeUp';
+    remainingSecs: number;
+    /** Ready to show, e.g. "10 minutes to drop-dead time". */
+    message: string;
+}
+
+const STOPPED: GameClockStatus = {
+    running: false,
+    elapsedSecs: 0,
+    timeLimitSecs: null,
+    remainingSecs: null,
+};
+
+/**
+ * The game clock kept by the desktop shell. It keeps running through a
+ * webview reload, so the hook reads it back on mount and then follows its
+ * ticks. `onAlert` is called at the team's warning times before the time
+ * limit and when it is reached. Only available in the desktop app.
+ */
+export function useGameClock(onAlert?: (alert: GameClockAlert) => void) {
+    const supported = isTauri();
+    const [status, setStatus] = useState<GameClockStatus>(STOPPED);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<GameClockStatus>('get_game_clock')
+            .then((current) => !disposed && setStatus(current))
+            .catch((err) => console.warn('[GameClock] Failed to read game clock:', err));
+
+        const unlistenTick = listen<GameClockStatus>('game-clock-tick', ({ payload }) =>
+            setStatus(payload),
+        );
+        const unlistenAlert = listen<GameClockAlert>('game-clock-alert', ({ payload }) =>
+            onAlert?.(payload),
+        );
+
+        return () => {
+            disposed = true;
+            unlistenTick.then((fn) => fn());
+            unlistenAlert.then((fn) => fn());
+        };
+    }, [supported, onAlert]);
+
+    const start = useCallback(async () => {
+        setStatus(await invoke<GameClockStatus>('start_game_clock'));
+    }, []);
+
+    const pause = useCallback(async () => {
+        setStatus(await invoke<GameClockStatus>('pause_game_clock'));
+    }, []);
+
+    const reset = useCallback(async () => {
+        setStatus(await invoke<GameClockStatus>('reset_game_clock'));
+    }, []);
+
+    /**
+     * Minutes from the start of the game, or `null` to clear the limit.
+     * `teamId` picks whose warning times apply.
+     */
+    const setTimeLimit = useCallback(async (minutes: number | null, teamId?: string) => {
+        setStatus(await invoke<GameClockStatus>('set_time_limit', { minutes, teamId }));
+    }, []);
+
+    return { supported, status, start, pause, reset, setTimeLimit };
+}
@@ -0,0 +1,32 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+interface GameModeStatus {
+    active: boolean;
+}
+
+/**
+ * Hook to enter and leave Game Mode, the compact always-on-top window the
+ * lineup shrinks into during a live game. `active` follows the shell, so
+ * every component sees the same state.
+ */
+export function useGameMode() {
+    const [active, setActive] = useState(false);
+
+    useEffect(() => {
+        if (!isTauri()) return;
+
+        invoke<GameModeStatus>('get_game_mode').then((s) => setActive(s.active)).catch(console.error);
+        const unlisten = listen<GameModeStatus>('game-mode-changed', ({ payload }) => setActive(payload.active));
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    const enter = useCallback(() => invoke<GameModeStatus>('enter_game_mode'), []);
+    const exit = useCallback(() => invoke<GameModeStatus>('exit_game_mode'), []);
+
+    return { active, enter, exit };
+}
@@ -0,0 +1,38 @@
+import { useCallback, useEffect } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface GameReminder {
+    gameId: string;
+    lineupSet: boolean;
+    /** Ready to show, e.g. "Game vs Tigers in 2 hours — lineup not set!". */
+    message: string;
+}
+
+/**
+ * Game reminders from the desktop shell, which also shows them as OS
+ * notifications. `onReminder` is called as each one fires, e.g. to offer a
+ * snooze. Only available in the desktop app.
+ */
+export function useGameReminders(onReminder?: (reminder: GameReminder) => void) {
+    const supported = isTauri();
+
+    useEffect(() => {
+        if (!supported) return;
+
+        const unlisten = listen<GameReminder>('game-reminder', ({ payload }) =>
+            onReminder?.(payload),
+        );
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, onReminder]);
+
+    const snooze = useCallback(
+        (gameId: string, minutes?: number) => invoke('snooze_reminder', { gameId, minutes }),
+        [],
+    );
+
+    return { supported, snooze };
+}
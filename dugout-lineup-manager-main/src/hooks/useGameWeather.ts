@@ -0,0 +1,58 @@
+import { useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export interface GameWeather {
+    gameId: string;
+    date: string;
+    provider: 'openMeteo' | 'nws';
+    address: string;
+    /** Where the geocoder placed the address. */
+    place: string;
+    units: 'imperial' | 'metric';
+    temperatureHigh: number;
+    temperatureLow: number;
+    /** Highest chance of rain during the day, in percent. */
+    precipitationChance: number | null;
+    precipitation: number | null;
+    windSpeed: number;
+    windGusts: number | null;
+    rainoutRisk: boolean;
+    /** Ready to show in the pre-game checklist. */
+    warnings: string[];
+    fetchedAt: string;
+    /** The last forecast fetched, shown because a new one couldn't be. */
+    stale: boolean;
+}
+
+/**
+ * The game-day forecast for a game's field. Only available in the desktop
+ * app; `error` explains why there's no forecast (no address, too far out).
+ */
+export function useGameWeather(gameId: string | undefined) {
+    const supported = isTauri();
+    const [weather, setWeather] = useState<GameWeather | null>(null);
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        if (!supported || !gameId) {
+            setWeather(null);
+            return;
+        }
+
+        let disposed = false;
+        setError(null);
+        invoke<GameWeather>('get_game_weather', { gameId })
+            .then((forecast) => !disposed && setWeather(forecast))
+            .catch((err) => {
+                if (disposed) return;
+                setWeather(null);
+                setError(String(err));
+            });
+
+        return () => {
+            disposed = true;
+        };
+    }, [supported, gameId]);
+
+    return { supported, weather, error };
+}
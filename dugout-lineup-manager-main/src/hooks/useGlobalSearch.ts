@@ -0,0 +1,45 @@
+import { useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export type HitKind = 'player' | 'team' | 'game' | 'note';
+
+export interface SearchHit {
+    kind: HitKind;
+    /** The player, team or game. */
+    id: string;
+    title: string;
+    /** Number, position and team for players; for notes, the text around the match. */
+    subtitle: string;
+    /** Route to navigate to, e.g. `/games/<id>/stats`. */
+    route: string;
+    score: number;
+}
+
+/**
+ * Ranked matches for the command palette across players, teams, games and
+ * notes, tolerant of typos. Searches again as `query` changes, dropping
+ * results for queries that are no longer current. Only available in the
+ * desktop app.
+ */
+export function useGlobalSearch(query: string, limit?: number) {
+    const supported = isTauri();
+    const [hits, setHits] = useState<SearchHit[]>([]);
+
+    useEffect(() => {
+        if (!supported || !query.trim()) {
+            setHits([]);
+            return;
+        }
+
+        let stale = false;
+        invoke<SearchHit[]>('global_search', { query, limit })
+            .then((found) => !stale && setHits(found))
+            .catch((err) => console.warn('[Search] Search failed:', err));
+
+        return () => {
+            stale = true;
+        };
+    }, [supported, query, limit]);
+
+    return { supported, hits };
+}
@@ -0,0 +1,58 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type HotkeyAction = 'advanceBatter' | 'toggleGameClock' | 'toggleGameMode';
+
+/** Combos like `CmdOrCtrl+Shift+B`; `null` leaves an action without one. */
+export interface HotkeySettings {
+    advanceBatter: string | null;
+    toggleGameClock: string | null;
+    toggleGameMode: string | null;
+}
+
+export interface Hotkey {
+    action: HotkeyAction;
+    shortcut: string | null;
+    /** Whether it works right now; false if another app took it first. */
+    registered: boolean;
+}
+
+/**
+ * Global shortcuts for in-game actions, which work while another app has
+ * focus. The shell runs the action itself; `onPress` is told about each
+ * press as well. Only available in the desktop app.
+ */
+export function useHotkeys(onPress?: (action: HotkeyAction) => void) {
+    const supported = isTauri();
+    const [hotkeys, setHotkeys] = useState<Hotkey[]>([]);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        invoke<Hotkey[]>('get_hotkeys')
+            .then(setHotkeys)
+            .catch((err) => console.warn('[Hotkeys] Failed to read hotkeys:', err));
+    }, [supported]);
+
+    useEffect(() => {
+        if (!supported || !onPress) return;
+
+        const unlisten = listen<HotkeyAction>('hotkey-pressed', ({ payload }) => {
+            onPress(payload);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, onPress]);
+
+    /** Rejects, changing nothing, if a combo clashes or is already taken. */
+    const save = useCallback(async (settings: HotkeySettings) => {
+        const saved = await invoke<Hotkey[]>('set_hotkeys', { hotkeys: settings });
+        setHotkeys(saved);
+        return saved;
+    }, []);
+
+    return { supported, hotkeys, save };
+}
@@ -0,0 +1,73 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export type ImageOwner =
+    | { kind: 'team'; teamId: string }
+    | { kind: 'player'; playerId: string };
+
+export interface ImageAsset {
+    id: string;
+    teamId: string | null;
+    playerId: string | null;
+    width: number;
+    height: number;
+    /** Up to 2048 pixels on the long side. */
+    url: string;
+    /** Up to 512 pixels, for cards and profiles. */
+    mediumUrl: string;
+    /** Up to 128 pixels, for lists and avatars. */
+    smallUrl: string;
+    createdAt: string;
+}
+
+/**
+ * Team logos and player photos, with URLs ready for an `<img>`. `importImage`
+ * takes a PNG or JPEG path (e.g. from the open dialog) and replaces the
+ * owner's current one. Only available in the desktop app.
+ */
+export function useImages() {
+    const supported = isTauri();
+    const [images, setImages] = useState<ImageAsset[]>([]);
+
+    const refresh = useCallback(async () => {
+        if (!supported) return;
+        try {
+            setImages(await invoke<ImageAsset[]>('list_images'));
+        } catch (err) {
+            console.warn('[Images] Failed to list images:', err);
+        }
+    }, [supported]);
+
+    useEffect(() => {
+        refresh();
+    }, [refresh]);
+
+    const importImage = useCallback(
+        async (path: string, owner: ImageOwner) => {
+            const image = await invoke<ImageAsset>('import_image', { path, owner });
+            await refresh();
+            return image;
+        },
+        [refresh],
+    );
+
+    const deleteImage = useCallback(
+        async (id: string) => {
+            await invoke('delete_image', { id });
+            await refresh();
+        },
+        [refresh],
+    );
+
+    const teamLogo = useCallback(
+        (teamId: string) => images.find((image) => image.teamId === teamId) ?? null,
+        [images],
+    );
+
+    const playerPhoto = useCallback(
+        (playerId: string) => images.find((image) => image.playerId === playerId) ?? null,
+        [images],
+    );
+
+    return { supported, images, refresh, importImage, deleteImage, teamLogo, playerPhoto };
+}
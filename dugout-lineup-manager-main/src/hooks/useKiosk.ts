@@ -0,0 +1,43 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface KioskStatus {
+    locked: boolean;
+    pinSet: boolean;
+}
+
+/**
+ * Hook for kiosk mode, the lockdown for a shared check-in laptop: full
+ * screen, no closing, and no settings or updates until the PIN is entered.
+ * The shell refuses those while locked, so hiding them here is only so they
+ * don't show up broken.
+ */
+export function useKiosk() {
+    const supported = isTauri();
+    const [status, setStatus] = useState<KioskStatus>({ locked: false, pinSet: false });
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<KioskStatus>('get_kiosk_status')
+            .then((current) => !disposed && setStatus(current))
+            .catch(() => {});
+
+        const unlisten = listen<KioskStatus>('kiosk-changed', ({ payload }) => setStatus(payload));
+        return () => {
+            disposed = true;
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const setPin = useCallback(async (pin: string) => {
+        setStatus(await invoke<KioskStatus>('set_kiosk_pin', { pin }));
+    }, []);
+
+    const enter = useCallback(() => invoke<KioskStatus>('enter_kiosk_mode'), []);
+    const exit = useCallback((pin: string) => invoke<KioskStatus>('exit_kiosk_mode', { pin }), []);
+
+    return { supported, locked: status.locked, pinSet: status.pinSet, setPin, enter, exit };
+}
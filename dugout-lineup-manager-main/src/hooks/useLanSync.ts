@@ -0,0 +1,83 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface SyncPeer {
+    deviceId: string;
+    deviceName: string;
+}
+
+export interface DiscoveredPeer extends SyncPeer {
+    address: string;
+}
+
+export interface SyncStatus {
+    deviceName: string;
+    pairingCode: string | null;
+    discovering: boolean;
+    discovered: DiscoveredPeer[];
+    connected: SyncPeer[];
+}
+
+export type SyncChange =
+    | { kind: 'playerSaved'; player: { id: string } }
+    | { kind: 'playerDeleted'; id: string }
+    | { kind: 'lineupSaved'; lineup: { id: string } }
+    | { kind: 'lineupDeleted'; id: string };
+
+/**
+ * LAN sync with another coach's device.
+ *
+ * One device calls `host` and shows `status.pairingCode`; the other calls
+ * `discover`, picks the host from `status.discovered` and calls `pair` with
+ * that code. `onChange` runs for every edit applied from a paired device.
+ * Only available in the desktop app.
+ */
+export function useLanSync(onChange?: (change: SyncChange) => void) {
+    const supported = isTauri();
+    const [status, setStatus] = useState<SyncStatus | null>(null);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<SyncStatus>('get_sync_status')
+            .then((current) => !disposed && setStatus(current))
+            .catch((err) => console.warn('[LanSync] Failed to read sync status:', err));
+
+        const unlisten = listen<SyncStatus>('sync-status', ({ payload }) => setStatus(payload));
+
+        return () => {
+            disposed = true;
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    useEffect(() => {
+        if (!supported || !onChange) return;
+
+        const unlisten = listen<SyncChange>('sync-data-changed', ({ payload }) => onChange(payload));
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, onChange]);
+
+    const host = useCallback(async () => {
+        setStatus(await invoke<SyncStatus>('start_sync_hosting'));
+    }, []);
+
+    const discover = useCallback(async () => {
+        setStatus(await invoke<SyncStatus>('discover_sync_peers'));
+    }, []);
+
+    const pair = useCallback(async (deviceId: string, code: string) => {
+        setStatus(await invoke<SyncStatus>('pair_with_peer', { deviceId, code }));
+    }, []);
+
+    const stop = useCallback(async () => {
+        setStatus(await invoke<SyncStatus>('stop_sync'));
+    }, []);
+
+    return { supported, status, host, discover, pair, stop };
+}
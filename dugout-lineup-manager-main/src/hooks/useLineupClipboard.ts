@@ -0,0 +1,24 @@
+import { useCallback } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+/**
+ * `text` for a group chat, `html` for email and documents (with the text
+ * as a fallback), or `image` for chat apps that mangle pasted text.
+ */
+export type ClipboardFormat = 'text' | 'html' | 'image';
+
+/**
+ * Copying a lineup's batting order to the clipboard from the desktop shell.
+ * Only available in the desktop app.
+ */
+export function useLineupClipboard() {
+    const supported = isTauri();
+
+    const copyLineup = useCallback(
+        (lineupId: string, format: ClipboardFormat = 'text') =>
+            invoke('copy_lineup_to_clipboard', { lineupId, format }),
+        [],
+    );
+
+    return { supported, copyLineup };
+}
@@ -0,0 +1,39 @@
+import { useCallback } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export type ShareFormat = 'pdf' | 'xlsx';
+
+export interface EmailShare {
+    /** Where the attachment was written. */
+    path: string;
+    recipients: number;
+    /** False when the coach needs to drag the file in from the file manager. */
+    attached: boolean;
+}
+
+/**
+ * Emailing lineups to parents through the default mail app, and the parent
+ * emails kept per player. Only available in the desktop app.
+ */
+export function useLineupEmail() {
+    const supported = isTauri();
+
+    const shareLineup = useCallback(
+        (lineupId: string, format: ShareFormat = 'pdf', recipients?: string[]) =>
+            invoke<EmailShare>('share_lineup_email', { lineupId, format, recipients }),
+        [],
+    );
+
+    const getContacts = useCallback(
+        (playerId: string) => invoke<string[]>('get_player_contacts', { playerId }),
+        [],
+    );
+
+    const setContacts = useCallback(
+        (playerId: string, emails: string[]) =>
+            invoke<string[]>('set_player_contacts', { playerId, emails }),
+        [],
+    );
+
+    return { supported, shareLineup, getContacts, setContacts };
+}
@@ -0,0 +1,63 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export interface HistoryEntry {
+    id: number;
+    action: 'saveLineup' | 'deleteLineup';
+    lineupId: string;
+    lineupName: string;
+    /** Undone and waiting to be redone. */
+    undone: boolean;
+    createdAt: string;
+}
+
+export interface LineupHistory {
+    /** Newest first. */
+    entries: HistoryEntry[];
+    canUndo: boolean;
+    canRedo: boolean;
+}
+
+export interface UndoStep {
+    entry: HistoryEntry;
+    /** The lineup as it is now; `null` if the step deleted it. */
+    lineup: unknown | null;
+}
+
+/**
+ * Undo and redo for lineup saves and deletes, kept across restarts.
+ * `onStep` runs after each undo or redo so the caller can reload the lineup
+ * it changed. Only available in the desktop app.
+ */
+export function useLineupHistory(onStep?: (step: UndoStep) => void) {
+    const supported = isTauri();
+    const [history, setHistory] = useState<LineupHistory | null>(null);
+
+    const refresh = useCallback(async () => {
+        if (!supported) return;
+        try {
+            setHistory(await invoke<LineupHistory>('get_history'));
+        } catch (err) {
+            console.warn('[LineupHistory] Failed to load history:', err);
+        }
+    }, [supported]);
+
+    useEffect(() => {
+        refresh();
+    }, [refresh]);
+
+    const apply = useCallback(
+        async (command: 'undo' | 'redo') => {
+            const step = await invoke<UndoStep | null>(command);
+            await refresh();
+            if (step) onStep?.(step);
+            return step;
+        },
+        [refresh, onStep],
+    );
+
+    const undo = useCallback(() => apply('undo'), [apply]);
+    const redo = useCallback(() => apply('redo'), [apply]);
+
+    return { supported, history, refresh, undo, redo };
+}
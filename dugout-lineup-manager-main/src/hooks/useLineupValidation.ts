@@ -0,0 +1,48 @@
+import { useCallback } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export type Severity = 'error' | 'warning';
+
+export interface Violation {
+    /** Index into the rules checked against; `null` for the checks every lineup gets. */
+    rule: number | null;
+    /** The rule's kind, or e.g. `duplicatePosition`, `missingCatcher`. */
+    kind: string;
+    severity: Severity;
+    playerId: string | null;
+    innings: number[];
+    message: string;
+}
+
+/** A lineup as it would be saved, checked without saving it. */
+export interface ProposedLineup {
+    id?: string;
+    teamId?: string;
+    name: string;
+    gameId?: string;
+    useDh?: boolean;
+    innings?: number;
+    slots?: { slotNumber: number; playerId: string | null; position?: string | null }[];
+    positions?: { inning: number; position: string; playerId: string }[];
+}
+
+/**
+ * Checks a lineup against the league rules before it's printed. Errors
+ * should block printing; warnings are for the coach to decide on. Pass a
+ * saved lineup's id or a proposed lineup. Only available in the desktop
+ * app.
+ */
+export function useLineupValidation() {
+    const supported = isTauri();
+
+    const validateLineup = useCallback(
+        (lineup: string | ProposedLineup) =>
+            invoke<Violation[]>(
+                'validate_lineup',
+                typeof lineup === 'string' ? { id: lineup } : { lineup },
+            ),
+        [],
+    );
+
+    return { supported, validateLineup };
+}
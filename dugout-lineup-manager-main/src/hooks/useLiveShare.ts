@@ -0,0 +1,63 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface LiveShareStatus {
+    active: boolean;
+    /** Whether the relay connection is up right now. */
+    connected: boolean;
+    /** Link to send to family. */
+    url: string | null;
+    /** QR code of `url` as an SVG document. */
+    qrSvg: string | null;
+    viewers: number | null;
+    /** Why the last connection attempt failed; it keeps retrying. */
+    error: string | null;
+}
+
+const STOPPED: LiveShareStatus = {
+    active: false,
+    connected: false,
+    url: null,
+    qrSvg: null,
+    viewers: null,
+    error: null,
+};
+
+/**
+ * Sharing the game live with family away from the field, through the relay
+ * server set in settings (`relay.server`). Publishes whatever is pushed
+ * with `update_scoreboard`; each share gets a new link. Only available in
+ * the desktop app.
+ */
+export function useLiveShare() {
+    const supported = isTauri();
+    const [status, setStatus] = useState<LiveShareStatus>(STOPPED);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<LiveShareStatus>('get_live_share_status')
+            .then((current) => !disposed && setStatus(current))
+            .catch((err) => console.warn('[LiveShare] Failed to read share status:', err));
+        const unlisten = listen<LiveShareStatus>('live-share-status', ({ payload }) => {
+            setStatus(payload);
+        });
+
+        return () => {
+            disposed = true;
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const start = useCallback(async () => {
+        setStatus(await invoke<LiveShareStatus>('start_live_share'));
+    }, []);
+
+    const stop = useCallback(async () => {
+        setStatus(await invoke<LiveShareStatus>('stop_live_share'));
+    }, []);
+
+    return { supported, status, start, stop };
+}
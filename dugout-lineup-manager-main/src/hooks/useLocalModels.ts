@@ -0,0 +1,73 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface LocalModel {
+    name: string;
+    size: number;
+    /** `false` for an interrupted download that `download` will resume. */
+    complete: boolean;
+}
+
+export interface ModelDownloadProgress {
+    name: string;
+    downloaded: number;
+    total: number | null;
+}
+
+/**
+ * Local model files kept by the desktop shell for the sidecar.
+ *
+ * `download` resumes any partial file of the same name and only keeps the
+ * result if it matches `sha256`; `progress` holds the latest progress per
+ * model while downloads run. Only available in the desktop app.
+ */
+export function useLocalModels() {
+    const supported = isTauri();
+    const [models, setModels] = useState<LocalModel[]>([]);
+    const [progress, setProgress] = useState<Record<string, ModelDownloadProgress>>({});
+
+    const refresh = useCallback(async () => {
+        if (!supported) return;
+        try {
+            setModels(await invoke<LocalModel[]>('list_models'));
+        } catch (err) {
+            console.warn('[Models] Failed to list models:', err);
+        }
+    }, [supported]);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        refresh();
+        const unlisten = listen<ModelDownloadProgress>('model-download-progress', ({ payload }) =>
+            setProgress((prev) => ({ ...prev, [payload.name]: payload })),
+        );
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, refresh]);
+
+    const download = useCallback(
+        async (name: string, url: string, sha256: string) => {
+            try {
+                return await invoke<LocalModel>('download_model', { name, url, sha256 });
+            } finally {
+                setProgress(({ [name]: _done, ...rest }) => rest);
+                await refresh();
+            }
+        },
+        [refresh],
+    );
+
+    const remove = useCallback(
+        async (name: string) => {
+            await invoke('delete_model', { name });
+            await refresh();
+        },
+        [refresh],
+    );
+
+    return { supported, models, progress, download, remove, refresh };
+}
@@ -0,0 +1,66 @@
+import { useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export type NoteKind = 'player' | 'game';
+
+export interface NoteFilters {
+    kind?: NoteKind;
+    teamId?: string;
+    /** Earliest game date, `YYYY-MM-DD`. Either date leaves out player notes. */
+    from?: string;
+    /** Latest game date, `YYYY-MM-DD`, inclusive. */
+    to?: string;
+    limit?: number;
+}
+
+export interface NoteHit {
+    kind: NoteKind;
+    /** The player or game. */
+    id: string;
+    teamId: string;
+    /** The player's name, or the opponent as in "@ Eagles". */
+    title: string;
+    date: string | null;
+    /** HTML-escaped text around the matches, which are wrapped in `<b>`. */
+    snippet: string;
+    route: string;
+    score: number;
+}
+
+/**
+ * Full-text search over player and game notes. Every word has to match, and
+ * a quoted phrase has to match as written. Searches again as `query` or the
+ * filters change, dropping results that are no longer current. Only
+ * available in the desktop app.
+ */
+export function useNoteSearch(query: string, filters: NoteFilters = {}) {
+    const supported = isTauri();
+    const [hits, setHits] = useState<NoteHit[]>([]);
+    const [error, setError] = useState<string | null>(null);
+    const { kind, teamId, from, to, limit } = filters;
+
+    useEffect(() => {
+        if (!supported || !query.trim()) {
+            setHits([]);
+            return;
+        }
+
+        let stale = false;
+        invoke<NoteHit[]>('search_notes', { query, filters: { kind, teamId, from, to, limit } })
+            .then((found) => {
+                if (stale) return;
+                setHits(found);
+                setError(null);
+            })
+            .catch((err) => {
+                console.warn('[NoteSearch] Search failed:', err);
+                if (!stale) setError(String(err));
+            });
+
+        return () => {
+            stale = true;
+        };
+    }, [supported, query, kind, teamId, from, to, limit]);
+
+    return { supported, hits, error };
+}
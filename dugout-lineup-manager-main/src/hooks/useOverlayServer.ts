@@ -0,0 +1,45 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export interface OverlayStatus {
+    running: boolean;
+    url: string | null;
+    /** QR code of `url` as an SVG document. */
+    qrSvg: string | null;
+}
+
+const STOPPED: OverlayStatus = { running: false, url: null, qrSvg: null };
+
+/**
+ * The local scoreboard overlay server, for parents on the same Wi-Fi or an
+ * OBS browser source (append `?transparent` to the URL there). It shows
+ * whatever is pushed with `update_scoreboard`. Only available in the
+ * desktop app.
+ */
+export function useOverlayServer() {
+    const supported = isTauri();
+    const [status, setStatus] = useState<OverlayStatus>(STOPPED);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<OverlayStatus>('get_overlay_status')
+            .then((current) => !disposed && setStatus(current))
+            .catch((err) => console.warn('[Overlay] Failed to read overlay status:', err));
+
+        return () => {
+            disposed = true;
+        };
+    }, [supported]);
+
+    const start = useCallback(async (port?: number) => {
+        setStatus(await invoke<OverlayStatus>('start_overlay_server', { port }));
+    }, []);
+
+    const stop = useCallback(async () => {
+        setStatus(await invoke<OverlayStatus>('stop_overlay_server'));
+    }, []);
+
+    return { supported, status, start, stop };
+}
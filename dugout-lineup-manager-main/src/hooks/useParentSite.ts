@@ -0,0 +1,43 @@
+import { useCallback, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export interface ParentSiteOptions {
+    /** How much of each player's name is published; `firstNameLastInitial` by default. */
+    names?: 'full' | 'firstNameLastInitial' | 'initials';
+    /** A year; without it every season is published. */
+    seasonId?: string;
+    /** Leaves the stats page out. */
+    hideStats?: boolean;
+}
+
+export interface ParentSite {
+    dir: string;
+    /** The schedule (`index.html`) first, then the roster and stats. */
+    pages: string[];
+}
+
+/**
+ * Writes the schedule, roster and stats as a folder of static HTML pages
+ * for the families, ready to upload to the league website. Notes and
+ * inactive players are never included. Without `dir` a folder picker asks,
+ * and the export resolves to `null` if it's cancelled. Only available in
+ * the desktop app.
+ */
+export function useParentSite() {
+    const supported = isTauri();
+    const [exporting, setExporting] = useState(false);
+
+    const exportParentSite = useCallback(
+        async (options?: ParentSiteOptions, teamId?: string, dir?: string) => {
+            setExporting(true);
+            try {
+                return await invoke<ParentSite | null>('export_parent_site', { teamId, options, dir });
+            } finally {
+                setExporting(false);
+            }
+        },
+        [],
+    );
+
+    return { supported, exporting, exportParentSite };
+}
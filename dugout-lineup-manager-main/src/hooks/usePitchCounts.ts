@@ -0,0 +1,79 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface PitcherAvailability {
+    playerId: string;
+    name: string;
+    /** `YYYY-MM-DD` of the latest outing still inside the rest window. */
+    lastPitched: string | null;
+    lastPitches: number;
+    restDays: number;
+    pitchesToday: number;
+    remainingToday: number;
+    availableOn: string;
+    available: boolean;
+}
+
+export interface PitchThreshold {
+    playerId: string;
+    name: string;
+    gameId: string;
+    pitches: number;
+    restDays: number;
+    limitReached: boolean;
+    /** Ready to show, e.g. "Jake Miller needs 2 days rest". */
+    message: string;
+}
+
+/**
+ * Pitch counts and rest-rule availability for the team, using the pitch
+ * rules in settings. `record` sets a pitcher's running total for a game;
+ * `onThreshold` is called whenever a count crosses into more required rest,
+ * reaches the daily limit or passes one of the team's alert counts. Only available in the desktop app.
+ */
+export function usePitchCounts(onThreshold?: (threshold: PitchThreshold) => void) {
+    const supported = isTauri();
+    const [pitchers, setPitchers] = useState<PitcherAvailability[]>([]);
+
+    const refresh = useCallback(
+        async (date?: string) => {
+            if (!supported) return;
+            try {
+                setPitchers(await invoke<PitcherAvailability[]>('get_pitcher_availability', { date }));
+            } catch (err) {
+                console.warn('[Pitching] Failed to load pitcher availability:', err);
+            }
+        },
+        [supported],
+    );
+
+    useEffect(() => {
+        if (!supported) return;
+
+        refresh();
+        const unlisten = listen<PitchThreshold>('pitch-count-threshold', ({ payload }) =>
+            onThreshold?.(payload),
+        );
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, refresh, onThreshold]);
+
+    const record = useCallback(
+        async (playerId: string, gameId: string, date: string, pitches: number) => {
+            const updated = await invoke<PitcherAvailability>('record_pitches', {
+                playerId,
+                gameId,
+                date,
+                pitches,
+            });
+            setPitchers((prev) => prev.map((p) => (p.playerId === playerId ? updated : p)));
+            return updated;
+        },
+        [],
+    );
+
+    return { supported, pitchers, record, refresh };
+}
@@ -0,0 +1,142 @@
+import { useCallback, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type DrillFocus =
+    | 'hitting'
+    | 'fielding'
+    | 'throwing'
+    | 'pitching'
+    | 'catching'
+    | 'baserunning'
+    | 'general';
+
+export interface Drill {
+    id: string;
+    /** `null` for drills every team can use. */
+    teamId: string | null;
+    name: string;
+    focus: DrillFocus;
+    minutes: number;
+    description: string;
+    createdAt: string;
+    updatedAt: string;
+}
+
+export interface DrillInput {
+    /** Replaces the drill with this id; omit to add one. */
+    id?: string;
+    teamId?: string | null;
+    name: string;
+    focus: DrillFocus;
+    minutes: number;
+    description?: string;
+}
+
+export interface PracticePlan {
+    id: string;
+    teamId: string;
+    title: string;
+    minutes: number;
+    focus: DrillFocus[];
+    drillIds: string[];
+    prompt: string;
+    model: string;
+    content: string;
+    createdAt: string;
+    updatedAt: string;
+}
+
+export interface PracticePlanRequest {
+    teamId?: string;
+    /** Defaults to 90. */
+    minutes?: number;
+    /** Areas to work on besides the ones the team's recent stats point to. */
+    focus?: DrillFocus[];
+    notes?: string;
+    title?: string;
+    model: string;
+}
+
+type AIStreamEvent =
+    | { type: 'chunk'; text: string }
+    | { type: 'done' }
+    | { type: 'cancelled' }
+    | { type: 'error'; error: unknown };
+
+/**
+ * Drills and AI-written practice plans. `generatePracticePlan` builds the
+ * prompt from the team's last few games and its drills, passes the plan to
+ * `onChunk` as it's written and resolves to the saved plan, or `null` if
+ * `cancel` stopped it. Only available in the desktop app.
+ */
+export function usePracticePlans() {
+    const supported = isTauri();
+    const [requestId, setRequestId] = useState<string | null>(null);
+
+    const listDrills = useCallback(
+        (teamId?: string) => invoke<Drill[]>('list_drills', { teamId }),
+        [],
+    );
+
+    const saveDrill = useCallback(
+        (drill: DrillInput) => invoke<Drill>('save_drill', { drill }),
+        [],
+    );
+
+    const deleteDrill = useCallback((id: string) => invoke<void>('delete_drill', { id }), []);
+
+    const listPracticePlans = useCallback(
+        (teamId?: string) => invoke<PracticePlan[]>('list_practice_plans', { teamId }),
+        [],
+    );
+
+    const updatePracticePlan = useCallback(
+        (id: string, changes: { title?: string; content?: string }) =>
+            invoke<PracticePlan>('update_practice_plan', { id, changes }),
+        [],
+    );
+
+    const deletePracticePlan = useCallback(
+        (id: string) => invoke<void>('delete_practice_plan', { id }),
+        [],
+    );
+
+    const generatePracticePlan = useCallback(
+        async (request: PracticePlanRequest, onChunk?: (text: string) => void) => {
+            const id = crypto.randomUUID();
+            // Subscribe before starting so no text is missed
+            const unlisten = await listen<AIStreamEvent>(`ai-stream:${id}`, ({ payload }) => {
+                if (payload.type === 'chunk') onChunk?.(payload.text);
+            });
+            setRequestId(id);
+            try {
+                return await invoke<PracticePlan | null>('generate_practice_plan', {
+                    requestId: id,
+                    request,
+                });
+            } finally {
+                setRequestId(null);
+                unlisten();
+            }
+        },
+        [],
+    );
+
+    const cancel = useCallback(async () => {
+        if (requestId) await invoke<boolean>('cancel_ai_request', { requestId });
+    }, [requestId]);
+
+    return {
+        supported,
+        generating: requestId !== null,
+        listDrills,
+        saveDrill,
+        deleteDrill,
+        listPracticePlans,
+        updatePracticePlan,
+        deletePracticePlan,
+        generatePracticePlan,
+        cancel,
+    };
+}
@@ -0,0 +1,101 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface Display {
+    id: string;
+    name: string;
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+    scaleFactor: number;
+    /** Keeps the controls, so it can't be presented on. */
+    primary: boolean;
+}
+
+export interface Slide {
+    kind: 'lineup' | 'award';
+    title: string;
+    subtitle?: string | null;
+    /** The batting order for a lineup, or who won and why for an award. */
+    lines: string[];
+}
+
+export interface Presentation {
+    /** The display it's on, while it's running. */
+    displayId: string | null;
+    slides: Slide[];
+    index: number;
+    /** Seconds before it moves on by itself, or null to leave it to the controls. */
+    advanceSecs: number | null;
+}
+
+const idle: Presentation = { displayId: null, slides: [], index: 0, advanceSecs: null };
+
+/**
+ * Hook to run the lineup and awards slideshow full screen on a second
+ * display, with these controls staying on the primary one. Only available
+ * in the desktop app.
+ */
+export function usePresentation() {
+    const supported = isTauri();
+    const [presentation, setPresentation] = useState<Presentation>(idle);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<Presentation>('get_presentation')
+            .then((current) => !disposed && setPresentation(current))
+            .catch(() => {});
+
+        const unlisten = listen<Presentation>('presentation', ({ payload }) => setPresentation(payload));
+        return () => {
+            disposed = true;
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const listDisplays = useCallback(() => invoke<Display[]>('list_displays'), []);
+
+    const start = useCallback(
+        (displayId: string, slides: Slide[], advanceSecs?: number) =>
+            invoke<Presentation>('start_presentation', { displayId, slides, advanceSecs }),
+        [],
+    );
+
+    const update = useCallback(
+        (slides: Slide[], advanceSecs?: number) =>
+            invoke<Presentation>('update_presentation', { slides, advanceSecs }),
+        [],
+    );
+
+    const showSlide = useCallback((index: number) => invoke<Presentation>('show_slide', { index }), []);
+
+    const count = presentation.slides.length;
+    const next = useCallback(
+        () => (count ? showSlide((presentation.index + 1) % count) : Promise.resolve(presentation)),
+        [count, presentation, showSlide],
+    );
+    const previous = useCallback(
+        () =>
+            count ? showSlide((presentation.index + count - 1) % count) : Promise.resolve(presentation),
+        [count, presentation, showSlide],
+    );
+
+    const stop = useCallback(() => invoke<void>('stop_presentation'), []);
+
+    return {
+        supported,
+        presentation,
+        active: presentation.displayId !== null,
+        listDisplays,
+        start,
+        update,
+        showSlide,
+        next,
+        previous,
+        stop,
+    };
+}
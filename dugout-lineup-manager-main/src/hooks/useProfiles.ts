@@ -0,0 +1,63 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface Profile {
+    id: string;
+    name: string;
+    createdAt: string;
+}
+
+export interface ProfileList {
+    /** Id of the profile in use. */
+    active: string;
+    profiles: Profile[];
+}
+
+/**
+ * Separate workspaces for coaches who run more than one team, each with its
+ * own roster, lineups and backups. Switching restarts the AI backend, and
+ * `onSwitched` runs afterwards so the caller can reload what it shows. Only
+ * available in the desktop app.
+ */
+export function useProfiles(onSwitched?: (profile: Profile) => void) {
+    const supported = isTauri();
+    const [list, setList] = useState<ProfileList | null>(null);
+    const [switching, setSwitching] = useState(false);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<ProfileList>('list_profiles')
+            .then((current) => !disposed && setList(current))
+            .catch((err) => console.warn('[Profiles] Failed to list profiles:', err));
+
+        const unlisten = listen<Profile>('profile-changed', ({ payload }) => {
+            setList((current) => current && { ...current, active: payload.id });
+            onSwitched?.(payload);
+        });
+
+        return () => {
+            disposed = true;
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, onSwitched]);
+
+    const createProfile = useCallback(async (name: string) => {
+        const profile = await invoke<Profile>('create_profile', { name });
+        setList((current) => current && { ...current, profiles: [...current.profiles, profile] });
+        return profile;
+    }, []);
+
+    const switchProfile = useCallback(async (id: string) => {
+        setSwitching(true);
+        try {
+            return await invoke<Profile>('switch_profile', { id });
+        } finally {
+            setSwitching(false);
+        }
+    }, []);
+
+    return { supported, list, switching, createProfile, switchProfile };
+}
@@ -0,0 +1,32 @@
+import { useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+/**
+ * A QR code of `data` as a PNG data URL for an `<img>`, e.g. the LAN
+ * scoreboard address or an exported lineup link. `null` until it's ready,
+ * and outside the desktop app.
+ */
+export function useQrCode(data: string | null | undefined, size = 512) {
+    const [src, setSrc] = useState<string | null>(null);
+
+    useEffect(() => {
+        if (!isTauri() || !data) {
+            setSrc(null);
+            return;
+        }
+
+        let disposed = false;
+        invoke<string>('generate_qr', { data, size })
+            .then((url) => !disposed && setSrc(url))
+            .catch((err) => {
+                console.error('Failed to make QR code:', err);
+                if (!disposed) setSrc(null);
+            });
+
+        return () => {
+            disposed = true;
+        };
+    }, [data, size]);
+
+    return src;
+}
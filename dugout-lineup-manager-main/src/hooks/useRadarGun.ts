@@ -0,0 +1,73 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface RadarDevice {
+    /** `ble:` or `serial:` and the device's own id. */
+    id: string;
+    name: string;
+    transport: 'ble' | 'serial';
+}
+
+export interface RadarStatus {
+    device: RadarDevice | null;
+    /** The game readings are recorded in. */
+    gameId: string | null;
+}
+
+export interface RadarReading {
+    mph: number;
+    deviceId: string;
+    gameId: string | null;
+    /** Whether it went into the game's scorebook. */
+    recorded: boolean;
+}
+
+/**
+ * Pitch speeds from a radar gun over Bluetooth LE or a serial port. While a
+ * game is set, each reading is recorded in its scorebook as the current
+ * pitch. `scan` takes a few seconds. Only available in the desktop app.
+ */
+export function useRadarGun() {
+    const supported = isTauri();
+    const [status, setStatus] = useState<RadarStatus>({ device: null, gameId: null });
+    const [lastReading, setLastReading] = useState<RadarReading | null>(null);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<RadarStatus>('get_radar_status')
+            .then((current) => !disposed && setStatus(current))
+            .catch(() => {});
+
+        const unlistenStatus = listen<RadarStatus>('radar-status', ({ payload }) =>
+            setStatus(payload),
+        );
+        const unlistenReading = listen<RadarReading>('radar-reading', ({ payload }) =>
+            setLastReading(payload),
+        );
+        return () => {
+            disposed = true;
+            unlistenStatus.then((fn) => fn());
+            unlistenReading.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const scan = useCallback(() => invoke<RadarDevice[]>('list_radars'), []);
+
+    const connect = useCallback(
+        (deviceId: string, baudRate?: number) =>
+            invoke<RadarStatus>('connect_radar', { deviceId, baudRate }),
+        [],
+    );
+
+    const disconnect = useCallback(() => invoke<RadarStatus>('disconnect_radar'), []);
+
+    const setGame = useCallback(
+        (gameId: string | null) => invoke<RadarStatus>('set_radar_game', { gameId }),
+        [],
+    );
+
+    return { supported, status, lastReading, scan, connect, disconnect, setGame };
+}
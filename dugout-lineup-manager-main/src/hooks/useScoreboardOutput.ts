@@ -0,0 +1,72 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { RadarDevice } from './useRadarGun';
+
+/**
+ * A scoreboard controller's frame format. The template is sent as is except
+ * for placeholders: `{us}`, `{them}`, `{home}`, `{guest}`, `{inning}`,
+ * `{balls}`, `{strikes}` and `{outs}` (zero-padded with e.g. `{home:2}`),
+ * `{half}` for `T` or `B`, and `{stx}`, `{etx}`, `{cr}` and `{lf}`.
+ */
+export interface ScoreboardProtocol {
+    id: string;
+    name: string;
+    baudRate: number;
+    template: string;
+    /** Resends the frame this often even when nothing changed. */
+    resendMs?: number | null;
+}
+
+export interface ScoreboardOutputStatus {
+    port: string | null;
+    protocol: ScoreboardProtocol | null;
+}
+
+/**
+ * Sends the live scoreboard (score, inning and count, as pushed with
+ * `update_scoreboard`) to a scoreboard controller on a serial port. Vendors'
+ * protocols are added in the settings under `scoreboardProtocols`. Only
+ * available in the desktop app.
+ */
+export function useScoreboardOutput() {
+    const supported = isTauri();
+    const [status, setStatus] = useState<ScoreboardOutputStatus>({ port: null, protocol: null });
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        invoke<ScoreboardOutputStatus>('get_scoreboard_output')
+            .then((current) => !disposed && setStatus(current))
+            .catch(() => {});
+
+        const unlisten = listen<ScoreboardOutputStatus>('scoreboard-output', ({ payload }) =>
+            setStatus(payload),
+        );
+        return () => {
+            disposed = true;
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const listPorts = useCallback(() => invoke<RadarDevice[]>('list_serial_ports'), []);
+
+    const listProtocols = useCallback(
+        () => invoke<ScoreboardProtocol[]>('list_scoreboard_protocols'),
+        [],
+    );
+
+    const connect = useCallback(
+        (port: string, protocol: string) =>
+            invoke<ScoreboardOutputStatus>('connect_scoreboard', { port, protocol }),
+        [],
+    );
+
+    const disconnect = useCallback(
+        () => invoke<ScoreboardOutputStatus>('disconnect_scoreboard'),
+        [],
+    );
+
+    return { supported, status, listPorts, listProtocols, connect, disconnect };
+}
@@ -0,0 +1,153 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type Base = 'first' | 'second' | 'third';
+export type Destination = Base | 'home' | 'out';
+
+export type PlayResult =
+    | 'single'
+    | 'double'
+    | 'triple'
+    | 'homeRun'
+    | 'walk'
+    | 'hitByPitch'
+    | 'strikeout'
+    | 'out'
+    | 'sacrificeFly'
+    | 'sacrificeBunt'
+    | 'fieldersChoice'
+    | 'reachedOnError';
+
+export type RunnerPlay =
+    | 'stolenBase'
+    | 'caughtStealing'
+    | 'pickedOff'
+    | 'wildPitch'
+    | 'passedBall'
+    | 'balk'
+    | 'advance';
+
+export interface RunnerMove {
+    from: Base;
+    to: Destination;
+}
+
+export type ScorebookEvent =
+    | { type: 'startingLineup'; battingOrder: string[] }
+    | {
+          type: 'plateAppearance';
+          /** Defaults to the batter due up. */
+          batterId?: string;
+          result: PlayResult;
+          /** Replaces the usual advances; runners not listed stay put. */
+          runners?: RunnerMove[];
+          rbi?: number;
+      }
+    | { type: 'baserunning'; play: RunnerPlay; from: Base; to: Destination }
+    | { type: 'substitution'; playerIn: string; playerOut: string; position?: string }
+    /** A radar gun reading; doesn't change the count or score. */
+    | { type: 'pitch'; mph: number };
+
+export interface Play {
+    seq: number;
+    event: ScorebookEvent;
+    createdAt: string;
+}
+
+export interface TeamLine {
+    runs: number;
+    hits: number;
+    errors: number;
+}
+
+export interface BatterLine {
+    playerId: string;
+    name: string;
+    number: number | null;
+    pa: number;
+    ab: number;
+    r: number;
+    h: number;
+    doubles: number;
+    triples: number;
+    hr: number;
+    rbi: number;
+    bb: number;
+    so: number;
+    sb: number;
+    cs: number;
+}
+
+export interface BoxScore {
+    gameId: string;
+    inning: number;
+    topOfInning: boolean;
+    outs: number;
+    /** First, second and third; `playerId` is null for the opponent's runners. */
+    bases: ({ playerId: string | null } | null)[];
+    /** Whether our team is at bat. */
+    batting: boolean;
+    dueUp: string | null;
+    us: TeamLine;
+    them: TeamLine;
+    innings: { inning: number; us: number; them: number }[];
+    batters: BatterLine[];
+    /** Not counting the starting lineup or pitch speeds. */
+    plays: number;
+    lastPitchMph: number | null;
+    topPitchMph: number | null;
+}
+
+/**
+ * Scoring a game play by play, kept in the local database so it works
+ * without the AI backend. The box score follows every recorded or undone
+ * play, including ones made in another window. Only available in the
+ * desktop app.
+ */
+export function useScorebook(gameId: string | null) {
+    const supported = isTauri();
+    const [boxScore, setBoxScore] = useState<BoxScore | null>(null);
+
+    useEffect(() => {
+        if (!supported || !gameId) {
+            setBoxScore(null);
+            return;
+        }
+
+        invoke<BoxScore>('get_box_score', { gameId })
+            .then(setBoxScore)
+            .catch((err) => console.warn('[Scorebook] Failed to load box score:', err));
+        const unlisten = listen<BoxScore>('scorebook-updated', ({ payload }) => {
+            if (payload.gameId === gameId) setBoxScore(payload);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, gameId]);
+
+    const recordPlay = useCallback(
+        async (event: ScorebookEvent) => {
+            if (!gameId) throw new Error('No game selected');
+            const score = await invoke<BoxScore>('record_play', { gameId, event });
+            setBoxScore(score);
+            return score;
+        },
+        [gameId],
+    );
+
+    const undoLastPlay = useCallback(async () => {
+        if (!gameId) throw new Error('No game selected');
+        const score = await invoke<BoxScore>('undo_last_play', { gameId });
+        setBoxScore(score);
+        return score;
+    }, [gameId]);
+
+    const listPlays = useCallback(
+        () => (gameId ? invoke<Play[]>('list_plays', { gameId }) : Promise.resolve([])),
+        [gameId],
+    );
+
+    return { supported, boxScore, recordPlay, undoLastPlay, listPlays };
+}
@@ -0,0 +1,47 @@
+import { useCallback } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export interface SeasonArchive {
+    teamId: string;
+    /** The year, e.g. `2024`. */
+    season: string;
+    games: number;
+    archivedAt: string;
+}
+
+export interface RestoredSeason {
+    season: string;
+    games: number;
+    /** Box scores, pitch counts and fielding assignments of since-deleted players, left out. */
+    skipped: number;
+}
+
+/**
+ * Moves finished seasons out of the schedule, stats and search into a
+ * compressed archive, and back again. A season is the year of its games'
+ * dates and can only be archived once all of them have been played.
+ * Without `teamId` the first team is used. Only available in the desktop
+ * app.
+ */
+export function useSeasonArchives() {
+    const supported = isTauri();
+
+    const listArchivedSeasons = useCallback(
+        (teamId?: string) => invoke<SeasonArchive[]>('list_archived_seasons', { teamId }),
+        [],
+    );
+
+    const archiveSeason = useCallback(
+        (season: string, teamId?: string) =>
+            invoke<SeasonArchive>('archive_season', { teamId, season }),
+        [],
+    );
+
+    const restoreSeason = useCallback(
+        (season: string, teamId?: string) =>
+            invoke<RestoredSeason>('restore_season', { teamId, season }),
+        [],
+    );
+
+    return { supported, listArchivedSeasons, archiveSeason, restoreSeason };
+}
@@ -0,0 +1,57 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface SeasonPrintProgress {
+    done: number;
+    total: number;
+}
+
+export interface CardOptions {
+    paperSize?: 'letter' | 'legal' | 'a4' | 'a5' | 'halfLetter';
+    logoPath?: string;
+}
+
+/** One print job, or one PDF; without a path the save dialog asks. */
+export type SeasonOutput =
+    | { kind: 'print'; printer?: string; copies?: number }
+    | { kind: 'pdf'; path?: string };
+
+/**
+ * Printing the cards for every upcoming game with a saved lineup in one go.
+ * `progress` tracks the cards rendered so far while a batch runs. Resolves
+ * to the number of cards, or `null` if the save dialog was cancelled. Only
+ * available in the desktop app.
+ */
+export function useSeasonCards() {
+    const supported = isTauri();
+    const [progress, setProgress] = useState<SeasonPrintProgress | null>(null);
+
+    useEffect(() => {
+        if (!supported) return;
+        const unlisten = listen<SeasonPrintProgress>('season-print-progress', ({ payload }) => {
+            setProgress(payload);
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const printSeason = useCallback(
+        async (output: SeasonOutput, options?: CardOptions, teamId?: string) => {
+            setProgress(null);
+            try {
+                return await invoke<number | null>('print_season_cards', {
+                    teamId,
+                    options,
+                    output,
+                });
+            } finally {
+                setProgress(null);
+            }
+        },
+        [],
+    );
+
+    return { supported, progress, printSeason };
+}
@@ -0,0 +1,38 @@
+import { useCallback, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export interface StatsCsvFiles {
+    batting: string;
+    pitching: string;
+    fielding: string;
+}
+
+/**
+ * Season stats for the league statistician as batting, pitching and
+ * fielding CSVs with the usual column names (PA, AB, 2B, IP, ER, ...).
+ * `seasonId` is a year; without it every game counts. Without `dir` a
+ * folder picker asks, and the export resolves to `null` if it's
+ * cancelled. Only available in the desktop app.
+ */
+export function useStatsExport() {
+    const supported = isTauri();
+    const [exporting, setExporting] = useState(false);
+
+    const exportStatsCsv = useCallback(
+        async (seasonId?: string, teamId?: string, dir?: string) => {
+            setExporting(true);
+            try {
+                return await invoke<StatsCsvFiles | null>('export_stats_csv', {
+                    teamId,
+                    seasonId,
+                    dir,
+                });
+            } finally {
+                setExporting(false);
+            }
+        },
+        [],
+    );
+
+    return { supported, exporting, exportStatsCsv };
+}
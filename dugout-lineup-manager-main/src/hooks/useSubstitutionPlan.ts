@@ -0,0 +1,55 @@
+import { useCallback } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import type { ProposedLineup, Violation } from './useLineupValidation';
+
+export interface PlannedSub {
+    /** The change is made before this inning starts. */
+    inning: number;
+    outPlayerId: string;
+    inPlayerId: string;
+    /** Defaults to the position of the player coming out. */
+    position?: string;
+}
+
+export interface SubWindow {
+    playerId: string;
+    name: string;
+    /** Innings they could come into the game from the bench. */
+    canEnter: number[];
+    /** Innings they could come out for the rest of the game within the bench limits. */
+    canLeave: number[];
+    /** Positions they've left and can't go back to. */
+    barredPositions: string[];
+}
+
+export interface PlanViolation extends Violation {
+    /** Index of the planned change that causes it; `null` if the lineup breaks it anyway. */
+    sub: number | null;
+}
+
+export interface SubstitutionPlan {
+    positions: { inning: number; position: string; playerId: string }[];
+    windows: SubWindow[];
+    violations: PlanViolation[];
+}
+
+/**
+ * Plans substitutions from the starting lineup (its first inning) under the
+ * league's re-entry and bench rules: when each player can still go in or
+ * come out, and which planned changes would be illegal. Pass a saved
+ * lineup's id or a proposed lineup. Only available in the desktop app.
+ */
+export function useSubstitutionPlan() {
+    const supported = isTauri();
+
+    const planSubstitutions = useCallback(
+        (lineup: string | ProposedLineup, subs: PlannedSub[]) =>
+            invoke<SubstitutionPlan>('plan_substitutions', {
+                ...(typeof lineup === 'string' ? { id: lineup } : { lineup }),
+                subs,
+            }),
+        [],
+    );
+
+    return { supported, planSubstitutions };
+}
@@ -0,0 +1,73 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+/** A saved lineup as the desktop shell returns it. */
+export interface SyncedLineup {
+    id: string;
+    name: string;
+    innings: number;
+    positions: { inning: number; position: string; playerId: string }[];
+    updatedAt: string;
+}
+
+export interface SyncConflict {
+    lineupId: string;
+    /** The paired device the clashing edit came from. */
+    device: string;
+    detectedAt: string;
+    mine: SyncedLineup;
+    theirs: SyncedLineup;
+}
+
+/**
+ * `mergeInnings` keeps this device's lineup but takes the fielding for
+ * some innings from the other one.
+ */
+export type ConflictStrategy = 'keepMine' | 'keepTheirs' | 'mergeInnings';
+
+/**
+ * Lineups both coaches edited while synced without seeing each other's
+ * change. Each waits here until `resolveConflict` settles it; the result is
+ * sent to the other device. Only available in the desktop app.
+ */
+export function useSyncConflicts() {
+    const supported = isTauri();
+    const [conflicts, setConflicts] = useState<SyncConflict[]>([]);
+
+    const refresh = useCallback(async () => {
+        if (!supported) return;
+        try {
+            setConflicts(await invoke<SyncConflict[]>('get_sync_conflicts'));
+        } catch (err) {
+            console.warn('[SyncConflicts] Failed to list conflicts:', err);
+        }
+    }, [supported]);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        refresh();
+        const unlisten = listen<string>('sync-conflict', () => refresh());
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, refresh]);
+
+    /** `theirsInnings` picks the innings `mergeInnings` takes from theirs. */
+    const resolveConflict = useCallback(
+        async (lineupId: string, strategy: ConflictStrategy, theirsInnings?: number[]) => {
+            const lineup = await invoke<SyncedLineup>('resolve_conflict', {
+                lineupId,
+                strategy,
+                theirsInnings,
+            });
+            setConflicts((current) => current.filter((c) => c.lineupId !== lineupId));
+            return lineup;
+        },
+        [],
+    );
+
+    return { supported, conflicts, refresh, resolveConflict };
+}
@@ -0,0 +1,41 @@
+import { useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export type ModelSize = 'small' | 'medium' | 'large';
+
+export interface SystemCapabilities {
+    os: string;
+    arch: string;
+    cpuBrand: string | null;
+    logicalCores: number;
+    physicalCores: number | null;
+    totalMemory: number;
+    availableMemory: number;
+    gpu: { metal: boolean; unifiedMemory: boolean; cuda: boolean };
+    dataDisk: { mountPoint: string; total: number; available: number; nearlyFull: boolean } | null;
+    recommendedModel: ModelSize;
+}
+
+/**
+ * Hardware report from the desktop shell, read once on mount: used to
+ * suggest a local model size and to warn when the data disk is nearly
+ * full. `null` in a plain browser or until it loads.
+ */
+export function useSystemCapabilities() {
+    const [capabilities, setCapabilities] = useState<SystemCapabilities | null>(null);
+
+    useEffect(() => {
+        if (!isTauri()) return;
+
+        let disposed = false;
+        invoke<SystemCapabilities>('get_system_capabilities')
+            .then((report) => !disposed && setCapabilities(report))
+            .catch((err) => console.warn('[System] Failed to read capabilities:', err));
+
+        return () => {
+            disposed = true;
+        };
+    }, []);
+
+    return capabilities;
+}
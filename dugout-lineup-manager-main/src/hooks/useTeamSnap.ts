@@ -0,0 +1,77 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface TeamSnapTeam {
+    id: string;
+    name: string;
+    season: string | null;
+}
+
+export interface TeamSnapLink {
+    linked: boolean;
+    teams: TeamSnapTeam[];
+    /** Why linking failed, ready to show. */
+    error: string | null;
+}
+
+export interface TeamSnapImport {
+    created: number;
+    updated: number;
+    skipped: number;
+}
+
+/**
+ * Importing the roster and schedule from TeamSnap. `link` opens TeamSnap's
+ * sign-in in the browser; the teams arrive once the coach comes back to the
+ * app. Only available in the desktop app.
+ */
+export function useTeamSnap() {
+    const supported = isTauri();
+    const [teams, setTeams] = useState<TeamSnapTeam[]>([]);
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        let disposed = false;
+        // Fails until an account is linked, which is the common case
+        invoke<TeamSnapTeam[]>('list_teamsnap_teams')
+            .then((linked) => !disposed && setTeams(linked))
+            .catch(() => {});
+
+        const unlisten = listen<TeamSnapLink>('teamsnap-linked', ({ payload }) => {
+            setTeams(payload.teams);
+            setError(payload.error);
+        });
+
+        return () => {
+            disposed = true;
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const link = useCallback(async () => {
+        setError(null);
+        await invoke('link_teamsnap');
+    }, []);
+
+    const unlink = useCallback(async () => {
+        await invoke('unlink_teamsnap');
+        setTeams([]);
+    }, []);
+
+    const importRoster = useCallback(
+        (teamsnapTeamId: string) =>
+            invoke<TeamSnapImport>('import_teamsnap_roster', { teamsnapTeamId }),
+        [],
+    );
+
+    const importSchedule = useCallback(
+        (teamsnapTeamId: string) =>
+            invoke<TeamSnapImport>('import_teamsnap_schedule', { teamsnapTeamId }),
+        [],
+    );
+
+    return { supported, teams, error, link, unlink, importRoster, importSchedule };
+}
@@ -0,0 +1,61 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+
+export type TelemetryFeature =
+    | 'lineupSaved'
+    | 'lineupGenerated'
+    | 'lineupPrinted'
+    | 'pdfExported'
+    | 'spreadsheetExported'
+    | 'gamesImported'
+    | 'gameModeEntered'
+    | 'syncPaired'
+    | 'aiRequest';
+
+export interface UsageCount {
+    feature: TelemetryFeature;
+    /** `YYYY-MM-DD`. */
+    day: string;
+    count: number;
+}
+
+export interface PendingTelemetry {
+    enabled: boolean;
+    /** `null` when this build never uploads. */
+    uploadUrl: string | null;
+    installId: string;
+    appVersion: string;
+    os: string;
+    counts: UsageCount[];
+}
+
+/**
+ * The opt-in for anonymous feature-usage counts, and exactly what the next
+ * upload would send so the coach can check it. Only available in the
+ * desktop app.
+ */
+export function useTelemetry() {
+    const supported = isTauri();
+    const [pending, setPending] = useState<PendingTelemetry | null>(null);
+
+    const refresh = useCallback(async () => {
+        if (!supported) return;
+        try {
+            setPending(await invoke<PendingTelemetry>('get_pending_telemetry'));
+        } catch (err) {
+            console.warn('[Telemetry] Failed to load pending usage:', err);
+        }
+    }, [supported]);
+
+    useEffect(() => {
+        refresh();
+    }, [refresh]);
+
+    const setEnabled = useCallback(async (enabled: boolean) => {
+        const next = await invoke<PendingTelemetry>('set_telemetry_enabled', { enabled });
+        setPending(next);
+        return next;
+    }, []);
+
+    return { supported, pending, refresh, setEnabled };
+}
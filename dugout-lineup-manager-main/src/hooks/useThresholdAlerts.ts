@@ -0,0 +1,34 @@
+import { useEffect } from 'react';
+import { isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface ThresholdAlert {
+    source: 'pitchCount' | 'gameClock';
+    teamId: string | null;
+    /** Ready to show, e.g. "Jake Miller needs 2 days rest". */
+    message: string;
+}
+
+/**
+ * Pitch count and game clock alerts at the thresholds set for each team in
+ * settings. The desktop shell also shows them as OS notifications unless
+ * those are turned off; `onAlert` is for showing them prominently in the
+ * app. Only available in the desktop app.
+ */
+export function useThresholdAlerts(onAlert?: (alert: ThresholdAlert) => void) {
+    const supported = isTauri();
+
+    useEffect(() => {
+        if (!supported) return;
+
+        const unlisten = listen<ThresholdAlert>('threshold-alert', ({ payload }) =>
+            onAlert?.(payload),
+        );
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported, onAlert]);
+
+    return { supported };
+}
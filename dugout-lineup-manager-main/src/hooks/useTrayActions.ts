@@ -0,0 +1,31 @@
+import { useEffect } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type TrayAction =
+    | { action: 'openTodaysGame'; date: string; gameId: string | null }
+    | { action: 'startGameTimer' };
+
+/**
+ * Hook to respond to quick actions picked from the system tray menu.
+ * Must be used inside the router.
+ */
+export function useTrayActions(onAction?: (action: TrayAction) => void) {
+    const navigate = useNavigate();
+
+    useEffect(() => {
+        if (!isTauri()) return;
+
+        const unlisten = listen<TrayAction>('tray-action', ({ payload }) => {
+            if (payload.action === 'openTodaysGame') {
+                navigate(`/games?date=${payload.date}`);
+            }
+            onAction?.(payload);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [navigate, onAction]);
+}
@@ -0,0 +1,52 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { PlayResult, ScorebookEvent } from './useScorebook';
+
+export interface VoiceCall {
+    transcript: string;
+    number: number | null;
+    /** The roster player wearing `number`. */
+    playerId: string | null;
+    result: PlayResult | null;
+    /** Position code of the fielder the ball went to, e.g. `LF`. */
+    fielder: string | null;
+    /**
+     * Ready for `recordPlay` once the coach confirms it; `null` when the call
+     * had no result or named a number nobody on the roster wears.
+     */
+    event: ScorebookEvent | null;
+}
+
+/**
+ * Keeping score by voice: `start` listens on the default microphone and
+ * `stop` transcribes the call ("Number 12, single to left") and resolves to
+ * what it said. Nothing is recorded until its event is passed to the
+ * scorebook. Only available in the desktop app.
+ */
+export function useVoiceScoring() {
+    const supported = isTauri();
+    const [listening, setListening] = useState(false);
+    const [lastCall, setLastCall] = useState<VoiceCall | null>(null);
+
+    useEffect(() => {
+        if (!supported) return;
+
+        const unlisten = listen<VoiceCall>('voice-call', ({ payload }) => setLastCall(payload));
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [supported]);
+
+    const start = useCallback(async () => {
+        await invoke('start_voice_capture');
+        setListening(true);
+    }, []);
+
+    const stop = useCallback(async (teamId?: string) => {
+        setListening(false);
+        return invoke<VoiceCall>('stop_voice_capture', { teamId });
+    }, []);
+
+    return { supported, listening, lastCall, start, stop };
+}
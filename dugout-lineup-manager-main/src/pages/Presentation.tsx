@@ -0,0 +1,73 @@
+import { useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { Presentation as PresentationState } from '@/hooks/usePresentation';
+
+/**
+ * Slideshow shown full screen on a second display by `start_presentation`.
+ * It only displays the current slide; the controls stay in the main window,
+ * apart from moving on by itself when `advanceSecs` is set.
+ */
+const Presentation = () => {
+  const [presentation, setPresentation] = useState<PresentationState | null>(null);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    invoke<PresentationState>('get_presentation').then(setPresentation).catch(console.error);
+    const unlisten = listen<PresentationState>('presentation', ({ payload }) => setPresentation(payload));
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Restarted by every slide change, so moving on from the controls resets the timer
+  useEffect(() => {
+    if (!presentation?.advanceSecs || presentation.slides.length < 2) return;
+    const { index, slides, advanceSecs } = presentation;
+    const timer = window.setTimeout(
+      () => invoke('show_slide', { index: (index + 1) % slides.length }).catch(console.error),
+      advanceSecs * 1000,
+    );
+    return () => window.clearTimeout(timer);
+  }, [presentation]);
+
+  const slide = presentation?.slides[presentation.index];
+
+  return (
+    <div className="flex h-screen cursor-none select-none flex-col items-center justify-center bg-black p-16 text-white">
+      {presentation && slide ? (
+        <>
+          <div className="text-2xl uppercase tracking-widest text-white/60">
+            {slide.kind === 'award' ? 'Award' : 'Lineup'}
+          </div>
+          <h1 className="mt-4 text-center text-7xl font-bold">{slide.title}</h1>
+          {slide.subtitle && <div className="mt-4 text-center text-4xl text-yellow-300">{slide.subtitle}</div>}
+          {slide.kind === 'lineup' ? (
+            <ol className="mt-12 grid grid-cols-2 gap-x-16 gap-y-3 text-4xl">
+              {slide.lines.map((line, index) => (
+                <li key={`${index}-${line}`}>
+                  {index + 1}. {line}
+                </li>
+              ))}
+            </ol>
+          ) : (
+            <div className="mt-12 space-y-4 text-center text-4xl text-white/80">
+              {slide.lines.map((line, index) => (
+                <p key={`${index}-${line}`}>{line}</p>
+              ))}
+            </div>
+          )}
+          <div className="absolute bottom-8 text-xl text-white/40 tabular-nums">
+            {presentation.index + 1} / {presentation.slides.length}
+          </div>
+        </>
+      ) : (
+        <div className="text-2xl text-white/60">Waiting for the first slide…</div>
+      )}
+    </div>
+  );
+};
+
+export default Presentation;
@@ -0,0 +1,100 @@
+import { useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+
+export interface ScoreboardState {
+  teamName: string;
+  opponent: string;
+  scoreUs: number;
+  scoreThem: number;
+  inning: number;
+  topOfInning: boolean;
+  /** Whether we're the home team, for scoreboards that show home and guest. */
+  home: boolean;
+  balls: number;
+  strikes: number;
+  outs: number;
+  battingOrder: string[];
+  currentBatter: number | null;
+}
+
+/**
+ * Full-screen scoreboard shown in the secondary window opened with
+ * `open_scoreboard_window`. Drag anywhere to move it; double-click toggles
+ * full screen and Escape closes it.
+ */
+const Scoreboard = () => {
+  const [board, setBoard] = useState<ScoreboardState | null>(null);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    invoke<ScoreboardState>('get_scoreboard').then(setBoard).catch(console.error);
+    const unlisten = listen<ScoreboardState>('scoreboard-update', ({ payload }) => setBoard(payload));
+
+    const onKeyDown = (event: KeyboardEvent) => {
+      if (event.key === 'Escape') getCurrentWindow().close();
+    };
+    window.addEventListener('keydown', onKeyDown);
+
+    return () => {
+      unlisten.then((fn) => fn());
+      window.removeEventListener('keydown', onKeyDown);
+    };
+  }, []);
+
+  const toggleFullscreen = async () => {
+    const current = getCurrentWindow();
+    await current.setFullscreen(!(await current.isFullscreen()));
+  };
+
+  return (
+    <div
+      data-tauri-drag-region
+      onDoubleClick={toggleFullscreen}
+      className="flex h-screen select-none flex-col bg-black p-8 text-white"
+    >
+      {board ? (
+        <>
+          <div data-tauri-drag-region className="grid grid-cols-3 items-center text-center">
+            <div data-tauri-drag-region>
+              <div className="text-3xl font-semibold">{board.teamName || 'Home'}</div>
+              <div className="text-8xl font-bold tabular-nums">{board.scoreUs}</div>
+            </div>
+            <div data-tauri-drag-region className="text-4xl">
+              {board.inning > 0 && (
+                <>
+                  {board.topOfInning ? '▲' : '▼'} {board.inning}
+                  <div className="mt-2 text-2xl text-white/70">
+                    {board.outs} {board.outs === 1 ? 'out' : 'outs'}
+                  </div>
+                </>
+              )}
+            </div>
+            <div data-tauri-drag-region>
+              <div className="text-3xl font-semibold">{board.opponent || 'Away'}</div>
+              <div className="text-8xl font-bold tabular-nums">{board.scoreThem}</div>
+            </div>
+          </div>
+          <ol data-tauri-drag-region className="mt-10 grid flex-1 grid-cols-2 content-start gap-x-12 gap-y-2 text-2xl">
+            {board.battingOrder.map((name, index) => (
+              <li
+                key={`${index}-${name}`}
+                className={index === board.currentBatter ? 'font-bold text-yellow-300' : 'text-white/80'}
+              >
+                {index + 1}. {name}
+              </li>
+            ))}
+          </ol>
+        </>
+      ) : (
+        <div data-tauri-drag-region className="m-auto text-2xl text-white/60">
+          Waiting for the game to start…
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default Scoreboard;
@@ -0,0 +1,77 @@
+import { useEffect, useState } from 'react';
+import { invoke, isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type BootStage =
+  | 'starting'
+  | 'runningMigrations'
+  | 'migratingBackendData'
+  | 'startingBackend'
+  | 'connectingBackend'
+  | 'ready'
+  | 'degraded';
+
+export interface BootProgress {
+  stage: BootStage;
+  message: string;
+}
+
+/** Payload of the shell's `backend-migration` events. */
+interface MigrationProgress {
+  phase: 'snapshotting' | 'migrating' | 'done' | 'rolledBack' | 'failed';
+  step: number | null;
+  total: number | null;
+  message: string;
+}
+
+/**
+ * Boot splash shown in its own small window at launch, with what the shell
+ * is doing while the main window waits for the backend. The shell closes
+ * it once the main window is shown.
+ */
+const Splash = () => {
+  const [progress, setProgress] = useState<BootProgress | null>(null);
+  const [migration, setMigration] = useState<MigrationProgress | null>(null);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    invoke<BootProgress>('get_boot_progress').then(setProgress).catch(console.error);
+    const unlisten = listen<BootProgress>('boot-progress', ({ payload }) => setProgress(payload));
+    const unlistenMigration = listen<MigrationProgress>('backend-migration', ({ payload }) =>
+      setMigration(payload),
+    );
+
+    return () => {
+      unlisten.then((fn) => fn());
+      unlistenMigration.then((fn) => fn());
+    };
+  }, []);
+
+  return (
+    <div
+      data-tauri-drag-region
+      className="flex h-screen select-none flex-col items-center justify-center gap-4 bg-background"
+    >
+      <div data-tauri-drag-region className="text-3xl font-bold">
+        Dugout
+      </div>
+      <div
+        data-tauri-drag-region
+        className="h-1 w-40 overflow-hidden rounded-full bg-muted"
+      >
+        <div className="h-full w-1/3 animate-pulse rounded-full bg-primary" />
+      </div>
+      <div data-tauri-drag-region className="text-sm text-muted-foreground">
+        {progress?.message ?? 'Starting Dugout'}…
+      </div>
+      {progress?.stage === 'migratingBackendData' && migration?.total ? (
+        <div data-tauri-drag-region className="text-xs text-muted-foreground">
+          {migration.message} ({migration.step} of {migration.total})
+        </div>
+      ) : null}
+    </div>
+  );
+};
+
+export default Splash;